@@ -0,0 +1,27 @@
+use std::fs;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // gRPC 서비스 코드 생성 (proto/webchat.proto)
+    // 시스템에 protoc 가 없어도 빌드되도록 함께 받은 바이너리를 쓴다
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_build::configure().build_client(false).compile_protos(&["proto/webchat.proto"], &["proto"])?;
+
+    // 마이그레이션을 더하거나 고치면 다시 빌드해 바이너리에 든 마이그레이션(schema.rs)을 바꾼다
+    println!("cargo:rerun-if-changed=migrations");
+    println!("cargo:rerun-if-changed=migrations_sqlite");
+    println!("cargo:rerun-if-changed=migrations_mysql");
+    // 준비 상태 확인(/readyz)이 DB 에 적용됐는지 비교할 가장 최근 마이그레이션 버전
+    println!("cargo:rustc-env=LATEST_MIGRATION={}", latest_version("migrations")?);
+    println!("cargo:rustc-env=LATEST_SQLITE_MIGRATION={}", latest_version("migrations_sqlite")?);
+    println!("cargo:rustc-env=LATEST_MYSQL_MIGRATION={}", latest_version("migrations_mysql")?);
+    Ok(())
+}
+
+fn latest_version(dir: &str) -> std::io::Result<i64> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.split('_').next()?.parse::<i64>().ok())
+        .max()
+        .unwrap_or(0))
+}
@@ -0,0 +1,18 @@
+-- 처음부터 있던 두 테이블. 다른 마이그레이션이 모두 이 둘을 참조하므로 가장 먼저 적용한다.
+-- 이 마이그레이션이 생기기 전에 손으로 만든 DB 에서는 아무것도 바꾸지 않는다.
+-- 이후에 붙은 열(email, is_admin, format, hidden 등)은 각 기능의 마이그레이션이 추가한다.
+CREATE TABLE IF NOT EXISTS users (
+    id SERIAL PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL
+);
+
+-- user_id 에는 외래 키가 없다. 웹훅이 보낸 메시지는 NULL 이다.
+CREATE TABLE IF NOT EXISTS messages (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER,
+    username TEXT,
+    room TEXT,
+    content TEXT,
+    created_at TIMESTAMPTZ DEFAULT now()
+);
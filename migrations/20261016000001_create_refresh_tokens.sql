@@ -0,0 +1,16 @@
+-- 로그인 시 발급되는 장기 리프레시 토큰. 토큰 원문 대신 SHA-256 해시만 저장한다.
+-- 같은 로그인에서 회전(rotation)된 토큰들은 family_id 를 공유하며,
+-- 이미 사용된 토큰이 다시 제출되면 family 전체를 폐기한다.
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_family_idx ON refresh_tokens (family_id);
+CREATE INDEX IF NOT EXISTS refresh_tokens_user_idx ON refresh_tokens (user_id);
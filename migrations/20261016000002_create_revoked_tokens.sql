@@ -0,0 +1,9 @@
+-- 로그아웃 등으로 만료 전에 무효화된 액세스 토큰의 jti.
+-- 토큰이 원래 만료되는 시각이 지나면 더 이상 보관할 필요가 없다.
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti TEXT PRIMARY KEY,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS revoked_tokens_expires_idx ON revoked_tokens (expires_at);
@@ -0,0 +1,14 @@
+-- 소셜 로그인으로만 가입한 계정은 비밀번호가 없다
+ALTER TABLE users ALTER COLUMN password_hash DROP NOT NULL;
+
+-- 외부 제공자(google, github)의 계정과 로컬 사용자의 연결
+CREATE TABLE IF NOT EXISTS oauth_identities (
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (provider, subject)
+);
+
+CREATE INDEX IF NOT EXISTS oauth_identities_user_idx ON oauth_identities (user_id);
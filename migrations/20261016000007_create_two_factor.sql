@@ -0,0 +1,18 @@
+-- TOTP 2단계 인증. 등록 후 코드 하나를 확인해야(confirmed_at) 로그인에 적용된다.
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL, -- base32
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    confirmed_at TIMESTAMPTZ,
+    -- 같은 코드를 두 번 쓰지 못하도록 마지막으로 사용한 시간 스텝을 기록한다
+    last_used_step BIGINT
+);
+
+-- 비밀번호(또는 소셜 로그인)는 통과했지만 두 번째 단계가 남은 로그인 시도
+CREATE TABLE IF NOT EXISTS login_challenges (
+    token_hash TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
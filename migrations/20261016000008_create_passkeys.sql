@@ -0,0 +1,21 @@
+-- 사용자별 패스키(WebAuthn 자격 증명). passkey 는 webauthn-rs 의 Passkey 를 JSON 으로 직렬화한 값이다.
+CREATE TABLE IF NOT EXISTS passkeys (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    credential_id TEXT NOT NULL UNIQUE,
+    passkey TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS passkeys_user_idx ON passkeys (user_id);
+
+-- 진행 중인 등록/인증 절차의 서버 측 상태 (챌린지 포함). 한 번 쓰면 지운다.
+CREATE TABLE IF NOT EXISTS webauthn_ceremonies (
+    token_hash TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    state TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
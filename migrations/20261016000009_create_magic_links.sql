@@ -0,0 +1,9 @@
+-- 매직 링크 로그인 토큰. 가입 전 주소에도 보낼 수 있으므로 사용자 대신 메일 주소를 저장한다.
+CREATE TABLE IF NOT EXISTS magic_links (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
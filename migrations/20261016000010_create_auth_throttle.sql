@@ -0,0 +1,7 @@
+-- 로그인/가입 시도 제한 카운터. key 는 "login-ip:<ip>", "login-user:<username>", "register-ip:<ip>" 형태다.
+CREATE TABLE IF NOT EXISTS auth_throttle (
+    key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_until TIMESTAMPTZ
+);
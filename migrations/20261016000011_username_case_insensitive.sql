@@ -0,0 +1,2 @@
+-- 대소문자만 다른 사용자 이름을 막는다
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_lower_idx ON users (lower(username));
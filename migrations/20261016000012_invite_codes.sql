@@ -0,0 +1,17 @@
+-- 서버 관리자. 초대 코드 발급 등 관리 기능에 쓴다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT false;
+
+-- 가입 초대 코드. 원문은 발급할 때 한 번만 보여주고 해시만 저장한다.
+CREATE TABLE IF NOT EXISTS invite_codes (
+    id BIGSERIAL PRIMARY KEY,
+    code_hash TEXT NOT NULL UNIQUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    max_uses INTEGER NOT NULL DEFAULT 1,
+    uses INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- 어떤 초대 코드로 가입했는지
+ALTER TABLE users ADD COLUMN IF NOT EXISTS invite_id BIGINT REFERENCES invite_codes(id) ON DELETE SET NULL;
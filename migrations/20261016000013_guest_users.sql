@@ -0,0 +1,5 @@
+-- 게스트 계정 (POST /guest). 정식 계정으로 전환하면 false 가 된다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_guest BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS users_guest_created_idx ON users (created_at) WHERE is_guest;
@@ -0,0 +1,12 @@
+-- 개인 데이터 내보내기 작업. 백그라운드에서 archive(JSON)를 채우고, expires_at 이 지나면 지운다.
+CREATE TABLE IF NOT EXISTS data_exports (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    archive TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS data_exports_user_idx ON data_exports (user_id);
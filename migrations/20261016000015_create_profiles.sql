@@ -0,0 +1,8 @@
+-- 공개 프로필 (GET/PATCH /users/:username). 행이 없으면 모든 항목이 비어 있는 것으로 본다.
+CREATE TABLE IF NOT EXISTS profiles (
+    user_id      INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    display_name TEXT,
+    bio          TEXT,
+    pronouns     TEXT,
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
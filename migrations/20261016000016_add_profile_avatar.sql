@@ -0,0 +1,2 @@
+-- 아바타 (POST /me/avatar). 올릴 때마다 바뀌는 값으로, 캐시를 무효화하는 URL 의 ?v= 에 쓴다.
+ALTER TABLE profiles ADD COLUMN IF NOT EXISTS avatar_version TEXT;
@@ -0,0 +1,14 @@
+-- 메시지 첨부 파일 (POST /rooms/:room/attachments). 파일 본문은 저장소에 있고 여기에는 메타데이터만 둔다.
+CREATE TABLE IF NOT EXISTS attachments (
+    id           TEXT PRIMARY KEY,
+    message_id   BIGINT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    user_id      INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    room         TEXT NOT NULL,
+    filename     TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size         BIGINT NOT NULL,
+    storage_key  TEXT NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS attachments_message_idx ON attachments (message_id);
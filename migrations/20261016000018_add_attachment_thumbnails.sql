@@ -0,0 +1,6 @@
+-- 이미지 첨부의 원본 크기와 미리보기 이미지. 이미지가 아니거나 읽을 수 없으면 비어 있다.
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS width INTEGER;
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS height INTEGER;
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS thumbnail_key TEXT;
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS thumbnail_width INTEGER;
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS thumbnail_height INTEGER;
@@ -0,0 +1,3 @@
+-- 음성 메시지 (POST /rooms/:room/voice). kind 는 'file' 또는 'voice', duration_ms 는 음성일 때만 있다.
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'file';
+ALTER TABLE attachments ADD COLUMN IF NOT EXISTS duration_ms INTEGER;
@@ -0,0 +1,9 @@
+-- 링크 미리보기 캐시. 가져오지 못한 URL 도 모두 NULL 인 행으로 남겨 다시 요청하지 않는다.
+CREATE TABLE IF NOT EXISTS link_previews (
+    url         TEXT PRIMARY KEY,
+    title       TEXT,
+    description TEXT,
+    image_url   TEXT,
+    site_name   TEXT,
+    fetched_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
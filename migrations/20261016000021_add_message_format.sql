@@ -0,0 +1,3 @@
+-- 메시지 형식 ('plain' 또는 'markdown'). markdown 이면 html 에 서버가 만든 안전한 HTML 을 둔다.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS format TEXT NOT NULL DEFAULT 'plain';
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS html TEXT;
@@ -0,0 +1,11 @@
+-- 금칙어 목록. room 이 NULL 이면 모든 방에 적용된다. action 은 'mask' 또는 'reject'.
+CREATE TABLE IF NOT EXISTS word_filters (
+    id         BIGSERIAL PRIMARY KEY,
+    room       TEXT,
+    pattern    TEXT NOT NULL,
+    action     TEXT NOT NULL DEFAULT 'mask',
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS word_filters_room_pattern_idx ON word_filters (COALESCE(room, ''), pattern);
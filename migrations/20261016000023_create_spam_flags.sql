@@ -0,0 +1,11 @@
+-- 반복 메시지 스팸으로 감지된 기록. reason 은 'duplicate_messages' 또는 'repeated_link'.
+CREATE TABLE IF NOT EXISTS spam_flags (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room       TEXT NOT NULL,
+    reason     TEXT NOT NULL,
+    sample     TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS spam_flags_created_at_idx ON spam_flags (created_at DESC);
@@ -0,0 +1,22 @@
+-- 사용자 음소거. 이 시각까지 글을 쓸 수 없다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS muted_until TIMESTAMPTZ;
+
+-- 메시지 신고. 메시지가 지워져도 처리 기록이 남도록 신고할 때의 방, 작성자, 내용을 함께 둔다.
+-- status 는 'open', 'dismissed', 'resolved'. resolution 은 처리한 동작 ('dismiss', 'delete_message', 'mute_author').
+CREATE TABLE IF NOT EXISTS message_reports (
+    id          BIGSERIAL PRIMARY KEY,
+    message_id  BIGINT REFERENCES messages(id) ON DELETE SET NULL,
+    room        TEXT NOT NULL,
+    author_id   INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    content     TEXT,
+    reporter_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason      TEXT NOT NULL,
+    status      TEXT NOT NULL DEFAULT 'open',
+    resolution  TEXT,
+    resolved_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (message_id, reporter_id)
+);
+
+CREATE INDEX IF NOT EXISTS message_reports_status_idx ON message_reports (status, created_at);
@@ -0,0 +1,3 @@
+-- 그림자 차단. 차단된 사용자의 메시지는 hidden 으로 저장되어 본인 말고는 볼 수 없다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS shadow_banned BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS hidden BOOLEAN NOT NULL DEFAULT false;
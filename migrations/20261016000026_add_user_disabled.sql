@@ -0,0 +1,2 @@
+-- 관리자가 비활성화한 계정. 값이 있으면 로그인할 수 없다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS disabled_at TIMESTAMPTZ;
@@ -0,0 +1,11 @@
+-- 차단한 IP 대역. 한 주소는 /32 (IPv6 는 /128) 로 저장한다. expires_at 이 NULL 이면 풀 때까지 유지된다.
+CREATE TABLE IF NOT EXISTS ip_bans (
+    id         BIGSERIAL PRIMARY KEY,
+    network    CIDR NOT NULL,
+    reason     TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS ip_bans_network_idx ON ip_bans USING gist (network inet_ops);
@@ -0,0 +1,3 @@
+-- 사용자가 정한 접속 상태 ('online', 'away', 'busy', 'invisible') 와 상태 메시지
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'online';
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status_message TEXT;
@@ -0,0 +1,15 @@
+-- 사용자에게 남기는 알림 (언급, 친구 신청, 중재 안내 등). 접속하지 않은 동안 온 것도 나중에 볼 수 있다.
+CREATE TABLE IF NOT EXISTS notifications (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind       TEXT NOT NULL,
+    room       TEXT,
+    message_id BIGINT REFERENCES messages(id) ON DELETE SET NULL,
+    actor_id   INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    text       TEXT NOT NULL,
+    read_at    TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS notifications_user_idx ON notifications (user_id, id DESC);
+CREATE INDEX IF NOT EXISTS notifications_unread_idx ON notifications (user_id) WHERE read_at IS NULL;
@@ -0,0 +1,10 @@
+-- 방별 알림 설정. level 은 'all' (모든 메시지), 'mentions' (언급만, 기본), 'mute' (알림 없음).
+CREATE TABLE IF NOT EXISTS room_notification_prefs (
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room       TEXT NOT NULL,
+    level      TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, room)
+);
+
+CREATE INDEX IF NOT EXISTS room_notification_prefs_room_idx ON room_notification_prefs (room) WHERE level = 'all';
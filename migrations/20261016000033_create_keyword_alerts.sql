@@ -0,0 +1,11 @@
+-- 사용자가 지켜보는 단어나 구절. keyword 는 정규화(NFKC, 소문자)해서 공백 하나로 이은 단어들.
+-- room 이 NULL 이면 모든 방.
+CREATE TABLE IF NOT EXISTS keyword_alerts (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    keyword    TEXT NOT NULL,
+    room       TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS keyword_alerts_user_keyword_idx ON keyword_alerts (user_id, COALESCE(room, ''), keyword);
@@ -0,0 +1,13 @@
+-- 브라우저의 Web Push 구독. endpoint 는 푸시 서비스가 준 주소이고, p256dh 와 auth 는 내용 암호화에 쓰는
+-- 구독자의 공개키와 인증 비밀 (base64url).
+CREATE TABLE IF NOT EXISTS push_subscriptions (
+    id           BIGSERIAL PRIMARY KEY,
+    user_id      INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    endpoint     TEXT NOT NULL UNIQUE,
+    p256dh       TEXT NOT NULL,
+    auth         TEXT NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS push_subscriptions_user_idx ON push_subscriptions (user_id);
@@ -0,0 +1,8 @@
+-- 읽지 않은 언급을 메일로 모아 보내는 요약. email_digest 가 false 면 받지 않는다.
+-- digest_sent_at 은 마지막 요약에 넣은 알림의 시각, last_online_at 은 마지막 웹소켓 연결이 닫힌 시각.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_digest BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS digest_sent_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_online_at TIMESTAMPTZ;
+
+-- 이미 쌓여 있던 알림은 요약하지 않는다
+UPDATE users SET digest_sent_at = now() WHERE digest_sent_at IS NULL;
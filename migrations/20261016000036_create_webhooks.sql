@@ -0,0 +1,14 @@
+-- 방에 메시지를 올리는 수신 웹훅. 토큰은 해시만 저장한다.
+CREATE TABLE IF NOT EXISTS webhooks (
+    id           BIGSERIAL PRIMARY KEY,
+    room         TEXT NOT NULL,
+    name         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    created_by   INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at   TIMESTAMPTZ
+);
+
+-- 웹훅이 올린 메시지. user_id 는 NULL 이고 username 에는 웹훅 이름이 들어간다.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS webhook_id BIGINT REFERENCES webhooks(id) ON DELETE SET NULL;
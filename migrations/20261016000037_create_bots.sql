@@ -0,0 +1,20 @@
+-- 봇 계정. 사용자가 만들어 소유하며, 비밀번호 대신 API 토큰으로 인증한다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_bot BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS bot_owner_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS users_bot_owner_idx ON users (bot_owner_id) WHERE bot_owner_id IS NOT NULL;
+
+-- 봇의 API 토큰. 해시만 저장하고, scopes 는 'chat', 'read', 'write' 중 허용한 것.
+CREATE TABLE IF NOT EXISTS bot_tokens (
+    id           BIGSERIAL PRIMARY KEY,
+    bot_id       INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    scopes       TEXT[] NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ,
+    expires_at   TIMESTAMPTZ,
+    revoked_at   TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS bot_tokens_bot_idx ON bot_tokens (bot_id);
@@ -0,0 +1,7 @@
+-- 방 주제. 관리자가 `/topic` 명령으로 정하고, 방에 들어오면 함께 보낸다.
+CREATE TABLE IF NOT EXISTS room_topics (
+    room   TEXT PRIMARY KEY,
+    topic  TEXT NOT NULL,
+    set_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    set_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
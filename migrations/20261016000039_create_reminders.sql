@@ -0,0 +1,14 @@
+-- `/remind` 로 예약한 알림. 보내면 지운다.
+CREATE TABLE IF NOT EXISTS reminders (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room       TEXT NOT NULL,
+    -- 'me' 면 본인에게 알림으로, 'room' 이면 방 전체에 보낸다
+    target     TEXT NOT NULL,
+    text       TEXT NOT NULL,
+    remind_at  TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS reminders_remind_at_idx ON reminders (remind_at);
+CREATE INDEX IF NOT EXISTS reminders_user_idx ON reminders (user_id);
@@ -0,0 +1,13 @@
+-- 정해진 시각에 보낼 메시지. 보내거나 취소하면 지운다.
+CREATE TABLE IF NOT EXISTS scheduled_messages (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room       TEXT NOT NULL,
+    content    TEXT NOT NULL,
+    format     TEXT NOT NULL DEFAULT 'plain',
+    send_at    TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS scheduled_messages_send_at_idx ON scheduled_messages (send_at);
+CREATE INDEX IF NOT EXISTS scheduled_messages_user_idx ON scheduled_messages (user_id);
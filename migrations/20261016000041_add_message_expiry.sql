@@ -0,0 +1,4 @@
+-- 자동으로 사라지는 메시지. expires_at 이 지나면 정리 태스크가 지운다.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS messages_expires_at_idx ON messages (expires_at) WHERE expires_at IS NOT NULL;
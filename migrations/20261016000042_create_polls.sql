@@ -0,0 +1,27 @@
+-- 방에 올린 투표. closes_at 이 지나면 스케줄러가 closed_at 을 채운다.
+-- hidden 은 그림자 차단된 사용자가 올린 투표 (본인에게만 보인다)
+CREATE TABLE IF NOT EXISTS polls (
+    id         BIGSERIAL PRIMARY KEY,
+    room       TEXT NOT NULL,
+    user_id    INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    username   TEXT NOT NULL,
+    question   TEXT NOT NULL,
+    options    TEXT[] NOT NULL,
+    anonymous  BOOLEAN NOT NULL DEFAULT false,
+    hidden     BOOLEAN NOT NULL DEFAULT false,
+    closes_at  TIMESTAMPTZ,
+    closed_at  TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS polls_room_idx ON polls (room, id);
+CREATE INDEX IF NOT EXISTS polls_closes_at_idx ON polls (closes_at) WHERE closed_at IS NULL AND closes_at IS NOT NULL;
+
+-- 사용자마다 투표 하나에 선택지 하나. 다시 고르면 바뀐다.
+CREATE TABLE IF NOT EXISTS poll_votes (
+    poll_id  BIGINT NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    user_id  INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    option   SMALLINT NOT NULL,
+    voted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (poll_id, user_id)
+);
@@ -0,0 +1,6 @@
+-- 다른 방에서 전달한 메시지의 원래 작성자와 위치 ({message_id, room, user_id, username, sent_at}).
+-- 원래 메시지가 지워져도 남도록 복사해 둔다.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS forwarded JSONB;
+
+-- 고유 링크의 앞뒤 메시지 조회
+CREATE INDEX IF NOT EXISTS messages_room_id_idx ON messages (room, id);
@@ -0,0 +1,10 @@
+-- 사용자가 별표로 저장한 메시지. 본인만 본다.
+CREATE TABLE IF NOT EXISTS message_stars (
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    message_id BIGINT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (user_id, message_id)
+);
+
+CREATE INDEX IF NOT EXISTS message_stars_user_idx ON message_stars (user_id, id);
@@ -0,0 +1,2 @@
+-- 날짜로 건너뛰기 (GET /rooms/:room/messages?around=<시각>)
+CREATE INDEX IF NOT EXISTS messages_room_created_at_idx ON messages (room, created_at);
@@ -0,0 +1,10 @@
+-- 방 관리자가 아닌 일반 사용자에게 맡긴 방. 관리자가 지정한다.
+CREATE TABLE IF NOT EXISTS room_owners (
+    room       TEXT NOT NULL,
+    user_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_by   INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (room, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS room_owners_user_idx ON room_owners (user_id);
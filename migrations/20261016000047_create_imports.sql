@@ -0,0 +1,23 @@
+-- Slack/Discord 내보내기 가져오기 작업. status 는 'pending', 'running', 'done', 'failed'.
+CREATE TABLE IF NOT EXISTS imports (
+    id                BIGSERIAL PRIMARY KEY,
+    source            TEXT NOT NULL,
+    room_prefix       TEXT NOT NULL DEFAULT '',
+    status            TEXT NOT NULL DEFAULT 'pending',
+    rooms             INTEGER NOT NULL DEFAULT 0,
+    users_created     INTEGER NOT NULL DEFAULT 0,
+    messages_imported INTEGER NOT NULL DEFAULT 0,
+    messages_skipped  INTEGER NOT NULL DEFAULT 0,
+    error             TEXT,
+    created_by        INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at      TIMESTAMPTZ
+);
+
+-- 가져온 사용자와 메시지의 원래 id ('slack:U123', 'discord:1234:5678' 등). 같은 내보내기를 다시 가져와도
+-- 사용자와 메시지가 겹치지 않게 한다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS imported_from TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS users_imported_from_idx ON users (imported_from) WHERE imported_from IS NOT NULL;
+
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS imported_from TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS messages_imported_from_idx ON messages (imported_from) WHERE imported_from IS NOT NULL;
@@ -0,0 +1,21 @@
+-- 방마다의 메시지 보관 정책. days 와 action 이 NULL 이면 전역 기본값(RETENTION_DAYS, RETENTION_ACTION)을 따른다.
+-- exempt 인 방의 메시지는 지우지 않는다. action 은 'delete' 또는 'archive'.
+CREATE TABLE IF NOT EXISTS room_retention (
+    room       TEXT PRIMARY KEY,
+    days       INTEGER,
+    action     TEXT,
+    exempt     BOOLEAN NOT NULL DEFAULT false,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- 보관 기간이 지나 'archive' 정책으로 옮긴 메시지. message 에는 옮기기 전 행 전체가 들어 있다.
+CREATE TABLE IF NOT EXISTS archived_messages (
+    id          BIGINT PRIMARY KEY,
+    room        TEXT,
+    created_at  TIMESTAMPTZ,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    message     JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS archived_messages_room_idx ON archived_messages (room, created_at);
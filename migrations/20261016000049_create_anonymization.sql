@@ -0,0 +1,40 @@
+-- 지워진 계정의 이름. 익명화 작업이 메시지 속 언급 등을 지울 때 쓰고, 처리한 뒤에는 지운다.
+CREATE TABLE IF NOT EXISTS deleted_accounts (
+    user_id    INTEGER PRIMARY KEY,
+    username   TEXT NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- 계정 삭제, 게스트 정리, 봇 삭제(주인 계정과 함께 지워지는 경우 포함) 어느 경로로 지워져도 남긴다
+CREATE OR REPLACE FUNCTION record_deleted_account() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO deleted_accounts (user_id, username) VALUES (OLD.id, OLD.username)
+        ON CONFLICT (user_id) DO NOTHING;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS users_record_deleted ON users;
+CREATE TRIGGER users_record_deleted AFTER DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION record_deleted_account();
+
+-- 이 트리거보다 먼저 지워진 계정 중 메시지가 남아 있는 것
+INSERT INTO deleted_accounts (user_id, username)
+SELECT DISTINCT ON (m.user_id) m.user_id, m.username FROM messages m
+WHERE m.user_id IS NOT NULL AND m.username IS NOT NULL AND NOT EXISTS (SELECT 1 FROM users u WHERE u.id = m.user_id)
+ORDER BY m.user_id, m.id DESC
+ON CONFLICT (user_id) DO NOTHING;
+
+-- 관리자가 띄운 익명화 작업. status 는 'running', 'done', 'failed' 이고, touched 는 고친 행 수를 종류별로 센 것.
+CREATE TABLE IF NOT EXISTS anonymization_jobs (
+    id           BIGSERIAL PRIMARY KEY,
+    status       TEXT NOT NULL DEFAULT 'running',
+    touched      JSONB NOT NULL DEFAULT '{}',
+    error        TEXT,
+    created_by   INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+-- 동시에 하나만 돌게 한다
+CREATE UNIQUE INDEX IF NOT EXISTS anonymization_jobs_running_idx ON anonymization_jobs ((true)) WHERE status = 'running';
@@ -0,0 +1,10 @@
+-- 점검 모드. 행은 하나뿐이다(id 는 항상 true). message 가 NULL 이면 기본 안내 문구를 쓴다.
+CREATE TABLE IF NOT EXISTS maintenance (
+    id         BOOLEAN PRIMARY KEY DEFAULT true CHECK (id),
+    enabled    BOOLEAN NOT NULL DEFAULT false,
+    message    TEXT,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO maintenance (id) VALUES (true) ON CONFLICT DO NOTHING;
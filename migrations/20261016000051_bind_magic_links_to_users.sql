@@ -0,0 +1,2 @@
+-- 매직 링크는 요청할 때 그 주소를 가진 계정에 묶는다. 그 전에 만든 링크(user_id 가 NULL)는 더 이상 쓸 수 없다.
+ALTER TABLE magic_links ADD COLUMN IF NOT EXISTS user_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
@@ -0,0 +1,587 @@
+-- MySQL/MariaDB 저장소(src/repo/mysql/)가 쓰는 테이블. migrations/ 의 Postgres 마이그레이션을 모두 적용한 뒤의
+-- 모양을 한 파일로 옮겼다.
+--
+-- 시각은 UTC 로 DATETIME(6) 에 저장한다 (sqlx 가 연결의 time_zone 을 +00:00 으로 맞춘다). TEXT[] 와 JSONB 는 JSON,
+-- CIDR 은 "주소/길이" 문자열로 둔다. 문자열은 Postgres 처럼 그대로 비교하도록 utf8mb4_bin 으로 두고, 대소문자를
+-- 가리지 않는 이름과 메일 주소의 유니크 인덱스는 lower() 를 저장한 생성 열에 건다. 방 이름은 255 자까지 쓴다.
+--
+-- 부분 인덱스가 없으므로 `WHERE` 가 붙은 Postgres 인덱스는 조건 없는 인덱스나 조건에 맞는 행에만 값이 있는 생성
+-- 열의 유니크 인덱스로 바꿨다. 자동 증가 열은 sqlx 가 부호 없는 정수를 i64 로 읽지 않으므로 부호 있는 정수다.
+
+CREATE TABLE users (
+    id INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    username VARCHAR(64) NOT NULL,
+    password_hash VARCHAR(255),
+    email VARCHAR(254),
+    email_verified_at DATETIME(6),
+    is_admin BOOLEAN NOT NULL DEFAULT FALSE,
+    invite_id BIGINT,
+    is_guest BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    muted_until DATETIME(6),
+    shadow_banned BOOLEAN NOT NULL DEFAULT FALSE,
+    disabled_at DATETIME(6),
+    status VARCHAR(16) NOT NULL DEFAULT 'online',
+    status_message TEXT,
+    email_digest BOOLEAN NOT NULL DEFAULT TRUE,
+    digest_sent_at DATETIME(6) DEFAULT CURRENT_TIMESTAMP(6),
+    last_online_at DATETIME(6),
+    is_bot BOOLEAN NOT NULL DEFAULT FALSE,
+    bot_owner_id INT,
+    imported_from VARCHAR(255),
+    -- 대소문자만 다른 이름과 메일 주소도 막는다
+    username_key VARCHAR(64) AS (LOWER(username)) STORED,
+    email_key VARCHAR(254) AS (LOWER(email)) STORED,
+    UNIQUE KEY users_username_key (username_key),
+    UNIQUE KEY users_email_key (email_key),
+    UNIQUE KEY users_imported_from_key (imported_from),
+    KEY users_guest_created_idx (is_guest, created_at),
+    CONSTRAINT users_bot_owner_id_fkey FOREIGN KEY (bot_owner_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE invite_codes (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    code_hash CHAR(64) NOT NULL,
+    created_by INT,
+    max_uses INT NOT NULL DEFAULT 1,
+    uses INT NOT NULL DEFAULT 0,
+    expires_at DATETIME(6),
+    revoked_at DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    UNIQUE KEY invite_codes_code_hash_key (code_hash),
+    CONSTRAINT invite_codes_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+ALTER TABLE users
+    ADD CONSTRAINT users_invite_id_fkey FOREIGN KEY (invite_id) REFERENCES invite_codes (id) ON DELETE SET NULL;
+
+CREATE TABLE refresh_tokens (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    family_id VARCHAR(64) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at DATETIME(6) NOT NULL,
+    used_at DATETIME(6),
+    revoked_at DATETIME(6),
+    UNIQUE KEY refresh_tokens_token_hash_key (token_hash),
+    KEY refresh_tokens_family_idx (family_id),
+    CONSTRAINT refresh_tokens_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE revoked_tokens (
+    jti VARCHAR(64) NOT NULL PRIMARY KEY,
+    expires_at DATETIME(6) NOT NULL,
+    revoked_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY revoked_tokens_expires_idx (expires_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE sessions (
+    id VARCHAR(64) NOT NULL PRIMARY KEY,
+    user_id INT NOT NULL,
+    user_agent TEXT,
+    ip VARCHAR(64),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_seen DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    revoked_at DATETIME(6),
+    KEY sessions_revoked_at_idx (revoked_at),
+    CONSTRAINT sessions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE password_reset_tokens (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at DATETIME(6) NOT NULL,
+    used_at DATETIME(6),
+    UNIQUE KEY password_reset_tokens_token_hash_key (token_hash),
+    CONSTRAINT password_reset_tokens_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE email_verification_tokens (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    email VARCHAR(254) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at DATETIME(6) NOT NULL,
+    used_at DATETIME(6),
+    UNIQUE KEY email_verification_tokens_token_hash_key (token_hash),
+    CONSTRAINT email_verification_tokens_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE oauth_identities (
+    provider VARCHAR(32) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    user_id INT NOT NULL,
+    email VARCHAR(254),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    PRIMARY KEY (provider, subject),
+    CONSTRAINT oauth_identities_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE user_totp (
+    user_id INT NOT NULL PRIMARY KEY,
+    secret VARCHAR(255) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    confirmed_at DATETIME(6),
+    last_used_step BIGINT,
+    CONSTRAINT user_totp_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE login_challenges (
+    token_hash CHAR(64) NOT NULL PRIMARY KEY,
+    user_id INT NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at DATETIME(6) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    CONSTRAINT login_challenges_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 자격 증명 id 는 1023 바이트까지 될 수 있어 앞부분에만 유니크 인덱스를 건다
+CREATE TABLE passkeys (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    credential_id VARCHAR(1400) NOT NULL,
+    passkey MEDIUMTEXT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_used_at DATETIME(6),
+    UNIQUE KEY passkeys_credential_id_key (credential_id(255)),
+    CONSTRAINT passkeys_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE webauthn_ceremonies (
+    token_hash CHAR(64) NOT NULL PRIMARY KEY,
+    user_id INT NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    state MEDIUMTEXT NOT NULL,
+    expires_at DATETIME(6) NOT NULL,
+    CONSTRAINT webauthn_ceremonies_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE magic_links (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    email VARCHAR(254) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    expires_at DATETIME(6) NOT NULL,
+    used_at DATETIME(6),
+    user_id INT,
+    UNIQUE KEY magic_links_token_hash_key (token_hash),
+    CONSTRAINT magic_links_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- key 는 예약어라 쓸 때 `key` 로 감싼다
+CREATE TABLE auth_throttle (
+    `key` VARCHAR(255) NOT NULL PRIMARY KEY,
+    failures INT NOT NULL DEFAULT 0,
+    last_failure_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    locked_until DATETIME(6)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE data_exports (
+    id VARCHAR(64) NOT NULL PRIMARY KEY,
+    user_id INT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    archive VARCHAR(255),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    completed_at DATETIME(6),
+    expires_at DATETIME(6) NOT NULL,
+    CONSTRAINT data_exports_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE profiles (
+    user_id INT NOT NULL PRIMARY KEY,
+    display_name VARCHAR(255),
+    bio TEXT,
+    pronouns VARCHAR(255),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    avatar_version VARCHAR(64),
+    CONSTRAINT profiles_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE webhooks (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    room VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_by INT,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_used_at DATETIME(6),
+    revoked_at DATETIME(6),
+    UNIQUE KEY webhooks_token_hash_key (token_hash),
+    CONSTRAINT webhooks_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- user_id 에는 외래 키가 없다. 웹훅이 보낸 메시지는 NULL 이다.
+CREATE TABLE messages (
+    id BIGINT NOT NULL PRIMARY KEY,
+    user_id INT,
+    username VARCHAR(64),
+    room VARCHAR(255),
+    content TEXT,
+    created_at DATETIME(6) DEFAULT CURRENT_TIMESTAMP(6),
+    format VARCHAR(16) NOT NULL DEFAULT 'plain',
+    html MEDIUMTEXT,
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    webhook_id BIGINT,
+    expires_at DATETIME(6),
+    forwarded JSON,
+    imported_from VARCHAR(255),
+    KEY messages_room_id_idx (room, id),
+    KEY messages_room_created_at_idx (room, created_at),
+    KEY messages_user_idx (user_id),
+    KEY messages_expires_at_idx (expires_at),
+    UNIQUE KEY messages_imported_from_key (imported_from),
+    CONSTRAINT messages_webhook_id_fkey FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 시퀀스 대신 다음에 나눠 줄 메시지 id 를 한 행에 둔다. 메시지를 넣는 곳은 모두 여기서 id 를 받는다.
+CREATE TABLE message_id_sequence (
+    last_id BIGINT NOT NULL
+) ENGINE = InnoDB;
+
+INSERT INTO message_id_sequence (last_id) VALUES (0);
+
+CREATE TABLE attachments (
+    id VARCHAR(64) NOT NULL PRIMARY KEY,
+    message_id BIGINT NOT NULL,
+    user_id INT,
+    room VARCHAR(255) NOT NULL,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size BIGINT NOT NULL,
+    storage_key VARCHAR(255) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    width INT,
+    height INT,
+    thumbnail_key VARCHAR(255),
+    thumbnail_width INT,
+    thumbnail_height INT,
+    kind VARCHAR(16) NOT NULL DEFAULT 'file',
+    duration_ms INT,
+    KEY attachments_room_idx (room),
+    CONSTRAINT attachments_message_id_fkey FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE CASCADE,
+    CONSTRAINT attachments_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 주소는 인덱스에 다 들어가지 않을 만큼 길 수 있어 SHA-256 으로 찾는다
+CREATE TABLE link_previews (
+    url_hash CHAR(64) NOT NULL PRIMARY KEY,
+    url TEXT NOT NULL,
+    title TEXT,
+    description TEXT,
+    image_url TEXT,
+    site_name TEXT,
+    fetched_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY link_previews_fetched_at_idx (fetched_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE word_filters (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    room VARCHAR(255),
+    pattern VARCHAR(255) NOT NULL,
+    action VARCHAR(16) NOT NULL DEFAULT 'mask',
+    created_by INT,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    room_key VARCHAR(255) AS (COALESCE(room, '')) STORED,
+    UNIQUE KEY word_filters_room_pattern_key (room_key, pattern),
+    CONSTRAINT word_filters_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE spam_flags (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    room VARCHAR(255) NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    sample TEXT NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY spam_flags_created_at_idx (created_at),
+    CONSTRAINT spam_flags_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE message_reports (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    message_id BIGINT,
+    room VARCHAR(255) NOT NULL,
+    author_id INT,
+    content TEXT,
+    reporter_id INT NOT NULL,
+    reason TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    resolution VARCHAR(32),
+    resolved_by INT,
+    resolved_at DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    UNIQUE KEY message_reports_message_reporter_key (message_id, reporter_id),
+    KEY message_reports_status_idx (status, created_at),
+    CONSTRAINT message_reports_message_id_fkey FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE SET NULL,
+    CONSTRAINT message_reports_author_id_fkey FOREIGN KEY (author_id) REFERENCES users (id) ON DELETE SET NULL,
+    CONSTRAINT message_reports_reporter_id_fkey FOREIGN KEY (reporter_id) REFERENCES users (id) ON DELETE CASCADE,
+    CONSTRAINT message_reports_resolved_by_fkey FOREIGN KEY (resolved_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 추가만 할 수 있고 고치거나 지울 수 없다
+CREATE TABLE audit_log (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    actor_id INT,
+    actor_name VARCHAR(64),
+    action VARCHAR(64) NOT NULL,
+    target_type VARCHAR(32) NOT NULL,
+    target_id VARCHAR(255) NOT NULL,
+    reason TEXT,
+    details JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY audit_log_created_at_idx (created_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log FOR EACH ROW
+    SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'audit_log is append-only';
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log FOR EACH ROW
+    SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'audit_log is append-only';
+
+CREATE TABLE ip_bans (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    network VARCHAR(64) NOT NULL,
+    reason TEXT,
+    created_by INT,
+    expires_at DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT ip_bans_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 외래 키에 CASCADE 가 걸린 열은 CHECK 나 생성 열에 쓸 수 없다. 자기 자신에게 보내는 요청은 핸들러가 막고,
+-- 거꾸로 된 쌍(상대가 먼저 보낸 요청)은 저장소가 트랜잭션 안에서 막는다.
+CREATE TABLE friendships (
+    requester_id INT NOT NULL,
+    addressee_id INT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    accepted_at DATETIME(6),
+    PRIMARY KEY (requester_id, addressee_id),
+    KEY friendships_addressee_idx (addressee_id),
+    CONSTRAINT friendships_requester_id_fkey FOREIGN KEY (requester_id) REFERENCES users (id) ON DELETE CASCADE,
+    CONSTRAINT friendships_addressee_id_fkey FOREIGN KEY (addressee_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE notifications (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    room VARCHAR(255),
+    message_id BIGINT,
+    actor_id INT,
+    text TEXT NOT NULL,
+    read_at DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY notifications_user_idx (user_id, id),
+    KEY notifications_created_at_idx (created_at),
+    CONSTRAINT notifications_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CONSTRAINT notifications_message_id_fkey FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE SET NULL,
+    CONSTRAINT notifications_actor_id_fkey FOREIGN KEY (actor_id) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE room_notification_prefs (
+    user_id INT NOT NULL,
+    room VARCHAR(255) NOT NULL,
+    level VARCHAR(16) NOT NULL,
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    PRIMARY KEY (user_id, room),
+    KEY room_notification_prefs_room_idx (room, level),
+    CONSTRAINT room_notification_prefs_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE keyword_alerts (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    keyword VARCHAR(255) NOT NULL,
+    room VARCHAR(255),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    room_key VARCHAR(255) AS (COALESCE(room, '')) STORED,
+    UNIQUE KEY keyword_alerts_user_keyword_key (user_id, room_key, keyword),
+    CONSTRAINT keyword_alerts_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 엔드포인트 주소는 길 수 있어 앞부분에만 유니크 인덱스를 건다
+CREATE TABLE push_subscriptions (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    endpoint VARCHAR(2048) NOT NULL,
+    p256dh VARCHAR(255) NOT NULL,
+    auth VARCHAR(255) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_used_at DATETIME(6),
+    UNIQUE KEY push_subscriptions_endpoint_key (endpoint(700)),
+    CONSTRAINT push_subscriptions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE bot_tokens (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    bot_id INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    scopes JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_used_at DATETIME(6),
+    expires_at DATETIME(6),
+    revoked_at DATETIME(6),
+    UNIQUE KEY bot_tokens_token_hash_key (token_hash),
+    CONSTRAINT bot_tokens_bot_id_fkey FOREIGN KEY (bot_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE room_topics (
+    room VARCHAR(255) NOT NULL PRIMARY KEY,
+    topic TEXT NOT NULL,
+    set_by INT,
+    set_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT room_topics_set_by_fkey FOREIGN KEY (set_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE reminders (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    room VARCHAR(255) NOT NULL,
+    target VARCHAR(64) NOT NULL,
+    text TEXT NOT NULL,
+    remind_at DATETIME(6) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY reminders_remind_at_idx (remind_at),
+    CONSTRAINT reminders_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE scheduled_messages (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    room VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    format VARCHAR(16) NOT NULL DEFAULT 'plain',
+    send_at DATETIME(6) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY scheduled_messages_send_at_idx (send_at),
+    CONSTRAINT scheduled_messages_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE polls (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    room VARCHAR(255) NOT NULL,
+    user_id INT,
+    username VARCHAR(64) NOT NULL,
+    question TEXT NOT NULL,
+    options JSON NOT NULL,
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    closes_at DATETIME(6),
+    closed_at DATETIME(6),
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY polls_room_idx (room, id),
+    KEY polls_closes_at_idx (closed_at, closes_at),
+    CONSTRAINT polls_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- option 은 예약어라 쓸 때 `option` 으로 감싼다
+CREATE TABLE poll_votes (
+    poll_id BIGINT NOT NULL,
+    user_id INT NOT NULL,
+    `option` SMALLINT NOT NULL,
+    voted_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    PRIMARY KEY (poll_id, user_id),
+    CONSTRAINT poll_votes_poll_id_fkey FOREIGN KEY (poll_id) REFERENCES polls (id) ON DELETE CASCADE,
+    CONSTRAINT poll_votes_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE message_stars (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    message_id BIGINT NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    UNIQUE KEY message_stars_user_message_key (user_id, message_id),
+    CONSTRAINT message_stars_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CONSTRAINT message_stars_message_id_fkey FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE room_owners (
+    room VARCHAR(255) NOT NULL,
+    user_id INT NOT NULL,
+    added_by INT,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    PRIMARY KEY (room, user_id),
+    CONSTRAINT room_owners_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    CONSTRAINT room_owners_added_by_fkey FOREIGN KEY (added_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE imports (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    source VARCHAR(16) NOT NULL,
+    room_prefix VARCHAR(255) NOT NULL DEFAULT '',
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    rooms INT NOT NULL DEFAULT 0,
+    users_created INT NOT NULL DEFAULT 0,
+    messages_imported BIGINT NOT NULL DEFAULT 0,
+    messages_skipped BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    created_by INT,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    completed_at DATETIME(6),
+    CONSTRAINT imports_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE room_retention (
+    room VARCHAR(255) NOT NULL PRIMARY KEY,
+    days INT,
+    action VARCHAR(16),
+    exempt BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by INT,
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT room_retention_updated_by_fkey FOREIGN KEY (updated_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- message 는 옮기기 전 행 전체의 JSON
+CREATE TABLE archived_messages (
+    id BIGINT NOT NULL PRIMARY KEY,
+    room VARCHAR(255),
+    created_at DATETIME(6),
+    archived_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    message JSON NOT NULL,
+    KEY archived_messages_room_idx (room, created_at)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+CREATE TABLE deleted_accounts (
+    user_id INT NOT NULL PRIMARY KEY,
+    username VARCHAR(64) NOT NULL,
+    deleted_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 직접 지운 계정의 이름을 남긴다. 외래 키의 CASCADE 로 지워진 행에는 트리거가 돌지 않으므로, 주인과 함께 지울
+-- 봇은 저장소가 먼저 지운다.
+CREATE TRIGGER users_record_deleted AFTER DELETE ON users FOR EACH ROW
+    INSERT IGNORE INTO deleted_accounts (user_id, username) VALUES (OLD.id, OLD.username);
+
+CREATE TABLE anonymization_jobs (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    status VARCHAR(16) NOT NULL DEFAULT 'running',
+    touched JSON NOT NULL,
+    error TEXT,
+    created_by INT,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    completed_at DATETIME(6),
+    -- 동시에 하나만 돌게 한다. 돌고 있는 작업에만 값이 있다.
+    running_key BOOLEAN AS (CASE WHEN status = 'running' THEN TRUE END) STORED,
+    UNIQUE KEY anonymization_jobs_running_key (running_key),
+    CONSTRAINT anonymization_jobs_created_by_fkey FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+-- 점검 모드. 행은 하나뿐이다.
+CREATE TABLE maintenance (
+    id BOOLEAN NOT NULL DEFAULT TRUE PRIMARY KEY,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    message TEXT,
+    updated_by INT,
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CHECK (id),
+    CONSTRAINT maintenance_updated_by_fkey FOREIGN KEY (updated_by) REFERENCES users (id) ON DELETE SET NULL
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_bin;
+
+INSERT INTO maintenance (id) VALUES (TRUE);
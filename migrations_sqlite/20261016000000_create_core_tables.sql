@@ -0,0 +1,559 @@
+-- SQLite 저장소(src/repo/sqlite/)가 쓰는 테이블. migrations/ 의 Postgres 마이그레이션을 모두 적용한 뒤의 모양을
+-- 한 파일로 옮겼다.
+--
+-- 시각은 sqlx 가 쓰는 RFC 3339 문자열(UTC)로 저장하고 비교한다. 기본값도 같은 모양으로 만들어 문자열 순서가 시간
+-- 순서와 같게 한다. TEXT[] 와 JSONB 는 JSON 문자열, CIDR 은 "주소/길이" 문자열로 둔다.
+
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    password_hash TEXT,
+    email TEXT,
+    email_verified_at TEXT,
+    is_admin BOOLEAN NOT NULL DEFAULT 0,
+    invite_id INTEGER REFERENCES invite_codes(id) ON DELETE SET NULL,
+    is_guest BOOLEAN NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    muted_until TEXT,
+    shadow_banned BOOLEAN NOT NULL DEFAULT 0,
+    disabled_at TEXT,
+    status TEXT NOT NULL DEFAULT 'online',
+    status_message TEXT,
+    email_digest BOOLEAN NOT NULL DEFAULT 1,
+    digest_sent_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_online_at TEXT,
+    is_bot BOOLEAN NOT NULL DEFAULT 0,
+    bot_owner_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    imported_from TEXT
+);
+
+-- 대소문자만 다른 이름과 메일 주소도 막는다
+CREATE UNIQUE INDEX users_username_lower_key ON users (lower(username));
+CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email)) WHERE email IS NOT NULL;
+CREATE INDEX users_guest_created_idx ON users (created_at) WHERE is_guest;
+CREATE INDEX users_bot_owner_idx ON users (bot_owner_id) WHERE bot_owner_id IS NOT NULL;
+CREATE UNIQUE INDEX users_imported_from_idx ON users (imported_from) WHERE imported_from IS NOT NULL;
+
+CREATE TABLE refresh_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    expires_at TEXT NOT NULL,
+    used_at TEXT,
+    revoked_at TEXT
+);
+
+CREATE INDEX refresh_tokens_family_idx ON refresh_tokens (family_id);
+CREATE INDEX refresh_tokens_user_idx ON refresh_tokens (user_id);
+
+CREATE TABLE revoked_tokens (
+    jti TEXT PRIMARY KEY,
+    expires_at TEXT NOT NULL,
+    revoked_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX revoked_tokens_expires_idx ON revoked_tokens (expires_at);
+
+CREATE TABLE sessions (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_seen TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    revoked_at TEXT
+);
+
+CREATE INDEX sessions_user_idx ON sessions (user_id);
+
+CREATE TABLE password_reset_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    expires_at TEXT NOT NULL,
+    used_at TEXT
+);
+
+CREATE INDEX password_reset_tokens_user_idx ON password_reset_tokens (user_id);
+
+CREATE TABLE email_verification_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    expires_at TEXT NOT NULL,
+    used_at TEXT
+);
+
+CREATE INDEX email_verification_tokens_user_idx ON email_verification_tokens (user_id);
+
+CREATE TABLE oauth_identities (
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (provider, subject)
+);
+
+CREATE INDEX oauth_identities_user_idx ON oauth_identities (user_id);
+
+CREATE TABLE user_totp (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    confirmed_at TEXT,
+    last_used_step INTEGER
+);
+
+CREATE TABLE login_challenges (
+    token_hash TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    expires_at TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE passkeys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    credential_id TEXT NOT NULL UNIQUE,
+    passkey TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_used_at TEXT
+);
+
+CREATE INDEX passkeys_user_idx ON passkeys (user_id);
+
+CREATE TABLE webauthn_ceremonies (
+    token_hash TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    state TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE TABLE magic_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    expires_at TEXT NOT NULL,
+    used_at TEXT,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE auth_throttle (
+    key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    locked_until TEXT
+);
+
+CREATE TABLE invite_codes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code_hash TEXT NOT NULL UNIQUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    max_uses INTEGER NOT NULL DEFAULT 1,
+    uses INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT,
+    revoked_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE data_exports (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    archive TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    completed_at TEXT,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX data_exports_user_idx ON data_exports (user_id);
+
+CREATE TABLE profiles (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    display_name TEXT,
+    bio TEXT,
+    pronouns TEXT,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    avatar_version TEXT
+);
+
+CREATE TABLE webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_used_at TEXT,
+    revoked_at TEXT
+);
+
+-- user_id 에는 외래 키가 없다. 웹훅이 보낸 메시지는 NULL 이다.
+CREATE TABLE messages (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER,
+    username TEXT,
+    room TEXT,
+    content TEXT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    format TEXT NOT NULL DEFAULT 'plain',
+    html TEXT,
+    hidden BOOLEAN NOT NULL DEFAULT 0,
+    webhook_id INTEGER REFERENCES webhooks(id) ON DELETE SET NULL,
+    expires_at TEXT,
+    forwarded TEXT,
+    imported_from TEXT
+);
+
+CREATE INDEX messages_room_id_idx ON messages (room, id);
+CREATE INDEX messages_room_created_at_idx ON messages (room, created_at);
+CREATE INDEX messages_expires_at_idx ON messages (expires_at) WHERE expires_at IS NOT NULL;
+CREATE UNIQUE INDEX messages_imported_from_idx ON messages (imported_from) WHERE imported_from IS NOT NULL;
+
+-- SQLite 에는 시퀀스가 없으므로 다음에 나눠 줄 메시지 id 를 한 행에 둔다. 메시지를 넣는 곳은 모두 여기서 id 를
+-- 받는다.
+CREATE TABLE message_id_sequence (
+    last_id INTEGER NOT NULL
+);
+
+INSERT INTO message_id_sequence (last_id) VALUES (0);
+
+CREATE TABLE attachments (
+    id TEXT PRIMARY KEY,
+    message_id INTEGER NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    room TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    storage_key TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    width INTEGER,
+    height INTEGER,
+    thumbnail_key TEXT,
+    thumbnail_width INTEGER,
+    thumbnail_height INTEGER,
+    kind TEXT NOT NULL DEFAULT 'file',
+    duration_ms INTEGER
+);
+
+CREATE INDEX attachments_message_idx ON attachments (message_id);
+
+CREATE TABLE link_previews (
+    url TEXT PRIMARY KEY,
+    title TEXT,
+    description TEXT,
+    image_url TEXT,
+    site_name TEXT,
+    fetched_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE word_filters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room TEXT,
+    pattern TEXT NOT NULL,
+    action TEXT NOT NULL DEFAULT 'mask',
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE UNIQUE INDEX word_filters_room_pattern_idx ON word_filters (COALESCE(room, ''), pattern);
+
+CREATE TABLE spam_flags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    sample TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX spam_flags_created_at_idx ON spam_flags (created_at DESC);
+
+CREATE TABLE message_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id INTEGER REFERENCES messages(id) ON DELETE SET NULL,
+    room TEXT NOT NULL,
+    author_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    content TEXT,
+    reporter_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    resolution TEXT,
+    resolved_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    UNIQUE (message_id, reporter_id)
+);
+
+CREATE INDEX message_reports_status_idx ON message_reports (status, created_at);
+
+-- 추가만 할 수 있고 고치거나 지울 수 없다
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id INTEGER,
+    actor_name TEXT,
+    action TEXT NOT NULL,
+    target_type TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    reason TEXT,
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX audit_log_created_at_idx ON audit_log (created_at);
+
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TABLE ip_bans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    network TEXT NOT NULL,
+    reason TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE friendships (
+    requester_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    addressee_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    accepted_at TEXT,
+    PRIMARY KEY (requester_id, addressee_id),
+    CHECK (requester_id <> addressee_id)
+);
+
+CREATE UNIQUE INDEX friendships_pair_idx
+    ON friendships (min(requester_id, addressee_id), max(requester_id, addressee_id));
+CREATE INDEX friendships_addressee_idx ON friendships (addressee_id);
+
+CREATE TABLE notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    room TEXT,
+    message_id INTEGER REFERENCES messages(id) ON DELETE SET NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    text TEXT NOT NULL,
+    read_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX notifications_user_idx ON notifications (user_id, id DESC);
+CREATE INDEX notifications_unread_idx ON notifications (user_id) WHERE read_at IS NULL;
+
+CREATE TABLE room_notification_prefs (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room TEXT NOT NULL,
+    level TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (user_id, room)
+);
+
+CREATE INDEX room_notification_prefs_room_idx ON room_notification_prefs (room) WHERE level = 'all';
+
+CREATE TABLE keyword_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    keyword TEXT NOT NULL,
+    room TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE UNIQUE INDEX keyword_alerts_user_keyword_idx ON keyword_alerts (user_id, COALESCE(room, ''), keyword);
+
+CREATE TABLE push_subscriptions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    endpoint TEXT NOT NULL UNIQUE,
+    p256dh TEXT NOT NULL,
+    auth TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_used_at TEXT
+);
+
+CREATE INDEX push_subscriptions_user_idx ON push_subscriptions (user_id);
+
+-- scopes 는 JSON 배열
+CREATE TABLE bot_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bot_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    last_used_at TEXT,
+    expires_at TEXT,
+    revoked_at TEXT
+);
+
+CREATE INDEX bot_tokens_bot_idx ON bot_tokens (bot_id);
+
+CREATE TABLE room_topics (
+    room TEXT PRIMARY KEY,
+    topic TEXT NOT NULL,
+    set_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    set_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE reminders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room TEXT NOT NULL,
+    target TEXT NOT NULL,
+    text TEXT NOT NULL,
+    remind_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX reminders_remind_at_idx ON reminders (remind_at);
+CREATE INDEX reminders_user_idx ON reminders (user_id);
+
+CREATE TABLE scheduled_messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room TEXT NOT NULL,
+    content TEXT NOT NULL,
+    format TEXT NOT NULL DEFAULT 'plain',
+    send_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX scheduled_messages_send_at_idx ON scheduled_messages (send_at);
+CREATE INDEX scheduled_messages_user_idx ON scheduled_messages (user_id);
+
+-- options 는 JSON 배열
+CREATE TABLE polls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    room TEXT NOT NULL,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    username TEXT NOT NULL,
+    question TEXT NOT NULL,
+    options TEXT NOT NULL,
+    anonymous BOOLEAN NOT NULL DEFAULT 0,
+    hidden BOOLEAN NOT NULL DEFAULT 0,
+    closes_at TEXT,
+    closed_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE INDEX polls_room_idx ON polls (room, id);
+CREATE INDEX polls_closes_at_idx ON polls (closes_at) WHERE closed_at IS NULL AND closes_at IS NOT NULL;
+
+CREATE TABLE poll_votes (
+    poll_id INTEGER NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    option INTEGER NOT NULL,
+    voted_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (poll_id, user_id)
+);
+
+CREATE TABLE message_stars (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    message_id INTEGER NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    UNIQUE (user_id, message_id)
+);
+
+CREATE INDEX message_stars_user_idx ON message_stars (user_id, id);
+
+CREATE TABLE room_owners (
+    room TEXT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (room, user_id)
+);
+
+CREATE INDEX room_owners_user_idx ON room_owners (user_id);
+
+CREATE TABLE imports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    room_prefix TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'pending',
+    rooms INTEGER NOT NULL DEFAULT 0,
+    users_created INTEGER NOT NULL DEFAULT 0,
+    messages_imported INTEGER NOT NULL DEFAULT 0,
+    messages_skipped INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    completed_at TEXT
+);
+
+CREATE TABLE room_retention (
+    room TEXT PRIMARY KEY,
+    days INTEGER,
+    action TEXT,
+    exempt BOOLEAN NOT NULL DEFAULT 0,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+-- message 는 옮기기 전 행 전체의 JSON
+CREATE TABLE archived_messages (
+    id INTEGER PRIMARY KEY,
+    room TEXT,
+    created_at TEXT,
+    archived_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    message TEXT NOT NULL
+);
+
+CREATE INDEX archived_messages_room_idx ON archived_messages (room, created_at);
+
+CREATE TABLE deleted_accounts (
+    user_id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL,
+    deleted_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+-- 어느 경로로 지워진 계정이든 이름을 남긴다
+CREATE TRIGGER users_record_deleted AFTER DELETE ON users
+BEGIN
+    INSERT OR IGNORE INTO deleted_accounts (user_id, username) VALUES (OLD.id, OLD.username);
+END;
+
+CREATE TABLE anonymization_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    status TEXT NOT NULL DEFAULT 'running',
+    touched TEXT NOT NULL DEFAULT '{}',
+    error TEXT,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    completed_at TEXT
+);
+
+-- 동시에 하나만 돌게 한다
+CREATE UNIQUE INDEX anonymization_jobs_running_idx ON anonymization_jobs (status) WHERE status = 'running';
+
+-- 점검 모드. 행은 하나뿐이다.
+CREATE TABLE maintenance (
+    id BOOLEAN PRIMARY KEY DEFAULT 1 CHECK (id),
+    enabled BOOLEAN NOT NULL DEFAULT 0,
+    message TEXT,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+INSERT INTO maintenance (id) VALUES (1);
@@ -0,0 +1,159 @@
+// --- 내 계정 관리 (/me) ---
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthUser,
+    avatar, config,
+    error::{ApiError, ApiJson},
+    repo::User,
+    session::{clear_auth_cookies, revoke_user_sessions},
+    validation,
+    ws::CloseReason,
+    AppState,
+};
+
+// 계정을 지울 때 그 사용자가 쓴 메시지를 어떻게 할지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessagePolicy {
+    // 내용은 남기고 작성자 정보만 지운다 (대화 흐름 유지)
+    Anonymize,
+    // 메시지까지 모두 지운다
+    Purge,
+}
+
+static DELETION_MESSAGE_POLICY: Lazy<MessagePolicy> = Lazy::new(|| config::get().retention.account_deletion_messages);
+
+// 익명화된 메시지에 표시되는 작성자 이름
+pub const DELETED_USERNAME: &str = "[deleted]";
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordPayload {
+    current_password: String,
+    new_password: String,
+}
+
+// 비밀번호가 있는 계정은 password, 없는 계정(소셜 로그인, 매직 링크, 게스트)은 confirm 에 사용자 이름을 넣는다
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountPayload {
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    confirm: Option<String>,
+}
+
+// POST /me/password: 현재 비밀번호 확인 후 변경하고, 지금 세션을 제외한 모든 세션을 폐기한다
+pub async fn change_password_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<ChangePasswordPayload>,
+) -> Response {
+    let stored = match state.repos.users.find(claims.user_id).await {
+        Ok(Some(User { password_hash: Some(hash), .. })) => hash,
+        // 소셜 로그인 전용 계정은 비밀번호 재설정으로 처음 비밀번호를 정한다
+        Ok(Some(_)) => {
+            return ApiError::bad_request("no_password", "Account has no password; use password reset to set one")
+                .into_response()
+        }
+        Ok(None) => return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    if !state.passwords.verify_async(&payload.current_password, &stored).await.unwrap_or(false) {
+        return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response();
+    }
+
+    if let Err(errors) = validation::validate_password("new_password", &payload.new_password, Some(&claims.sub)) {
+        return errors.into_response();
+    }
+
+    let new_hash = match state.passwords.hash_async(&payload.new_password).await {
+        Ok(h) => h,
+        Err(_) => return ApiError::internal().into_response(),
+    };
+
+    if state.repos.users.set_password_hash(claims.user_id, &new_hash, None).await.is_err() {
+        return ApiError::internal().into_response();
+    }
+
+    // 비밀번호가 유출됐을 수 있으므로 다른 기기의 로그인은 모두 끊는다
+    let revoked = revoke_user_sessions(&state, claims.user_id, Some(&claims.sid)).await;
+
+    tracing::info!(
+        "User {} changed password, {} other sessions revoked",
+        claims.user_id,
+        revoked
+    );
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// DELETE /me: 모든 소켓을 닫고 세션을 폐기한 뒤, 정책에 따라 메시지를 익명화하거나 지우고 계정을 삭제한다
+pub async fn delete_account_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<DeleteAccountPayload>,
+) -> Response {
+    let stored = match state.repos.users.find(claims.user_id).await {
+        Ok(Some(user)) => user.password_hash,
+        Ok(None) => return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let confirmed = match (&stored, &payload.password, &payload.confirm) {
+        (Some(hash), Some(password), _) => state.passwords.verify_async(password, hash).await.unwrap_or(false),
+        (None, _, Some(confirm)) => confirm.trim() == claims.sub,
+        _ => false,
+    };
+    if !confirmed {
+        return match stored {
+            Some(_) => ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+            None => ApiError::bad_request("confirmation_required", "Type your username to confirm").into_response(),
+        };
+    }
+
+    // 먼저 연결과 세션을 끊어 삭제 도중에 새 메시지가 들어오지 않게 한다
+    let closed = state.connections.close_user(claims.user_id, CloseReason::AccountDeleted);
+    let revoked = revoke_user_sessions(&state, claims.user_id, None).await;
+    // 소유한 봇도 외래 키로 함께 지워지므로 봇의 연결도 닫는다
+    let bots = state.repos.accounts.bot_ids(claims.user_id).await.unwrap_or_default();
+    for bot_id in bots {
+        state.connections.close_user(bot_id, CloseReason::AccountDeleted);
+    }
+
+    let policy = *DELETION_MESSAGE_POLICY;
+    let anonymize_as = match policy {
+        MessagePolicy::Anonymize => Some(DELETED_USERNAME),
+        MessagePolicy::Purge => None,
+    };
+    let deleted = state.repos.accounts.delete_account(claims.user_id, anonymize_as).await;
+
+    match deleted {
+        Ok((messages, attachment_keys)) => {
+            tracing::info!(
+                "User {} deleted their account ({} sessions, {} connections, {} messages {:?})",
+                claims.user_id,
+                revoked,
+                closed,
+                messages,
+                policy
+            );
+            avatar::delete_files(&state, claims.user_id).await;
+            for key in attachment_keys {
+                if let Err(e) = state.attachments.delete(&key).await {
+                    tracing::warn!("Failed to delete attachment {} of user {}: {}", key, claims.user_id, e);
+                }
+            }
+            (StatusCode::NO_CONTENT, clear_auth_cookies(jar)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,285 @@
+// --- 서버 관리 API ---
+//
+// `/admin` 아래의 라우트는 모두 여기서 묶고 `auth::require_admin` 미들웨어를 건다. 사용자 목록, 계정 비활성화,
+// 강제 로그아웃, 방 삭제, 공지 방송을 제공하고, 다른 모듈의 관리 라우트(초대 코드, 금칙어, 신고, IP 차단, 웹훅,
+// 방 주인, 보관 정책, 기록 가져오기, 익명화 작업, 점검 모드 등)도 함께 둔다.
+// 관리자는 `users.is_admin` 으로 정하며 토큰이 아니라 요청마다 DB 에서 확인한다.
+
+use axum::{
+    extract::{DefaultBodyLimit, Path, Query, State},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    anonymization,
+    audit::{self, Target},
+    auth::{self, AdminUser, Claims},
+    error::{ApiError, ApiJson},
+    imports, invites, ip_bans, maintenance, moderation,
+    protocol::ServerEvent,
+    reports, request_limits, retention, room_owners,
+    session::revoke_user_sessions,
+    spam,
+    validation::ValidationErrors,
+    webhooks, word_filter,
+    ws::{CloseReason, MESSAGE_MAX_CHARS},
+    AppState,
+};
+
+const USER_PAGE_MAX: i64 = 200;
+
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/users", get(list_users_handler))
+        .route("/users/:id/disabled", put(disable_handler))
+        .route("/users/:id/logout", post(logout_handler))
+        .route("/users/:id/shadow-ban", put(moderation::shadow_ban_handler))
+        .route("/rooms/:room", delete(delete_room_handler))
+        .route("/rooms/:room/owners", get(room_owners::list_handler))
+        .route("/rooms/:room/owners/:user_id", put(room_owners::add_handler).delete(room_owners::remove_handler))
+        .route("/rooms/:room/retention/exempt", put(retention::exempt_handler))
+        .route("/retention", get(retention::list_handler))
+        .route("/room-registry", get(room_registry_handler))
+        .route("/announcements", post(announcement_handler))
+        .route("/invites", get(invites::list_handler).post(invites::create_handler))
+        .route("/invites/:id", delete(invites::revoke_handler))
+        .route("/word-filters", get(word_filter::list_handler).post(word_filter::create_handler))
+        .route("/word-filters/:id", delete(word_filter::delete_handler))
+        .route("/spam-flags", get(spam::list_handler))
+        .route("/reports", get(reports::list_handler))
+        .route("/reports/:id/resolve", post(reports::resolve_handler))
+        .route("/ip-bans", get(ip_bans::list_handler).post(ip_bans::create_handler))
+        .route("/ip-bans/:id", delete(ip_bans::delete_handler))
+        .route("/webhooks", get(webhooks::list_handler).post(webhooks::create_handler))
+        .route("/webhooks/:id", delete(webhooks::revoke_handler))
+        .route(
+            "/imports",
+            get(imports::list_handler)
+                .post(imports::create_handler)
+                .layer(DefaultBodyLimit::max(*imports::IMPORT_MAX_BYTES))
+                .layer(middleware::from_fn(request_limits::for_uploads)),
+        )
+        .route("/imports/:id", get(imports::status_handler))
+        .route("/anonymization", get(anonymization::list_handler).post(anonymization::create_handler))
+        .route("/anonymization/:id", get(anonymization::status_handler))
+        .route("/audit", get(audit::list_handler))
+        .route("/maintenance", get(maintenance::get_handler).put(maintenance::set_handler))
+        .route_layer(middleware::from_fn_with_state(state, auth::require_admin))
+}
+
+// --- 사용자 ---
+
+#[derive(Debug, Deserialize)]
+pub struct ListUsersQuery {
+    // 사용자 이름이나 이메일의 일부
+    #[serde(default)]
+    q: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// LIKE 패턴에서 특수 문자를 글자 그대로 찾도록 한다
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+// GET /admin/users?q=&limit=&offset=
+pub async fn list_users_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Query(query): Query<ListUsersQuery>,
+) -> Response {
+    let pattern = query
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", escape_like(q)));
+    let limit = query.limit.clamp(1, USER_PAGE_MAX);
+    let users = state.repos.users.search(pattern.as_deref(), limit, query.offset.max(0)).await;
+    let mut users = match users {
+        Ok(users) => users,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let counts = state.connections.counts_by_user();
+    for user in &mut users {
+        user.connections = counts.get(&user.id).copied().unwrap_or(0);
+    }
+    Json(users).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisablePayload {
+    disabled: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+// PUT /admin/users/:id/disabled: 비활성화하면 모든 세션을 폐기하고 연결을 끊는다
+pub async fn disable_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(user_id): Path<i32>,
+    ApiJson(payload): ApiJson<DisablePayload>,
+) -> Response {
+    if payload.disabled && user_id == claims.user_id {
+        return ApiError::bad_request("cannot_disable_self", "You cannot disable your own account").into_response();
+    }
+    let updated = state.repos.users.set_disabled(user_id, payload.disabled).await;
+    let (username, disabled_at) = match updated {
+        Ok(Some(user)) => user,
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let (mut closed, mut revoked) = (0, 0);
+    if payload.disabled {
+        // 세션 폐기보다 먼저 닫아야 클라이언트가 account_disabled 를 받는다
+        closed = state.connections.close_user(user_id, CloseReason::AccountDisabled);
+        revoked = revoke_user_sessions(&state, user_id, None).await;
+    }
+    tracing::info!(
+        "User {} {} user {} ({} sessions, {} connections)",
+        claims.user_id,
+        if payload.disabled { "disabled" } else { "enabled" },
+        user_id,
+        revoked,
+        closed
+    );
+    let action = if payload.disabled { "user.disable" } else { "user.enable" };
+    let details = json!({ "username": username, "sessions_revoked": revoked, "connections_closed": closed });
+    audit::record(&state, &claims, action, Target::User(user_id), payload.reason.as_deref(), details).await;
+    Json(json!({
+        "user_id": user_id,
+        "username": username,
+        "disabled_at": disabled_at,
+        "sessions_revoked": revoked,
+        "connections_closed": closed,
+    }))
+    .into_response()
+}
+
+// POST /admin/users/:id/logout: 모든 기기에서 로그아웃시킨다
+pub async fn logout_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(user_id): Path<i32>,
+) -> Response {
+    match state.repos.users.find(user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+    let revoked = revoke_user_sessions(&state, user_id, None).await;
+    tracing::info!("User {} logged out user {} ({} sessions)", claims.user_id, user_id, revoked);
+    let details = json!({ "sessions_revoked": revoked });
+    audit::record(&state, &claims, "user.logout", Target::User(user_id), None, details).await;
+    Json(json!({ "user_id": user_id, "sessions_revoked": revoked })).into_response()
+}
+
+// --- 방 ---
+
+// GET /admin/room-registry: 이 서버에 열린 방과 구독자 수 (조각별)
+async fn room_registry_handler(State(state): State<AppState>) -> Response {
+    Json(state.rooms.stats()).into_response()
+}
+
+// DELETE /admin/rooms/:room: 연결을 끊고 방의 메시지와 첨부 파일을 모두 지운다
+pub async fn delete_room_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(room): Path<String>,
+) -> Response {
+    match delete_room(&state, &claims, &room).await {
+        Ok((messages, closed)) => {
+            Json(json!({ "room": room, "messages_deleted": messages, "connections_closed": closed })).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+// 방을 지우고 지운 메시지 수와 끊은 연결 수를 돌려준다. gRPC 의 DeleteRoom 도 쓴다.
+pub async fn delete_room(state: &AppState, claims: &Claims, room: &str) -> Result<(u64, usize), ApiError> {
+    let closed = state.connections.close_room(room, CloseReason::RoomDeleted);
+    let active = state.bus.close(room);
+
+    let (messages, keys) = state.repos.rooms.purge(room).await?;
+    if !active && messages == 0 {
+        return Err(ApiError::not_found("room_not_found", "Room not found"));
+    }
+    for key in keys {
+        if let Err(e) = state.attachments.delete(&key).await {
+            tracing::warn!("Failed to remove attachment file {} of deleted room '{}': {}", key, room, e);
+        }
+    }
+
+    tracing::info!(
+        "User {} deleted room '{}' ({} messages, {} connections)",
+        claims.user_id,
+        room,
+        messages,
+        closed
+    );
+    let details = json!({ "messages_deleted": messages, "connections_closed": closed });
+    audit::record(state, claims, "room.delete", Target::Room(room), None, details).await;
+    Ok((messages, closed))
+}
+
+// --- 공지 ---
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementPayload {
+    text: String,
+    // 생략하면 지금 열려 있는 모든 방
+    #[serde(default)]
+    room: Option<String>,
+}
+
+// POST /admin/announcements
+pub async fn announcement_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<AnnouncementPayload>,
+) -> Response {
+    let text = payload.text.trim();
+    if text.is_empty() {
+        return ValidationErrors::single("text", "required", "text is required").into_response();
+    }
+    if text.chars().count() > *MESSAGE_MAX_CHARS {
+        return ValidationErrors::single(
+            "text",
+            "too_long",
+            format!("text must be at most {} characters", *MESSAGE_MAX_CHARS),
+        )
+        .into_response();
+    }
+
+    let sent_at = Utc::now().timestamp_millis();
+    // 이 서버에서 받은 방 수. 다른 서버에 열린 방은 세지 않는다.
+    let delivered = match &payload.room {
+        Some(room) => {
+            let event = ServerEvent::Announcement { room: room.clone(), text: text.to_string(), sent_at };
+            usize::from(state.bus.publish(room, event))
+        }
+        None => state.bus.announce(text, sent_at),
+    };
+
+    tracing::info!("User {} sent an announcement to {} rooms", claims.user_id, delivered);
+    // 대상 방을 정하지 않은 공지는 "*" 로 남긴다
+    let target = Target::Room(payload.room.as_deref().unwrap_or("*"));
+    let details = json!({ "text": text, "rooms": delivered });
+    audit::record(&state, &claims, "announcement.send", target, None, details).await;
+    Json(json!({ "rooms": delivered })).into_response()
+}
@@ -0,0 +1,229 @@
+// --- 지워진 계정 익명화 ---
+//
+// 계정이 지워지면(탈퇴, 게스트 정리, 봇 삭제) DB 트리거가 id 와 이름을 `deleted_accounts` 에 남긴다. 관리자가
+// `POST /admin/anonymization` 으로 작업을 띄우면 백그라운드 태스크가 그 계정들의 흔적을 지운다.
+//
+// - 그 계정이 쓴 메시지: 작성자를 `[deleted]` 로 바꾸고 내용과 첨부 파일을 지운다. 탈퇴할 때 익명화 정책으로
+//   작성자만 지운 메시지(`[deleted]`)도 내용까지 지운다.
+// - 다른 메시지와 알림 속 `@이름` 언급은 `@[deleted]` 로 바꾼다.
+// - 전달한 메시지의 원래 작성자, 투표 작성자, 신고에 남은 내용, 보관함의 메시지도 같은 방식으로 고친다.
+//
+// 처리한 계정은 `deleted_accounts` 에서 지우므로 작업을 여러 번 돌려도 된다. 서버가 작업 도중 꺼지면 다음에 켤 때
+// 이어서 한다. 감사 기록은 고칠 수 없으므로 그대로 남는다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::collections::HashSet;
+
+use crate::{
+    account::DELETED_USERNAME,
+    audit::{self, Target},
+    auth::AdminUser,
+    error::ApiError,
+    markdown::{self, MessageFormat},
+    notifications::is_username_char,
+    repo::{RepoResult, Touched},
+    AppState,
+};
+
+// 한 트랜잭션에서 처리하는 계정 수
+const ACCOUNT_BATCH: i64 = 100;
+// 한 번에 읽는 메시지와 알림 수
+const ROW_BATCH: i64 = 500;
+
+const DONE: &str = "done";
+const FAILED: &str = "failed";
+
+// names(소문자) 중 하나를 언급한 곳을 @[deleted] 로 바꾼 글. 바뀐 것이 없으면 None.
+fn replace_mentions(text: &str, names: &HashSet<String>) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut prev: Option<char> = None;
+    let mut rest_start = 0;
+    for (i, c) in text.char_indices() {
+        if i < rest_start {
+            continue;
+        }
+        if c == '@' && !prev.is_some_and(is_username_char) {
+            let rest = &text[i + 1..];
+            let end = rest.find(|c| !is_username_char(c)).unwrap_or(rest.len());
+            // 문장 끝의 마침표 등은 이름에 넣지 않는다 (notifications 의 언급 규칙과 같다)
+            let name = rest[..end].trim_end_matches(['.', '-', '_']);
+            if !name.is_empty() && names.contains(&name.to_lowercase()) {
+                out.push('@');
+                out.push_str(DELETED_USERNAME);
+                changed = true;
+                rest_start = i + 1 + name.len();
+                prev = name.chars().last();
+                continue;
+            }
+        }
+        out.push(c);
+        prev = Some(c);
+    }
+    changed.then_some(out)
+}
+
+// ILIKE 에서 _ 와 % 가 글자 그대로 맞도록
+fn like_pattern(name: &str) -> String {
+    format!("%@{}%", name.replace('\\', "\\\\").replace('_', "\\_").replace('%', "\\%"))
+}
+
+// 다른 메시지와 알림 속 언급을 바꾼다
+async fn scrub_mentions(state: &AppState, names: &[String], touched: &mut Touched) -> RepoResult<()> {
+    let lower: HashSet<String> = names.iter().map(|n| n.to_lowercase()).collect();
+    let patterns: Vec<String> = names.iter().map(|n| like_pattern(n)).collect();
+
+    let mut after = 0;
+    loop {
+        let rows = state.repos.anonymization.mentioning_messages(&patterns, after, ROW_BATCH).await?;
+        let Some(last) = rows.last().map(|(id, _, _)| *id) else {
+            break;
+        };
+        after = last;
+        for (id, content, format) in rows {
+            let Some(content) = replace_mentions(&content, &lower) else {
+                continue;
+            };
+            let html = (MessageFormat::parse(&format) == MessageFormat::Markdown).then(|| markdown::render(&content));
+            state.repos.anonymization.rewrite_message(id, &content, html.as_deref()).await?;
+            touched.mentions += 1;
+        }
+    }
+
+    let mut after = 0;
+    loop {
+        let rows = state.repos.anonymization.mentioning_notifications(&patterns, after, ROW_BATCH).await?;
+        let Some(last) = rows.last().map(|(id, _)| *id) else {
+            break;
+        };
+        after = last;
+        for (id, text) in rows {
+            if let Some(text) = replace_mentions(&text, &lower) {
+                state.repos.anonymization.rewrite_notification(id, &text).await?;
+                touched.notifications += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn remove_files(state: &AppState, keys: Vec<String>) {
+    for key in keys {
+        if let Err(e) = state.attachments.delete(&key).await {
+            tracing::warn!("Failed to remove attachment file {} of a deleted account: {}", key, e);
+        }
+    }
+}
+
+// deleted_accounts 의 계정을 ACCOUNT_BATCH 개씩 처리한다. 계정은 흔적을 다 지운 뒤에 deleted_accounts 에서
+// 빼므로 중간에 멈춰도 다음 작업이 같은 계정부터 다시 한다.
+async fn scrub_accounts(state: &AppState, job_id: i64, touched: &mut Touched) -> RepoResult<()> {
+    loop {
+        let accounts = state.repos.anonymization.deleted_accounts(ACCOUNT_BATCH).await?;
+        if accounts.is_empty() {
+            return Ok(());
+        }
+        let (ids, names): (Vec<i32>, Vec<String>) = accounts.into_iter().unzip();
+
+        let keys = state.repos.anonymization.scrub_accounts(&ids, &names, DELETED_USERNAME, touched).await?;
+        scrub_mentions(state, &names, touched).await?;
+        state.repos.anonymization.forget_accounts(&ids).await?;
+        touched.accounts += ids.len() as u64;
+
+        remove_files(state, keys).await;
+        save_progress(state, job_id, touched).await;
+    }
+}
+
+// 탈퇴할 때 작성자만 지운 메시지(어느 계정인지 남지 않았다)의 내용을 지운다
+async fn scrub_anonymized(state: &AppState, job_id: i64, touched: &mut Touched) -> RepoResult<()> {
+    loop {
+        let (scrubbed, keys) = state.repos.anonymization.scrub_anonymized(DELETED_USERNAME, ROW_BATCH, touched).await?;
+        remove_files(state, keys).await;
+        save_progress(state, job_id, touched).await;
+        if scrubbed < ROW_BATCH {
+            return Ok(());
+        }
+    }
+}
+
+async fn save_progress(state: &AppState, id: i64, touched: &Touched) {
+    if let Err(e) = state.repos.anonymization.save_progress(id, touched).await {
+        tracing::warn!("Failed to save progress of anonymization job {}: {}", id, e);
+    }
+}
+
+async fn run(state: AppState, id: i64, mut touched: Touched) {
+    let result = async {
+        scrub_accounts(&state, id, &mut touched).await?;
+        scrub_anonymized(&state, id, &mut touched).await
+    }
+    .await;
+    let (status, error) = match result {
+        Ok(()) => (DONE, None),
+        Err(e) => {
+            tracing::error!("Anonymization job {} failed: {}", id, e);
+            (FAILED, Some("Database error"))
+        }
+    };
+    tracing::info!("Anonymization job {} finished: {} ({:?})", id, status, touched);
+    if let Err(e) = state.repos.anonymization.finish(id, status, &touched, error).await {
+        tracing::error!("Failed to finish anonymization job {}: {}", id, e);
+    }
+}
+
+// 서버가 꺼지기 전에 끝나지 않은 작업을 이어서 한다
+pub async fn resume(state: AppState) {
+    match state.repos.anonymization.running().await {
+        Ok(Some((id, touched))) => {
+            tracing::info!("Resuming anonymization job {}", id);
+            tokio::spawn(run(state, id, touched));
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to look up unfinished anonymization jobs: {}", e),
+    }
+}
+
+// --- 관리 API ---
+
+// POST /admin/anonymization: 이미 돌고 있으면 409
+pub async fn create_handler(State(state): State<AppState>, AdminUser(claims): AdminUser) -> Response {
+    let job = match state.repos.anonymization.create(claims.user_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => {
+            return ApiError::conflict("anonymization_running", "An anonymization job is already running")
+                .into_response()
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let pending = state.repos.anonymization.pending_accounts().await.unwrap_or_default();
+    tracing::info!("User {} started anonymization job {} ({} accounts)", claims.user_id, job.id, pending);
+    let details = json!({ "pending_accounts": pending });
+    audit::record(&state, &claims, "anonymization.run", Target::Anonymization(job.id), None, details).await;
+
+    tokio::spawn(run(state.clone(), job.id, Touched::default()));
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+// GET /admin/anonymization: 최근 작업부터
+pub async fn list_handler(State(state): State<AppState>) -> Response {
+    match state.repos.anonymization.list(100).await {
+        Ok(jobs) => Json(jobs).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /admin/anonymization/:id
+pub async fn status_handler(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
+    match state.repos.anonymization.find(id).await {
+        Ok(Some(job)) => Json(job).into_response(),
+        Ok(None) => ApiError::not_found("job_not_found", "Anonymization job not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,123 @@
+// --- 감사 기록 ---
+//
+// 음소거, 메시지 삭제, 차단, 계정 비활성화 같은 중재·관리 동작을 `audit_log` 에 남긴다. 테이블은 DB 트리거로
+// 추가만 가능하게 막혀 있다. 동작이 이미 끝난 뒤에 기록하므로 기록에 실패해도 요청은 실패로 돌리지 않고 로그만 남긴다.
+// 관리자는 `GET /admin/audit?since=` 로 조회한다.
+
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{
+    auth::{AdminUser, Claims},
+    error::ApiError,
+    repo::NewAuditEntry,
+    AppState,
+};
+
+const LIST_LIMIT_MAX: i64 = 500;
+
+// 동작의 대상. 지워진 뒤에도 알아볼 수 있게 종류와 id 를 글자로 남긴다.
+pub enum Target<'a> {
+    User(i32),
+    Message(i64),
+    Room(&'a str),
+    Report(i64),
+    Invite(i64),
+    WordFilter(i64),
+    IpBan(i64),
+    Webhook(i64),
+    Import(i64),
+    Anonymization(i64),
+    // 서버 전체 설정 (점검 모드 등)
+    Setting(&'a str),
+}
+
+impl Target<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Target::User(_) => "user",
+            Target::Message(_) => "message",
+            Target::Room(_) => "room",
+            Target::Report(_) => "report",
+            Target::Invite(_) => "invite",
+            Target::WordFilter(_) => "word_filter",
+            Target::IpBan(_) => "ip_ban",
+            Target::Webhook(_) => "webhook",
+            Target::Import(_) => "import",
+            Target::Anonymization(_) => "anonymization",
+            Target::Setting(_) => "setting",
+        }
+    }
+
+    fn id(&self) -> String {
+        match self {
+            Target::User(id) => id.to_string(),
+            Target::Message(id)
+            | Target::Report(id)
+            | Target::Invite(id)
+            | Target::WordFilter(id)
+            | Target::IpBan(id)
+            | Target::Webhook(id)
+            | Target::Import(id)
+            | Target::Anonymization(id) => id.to_string(),
+            Target::Room(name) | Target::Setting(name) => name.to_string(),
+        }
+    }
+}
+
+// action 은 "user.mute" 처럼 대상.동작 형식. details 에는 동작별 부가 정보를 넣는다.
+pub async fn record(
+    state: &AppState,
+    actor: &Claims,
+    action: &str,
+    target: Target<'_>,
+    reason: Option<&str>,
+    details: serde_json::Value,
+) {
+    let entry = NewAuditEntry {
+        actor_id: actor.user_id,
+        actor_name: &actor.sub,
+        action,
+        target_type: target.kind(),
+        target_id: target.id(),
+        reason: reason.map(str::trim).filter(|r| !r.is_empty()),
+        details,
+    };
+    if let Err(e) = state.repos.audit.record(&entry).await {
+        tracing::error!("Failed to record audit entry {} by user {}: {}", action, actor.user_id, e);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    // 이 시각 이후의 기록만 (RFC 3339)
+    #[serde(default)]
+    since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+// GET /admin/audit?since=&action=&limit=: 최근 것부터
+pub async fn list_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let entries =
+        state.repos.audit.list(query.since, query.action.as_deref(), query.limit.clamp(1, LIST_LIMIT_MAX)).await;
+    match entries {
+        Ok(entries) => Json(entries).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,219 @@
+// --- JWT 발급 및 검증 ---
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{bots, config, error::ApiError, keys, session::ACCESS_COOKIE, AppState};
+
+// 액세스 토큰 수명. 리프레시 토큰으로 갱신하므로 짧게 유지한다.
+pub static ACCESS_TOKEN_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::minutes(config::get().jwt.access_token_ttl_minutes));
+
+// JWT 클레임
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String, // 사용자 이름
+    pub user_id: i32,
+    pub exp: usize,
+    pub jti: String, // 토큰 식별자 (폐기용)
+    pub sid: String, // 로그인 세션 id
+    // 게스트 토큰 (읽기 전용 등 제한이 걸린다)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub guest: bool,
+    // 봇 API 토큰으로 인증했을 때 그 토큰에 허용된 권한. 사람 사용자의 JWT 면 None.
+    #[serde(skip)]
+    pub scopes: Option<Vec<String>>,
+}
+
+impl Claims {
+    pub fn is_bot(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    // 봇 토큰이면 scope 가 허용되었는지, 사람 사용자면 항상 true
+    pub fn allows(&self, scope: &str) -> bool {
+        self.scopes.as_ref().is_none_or(|scopes| scopes.iter().any(|s| s == scope))
+    }
+}
+
+pub fn encode_token(claims: &Claims) -> jsonwebtoken::errors::Result<String> {
+    keys::jwt_keys().encode(claims)
+}
+
+// 서명과 만료 시간을 검증하고 클레임을 돌려준다. 설정된 모든 검증 키를 시도한다.
+pub fn decode_token(token: &str) -> jsonwebtoken::errors::Result<Claims> {
+    keys::jwt_keys().decode(token)
+}
+
+// 새 액세스 토큰 발급
+pub fn issue_access_token(
+    user_id: i32,
+    username: &str,
+    session_id: &str,
+    guest: bool,
+) -> jsonwebtoken::errors::Result<(String, Claims)> {
+    let claims = Claims {
+        sub: username.to_string(),
+        user_id,
+        exp: (chrono::Utc::now() + *ACCESS_TOKEN_TTL).timestamp() as usize,
+        jti: generate_token(),
+        sid: session_id.to_string(),
+        guest,
+        scopes: None,
+    };
+    let token = encode_token(&claims)?;
+    Ok((token, claims))
+}
+
+// Authorization: Bearer 헤더 또는 인증 쿠키에서 액세스 토큰을 꺼낸다
+pub fn token_from_request(headers: &HeaderMap, jar: &CookieJar) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string);
+    bearer.or_else(|| jar.get(ACCESS_COOKIE).map(|c| c.value().to_string()))
+}
+
+// --- 보호된 REST 라우트용 인증 추출기 ---
+
+// 핸들러 인자에 `AuthUser` 를 두면 쿠키나 Authorization 헤더의 JWT 나 봇 API 토큰을 검증한다.
+// 실패하면 핸들러는 실행되지 않고 401 JSON 이 응답된다. 봇 토큰은 GET 요청에 read, 나머지에 write 권한이
+// 있어야 한다 (없으면 403).
+pub struct AuthUser(pub Claims);
+
+#[derive(Debug)]
+pub enum AuthError {
+    Missing,
+    Invalid,
+    Revoked,
+    // 봇 토큰에 필요한 권한이 없음
+    Scope,
+}
+
+impl AuthError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AuthError::Missing => "missing_token",
+            AuthError::Invalid => "invalid_token",
+            AuthError::Revoked => "revoked_token",
+            AuthError::Scope => "insufficient_scope",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            AuthError::Missing => "Token not provided",
+            AuthError::Invalid => "Invalid token",
+            AuthError::Revoked => "Token revoked",
+            AuthError::Scope => "Token does not allow this request",
+        }
+    }
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+// 서명, 만료, 폐기 여부를 모두 확인한다
+pub fn verify_access_token(state: &AppState, token: &str) -> Result<Claims, AuthError> {
+    let claims = decode_token(token).map_err(|_| AuthError::Invalid)?;
+    if state.revoked_tokens.is_revoked(&claims) {
+        return Err(AuthError::Revoked);
+    }
+    Ok(claims)
+}
+
+// 액세스 토큰이나 봇 API 토큰을 검증한다
+pub async fn authenticate(state: &AppState, token: &str) -> Result<Claims, AuthError> {
+    if token.starts_with(bots::TOKEN_PREFIX) {
+        return bots::authenticate(state, token).await;
+    }
+    verify_access_token(state, token)
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let token = token_from_request(&parts.headers, &jar).ok_or(AuthError::Missing)?;
+        let claims = authenticate(state, &token).await?;
+        let scope = if matches!(parts.method, Method::GET | Method::HEAD) { "read" } else { "write" };
+        if !claims.allows(scope) {
+            return Err(AuthError::Scope);
+        }
+        Ok(AuthUser(claims))
+    }
+}
+
+// 서버 관리자 전용 라우트. 관리자 권한은 토큰이 아니라 DB 에서 매번 확인하므로
+// 권한을 회수하면 바로 적용된다.
+pub struct AdminUser(pub Claims);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        if let Some(VerifiedAdmin(claims)) = parts.extensions.get::<VerifiedAdmin>() {
+            return Ok(AdminUser(claims.clone()));
+        }
+        let AuthUser(claims) = AuthUser::from_request_parts(parts, state).await?;
+        let user = state.repos.users.find(claims.user_id).await?;
+        if !user.is_some_and(|user| user.is_admin) {
+            return Err(ApiError::forbidden("admin_required", "Administrator access required"));
+        }
+        Ok(AdminUser(claims))
+    }
+}
+
+// require_admin 이 확인한 클레임. 핸들러의 AdminUser 가 DB 를 다시 조회하지 않게 한다.
+#[derive(Clone)]
+struct VerifiedAdmin(Claims);
+
+// `/admin` 라우트 묶음 전체에 거는 미들웨어. 핸들러가 AdminUser 를 받지 않더라도 관리자만 통과한다.
+pub async fn require_admin(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let (mut parts, body) = req.into_parts();
+    match AdminUser::from_request_parts(&mut parts, &state).await {
+        Ok(AdminUser(claims)) => {
+            parts.extensions.insert(VerifiedAdmin(claims));
+            next.run(Request::from_parts(parts, body)).await
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+// --- 불투명(opaque) 토큰 ---
+
+// URL 에 그대로 쓸 수 있는 256비트 난수 토큰
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// DB 에는 토큰 원문 대신 해시만 저장한다
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
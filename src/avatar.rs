@@ -0,0 +1,219 @@
+// --- 아바타 ---
+//
+// `POST /me/avatar` 로 이미지를 multipart 로 올리면 가운데를 정사각형으로 잘라 AVATAR_SIZES 크기의 PNG 로
+// 만들어 저장한다. `GET /avatars/:user?size=` 는 누구나 볼 수 있고, URL 의 `v` 가 현재 버전과 같으면
+// 오래 캐시해도 된다 (새로 올리면 버전이 바뀐다).
+
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use image::{imageops::FilterType, ImageFormat};
+use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::Cursor;
+
+use crate::{auth::AuthUser, config, error::ApiError, validation, AppState};
+
+// 작은 것부터. 요청한 크기보다 크거나 같은 것 중 가장 작은 것을 돌려준다.
+pub const AVATAR_SIZES: [u32; 4] = [32, 64, 128, 256];
+const DEFAULT_SIZE: u32 = 128;
+// 디코딩 전에 거르는 원본 해상도 한도 (압축 폭탄 방지)
+const MAX_SOURCE_DIMENSION: u32 = 4096;
+
+pub static AVATAR_MAX_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.avatar_max_bytes);
+
+#[derive(Debug, Deserialize)]
+pub struct AvatarQuery {
+    #[serde(default)]
+    size: Option<u32>,
+    #[serde(default)]
+    v: Option<String>,
+}
+
+// 이벤트와 프로필에 싣는 상대 URL. 아바타가 없으면 None.
+pub fn avatar_url(username: &str, version: Option<&str>) -> Option<String> {
+    version.map(|v| format!("/avatars/{}?v={}", username, v))
+}
+
+fn storage_key(user_id: i32, size: u32) -> String {
+    format!("{}/{}.png", user_id, size)
+}
+
+// 원본을 받아 크기별 PNG 를 만든다. CPU 를 많이 쓰므로 spawn_blocking 안에서 부른다.
+fn render_sizes(data: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, ApiError> {
+    let unsupported = || {
+        ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_image",
+            "Avatar must be a PNG, JPEG, GIF or WebP image",
+        )
+    };
+    let format = image::guess_format(data).map_err(|_| unsupported())?;
+    if !matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP) {
+        return Err(unsupported());
+    }
+
+    let mut reader = image::ImageReader::with_format(Cursor::new(data), format);
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+    let source = reader
+        .decode()
+        .map_err(|_| ApiError::bad_request("invalid_image", "Image could not be decoded"))?;
+
+    AVATAR_SIZES
+        .iter()
+        .map(|&size| {
+            let mut png = Vec::new();
+            source
+                .resize_to_fill(size, size, FilterType::Lanczos3)
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .map_err(|e| {
+                    tracing::error!("Failed to encode avatar: {}", e);
+                    ApiError::internal()
+                })?;
+            Ok((size, png))
+        })
+        .collect()
+}
+
+fn too_large() -> ApiError {
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "payload_too_large",
+        format!("Avatar must be at most {} bytes", *AVATAR_MAX_BYTES),
+    )
+}
+
+// 본문 크기 제한에 걸린 경우도 multipart 오류로 올라온다
+fn multipart_error(e: MultipartError) -> ApiError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        too_large()
+    } else {
+        ApiError::bad_request("invalid_body", "Malformed multipart body")
+    }
+}
+
+// multipart 에서 `avatar` 필드(없으면 첫 파일 필드)를 읽는다
+async fn read_upload(mut multipart: Multipart) -> Result<Vec<u8>, ApiError> {
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("avatar") && field.file_name().is_none() {
+            continue;
+        }
+        let data = field.bytes().await.map_err(multipart_error)?;
+        if data.len() > *AVATAR_MAX_BYTES {
+            return Err(too_large());
+        }
+        return Ok(data.to_vec());
+    }
+    Err(ApiError::bad_request("missing_file", "No avatar file in the request"))
+}
+
+// POST /me/avatar
+pub async fn upload_handler(State(state): State<AppState>, AuthUser(claims): AuthUser, multipart: Multipart) -> Response {
+    let data = match read_upload(multipart).await {
+        Ok(data) => data,
+        Err(e) => return e.into_response(),
+    };
+    let rendered = match tokio::task::spawn_blocking(move || render_sizes(&data)).await {
+        Ok(Ok(rendered)) => rendered,
+        Ok(Err(e)) => return e.into_response(),
+        Err(e) => {
+            tracing::error!("Avatar rendering task failed: {}", e);
+            return ApiError::internal().into_response();
+        }
+    };
+
+    for (size, png) in rendered {
+        if let Err(e) = state.avatars.put(&storage_key(claims.user_id, size), png, "image/png").await {
+            tracing::error!("Failed to store avatar for user {}: {}", claims.user_id, e);
+            return ApiError::internal().into_response();
+        }
+    }
+
+    let version: String = rand::thread_rng().sample_iter(&Alphanumeric).take(12).map(char::from).collect();
+    if let Err(e) = state.repos.profiles.set_avatar(claims.user_id, Some(&version)).await {
+        return ApiError::from(e).into_response();
+    }
+    tracing::info!("User {} uploaded a new avatar", claims.user_id);
+
+    Json(json!({
+        "avatar_url": avatar_url(&claims.sub, Some(&version)),
+        "sizes": AVATAR_SIZES,
+    }))
+    .into_response()
+}
+
+// DELETE /me/avatar
+pub async fn delete_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    if let Err(e) = state.repos.profiles.set_avatar(claims.user_id, None).await {
+        return ApiError::from(e).into_response();
+    }
+    delete_files(&state, claims.user_id).await;
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// 저장된 파일을 지운다. 실패해도 기록만 남긴다 (DB 에서 이미 연결이 끊겼으므로 보이지 않는다).
+pub async fn delete_files(state: &AppState, user_id: i32) {
+    for size in AVATAR_SIZES {
+        if let Err(e) = state.avatars.delete(&storage_key(user_id, size)).await {
+            tracing::warn!("Failed to delete avatar file for user {}: {}", user_id, e);
+        }
+    }
+}
+
+// GET /avatars/:user
+pub async fn serve_handler(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Query(query): Query<AvatarQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let found = state.repos.profiles.avatar_by_name(&validation::normalize_username(&username)).await;
+    let (user_id, version) = match found {
+        Ok(Some(found)) => found,
+        Ok(None) => return ApiError::not_found("avatar_not_found", "Avatar not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let requested = query.size.unwrap_or(DEFAULT_SIZE);
+    let size = AVATAR_SIZES
+        .into_iter()
+        .find(|&s| s >= requested)
+        .unwrap_or(AVATAR_SIZES[AVATAR_SIZES.len() - 1]);
+
+    let etag = format!("\"{}-{}\"", version, size);
+    // 버전이 붙은 URL 은 내용이 바뀌지 않으므로 오래 캐시한다
+    let cache_control = if query.v.as_deref() == Some(version.as_str()) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "public, max-age=300"
+    };
+    if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some(etag.as_str()) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control.to_string())])
+            .into_response();
+    }
+
+    match state.avatars.get(&storage_key(user_id, size)).await {
+        Ok(Some(png)) => (
+            [
+                (header::CONTENT_TYPE, "image/png".to_string()),
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+            png,
+        )
+            .into_response(),
+        Ok(None) => ApiError::not_found("avatar_not_found", "Avatar not found").into_response(),
+        Err(e) => {
+            tracing::error!("Failed to read avatar for user {}: {}", user_id, e);
+            ApiError::internal().into_response()
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// --- 봇 계정 ---
+//
+// 사용자는 `POST /me/bots` 로 자신이 소유하는 봇 계정을 만들고, `POST /me/bots/:id/tokens` 로 봇의 API 토큰을
+// 발급한다. 토큰은 `Authorization: Bearer wcb_...` 로 REST API 와 웹소켓 모두에 쓸 수 있어서, 사람의 JWT 를
+// 나눠 주지 않고도 봇을 만들 수 있다. 토큰마다 권한(scope)을 정한다: chat 은 웹소켓 접속과 메시지 전송,
+// read 는 REST GET 요청, write 는 그 밖의 REST 요청. 토큰은 폐기할 수 있고 만료 기간을 둘 수도 있다.
+//
+// 봇이 보낸 메시지 이벤트에는 `bot: true` 가 붙는다. 봇은 비밀번호와 메일 주소가 없어 다른 방법으로는
+// 로그인할 수 없다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    auth::{generate_token, hash_token, AuthError, AuthUser, Claims},
+    error::{ApiError, ApiJson},
+    repo::RepoError,
+    validation::{self, ValidationErrors},
+    ws::CloseReason,
+    AppState,
+};
+
+// 봇 토큰은 이 접두사로 JWT 와 구분한다
+pub const TOKEN_PREFIX: &str = "wcb_";
+pub const SCOPES: [&str; 3] = ["chat", "read", "write"];
+
+const BOTS_PER_USER_MAX: i64 = 5;
+const TOKENS_PER_BOT_MAX: i64 = 10;
+const TOKEN_NAME_MAX_CHARS: usize = 64;
+
+// 토큰마다 연결을 따로 닫을 수 있도록 세션 id 대신 쓴다
+fn token_session_id(token_id: i64) -> String {
+    format!("bot-token-{}", token_id)
+}
+
+// 봇 토큰을 확인하고 봇의 클레임을 만든다. 폐기·만료된 토큰이나 비활성화된 봇이면 거부한다.
+pub async fn authenticate(state: &AppState, token: &str) -> Result<Claims, AuthError> {
+    let owner = match state.repos.bots.authenticate(&hash_token(token)).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return Err(AuthError::Invalid),
+        Err(e) => {
+            tracing::error!("Failed to verify bot token: {}", e);
+            return Err(AuthError::Invalid);
+        }
+    };
+    Ok(Claims {
+        sub: owner.username,
+        user_id: owner.bot_id,
+        // 만료 기간이 없는 토큰
+        exp: owner.expires_at.map_or(usize::MAX, |t| t.timestamp() as usize),
+        jti: token_session_id(owner.token_id),
+        sid: token_session_id(owner.token_id),
+        guest: false,
+        scopes: Some(owner.scopes),
+    })
+}
+
+// 봇 관리는 사람 사용자만 한다
+fn reject_non_human(claims: &Claims) -> Result<(), ApiError> {
+    if claims.guest || claims.is_bot() {
+        return Err(ApiError::forbidden("bots_forbidden", "Only registered users can manage bots"));
+    }
+    Ok(())
+}
+
+// 내가 소유한 봇인지
+async fn owned_bot(state: &AppState, claims: &Claims, bot_id: i32) -> Result<(), ApiError> {
+    if state.repos.bots.is_owner(bot_id, claims.user_id).await? {
+        Ok(())
+    } else {
+        Err(ApiError::not_found("bot_not_found", "Bot not found"))
+    }
+}
+
+// --- 봇 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBotPayload {
+    username: String,
+}
+
+// GET /me/bots
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.bots.list(claims.user_id).await {
+        Ok(bots) => Json(bots).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/bots
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<CreateBotPayload>,
+) -> Response {
+    if let Err(e) = reject_non_human(&claims) {
+        return e.into_response();
+    }
+    let username = validation::normalize_username(&payload.username);
+    let mut errors = ValidationErrors::default();
+    validation::check_username(&username, &mut errors);
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    match state.repos.bots.count(claims.user_id).await {
+        Ok(count) if count >= BOTS_PER_USER_MAX => {
+            return ApiError::conflict("bot_limit_reached", format!("You can own at most {} bots", BOTS_PER_USER_MAX))
+                .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    match state.repos.bots.create(claims.user_id, &username).await {
+        Ok(bot) => {
+            tracing::info!("User {} created bot '{}' ({})", claims.user_id, bot.username, bot.id);
+            (StatusCode::CREATED, Json(bot)).into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            ValidationErrors::single("username", "taken", "Username is already taken").into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/bots/:id: 봇 계정과 토큰을 지우고 연결을 닫는다. 보낸 메시지는 남는다.
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(bot_id): Path<i32>,
+) -> Response {
+    if let Err(e) = reject_non_human(&claims) {
+        return e.into_response();
+    }
+    match state.repos.bots.delete(bot_id, claims.user_id).await {
+        Ok(true) => {
+            tracing::info!("User {} deleted bot {}", claims.user_id, bot_id);
+            state.connections.close_user(bot_id, CloseReason::AccountDeleted);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("bot_not_found", "Bot not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// --- 토큰 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenPayload {
+    name: String,
+    scopes: Vec<String>,
+    // 없으면 만료되지 않는다
+    #[serde(default)]
+    expires_in_days: Option<i64>,
+}
+
+// GET /me/bots/:id/tokens
+pub async fn list_tokens_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(bot_id): Path<i32>,
+) -> Response {
+    if let Err(e) = owned_bot(&state, &claims, bot_id).await {
+        return e.into_response();
+    }
+    match state.repos.bots.tokens(bot_id).await {
+        Ok(tokens) => Json(tokens).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/bots/:id/tokens: 토큰 원문은 이 응답에서만 볼 수 있다
+pub async fn create_token_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(bot_id): Path<i32>,
+    ApiJson(payload): ApiJson<CreateTokenPayload>,
+) -> Response {
+    if let Err(e) = reject_non_human(&claims) {
+        return e.into_response();
+    }
+    if let Err(e) = owned_bot(&state, &claims, bot_id).await {
+        return e.into_response();
+    }
+
+    let name = payload.name.trim();
+    let mut scopes = payload.scopes;
+    scopes.sort();
+    scopes.dedup();
+    let mut errors = ValidationErrors::default();
+    if name.is_empty() {
+        errors.add("name", "required", "name is required");
+    } else if name.chars().count() > TOKEN_NAME_MAX_CHARS {
+        errors.add("name", "too_long", format!("name must be at most {} characters", TOKEN_NAME_MAX_CHARS));
+    }
+    if scopes.is_empty() {
+        errors.add("scopes", "required", "at least one scope is required");
+    } else if !scopes.iter().all(|s| SCOPES.contains(&s.as_str())) {
+        errors.add("scopes", "invalid_value", "scopes must be 'chat', 'read' or 'write'");
+    }
+    if payload.expires_in_days.is_some_and(|d| d <= 0) {
+        errors.add("expires_in_days", "out_of_range", "expires_in_days must be positive");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    match state.repos.bots.active_token_count(bot_id).await {
+        Ok(count) if count >= TOKENS_PER_BOT_MAX => {
+            return ApiError::conflict(
+                "bot_token_limit_reached",
+                format!("A bot can have at most {} active tokens", TOKENS_PER_BOT_MAX),
+            )
+            .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let token = format!("{}{}", TOKEN_PREFIX, generate_token());
+    let expires_at = payload.expires_in_days.map(|d| Utc::now() + chrono::Duration::days(d));
+    match state.repos.bots.create_token(bot_id, name, &hash_token(&token), &scopes, expires_at).await {
+        Ok(created) => {
+            tracing::info!("User {} created token {} for bot {}", claims.user_id, created.id, bot_id);
+            let body = json!({
+                "id": created.id,
+                "name": created.name,
+                "scopes": created.scopes,
+                "token": token,
+                "expires_at": created.expires_at,
+                "created_at": created.created_at,
+            });
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/bots/:id/tokens/:token_id: 이 토큰으로 열린 연결도 닫는다
+pub async fn revoke_token_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((bot_id, token_id)): Path<(i32, i64)>,
+) -> Response {
+    if let Err(e) = reject_non_human(&claims) {
+        return e.into_response();
+    }
+    if let Err(e) = owned_bot(&state, &claims, bot_id).await {
+        return e.into_response();
+    }
+    match state.repos.bots.revoke_token(token_id, bot_id).await {
+        Ok(true) => {
+            tracing::info!("User {} revoked token {} of bot {}", claims.user_id, token_id, bot_id);
+            state.connections.close_session(&token_session_id(token_id), CloseReason::SessionRevoked);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("bot_token_not_found", "Bot token not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,95 @@
+// --- 방 이벤트 전달 (메시지 버스) ---
+//
+// 방에 보내는 이벤트를 그 방에 접속한 연결에 나눠 주는 부분. 웹소켓 처리나 이벤트를 보내는 곳은 MessageBus 만
+// 쓰므로, 전달 방식을 바꾸려면 구현을 하나 더 만들어 configured 에서 고르면 된다.
+//
+// - local: 서버 프로세스 안의 방별 브로드캐스트 채널 (기본값)
+// - redis: local 에 더해 Redis pub/sub 으로 다른 서버와 이벤트를 주고받는다 (cluster.redis_url 을 설정하면 쓴다). 방 이벤트
+//   말고도 연결 종료와 토큰 폐기(`cluster::ClusterEvent`)를 주고받아 모든 서버에 적용한다.
+
+mod redis;
+
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::{
+    config, connections::ConnectionRegistry, monitoring, protocol::ServerEvent, revocation::RevocationStore,
+    room_registry::RoomRegistry,
+};
+
+pub use self::redis::RedisBus;
+
+pub trait MessageBus: Send + Sync {
+    // 시작할 때 로그에 남길 설명
+    fn describe(&self) -> String;
+
+    // 방의 이벤트를 받는다. 방이 열려 있지 않으면 연다.
+    fn subscribe(&self, room: &str) -> broadcast::Receiver<ServerEvent>;
+
+    // 방에 이벤트를 보낸다. 이 서버에서 받은 연결이 있으면 true.
+    fn publish(&self, room: &str, event: ServerEvent) -> bool;
+
+    // 열려 있는 모든 방에 공지를 보낸다. 이 서버에서 받은 방 수를 돌려준다.
+    fn announce(&self, text: &str, sent_at: i64) -> usize;
+
+    // 이 서버에 열려 있는 방
+    fn rooms(&self) -> Vec<String>;
+
+    // 방을 닫아 구독을 끝낸다. 열려 있었으면 true.
+    fn close(&self, room: &str) -> bool;
+
+    // 다른 서버의 이벤트를 받을 수 있는지 (준비 상태 확인용). 서버 안에서만 전달하면 항상 true.
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+// 이 서버의 방은 어느 구현이든 rooms 에 연다. 서버끼리 주고받는 구현은 연결 종료와 토큰 폐기도 주고받는다.
+pub fn configured(
+    rooms: Arc<RoomRegistry>,
+    connections: Arc<ConnectionRegistry>,
+    revocations: Arc<RevocationStore>,
+) -> Arc<dyn MessageBus> {
+    let local = LocalBus { rooms };
+    let bus: Arc<dyn MessageBus> = match &config::get().cluster.redis_url {
+        Some(url) => Arc::new(RedisBus::start(url, local, connections, revocations)),
+        None => Arc::new(local),
+    };
+    tracing::info!("Message bus: {}", bus.describe());
+    bus
+}
+
+// 서버 프로세스 안에서만 전달한다
+#[derive(Clone)]
+pub struct LocalBus {
+    rooms: Arc<RoomRegistry>,
+}
+
+impl MessageBus for LocalBus {
+    fn describe(&self) -> String {
+        "in-process".to_string()
+    }
+
+    fn subscribe(&self, room: &str) -> broadcast::Receiver<ServerEvent> {
+        self.rooms.subscribe(room)
+    }
+
+    // 접속한 사람이 없는 방이면 버린다
+    fn publish(&self, room: &str, event: ServerEvent) -> bool {
+        monitoring::message_broadcast(&event);
+        self.rooms.send(room, event)
+    }
+
+    fn announce(&self, text: &str, sent_at: i64) -> usize {
+        let text = text.to_string();
+        self.rooms.send_all(|room| ServerEvent::Announcement { room: room.to_string(), text: text.clone(), sent_at })
+    }
+
+    fn rooms(&self) -> Vec<String> {
+        self.rooms.names()
+    }
+
+    fn close(&self, room: &str) -> bool {
+        self.rooms.remove(room)
+    }
+}
@@ -0,0 +1,218 @@
+// 여러 서버 사이의 방 이벤트 중계 (Redis pub/sub)
+//
+// 방의 브로드캐스트 채널은 서버 프로세스 안에만 있으므로 서버를 여러 대 띄우면 다른 서버에 접속한 사람에게는
+// 이벤트가 가지 않는다. 이 버스는 방에 보내는 이벤트를 이 서버의 방에 전달하면서 `<prefix>room:<방>` 채널에도
+// 발행하고, 모든 서버가 `<prefix>room:*` 를 구독해 받은 이벤트를 자기에게 접속한 사람에게 전달한다. 대상 방을
+// 정하지 않은 공지는 `<prefix>announcement` 로 보내고, 받은 서버가 자기에게 열려 있는 방마다 만든다. 연결 종료와
+// 토큰 폐기(`ClusterEvent`)는 `<prefix>cluster` 로 보내고, 받은 서버가 자기 연결과 폐기 목록에 적용한다.
+//
+// 발행은 제한된 대기열을 거쳐 전용 태스크가 맡으므로 Redis 가 느려도 채팅이 막히지 않는다. 대기열이 차거나 연결이
+// 끊긴 동안의 이벤트는 다른 서버에 가지 않는다(저장된 메시지는 기록으로 볼 수 있다). 끊긴 연결은 다시 맺는다.
+
+use futures::StreamExt;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{
+    broadcast,
+    mpsc::{self, error::TrySendError},
+};
+
+use super::{LocalBus, MessageBus};
+use crate::{
+    cluster::{ClusterEvent, KEY_PREFIX, NODE_ID},
+    connections::ConnectionRegistry,
+    protocol::ServerEvent,
+    revocation::RevocationStore,
+};
+
+// 발행을 기다리는 이벤트 수
+const PUBLISH_QUEUE_CAPACITY: usize = 4096;
+// 발행을 기다리는 연결 종료와 폐기 수
+const CLUSTER_QUEUE_CAPACITY: usize = 1024;
+// 연결이 끊기면 이만큼 기다렸다 다시 맺는다
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize)]
+struct Announcement {
+    text: String,
+    sent_at: i64,
+}
+
+pub struct RedisBus {
+    local: LocalBus,
+    // (채널, 내용)
+    queue: mpsc::Sender<(String, String)>,
+    // 구독이 살아 있는지
+    subscribed: Arc<AtomicBool>,
+}
+
+impl RedisBus {
+    // 발행 태스크와 구독 태스크를 띄운다. 연결은 각 태스크가 맺는다.
+    // connections 와 revocations 가 알리는 ClusterEvent 도 발행하고, 다른 서버의 것은 둘에 적용한다.
+    pub fn start(
+        url: &str,
+        local: LocalBus,
+        connections: Arc<ConnectionRegistry>,
+        revocations: Arc<RevocationStore>,
+    ) -> Self {
+        let client = redis::Client::open(url).expect("cluster.redis_url is validated");
+        let (queue, rx) = mpsc::channel(PUBLISH_QUEUE_CAPACITY);
+        tokio::spawn(publish_loop(client.clone(), rx));
+
+        let (cluster_tx, mut cluster_rx) = mpsc::channel(CLUSTER_QUEUE_CAPACITY);
+        connections.relay_to(cluster_tx.clone());
+        revocations.relay_to(cluster_tx);
+        let cluster_queue = queue.clone();
+        tokio::spawn(async move {
+            let channel = format!("{}cluster", *KEY_PREFIX);
+            while let Some(event) = cluster_rx.recv().await {
+                let json = serde_json::to_string(&event).expect("ClusterEvent is always serializable");
+                enqueue(&cluster_queue, channel.clone(), json);
+            }
+        });
+
+        let subscribed = Arc::new(AtomicBool::new(false));
+        let receivers = Receivers { local: local.clone(), connections, revocations };
+        tokio::spawn(subscribe_loop(client, receivers, subscribed.clone()));
+        RedisBus { local, queue, subscribed }
+    }
+}
+
+fn enqueue(queue: &mpsc::Sender<(String, String)>, channel: String, json: String) {
+    match queue.try_send((channel, format!("{}\n{}", *NODE_ID, json))) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => tracing::warn!("Redis publish queue is full; dropping an event"),
+        Err(TrySendError::Closed(_)) => {}
+    }
+}
+
+// 다른 서버에서 받은 이벤트를 적용할 곳
+struct Receivers {
+    local: LocalBus,
+    connections: Arc<ConnectionRegistry>,
+    revocations: Arc<RevocationStore>,
+}
+
+impl MessageBus for RedisBus {
+    fn describe(&self) -> String {
+        format!("Redis pub/sub (node {}, channels {}*)", *NODE_ID, *KEY_PREFIX)
+    }
+
+    fn subscribe(&self, room: &str) -> broadcast::Receiver<ServerEvent> {
+        self.local.subscribe(room)
+    }
+
+    fn publish(&self, room: &str, event: ServerEvent) -> bool {
+        let json = serde_json::to_string(&event).expect("ServerEvent is always serializable");
+        enqueue(&self.queue, format!("{}room:{}", *KEY_PREFIX, room), json);
+        self.local.publish(room, event)
+    }
+
+    fn announce(&self, text: &str, sent_at: i64) -> usize {
+        let json = serde_json::to_string(&Announcement { text: text.to_string(), sent_at }).unwrap_or_default();
+        enqueue(&self.queue, format!("{}announcement", *KEY_PREFIX), json);
+        self.local.announce(text, sent_at)
+    }
+
+    fn rooms(&self) -> Vec<String> {
+        self.local.rooms()
+    }
+
+    // 이 서버의 방만 닫는다
+    fn close(&self, room: &str) -> bool {
+        self.local.close(room)
+    }
+
+    fn is_connected(&self) -> bool {
+        self.subscribed.load(Ordering::Relaxed)
+    }
+}
+
+async fn publish_loop(client: redis::Client, mut rx: mpsc::Receiver<(String, String)>) {
+    let mut conn = None;
+    while let Some((channel, payload)) = rx.recv().await {
+        if conn.is_none() {
+            match client.get_multiplexed_tokio_connection().await {
+                Ok(c) => conn = Some(c),
+                Err(e) => {
+                    tracing::warn!("Failed to connect to Redis for publishing: {}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            }
+        }
+        let Some(c) = conn.as_mut() else {
+            continue;
+        };
+        if let Err(e) = c.publish::<_, _, ()>(&channel, &payload).await {
+            tracing::warn!("Failed to publish to Redis channel {}: {}", channel, e);
+            conn = None;
+        }
+    }
+}
+
+// 다른 서버가 발행한 이벤트를 받아 이 서버에 접속한 사람에게 전달한다
+async fn subscribe_loop(client: redis::Client, receivers: Receivers, connected: Arc<AtomicBool>) {
+    let Receivers { local, connections, revocations } = receivers;
+    let room_prefix = format!("{}room:", *KEY_PREFIX);
+    let announcement_channel = format!("{}announcement", *KEY_PREFIX);
+    let cluster_channel = format!("{}cluster", *KEY_PREFIX);
+    loop {
+        let subscribed = async {
+            let mut pubsub = client.get_async_pubsub().await?;
+            pubsub.psubscribe(format!("{}*", room_prefix)).await?;
+            pubsub.subscribe(&announcement_channel).await?;
+            pubsub.subscribe(&cluster_channel).await?;
+            Ok::<_, redis::RedisError>(pubsub)
+        }
+        .await;
+        let mut pubsub = match subscribed {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                tracing::warn!("Failed to subscribe to Redis: {}", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        tracing::info!("Subscribed to room events from other nodes");
+        connected.store(true, Ordering::Relaxed);
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            let Ok(payload) = msg.get_payload::<String>() else {
+                continue;
+            };
+            let Some((node, json)) = payload.split_once('\n') else {
+                continue;
+            };
+            // 자기가 발행한 것
+            if node == *NODE_ID {
+                continue;
+            }
+            // 이 서버의 방에만 전달한다. 다시 발행하면 서버끼리 주고받기를 반복한다.
+            let channel = msg.get_channel_name();
+            if let Some(room) = channel.strip_prefix(&room_prefix) {
+                local.publish(room, ServerEvent::Relayed(json.into()));
+            } else if channel == announcement_channel {
+                if let Ok(announcement) = serde_json::from_str::<Announcement>(json) {
+                    local.announce(&announcement.text, announcement.sent_at);
+                }
+            } else if channel == cluster_channel {
+                if let Ok(event) = serde_json::from_str::<ClusterEvent>(json) {
+                    revocations.apply(&event);
+                    connections.apply(&event);
+                }
+            }
+        }
+        connected.store(false, Ordering::Relaxed);
+        tracing::warn!("Lost the Redis subscription; reconnecting");
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
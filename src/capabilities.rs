@@ -0,0 +1,29 @@
+// --- 서버 기능 안내 ---
+//
+// `GET /.well-known/webchat` 은 클라이언트가 서버 동작을 하드코딩하지 않도록 지원하는 프로토콜 버전과 인코딩,
+// 기능, 설정된 제한을 알려 준다. 로그인하기 전에도 읽을 수 있다. 기능 목록은 웹소켓 hello 의 답과 같다.
+
+use axum::{response::IntoResponse, Json};
+use serde_json::json;
+
+use crate::{attachments, avatar, ephemeral, guest, protocol, voice, ws};
+
+pub async fn discovery_handler() -> impl IntoResponse {
+    Json(json!({
+        "protocol": {
+            "versions": protocol::SUPPORTED_VERSIONS,
+            "subprotocols": ws::SUBPROTOCOLS.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        },
+        "features": protocol::CAPABILITIES,
+        "guest_access": *guest::GUEST_ACCESS,
+        "limits": {
+            "message_max_chars": *ws::MESSAGE_MAX_CHARS,
+            "ws_max_message_bytes": *ws::WS_MAX_MESSAGE_BYTES,
+            "ephemeral_max_seconds": *ephemeral::EPHEMERAL_MAX_SECONDS,
+            "attachment_max_bytes": *attachments::ATTACHMENT_MAX_BYTES,
+            "avatar_max_bytes": *avatar::AVATAR_MAX_BYTES,
+            "voice_max_bytes": *voice::VOICE_MAX_BYTES,
+            "voice_max_duration_seconds": *voice::VOICE_MAX_DURATION_SECONDS,
+        },
+    }))
+}
@@ -0,0 +1,121 @@
+// --- 가입 CAPTCHA (hCaptcha / Cloudflare Turnstile) ---
+//
+// captcha.provider(CAPTCHA_PROVIDER)를 설정하면 `POST /register` 에 위젯이 만든 captcha_token 이 있어야 한다.
+// 두 서비스 모두 같은 형태의 siteverify API 를 쓴다. 기본값은 꺼짐이다.
+
+use axum::{response::IntoResponse, Json};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{config, oauth::HTTP};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    #[serde(alias = "")]
+    None,
+    HCaptcha,
+    Turnstile,
+}
+
+impl Provider {
+    fn name(self) -> &'static str {
+        match self {
+            Provider::None => "none",
+            Provider::HCaptcha => "hcaptcha",
+            Provider::Turnstile => "turnstile",
+        }
+    }
+
+    fn verify_url(self) -> &'static str {
+        match self {
+            Provider::None => unreachable!("CAPTCHA is disabled"),
+            Provider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            Provider::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+        }
+    }
+}
+
+struct CaptchaConfig {
+    provider: Provider,
+    site_key: String,
+    secret: String,
+}
+
+// 키는 설정을 읽을 때 provider 와 함께 있는지 확인했다
+static CAPTCHA: Lazy<Option<CaptchaConfig>> = Lazy::new(|| {
+    let captcha = &config::get().captcha;
+    if captcha.provider == Provider::None {
+        return None;
+    }
+    Some(CaptchaConfig {
+        provider: captcha.provider,
+        site_key: captcha.site_key.clone().unwrap_or_default(),
+        secret: captcha.secret.clone().unwrap_or_default(),
+    })
+});
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+pub enum CaptchaError {
+    Missing,
+    Rejected,
+    // 검증 서비스에 연결할 수 없음
+    Unavailable,
+}
+
+pub fn init() {
+    if let Some(config) = CAPTCHA.as_ref() {
+        tracing::info!("Registration CAPTCHA enabled ({})", config.provider.name());
+    }
+}
+
+// 꺼져 있으면 항상 통과한다
+pub async fn verify(token: Option<&str>, remote_ip: Option<&str>) -> Result<(), CaptchaError> {
+    let Some(config) = CAPTCHA.as_ref() else {
+        return Ok(());
+    };
+    let token = token.map(str::trim).filter(|t| !t.is_empty()).ok_or(CaptchaError::Missing)?;
+
+    let mut form = vec![("secret", config.secret.as_str()), ("response", token)];
+    if let Some(ip) = remote_ip {
+        form.push(("remoteip", ip));
+    }
+    let result = async {
+        HTTP.post(config.provider.verify_url())
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<VerifyResponse>()
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(r) if r.success => Ok(()),
+        Ok(r) => {
+            tracing::info!("CAPTCHA rejected: {:?}", r.error_codes);
+            Err(CaptchaError::Rejected)
+        }
+        Err(e) => {
+            tracing::warn!("CAPTCHA verification failed: {}", e);
+            Err(CaptchaError::Unavailable)
+        }
+    }
+}
+
+// GET /auth/captcha: 가입 화면이 위젯을 그릴 때 쓰는 공개 설정
+pub async fn config_handler() -> impl IntoResponse {
+    match CAPTCHA.as_ref() {
+        Some(config) => Json(json!({ "provider": config.provider.name(), "site_key": config.site_key })),
+        None => Json(json!({ "provider": null })),
+    }
+}
@@ -0,0 +1,181 @@
+// --- 명령줄 ---
+//
+// 인자 없이 실행하면 서버를 띄운다(`serve`). 나머지 명령은 psql 없이 인스턴스를 관리하기 위한 것으로,
+// 서버와 같은 설정(설정 파일, 환경 변수, .env)으로 DB 에 붙어 한 가지 일을 하고 끝난다. database.url 이 가리키는
+// 어느 저장소(Postgres, SQLite, 메모리, MySQL)에서든 돈다.
+//
+// - `migrate`: 바이너리에 든 마이그레이션 중 아직 적용하지 않은 것을 적용한다 (schema.rs)
+// - `create-admin <user>`: 관리자 계정을 만든다. 이미 있는 사용자면 관리자로 올리기만 한다.
+// - `reset-password <user>`: 비밀번호를 바꾸고 그 사용자의 세션을 모두 폐기한다
+// - `gen-config`: 기본값으로 채운 설정 파일(TOML)을 표준 출력에 쓴다
+//
+// 비밀번호는 터미널에서 두 번 묻는다. 표준 입력이 터미널이 아니면 첫 줄을 비밀번호로 읽는다.
+
+use clap::{Parser, Subcommand};
+use sqlx::PgPool;
+use std::{
+    io::{self, BufRead, IsTerminal},
+    process,
+};
+
+use crate::{
+    config, connect_db, logging,
+    password::Passwords,
+    repo::{Backend, RepoError, Repos, User},
+    schema, validation, Server,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "webchat", version, about = "WebChat server and administration commands")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Run the server (the default when no command is given)")]
+    Serve,
+    #[command(about = "Apply pending database migrations")]
+    Migrate,
+    #[command(about = "Create an administrator account, or make an existing user an administrator")]
+    CreateAdmin {
+        username: String,
+        #[arg(long, help = "Email address of the new account (stored as verified)")]
+        email: Option<String>,
+    },
+    #[command(about = "Set a new password for a user and revoke all of their sessions")]
+    ResetPassword { username: String },
+    #[command(about = "Print a configuration file filled with the default settings")]
+    GenConfig,
+}
+
+// 명령이 실패하면 이유를 표준 오류에 쓰고 종료 코드 1 로 끝낸다
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+// 명령을 실행한다. gen-config 가 아니면 config::init 을 먼저 불러 둔다.
+pub async fn run(command: Command) {
+    match command {
+        Command::Serve => serve().await,
+        Command::GenConfig => gen_config(),
+        Command::Migrate if Backend::configured() == Backend::Postgres => {
+            migrate(&connect_db().await.unwrap_or_else(|e| fail(e))).await
+        }
+        Command::Migrate => migrate_repos().await,
+        Command::CreateAdmin { username, email } => create_admin(&repos().await, &username, email.as_deref()).await,
+        Command::ResetPassword { username } => reset_password(&repos().await, &username).await,
+    }
+}
+
+// database.url 의 저장소. SQLite 와 MySQL 은 열 때 마이그레이션을 적용하고, Postgres 는 `migrate` 로 따로 적용한다.
+async fn repos() -> Repos {
+    let database = &config::get().database;
+    let url = database.url.as_deref().expect("database.url is validated");
+    Repos::open(url, database.max_connections).await.unwrap_or_else(|e| fail(e))
+}
+
+// 서버를 띄운다. 시작하지 못하면 이유를 로그에 남기고 종료 코드 1 로 끝낸다.
+async fn serve() {
+    let log_guard = logging::init();
+    let served = match Server::builder().build().await {
+        Ok(server) => server.serve().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = served {
+        tracing::error!("{}", e);
+        drop(log_guard);
+        process::exit(1);
+    }
+}
+
+fn gen_config() {
+    let text = toml::to_string_pretty(&config::Config::default()).unwrap_or_else(|e| fail(e));
+    println!("# WebChat configuration. Environment variables override these settings.");
+    println!("# database.url and jwt.secret (or the RS256/EdDSA key paths) must be set.");
+    println!();
+    print!("{}", text);
+}
+
+async fn migrate(db: &PgPool) {
+    let pending = schema::migrate(db).await.unwrap_or_else(|e| fail(format!("migration failed: {}", e)));
+    println!("Applied {} migration(s); the database is up to date.", pending);
+}
+
+// SQLite 와 MySQL 저장소는 열 때 마이그레이션을 적용한다. 메모리 저장소는 프로세스가 끝나면 사라지므로 확인만 된다.
+async fn migrate_repos() {
+    repos().await;
+    println!("The database is up to date.");
+}
+
+async fn create_admin(repos: &Repos, username: &str, email: Option<&str>) {
+    let username = validation::normalize_username(username);
+    let existing = find_user(repos, &username).await;
+    if let Some(user) = existing {
+        repos.accounts.grant_admin(user.id).await.unwrap_or_else(|e| fail(e));
+        println!("User '{}' (id {}) is now an administrator.", user.username, user.id);
+        return;
+    }
+
+    let mut errors = validation::ValidationErrors::default();
+    validation::check_username(&username, &mut errors);
+    let email = email.map(str::trim).filter(|e| !e.is_empty());
+    if let Some(email) = email {
+        validation::check_email(email, &mut errors);
+    }
+    if let Err(e) = errors.into_result() {
+        fail(e);
+    }
+
+    let password = read_password(&username);
+    let hash = Passwords::configured().hash(&password).unwrap_or_else(|e| fail(e));
+    let id = repos.accounts.create_admin(&username, &hash, email).await.unwrap_or_else(|e| match e {
+        RepoError::Conflict("email") => fail("the email address is already in use"),
+        RepoError::Conflict(_) => fail(format!("user '{}' already exists", username)),
+        e => fail(e),
+    });
+    println!("Created administrator '{}' (id {}).", username, id);
+}
+
+async fn reset_password(repos: &Repos, username: &str) {
+    let Some(user) = find_user(repos, &validation::normalize_username(username)).await else {
+        fail(format!("user '{}' does not exist", username));
+    };
+    let password = read_password(&user.username);
+    let hash = Passwords::configured().hash(&password).unwrap_or_else(|e| fail(e));
+
+    // 비밀번호를 바꾼 뒤 남은 재설정 링크, 세션과 그 리프레시 토큰을 무효화한다
+    repos.users.set_password_hash(user.id, &hash, None).await.unwrap_or_else(|e| fail(e));
+    repos.tokens.expire_password_resets(user.id).await.unwrap_or_else(|e| fail(e));
+    let sessions = repos.sessions.active_ids(user.id, None).await.unwrap_or_else(|e| fail(e));
+    for session_id in &sessions {
+        repos.sessions.revoke(session_id).await.unwrap_or_else(|e| fail(e));
+    }
+    println!("Password of '{}' (id {}) changed; {} session(s) revoked.", user.username, user.id, sessions.len());
+}
+
+async fn find_user(repos: &Repos, username: &str) -> Option<User> {
+    repos.users.find_by_name(username).await.unwrap_or_else(|e| fail(e))
+}
+
+// 새 비밀번호를 읽고 가입할 때와 같은 규칙으로 검사한다
+fn read_password(username: &str) -> String {
+    let password = if io::stdin().is_terminal() {
+        let password = rpassword::prompt_password("New password: ").unwrap_or_else(|e| fail(e));
+        let again = rpassword::prompt_password("Repeat password: ").unwrap_or_else(|e| fail(e));
+        if password != again {
+            fail("the passwords do not match");
+        }
+        password
+    } else {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line).unwrap_or_else(|e| fail(e));
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if let Err(errors) = validation::validate_password("password", &password, Some(username)) {
+        fail(errors);
+    }
+    password
+}
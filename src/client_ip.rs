@@ -0,0 +1,156 @@
+// --- 클라이언트 주소 ---
+//
+// nginx, Cloudflare 같은 리버스 프록시 뒤에서는 TCP 연결의 상대가 프록시이므로, 로그인 시도 제한, IP 차단, 연결 수
+// 제한, 세션 목록, 로그가 모두 프록시 주소를 보게 된다. server.trusted_proxies (TRUSTED_PROXIES, 쉼표로 구분한
+// 주소나 `10.0.0.0/8` 같은 네트워크) 에 든 곳에서 온 요청만 server.forwarded_header (FORWARDED_HEADER) 로
+// 정한 헤더 하나를 믿는다. 프록시가 쓰지 않는 다른 헤더는 클라이언트가 마음대로 보낼 수 있으므로 보지 않는다.
+//
+// 헤더의 주소 목록을 오른쪽(가장 가까운 프록시가 붙인 것)부터 거슬러 올라가며 믿을 수 있는 프록시를 건너뛰고,
+// 처음 만난 그 밖의 주소를 클라이언트로 본다. 클라이언트가 헤더에 미리 써 둔 가짜 주소는 왼쪽에 남으므로
+// 쓰이지 않는다. 프록시를 설정하지 않으면 헤더는 보지 않는다.
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, Extensions, HeaderMap},
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use crate::{config, error::ApiError};
+
+static TRUSTED_PROXIES: Lazy<Vec<Network>> = Lazy::new(|| {
+    config::get().server.trusted_proxies.iter().map(|p| p.parse().expect("trusted proxies are validated")).collect()
+});
+
+static X_FORWARDED_FOR: header::HeaderName = header::HeaderName::from_static("x-forwarded-for");
+
+// 믿을 수 있는 프록시가 클라이언트 주소를 적는 헤더
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForwardedHeader {
+    // nginx 의 `proxy_add_x_forwarded_for`, 대부분의 로드 밸런서
+    #[default]
+    XForwardedFor,
+    // RFC 7239
+    Forwarded,
+}
+
+// 주소 하나 또는 네트워크
+#[derive(Debug, Clone, Copy)]
+pub struct Network {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Network {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not an address or network", s);
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = addr.trim().parse::<IpAddr>().map_err(|_| invalid())?.to_canonical();
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>().ok().filter(|&p| p <= max).ok_or_else(invalid)?,
+            None => max,
+        };
+        Ok(Network { addr, prefix })
+    }
+}
+
+fn is_trusted(trusted: &[Network], ip: IpAddr) -> bool {
+    trusted.iter().any(|network| network.contains(ip))
+}
+
+// `Forwarded: for=192.0.2.60;proto=https, for="[2001:db8::1]:4711"` 의 for 값
+fn forwarded_for(value: &str) -> Option<&str> {
+    value.split(';').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        name.trim().eq_ignore_ascii_case("for").then(|| value.trim().trim_matches('"'))
+    })
+}
+
+// 헤더의 주소 하나. 포트나 IPv6 의 대괄호가 붙어 있을 수 있다. `unknown` 이나 가린 이름이면 None.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+}
+
+// 프록시가 전한 주소 목록 (왼쪽이 원래 클라이언트 쪽). 설정한 헤더만 읽고 다른 헤더로 넘어가지 않는다.
+fn forwarded_chain(headers: &HeaderMap, header: ForwardedHeader) -> Vec<&str> {
+    let values = |name| headers.get_all(name).iter().filter_map(|v| v.to_str().ok()).flat_map(|v| v.split(','));
+    match header {
+        ForwardedHeader::XForwardedFor => values(&X_FORWARDED_FOR).collect(),
+        ForwardedHeader::Forwarded => values(&header::FORWARDED).filter_map(forwarded_for).collect(),
+    }
+}
+
+// 연결 상대 주소와 요청 헤더로 실제 클라이언트 주소를 구한다
+pub fn resolve(peer: IpAddr, headers: &HeaderMap, trusted: &[Network], header: ForwardedHeader) -> IpAddr {
+    let mut client = peer.to_canonical();
+    if !is_trusted(trusted, client) {
+        return client;
+    }
+    for node in forwarded_chain(headers, header).into_iter().rev() {
+        // 읽을 수 없는 주소를 만나면 거기서 멈추고 마지막으로 확인한 프록시를 클라이언트로 본다
+        let Some(ip) = parse_node(node) else { break };
+        client = ip.to_canonical();
+        if !is_trusted(trusted, client) {
+            break;
+        }
+    }
+    client
+}
+
+// 요청을 보낸 클라이언트의 주소
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    // 요청의 확장(연결 정보)과 헤더에서 구한다
+    pub fn of(extensions: &Extensions, headers: &HeaderMap) -> Option<Self> {
+        let ConnectInfo(peer) = extensions.get::<ConnectInfo<SocketAddr>>()?;
+        let header = config::get().server.forwarded_header;
+        Some(ClientIp(resolve(peer.ip(), headers, &TRUSTED_PROXIES, header)))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // into_make_service_with_connect_info 로 띄우면 항상 있다
+        ClientIp::of(&parts.extensions, &parts.headers).ok_or_else(ApiError::internal)
+    }
+}
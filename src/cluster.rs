@@ -0,0 +1,147 @@
+// --- 여러 서버로 운영할 때의 공유 상태 ---
+//
+// cluster.redis_url 을 설정하면 서버마다 자기에게 열린 웹소켓 연결(사용자, 방, 연결 수)을 `<prefix>presence:<서버 id>`
+// 키에 cluster.presence_heartbeat_seconds 마다 기록한다. 키는 그 세 배가 지나면 사라지므로 멈춘 서버의 연결은 저절로
+// 빠진다. 같은 주기로 다른 서버들의 키를 읽어 ConnectionRegistry 에 넣으므로, 친구 접속 상태나 관리 화면의 연결
+// 수는 모든 서버를 합친 값이 된다(한 주기만큼 늦을 수 있다). Redis 에 닿지 않는 동안에는 이 서버의 연결만 센다.
+//
+// 세션이나 토큰 폐기, 강퇴나 계정 비활성화처럼 소켓을 닫는 일은 `ClusterEvent` 로 만들어 버스로 다른 서버에도
+// 알리고, 받은 서버는 자기 연결과 폐기 목록에 적용한다. ConnectionRegistry 와 RevocationStore 가 `Relay` 로
+// 알리므로 부르는 쪽은 신경 쓰지 않아도 된다.
+
+use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, Rng};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::{sync::OnceLock, time::Duration};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::{config, connections::RemoteConnections, ws::CloseReason, AppState};
+
+// 이 서버 프로세스의 id. 시작할 때마다 새로 만든다.
+pub static NODE_ID: Lazy<String> =
+    Lazy::new(|| rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect());
+
+// Redis 채널과 키 이름 앞에 붙인다. 같은 Redis 를 쓰는 다른 서비스나 다른 WebChat 설치와 겹치지 않게 한다.
+pub static KEY_PREFIX: Lazy<String> = Lazy::new(|| config::get().cluster.channel_prefix.clone());
+
+static HEARTBEAT_INTERVAL: Lazy<Duration> =
+    Lazy::new(|| Duration::from_secs(config::get().cluster.presence_heartbeat_seconds.max(1)));
+
+// 모든 서버에 적용해야 하는 연결 종료와 폐기
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClusterEvent {
+    CloseSession { session_id: String, reason: CloseReason },
+    CloseUser { user_id: i32, reason: CloseReason },
+    CloseUserInRoom { user_id: i32, room: String, reason: CloseReason },
+    CloseRoom { room: String, reason: CloseReason },
+    // exp 는 토큰의 원래 만료 시각 (유닉스 초)
+    RevokeToken { jti: String, exp: usize },
+    RevokeSession { session_id: String },
+}
+
+// 다른 서버에 알릴 이벤트를 버스에 넘긴다. 서버 하나로 운영하면(연결되지 않으면) 버린다.
+#[derive(Default)]
+pub struct Relay(OnceLock<mpsc::Sender<ClusterEvent>>);
+
+impl Relay {
+    pub fn connect(&self, tx: mpsc::Sender<ClusterEvent>) {
+        let _ = self.0.set(tx);
+    }
+
+    pub fn send(&self, event: ClusterEvent) {
+        let Some(tx) = self.0.get() else {
+            return;
+        };
+        match tx.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(event)) => tracing::warn!("Cluster event queue is full; dropping {:?}", event),
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    user_id: i32,
+    room: String,
+    connections: usize,
+}
+
+pub fn spawn_presence_task(state: AppState) {
+    let Some(url) = &config::get().cluster.redis_url else {
+        return;
+    };
+    let client = redis::Client::open(url.as_str()).expect("cluster.redis_url is validated");
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(*HEARTBEAT_INTERVAL);
+        let mut conn = None;
+        loop {
+            interval.tick().await;
+            if conn.is_none() {
+                match client.get_multiplexed_tokio_connection().await {
+                    Ok(c) => conn = Some(c),
+                    Err(e) => {
+                        tracing::warn!("Failed to connect to Redis for presence: {}", e);
+                        state.connections.set_remote(RemoteConnections::new());
+                        continue;
+                    }
+                }
+            }
+            let Some(c) = conn.as_mut() else {
+                continue;
+            };
+            match heartbeat(&state, c).await {
+                Ok(remote) => state.connections.set_remote(remote),
+                Err(e) => {
+                    tracing::warn!("Failed to share presence through Redis: {}", e);
+                    state.connections.set_remote(RemoteConnections::new());
+                    conn = None;
+                }
+            }
+        }
+    });
+}
+
+// 이 서버의 연결을 기록하고 다른 서버들의 연결을 읽는다
+async fn heartbeat(
+    state: &AppState,
+    conn: &mut redis::aio::MultiplexedConnection,
+) -> redis::RedisResult<RemoteConnections> {
+    let own_key = format!("{}presence:{}", *KEY_PREFIX, *NODE_ID);
+    let entries: Vec<Entry> = state
+        .connections
+        .local_connections()
+        .into_iter()
+        .flat_map(|(user_id, rooms)| {
+            rooms.into_iter().map(move |(room, connections)| Entry { user_id, room, connections })
+        })
+        .collect();
+    let json = serde_json::to_string(&entries).unwrap_or_default();
+    conn.set_ex::<_, _, ()>(&own_key, json, HEARTBEAT_INTERVAL.as_secs() * 3).await?;
+
+    let mut keys = Vec::new();
+    {
+        let mut iter = conn.scan_match::<_, String>(format!("{}presence:*", *KEY_PREFIX)).await?;
+        while let Some(key) = iter.next_item().await {
+            if key != own_key {
+                keys.push(key);
+            }
+        }
+    }
+    let mut remote = RemoteConnections::new();
+    if keys.is_empty() {
+        return Ok(remote);
+    }
+    let nodes: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(conn).await?;
+    for json in nodes.into_iter().flatten() {
+        let Ok(entries) = serde_json::from_str::<Vec<Entry>>(&json) else {
+            continue;
+        };
+        for entry in entries {
+            *remote.entry(entry.user_id).or_default().entry(entry.room).or_insert(0) += entry.connections;
+        }
+    }
+    Ok(remote)
+}
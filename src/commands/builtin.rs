@@ -0,0 +1,88 @@
+// --- 기본 명령: /me, /shrug, /help ---
+
+use axum::async_trait;
+
+use super::{Command, CommandContext, CommandError, Outcome};
+use crate::markdown::MessageFormat;
+
+const SHRUG: &str = r"¯\_(ツ)_/¯";
+
+// /me <동작>: 3인칭 동작 메시지
+pub struct Me;
+
+#[async_trait]
+impl Command for Me {
+    fn name(&self) -> &'static str {
+        "me"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/me <action>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Describe what you are doing"
+    }
+
+    fn posts(&self, _args: &str) -> bool {
+        true
+    }
+
+    async fn run(&self, _ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError> {
+        if args.is_empty() {
+            return Err(CommandError::usage(self));
+        }
+        Ok(Outcome::Post { text: args.to_string(), format: MessageFormat::Action })
+    }
+}
+
+// /shrug [메시지]: 메시지 끝에 ¯\_(ツ)_/¯ 를 붙인다
+pub struct Shrug;
+
+#[async_trait]
+impl Command for Shrug {
+    fn name(&self) -> &'static str {
+        "shrug"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/shrug [message]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Append ¯\\_(ツ)_/¯ to your message"
+    }
+
+    fn posts(&self, _args: &str) -> bool {
+        true
+    }
+
+    async fn run(&self, _ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError> {
+        let text = if args.is_empty() { SHRUG.to_string() } else { format!("{} {}", args, SHRUG) };
+        Ok(Outcome::Post { text, format: MessageFormat::Plain })
+    }
+}
+
+// /help: 등록된 명령 목록
+pub struct Help;
+
+#[async_trait]
+impl Command for Help {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/help"
+    }
+
+    fn description(&self) -> &'static str {
+        "List available commands"
+    }
+
+    async fn run(&self, ctx: &CommandContext<'_>, _args: &str) -> Result<Outcome, CommandError> {
+        let lines: Vec<String> =
+            ctx.state.commands.commands().map(|c| format!("{} - {}", c.usage(), c.description())).collect();
+        Ok(Outcome::Reply(lines.join("\n")))
+    }
+}
@@ -0,0 +1,67 @@
+// --- /kick ---
+//
+// 방 주인이나 관리자가 사용자의 이 방 연결을 `4003 kicked` 로 닫는다. 차단은 아니므로 다시 들어올 수 있다.
+
+use axum::async_trait;
+use serde_json::json;
+
+use super::{require_manager, Command, CommandContext, CommandError, Outcome};
+use crate::{
+    audit::{self, Target},
+    protocol::ServerEvent,
+    repo::User,
+    ws::CloseReason,
+};
+
+pub struct Kick;
+
+#[async_trait]
+impl Command for Kick {
+    fn name(&self) -> &'static str {
+        "kick"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/kick <username> [reason]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Disconnect a user from this room (room owners and administrators)"
+    }
+
+    fn posts(&self, _args: &str) -> bool {
+        true
+    }
+
+    async fn run(&self, ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError> {
+        require_manager(ctx).await?;
+        let (username, reason) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let username = username.trim_start_matches('@');
+        if username.is_empty() {
+            return Err(CommandError::usage(self));
+        }
+        let reason = Some(reason.trim()).filter(|r| !r.is_empty());
+
+        let Some(User { id: user_id, username, .. }) = ctx.state.repos.users.find_by_name(username).await? else {
+            return Err(CommandError::new("user_not_found", format!("No user named {}", username)));
+        };
+        if user_id == ctx.claims.user_id {
+            return Err(CommandError::new("cannot_kick_self", "You cannot kick yourself"));
+        }
+        let closed = ctx.state.connections.close_user_in_room(user_id, ctx.room, CloseReason::Kicked);
+        if closed == 0 {
+            return Err(CommandError::new("not_in_room", format!("{} is not in this room", username)));
+        }
+
+        tracing::info!("User {} kicked {} from '{}' ({} connections)", ctx.claims.user_id, user_id, ctx.room, closed);
+        let details = json!({ "room": ctx.room });
+        audit::record(ctx.state, ctx.claims, "user.kick", Target::User(user_id), reason, details).await;
+        Ok(Outcome::Broadcast(Box::new(ServerEvent::UserKicked {
+            room: ctx.room.to_string(),
+            user_id,
+            username,
+            kicked_by: ctx.claims.sub.clone(),
+            reason: reason.map(str::to_string),
+        })))
+    }
+}
@@ -0,0 +1,157 @@
+// --- 슬래시 명령 ---
+//
+// `/` 로 시작하는 채팅 메시지는 명령으로 보고, 이름으로 등록된 `Command` 에 넘긴다. 명령은 보낸 사람에게만
+// 보이는 응답(`command_response`), 방 전체에 보낼 이벤트, 또는 평범한 메시지로 보낼 본문 중 하나를 돌려준다.
+// 방에 글을 쓰거나 이벤트를 보낼 수 있는 명령(`Command::posts`)은 실행하기 전에 길이, 게스트 간격, 글쓰기 조건
+// (게스트, 메일 확인, 음소거), 스팸 검사를 보통 메시지와 똑같이 거친다. 돌려준 본문은 금칙어 검사를 거쳐 저장하고,
+// 이벤트에 사용자가 쓴 글을 싣는 명령은 스스로 금칙어 검사(`CommandContext::filter`)를 한다.
+//
+// 기본 명령은 `/me`, `/shrug`, `/kick`, `/topic`, `/remind`, `/help` 다. 새 명령은 `Command` 를 구현해
+// `CommandRegistry::register` 로 등록한다. `//` 로 시작하면 명령이 아니라 `/` 로 시작하는 메시지로 보낸다.
+
+mod builtin;
+mod kick;
+mod remind;
+pub mod topic;
+
+use axum::async_trait;
+use std::collections::BTreeMap;
+
+use crate::{auth::Claims, markdown::MessageFormat, protocol::ServerEvent, room_owners, word_filter, AppState};
+
+// 명령 이름에 쓸 수 있는 글자. 이 밖의 글자가 있으면(`/usr/bin` 등) 명령이 아니라 메시지로 본다.
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+pub struct CommandContext<'a> {
+    pub state: &'a AppState,
+    // 명령을 보낸 사용자
+    pub claims: &'a Claims,
+    pub room: &'a str,
+}
+
+impl CommandContext<'_> {
+    // 방에 보낼 글의 금칙어를 가리거나 거부한다
+    pub fn filter(&self, text: &str) -> Result<String, CommandError> {
+        match self.state.word_filter.check(self.room, text) {
+            word_filter::Verdict::Allow(text) => Ok(text),
+            word_filter::Verdict::Reject => {
+                Err(CommandError::new("message_rejected", "Message contains a blocked word"))
+            }
+        }
+    }
+}
+
+pub enum Outcome {
+    // 보낸 사람에게만 보이는 응답
+    Reply(String),
+    // 방 전체에 보낼 이벤트
+    Broadcast(Box<ServerEvent>),
+    // 보통 메시지처럼 검사하고 저장해 보낼 본문
+    Post { text: String, format: MessageFormat },
+}
+
+// 보낸 사람에게 `error` 이벤트로 간다
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    pub fn usage(command: &dyn Command) -> Self {
+        Self::new("invalid_arguments", format!("Usage: {}", command.usage()))
+    }
+}
+
+#[async_trait]
+pub trait Command: Send + Sync {
+    // `/` 를 뺀 이름 (소문자)
+    fn name(&self) -> &'static str;
+
+    // `/help` 에 보여 줄 사용법과 설명
+    fn usage(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+
+    // 이 인자로 실행하면 방에 글을 쓰거나 이벤트를 보낼 수 있는지. true 면 실행하기 전에 글쓰기 검사를 거친다.
+    fn posts(&self, _args: &str) -> bool {
+        false
+    }
+
+    // args 는 이름 뒤의 나머지 (앞뒤 공백 제거)
+    async fn run(&self, ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError>;
+}
+
+// 방을 다루는 명령은 그 방의 주인(room_owners)이나 관리자만 쓴다
+async fn require_manager(ctx: &CommandContext<'_>) -> Result<(), CommandError> {
+    if !room_owners::can_manage(ctx.state, ctx.claims, ctx.room).await? {
+        return Err(CommandError::new(
+            "room_owner_required",
+            "Only the room's owners or an administrator can do this",
+        ));
+    }
+    Ok(())
+}
+
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, Box<dyn Command>>,
+}
+
+// 메시지를 나눈 결과
+pub enum Parsed<'a> {
+    // 명령 이름과 인자
+    Command(&'a str, &'a str),
+    // 명령이 아닌 메시지 (`//` 로 시작하면 `/` 하나를 뗀 본문)
+    Text(&'a str),
+}
+
+impl CommandRegistry {
+    pub fn with_builtins() -> Self {
+        let mut registry = Self { commands: BTreeMap::new() };
+        registry.register(builtin::Me);
+        registry.register(builtin::Shrug);
+        registry.register(builtin::Help);
+        registry.register(kick::Kick);
+        registry.register(remind::Remind);
+        registry.register(topic::Topic);
+        registry
+    }
+
+    // 같은 이름이 있으면 바꾼다
+    pub fn register(&mut self, command: impl Command + 'static) {
+        self.commands.insert(command.name(), Box::new(command));
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.values().map(|c| c.as_ref())
+    }
+
+    pub fn parse(text: &str) -> Parsed<'_> {
+        if text.starts_with("//") {
+            return Parsed::Text(&text[1..]);
+        }
+        let Some(rest) = text.strip_prefix('/') else {
+            return Parsed::Text(text);
+        };
+        let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return Parsed::Text(text);
+        }
+        Parsed::Command(name, args.trim())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&dyn Command, CommandError> {
+        match self.commands.get(name.to_ascii_lowercase().as_str()) {
+            Some(command) => Ok(command.as_ref()),
+            None => Err(CommandError::new(
+                "unknown_command",
+                format!("Unknown command /{}; type /help for a list", name),
+            )),
+        }
+    }
+}
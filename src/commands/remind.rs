@@ -0,0 +1,89 @@
+// --- /remind ---
+//
+// `/remind me in 10m <내용>` 은 본인에게, `/remind here in 1h <내용>` 은 이 방 전체에 알린다.
+// 보내는 일은 `reminders` 모듈의 스케줄러가 맡는다.
+
+use axum::async_trait;
+use chrono::Utc;
+
+use super::{Command, CommandContext, CommandError, Outcome};
+use crate::reminders::{self, Target, REMINDERS_PER_USER_MAX, REMIND_MAX_DAYS};
+
+const TEXT_MAX_CHARS: usize = 500;
+
+pub struct Remind;
+
+#[async_trait]
+impl Command for Remind {
+    fn name(&self) -> &'static str {
+        "remind"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/remind <me|here> in <duration, e.g. 10m, 1h30m, 2d> <text>"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remind yourself, or everyone in this room, later"
+    }
+
+    // `/remind here` 는 나중에 방에 글을 쓴다
+    fn posts(&self, args: &str) -> bool {
+        args.split_whitespace().next().is_some_and(|target| target.eq_ignore_ascii_case("here"))
+    }
+
+    async fn run(&self, ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError> {
+        let mut words = args.split_whitespace();
+        let target = match words.next().map(str::to_ascii_lowercase).as_deref() {
+            Some("me") => Target::Me,
+            Some("here") => Target::Room,
+            _ => return Err(CommandError::usage(self)),
+        };
+        let mut duration = words.next().unwrap_or_default();
+        if duration.eq_ignore_ascii_case("in") {
+            duration = words.next().unwrap_or_default();
+        }
+        let Some(delay) = reminders::parse_duration(duration) else {
+            return Err(CommandError::usage(self));
+        };
+        if delay > chrono::Duration::days(REMIND_MAX_DAYS) {
+            return Err(CommandError::new(
+                "invalid_arguments",
+                format!("Reminders can be at most {} days ahead", REMIND_MAX_DAYS),
+            ));
+        }
+        let text = words.collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return Err(CommandError::usage(self));
+        }
+        if text.chars().count() > TEXT_MAX_CHARS {
+            return Err(CommandError::new(
+                "invalid_arguments",
+                format!("Reminders must be at most {} characters", TEXT_MAX_CHARS),
+            ));
+        }
+
+        // 방 전체에 가는 리마인더는 보통 메시지와 같은 검사를 이미 거쳤다(posts). 금칙어는 본문에만 적용한다.
+        let text = match target {
+            Target::Me => text,
+            Target::Room => ctx.filter(&text)?,
+        };
+
+        let pending = ctx.state.repos.reminders.count(ctx.claims.user_id).await?;
+        if pending >= REMINDERS_PER_USER_MAX {
+            return Err(CommandError::new(
+                "reminder_limit_reached",
+                format!("You can have at most {} pending reminders", REMINDERS_PER_USER_MAX),
+            ));
+        }
+
+        let remind_at = Utc::now() + delay;
+        let id = reminders::schedule(ctx.state, ctx.claims.user_id, ctx.room, target, &text, remind_at).await?;
+        tracing::info!("User {} set reminder {} for {}", ctx.claims.user_id, id, remind_at);
+        let whom = match target {
+            Target::Me => "you",
+            Target::Room => "this room",
+        };
+        Ok(Outcome::Reply(format!("I will remind {} at {}", whom, remind_at.format("%Y-%m-%d %H:%M:%S UTC"))))
+    }
+}
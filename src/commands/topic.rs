@@ -0,0 +1,82 @@
+// --- /topic ---
+//
+// 인자 없이 쓰면 방 주제를 보여 주고, 방 주인이나 관리자가 인자와 함께 쓰면 주제를 바꿔 방 전체에 `topic` 이벤트를 보낸다.
+// 방에 들어오면 주제가 있을 때 `topic` 이벤트를 먼저 받는다.
+
+use axum::async_trait;
+use serde_json::json;
+
+use super::{require_manager, Command, CommandContext, CommandError, Outcome};
+use crate::{
+    audit::{self, Target},
+    protocol::ServerEvent,
+    repo::{RepoResult, RoomTopic},
+    AppState,
+};
+
+const TOPIC_MAX_CHARS: usize = 200;
+
+// 방의 현재 주제 이벤트. 주제가 없으면 None.
+pub async fn current(state: &AppState, room: &str) -> RepoResult<Option<ServerEvent>> {
+    let topic = state.repos.rooms.topic(room).await?;
+    Ok(topic.map(|RoomTopic { topic, set_by, set_at }| ServerEvent::Topic {
+        room: room.to_string(),
+        topic,
+        set_by,
+        set_at: set_at.timestamp_millis(),
+    }))
+}
+
+pub struct Topic;
+
+#[async_trait]
+impl Command for Topic {
+    fn name(&self) -> &'static str {
+        "topic"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/topic [new topic]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the room topic, or change it (room owners and administrators)"
+    }
+
+    // 보여 주기만 할 때는 글쓰기 검사를 하지 않는다
+    fn posts(&self, args: &str) -> bool {
+        !args.is_empty()
+    }
+
+    async fn run(&self, ctx: &CommandContext<'_>, args: &str) -> Result<Outcome, CommandError> {
+        if args.is_empty() {
+            let reply = match current(ctx.state, ctx.room).await? {
+                Some(ServerEvent::Topic { topic, set_by, .. }) => {
+                    format!("Topic: {} (set by {})", topic, set_by.as_deref().unwrap_or("a deleted user"))
+                }
+                _ => "No topic is set for this room".to_string(),
+            };
+            return Ok(Outcome::Reply(reply));
+        }
+
+        require_manager(ctx).await?;
+        if args.chars().count() > TOPIC_MAX_CHARS {
+            return Err(CommandError::new(
+                "topic_too_long",
+                format!("Topics must be at most {} characters", TOPIC_MAX_CHARS),
+            ));
+        }
+        let topic = ctx.filter(args)?;
+        let set_at = ctx.state.repos.rooms.set_topic(ctx.room, &topic, ctx.claims.user_id).await?;
+
+        tracing::info!("User {} set the topic of '{}'", ctx.claims.user_id, ctx.room);
+        let details = json!({ "topic": topic });
+        audit::record(ctx.state, ctx.claims, "room.topic", Target::Room(ctx.room), None, details).await;
+        Ok(Outcome::Broadcast(Box::new(ServerEvent::Topic {
+            room: ctx.room.to_string(),
+            topic,
+            set_by: Some(ctx.claims.sub.clone()),
+            set_at: set_at.timestamp_millis(),
+        })))
+    }
+}
@@ -0,0 +1,1255 @@
+// --- 설정 ---
+//
+// 서버 주소, TLS, DB, JWT, 메일, 저장소, 로그, 제한, 기능 스위치 등 서버의 모든 설정은 설정 파일과 환경 변수에서
+// 읽어 타입이 정해진 `Config` 하나로 모은다. 각 모듈은 환경 변수를 직접 읽지 않고 `config::get()` 을 본다.
+// 우선순위는 기본값 < 설정 파일 < 환경 변수(.env 포함)이다.
+//
+// 설정 파일은 CONFIG_FILE 로 고른다. 지정하지 않으면 작업 디렉터리의 webchat.toml 을 쓰고, 없으면 건너뛴다.
+// 확장자가 .yaml 이나 .yml 이면 YAML 로 읽는다. 파일의 키는 `[limits] ws_max_connections_per_user = 5` 처럼
+// 섹션과 소문자 이름이고, 환경 변수는 예전 이름 그대로다(ENV_KEYS).
+//
+// 시작할 때 한 번 읽고 검증한다. 잘못된 값이 있으면 어느 키(또는 환경 변수)인지 모두 보여 주고 끝낸다.
+// 함께 있어야 하는 값(CAPTCHA 키, S3 자격 증명 등)도 이때 확인하므로, 설정 때문에 요청 처리 중에 멈추지 않는다.
+
+use axum::http::{HeaderValue, Method, Uri};
+use figment::{
+    providers::{Format, Serialized, Toml, Yaml},
+    value::Value,
+    Figment,
+};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{collections::BTreeMap, env, net::SocketAddr, path::Path, process};
+use tracing_subscriber::EnvFilter;
+
+use crate::{
+    account::MessagePolicy,
+    captcha,
+    client_ip::{ForwardedHeader, Network},
+    cors, keys,
+    logging::{LogFormat, LogRotation},
+    mail::{self, SmtpTls},
+    passkeys,
+    password::{Argon2Hasher, HashAlgorithm},
+    repo,
+    retention::RetentionAction,
+    storage, web_push,
+    ws::LagPolicy,
+};
+
+const DEFAULT_FILE: &str = "webchat.toml";
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub tls: TlsConfig,
+    pub cors: CorsConfig,
+    pub security_headers: SecurityHeadersConfig,
+    pub database: DatabaseConfig,
+    pub jwt: JwtConfig,
+    pub passwords: PasswordsConfig,
+    pub auth: AuthConfig,
+    pub oauth: OAuthConfig,
+    pub captcha: CaptchaConfig,
+    pub mail: MailConfig,
+    pub storage: StorageConfig,
+    pub rooms: RoomsConfig,
+    pub guests: GuestsConfig,
+    pub retention: RetentionConfig,
+    pub notifications: NotificationsConfig,
+    pub cluster: ClusterConfig,
+    pub logging: LoggingConfig,
+    pub telemetry: TelemetryConfig,
+    pub limits: LimitsConfig,
+    pub features: FeaturesConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    // HTTP 서버가 들을 주소
+    pub bind: SocketAddr,
+    // 외부에서 접근하는 기준 주소 (메일 속 링크, OAuth 리다이렉트 URI 등)
+    pub public_url: String,
+    // 설정하면 이 주소에서 gRPC 서버도 띄운다
+    pub grpc_addr: Option<SocketAddr>,
+    pub shutdown_drain_seconds: u64,
+    // 클라이언트 주소 헤더를 믿을 리버스 프록시의 주소나 네트워크 (client_ip.rs)
+    #[serde(deserialize_with = "list")]
+    pub trusted_proxies: Vec<String>,
+    // 그 프록시가 클라이언트 주소를 적는 헤더. 이 헤더만 읽는다.
+    pub forwarded_header: ForwardedHeader,
+    // 설정하면 /metrics 에 이 Bearer 토큰이 있어야 한다
+    pub metrics_token: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind: SocketAddr::from(([127, 0, 0, 1], 3000)),
+            public_url: "http://localhost:3000".to_string(),
+            grpc_addr: None,
+            shutdown_drain_seconds: 10,
+            trusted_proxies: Vec::new(),
+            forwarded_header: ForwardedHeader::default(),
+            metrics_token: None,
+        }
+    }
+}
+
+// 둘 다 설정하면 HTTPS 로 직접 받는다 (tls.rs)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsConfig {
+    // 인증서 체인 PEM
+    pub cert_path: Option<String>,
+    // 개인 키 PEM
+    pub key_path: Option<String>,
+    // 두 파일이 바뀌었는지 확인하는 간격
+    pub reload_check_seconds: u64,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig { cert_path: None, key_path: None, reload_check_seconds: 60 }
+    }
+}
+
+// 다른 출처의 브라우저 앱이 API 를 부를 수 있게 한다 (cors.rs). 출처가 없으면 CORS 헤더를 보내지 않는다.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    // `https://app.example.com` 같은 출처, 또는 모든 출처를 뜻하는 `*`
+    #[serde(deserialize_with = "list")]
+    pub allowed_origins: Vec<String>,
+    // 쿠키와 Authorization 헤더를 함께 보낼 수 있게 한다 (`*` 와 함께 쓸 수 없다)
+    pub allow_credentials: bool,
+    #[serde(deserialize_with = "list")]
+    pub allowed_methods: Vec<String>,
+    // 브라우저가 사전 요청(preflight) 결과를 캐시하는 시간
+    pub max_age_seconds: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: Vec::new(),
+            allow_credentials: false,
+            allowed_methods: ["GET", "POST", "PUT", "PATCH", "DELETE"].map(str::to_string).to_vec(),
+            max_age_seconds: 600,
+        }
+    }
+}
+
+// 모든 응답에 붙이는 보안 헤더 (security_headers.rs). 빈 문자열이나 0 이면 그 헤더를 보내지 않는다.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityHeadersConfig {
+    // frame-ancestors 를 뺀 Content-Security-Policy
+    pub content_security_policy: String,
+    // 이 서버의 페이지를 iframe 에 넣을 수 있는 출처 (`'none'`, `'self'`, `https://portal.example.com` 등)
+    pub frame_ancestors: String,
+    // Strict-Transport-Security 의 max-age
+    pub hsts_max_age_seconds: u64,
+    pub hsts_include_subdomains: bool,
+    pub hsts_preload: bool,
+    // X-Content-Type-Options: nosniff
+    pub content_type_nosniff: bool,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        SecurityHeadersConfig {
+            content_security_policy: [
+                "default-src 'self'",
+                "script-src 'self' 'unsafe-inline' https://js.hcaptcha.com https://challenges.cloudflare.com",
+                "style-src 'self' 'unsafe-inline' https://hcaptcha.com https://*.hcaptcha.com",
+                "img-src 'self' data: blob: https:",
+                "media-src 'self' blob: https:",
+                "connect-src 'self' https://hcaptcha.com https://*.hcaptcha.com",
+                "frame-src https://hcaptcha.com https://*.hcaptcha.com https://challenges.cloudflare.com",
+                "object-src 'none'",
+                "base-uri 'self'",
+                "form-action 'self'",
+            ]
+            .join("; "),
+            frame_ancestors: "'none'".to_string(),
+            hsts_max_age_seconds: 365 * 24 * 60 * 60,
+            hsts_include_subdomains: false,
+            hsts_preload: false,
+            content_type_nosniff: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: Option<String>,
+    pub max_connections: u32,
+    // 시작할 때 밀린 마이그레이션을 적용한다. 끄면 확인만 한다 (schema.rs).
+    pub migrate_on_startup: bool,
+    // 한 번에 받아 두는 메시지 id 수 (message_writer.rs)
+    pub message_id_block: i64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig { url: None, max_connections: 10, migrate_on_startup: true, message_id_block: 1 }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JwtConfig {
+    // HS256 | RS256 | EdDSA
+    pub algorithm: String,
+    pub secret: Option<String>,
+    pub private_key_path: Option<String>,
+    pub public_key_path: Option<String>,
+    // 교체 중인 이전 키 (검증에만 쓴다)
+    #[serde(deserialize_with = "list")]
+    pub previous_secrets: Vec<String>,
+    #[serde(deserialize_with = "list")]
+    pub previous_public_key_paths: Vec<String>,
+    pub access_token_ttl_minutes: i64,
+    pub refresh_token_ttl_days: i64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        JwtConfig {
+            algorithm: "HS256".to_string(),
+            secret: None,
+            private_key_path: None,
+            public_key_path: None,
+            previous_secrets: Vec::new(),
+            previous_public_key_paths: Vec::new(),
+            access_token_ttl_minutes: 15,
+            refresh_token_ttl_days: 30,
+        }
+    }
+}
+
+// 새 비밀번호 해시와 비밀번호 규칙 (password.rs, validation.rs)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordsConfig {
+    pub hash_algorithm: HashAlgorithm,
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+    pub bcrypt_cost: u32,
+    pub min_length: usize,
+    // 길이 × log2(쓴 문자 종류의 크기)로 어림한 값
+    pub min_entropy_bits: f64,
+}
+
+impl Default for PasswordsConfig {
+    fn default() -> Self {
+        PasswordsConfig {
+            hash_algorithm: HashAlgorithm::Argon2id,
+            argon2_memory_kib: 19 * 1024,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+            bcrypt_cost: 12,
+            min_length: 8,
+            min_entropy_bits: 40.0,
+        }
+    }
+}
+
+// 메일로 보내는 링크의 수명과 2단계 인증 설정
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub email_verification_ttl_hours: i64,
+    pub password_reset_ttl_minutes: i64,
+    pub magic_link_ttl_minutes: i64,
+    // 인증 앱에 보이는 발급자 이름
+    pub totp_issuer: String,
+    // 없으면 server.public_url 의 호스트 이름 (passkeys.rs)
+    pub webauthn_rp_id: Option<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            email_verification_ttl_hours: 48,
+            password_reset_ttl_minutes: 30,
+            magic_link_ttl_minutes: 15,
+            totp_issuer: "WebChat".to_string(),
+            webauthn_rp_id: None,
+        }
+    }
+}
+
+// id 와 secret 이 모두 있는 제공자만 켠다 (oauth.rs)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OAuthConfig {
+    pub google_client_id: Option<String>,
+    pub google_client_secret: Option<String>,
+    pub github_client_id: Option<String>,
+    pub github_client_secret: Option<String>,
+}
+
+// 가입 CAPTCHA (captcha.rs)
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptchaConfig {
+    pub provider: captcha::Provider,
+    pub site_key: Option<String>,
+    pub secret: Option<String>,
+}
+
+// smtp_host 가 없으면 메일을 로그로만 남긴다 (mail.rs)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub from: String,
+    pub smtp_host: Option<String>,
+    // 없으면 smtp_tls 에 맞는 기본 포트
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_tls: SmtpTls,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        MailConfig {
+            from: "WebChat <no-reply@localhost>".to_string(),
+            smtp_host: None,
+            smtp_port: None,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_tls: SmtpTls::Starttls,
+        }
+    }
+}
+
+// 아바타와 첨부 파일을 둘 곳 (storage.rs), 첨부 파일을 받고 내려주는 방식 (attachments.rs)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub avatar: StoreConfig,
+    pub attachment: StoreConfig,
+    pub s3: S3Config,
+    // 내용으로 판별한 형식. `image/*` 처럼 하위 형식 전체를 허용할 수 있다.
+    #[serde(deserialize_with = "list")]
+    pub attachment_allowed_types: Vec<String>,
+    // 내려받기 URL 서명 키. 없으면 실행할 때마다 새로 만든다.
+    pub attachment_url_secret: Option<String>,
+    pub attachment_url_ttl_seconds: i64,
+    // 이미지 첨부 파일 미리보기의 긴 변
+    pub thumbnail_max_dimension: u32,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            avatar: StoreConfig::new("data/avatars", "avatar/"),
+            attachment: StoreConfig::new("data/attachments", "attachment/"),
+            s3: S3Config::default(),
+            attachment_allowed_types: [
+                "image/png",
+                "image/jpeg",
+                "image/gif",
+                "image/webp",
+                "application/pdf",
+                "text/plain",
+                "application/zip",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            attachment_url_secret: None,
+            attachment_url_ttl_seconds: 3600,
+            thumbnail_max_dimension: 320,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StoreConfig {
+    pub backend: storage::Backend,
+    // backend 가 local 일 때의 디렉터리
+    pub dir: String,
+    // backend 가 s3 일 때 키 앞에 붙인다 (한 버킷을 함께 쓸 수 있게)
+    pub s3_prefix: String,
+}
+
+impl StoreConfig {
+    fn new(dir: &str, s3_prefix: &str) -> Self {
+        StoreConfig { backend: storage::Backend::Local, dir: dir.to_string(), s3_prefix: s3_prefix.to_string() }
+    }
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        StoreConfig::new("data", "")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+    pub bucket: Option<String>,
+    pub region: String,
+    // 없으면 https://s3.<region>.amazonaws.com
+    pub endpoint: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    // true 면 <endpoint>/<bucket>/<key>, false 면 <bucket>.<host>/<key>
+    pub path_style: bool,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        S3Config {
+            bucket: None,
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            access_key_id: None,
+            secret_access_key: None,
+            path_style: false,
+        }
+    }
+}
+
+// 방 레지스트리와 방 채널 (room_registry.rs, ws)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RoomsConfig {
+    pub registry_shards: usize,
+    // 방마다 쌓아 두는 이벤트 수
+    pub channel_capacity: usize,
+    // 방별로 다르게 줄 때. 환경 변수에서는 `room=capacity,room=capacity`.
+    #[serde(deserialize_with = "capacities")]
+    pub channel_capacity_overrides: BTreeMap<String, usize>,
+    pub lag_policy: LagPolicy,
+}
+
+impl Default for RoomsConfig {
+    fn default() -> Self {
+        RoomsConfig {
+            registry_shards: 64,
+            channel_capacity: 100,
+            channel_capacity_overrides: BTreeMap::new(),
+            lag_policy: LagPolicy::DropOldest,
+        }
+    }
+}
+
+// 게스트 접속 (guest.rs). 켜고 끄는 것은 features.guest_access 와 features.guest_can_post.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuestsConfig {
+    // 게스트가 들어갈 수 있는 방. 비어 있으면 모든 방.
+    #[serde(deserialize_with = "list")]
+    pub rooms: Vec<String>,
+    pub post_interval_seconds: u64,
+    // 정식 계정이 되지 않은 게스트 계정을 지우기까지의 기간
+    pub ttl_days: i64,
+}
+
+impl Default for GuestsConfig {
+    fn default() -> Self {
+        GuestsConfig { rooms: Vec::new(), post_interval_seconds: 10, ttl_days: 7 }
+    }
+}
+
+// 저장한 데이터를 얼마나 두고 어떻게 지우는지
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionConfig {
+    // 정책이 없는 방의 메시지 보관 기간(일). 0 이면 지우지 않는다 (retention.rs).
+    pub days: i32,
+    pub action: RetentionAction,
+    pub check_seconds: u64,
+    pub notification_days: i64,
+    // 끝난 개인 데이터 내보내기를 받을 수 있는 시간 (export.rs)
+    pub export_ttl_hours: i64,
+    // 계정을 지울 때 메시지를 익명화할지 함께 지울지 (account.rs)
+    pub account_deletion_messages: MessagePolicy,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        RetentionConfig {
+            days: 0,
+            action: RetentionAction::Delete,
+            check_seconds: 3600,
+            notification_days: 90,
+            export_ttl_hours: 24,
+            account_deletion_messages: MessagePolicy::Anonymize,
+        }
+    }
+}
+
+// 메일 요약과 Web Push (digest.rs, web_push.rs). 요약을 켜는 것은 features.email_digest.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    pub email_digest_after_minutes: i64,
+    pub email_digest_check_seconds: u64,
+    // P-256 개인 키 (32바이트, base64url). 없으면 Web Push 를 끈다.
+    pub vapid_private_key: Option<String>,
+    // 없으면 server.public_url
+    pub vapid_subject: Option<String>,
+    pub push_ttl_seconds: u32,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        NotificationsConfig {
+            email_digest_after_minutes: 60,
+            email_digest_check_seconds: 300,
+            vapid_private_key: None,
+            vapid_subject: None,
+            push_ttl_seconds: 86400,
+        }
+    }
+}
+
+// 서버를 여러 대 띄울 때 (bus/redis.rs, cluster.rs). redis_url 이 없으면 서버 하나로 돈다.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClusterConfig {
+    pub redis_url: Option<String>,
+    pub channel_prefix: String,
+    pub presence_heartbeat_seconds: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        ClusterConfig { redis_url: None, channel_prefix: "webchat:".to_string(), presence_heartbeat_seconds: 10 }
+    }
+}
+
+// logging.rs
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    // EnvFilter 문법 (예: `info,webchat::ws=debug,sqlx=warn`)
+    pub level: String,
+    pub format: LogFormat,
+    pub file: Option<String>,
+    pub rotation: LogRotation,
+    // 0 이면 교체한 파일을 모두 남긴다
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            file: None,
+            rotation: LogRotation::Daily,
+            max_files: 0,
+        }
+    }
+}
+
+// OTLP 로 span 을 내보낸다 (telemetry.rs). otlp_endpoint 가 없으면 내보내지 않는다.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+    pub sample_ratio: f64,
+    pub message_sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        TelemetryConfig {
+            otlp_endpoint: None,
+            service_name: "webchat".to_string(),
+            sample_ratio: 1.0,
+            message_sample_ratio: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub ws_max_connections_per_user: usize,
+    pub ws_max_connections_per_ip: usize,
+    pub ws_max_message_bytes: usize,
+    pub ws_send_queue_capacity: usize,
+    pub ws_flood_rate: f64,
+    pub ws_flood_burst: f64,
+    pub ws_flood_mute_seconds: u64,
+    pub message_max_chars: usize,
+    pub attachment_max_bytes: usize,
+    pub avatar_max_bytes: usize,
+    pub voice_max_bytes: usize,
+    pub voice_max_duration_seconds: u64,
+    pub import_max_bytes: usize,
+    pub login_max_failures: i32,
+    pub login_ip_max_failures: i32,
+    pub register_ip_max_attempts: i32,
+    pub password_reset_ip_max_attempts: i32,
+    pub password_reset_email_max_attempts: i32,
+    pub magic_link_ip_max_attempts: i32,
+    pub magic_link_email_max_attempts: i32,
+    // 로그인·가입·토큰 갱신 요청의 JSON 본문 크기 (request_limits.rs)
+    pub auth_body_max_bytes: usize,
+    pub request_timeout_seconds: u64,
+    pub upload_timeout_seconds: u64,
+    // 첫 잠금 시간. 실패할 때마다 두 배가 된다 (throttle.rs).
+    pub lockout_base_seconds: i64,
+    pub lockout_max_seconds: i64,
+    // 이만큼 실패가 없으면 세던 횟수를 처음부터 다시 센다
+    pub login_failure_window_minutes: i64,
+    pub link_preview_timeout_seconds: u64,
+    // 링크한 페이지에서 읽는 양
+    pub link_preview_max_bytes: usize,
+    // 가져온 미리보기(와 실패)를 다시 쓰는 시간
+    pub link_preview_cache_hours: i64,
+    pub spam_window_seconds: u64,
+    pub spam_duplicate_limit: usize,
+    pub spam_link_limit: usize,
+    // 두 메시지가 거의 같다고 볼 유사도 (0-1)
+    pub spam_similarity: f64,
+    pub spam_cooldown_seconds: u64,
+    // 신고로 음소거할 때 기간을 정하지 않으면 쓰는 시간
+    pub moderation_mute_minutes: i64,
+    // 자동으로 사라지는 메시지의 가장 긴 수명
+    pub ephemeral_max_seconds: u64,
+    // 웹훅 하나가 분당 보낼 수 있는 메시지 수와 한 번에 몰아 보낼 수 있는 수 (webhooks.rs)
+    pub webhook_rate_per_minute: f64,
+    pub webhook_burst: f64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig {
+            ws_max_connections_per_user: 5,
+            ws_max_connections_per_ip: 20,
+            ws_max_message_bytes: 64 * 1024,
+            ws_send_queue_capacity: 128,
+            ws_flood_rate: 5.0,
+            ws_flood_burst: 10.0,
+            ws_flood_mute_seconds: 10,
+            message_max_chars: 4000,
+            attachment_max_bytes: 10 * 1024 * 1024,
+            avatar_max_bytes: 5 * 1024 * 1024,
+            voice_max_bytes: 2 * 1024 * 1024,
+            voice_max_duration_seconds: 120,
+            import_max_bytes: 200 * 1024 * 1024,
+            login_max_failures: 5,
+            login_ip_max_failures: 20,
+            register_ip_max_attempts: 10,
+            password_reset_ip_max_attempts: 10,
+            password_reset_email_max_attempts: 3,
+            magic_link_ip_max_attempts: 10,
+            magic_link_email_max_attempts: 3,
+            auth_body_max_bytes: 64 * 1024,
+            request_timeout_seconds: 30,
+            upload_timeout_seconds: 600,
+            lockout_base_seconds: 30,
+            lockout_max_seconds: 3600,
+            login_failure_window_minutes: 60,
+            link_preview_timeout_seconds: 5,
+            link_preview_max_bytes: 1024 * 1024,
+            link_preview_cache_hours: 24,
+            spam_window_seconds: 60,
+            spam_duplicate_limit: 3,
+            spam_link_limit: 3,
+            spam_similarity: 0.9,
+            spam_cooldown_seconds: 60,
+            moderation_mute_minutes: 60,
+            ephemeral_max_seconds: 7 * 24 * 60 * 60,
+            webhook_rate_per_minute: 30.0,
+            webhook_burst: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeaturesConfig {
+    pub require_invite: bool,
+    pub require_verified_email: bool,
+    pub guest_access: bool,
+    pub guest_can_post: bool,
+    pub link_previews: bool,
+    pub email_digest: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        FeaturesConfig {
+            require_invite: false,
+            require_verified_email: true,
+            guest_access: false,
+            guest_can_post: false,
+            link_previews: true,
+            email_digest: false,
+        }
+    }
+}
+
+// 환경 변수 → 설정 키. 세 번째 값이 true 면 값을 숫자나 참/거짓으로 풀지 않고 문자열 그대로 쓴다.
+const ENV_KEYS: &[(&str, &str, bool)] = &[
+    ("BIND_ADDR", "server.bind", true),
+    ("PUBLIC_URL", "server.public_url", true),
+    ("GRPC_ADDR", "server.grpc_addr", true),
+    ("SHUTDOWN_DRAIN_SECONDS", "server.shutdown_drain_seconds", false),
+    ("TRUSTED_PROXIES", "server.trusted_proxies", true),
+    ("FORWARDED_HEADER", "server.forwarded_header", true),
+    ("METRICS_TOKEN", "server.metrics_token", true),
+    ("TLS_CERT_PATH", "tls.cert_path", true),
+    ("TLS_KEY_PATH", "tls.key_path", true),
+    ("TLS_RELOAD_CHECK_SECONDS", "tls.reload_check_seconds", false),
+    ("CORS_ALLOWED_ORIGINS", "cors.allowed_origins", true),
+    ("CORS_ALLOW_CREDENTIALS", "cors.allow_credentials", false),
+    ("CORS_ALLOWED_METHODS", "cors.allowed_methods", true),
+    ("CORS_MAX_AGE_SECONDS", "cors.max_age_seconds", false),
+    ("CONTENT_SECURITY_POLICY", "security_headers.content_security_policy", true),
+    ("FRAME_ANCESTORS", "security_headers.frame_ancestors", true),
+    ("HSTS_MAX_AGE_SECONDS", "security_headers.hsts_max_age_seconds", false),
+    ("HSTS_INCLUDE_SUBDOMAINS", "security_headers.hsts_include_subdomains", false),
+    ("HSTS_PRELOAD", "security_headers.hsts_preload", false),
+    ("CONTENT_TYPE_NOSNIFF", "security_headers.content_type_nosniff", false),
+    ("DATABASE_URL", "database.url", true),
+    ("DATABASE_MAX_CONNECTIONS", "database.max_connections", false),
+    ("DATABASE_MIGRATE_ON_STARTUP", "database.migrate_on_startup", false),
+    ("MESSAGE_ID_BLOCK", "database.message_id_block", false),
+    ("JWT_ALGORITHM", "jwt.algorithm", true),
+    ("JWT_SECRET", "jwt.secret", true),
+    ("JWT_PRIVATE_KEY_PATH", "jwt.private_key_path", true),
+    ("JWT_PUBLIC_KEY_PATH", "jwt.public_key_path", true),
+    ("JWT_PREVIOUS_SECRETS", "jwt.previous_secrets", true),
+    ("JWT_PREVIOUS_PUBLIC_KEY_PATHS", "jwt.previous_public_key_paths", true),
+    ("ACCESS_TOKEN_TTL_MINUTES", "jwt.access_token_ttl_minutes", false),
+    ("REFRESH_TOKEN_TTL_DAYS", "jwt.refresh_token_ttl_days", false),
+    ("PASSWORD_HASH_ALGORITHM", "passwords.hash_algorithm", true),
+    ("ARGON2_MEMORY_KIB", "passwords.argon2_memory_kib", false),
+    ("ARGON2_ITERATIONS", "passwords.argon2_iterations", false),
+    ("ARGON2_PARALLELISM", "passwords.argon2_parallelism", false),
+    ("BCRYPT_COST", "passwords.bcrypt_cost", false),
+    ("PASSWORD_MIN_LENGTH", "passwords.min_length", false),
+    ("PASSWORD_MIN_ENTROPY_BITS", "passwords.min_entropy_bits", false),
+    ("EMAIL_VERIFICATION_TTL_HOURS", "auth.email_verification_ttl_hours", false),
+    ("PASSWORD_RESET_TTL_MINUTES", "auth.password_reset_ttl_minutes", false),
+    ("MAGIC_LINK_TTL_MINUTES", "auth.magic_link_ttl_minutes", false),
+    ("TOTP_ISSUER", "auth.totp_issuer", true),
+    ("WEBAUTHN_RP_ID", "auth.webauthn_rp_id", true),
+    ("GOOGLE_CLIENT_ID", "oauth.google_client_id", true),
+    ("GOOGLE_CLIENT_SECRET", "oauth.google_client_secret", true),
+    ("GITHUB_CLIENT_ID", "oauth.github_client_id", true),
+    ("GITHUB_CLIENT_SECRET", "oauth.github_client_secret", true),
+    ("CAPTCHA_PROVIDER", "captcha.provider", true),
+    ("CAPTCHA_SITE_KEY", "captcha.site_key", true),
+    ("CAPTCHA_SECRET", "captcha.secret", true),
+    ("MAIL_FROM", "mail.from", true),
+    ("SMTP_HOST", "mail.smtp_host", true),
+    ("SMTP_PORT", "mail.smtp_port", false),
+    ("SMTP_USERNAME", "mail.smtp_username", true),
+    ("SMTP_PASSWORD", "mail.smtp_password", true),
+    ("SMTP_TLS", "mail.smtp_tls", true),
+    ("AVATAR_STORAGE", "storage.avatar.backend", true),
+    ("AVATAR_DIR", "storage.avatar.dir", true),
+    ("AVATAR_S3_PREFIX", "storage.avatar.s3_prefix", true),
+    ("ATTACHMENT_STORAGE", "storage.attachment.backend", true),
+    ("ATTACHMENT_DIR", "storage.attachment.dir", true),
+    ("ATTACHMENT_S3_PREFIX", "storage.attachment.s3_prefix", true),
+    ("S3_BUCKET", "storage.s3.bucket", true),
+    ("S3_REGION", "storage.s3.region", true),
+    ("S3_ENDPOINT", "storage.s3.endpoint", true),
+    ("S3_ACCESS_KEY_ID", "storage.s3.access_key_id", true),
+    ("S3_SECRET_ACCESS_KEY", "storage.s3.secret_access_key", true),
+    ("S3_PATH_STYLE", "storage.s3.path_style", false),
+    ("ATTACHMENT_ALLOWED_TYPES", "storage.attachment_allowed_types", true),
+    ("ATTACHMENT_URL_SECRET", "storage.attachment_url_secret", true),
+    ("ATTACHMENT_URL_TTL_SECONDS", "storage.attachment_url_ttl_seconds", false),
+    ("THUMBNAIL_MAX_DIMENSION", "storage.thumbnail_max_dimension", false),
+    ("ROOM_REGISTRY_SHARDS", "rooms.registry_shards", false),
+    ("ROOM_CHANNEL_CAPACITY", "rooms.channel_capacity", false),
+    ("ROOM_CHANNEL_CAPACITY_OVERRIDES", "rooms.channel_capacity_overrides", true),
+    ("ROOM_LAG_POLICY", "rooms.lag_policy", true),
+    ("GUEST_ROOMS", "guests.rooms", true),
+    ("GUEST_POST_INTERVAL_SECONDS", "guests.post_interval_seconds", false),
+    ("GUEST_TTL_DAYS", "guests.ttl_days", false),
+    ("RETENTION_DAYS", "retention.days", false),
+    ("RETENTION_ACTION", "retention.action", true),
+    ("RETENTION_CHECK_SECONDS", "retention.check_seconds", false),
+    ("NOTIFICATION_RETENTION_DAYS", "retention.notification_days", false),
+    ("EXPORT_TTL_HOURS", "retention.export_ttl_hours", false),
+    ("ACCOUNT_DELETION_MESSAGES", "retention.account_deletion_messages", true),
+    ("EMAIL_DIGEST_AFTER_MINUTES", "notifications.email_digest_after_minutes", false),
+    ("EMAIL_DIGEST_CHECK_SECONDS", "notifications.email_digest_check_seconds", false),
+    ("VAPID_PRIVATE_KEY", "notifications.vapid_private_key", true),
+    ("VAPID_SUBJECT", "notifications.vapid_subject", true),
+    ("PUSH_TTL_SECONDS", "notifications.push_ttl_seconds", false),
+    ("REDIS_URL", "cluster.redis_url", true),
+    ("REDIS_CHANNEL_PREFIX", "cluster.channel_prefix", true),
+    ("PRESENCE_HEARTBEAT_SECONDS", "cluster.presence_heartbeat_seconds", false),
+    ("LOG_LEVEL", "logging.level", true),
+    ("LOG_FORMAT", "logging.format", true),
+    ("LOG_FILE", "logging.file", true),
+    ("LOG_ROTATION", "logging.rotation", true),
+    ("LOG_MAX_FILES", "logging.max_files", false),
+    ("OTEL_EXPORTER_OTLP_ENDPOINT", "telemetry.otlp_endpoint", true),
+    ("OTEL_SERVICE_NAME", "telemetry.service_name", true),
+    ("OTEL_SAMPLE_RATIO", "telemetry.sample_ratio", false),
+    ("OTEL_MESSAGE_SAMPLE_RATIO", "telemetry.message_sample_ratio", false),
+    ("WS_MAX_CONNECTIONS_PER_USER", "limits.ws_max_connections_per_user", false),
+    ("WS_MAX_CONNECTIONS_PER_IP", "limits.ws_max_connections_per_ip", false),
+    ("WS_MAX_MESSAGE_BYTES", "limits.ws_max_message_bytes", false),
+    ("WS_SEND_QUEUE_CAPACITY", "limits.ws_send_queue_capacity", false),
+    ("WS_FLOOD_RATE", "limits.ws_flood_rate", false),
+    ("WS_FLOOD_BURST", "limits.ws_flood_burst", false),
+    ("WS_FLOOD_MUTE_SECONDS", "limits.ws_flood_mute_seconds", false),
+    ("MESSAGE_MAX_CHARS", "limits.message_max_chars", false),
+    ("ATTACHMENT_MAX_BYTES", "limits.attachment_max_bytes", false),
+    ("AVATAR_MAX_BYTES", "limits.avatar_max_bytes", false),
+    ("VOICE_MAX_BYTES", "limits.voice_max_bytes", false),
+    ("VOICE_MAX_DURATION_SECONDS", "limits.voice_max_duration_seconds", false),
+    ("IMPORT_MAX_BYTES", "limits.import_max_bytes", false),
+    ("LOGIN_MAX_FAILURES", "limits.login_max_failures", false),
+    ("LOGIN_IP_MAX_FAILURES", "limits.login_ip_max_failures", false),
+    ("REGISTER_IP_MAX_ATTEMPTS", "limits.register_ip_max_attempts", false),
+    ("PASSWORD_RESET_IP_MAX_ATTEMPTS", "limits.password_reset_ip_max_attempts", false),
+    ("PASSWORD_RESET_EMAIL_MAX_ATTEMPTS", "limits.password_reset_email_max_attempts", false),
+    ("MAGIC_LINK_IP_MAX_ATTEMPTS", "limits.magic_link_ip_max_attempts", false),
+    ("MAGIC_LINK_EMAIL_MAX_ATTEMPTS", "limits.magic_link_email_max_attempts", false),
+    ("AUTH_BODY_MAX_BYTES", "limits.auth_body_max_bytes", false),
+    ("REQUEST_TIMEOUT_SECONDS", "limits.request_timeout_seconds", false),
+    ("UPLOAD_TIMEOUT_SECONDS", "limits.upload_timeout_seconds", false),
+    ("LOCKOUT_BASE_SECONDS", "limits.lockout_base_seconds", false),
+    ("LOCKOUT_MAX_SECONDS", "limits.lockout_max_seconds", false),
+    ("LOGIN_FAILURE_WINDOW_MINUTES", "limits.login_failure_window_minutes", false),
+    ("LINK_PREVIEW_TIMEOUT_SECONDS", "limits.link_preview_timeout_seconds", false),
+    ("LINK_PREVIEW_MAX_BYTES", "limits.link_preview_max_bytes", false),
+    ("LINK_PREVIEW_CACHE_HOURS", "limits.link_preview_cache_hours", false),
+    ("SPAM_WINDOW_SECONDS", "limits.spam_window_seconds", false),
+    ("SPAM_DUPLICATE_LIMIT", "limits.spam_duplicate_limit", false),
+    ("SPAM_LINK_LIMIT", "limits.spam_link_limit", false),
+    ("SPAM_SIMILARITY", "limits.spam_similarity", false),
+    ("SPAM_COOLDOWN_SECONDS", "limits.spam_cooldown_seconds", false),
+    ("WEBHOOK_RATE_PER_MINUTE", "limits.webhook_rate_per_minute", false),
+    ("WEBHOOK_BURST", "limits.webhook_burst", false),
+    ("MODERATION_MUTE_MINUTES", "limits.moderation_mute_minutes", false),
+    ("EPHEMERAL_MAX_SECONDS", "limits.ephemeral_max_seconds", false),
+    ("REQUIRE_INVITE", "features.require_invite", false),
+    ("REQUIRE_VERIFIED_EMAIL", "features.require_verified_email", false),
+    ("GUEST_ACCESS", "features.guest_access", false),
+    ("GUEST_CAN_POST", "features.guest_can_post", false),
+    ("LINK_PREVIEWS", "features.link_previews", false),
+    ("EMAIL_DIGEST", "features.email_digest", false),
+];
+
+// 목록은 설정 파일에서는 배열로, 환경 변수에서는 쉼표로 구분한 문자열로 받는다
+fn list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Joined(String),
+        Items(Vec<String>),
+    }
+    Ok(match List::deserialize(deserializer)? {
+        List::Joined(joined) => {
+            joined.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+        }
+        List::Items(items) => items,
+    })
+}
+
+// 방별 값은 설정 파일에서는 표로, 환경 변수에서는 `room=capacity,room=capacity` 로 받는다
+fn capacities<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, usize>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Capacities {
+        Joined(String),
+        Table(BTreeMap<String, usize>),
+    }
+    match Capacities::deserialize(deserializer)? {
+        Capacities::Joined(joined) => joined
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .rsplit_once('=')
+                    .and_then(|(room, capacity)| Some((room.trim().to_string(), capacity.trim().parse().ok()?)))
+                    .filter(|(room, _)| !room.is_empty())
+                    .ok_or_else(|| serde::de::Error::custom(format!("'{}' is not room=capacity", entry)))
+            })
+            .collect(),
+        Capacities::Table(table) => Ok(table),
+    }
+}
+
+// 오류 메시지에 붙일 값의 출처
+fn source(key: &str, file: Option<&Path>) -> String {
+    match ENV_KEYS.iter().find(|(var, k, _)| *k == key && env::var_os(var).is_some()) {
+        Some((var, _, _)) => format!("environment variable {}", var),
+        None => match file {
+            Some(file) => file.display().to_string(),
+            None => "default".to_string(),
+        },
+    }
+}
+
+fn env_hint(key: &str) -> String {
+    match ENV_KEYS.iter().find(|(_, k, _)| *k == key) {
+        Some((var, _, _)) => format!("{} ({})", key, var),
+        None => key.to_string(),
+    }
+}
+
+fn config_file() -> Result<Option<std::path::PathBuf>, String> {
+    match env::var("CONFIG_FILE") {
+        Ok(path) if Path::new(&path).is_file() => Ok(Some(path.into())),
+        Ok(path) => Err(format!("CONFIG_FILE: '{}' does not exist", path)),
+        Err(_) => Ok(Path::new(DEFAULT_FILE).is_file().then(|| DEFAULT_FILE.into())),
+    }
+}
+
+// 기본값, 설정 파일, 환경 변수를 합쳐 읽고 검증한다
+pub fn load() -> Result<Config, Vec<String>> {
+    let file = config_file().map_err(|e| vec![e])?;
+    let mut figment = Figment::from(Serialized::defaults(Config::default()));
+    if let Some(file) = &file {
+        let yaml = matches!(file.extension().and_then(|e| e.to_str()), Some("yaml" | "yml"));
+        figment = if yaml { figment.merge(Yaml::file_exact(file)) } else { figment.merge(Toml::file_exact(file)) };
+    }
+    for &(var, key, text) in ENV_KEYS {
+        let Ok(raw) = env::var(var) else { continue };
+        let value = if text { Value::from(raw) } else { raw.parse().unwrap_or_else(|_| Value::from(raw)) };
+        figment = figment.merge((key, value));
+    }
+
+    let config: Config = figment.extract().map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|e| {
+                let key = e.path.join(".");
+                let origin = source(&key, file.as_deref());
+                if key.is_empty() {
+                    format!("{} ({})", e.kind, origin)
+                } else {
+                    format!("{}: {} ({})", key, e.kind, origin)
+                }
+            })
+            .collect::<Vec<_>>()
+    })?;
+    let problems = config.validate();
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(problems)
+    }
+}
+
+impl Config {
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut require = |ok: bool, key: &str, message: &str| {
+            if !ok {
+                problems.push(format!("{}: {}", env_hint(key), message));
+            }
+        };
+
+        let public_url = &self.server.public_url;
+        require(
+            public_url.starts_with("http://") || public_url.starts_with("https://"),
+            "server.public_url",
+            "must start with http:// or https://",
+        );
+        for proxy in &self.server.trusted_proxies {
+            if let Err(e) = proxy.parse::<Network>() {
+                require(false, "server.trusted_proxies", &e);
+            }
+        }
+        let tls = &self.tls;
+        if tls.cert_path.is_some() || tls.key_path.is_some() {
+            for (key, path) in [("tls.cert_path", &tls.cert_path), ("tls.key_path", &tls.key_path)] {
+                match path {
+                    Some(path) => require(Path::new(path).is_file(), key, &format!("'{}' does not exist", path)),
+                    None => require(false, key, "must be set together with the other TLS file"),
+                }
+            }
+        }
+        require(tls.reload_check_seconds > 0, "tls.reload_check_seconds", "must be at least 1");
+        let cors = &self.cors;
+        let any_origin = cors.allowed_origins.iter().any(|origin| origin == "*");
+        for origin in cors.allowed_origins.iter().filter(|origin| *origin != "*") {
+            if let Err(e) = cors::parse_origin(origin) {
+                require(false, "cors.allowed_origins", &e);
+            }
+        }
+        require(
+            !(any_origin && cors.allow_credentials),
+            "cors.allow_credentials",
+            "cannot be used with the origin '*'; list the origins instead",
+        );
+        for method in &cors.allowed_methods {
+            require(
+                method.parse::<Method>().is_ok(),
+                "cors.allowed_methods",
+                &format!("'{}' is not an HTTP method", method),
+            );
+        }
+        let headers = &self.security_headers;
+        let policy = headers.content_security_policy.to_ascii_lowercase();
+        require(
+            HeaderValue::from_str(&headers.content_security_policy).is_ok(),
+            "security_headers.content_security_policy",
+            "must be a single line of visible ASCII",
+        );
+        require(
+            !policy.split(';').any(|directive| directive.trim_start().starts_with("frame-ancestors")),
+            "security_headers.content_security_policy",
+            "must not contain frame-ancestors; set security_headers.frame_ancestors instead",
+        );
+        require(
+            HeaderValue::from_str(&headers.frame_ancestors).is_ok() && !headers.frame_ancestors.contains(';'),
+            "security_headers.frame_ancestors",
+            "must be a space-separated list of sources",
+        );
+        require(
+            !headers.hsts_preload || (headers.hsts_include_subdomains && headers.hsts_max_age_seconds >= 31_536_000),
+            "security_headers.hsts_preload",
+            "requires hsts_include_subdomains and an hsts_max_age_seconds of at least 31536000",
+        );
+        let database_url = self.database.url.as_deref().unwrap_or_default();
+        require(!database_url.is_empty(), "database.url", "must be set");
+        require(
+            database_url.is_empty() || repo::Backend::from_url(database_url).is_some(),
+            "database.url",
+            "must be a postgres://, mysql://, sqlite: or memory: URL",
+        );
+        require(
+            cfg!(feature = "mysql") || repo::Backend::from_url(database_url) != Some(repo::Backend::MySql),
+            "database.url",
+            "mysql:// needs a build with the mysql feature (cargo build --features mysql)",
+        );
+        require(self.database.max_connections > 0, "database.max_connections", "must be at least 1");
+
+        let jwt_set = match self.jwt.algorithm.as_str() {
+            "HS256" => {
+                let set = self.jwt.secret.as_deref().is_some_and(|s| !s.is_empty());
+                require(set, "jwt.secret", "must be set");
+                set
+            }
+            "RS256" | "EdDSA" => {
+                let needs_key = "must be set when jwt.algorithm is RS256 or EdDSA";
+                require(self.jwt.private_key_path.is_some(), "jwt.private_key_path", needs_key);
+                require(self.jwt.public_key_path.is_some(), "jwt.public_key_path", needs_key);
+                self.jwt.private_key_path.is_some() && self.jwt.public_key_path.is_some()
+            }
+            _ => {
+                require(false, "jwt.algorithm", "must be HS256, RS256 or EdDSA");
+                false
+            }
+        };
+        // 키 파일을 읽어 보고, 읽지 못하거나 키가 아니면 다른 설정 문제와 함께 알린다
+        if jwt_set {
+            if let Err(e) = keys::JwtKeys::from_config(&self.jwt) {
+                require(false, e.setting, &e.message);
+            }
+        }
+        require(self.jwt.access_token_ttl_minutes > 0, "jwt.access_token_ttl_minutes", "must be at least 1");
+        require(self.jwt.refresh_token_ttl_days > 0, "jwt.refresh_token_ttl_days", "must be at least 1");
+
+        let limits = &self.limits;
+        for (key, value) in [
+            ("limits.ws_max_connections_per_user", limits.ws_max_connections_per_user),
+            ("limits.ws_max_connections_per_ip", limits.ws_max_connections_per_ip),
+            ("limits.ws_max_message_bytes", limits.ws_max_message_bytes),
+            ("limits.ws_send_queue_capacity", limits.ws_send_queue_capacity),
+            ("limits.message_max_chars", limits.message_max_chars),
+            ("limits.attachment_max_bytes", limits.attachment_max_bytes),
+            ("limits.avatar_max_bytes", limits.avatar_max_bytes),
+            ("limits.voice_max_bytes", limits.voice_max_bytes),
+            ("limits.import_max_bytes", limits.import_max_bytes),
+            ("limits.auth_body_max_bytes", limits.auth_body_max_bytes),
+        ] {
+            require(value > 0, key, "must be at least 1");
+        }
+        require(limits.request_timeout_seconds > 0, "limits.request_timeout_seconds", "must be at least 1");
+        require(
+            limits.upload_timeout_seconds >= limits.request_timeout_seconds,
+            "limits.upload_timeout_seconds",
+            "must be at least limits.request_timeout_seconds",
+        );
+        require(limits.ws_flood_rate > 0.0, "limits.ws_flood_rate", "must be greater than 0");
+        require(limits.ws_flood_burst >= 1.0, "limits.ws_flood_burst", "must be at least 1");
+        require(limits.webhook_rate_per_minute > 0.0, "limits.webhook_rate_per_minute", "must be greater than 0");
+        require(limits.webhook_burst >= 1.0, "limits.webhook_burst", "must be at least 1");
+        for (key, value) in [
+            ("limits.login_max_failures", limits.login_max_failures),
+            ("limits.login_ip_max_failures", limits.login_ip_max_failures),
+            ("limits.register_ip_max_attempts", limits.register_ip_max_attempts),
+            ("limits.password_reset_ip_max_attempts", limits.password_reset_ip_max_attempts),
+            ("limits.password_reset_email_max_attempts", limits.password_reset_email_max_attempts),
+            ("limits.magic_link_ip_max_attempts", limits.magic_link_ip_max_attempts),
+            ("limits.magic_link_email_max_attempts", limits.magic_link_email_max_attempts),
+        ] {
+            require(value > 0, key, "must be at least 1");
+        }
+        for (key, value) in [
+            ("limits.lockout_base_seconds", limits.lockout_base_seconds),
+            ("limits.login_failure_window_minutes", limits.login_failure_window_minutes),
+            ("limits.link_preview_cache_hours", limits.link_preview_cache_hours),
+            ("limits.moderation_mute_minutes", limits.moderation_mute_minutes),
+            ("auth.email_verification_ttl_hours", self.auth.email_verification_ttl_hours),
+            ("auth.password_reset_ttl_minutes", self.auth.password_reset_ttl_minutes),
+            ("auth.magic_link_ttl_minutes", self.auth.magic_link_ttl_minutes),
+            ("storage.attachment_url_ttl_seconds", self.storage.attachment_url_ttl_seconds),
+            ("guests.ttl_days", self.guests.ttl_days),
+            ("retention.notification_days", self.retention.notification_days),
+            ("retention.export_ttl_hours", self.retention.export_ttl_hours),
+            ("notifications.email_digest_after_minutes", self.notifications.email_digest_after_minutes),
+            ("database.message_id_block", self.database.message_id_block),
+        ] {
+            require(value > 0, key, "must be at least 1");
+        }
+        for (key, value) in [
+            ("limits.link_preview_timeout_seconds", limits.link_preview_timeout_seconds),
+            ("limits.spam_window_seconds", limits.spam_window_seconds),
+            ("limits.ephemeral_max_seconds", limits.ephemeral_max_seconds),
+            ("limits.link_preview_max_bytes", limits.link_preview_max_bytes as u64),
+            ("limits.spam_duplicate_limit", limits.spam_duplicate_limit as u64),
+            ("limits.spam_link_limit", limits.spam_link_limit as u64),
+            ("retention.check_seconds", self.retention.check_seconds),
+            ("notifications.email_digest_check_seconds", self.notifications.email_digest_check_seconds),
+            ("cluster.presence_heartbeat_seconds", self.cluster.presence_heartbeat_seconds),
+        ] {
+            require(value > 0, key, "must be at least 1");
+        }
+        require(
+            limits.lockout_max_seconds >= limits.lockout_base_seconds,
+            "limits.lockout_max_seconds",
+            "must be at least limits.lockout_base_seconds",
+        );
+        require((0.0..=1.0).contains(&limits.spam_similarity), "limits.spam_similarity", "must be between 0 and 1");
+
+        let passwords = &self.passwords;
+        let argon2 = Argon2Hasher::new(
+            passwords.argon2_memory_kib,
+            passwords.argon2_iterations,
+            passwords.argon2_parallelism,
+        );
+        if let Err(e) = argon2.check() {
+            require(false, "passwords.argon2_memory_kib", &e.to_string());
+        }
+        require((4..=31).contains(&passwords.bcrypt_cost), "passwords.bcrypt_cost", "must be between 4 and 31");
+        require(passwords.min_length > 0, "passwords.min_length", "must be at least 1");
+        require(passwords.min_entropy_bits >= 0.0, "passwords.min_entropy_bits", "must not be negative");
+        if let Err(e) = passkeys::check(public_url, self.auth.webauthn_rp_id.as_deref()) {
+            require(false, "auth.webauthn_rp_id", &e);
+        }
+        let oauth = &self.oauth;
+        for (key, id, secret) in [
+            ("oauth.google_client_secret", &oauth.google_client_id, &oauth.google_client_secret),
+            ("oauth.github_client_secret", &oauth.github_client_id, &oauth.github_client_secret),
+        ] {
+            require(id.is_some() == secret.is_some(), key, "must be set together with the client id");
+        }
+        if self.captcha.provider != captcha::Provider::None {
+            let needs_key = "must be set when captcha.provider is set";
+            require(self.captcha.site_key.is_some(), "captcha.site_key", needs_key);
+            require(self.captcha.secret.is_some(), "captcha.secret", needs_key);
+        }
+        for (key, message) in mail::check(&self.mail) {
+            require(false, key, &message);
+        }
+
+        let storage = &self.storage;
+        if [&storage.avatar, &storage.attachment].iter().any(|store| store.backend == storage::Backend::S3) {
+            let needs_s3 = "must be set when a store uses the s3 backend";
+            require(storage.s3.bucket.is_some(), "storage.s3.bucket", needs_s3);
+            require(storage.s3.access_key_id.is_some(), "storage.s3.access_key_id", needs_s3);
+            require(storage.s3.secret_access_key.is_some(), "storage.s3.secret_access_key", needs_s3);
+        }
+        require(
+            !storage.attachment_allowed_types.is_empty(),
+            "storage.attachment_allowed_types",
+            "must list at least one type",
+        );
+        require(storage.thumbnail_max_dimension > 0, "storage.thumbnail_max_dimension", "must be at least 1");
+
+        let rooms = &self.rooms;
+        require(rooms.registry_shards > 0, "rooms.registry_shards", "must be at least 1");
+        require(rooms.channel_capacity > 0, "rooms.channel_capacity", "must be at least 1");
+        for (room, capacity) in &rooms.channel_capacity_overrides {
+            require(*capacity > 0, "rooms.channel_capacity_overrides", &format!("'{}' must be at least 1", room));
+        }
+        require(self.guests.post_interval_seconds > 0, "guests.post_interval_seconds", "must be at least 1");
+        require(self.retention.days >= 0, "retention.days", "must not be negative");
+        if let Some(key) = &self.notifications.vapid_private_key {
+            if let Err(e) = web_push::check_vapid_key(key) {
+                require(false, "notifications.vapid_private_key", &e);
+            }
+        }
+        if let Some(url) = &self.cluster.redis_url {
+            if let Err(e) = redis::Client::open(url.as_str()) {
+                require(false, "cluster.redis_url", &e.to_string());
+            }
+        }
+
+        if let Err(e) = EnvFilter::try_new(&self.logging.level) {
+            require(false, "logging.level", &e.to_string());
+        }
+        let telemetry = &self.telemetry;
+        if let Some(endpoint) = &telemetry.otlp_endpoint {
+            require(
+                endpoint.parse::<Uri>().is_ok_and(|uri| uri.scheme().is_some() && uri.host().is_some()),
+                "telemetry.otlp_endpoint",
+                "must be a URL such as http://localhost:4317",
+            );
+        }
+        for (key, value) in [
+            ("telemetry.sample_ratio", telemetry.sample_ratio),
+            ("telemetry.message_sample_ratio", telemetry.message_sample_ratio),
+        ] {
+            require((0.0..=1.0).contains(&value), key, "must be between 0 and 1");
+        }
+        problems
+    }
+}
+
+// 설정을 읽는다. 문제가 있으면 모두 보여 주고 끝낸다.
+pub fn init() {
+    match load() {
+        Ok(config) => {
+            let _ = CONFIG.set(config);
+        }
+        Err(problems) => {
+            eprintln!("Invalid configuration:");
+            for problem in problems {
+                eprintln!("  - {}", problem);
+            }
+            process::exit(1);
+        }
+    }
+}
+
+// 검증한 설정을 전역 설정으로 둔다. 한 번만 정할 수 있다.
+pub fn install(config: Config) -> Result<(), Vec<String>> {
+    let problems = config.validate();
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    CONFIG.set(config).map_err(|_| vec!["the configuration is already installed".to_string()])
+}
+
+pub fn is_installed() -> bool {
+    CONFIG.get().is_some()
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get().expect("config::init has not been called")
+}
@@ -0,0 +1,287 @@
+// --- 열려 있는 웹소켓 연결 목록 ---
+//
+// 세션 폐기나 강제 로그아웃처럼 연결 바깥에서 특정 소켓을 닫아야 할 때, 또는 방 전체가 아니라 특정 사용자의
+// 소켓에만 이벤트를 보내야 할 때 사용한다. 사용자가 접속해 있는지(친구 접속 상태)도 이 목록으로 판단한다.
+// 서버를 여러 대 띄우면 다른 서버에 열린 연결도 접속으로 친다(`cluster` 가 주기적으로 채운다). 소켓을 닫으면
+// 다른 서버에도 알려 그 서버의 연결도 닫는다(서버 종료는 제외). 이벤트를 보내는 것은 이 서버의 연결에만 한다.
+//
+// 사용자별, IP 별 연결 수 제한(`ConnectionLimits`)도 여기서 센다. 업그레이드 전에 자리를 잡고 연결이 끝나면
+// 돌려주므로, 한 클라이언트가 연결을 잔뜩 열어 방의 구독자 자리를 채우지 못한다.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use once_cell::sync::Lazy;
+use tokio::sync::mpsc;
+
+use crate::{
+    cluster::{ClusterEvent, Relay},
+    config,
+    protocol::ServerEvent,
+    ws::CloseReason,
+};
+
+static MAX_PER_USER: Lazy<usize> = Lazy::new(|| config::get().limits.ws_max_connections_per_user);
+static MAX_PER_IP: Lazy<usize> = Lazy::new(|| config::get().limits.ws_max_connections_per_ip);
+
+struct ConnectionHandle {
+    user_id: i32,
+    session_id: String,
+    room: String,
+    close: mpsc::Sender<CloseReason>,
+    // 연결의 전송 대기열
+    out: mpsc::Sender<ServerEvent>,
+}
+
+// 다른 서버에 열린 연결: 사용자 → 방 → 연결 수
+pub type RemoteConnections = HashMap<i32, HashMap<String, usize>>;
+
+#[derive(Default)]
+pub struct ConnectionRegistry {
+    next_id: AtomicU64,
+    connections: Mutex<HashMap<u64, ConnectionHandle>>,
+    remote: Mutex<RemoteConnections>,
+    relay: Relay,
+}
+
+impl ConnectionRegistry {
+    // 새 연결의 id. 로그에 남기고 register 와 unregister 에 쓴다.
+    pub fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // 연결을 등록하고, 이 사용자의 첫 연결인지를 돌려준다
+    pub fn register(
+        &self,
+        id: u64,
+        user_id: i32,
+        session_id: &str,
+        room: &str,
+        close: mpsc::Sender<CloseReason>,
+        out: mpsc::Sender<ServerEvent>,
+    ) -> bool {
+        let mut connections = self.connections.lock().unwrap();
+        let first = !connections.values().any(|c| c.user_id == user_id) && !self.is_online_elsewhere(user_id);
+        connections.insert(
+            id,
+            ConnectionHandle {
+                user_id,
+                session_id: session_id.to_string(),
+                room: room.to_string(),
+                close,
+                out,
+            },
+        );
+        first
+    }
+
+    // 연결을 뺀다. 그 사용자의 마지막 연결이었으면 true.
+    pub fn unregister(&self, id: u64) -> bool {
+        let mut connections = self.connections.lock().unwrap();
+        match connections.remove(&id) {
+            Some(removed) => {
+                !connections.values().any(|c| c.user_id == removed.user_id)
+                    && !self.is_online_elsewhere(removed.user_id)
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_online(&self, user_id: i32) -> bool {
+        self.connections.lock().unwrap().values().any(|c| c.user_id == user_id) || self.is_online_elsewhere(user_id)
+    }
+
+    pub fn is_in_room(&self, user_id: i32, room: &str) -> bool {
+        self.connections.lock().unwrap().values().any(|c| c.user_id == user_id && c.room == room)
+            || self.remote.lock().unwrap().get(&user_id).is_some_and(|rooms| rooms.contains_key(room))
+    }
+
+    fn is_online_elsewhere(&self, user_id: i32) -> bool {
+        self.remote.lock().unwrap().contains_key(&user_id)
+    }
+
+    // 이 서버에 열린 연결 (cluster 가 다른 서버에 알린다)
+    pub fn local_connections(&self) -> RemoteConnections {
+        let mut counts = RemoteConnections::new();
+        for connection in self.connections.lock().unwrap().values() {
+            *counts.entry(connection.user_id).or_default().entry(connection.room.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn set_remote(&self, remote: RemoteConnections) {
+        *self.remote.lock().unwrap() = remote;
+    }
+
+    // 다른 서버에 종료를 알릴 대기열 (Redis 버스가 연결한다)
+    pub fn relay_to(&self, tx: mpsc::Sender<ClusterEvent>) {
+        self.relay.connect(tx);
+    }
+
+    // 해당 세션으로 열린 모든 소켓에 종료를 요청한다. 이 서버에서 닫은 연결 수를 돌려준다.
+    pub fn close_session(&self, session_id: &str, reason: CloseReason) -> usize {
+        self.close(ClusterEvent::CloseSession { session_id: session_id.to_string(), reason })
+    }
+
+    // 사용자의 모든 소켓에 종료를 요청한다 (세션과 관계없이)
+    pub fn close_user(&self, user_id: i32, reason: CloseReason) -> usize {
+        self.close(ClusterEvent::CloseUser { user_id, reason })
+    }
+
+    // 사용자가 room 에 연 소켓에만 종료를 요청한다
+    pub fn close_user_in_room(&self, user_id: i32, room: &str, reason: CloseReason) -> usize {
+        self.close(ClusterEvent::CloseUserInRoom { user_id, room: room.to_string(), reason })
+    }
+
+    // 방에 열린 모든 소켓에 종료를 요청한다
+    pub fn close_room(&self, room: &str, reason: CloseReason) -> usize {
+        self.close(ClusterEvent::CloseRoom { room: room.to_string(), reason })
+    }
+
+    fn close(&self, event: ClusterEvent) -> usize {
+        let closed = self.apply(&event);
+        self.relay.send(event);
+        closed
+    }
+
+    // 종료를 이 서버의 연결에만 적용한다. 다른 서버가 알린 이벤트도 여기로 온다.
+    pub fn apply(&self, event: &ClusterEvent) -> usize {
+        match event {
+            ClusterEvent::CloseSession { session_id, reason } => {
+                self.close_matching(|c| &c.session_id == session_id, *reason)
+            }
+            ClusterEvent::CloseUser { user_id, reason } => self.close_matching(|c| c.user_id == *user_id, *reason),
+            ClusterEvent::CloseUserInRoom { user_id, room, reason } => {
+                self.close_matching(|c| c.user_id == *user_id && &c.room == room, *reason)
+            }
+            ClusterEvent::CloseRoom { room, reason } => self.close_matching(|c| &c.room == room, *reason),
+            ClusterEvent::RevokeToken { .. } | ClusterEvent::RevokeSession { .. } => 0,
+        }
+    }
+
+    // 이 서버의 모든 소켓에 종료를 요청한다 (서버 종료)
+    pub fn close_all(&self, reason: CloseReason) -> usize {
+        self.close_matching(|_| true, reason)
+    }
+
+    // 이 서버에 열린 연결이 없는지
+    pub fn is_empty(&self) -> bool {
+        self.connections.lock().unwrap().is_empty()
+    }
+
+    // 사용자별로 열려 있는 소켓 수 (다른 서버 포함)
+    pub fn counts_by_user(&self) -> HashMap<i32, usize> {
+        let mut counts = HashMap::new();
+        for connection in self.connections.lock().unwrap().values() {
+            *counts.entry(connection.user_id).or_insert(0) += 1;
+        }
+        for (user_id, rooms) in self.remote.lock().unwrap().iter() {
+            *counts.entry(*user_id).or_insert(0) += rooms.values().sum::<usize>();
+        }
+        counts
+    }
+
+    // 사용자가 room 에 연 소켓에만 이벤트를 보낸다. 대기열이 가득 찬 연결은 건너뛴다.
+    pub fn send_to_user(&self, user_id: i32, room: &str, event: &ServerEvent) -> usize {
+        self.send_matching(|c| c.user_id == user_id && c.room == room, event)
+    }
+
+    // 여러 사용자의 모든 소켓에 방과 관계없이 이벤트를 보낸다
+    pub fn send_to_users(&self, user_ids: &[i32], event: &ServerEvent) -> usize {
+        self.send_matching(|c| user_ids.contains(&c.user_id), event)
+    }
+
+    fn send_matching(&self, matches: impl Fn(&ConnectionHandle) -> bool, event: &ServerEvent) -> usize {
+        let connections = self.connections.lock().unwrap();
+        let mut sent = 0;
+        for connection in connections.values().filter(|c| matches(c)) {
+            if connection.out.try_send(event.clone()).is_ok() {
+                sent += 1;
+            }
+        }
+        sent
+    }
+
+    fn close_matching(&self, matches: impl Fn(&ConnectionHandle) -> bool, reason: CloseReason) -> usize {
+        let connections = self.connections.lock().unwrap();
+        let mut closed = 0;
+        for connection in connections.values().filter(|c| matches(c)) {
+            let _ = connection.close.try_send(reason);
+            closed += 1;
+        }
+        closed
+    }
+}
+
+// --- 연결 수 제한 ---
+
+pub enum LimitExceeded {
+    User(usize),
+    Ip(usize),
+}
+
+#[derive(Default)]
+struct Counts {
+    users: HashMap<i32, usize>,
+    ips: HashMap<IpAddr, usize>,
+}
+
+#[derive(Default)]
+pub struct ConnectionLimits {
+    counts: Mutex<Counts>,
+}
+
+impl ConnectionLimits {
+    // 자리가 있으면 하나 잡는다. 돌려받은 ConnectionSlot 이 사라질 때 자리가 풀린다.
+    pub fn acquire(self: &Arc<Self>, user_id: i32, ip: IpAddr) -> Result<ConnectionSlot, LimitExceeded> {
+        let mut counts = self.counts.lock().unwrap();
+        if counts.users.get(&user_id).is_some_and(|&n| n >= *MAX_PER_USER) {
+            return Err(LimitExceeded::User(*MAX_PER_USER));
+        }
+        if counts.ips.get(&ip).is_some_and(|&n| n >= *MAX_PER_IP) {
+            return Err(LimitExceeded::Ip(*MAX_PER_IP));
+        }
+        *counts.users.entry(user_id).or_insert(0) += 1;
+        *counts.ips.entry(ip).or_insert(0) += 1;
+        Ok(ConnectionSlot { limits: self.clone(), user_id, ip })
+    }
+
+    // 자리를 잡은 연결이 없는지. 연결은 정리를 모두 마친 뒤에 자리를 돌려준다.
+    pub fn is_empty(&self) -> bool {
+        self.counts.lock().unwrap().users.is_empty()
+    }
+
+    fn release(&self, user_id: i32, ip: IpAddr) {
+        let mut counts = self.counts.lock().unwrap();
+        if let Some(n) = counts.users.get_mut(&user_id) {
+            *n -= 1;
+            if *n == 0 {
+                counts.users.remove(&user_id);
+            }
+        }
+        if let Some(n) = counts.ips.get_mut(&ip) {
+            *n -= 1;
+            if *n == 0 {
+                counts.ips.remove(&ip);
+            }
+        }
+    }
+}
+
+// 연결 하나가 차지한 자리. 연결이 끝날 때까지 들고 있는다.
+pub struct ConnectionSlot {
+    limits: Arc<ConnectionLimits>,
+    user_id: i32,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.limits.release(self.user_id, self.ip);
+    }
+}
@@ -0,0 +1,50 @@
+// --- CORS ---
+//
+// 프론트엔드를 API 와 다른 출처(예: https://app.example.com 에서 https://api.example.com 호출)에 두면 브라우저가
+// 응답을 막는다. cors.allowed_origins (CORS_ALLOWED_ORIGINS, 쉼표로 구분) 에 든 출처에만 CORS 헤더를 보내고,
+// 사전 요청(OPTIONS)은 라우터에 닿기 전에 여기서 답한다. 설정하지 않으면 이 레이어를 걸지 않는다(같은 출처만).
+//
+// 요청 헤더는 브라우저가 묻는 대로 허용하고, 응답 헤더 중 x-request-id, Retry-After, ETag 를 스크립트에 보인다.
+// allow_credentials 를 켜면 쿠키를 함께 보낼 수 있지만 세션 쿠키는 SameSite 라서 다른 사이트의 요청에는
+// 실리지 않는다. 다른 사이트의 클라이언트는 /login 이 돌려준 토큰을 Authorization: Bearer 로 보내면 된다.
+
+use axum::http::{header, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+
+use crate::{config, request_id};
+
+// `scheme://host[:port]` 꼴의 출처인지 확인한다. 브라우저가 보내는 Origin 헤더와 글자 그대로 비교하므로
+// 경로나 끝의 `/` 가 붙으면 절대 일치하지 않는다.
+pub fn parse_origin(origin: &str) -> Result<HeaderValue, String> {
+    let invalid = || format!("'{}' is not an origin like https://app.example.com", origin);
+    let (scheme, host) = origin.split_once("://").ok_or_else(invalid)?;
+    if !matches!(scheme, "http" | "https") || host.is_empty() || host.contains(['/', '?', '#']) {
+        return Err(invalid());
+    }
+    HeaderValue::from_str(origin).map_err(|_| invalid())
+}
+
+// 허용한 출처가 없으면 None
+pub fn layer() -> Option<CorsLayer> {
+    let cors = &config::get().cors;
+    if cors.allowed_origins.is_empty() {
+        return None;
+    }
+    let origins = if cors.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        // 설정을 읽을 때 검증했다
+        AllowOrigin::list(cors.allowed_origins.iter().filter_map(|origin| parse_origin(origin).ok()))
+    };
+    let methods: Vec<Method> = cors.allowed_methods.iter().filter_map(|method| method.parse().ok()).collect();
+    Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(AllowHeaders::mirror_request())
+            .allow_credentials(cors.allow_credentials)
+            .expose_headers([request_id::HEADER.clone(), header::RETRY_AFTER, header::ETAG])
+            .max_age(Duration::from_secs(cors.max_age_seconds)),
+    )
+}
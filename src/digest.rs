@@ -0,0 +1,130 @@
+// --- 메일 요약 ---
+//
+// EMAIL_DIGEST=true 이면 백그라운드 작업이 읽지 않은 언급을 모아 메일로 보낸다. 언급이 생긴 뒤
+// EMAIL_DIGEST_AFTER_MINUTES 동안 사용자가 한 번도 접속하지 않았고 아직 읽지 않았을 때만 넣는다. 접속해
+// 있던 동안 받은 언급은 이미 `notification` 이벤트로 보았으므로 넣지 않는다. 한 번 요약에 넣은 알림은 다시
+// 보내지 않는다.
+//
+// 메일 인증을 마친 주소로만 보내고, 사용자는 `PUT /me/email-digest` 로 끌 수 있다.
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{
+    auth::AuthUser,
+    config,
+    error::{ApiError, ApiJson},
+    repo::{DigestMention, RepoResult},
+    AppState, PUBLIC_URL,
+};
+
+static ENABLED: Lazy<bool> = Lazy::new(|| config::get().features.email_digest);
+static AFTER_MINUTES: Lazy<i64> = Lazy::new(|| config::get().notifications.email_digest_after_minutes);
+static CHECK_INTERVAL: Lazy<Duration> =
+    Lazy::new(|| Duration::from_secs(config::get().notifications.email_digest_check_seconds));
+
+// 메일 하나에 적는 최대 언급 수
+const ITEMS_MAX: usize = 20;
+
+// 요약을 보낼 때가 된 사용자마다 메일을 보내는 백그라운드 태스크. EMAIL_DIGEST 가 꺼져 있으면 띄우지 않는다.
+pub fn spawn_task(state: AppState) {
+    if !*ENABLED {
+        return;
+    }
+    tracing::info!("Email digests enabled after {} minutes offline", *AFTER_MINUTES);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(*CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = send_digests(&state).await {
+                tracing::warn!("Failed to send email digests: {}", e);
+            }
+        }
+    });
+}
+
+async fn send_digests(state: &AppState) -> RepoResult<()> {
+    let created_before = Utc::now() - chrono::Duration::minutes(*AFTER_MINUTES);
+    let mentions = state.repos.notifications.pending_digest(created_before).await?;
+
+    for mentions in mentions.chunk_by(|a, b| a.user_id == b.user_id) {
+        let user_id = mentions[0].user_id;
+        // 지금 접속해 있으면 알림을 곧 볼 것이다
+        if state.connections.is_online(user_id) {
+            continue;
+        }
+        // 보내기 전에 기록해, 메일 발송이 실패해도 같은 요약을 되풀이하지 않는다
+        let latest = mentions.iter().map(|m| m.created_at).max().unwrap_or_else(Utc::now);
+        state.repos.notifications.mark_digest_sent(user_id, latest).await?;
+
+        let subject = if mentions.len() == 1 {
+            "You have an unread mention on WebChat".to_string()
+        } else {
+            format!("You have {} unread mentions on WebChat", mentions.len())
+        };
+        if let Err(e) = state.mailer.send(&mentions[0].email, &subject, body(mentions)).await {
+            tracing::warn!("Failed to send email digest to user {}: {}", user_id, e);
+            continue;
+        }
+        tracing::info!("Sent email digest of {} mentions to user {}", mentions.len(), user_id);
+    }
+    Ok(())
+}
+
+fn body(mentions: &[DigestMention]) -> String {
+    let mut body = String::from("While you were away, people mentioned you on WebChat:\n\n");
+    for mention in mentions.iter().take(ITEMS_MAX) {
+        body.push_str(&format!(
+            "#{} - {} ({}):\n  {}\n\n",
+            mention.room.as_deref().unwrap_or("?"),
+            mention.actor.as_deref().unwrap_or("someone"),
+            mention.created_at.format("%Y-%m-%d %H:%M UTC"),
+            mention.text.replace('\n', "\n  ")
+        ));
+    }
+    if mentions.len() > ITEMS_MAX {
+        body.push_str(&format!("...and {} more.\n\n", mentions.len() - ITEMS_MAX));
+    }
+    body.push_str(&format!(
+        "Open WebChat to reply: {}\n\nYou can turn off these emails in your notification settings.",
+        *PUBLIC_URL
+    ));
+    body
+}
+
+// --- 설정 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct DigestPayload {
+    enabled: bool,
+}
+
+// GET /me/email-digest
+pub async fn get_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.notifications.digest_enabled(claims.user_id).await {
+        Ok(Some(enabled)) => Json(json!({ "enabled": enabled })).into_response(),
+        Ok(None) => ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PUT /me/email-digest: 끄는 동안 온 언급은 다시 켜도 보내지 않는다
+pub async fn put_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<DigestPayload>,
+) -> Response {
+    match state.repos.notifications.set_digest(claims.user_id, payload.enabled).await {
+        Ok(true) => Json(json!({ "enabled": payload.enabled })).into_response(),
+        Ok(false) => ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
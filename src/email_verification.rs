@@ -0,0 +1,113 @@
+// --- 메일 주소 확인 ---
+//
+// 가입 시(또는 주소 변경 시) 확인 링크를 보내고, 확인되지 않은 계정은 채팅방에 글을 쓸 수 없다.
+// 로그인과 읽기는 막지 않으므로 확인 메일을 다시 요청할 수 있다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{
+    auth::{generate_token, hash_token, AuthUser},
+    config,
+    error::{ApiError, ApiJson},
+    repo::{RepoError, RepoResult},
+    validation::{self, ValidationErrors},
+    AppState, PUBLIC_URL,
+};
+
+// 확인 링크의 유효 시간
+static EMAIL_VERIFICATION_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::hours(config::get().auth.email_verification_ttl_hours));
+
+// false 로 두면 메일 주소는 선택 사항이 되고 확인 여부와 관계없이 글을 쓸 수 있다
+pub static REQUIRE_VERIFIED_EMAIL: Lazy<bool> = Lazy::new(|| config::get().features.require_verified_email);
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeEmailPayload {
+    email: String,
+}
+
+// 확인 토큰을 만들고 메일을 보낸다
+pub async fn send_verification(state: &AppState, user_id: i32, email: &str) -> RepoResult<()> {
+    let token = generate_token();
+    let expires_at = chrono::Utc::now() + *EMAIL_VERIFICATION_TTL;
+    state.repos.tokens.add_email_verification(user_id, email, &hash_token(&token), expires_at).await?;
+
+    let link = format!("{}/static/verify.html#token={}", *PUBLIC_URL, token);
+    let body = format!(
+        "Welcome to WebChat!\n\nPlease confirm your email address by opening this link within {} hours:\n{}\n\n\
+         If you didn't create an account, you can ignore this email.",
+        EMAIL_VERIFICATION_TTL.num_hours(),
+        link
+    );
+    state.mailer.send_in_background(email.to_string(), "Confirm your WebChat email address", body);
+    Ok(())
+}
+
+// 글쓰기가 허용되는지 확인한다 (확인이 필요 없게 설정됐으면 항상 true, 메일이 없는 봇도 true)
+pub async fn can_post(state: &AppState, user_id: i32) -> bool {
+    if !*REQUIRE_VERIFIED_EMAIL {
+        return true;
+    }
+    state.repos.accounts.is_verified(user_id).await.unwrap_or(false)
+}
+
+// POST /verify/:token
+pub async fn verify_handler(State(state): State<AppState>, Path(token): Path<String>) -> Response {
+    match state.repos.tokens.use_email_verification(&hash_token(&token)).await {
+        Ok(Some(user_id)) => {
+            tracing::info!("User {} verified their email address", user_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(None) => ApiError::bad_request("invalid_verification_token", "Invalid or expired verification token").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PUT /me/email: 주소를 바꾸고 (확인 상태는 초기화) 새 주소로 확인 메일을 보낸다
+pub async fn change_email_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<ChangeEmailPayload>,
+) -> Response {
+    let email = payload.email.trim();
+    let mut errors = ValidationErrors::default();
+    validation::check_email(email, &mut errors);
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    match state.repos.accounts.set_email(claims.user_id, email).await {
+        Ok(()) => {}
+        Err(RepoError::Conflict(_)) => {
+            return ValidationErrors::single("email", "taken", "Email is already in use").into_response()
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    match send_verification(&state, claims.user_id, email).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/email/verify: 확인 메일 재전송
+pub async fn resend_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let email = match state.repos.accounts.email_status(claims.user_id).await {
+        Ok(Some((Some(email), false))) => email,
+        Ok(Some((Some(_), true))) => return ApiError::conflict("email_already_verified", "Email is already verified").into_response(),
+        Ok(Some((None, _))) => return ApiError::bad_request("no_email", "No email address on this account").into_response(),
+        Ok(None) => return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    match send_verification(&state, claims.user_id, &email).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
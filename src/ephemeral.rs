@@ -0,0 +1,80 @@
+// --- 자동으로 사라지는 메시지 ---
+//
+// `message` 이벤트에 `ttl_seconds` 를 주면 메시지에 만료 시각(`expires_at`)이 붙는다. 정리 태스크는 가장 이른
+// 만료 시각까지 잠들었다가 시각이 지난 메시지를 지우고, 방에 `message_expired` 이벤트를 보내 클라이언트가
+// 화면에서 지우게 한다. 그 메시지를 가리키는 알림도 함께 지운다.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::{config, protocol::ServerEvent, repo::RepoResult, AppState};
+
+// 가장 긴 수명 (기본 7일)
+pub static EPHEMERAL_MAX_SECONDS: Lazy<u64> = Lazy::new(|| config::get().limits.ephemeral_max_seconds);
+
+// 만료될 메시지가 없어도 이만큼마다 한 번은 확인한다
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// 한 번에 지우는 최대 수
+const SWEEP_BATCH: i64 = 500;
+
+// 정리 태스크를 깨우는 신호
+#[derive(Default)]
+pub struct Sweeper {
+    wake: Notify,
+}
+
+impl Sweeper {
+    // 만료 시각이 있는 메시지를 저장한 뒤 부른다
+    pub fn notify(&self) {
+        self.wake.notify_one();
+    }
+}
+
+// 1초 이상, EPHEMERAL_MAX_SECONDS 이하의 수명이면 만료 시각을 돌려준다
+pub fn expires_at(ttl_seconds: u64) -> Option<DateTime<Utc>> {
+    if ttl_seconds == 0 || ttl_seconds > *EPHEMERAL_MAX_SECONDS {
+        return None;
+    }
+    Some(Utc::now() + chrono::Duration::seconds(ttl_seconds as i64))
+}
+
+pub fn spawn_task(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            let next = match sweep(&state).await {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::warn!("Failed to remove expired messages: {}", e);
+                    None
+                }
+            };
+            let wait = next
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(IDLE_CHECK_INTERVAL, |wait| wait.min(IDLE_CHECK_INTERVAL));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.ephemeral.wake.notified() => {}
+            }
+        }
+    });
+}
+
+// 만료된 메시지를 지우고 방에 알린 뒤, 다음 만료 시각을 돌려준다
+async fn sweep(state: &AppState) -> RepoResult<Option<DateTime<Utc>>> {
+    loop {
+        let expired = state.repos.messages.expire_due(SWEEP_BATCH).await?;
+        for (message_id, room) in &expired {
+            let event = ServerEvent::MessageExpired { room: room.clone(), message_id: *message_id };
+            state.bus.publish(room, event);
+        }
+        if !expired.is_empty() {
+            tracing::debug!("Removed {} expired messages", expired.len());
+        }
+        if (expired.len() as i64) < SWEEP_BATCH {
+            break;
+        }
+    }
+    state.repos.messages.next_expiry().await
+}
@@ -0,0 +1,145 @@
+// --- API 오류 응답 ---
+//
+// 모든 REST 오류는 `{"code": "...", "message": "..."}` 형태의 JSON 으로 돌려준다. `code` 는 클라이언트가
+// 분기할 수 있는 고정된 문자열이고, `message` 는 사람이 읽는 설명이다. 내부 오류(DB 등)의 상세 내용은
+// 로그에만 남기고 응답에는 싣지 않는다. 문의할 때 로그를 찾을 수 있도록 요청 id(`request_id`)를 함께 싣는다.
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::borrow::Cow;
+
+use crate::{auth::AuthError, monitoring, request_id, validation::ValidationErrors};
+
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: Cow<'static, str>,
+    // 검증 실패일 때의 필드별 오류
+    fields: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self { status, code, message: message.into(), fields: None }
+    }
+
+    pub fn bad_request(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code, message)
+    }
+
+    pub fn unauthorized(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, code, message)
+    }
+
+    pub fn forbidden(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, code, message)
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, message)
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(StatusCode::CONFLICT, code, message)
+    }
+
+    // 원인은 호출한 쪽에서 로그로 남긴다
+    pub fn internal() -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "Internal server error")
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    // 웹소켓 `error` 이벤트로 돌려줄 때 쓴다
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status == StatusCode::UNAUTHORIZED {
+            monitoring::auth_failure(self.code);
+        }
+        let mut body = json!({ "code": self.code, "message": self.message });
+        if let Some(fields) = self.fields {
+            body["fields"] = fields;
+        }
+        if let Some(request_id) = request_id::current() {
+            body["request_id"] = json!(request_id);
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(e: AuthError) -> Self {
+        match e {
+            AuthError::Scope => Self::forbidden(e.code(), e.message()),
+            _ => Self::unauthorized(e.code(), e.message()),
+        }
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        let status = if errors.has_code("taken") {
+            StatusCode::CONFLICT
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        Self {
+            status,
+            code: "validation_failed",
+            message: "Some fields are invalid".into(),
+            fields: Some(errors.to_json()),
+        }
+    }
+}
+
+// axum::Json 과 같지만, 본문을 읽지 못하면 (형식 오류, 필드 누락, Content-Type 불일치, 크기 초과) ApiError 로 응답한다
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(rejection) if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                Err(ApiError::new(rejection.status(), "body_too_large", "The request body is too large"))
+            }
+            Err(rejection) => Err(ApiError::new(rejection.status(), "invalid_body", rejection.body_text())),
+        }
+    }
+}
+
+// 등록되지 않은 경로
+pub async fn not_found_handler() -> ApiError {
+    ApiError::not_found("not_found", "Not found")
+}
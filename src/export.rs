@@ -0,0 +1,147 @@
+// --- 개인 데이터 내보내기 ---
+//
+// `POST /me/export` 가 작업을 만들고 백그라운드 태스크가 JSON 아카이브를 만든다. 클라이언트는
+// `GET /me/export/:id` 로 상태를 확인하고, ready 가 되면 `GET /me/export/:id/download` 로 받는다.
+// 아카이브는 EXPORT_TTL_HOURS 동안만 보관한다.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+use crate::{
+    auth::{generate_token, AuthUser},
+    config,
+    error::ApiError,
+    mail::Mailer,
+    repo::{PersonalData, Repos},
+    AppState,
+};
+
+static EXPORT_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::hours(config::get().retention.export_ttl_hours));
+
+const READY: &str = "ready";
+const FAILED: &str = "failed";
+
+// POST /me/export: 진행 중인 작업이 있으면 새로 만들지 않고 그 작업을 돌려준다
+pub async fn request_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    if let Err(e) = state.repos.data_exports.purge_expired().await {
+        return ApiError::from(e).into_response();
+    }
+
+    match state.repos.data_exports.pending(claims.user_id).await {
+        Ok(Some(export)) => return (StatusCode::ACCEPTED, Json(export)).into_response(),
+        Ok(None) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let id = generate_token();
+    let created = state.repos.data_exports.create(&id, claims.user_id, chrono::Utc::now() + *EXPORT_TTL).await;
+    let export = match created {
+        Ok(export) => export,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    tokio::spawn(run_export(state.repos.clone(), state.mailer.clone(), id, claims.user_id));
+    (StatusCode::ACCEPTED, Json(export)).into_response()
+}
+
+// GET /me/export: 내 내보내기 작업 목록
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.data_exports.list(claims.user_id).await {
+        Ok(exports) => Json(exports).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /me/export/:id
+pub async fn status_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    match state.repos.data_exports.find(&id, claims.user_id).await {
+        Ok(Some(export)) => Json(export).into_response(),
+        Ok(None) => ApiError::not_found("export_not_found", "Export not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /me/export/:id/download
+pub async fn download_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<String>,
+) -> Response {
+    let export = state.repos.data_exports.archive(&id, claims.user_id).await;
+    match export {
+        Ok(Some((status, Some(archive)))) if status == READY => {
+            let filename = format!("attachment; filename=\"webchat-export-{}.json\"", claims.sub);
+            (
+                [
+                    (header::CONTENT_TYPE, "application/json".to_string()),
+                    (header::CONTENT_DISPOSITION, filename),
+                ],
+                archive,
+            )
+                .into_response()
+        }
+        Ok(Some(_)) => ApiError::conflict("export_not_ready", "Export is not ready yet").into_response(),
+        Ok(None) => ApiError::not_found("export_not_found", "Export not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+async fn run_export(repos: Repos, mailer: std::sync::Arc<Mailer>, id: String, user_id: i32) {
+    let result = match repos.data_exports.personal_data(user_id).await {
+        Ok(data) => {
+            let archive = build_archive(user_id, data).to_string();
+            repos.data_exports.finish(&id, READY, Some(&archive)).await
+        }
+        Err(e) => {
+            tracing::error!("Data export {} for user {} failed: {}", id, user_id, e);
+            repos.data_exports.finish(&id, FAILED, None).await
+        }
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to store data export {}: {}", id, e);
+        return;
+    }
+    tracing::info!("Data export {} for user {} finished", id, user_id);
+
+    if let Ok(Some((Some(email), _))) = repos.accounts.email_status(user_id).await {
+        let body = format!(
+            "Your WebChat data export has finished. Download it from your account within {} hours.",
+            EXPORT_TTL.num_hours()
+        );
+        mailer.send_in_background(email, "Your WebChat data export", body);
+    }
+}
+
+// 내려받는 JSON 아카이브
+fn build_archive(user_id: i32, data: PersonalData) -> Value {
+    json!({
+        "exported_at": chrono::Utc::now(),
+        "profile": {
+            "id": user_id,
+            "username": data.username,
+            "display_name": data.display_name,
+            "bio": data.bio,
+            "pronouns": data.pronouns,
+            "email": data.email,
+            "email_verified_at": data.email_verified_at,
+            "created_at": data.created_at,
+            "two_factor_enabled": data.two_factor,
+        },
+        "passkeys": data.passkeys,
+        "linked_accounts": data.linked_accounts,
+        "sessions": data.sessions,
+        "rooms": data.rooms,
+        "messages": data.messages,
+    })
+}
@@ -0,0 +1,96 @@
+// --- 메시지 전달 ---
+//
+// `POST /messages/:id/forward` 는 볼 수 있는 메시지를 다른 방에 인용한 사본으로 다시 올린다. 사본은 전달한
+// 사람이 쓴 메시지로 저장되고, `forwarded` 에 원래 메시지의 방, 작성자와 시각이 남는다. 전달한 메시지를 다시
+// 전달하면 맨 처음 작성자를 그대로 가리킨다.
+//
+// 받는 방에는 그 방에 글을 쓸 때와 같은 조건(게스트, 메일 확인, 음소거, 금칙어)이 적용된다. 수명이 정해진
+// 메시지는 사본이 남지 않도록 전달할 수 없다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    auth::AuthUser,
+    error::{ApiError, ApiJson},
+    history,
+    markdown::MessageFormat,
+    messages::{self, Author, Draft, Forwarded},
+    profile, word_filter, AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ForwardPayload {
+    room: String,
+}
+
+// POST /messages/:id/forward: 메시지를 room 에 전달한다
+pub async fn forward_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+    ApiJson(payload): ApiJson<ForwardPayload>,
+) -> Response {
+    let source = match state.repos.messages.find_visible(claims.user_id, id).await {
+        Ok(Some(source)) => source,
+        Ok(None) => return ApiError::not_found("message_not_found", "Message not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let Some(source_room) = source.room else {
+        return ApiError::not_found("message_not_found", "Message not found").into_response();
+    };
+    if let Err(e) = history::check_can_read(&claims, &source_room) {
+        return e.into_response();
+    }
+    if source.expires_at.is_some() {
+        return ApiError::bad_request("cannot_forward_ephemeral", "Disappearing messages cannot be forwarded")
+            .into_response();
+    }
+    let text = source.text.unwrap_or_default();
+    if text.trim().is_empty() {
+        return ApiError::bad_request("nothing_to_forward", "Only text messages can be forwarded").into_response();
+    }
+    let room = payload.room.trim();
+    if room.is_empty() {
+        return ApiError::bad_request("room_required", "room is required").into_response();
+    }
+
+    let shadow_banned = match messages::check_can_post(&state, &claims, room).await {
+        Ok(shadow_banned) => shadow_banned,
+        Err(e) => return e.into_response(),
+    };
+    let text = match state.word_filter.check(room, &text) {
+        word_filter::Verdict::Allow(text) => text,
+        word_filter::Verdict::Reject => {
+            return ApiError::bad_request("message_rejected", "Message contains a blocked word").into_response()
+        }
+    };
+
+    let forwarded = source.forwarded.unwrap_or_else(|| Forwarded {
+        message_id: source.id,
+        room: source_room.clone(),
+        user_id: source.user_id,
+        username: source.username.unwrap_or_default(),
+        sent_at: source.created_at.timestamp_millis(),
+    });
+    let identity = profile::chat_identity(&state, claims.user_id, &claims.sub).await;
+    let author = Author {
+        user_id: claims.user_id,
+        username: claims.sub.clone(),
+        display_name: identity.display_name,
+        avatar_url: identity.avatar_url,
+        bot: claims.is_bot(),
+    };
+    let draft = Draft { forwarded: Some(forwarded), ..Draft::new(text, MessageFormat::parse(&source.format)) };
+    let Some(message_id) = messages::send(&state, room, &author, draft, shadow_banned).await else {
+        return ApiError::internal().into_response();
+    };
+    tracing::info!("User {} forwarded message {} from '{}' to '{}'", claims.user_id, id, source_room, room);
+    (StatusCode::CREATED, Json(json!({ "message_id": message_id, "room": room }))).into_response()
+}
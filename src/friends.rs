@@ -0,0 +1,200 @@
+// --- 친구와 접속 상태 ---
+//
+// `POST /me/friends` 에 사용자 이름을 보내 친구 신청을 하고, 상대가 `POST /me/friends/:id/accept` 로 수락하면
+// 친구가 된다. 상대가 이미 나에게 신청해 두었다면 바로 친구가 된다. `DELETE /me/friends/:id` 는 친구 끊기,
+// 신청 거절, 신청 취소를 모두 맡는다.
+//
+// 친구의 접속 상태는 `presence` 모듈이 알린다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    auth::{AuthUser, Claims},
+    error::{ApiError, ApiJson},
+    notifications::{self, Notice},
+    presence,
+    repo::{RepoError, User},
+    validation, AppState,
+};
+
+// 신청한 사람에게 수락되었음을 알린다
+async fn notify_accepted(state: &AppState, claims: &Claims, requester_id: i32) {
+    let notice = Notice {
+        kind: "friend_accepted",
+        room: None,
+        message_id: None,
+        actor: Some((claims.user_id, &claims.sub)),
+        text: format!("{} accepted your friend request", claims.sub),
+    };
+    notifications::create(state, requester_id, notice).await;
+}
+
+fn reject_guest(claims: &Claims) -> Result<(), ApiError> {
+    if claims.guest {
+        return Err(ApiError::forbidden("guest_friends_forbidden", "Guests cannot add friends"));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct Contact {
+    user_id: i32,
+    username: String,
+    display_name: Option<String>,
+    // 친구일 때만: "online", "away", "busy", "offline"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status_message: Option<String>,
+    // 친구가 된 시각, 신청이면 신청한 시각
+    since: DateTime<Utc>,
+}
+
+#[derive(Serialize, Default)]
+pub struct Contacts {
+    friends: Vec<Contact>,
+    // 나에게 온 신청
+    incoming: Vec<Contact>,
+    // 내가 보낸 신청
+    outgoing: Vec<Contact>,
+}
+
+// GET /me/friends
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let rows = match state.repos.friends.contacts(claims.user_id).await {
+        Ok(rows) => rows,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let mut contacts = Contacts::default();
+    for row in rows {
+        let mut contact = Contact {
+            user_id: row.user_id,
+            username: row.username,
+            display_name: row.display_name,
+            status: None,
+            status_message: None,
+            since: row.since,
+        };
+        if row.accepted {
+            let status = presence::visible_status(state.connections.is_online(row.user_id), &row.status);
+            contact.status = Some(status);
+            contact.status_message = row.status_message.filter(|_| status != "offline");
+            contacts.friends.push(contact);
+        } else if row.outgoing {
+            contacts.outgoing.push(contact);
+        } else {
+            contacts.incoming.push(contact);
+        }
+    }
+    Json(contacts).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FriendRequestPayload {
+    username: String,
+}
+
+// POST /me/friends: 상대가 먼저 신청했으면 수락한다
+pub async fn request_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<FriendRequestPayload>,
+) -> Response {
+    if let Err(e) = reject_guest(&claims) {
+        return e.into_response();
+    }
+    let target = state.repos.users.find_by_name(&validation::normalize_username(&payload.username)).await;
+    let (target_id, target_name) = match target {
+        Ok(Some(User { id, username, .. })) => (id, username),
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if target_id == claims.user_id {
+        return ApiError::bad_request("cannot_friend_self", "You cannot add yourself as a friend").into_response();
+    }
+
+    // 상대가 보낸 신청이 있으면 수락, 아니면 새 신청
+    match state.repos.friends.accept(target_id, claims.user_id).await {
+        Ok(Some(_)) => {
+            tracing::info!("User {} accepted the friend request of user {}", claims.user_id, target_id);
+            notify_accepted(&state, &claims, target_id).await;
+            presence::exchange(&state, (claims.user_id, &claims.sub), (target_id, &target_name)).await;
+            let body = json!({ "user_id": target_id, "username": target_name, "status": "accepted" });
+            return Json(body).into_response();
+        }
+        Ok(None) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    match state.repos.friends.request(claims.user_id, target_id).await {
+        Ok(_) => {
+            tracing::info!("User {} sent a friend request to user {}", claims.user_id, target_id);
+            let notice = Notice {
+                kind: "friend_request",
+                room: None,
+                message_id: None,
+                actor: Some((claims.user_id, &claims.sub)),
+                text: format!("{} sent you a friend request", claims.sub),
+            };
+            notifications::create(&state, target_id, notice).await;
+            let body = json!({ "user_id": target_id, "username": target_name, "status": "pending" });
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        // 둘 사이에 이미 관계가 있다
+        Err(RepoError::Conflict(_)) => {
+            match state.repos.friends.is_accepted(claims.user_id, target_id).await {
+                Ok(Some(true)) => ApiError::conflict("already_friends", "You are already friends").into_response(),
+                Ok(_) => ApiError::conflict("friend_request_exists", "A friend request is already pending")
+                    .into_response(),
+                Err(e) => ApiError::from(e).into_response(),
+            }
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/friends/:id/accept
+pub async fn accept_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(requester_id): Path<i32>,
+) -> Response {
+    if let Err(e) = reject_guest(&claims) {
+        return e.into_response();
+    }
+    match state.repos.friends.accept(requester_id, claims.user_id).await {
+        Ok(Some(username)) => {
+            tracing::info!("User {} accepted the friend request of user {}", claims.user_id, requester_id);
+            notify_accepted(&state, &claims, requester_id).await;
+            presence::exchange(&state, (claims.user_id, &claims.sub), (requester_id, &username)).await;
+            Json(json!({ "user_id": requester_id, "username": username, "status": "accepted" })).into_response()
+        }
+        Ok(None) => ApiError::not_found("friend_request_not_found", "Friend request not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/friends/:id: 친구 끊기, 받은 신청 거절, 보낸 신청 취소
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(friend_id): Path<i32>,
+) -> Response {
+    match state.repos.friends.delete(claims.user_id, friend_id).await {
+        Ok(true) => {
+            tracing::info!("User {} removed user {} from friends", claims.user_id, friend_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("friend_not_found", "Friend not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,192 @@
+// --- gRPC API ---
+//
+// 웹소켓 대신 타입이 정해진 RPC 를 쓰려는 백엔드 서비스용. GRPC_ADDR 을 설정하면 HTTP 서버와 함께 그 주소에
+// `webchat.v1.Chat` 서비스(proto/webchat.proto)를 연다. HTTP 와 같은 AppState 를 쓰므로 보낸 메시지와 방
+// 이벤트는 웹소켓 클라이언트와 그대로 주고받는다.
+//
+// 인증은 `authorization: Bearer <토큰>` 메타데이터로 하며 REST 와 같은 토큰(봇 토큰 포함)을 받는다. 오류는 REST 의
+// 상태 코드에 맞는 gRPC 상태로 돌려주고, REST 오류의 code 는 `error-code` 메타데이터에 담는다.
+
+use axum::http::StatusCode;
+use futures::{stream, Stream};
+use once_cell::sync::Lazy;
+use std::{net::SocketAddr, pin::Pin};
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+use tonic::{metadata::MetadataValue, transport::Server, Code, Request, Response, Status};
+
+use crate::{
+    admin,
+    auth::{self, AuthError, Claims},
+    config,
+    error::ApiError,
+    history,
+    markdown::MessageFormat,
+    messages::{self, Author, Posted},
+    monitoring,
+    profile,
+    protocol::ServerEvent,
+    shutdown, ws, AppState,
+};
+
+pub mod proto {
+    tonic::include_proto!("webchat.v1");
+}
+
+use proto::{
+    chat_server::{Chat, ChatServer},
+    CommandReply, DeleteRoomRequest, DeleteRoomResponse, ListRoomsRequest, ListRoomsResponse, RoomEvent,
+    SendMessageRequest, SendMessageResponse, StreamRoomRequest,
+};
+
+// 설정하지 않으면 gRPC 서버를 열지 않는다
+static GRPC_ADDR: Lazy<Option<SocketAddr>> = Lazy::new(|| config::get().server.grpc_addr);
+
+// 서버가 종료할 때는 열린 스트림을 끝내고 처리 중인 호출을 마친 뒤 태스크가 끝난다
+pub fn spawn_server(state: AppState) -> Option<JoinHandle<()>> {
+    let addr = (*GRPC_ADDR)?;
+    Some(tokio::spawn(async move {
+        tracing::info!("gRPC server listening on {}", addr);
+        let shutdown = state.shutdown.clone();
+        let service = ChatServer::new(ChatService { state });
+        let server = Server::builder()
+            .add_service(service)
+            .serve_with_shutdown(addr, async move { shutdown.wait().await });
+        if let Err(e) = server.await {
+            tracing::error!("gRPC server stopped: {}", e);
+        }
+    }))
+}
+
+fn status(e: ApiError) -> Status {
+    if e.status() == StatusCode::UNAUTHORIZED {
+        monitoring::auth_failure(e.code());
+    }
+    let code = match e.status().as_u16() {
+        400 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        409 => Code::AlreadyExists,
+        429 => Code::ResourceExhausted,
+        _ => Code::Internal,
+    };
+    let mut status = Status::new(code, e.message());
+    status.metadata_mut().insert("error-code", MetadataValue::from_static(e.code()));
+    status
+}
+
+// 요청 메타데이터의 토큰을 확인한다. scope 는 봇 토큰에 필요한 권한.
+async fn authenticate<T>(state: &AppState, request: &Request<T>, scope: &str) -> Result<Claims, Status> {
+    let token = request
+        .metadata()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| status(AuthError::Missing.into()))?;
+    let claims = auth::authenticate(state, token.trim()).await.map_err(|e| status(e.into()))?;
+    if !claims.allows(scope) {
+        return Err(status(AuthError::Scope.into()));
+    }
+    Ok(claims)
+}
+
+pub struct ChatService {
+    state: AppState,
+}
+
+type RoomEventStream = Pin<Box<dyn Stream<Item = Result<RoomEvent, Status>> + Send>>;
+
+fn room_event(event: &ServerEvent) -> RoomEvent {
+    let json = event.to_json();
+    let kind = serde_json::from_str::<serde_json::Value>(&json)
+        .ok()
+        .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+        .unwrap_or_default();
+    RoomEvent { r#type: kind, json }
+}
+
+#[tonic::async_trait]
+impl Chat for ChatService {
+    async fn send_message(
+        &self,
+        request: Request<SendMessageRequest>,
+    ) -> Result<Response<SendMessageResponse>, Status> {
+        let state = &self.state;
+        let claims = authenticate(state, &request, "write").await?;
+        let SendMessageRequest { room, text, format, ttl_seconds } = request.into_inner();
+        if text.trim().is_empty() {
+            return Err(Status::invalid_argument("text is required"));
+        }
+
+        let identity = profile::chat_identity(state, claims.user_id, &claims.sub).await;
+        let author = Author {
+            user_id: claims.user_id,
+            username: claims.sub.clone(),
+            display_name: identity.display_name,
+            avatar_url: identity.avatar_url,
+            bot: claims.is_bot(),
+        };
+        let format = MessageFormat::parse(&format);
+        let posted = messages::post(state, &claims, &room, &author, text, format, ttl_seconds).await.map_err(status)?;
+        let response = match posted {
+            Posted::Message(message_id) => SendMessageResponse { message_id, reply: None },
+            Posted::Reply { command, text } => {
+                SendMessageResponse { message_id: None, reply: Some(CommandReply { command, text }) }
+            }
+            Posted::Command => SendMessageResponse { message_id: None, reply: None },
+        };
+        Ok(Response::new(response))
+    }
+
+    type StreamRoomStream = RoomEventStream;
+
+    // 방 채널을 직접 구독한다. ROOM_LAG_POLICY=disconnect 에서 뒤처지면 RESOURCE_EXHAUSTED 로 끝난다.
+    // 서버가 종료할 때는 server_shutting_down 이벤트를 보내고 끝난다.
+    async fn stream_room(&self, request: Request<StreamRoomRequest>) -> Result<Response<RoomEventStream>, Status> {
+        let claims = authenticate(&self.state, &request, "read").await?;
+        let room = request.into_inner().room;
+        history::check_can_read(&claims, &room).map_err(status)?;
+        tracing::info!("User {} is streaming room '{}' over gRPC", claims.user_id, room);
+
+        let rx = self.state.bus.subscribe(&room);
+        let stopping = self.state.shutdown.clone();
+        let events = stream::unfold(Some((rx, room, stopping)), |next| async move {
+            let (mut rx, room, stopping) = next?;
+            let received = tokio::select! {
+                biased;
+                _ = stopping.wait() => return Some((Ok(room_event(&shutdown::event())), None)),
+                received = rx.recv() => received,
+            };
+            let item = match received {
+                Ok(event) => Ok(room_event(&event)),
+                Err(RecvError::Lagged(skipped)) => match ws::lagged(&room, skipped) {
+                    Some(event) => Ok(room_event(&event)),
+                    None => return Some((Err(Status::resource_exhausted("slow_consumer")), None)),
+                },
+                Err(RecvError::Closed) => return None,
+            };
+            Some((item, Some((rx, room, stopping))))
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
+
+    async fn list_rooms(&self, request: Request<ListRoomsRequest>) -> Result<Response<ListRoomsResponse>, Status> {
+        authenticate(&self.state, &request, "read").await?;
+        Ok(Response::new(ListRoomsResponse { rooms: self.state.bus.rooms() }))
+    }
+
+    async fn delete_room(
+        &self,
+        request: Request<DeleteRoomRequest>,
+    ) -> Result<Response<DeleteRoomResponse>, Status> {
+        let state = &self.state;
+        let claims = authenticate(state, &request, "write").await?;
+        let user = state.repos.users.find(claims.user_id).await.map_err(|e| status(e.into()))?;
+        if !user.is_some_and(|user| user.is_admin) {
+            return Err(status(ApiError::forbidden("admin_required", "Administrator access required")));
+        }
+        let room = request.into_inner().room;
+        let (messages_deleted, closed) = admin::delete_room(state, &claims, &room).await.map_err(status)?;
+        Ok(Response::new(DeleteRoomResponse { messages_deleted, connections_closed: closed as u64 }))
+    }
+}
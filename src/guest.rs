@@ -0,0 +1,197 @@
+// --- 게스트 접속 ---
+//
+// `POST /guest` 는 비밀번호 없는 임시 계정을 만들고 guest 클레임이 붙은 토큰을 발급한다.
+// 게스트는 GUEST_ROOMS 에 있는 방에만 들어갈 수 있고, 기본적으로 읽기만 가능하다
+// (GUEST_CAN_POST=true 면 GUEST_POST_INTERVAL_SECONDS 마다 한 번씩 쓸 수 있다).
+// `POST /guest/upgrade` 로 정식 계정이 되면 같은 사용자 id 와 기록이 그대로 이어진다.
+// 전환하지 않은 게스트 계정은 GUEST_TTL_DAYS 가 지나면 지워진다.
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    auth::AuthUser,
+    config, email_verification,
+    error::{ApiError, ApiJson},
+    invites,
+    repo::{GuestUpgrade, RepoError, Repos},
+    session::{issue_tokens, start_session, ClientInfo},
+    validation::{self, ValidationErrors, GUEST_USERNAME_PREFIX},
+    AppState,
+};
+
+const GUEST_SUFFIX_LEN: usize = 6;
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub static GUEST_ACCESS: Lazy<bool> = Lazy::new(|| config::get().features.guest_access);
+pub static GUEST_CAN_POST: Lazy<bool> = Lazy::new(|| config::get().features.guest_can_post);
+pub static GUEST_POST_INTERVAL: Lazy<Duration> =
+    Lazy::new(|| Duration::from_secs(config::get().guests.post_interval_seconds));
+static GUEST_TTL: Lazy<chrono::Duration> = Lazy::new(|| chrono::Duration::days(config::get().guests.ttl_days));
+
+// 게스트가 들어갈 수 있는 방. 비어 있으면 모든 방.
+static GUEST_ROOMS: Lazy<HashSet<String>> = Lazy::new(|| config::get().guests.rooms.iter().cloned().collect());
+
+#[derive(Debug, Deserialize)]
+pub struct UpgradePayload {
+    // 없으면 게스트 이름을 그대로 쓴다
+    #[serde(default)]
+    username: Option<String>,
+    password: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    invite_code: Option<String>,
+}
+
+// 게스트가 마지막으로 글을 쓴 시각. 웹소켓과 REST 로 보낸 글을 함께 센다.
+#[derive(Default)]
+pub struct PostInterval {
+    last: Mutex<HashMap<i32, Instant>>,
+}
+
+impl PostInterval {
+    // GUEST_POST_INTERVAL 이 지났으면 지금 쓴 것으로 기록하고 true
+    pub fn allow(&self, user_id: i32) -> bool {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap();
+        last.retain(|_, t| now.duration_since(*t) < *GUEST_POST_INTERVAL);
+        if last.contains_key(&user_id) {
+            return false;
+        }
+        last.insert(user_id, now);
+        true
+    }
+}
+
+pub fn can_join(room: &str) -> bool {
+    GUEST_ROOMS.is_empty() || GUEST_ROOMS.contains(room)
+}
+
+fn guest_username() -> String {
+    let suffix: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(GUEST_SUFFIX_LEN)
+        .map(|c| (c as char).to_ascii_lowercase())
+        .collect();
+    format!("{}{}", GUEST_USERNAME_PREFIX, suffix)
+}
+
+// POST /guest
+pub async fn guest_handler(State(state): State<AppState>, jar: CookieJar, client: ClientInfo) -> Response {
+    if !*GUEST_ACCESS {
+        return ApiError::forbidden("guest_access_disabled", "Guest access is disabled").into_response();
+    }
+
+    // 임의 이름이 겹칠 일은 드물지만, 겹치면 다시 뽑는다
+    for _ in 0..5 {
+        let username = guest_username();
+        match state.repos.accounts.create_guest(&username).await {
+            Ok(Some(user_id)) => {
+                tracing::info!("Guest {} ({}) created", username, user_id);
+                return start_session(&state, jar, user_id, &username, client).await;
+            }
+            Ok(None) => continue,
+            Err(e) => return ApiError::from(e).into_response(),
+        }
+    }
+    ApiError::internal().into_response()
+}
+
+// POST /guest/upgrade: 게스트 계정을 정식 계정으로 바꾸고 새 토큰을 발급한다
+pub async fn upgrade_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<UpgradePayload>,
+) -> Response {
+    if !claims.guest {
+        return ApiError::bad_request("not_a_guest", "This account is not a guest account").into_response();
+    }
+
+    let mut errors = ValidationErrors::default();
+    let username = match payload.username.as_deref() {
+        Some(name) => {
+            let name = validation::normalize_username(name);
+            validation::check_username(&name, &mut errors);
+            name
+        }
+        None => claims.sub.clone(),
+    };
+    validation::check_password("password", &payload.password, Some(&username), &mut errors);
+    let email = payload.email.as_deref().map(str::trim).filter(|e| !e.is_empty());
+    match email {
+        Some(email) => validation::check_email(email, &mut errors),
+        None if *email_verification::REQUIRE_VERIFIED_EMAIL => {
+            errors.add("email", "required", "Email is required")
+        }
+        None => {}
+    }
+    let invite_code = payload.invite_code.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if *invites::REQUIRE_INVITE && invite_code.is_none() {
+        errors.add("invite_code", "required", "An invite code is required to register");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let password_hash = match state.passwords.hash_async(&payload.password).await {
+        Ok(h) => h,
+        Err(_) => return ApiError::internal().into_response(),
+    };
+
+    let upgrade = GuestUpgrade {
+        user_id: claims.user_id,
+        username: &username,
+        password_hash: &password_hash,
+        email,
+        invite_code: invite_code.filter(|_| *invites::REQUIRE_INVITE),
+    };
+    match state.repos.accounts.upgrade_guest(&upgrade).await {
+        Ok(true) => {}
+        Ok(false) => return ApiError::bad_request("not_a_guest", "This account is not a guest account").into_response(),
+        Err(RepoError::Conflict("email")) => {
+            return ValidationErrors::single("email", "taken", "Email is already in use").into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            return ValidationErrors::single("username", "taken", "Username is already taken").into_response()
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+    tracing::info!("Guest {} upgraded to account '{}'", claims.user_id, username);
+
+    if let Some(email) = email {
+        if let Err(e) = email_verification::send_verification(&state, claims.user_id, email).await {
+            tracing::warn!("Failed to create verification token for user {}: {}", claims.user_id, e);
+        }
+    }
+
+    // 지금 세션은 유지하고 guest 클레임이 없는 토큰으로 바꾼다
+    issue_tokens(&state, jar, claims.user_id, &username, &claims.sid, false).await
+}
+
+// 전환하지 않고 기한이 지난 게스트 계정을 주기적으로 지운다
+pub fn spawn_purge_task(repos: Repos) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match repos.accounts.purge_guests(chrono::Utc::now() - *GUEST_TTL).await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!("Purged {} expired guest accounts", purged),
+                Err(e) => tracing::warn!("Failed to purge guest accounts: {}", e),
+            }
+        }
+    });
+}
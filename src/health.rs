@@ -0,0 +1,89 @@
+// --- 상태 확인 ---
+//
+// 쿠버네티스 프로브와 로드 밸런서용. 로그인 없이 부른다.
+//
+// - `GET /healthz`: 프로세스가 요청에 답할 수 있으면 200 (liveness)
+// - `GET /readyz`: DB 에 쿼리가 되고, 메시지 버스가 연결되어 있고, 이 빌드가 아는 마이그레이션이 모두 적용됐으면
+//   200, 아니면 503 (readiness). 어느 확인이 실패했는지 본문에 담는다.
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{repo::Backend, AppState};
+
+// 빌드할 때 migrations, migrations_sqlite, migrations_mysql 디렉터리에서 읽은 가장 최근 버전 (build.rs)
+const LATEST_MIGRATION: &str = env!("LATEST_MIGRATION");
+const LATEST_SQLITE_MIGRATION: &str = env!("LATEST_SQLITE_MIGRATION");
+const LATEST_MYSQL_MIGRATION: &str = env!("LATEST_MYSQL_MIGRATION");
+
+// DB 가 이 안에 답하지 않으면 준비되지 않은 것으로 본다
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct Check {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Check {
+    fn passed() -> Self {
+        Check { ok: true, error: None }
+    }
+
+    fn failed(error: impl ToString) -> Self {
+        Check { ok: false, error: Some(error.to_string()) }
+    }
+}
+
+// GET /healthz
+pub async fn liveness_handler() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+// GET /readyz
+pub async fn readiness_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let database = match tokio::time::timeout(CHECK_TIMEOUT, state.repos.health.ping()).await {
+        Ok(Ok(_)) => Check::passed(),
+        Ok(Err(e)) => Check::failed(e),
+        Err(_) => Check::failed("timed out"),
+    };
+    let message_bus = if state.bus.is_connected() {
+        Check::passed()
+    } else {
+        Check::failed(format!("{} is not connected", state.bus.describe()))
+    };
+
+    let latest = match Backend::configured() {
+        Backend::Postgres => LATEST_MIGRATION,
+        Backend::Sqlite | Backend::Memory => LATEST_SQLITE_MIGRATION,
+        Backend::MySql => LATEST_MYSQL_MIGRATION,
+    };
+    let expected: i64 = latest.parse().unwrap_or(0);
+    let applied = tokio::time::timeout(CHECK_TIMEOUT, state.repos.health.applied_migrations())
+        .await
+        .map(|versions| versions.map(|versions| versions.last().copied()));
+    let (migrations, applied) = match applied {
+        Ok(Ok(applied)) if applied.unwrap_or(0) >= expected => (Check::passed(), applied),
+        Ok(Ok(applied)) => (Check::failed(format!("migration {} has not been applied", expected)), applied),
+        Ok(Err(e)) => (Check::failed(e), None),
+        Err(_) => (Check::failed("timed out"), None),
+    };
+
+    let ready = database.ok && message_bus.ok && migrations.ok;
+    let mut migrations = json!(migrations);
+    migrations["applied"] = json!(applied);
+    migrations["expected"] = json!(expected);
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    let body = json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "checks": {
+            "database": database,
+            "message_bus": message_bus,
+            "migrations": migrations,
+        },
+    });
+    (status, Json(body))
+}
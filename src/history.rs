@@ -0,0 +1,199 @@
+// --- 저장된 메시지 조회 ---
+//
+// `GET /rooms/:room/messages/:id` 는 메시지 하나의 고유 링크다. 메시지와 함께 같은 방의 앞뒤 메시지를
+// `context` 개씩 돌려주므로 클라이언트는 링크를 열었을 때 그 자리의 대화를 바로 보여 줄 수 있다.
+// `GET /rooms/:room/messages` 는 방의 지난 메시지를 id 기준으로 넘겨 보며, `around` 로 특정 메시지나 날짜
+// 근처로 바로 건너뛸 수 있다.
+//
+// 그림자 차단으로 숨겨진 메시지는 쓴 사람에게만, 수명이 지난 메시지는 정리 태스크가 지우기 전이라도 누구에게도
+// 보이지 않는다.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{AuthUser, Claims},
+    error::ApiError,
+    guest,
+    repo::{MessageRepo, RepoResult, StoredMessage},
+    AppState,
+};
+
+// 앞뒤로 붙이는 메시지 수
+const CONTEXT_DEFAULT: i64 = 5;
+const CONTEXT_MAX: i64 = 50;
+// GET /rooms/:room/messages 의 최대 limit
+const PAGE_MAX: i64 = 100;
+
+// REST 로 방의 메시지를 읽을 수 있는지. 게스트는 들어갈 수 있는 방만 읽는다.
+pub fn check_can_read(claims: &Claims, room: &str) -> Result<(), ApiError> {
+    if claims.guest && !guest::can_join(room) {
+        return Err(ApiError::forbidden("guest_room_forbidden", "Guests cannot join this room"));
+    }
+    Ok(())
+}
+
+// room 에서 viewer 가 볼 수 있는 메시지
+async fn find_in_room(
+    messages: &dyn MessageRepo,
+    viewer: i32,
+    room: &str,
+    message_id: i64,
+) -> RepoResult<Option<StoredMessage>> {
+    Ok(messages.find_visible(viewer, message_id).await?.filter(|m| m.room.as_deref() == Some(room)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PermalinkQuery {
+    #[serde(default)]
+    context: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct Permalink {
+    message: StoredMessage,
+    // 오래된 것부터
+    before: Vec<StoredMessage>,
+    after: Vec<StoredMessage>,
+}
+
+// GET /rooms/:room/messages/:id?context=5: 메시지와 앞뒤 메시지
+pub async fn permalink_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((room, id)): Path<(String, i64)>,
+    Query(query): Query<PermalinkQuery>,
+) -> Response {
+    if let Err(e) = check_can_read(&claims, &room) {
+        return e.into_response();
+    }
+    let context = query.context.unwrap_or(CONTEXT_DEFAULT).clamp(0, CONTEXT_MAX);
+
+    let messages = state.repos.messages.as_ref();
+    let message = match find_in_room(messages, claims.user_id, &room, id).await {
+        Ok(Some(message)) => message,
+        Ok(None) => return ApiError::not_found("message_not_found", "Message not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let before = messages.page_before(claims.user_id, &room, Some(id), context).await;
+    let after = messages.page_after(claims.user_id, &room, id, context).await;
+    match (before, after) {
+        (Ok(before), Ok(after)) => Json(Permalink { message, before, after }).into_response(),
+        (Err(e), _) | (_, Err(e)) => ApiError::from(e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    after: Option<i64>,
+    // 메시지 id 나 RFC 3339 시각
+    #[serde(default)]
+    around: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// around 의 기준. 숫자면 메시지 id, 아니면 시각.
+enum Anchor {
+    Message(i64),
+    Time(DateTime<Utc>),
+}
+
+impl Anchor {
+    fn parse(value: &str) -> Option<Self> {
+        if let Ok(id) = value.parse::<i64>() {
+            return Some(Anchor::Message(id));
+        }
+        DateTime::parse_from_rfc3339(value).ok().map(|t| Anchor::Time(t.with_timezone(&Utc)))
+    }
+}
+
+#[derive(Serialize)]
+struct History {
+    // 오래된 것부터
+    messages: Vec<StoredMessage>,
+    // around 로 찾은 기준 메시지. 그 시각 뒤에 메시지가 없으면 null.
+    anchor_id: Option<i64>,
+}
+
+// GET /rooms/:room/messages?before=&after=&around=&limit=: 방의 지난 메시지
+//
+// 아무것도 주지 않으면 가장 최근 limit 개, before/after 는 그 id 의 앞/뒤 limit 개를 돌려준다. around 는
+// 기준 메시지(시각이면 그 시각 이후 첫 메시지)를 가운데 두고 앞뒤로 limit 의 절반씩 돌려준다.
+pub async fn list_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    if let Err(e) = check_can_read(&claims, &room) {
+        return e.into_response();
+    }
+    let given = [query.before.is_some(), query.after.is_some(), query.around.is_some()];
+    if given.iter().filter(|&&g| g).count() > 1 {
+        return ApiError::bad_request("conflicting_parameters", "Use only one of before, after and around")
+            .into_response();
+    }
+    let limit = query.limit.clamp(1, PAGE_MAX);
+    let (messages, viewer) = (state.repos.messages.as_ref(), claims.user_id);
+
+    let history = match (query.after, query.around.as_deref()) {
+        (Some(after), _) => messages.page_after(viewer, &room, after, limit).await.map(|m| (m, None)),
+        (None, None) => messages.page_before(viewer, &room, query.before, limit).await.map(|m| (m, None)),
+        (None, Some(around)) => {
+            let anchor = match Anchor::parse(around) {
+                Some(Anchor::Message(id)) => match find_in_room(messages, viewer, &room, id).await {
+                    Ok(Some(message)) => Some(message),
+                    Ok(None) => return ApiError::not_found("message_not_found", "Message not found").into_response(),
+                    Err(e) => return ApiError::from(e).into_response(),
+                },
+                Some(Anchor::Time(at)) => match messages.first_since(viewer, &room, at).await {
+                    Ok(first) => first,
+                    Err(e) => return ApiError::from(e).into_response(),
+                },
+                None => {
+                    return ApiError::bad_request("invalid_around", "around must be a message id or an RFC 3339 time")
+                        .into_response()
+                }
+            };
+            match anchor {
+                Some(anchor) => around_anchor(messages, viewer, &room, anchor, limit).await,
+                // 그 시각 뒤에 메시지가 없으면 가장 최근 것
+                None => messages.page_before(viewer, &room, None, limit).await.map(|m| (m, None)),
+            }
+        }
+    };
+    match history {
+        Ok((messages, anchor_id)) => Json(History { messages, anchor_id }).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// 기준 메시지와 그 앞뒤. 앞쪽이 모자라면 그만큼 뒤쪽을 더 채우지는 않는다.
+async fn around_anchor(
+    messages: &dyn MessageRepo,
+    viewer: i32,
+    room: &str,
+    anchor: StoredMessage,
+    limit: i64,
+) -> RepoResult<(Vec<StoredMessage>, Option<i64>)> {
+    let before_count = (limit - 1) / 2;
+    let after_count = limit - 1 - before_count;
+    let anchor_id = anchor.id;
+    let mut page = messages.page_before(viewer, room, Some(anchor_id), before_count).await?;
+    page.push(anchor);
+    page.extend(messages.page_after(viewer, room, anchor_id, after_count).await?);
+    Ok((page, Some(anchor_id)))
+}
@@ -0,0 +1,110 @@
+// DiscordChatExporter 의 JSON 내보내기: 파일 하나가 채널 하나이고, 작성자 정보가 메시지마다 들어 있다.
+// 여러 채널을 zip 으로 묶어 올리면 채널 이름이 같은 파일(나눠 내보낸 것)은 한 방으로 합친다. 고정, 입장 같은
+// 시스템 메시지는 뺀다.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::{Archive, Channel, ImportedMessage, ImportedUser};
+
+#[derive(Deserialize)]
+struct Export {
+    channel: DiscordChannel,
+    #[serde(default)]
+    messages: Vec<DiscordMessage>,
+}
+
+#[derive(Deserialize)]
+struct DiscordChannel {
+    name: String,
+    #[serde(default)]
+    topic: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscordMessage {
+    id: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    timestamp: DateTime<Utc>,
+    #[serde(default)]
+    content: String,
+    author: DiscordAuthor,
+    #[serde(default)]
+    attachments: Vec<DiscordAttachment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscordAuthor {
+    id: String,
+    name: String,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    is_bot: bool,
+}
+
+#[derive(Deserialize)]
+struct DiscordAttachment {
+    url: String,
+}
+
+pub(super) fn parse(files: &[(String, Vec<u8>)]) -> Result<Archive, String> {
+    let mut channels: BTreeMap<String, Channel> = BTreeMap::new();
+    let mut users: BTreeMap<String, ImportedUser> = BTreeMap::new();
+    for (path, content) in files.iter().filter(|(path, _)| path.ends_with(".json")) {
+        let export: Export = serde_json::from_slice(content).map_err(|e| format!("Invalid {}: {}", path, e))?;
+        let channel = channels.entry(export.channel.name.clone()).or_insert_with(|| Channel {
+            name: export.channel.name,
+            topic: None,
+            messages: Vec::new(),
+        });
+        if channel.topic.is_none() {
+            channel.topic = export.channel.topic.filter(|t| !t.trim().is_empty());
+        }
+
+        for message in export.messages {
+            // Default 와 Reply 만 사람이 쓴 메시지다
+            if !matches!(message.kind.as_str(), "" | "Default" | "Reply") {
+                continue;
+            }
+            let mut text = message.content;
+            for attachment in &message.attachments {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&attachment.url);
+            }
+            if text.trim().is_empty() {
+                continue;
+            }
+            let author = message.author;
+            users.entry(author.id.clone()).or_insert_with(|| ImportedUser {
+                external_id: author.id.clone(),
+                name: author.name.clone(),
+                display_name: author.nickname.filter(|n| *n != author.name),
+                bot: author.is_bot,
+            });
+            channel.messages.push(ImportedMessage {
+                // 메시지 id 는 Discord 전체에서 유일하다
+                external_id: message.id,
+                user_external_id: Some(author.id),
+                fallback_name: author.name,
+                text,
+                sent_at: message.timestamp,
+            });
+        }
+    }
+    if channels.is_empty() {
+        return Err("No DiscordChatExporter JSON files found".to_string());
+    }
+
+    let mut archive = Archive { users: users.into_values().collect(), channels: channels.into_values().collect() };
+    for channel in &mut archive.channels {
+        channel.messages.sort_by_key(|m| m.sent_at);
+    }
+    Ok(archive)
+}
@@ -0,0 +1,296 @@
+// --- Slack/Discord 기록 가져오기 ---
+//
+// 관리자가 `POST /admin/imports?source=slack|discord` 로 내보내기 파일을 올리면 작업을 만들고 백그라운드
+// 태스크가 가져온다. 채널마다 방을 만들고(room_prefix + 채널 이름), 원래 작성자마다 로그인할 수 없는 자리표시
+// 사용자(비밀번호와 메일 주소가 없다)를 만든 뒤, 메시지를 원래 시각으로 저장한다. 진행 상황은
+// `GET /admin/imports/:id` 로 본다.
+//
+// 가져온 사용자와 메시지에는 원래 id 를 `imported_from` 에 남기므로 같은 파일을 다시 올려도 겹치지 않는다.
+// 작업이 중간에 실패하거나 서버가 꺼졌다면 같은 파일로 다시 가져오면 된다.
+
+mod discord;
+mod slack;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+use crate::{
+    audit::{self, Target},
+    auth::AdminUser,
+    config,
+    error::ApiError,
+    repo::{ImportProgress, ImportedRow, RepoResult, Repos},
+    validation::{self, ValidationErrors, GUEST_USERNAME_PREFIX},
+    AppState,
+};
+
+// 올릴 수 있는 파일 크기 (기본 200MiB)
+pub static IMPORT_MAX_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.import_max_bytes);
+
+const ROOM_MAX_CHARS: usize = 64;
+const USERNAME_MAX_CHARS: usize = 32;
+// 한 번에 넣는 메시지 수
+const INSERT_BATCH: usize = 500;
+
+const RUNNING: &str = "running";
+const DONE: &str = "done";
+const FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Slack,
+    Discord,
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Slack => "slack",
+            Source::Discord => "discord",
+        }
+    }
+}
+
+// 내보내기 파일에서 읽은 내용. external_id 는 원래 서비스의 id 로, imported_from 의 재료가 된다.
+#[derive(Debug, Default)]
+struct Archive {
+    users: Vec<ImportedUser>,
+    channels: Vec<Channel>,
+}
+
+#[derive(Debug)]
+struct ImportedUser {
+    external_id: String,
+    name: String,
+    display_name: Option<String>,
+    bot: bool,
+}
+
+#[derive(Debug)]
+struct Channel {
+    name: String,
+    topic: Option<String>,
+    messages: Vec<ImportedMessage>,
+}
+
+#[derive(Debug)]
+struct ImportedMessage {
+    external_id: String,
+    // 원래 작성자. 사용자 목록에 없으면 fallback_name 으로만 남긴다.
+    user_external_id: Option<String>,
+    fallback_name: String,
+    text: String,
+    sent_at: DateTime<Utc>,
+}
+
+// zip 안의 파일 이름과 내용
+fn read_zip(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut zip = ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Not a zip archive: {}", e))?;
+    let mut files = Vec::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(|e| format!("Unreadable zip entry: {}", e))?;
+        if !file.is_file() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut content = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut content).map_err(|e| format!("Unreadable zip entry {}: {}", name, e))?;
+        files.push((name, content));
+    }
+    Ok(files)
+}
+
+fn parse(source: Source, data: &[u8]) -> Result<Archive, String> {
+    match source {
+        Source::Slack => slack::parse(&read_zip(data)?),
+        // DiscordChatExporter 의 JSON 파일 하나를 그대로 올려도 된다
+        Source::Discord if data.first() == Some(&b'{') => discord::parse(&[("export.json".to_string(), data.to_vec())]),
+        Source::Discord => discord::parse(&read_zip(data)?),
+    }
+}
+
+// 사용자 이름 규칙(영문자·숫자로 시작, 영문자·숫자·_·-·. 만)에 맞춘 이름. 게스트 이름처럼 보이면 안 된다.
+fn username_base(name: &str) -> String {
+    let mut base: String = validation::normalize_username(name)
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') { c } else { '_' })
+        .skip_while(|c| !c.is_alphanumeric())
+        .take(USERNAME_MAX_CHARS - 4)
+        .collect();
+    while base.chars().count() < 3 {
+        base.push('_');
+    }
+    if base.to_lowercase().starts_with(GUEST_USERNAME_PREFIX) {
+        base.replace_range(GUEST_USERNAME_PREFIX.len() - 1..GUEST_USERNAME_PREFIX.len(), "_");
+    }
+    base
+}
+
+fn room_name(prefix: &str, channel: &str) -> String {
+    format!("{}{}", prefix, channel).chars().take(ROOM_MAX_CHARS).collect()
+}
+
+// 원래 사용자에 해당하는 자리표시 사용자. 이미 가져온 사용자면 그 사용자를 돌려준다. (id, 이름, 새로 만들었는지)
+async fn placeholder_user(repos: &Repos, source: Source, user: &ImportedUser) -> RepoResult<(i32, String, bool)> {
+    let imported_from = format!("{}:{}", source.as_str(), user.external_id);
+    if let Some((id, username)) = repos.imports.imported_user(&imported_from).await? {
+        return Ok((id, username, false));
+    }
+
+    // 이름이 겹치거나 예약된 이름이면 -2, -3 … 을 붙인다
+    let base = username_base(&user.name);
+    let mut n = 1;
+    loop {
+        let username = if n == 1 { base.clone() } else { format!("{}-{}", base, n) };
+        let mut errors = ValidationErrors::default();
+        validation::check_username(&username, &mut errors);
+        n += 1;
+        if !errors.is_empty() {
+            continue;
+        }
+        let display_name = user.display_name.as_deref();
+        if let Some(id) = repos.imports.create_placeholder(&username, user.bot, &imported_from, display_name).await? {
+            return Ok((id, username, true));
+        }
+        // 다른 작업이 같은 사용자를 먼저 만들었을 수 있다
+        if let Some((id, username)) = repos.imports.imported_user(&imported_from).await? {
+            return Ok((id, username, false));
+        }
+    }
+}
+
+async fn update_progress(repos: &Repos, id: i64, progress: ImportProgress) {
+    if let Err(e) = repos.imports.set_progress(id, progress).await {
+        tracing::warn!("Failed to update progress of import {}: {}", id, e);
+    }
+}
+
+async fn run_import(repos: Repos, id: i64, source: Source, room_prefix: String, data: Bytes) {
+    let _ = repos.imports.set_status(id, RUNNING).await;
+
+    let result = match tokio::task::spawn_blocking(move || parse(source, &data)).await {
+        Ok(Ok(archive)) => import_archive(&repos, id, source, &room_prefix, archive).await.map_err(|e| {
+            tracing::error!("Import {} failed: {}", id, e);
+            "Database error".to_string()
+        }),
+        Ok(Err(e)) => Err(e),
+        Err(e) => Err(format!("Parser crashed: {}", e)),
+    };
+    let (status, error) = match result {
+        Ok(()) => (DONE, None),
+        Err(e) => (FAILED, Some(e)),
+    };
+    tracing::info!("Import {} finished: {}", id, status);
+    if let Err(e) = repos.imports.finish(id, status, error.as_deref()).await {
+        tracing::error!("Failed to finish import {}: {}", id, e);
+    }
+}
+
+async fn import_archive(repos: &Repos, id: i64, source: Source, prefix: &str, archive: Archive) -> RepoResult<()> {
+    let mut progress = ImportProgress::default();
+
+    let mut users = std::collections::HashMap::new();
+    for user in &archive.users {
+        let (user_id, username, created) = placeholder_user(repos, source, user).await?;
+        if created {
+            progress.users_created += 1;
+        }
+        users.insert(user.external_id.clone(), (user_id, username));
+    }
+    update_progress(repos, id, progress).await;
+
+    for channel in archive.channels {
+        let room = room_name(prefix, &channel.name);
+        if let Some(topic) = &channel.topic {
+            repos.imports.set_topic(&room, topic).await?;
+        }
+        for batch in channel.messages.chunks(INSERT_BATCH) {
+            let rows: Vec<_> = batch
+                .iter()
+                .map(|message| {
+                    let author = message.user_external_id.as_ref().and_then(|u| users.get(u));
+                    ImportedRow {
+                        user_id: author.map(|(id, _)| *id),
+                        username: author.map_or(message.fallback_name.as_str(), |(_, name)| name.as_str()),
+                        text: &message.text,
+                        sent_at: message.sent_at,
+                        imported_from: format!("{}:{}", source.as_str(), message.external_id),
+                    }
+                })
+                .collect();
+            let inserted = repos.imports.insert_messages(&room, &rows).await? as i32;
+            progress.imported += inserted;
+            progress.skipped += batch.len() as i32 - inserted;
+        }
+        progress.rooms += 1;
+        update_progress(repos, id, progress).await;
+    }
+    Ok(())
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateQuery {
+    source: Source,
+    // 가져온 채널의 방 이름 앞에 붙인다 (예: "slack-")
+    #[serde(default)]
+    room_prefix: String,
+}
+
+// POST /admin/imports?source=slack|discord&room_prefix=: 본문은 내보내기 zip (Discord 는 JSON 파일 하나도 된다)
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Query(query): Query<CreateQuery>,
+    body: Bytes,
+) -> Response {
+    if body.is_empty() {
+        return ApiError::bad_request("empty_file", "Upload the export archive as the request body").into_response();
+    }
+    let prefix = query.room_prefix.trim().to_string();
+    if prefix.chars().count() > ROOM_MAX_CHARS / 2 {
+        return ApiError::bad_request("room_prefix_too_long", "room_prefix is too long").into_response();
+    }
+
+    let created = state.repos.imports.create(query.source.as_str(), &prefix, claims.user_id).await;
+    let job = match created {
+        Ok(job) => job,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    tracing::info!("User {} started import {} from {} ({} bytes)", claims.user_id, job.id, job.source, body.len());
+    let details = json!({ "source": query.source, "room_prefix": prefix, "bytes": body.len() });
+    audit::record(&state, &claims, "import.create", Target::Import(job.id), None, details).await;
+
+    tokio::spawn(run_import(state.repos.clone(), job.id, query.source, prefix, body));
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+// GET /admin/imports: 최근 작업부터
+pub async fn list_handler(State(state): State<AppState>) -> Response {
+    match state.repos.imports.list(100).await {
+        Ok(jobs) => Json(jobs).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /admin/imports/:id
+pub async fn status_handler(State(state): State<AppState>, Path(id): Path<i64>) -> Response {
+    match state.repos.imports.find(id).await {
+        Ok(Some(job)) => Json(job).into_response(),
+        Ok(None) => ApiError::not_found("import_not_found", "Import not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,210 @@
+// Slack 작업 공간 내보내기(zip): users.json, channels.json(비공개 채널은 groups.json), 그리고 채널마다
+// `<채널 이름>/<날짜>.json` 에 그날의 메시지 배열이 들어 있다. 입장·퇴장, 주제 변경 같은 알림 메시지는 뺀다.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::{Archive, Channel, ImportedMessage, ImportedUser};
+
+#[derive(Deserialize)]
+struct SlackUser {
+    id: String,
+    name: String,
+    #[serde(default)]
+    real_name: Option<String>,
+    #[serde(default)]
+    is_bot: bool,
+    #[serde(default)]
+    profile: SlackProfile,
+}
+
+#[derive(Default, Deserialize)]
+struct SlackProfile {
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SlackChannel {
+    name: String,
+    #[serde(default)]
+    topic: Option<SlackText>,
+    #[serde(default)]
+    purpose: Option<SlackText>,
+}
+
+#[derive(Deserialize)]
+struct SlackText {
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct SlackMessage {
+    #[serde(default)]
+    subtype: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    // 봇이나 연동이 보낸 메시지의 이름
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    text: String,
+    ts: String,
+    #[serde(default)]
+    files: Vec<SlackFile>,
+}
+
+#[derive(Deserialize)]
+struct SlackFile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    permalink: Option<String>,
+}
+
+// 채팅 기록이 아닌 알림 메시지
+fn is_notice(subtype: &str) -> bool {
+    subtype.starts_with("channel_") || subtype.starts_with("group_") || matches!(subtype, "pinned_item" | "bot_add")
+}
+
+// "1355517523.000005" → 보낸 시각
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    let (secs, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+    let micros = format!("{:0<6}", micros.get(..6).unwrap_or(micros)).parse::<u32>().ok()?;
+    DateTime::from_timestamp(secs.parse().ok()?, micros * 1000)
+}
+
+// Slack 의 표기(<@U123>, <#C123|general>, <https://…|설명>, <!here>, &lt; 등)를 읽을 수 있는 글로 바꾼다
+fn convert_text(text: &str, names: &BTreeMap<&str, &str>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let inner = &rest[start + 1..start + end];
+        let (target, label) = match inner.split_once('|') {
+            Some((target, label)) => (target, Some(label)),
+            None => (inner, None),
+        };
+        if let Some(user) = target.strip_prefix('@') {
+            out.push('@');
+            out.push_str(label.or_else(|| names.get(user).copied()).unwrap_or(user));
+        } else if let Some(channel) = target.strip_prefix('#') {
+            out.push('#');
+            out.push_str(label.unwrap_or(channel));
+        } else if let Some(special) = target.strip_prefix('!') {
+            out.push('@');
+            out.push_str(label.unwrap_or(special.split('^').next().unwrap_or(special)));
+        } else {
+            match label {
+                Some(label) if label != target => {
+                    out.push_str(label);
+                    out.push_str(" (");
+                    out.push_str(target);
+                    out.push(')');
+                }
+                _ => out.push_str(target),
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+fn from_json<'a, T: Deserialize<'a>>(name: &str, content: &'a [u8]) -> Result<T, String> {
+    serde_json::from_slice(content).map_err(|e| format!("Invalid {}: {}", name, e))
+}
+
+pub(super) fn parse(files: &[(String, Vec<u8>)]) -> Result<Archive, String> {
+    // 내보내기를 폴더째 압축하면 모든 경로 앞에 폴더 이름이 붙는다
+    let Some(users_path) = files.iter().map(|(name, _)| name).find(|name| name.ends_with("users.json")) else {
+        return Err("users.json not found; is this a Slack export?".to_string());
+    };
+    let root = &users_path[..users_path.len() - "users.json".len()];
+    let file = |name: &str| files.iter().find(|(path, _)| path.strip_prefix(root) == Some(name));
+
+    let users: Vec<SlackUser> = from_json("users.json", &file("users.json").expect("found above").1)?;
+    let mut channels = Vec::new();
+    for list in ["channels.json", "groups.json"] {
+        if let Some((_, content)) = file(list) {
+            channels.extend(from_json::<Vec<SlackChannel>>(list, content)?);
+        }
+    }
+    let names: BTreeMap<&str, &str> = users.iter().map(|u| (u.id.as_str(), u.name.as_str())).collect();
+
+    let mut archive = Archive::default();
+    for channel in &channels {
+        // 파일 이름이 날짜이므로 이름순이 시간순이다
+        let mut days: Vec<&(String, Vec<u8>)> = files
+            .iter()
+            .filter(|(path, _)| {
+                path.strip_prefix(root)
+                    .and_then(|p| p.strip_prefix(channel.name.as_str()))
+                    .and_then(|p| p.strip_prefix('/'))
+                    .is_some_and(|day| !day.contains('/') && day.ends_with(".json"))
+            })
+            .collect();
+        days.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut messages = Vec::new();
+        for (path, content) in days {
+            for message in from_json::<Vec<SlackMessage>>(path, content)? {
+                if message.subtype.as_deref().is_some_and(is_notice) {
+                    continue;
+                }
+                let Some(sent_at) = parse_ts(&message.ts) else {
+                    continue;
+                };
+                let mut text = convert_text(&message.text, &names);
+                for attached in &message.files {
+                    if let Some(link) = attached.permalink.as_ref().or(attached.name.as_ref()) {
+                        if !text.is_empty() {
+                            text.push('\n');
+                        }
+                        text.push_str(link);
+                    }
+                }
+                if text.trim().is_empty() {
+                    continue;
+                }
+                messages.push(ImportedMessage {
+                    // ts 는 채널 안에서만 유일하다
+                    external_id: format!("{}:{}", channel.name, message.ts),
+                    fallback_name: message
+                        .user
+                        .as_deref()
+                        .and_then(|u| names.get(u).copied())
+                        .or(message.username.as_deref())
+                        .unwrap_or("slack")
+                        .to_string(),
+                    user_external_id: message.user,
+                    text,
+                    sent_at,
+                });
+            }
+        }
+        messages.sort_by_key(|m| m.sent_at);
+
+        let topic = [&channel.topic, &channel.purpose]
+            .into_iter()
+            .flatten()
+            .map(|t| t.value.trim())
+            .find(|t| !t.is_empty())
+            .map(|t| convert_text(t, &names));
+        archive.channels.push(Channel { name: channel.name.clone(), topic, messages });
+    }
+
+    archive.users = users
+        .into_iter()
+        .map(|u| {
+            let display_name = u.profile.display_name.filter(|n| !n.trim().is_empty()).or(u.real_name);
+            ImportedUser { external_id: u.id, name: u.name, display_name, bot: u.is_bot }
+        })
+        .collect();
+    Ok(archive)
+}
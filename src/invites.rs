@@ -0,0 +1,107 @@
+// --- 초대 코드 가입 ---
+//
+// REQUIRE_INVITE=true 면 `POST /register` 에 관리자가 발급한 초대 코드가 있어야 한다. 코드는 여러 번
+// 쓸 수 있게 (max_uses) 발급할 수 있고 만료 시간을 둘 수 있다. 이 모드에서는 소셜 로그인으로도
+// 새 계정을 만들 수 없다 (기존 계정의 로그인은 그대로 된다).
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    audit::{self, Target},
+    auth::{generate_token, hash_token, AdminUser},
+    config,
+    error::{ApiError, ApiJson},
+    validation::ValidationErrors,
+    AppState, PUBLIC_URL,
+};
+
+const MAX_USES_LIMIT: i32 = 10_000;
+
+pub static REQUIRE_INVITE: Lazy<bool> = Lazy::new(|| config::get().features.require_invite);
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitePayload {
+    #[serde(default)]
+    max_uses: Option<i32>,
+    // 없으면 만료되지 않는다
+    #[serde(default)]
+    expires_in_hours: Option<i64>,
+}
+
+// POST /admin/invites: 코드 원문은 이 응답에서만 볼 수 있다
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<CreateInvitePayload>,
+) -> Response {
+    let max_uses = payload.max_uses.unwrap_or(1);
+    let mut errors = ValidationErrors::default();
+    if !(1..=MAX_USES_LIMIT).contains(&max_uses) {
+        errors.add(
+            "max_uses",
+            "out_of_range",
+            format!("max_uses must be between 1 and {}", MAX_USES_LIMIT),
+        );
+    }
+    if payload.expires_in_hours.is_some_and(|h| h <= 0) {
+        errors.add("expires_in_hours", "out_of_range", "expires_in_hours must be positive");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let code = generate_token();
+    let expires_at = payload
+        .expires_in_hours
+        .map(|h| chrono::Utc::now() + chrono::Duration::hours(h));
+    match state.repos.invites.create(&hash_token(&code), claims.user_id, max_uses, expires_at).await {
+        Ok(id) => {
+            tracing::info!("User {} created invite {} (max_uses {})", claims.user_id, id, max_uses);
+            let details = json!({ "max_uses": max_uses, "expires_at": expires_at });
+            audit::record(&state, &claims, "invite.create", Target::Invite(id), None, details).await;
+            let link = format!("{}/static/login.html#invite={}", *PUBLIC_URL, code);
+            let body = json!({
+                "id": id,
+                "code": code,
+                "link": link,
+                "max_uses": max_uses,
+                "expires_at": expires_at,
+            });
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /admin/invites
+pub async fn list_handler(State(state): State<AppState>, _admin: AdminUser) -> Response {
+    match state.repos.invites.list().await {
+        Ok(invites) => Json(invites).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /admin/invites/:id: 남은 사용 횟수와 관계없이 더 이상 쓸 수 없게 한다
+pub async fn revoke_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.invites.revoke(id).await {
+        Ok(true) => {
+            tracing::info!("User {} revoked invite {}", claims.user_id, id);
+            audit::record(&state, &claims, "invite.revoke", Target::Invite(id), None, json!({})).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("invite_not_found", "Invite not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,144 @@
+// --- IP 차단 ---
+//
+// 관리자가 `ip_bans` 에 넣은 주소나 대역(CIDR)에서 오는 웹소켓 연결과 로그인·가입·토큰 갱신 같은 인증 요청을
+// 403 `ip_banned` 로 거부한다. 계정과는 상관없이 접속한 곳만 본다. 만료 시간을 둘 수 있으며, 이미 열려 있는
+// 연결은 끊지 않는다.
+
+use axum::{
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::net::IpAddr;
+
+use crate::{
+    audit::{self, Target},
+    auth::AdminUser,
+    client_ip::ClientIp,
+    error::{ApiError, ApiJson},
+    validation::ValidationErrors,
+    AppState,
+};
+
+const REASON_MAX_CHARS: usize = 500;
+
+// 차단된 주소인지 확인한다. DB 오류가 나면 막지 않는다.
+pub async fn is_banned(state: &AppState, ip: IpAddr) -> bool {
+    match state.repos.ip_bans.is_banned(ip).await {
+        Ok(banned) => banned,
+        Err(e) => {
+            tracing::warn!("Failed to check IP ban for {}: {}", ip, e);
+            false
+        }
+    }
+}
+
+pub fn banned_response() -> Response {
+    ApiError::forbidden("ip_banned", "Access from your network has been blocked").into_response()
+}
+
+// 인증 라우트에 거는 미들웨어
+pub async fn enforce(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    if is_banned(&state, ip).await {
+        tracing::info!("Rejected {} {} from banned address {}", req.method(), req.uri().path(), ip);
+        return banned_response();
+    }
+    next.run(req).await
+}
+
+// "주소" 또는 "주소/접두사 길이"
+fn is_valid_network(network: &str) -> bool {
+    let (addr, prefix) = match network.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (network, None),
+    };
+    let Ok(addr) = addr.parse::<IpAddr>() else {
+        return false;
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    prefix.is_none_or(|p| p.parse::<u8>().is_ok_and(|p| p <= max))
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBanPayload {
+    // "203.0.113.7", "203.0.113.0/24", "2001:db8::/32"
+    network: String,
+    #[serde(default)]
+    reason: Option<String>,
+    // 없으면 만료되지 않는다
+    #[serde(default)]
+    expires_in_hours: Option<i64>,
+}
+
+// GET /admin/ip-bans: 만료되지 않은 것만
+pub async fn list_handler(State(state): State<AppState>, _admin: AdminUser) -> Response {
+    match state.repos.ip_bans.list().await {
+        Ok(bans) => Json(bans).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /admin/ip-bans
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<CreateBanPayload>,
+) -> Response {
+    let network = payload.network.trim();
+    let reason = payload.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let mut errors = ValidationErrors::default();
+    if !is_valid_network(network) {
+        errors.add("network", "invalid_format", "network must be an IP address or CIDR range");
+    }
+    if reason.is_some_and(|r| r.chars().count() > REASON_MAX_CHARS) {
+        errors.add("reason", "too_long", format!("reason must be at most {} characters", REASON_MAX_CHARS));
+    }
+    if payload.expires_in_hours.is_some_and(|h| h <= 0) {
+        errors.add("expires_in_hours", "out_of_range", "expires_in_hours must be positive");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let expires_at = payload.expires_in_hours.map(|h| Utc::now() + chrono::Duration::hours(h));
+    // 호스트 부분은 지워 "10.1.2.3/8" 도 "10.0.0.0/8" 로 저장한다
+    match state.repos.ip_bans.create(network, reason, claims.user_id, expires_at).await {
+        Ok(ban) => {
+            tracing::info!("User {} banned {} (ban {})", claims.user_id, ban.network, ban.id);
+            let details = json!({ "network": ban.network, "expires_at": ban.expires_at });
+            audit::record(&state, &claims, "ip_ban.create", Target::IpBan(ban.id), reason, details).await;
+            (StatusCode::CREATED, Json(ban)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /admin/ip-bans/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.ip_bans.delete(id).await {
+        Ok(Some(network)) => {
+            tracing::info!("User {} lifted ban {} on {}", claims.user_id, id, network);
+            let details = json!({ "network": network });
+            audit::record(&state, &claims, "ip_ban.delete", Target::IpBan(id), None, details).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(None) => ApiError::not_found("ip_ban_not_found", "IP ban not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,253 @@
+// --- JWT 서명 키 ---
+//
+// 기본은 공유 비밀(JWT_SECRET)을 쓰는 HS256 이다. JWT_ALGORITHM 을 RS256 또는 EdDSA 로
+// 설정하면 PEM 키쌍으로 서명하고, 공개키를 `/.well-known/jwks.json` 으로 공개해
+// 다른 서비스가 비밀을 공유하지 않고도 토큰을 검증할 수 있게 한다.
+//
+// 키 교체: 서명은 항상 현재 키 하나로 하고, 이전 키들은 검증용으로만 남겨 둔다.
+// 모든 토큰 헤더에는 키 식별자(kid)가 들어가므로 검증 시 해당 키를 바로 찾을 수 있다.
+// 이전 키로 서명된 토큰이 모두 만료된 뒤에 설정에서 이전 키를 빼면 된다.
+
+use axum::{response::IntoResponse, Json};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::OnceCell;
+use rsa::{pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{fmt, fs};
+
+use crate::config::{self, JwtConfig};
+
+static JWT_KEYS: OnceCell<JwtKeys> = OnceCell::new();
+
+// 설정의 키를 읽는다. 처음 한 번만 읽고, 실패하면 다음에 부를 때 다시 읽는다.
+pub fn load() -> Result<&'static JwtKeys, KeyError> {
+    JWT_KEYS.get_or_try_init(|| JwtKeys::from_config(&config::get().jwt))
+}
+
+// 서명과 검증에 쓰는 키. 설정을 정할 때(Config::validate)와 서버를 만들 때 이미 읽어 봤으므로, 실패하는 것은 그
+// 뒤에 키 파일이 사라진 경우뿐이다.
+pub fn jwt_keys() -> &'static JwtKeys {
+    load().unwrap_or_else(|e| panic!("{}", e))
+}
+
+// 키를 읽지 못한 이유와 그 키를 정하는 설정
+#[derive(Debug)]
+pub struct KeyError {
+    pub setting: &'static str,
+    pub message: String,
+}
+
+impl KeyError {
+    fn new(setting: &'static str, message: impl Into<String>) -> Self {
+        Self { setting, message: message.into() }
+    }
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.setting, self.message)
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+// 검증에 쓸 수 있는 키 하나
+struct VerificationKey {
+    kid: String,
+    algorithm: Algorithm,
+    decoding: DecodingKey,
+    // 비대칭 키만 JWKS 로 공개한다
+    jwk: Option<Value>,
+}
+
+pub struct JwtKeys {
+    signing_kid: String,
+    signing_algorithm: Algorithm,
+    encoding: EncodingKey,
+    // 현재 키가 맨 앞에 온다
+    verification: Vec<VerificationKey>,
+}
+
+impl JwtKeys {
+    // jwt.algorithm (JWT_ALGORITHM) = HS256 (기본) | RS256 | EdDSA
+    // 비대칭 알고리즘은 jwt.private_key_path / jwt.public_key_path 의 PEM 파일을 읽는다.
+    // 교체 중인 이전 키는 jwt.previous_secrets(JWT_PREVIOUS_SECRETS, HMAC 비밀),
+    // jwt.previous_public_key_paths(JWT_PREVIOUS_PUBLIC_KEY_PATHS, 공개키 PEM 경로)로 지정한다.
+    // 필요한 값이 있는지는 설정을 읽을 때 확인했다. 파일을 읽지 못하거나 키가 아니면 KeyError.
+    pub fn from_config(config: &JwtConfig) -> Result<Self, KeyError> {
+        let algorithm = config.algorithm.as_str();
+        let (signing_algorithm, encoding, current) = match algorithm {
+            "HS256" => {
+                let secret = config.secret.as_deref().unwrap_or_default();
+                (Algorithm::HS256, EncodingKey::from_secret(secret.as_ref()), hmac_key(secret))
+            }
+            "RS256" | "EdDSA" => {
+                let private_path = config.private_key_path.as_deref().unwrap_or_default();
+                let public_path = config.public_key_path.as_deref().unwrap_or_default();
+                let private_pem = read_key("jwt.private_key_path", private_path)?;
+                let public = public_key(&read_key("jwt.public_key_path", public_path)?).map_err(|e| {
+                    KeyError::new("jwt.public_key_path", format!("'{}' is not a valid public key: {}", public_path, e))
+                })?;
+                let expected = if algorithm == "RS256" { Algorithm::RS256 } else { Algorithm::EdDSA };
+                if public.algorithm != expected {
+                    return Err(KeyError::new(
+                        "jwt.public_key_path",
+                        format!("'{}' does not contain a {} key", public_path, algorithm),
+                    ));
+                }
+                let encoding = match expected {
+                    Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+                    _ => EncodingKey::from_ed_pem(&private_pem),
+                }
+                .map_err(|e| {
+                    KeyError::new(
+                        "jwt.private_key_path",
+                        format!("'{}' is not a valid {} private key: {}", private_path, algorithm, e),
+                    )
+                })?;
+                (public.algorithm, encoding, public)
+            }
+            other => {
+                return Err(KeyError::new(
+                    "jwt.algorithm",
+                    format!("unsupported algorithm '{}' (expected HS256, RS256 or EdDSA)", other),
+                ))
+            }
+        };
+
+        let mut keys = Self {
+            signing_kid: current.kid.clone(),
+            signing_algorithm,
+            encoding,
+            verification: vec![current],
+        };
+
+        for secret in &config.previous_secrets {
+            keys.verification.push(hmac_key(secret));
+        }
+        for path in &config.previous_public_key_paths {
+            let key = public_key(&read_key("jwt.previous_public_key_paths", path)?).map_err(|e| {
+                KeyError::new("jwt.previous_public_key_paths", format!("'{}' is not a valid public key: {}", path, e))
+            })?;
+            keys.verification.push(key);
+        }
+        Ok(keys)
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.signing_algorithm
+    }
+
+    pub fn kids(&self) -> Vec<&str> {
+        self.verification.iter().map(|k| k.kid.as_str()).collect()
+    }
+
+    // 현재 키로 서명하고 헤더에 kid 를 남긴다
+    pub fn encode<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let mut header = Header::new(self.signing_algorithm);
+        header.kid = Some(self.signing_kid.clone());
+        jsonwebtoken::encode(&header, claims, &self.encoding)
+    }
+
+    // kid 가 있으면 해당 키로, 없으면 (교체 기능 이전에 발급된 토큰) 모든 키로 검증해 본다
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> jsonwebtoken::errors::Result<T> {
+        let header = decode_header(token)?;
+        let candidates: Vec<&VerificationKey> = match &header.kid {
+            Some(kid) => self.verification.iter().filter(|k| &k.kid == kid).collect(),
+            None => self.verification.iter().collect(),
+        };
+
+        let mut last_err = jsonwebtoken::errors::ErrorKind::InvalidSignature.into();
+        for key in candidates.into_iter().filter(|k| k.algorithm == header.alg) {
+            match decode::<T>(token, &key.decoding, &Validation::new(key.algorithm)) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) => last_err = e,
+            }
+        }
+        Err(last_err)
+    }
+
+    // JWKS 문서. 대칭 키(HS256)는 공개하지 않는다.
+    pub fn jwks(&self) -> Value {
+        let keys: Vec<&Value> = self.verification.iter().filter_map(|k| k.jwk.as_ref()).collect();
+        json!({ "keys": keys })
+    }
+}
+
+fn hmac_key(secret: &str) -> VerificationKey {
+    // 비밀 자체를 드러내지 않도록 해시의 앞부분만 식별자로 쓴다
+    let kid = format!("hs-{}", URL_SAFE_NO_PAD.encode(&Sha256::digest(secret.as_bytes())[..9]));
+    VerificationKey {
+        kid,
+        algorithm: Algorithm::HS256,
+        decoding: DecodingKey::from_secret(secret.as_ref()),
+        jwk: None,
+    }
+}
+
+// PEM 공개키에서 알고리즘, kid, JWK 를 만든다 (RSA 또는 Ed25519)
+fn public_key(pem: &[u8]) -> Result<VerificationKey, String> {
+    let der = pem_to_der(pem)?;
+    let kid = URL_SAFE_NO_PAD.encode(&Sha256::digest(&der)[..12]);
+
+    // Ed25519 SubjectPublicKeyInfo 는 44바이트이고, 마지막 32바이트가 원본 공개키다
+    if der.len() == 44 {
+        let jwk = json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": kid,
+            "x": URL_SAFE_NO_PAD.encode(&der[12..]),
+        });
+        return Ok(VerificationKey {
+            kid,
+            algorithm: Algorithm::EdDSA,
+            decoding: DecodingKey::from_ed_pem(pem).map_err(|e| e.to_string())?,
+            jwk: Some(jwk),
+        });
+    }
+
+    let public = RsaPublicKey::from_public_key_der(&der)
+        .or_else(|_| RsaPublicKey::from_pkcs1_der(&der))
+        .map_err(|e| e.to_string())?;
+    let jwk = json!({
+        "kty": "RSA",
+        "use": "sig",
+        "alg": "RS256",
+        "kid": kid,
+        "n": URL_SAFE_NO_PAD.encode(public.n().to_bytes_be()),
+        "e": URL_SAFE_NO_PAD.encode(public.e().to_bytes_be()),
+    });
+    Ok(VerificationKey {
+        kid,
+        algorithm: Algorithm::RS256,
+        decoding: DecodingKey::from_rsa_pem(pem).map_err(|e| e.to_string())?,
+        jwk: Some(jwk),
+    })
+}
+
+fn read_key(setting: &'static str, path: &str) -> Result<Vec<u8>, KeyError> {
+    fs::read(path).map_err(|e| KeyError::new(setting, format!("cannot read '{}': {}", path, e)))
+}
+
+fn pem_to_der(pem: &[u8]) -> Result<Vec<u8>, String> {
+    let pem = std::str::from_utf8(pem).map_err(|e| e.to_string())?;
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<Vec<_>>()
+        .concat();
+    STANDARD.decode(body.trim()).map_err(|e| e.to_string())
+}
+
+// GET /.well-known/jwks.json
+pub async fn jwks_handler() -> impl IntoResponse {
+    Json(jwt_keys().jwks())
+}
@@ -0,0 +1,194 @@
+// --- 키워드 알림 ---
+//
+// 사용자는 `POST /me/keywords` 로 지켜볼 단어나 구절을 등록한다. 어느 방에서든(방을 정했으면 그 방에서만)
+// 메시지에 그 구절이 나오면 언급이 없어도 `keyword` 알림을 받는다. 알림을 끈(mute) 방은 빼고, 방별 설정의
+// 나머지 규칙은 `notifications` 모듈이 따른다.
+//
+// 비교는 금칙어 필터처럼 NFKC 정규화 후 대소문자를 무시하고 단어 단위로 한다. 메시지마다 모든 키워드를 훑지
+// 않도록, 메모리에 방(전체 방은 None)별로 구절의 첫 단어를 키로 하는 색인을 두고 메시지의 단어로만 찾는다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::RwLock,
+};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    auth::AuthUser,
+    error::{ApiError, ApiJson},
+    repo::{Keyword, RepoError, RepoResult, Repos},
+    validation::ValidationErrors,
+    AppState,
+};
+
+const KEYWORD_MAX_CHARS: usize = 64;
+const KEYWORD_MAX_WORDS: usize = 5;
+// 사용자 한 명이 등록할 수 있는 키워드 수
+const KEYWORDS_PER_USER_MAX: i64 = 25;
+
+struct Watch {
+    id: i64,
+    user_id: i32,
+    words: Vec<String>,
+}
+
+// 방 -> 첫 단어 -> 그 단어로 시작하는 키워드
+type Index = HashMap<Option<String>, HashMap<String, Vec<Watch>>>;
+
+#[derive(Default)]
+pub struct KeywordIndex {
+    index: RwLock<Index>,
+}
+
+// 정규화한 단어 목록
+fn words(text: &str) -> Vec<String> {
+    text.nfkc()
+        .collect::<String>()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl KeywordIndex {
+    pub async fn load(repos: &Repos) -> RepoResult<Self> {
+        let keywords = repos.keywords.all().await?;
+        let index = Self::default();
+        for keyword in &keywords {
+            index.insert(keyword);
+        }
+        Ok(index)
+    }
+
+    fn insert(&self, keyword: &Keyword) {
+        let words: Vec<String> = keyword.keyword.split(' ').map(str::to_string).collect();
+        let watch = Watch { id: keyword.id, user_id: keyword.user_id, words };
+        self.index
+            .write()
+            .unwrap()
+            .entry(keyword.room.clone())
+            .or_default()
+            .entry(watch.words[0].clone())
+            .or_default()
+            .push(watch);
+    }
+
+    fn remove(&self, id: i64) {
+        let mut index = self.index.write().unwrap();
+        for by_word in index.values_mut() {
+            for watches in by_word.values_mut() {
+                watches.retain(|w| w.id != id);
+            }
+            by_word.retain(|_, watches| !watches.is_empty());
+        }
+        index.retain(|_, by_word| !by_word.is_empty());
+    }
+
+    // room 의 메시지에 등록한 키워드가 나오는 사용자
+    pub fn matches(&self, room: &str, text: &str) -> HashSet<i32> {
+        let mut users = HashSet::new();
+        let index = self.index.read().unwrap();
+        let scopes: Vec<&HashMap<String, Vec<Watch>>> =
+            [index.get(&None), index.get(&Some(room.to_string()))].into_iter().flatten().collect();
+        if scopes.is_empty() {
+            return users;
+        }
+        let words = words(text);
+        for (i, word) in words.iter().enumerate() {
+            for watches in scopes.iter().filter_map(|by_word| by_word.get(word)) {
+                for watch in watches {
+                    if words[i..].starts_with(&watch.words) {
+                        users.insert(watch.user_id);
+                    }
+                }
+            }
+        }
+        users
+    }
+}
+
+// --- API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateKeywordPayload {
+    keyword: String,
+    #[serde(default)]
+    room: Option<String>,
+}
+
+// GET /me/keywords
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.keywords.list(claims.user_id).await {
+        Ok(keywords) => Json(keywords).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/keywords
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<CreateKeywordPayload>,
+) -> Response {
+    let keyword = words(&payload.keyword).join(" ");
+    let room = payload.room.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    let mut errors = ValidationErrors::default();
+    if keyword.is_empty() {
+        errors.add("keyword", "required", "keyword must contain a letter or digit");
+    } else if keyword.chars().count() > KEYWORD_MAX_CHARS {
+        errors.add("keyword", "too_long", format!("keyword must be at most {} characters", KEYWORD_MAX_CHARS));
+    } else if keyword.split(' ').count() > KEYWORD_MAX_WORDS {
+        errors.add("keyword", "too_many_words", format!("keyword must be at most {} words", KEYWORD_MAX_WORDS));
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    match state.repos.keywords.count(claims.user_id).await {
+        Ok(count) if count >= KEYWORDS_PER_USER_MAX => {
+            return ApiError::conflict(
+                "keyword_limit_reached",
+                format!("You can watch at most {} keywords", KEYWORDS_PER_USER_MAX),
+            )
+            .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    match state.repos.keywords.create(claims.user_id, &keyword, room.as_deref()).await {
+        Ok(keyword) => {
+            state.keywords.insert(&keyword);
+            (StatusCode::CREATED, Json(keyword)).into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            ApiError::conflict("keyword_exists", "You are already watching this keyword").into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/keywords/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.keywords.delete(id, claims.user_id).await {
+        Ok(true) => {
+            state.keywords.remove(id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("keyword_not_found", "Keyword not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
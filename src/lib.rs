@@ -0,0 +1,357 @@
+// --- WebChat ---
+//
+// 채팅 서버 전체를 라이브러리로 묶었다. 바이너리(main.rs)는 명령줄을 읽어 `cli` 로 넘길 뿐이다.
+//
+// 다른 axum 앱에 붙이려면 `Server::builder()` 로 서버를 만들고 `router()` 를 그 앱의 라우터에 merge 한다.
+// 빌드할 때 설정을 읽고(또는 넘겨받은 `Config` 를 쓰고) DB 마이그레이션과 백그라운드 태스크를 시작한다. 정적
+// 프론트엔드와 메일 속 링크는 루트에 붙었다고 가정하고, 클라이언트 주소를 읽으므로 앱은
+// `into_make_service_with_connect_info::<SocketAddr>()` 로 띄워야 한다. 설정과 지표, 백그라운드 태스크는
+// 프로세스에 하나뿐이라 서버도 한 프로세스에 하나만 만든다.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::sync::Arc;
+
+mod account;
+mod admin;
+mod anonymization;
+mod attachments;
+mod audit;
+mod auth;
+mod avatar;
+mod bots;
+mod bus;
+mod capabilities;
+mod captcha;
+pub mod cli;
+mod client_ip;
+mod cluster;
+mod commands;
+pub mod config;
+mod connections;
+mod cors;
+mod digest;
+mod email_verification;
+mod ephemeral;
+mod error;
+mod export;
+mod forwarding;
+mod friends;
+mod grpc;
+mod guest;
+mod health;
+mod history;
+mod imports;
+mod invites;
+mod keyword_alerts;
+mod ip_bans;
+mod keys;
+mod link_preview;
+mod logging;
+mod magic_link;
+mod mail;
+mod maintenance;
+mod markdown;
+mod message_writer;
+mod messages;
+mod moderation;
+mod monitoring;
+mod notifications;
+mod oauth;
+mod passkeys;
+mod password;
+mod password_reset;
+mod polls;
+mod presence;
+mod profile;
+mod protocol;
+mod reminders;
+mod scheduled_messages;
+mod reports;
+mod repo;
+mod request_id;
+mod request_limits;
+mod retention;
+mod room_registry;
+mod room_export;
+mod room_owners;
+mod revocation;
+mod schema;
+mod security_headers;
+mod server;
+mod session;
+mod shutdown;
+mod spam;
+mod sse;
+mod stars;
+mod storage;
+mod telemetry;
+mod throttle;
+mod thumbnail;
+mod tls;
+mod two_factor;
+mod validation;
+mod voice;
+mod web_push;
+mod webhooks;
+mod word_filter;
+mod ws;
+
+#[cfg(test)]
+mod tests;
+
+use auth::AuthUser;
+use bus::MessageBus;
+use connections::{ConnectionLimits, ConnectionRegistry};
+use mail::Mailer;
+use password::Passwords;
+use repo::{NewUser, RepoError, Repos};
+use revocation::RevocationStore;
+use room_registry::RoomRegistry;
+use storage::Storage;
+use captcha::CaptchaError;
+use commands::CommandRegistry;
+use error::{ApiError, ApiJson};
+use keyword_alerts::KeywordIndex;
+use session::{start_session, ClientInfo};
+use spam::SpamDetector;
+use validation::ValidationErrors;
+use web_push::WebPush;
+use word_filter::WordFilter;
+
+pub use server::{Server, ServerBuilder};
+
+// --- 모델 및 상태 정의 ---
+
+// 인증 요청 페이로드
+#[derive(Debug, Deserialize)]
+struct AuthPayload {
+    username: String,
+    password: String,
+    // 회원가입 시 입력. REQUIRE_VERIFIED_EMAIL=false 이면 생략할 수 있다.
+    #[serde(default)]
+    email: Option<String>,
+    // 회원가입 시 입력. REQUIRE_INVITE=true 일 때만 필요하다.
+    #[serde(default)]
+    invite_code: Option<String>,
+    // CAPTCHA_PROVIDER 가 설정된 경우 위젯이 만든 토큰
+    #[serde(default)]
+    captcha_token: Option<String>,
+}
+
+// 애플리케이션 공유 상태
+#[derive(Clone)]
+struct AppState {
+    // 사용자, 메시지, 방 정보
+    repos: Repos,
+    // 이 서버에 열린 방
+    rooms: Arc<RoomRegistry>,
+    // 방 이벤트 전달
+    bus: Arc<dyn MessageBus>,
+    passwords: Arc<Passwords>,
+    revoked_tokens: Arc<RevocationStore>,
+    connections: Arc<ConnectionRegistry>,
+    // 사용자별, IP 별 웹소켓 연결 수
+    connection_limits: Arc<ConnectionLimits>,
+    mailer: Arc<Mailer>,
+    avatars: Arc<dyn Storage>,
+    attachments: Arc<dyn Storage>,
+    word_filter: Arc<WordFilter>,
+    // 사용자가 지켜보는 키워드의 방별 색인
+    keywords: Arc<KeywordIndex>,
+    push: Arc<WebPush>,
+    spam: Arc<SpamDetector>,
+    guest_posts: Arc<guest::PostInterval>,
+    // 웹훅별 메시지 속도 제한
+    webhook_posts: Arc<webhooks::PostRate>,
+    // 슬래시 명령
+    commands: Arc<CommandRegistry>,
+    reminders: Arc<reminders::Scheduler>,
+    scheduled_messages: Arc<scheduled_messages::Scheduler>,
+    // 자동으로 사라지는 메시지의 정리 태스크
+    ephemeral: Arc<ephemeral::Sweeper>,
+    polls: Arc<polls::Scheduler>,
+    // 메시지 저장 대기열
+    message_writer: Arc<message_writer::MessageWriter>,
+    shutdown: Arc<shutdown::Shutdown>,
+}
+
+async fn get_rooms_handler(State(state): State<AppState>, _user: AuthUser) -> impl IntoResponse {
+    Json(state.bus.rooms())
+}
+
+// --- 설정 ---
+
+// 외부에서 접근하는 기준 주소 (메일 속 링크, OAuth 리다이렉트 URI 등)
+static PUBLIC_URL: Lazy<String> = Lazy::new(|| {
+    config::get().server.public_url
+        .trim_end_matches('/')
+        .to_string()
+});
+
+// --- 데이터베이스 ---
+
+// 데이터베이스 연결 풀 생성
+async fn connect_db() -> Result<PgPool, String> {
+    let database = &config::get().database;
+    let db_url = database.url.as_deref().expect("database.url is validated");
+    PgPoolOptions::new()
+        .max_connections(database.max_connections)
+        .connect(db_url)
+        .await
+        .map_err(|e| format!("Failed to connect to the database: {}", e))
+}
+
+// --- 핸들러 함수들 ---
+
+// 회원가입 핸들러
+async fn register_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    ApiJson(payload): ApiJson<AuthPayload>,
+) -> impl IntoResponse {
+    let username = validation::normalize_username(&payload.username);
+    let email = payload.email.as_deref().map(str::trim).filter(|e| !e.is_empty());
+
+    let mut errors = ValidationErrors::default();
+    validation::check_username(&username, &mut errors);
+    validation::check_password("password", &payload.password, Some(&username), &mut errors);
+    match email {
+        Some(email) => validation::check_email(email, &mut errors),
+        None if *email_verification::REQUIRE_VERIFIED_EMAIL => {
+            errors.add("email", "required", "Email is required")
+        }
+        None => {}
+    }
+    let invite_code = payload.invite_code.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if *invites::REQUIRE_INVITE && invite_code.is_none() {
+        errors.add("invite_code", "required", "An invite code is required to register");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    // 토큰은 한 번만 검증할 수 있으므로 입력 검사를 통과한 뒤에 확인한다
+    match captcha::verify(payload.captcha_token.as_deref(), client.ip.as_deref()).await {
+        Ok(()) => {}
+        Err(CaptchaError::Missing) => {
+            return ValidationErrors::single("captcha_token", "required", "Please complete the CAPTCHA").into_response()
+        }
+        Err(CaptchaError::Rejected) => {
+            return ValidationErrors::single("captcha_token", "invalid", "CAPTCHA verification failed").into_response()
+        }
+        Err(CaptchaError::Unavailable) => {
+            return ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "captcha_unavailable",
+                "CAPTCHA verification is temporarily unavailable",
+            )
+            .into_response()
+        }
+    }
+
+    let hashed_password = match state.passwords.hash_async(&payload.password).await {
+        Ok(h) => h,
+        Err(_) => return ApiError::internal().into_response(),
+    };
+
+    // 가입이 실패하면 초대 코드의 사용 횟수도 함께 되돌려진다
+    let new_user = NewUser {
+        username: &username,
+        password_hash: &hashed_password,
+        email,
+        invite_code: invite_code.filter(|_| *invites::REQUIRE_INVITE),
+    };
+    match state.repos.users.create(&new_user).await {
+        Ok(user) => {
+            if let Some(email) = email {
+                if let Err(e) = email_verification::send_verification(&state, user.id, email).await {
+                    tracing::warn!("Failed to create verification token for user {}: {}", user.id, e);
+                }
+            }
+            (StatusCode::CREATED, Json(json!({ "id": user.id, "username": user.username }))).into_response()
+        }
+        // 대소문자만 다른 이름도 겹치는 것으로 본다
+        Err(RepoError::Conflict("email")) => {
+            ValidationErrors::single("email", "taken", "Email is already in use").into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            ValidationErrors::single("username", "taken", "Username is already taken").into_response()
+        }
+        Err(RepoError::InvalidInvite) => {
+            ValidationErrors::single("invite_code", "invalid", "Invite code is invalid or expired").into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to create user: {}", e);
+            ApiError::internal().into_response()
+        }
+    }
+}
+
+// 로그인 핸들러
+async fn login_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    ApiJson(payload): ApiJson<AuthPayload>,
+) -> impl IntoResponse {
+    // 가입 때와 같이 정규화하고 대소문자를 구분하지 않는다
+    let user = match state.repos.users.find_by_name(&validation::normalize_username(&payload.username)).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            // 있는 사용자와 같은 시간을 쓰고 나서 거절한다
+            state.passwords.verify_dummy(&payload.password).await;
+            return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response();
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let verified = match user.password_hash.as_deref() {
+        Some(hash) => state.passwords.verify_async(&payload.password, hash).await.unwrap_or(false),
+        None => {
+            state.passwords.verify_dummy(&payload.password).await;
+            false
+        }
+    };
+    if !verified {
+        return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response();
+    }
+    let password_hash = user.password_hash.as_deref().unwrap_or_default();
+
+    // 저장된 해시가 예전 알고리즘(bcrypt)이거나 현재 설정보다 약하면 평문 비밀번호를 알고 있는 지금 다시 해싱.
+    // 그 사이 비밀번호가 바뀌었다면 덮어쓰지 않도록 기존 해시가 그대로일 때만 교체한다.
+    if state.passwords.needs_rehash(password_hash) {
+        match state.passwords.hash_async(&payload.password).await {
+            Ok(new_hash) => {
+                match state.repos.users.set_password_hash(user.id, &new_hash, Some(password_hash)).await {
+                    Ok(true) => tracing::info!(
+                        "Upgraded password hash for user {} to {:?}",
+                        user.id,
+                        state.passwords.algorithm()
+                    ),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Failed to upgrade password hash for user {}: {}", user.id, e),
+                }
+            }
+            Err(e) => tracing::warn!("Failed to rehash password for user {}: {}", user.id, e),
+        }
+    }
+
+    // 2단계 인증이 켜져 있으면 토큰 대신 challenge 를 돌려준다
+    match two_factor::login_challenge(&state, user.id).await {
+        Ok(Some(challenge)) => return two_factor::challenge_response(&challenge),
+        Ok(None) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    start_session(&state, jar, user.id, &user.username, client).await
+}
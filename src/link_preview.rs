@@ -0,0 +1,392 @@
+// --- 링크 미리보기 ---
+//
+// 메시지에 http(s) URL 이 있으면 서버가 페이지를 가져와 OpenGraph 제목/설명/이미지를 뽑고, 메시지를 보낸 뒤에
+// 같은 방에 `link_preview` 이벤트(message_id 로 원래 메시지를 가리킨다)로 알린다. 결과는 실패까지 포함해
+// `link_previews` 테이블에 LINK_PREVIEW_CACHE_HOURS 동안 캐시한다.
+//
+// 서버가 사용자 대신 요청을 보내므로 SSRF 를 막는다: 80/443 포트만 쓰고, 호스트가 가리키는 주소가 하나라도
+// 사설/루프백/링크 로컬 등이면 거부하며, 검사한 주소로 연결을 고정한다. 리다이렉트는 직접 따라가면서 매번
+// 다시 검사한다. 응답은 text/html 만, LINK_PREVIEW_MAX_BYTES 까지만 읽는다.
+
+use once_cell::sync::Lazy;
+use reqwest::{header, redirect, StatusCode, Url};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use crate::{
+    config,
+    protocol::ServerEvent,
+    repo::{LinkPreview, Repos},
+    AppState,
+};
+
+const MAX_URLS_PER_MESSAGE: usize = 3;
+const MAX_REDIRECTS: usize = 3;
+const TITLE_MAX_CHARS: usize = 200;
+const DESCRIPTION_MAX_CHARS: usize = 500;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+static ENABLED: Lazy<bool> = Lazy::new(|| config::get().features.link_previews);
+static TIMEOUT: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().limits.link_preview_timeout_seconds));
+static MAX_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.link_preview_max_bytes);
+static CACHE_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::hours(config::get().limits.link_preview_cache_hours));
+
+impl LinkPreview {
+    fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image_url.is_none()
+    }
+}
+
+// 메시지에서 미리보기를 만들 URL 을 찾는다 (중복 제외, 최대 MAX_URLS_PER_MESSAGE 개)
+pub fn extract_urls(text: &str) -> Vec<Url> {
+    let mut urls: Vec<Url> = Vec::new();
+    for word in text.split_whitespace() {
+        let start = match word.find("https://").or_else(|| word.find("http://")) {
+            Some(start) => start,
+            None => continue,
+        };
+        // 문장 부호로 끝나는 경우가 많다
+        let candidate = word[start..].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '>', '"', '\'']);
+        if let Ok(url) = Url::parse(candidate) {
+            if url.host_str().is_some() && !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        if urls.len() == MAX_URLS_PER_MESSAGE {
+            break;
+        }
+    }
+    urls
+}
+
+// 메시지를 보낸 직후 부른다. 미리보기는 백그라운드에서 만들어 따로 보낸다.
+pub fn spawn(state: AppState, room: String, message_id: i64, text: &str) {
+    if !*ENABLED {
+        return;
+    }
+    let urls = extract_urls(text);
+    if urls.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for url in urls {
+            let preview = match cached_or_fetch(&state.repos, &url).await {
+                Some(preview) if !preview.is_empty() => preview,
+                _ => continue,
+            };
+            let event = ServerEvent::LinkPreview {
+                room: room.clone(),
+                message_id,
+                url: url.to_string(),
+                title: preview.title,
+                description: preview.description,
+                image_url: preview.image_url,
+                site_name: preview.site_name,
+            };
+            state.bus.publish(&room, event);
+        }
+    });
+}
+
+async fn cached_or_fetch(repos: &Repos, url: &Url) -> Option<LinkPreview> {
+    let cached = repos.link_previews.cached(url.as_str(), chrono::Utc::now() - *CACHE_TTL).await;
+    match cached {
+        Ok(Some(preview)) => return Some(preview),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!("Failed to read link preview cache: {}", e);
+            return None;
+        }
+    }
+
+    // 실패도 빈 미리보기로 캐시해서 같은 URL 을 계속 두드리지 않는다
+    let preview = fetch(url).await.unwrap_or_else(|e| {
+        tracing::info!("No link preview for {}: {}", url, e);
+        LinkPreview::default()
+    });
+    if let Err(e) = repos.link_previews.save(url.as_str(), &preview).await {
+        tracing::warn!("Failed to cache link preview for {}: {}", url, e);
+    }
+    Some(preview)
+}
+
+pub fn spawn_purge_task(repos: Repos) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = repos.link_previews.purge(chrono::Utc::now() - *CACHE_TTL).await {
+                tracing::warn!("Failed to purge link previews: {}", e);
+            }
+        }
+    });
+}
+
+// --- 가져오기 (SSRF 방어) ---
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || a == 0
+        // 100.64.0.0/10 (CGNAT), 198.18.0.0/15 (벤치마크), 240.0.0.0/4 (예약)
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (b == 18 || b == 19))
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(v4);
+    }
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 (고유 로컬), fe80::/10 (링크 로컬), 2001:db8::/32 (문서용)
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+// 연결해도 되는 주소 하나를 고른다. 호스트가 가리키는 주소가 하나라도 내부망이면 거부한다.
+pub async fn resolve_public(url: &Url) -> Result<SocketAddr, String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("unsupported scheme".to_string());
+    }
+    let port = url.port_or_known_default().ok_or("missing port")?;
+    if port != 80 && port != 443 {
+        return Err(format!("port {} is not allowed", port));
+    }
+    let host = url.host_str().ok_or("missing host")?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("lookup failed: {}", e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err("host has no addresses".to_string());
+    }
+    if let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+        return Err(format!("{} resolves to non-public address {}", host, addr.ip()));
+    }
+    Ok(addrs[0])
+}
+
+async fn fetch(url: &Url) -> Result<LinkPreview, String> {
+    let mut url = url.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        let addr = resolve_public(&url).await?;
+        // 검사한 주소로만 연결하도록 이 요청 전용 클라이언트를 만든다 (DNS 재바인딩 방지)
+        let mut client = reqwest::Client::builder()
+            .user_agent("WebChat link preview")
+            .timeout(*TIMEOUT)
+            .redirect(redirect::Policy::none());
+        if let Some(domain) = url.domain() {
+            client = client.resolve(domain, addr);
+        }
+        let client = client.build().map_err(|e| e.to_string())?;
+
+        let mut response = client
+            .get(url.clone())
+            .header(header::ACCEPT, "text/html")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or("redirect without location")?;
+            url = url.join(location).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if response.status() != StatusCode::OK {
+            return Err(format!("status {}", response.status()));
+        }
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.to_ascii_lowercase().starts_with("text/html"));
+        if !is_html {
+            return Err("not an HTML page".to_string());
+        }
+
+        // 큰 페이지라도 앞부분의 <head> 만 있으면 된다
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            let remaining = *MAX_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            if body.len() >= *MAX_BYTES {
+                break;
+            }
+        }
+        return Ok(parse_html(&String::from_utf8_lossy(&body), &url));
+    }
+    Err("too many redirects".to_string())
+}
+
+// --- HTML 파싱 ---
+
+// <meta property="og:..." content="..."> 와 <title> 만 본다. 완전한 HTML 파서는 아니다.
+fn parse_html(html: &str, base: &Url) -> LinkPreview {
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut description = None;
+    let mut og_image = None;
+    let mut site_name = None;
+
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find("<meta") {
+        let start = pos + offset;
+        let end = match lower[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let attrs = parse_attributes(&html[start + 5..end]);
+        let key = attrs
+            .iter()
+            .find(|(name, _)| name == "property" || name == "name")
+            .map(|(_, value)| value.to_ascii_lowercase());
+        let content = attrs.iter().find(|(name, _)| name == "content").map(|(_, value)| value.clone());
+        if let (Some(key), Some(content)) = (key, content) {
+            let slot = match key.as_str() {
+                "og:title" => Some(&mut og_title),
+                "og:description" => Some(&mut og_description),
+                "description" => Some(&mut description),
+                "og:image" | "og:image:url" => Some(&mut og_image),
+                "og:site_name" => Some(&mut site_name),
+                _ => None,
+            };
+            // 같은 태그가 여러 번 있으면 처음 것을 쓴다
+            if let Some(slot) = slot {
+                slot.get_or_insert(content);
+            }
+        }
+        pos = end;
+    }
+
+    let title = og_title.or_else(|| {
+        let start = lower.find("<title")?;
+        let start = start + lower[start..].find('>')? + 1;
+        let end = start + lower[start..].find("</title")?;
+        Some(html[start..end].to_string())
+    });
+    // 이미지 URL 은 페이지 기준으로 풀고 http(s) 만 남긴다
+    let image_url = og_image
+        .and_then(|image| base.join(decode_entities(&image).trim()).ok())
+        .filter(|image| matches!(image.scheme(), "http" | "https"))
+        .map(String::from);
+
+    LinkPreview {
+        title: clean_text(title, TITLE_MAX_CHARS),
+        description: clean_text(og_description.or(description), DESCRIPTION_MAX_CHARS),
+        image_url,
+        site_name: clean_text(site_name, TITLE_MAX_CHARS),
+    }
+}
+
+// 이름은 소문자로. 값이 없는 속성은 건너뛴다.
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = tag;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        let name_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        if name_end == 0 {
+            break;
+        }
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let Some(after_eq) = rest.strip_prefix('=') else {
+            continue;
+        };
+        rest = after_eq.trim_start();
+        let value;
+        match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = rest[1..].find(quote).map(|e| e + 1).unwrap_or(rest.len());
+                value = &rest[1..end];
+                rest = rest.get(end + 1..).unwrap_or("");
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                value = &rest[..end];
+                rest = &rest[end..];
+            }
+        }
+        attrs.push((name, value.to_string()));
+    }
+    attrs
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" | "#39" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// 엔티티를 풀고 공백을 하나로 모은 뒤 길이를 자른다. 비면 None.
+fn clean_text(text: Option<String>, max_chars: usize) -> Option<String> {
+    let text = decode_entities(&text?);
+    let text: String = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(max_chars)
+        .collect();
+    (!text.is_empty()).then_some(text)
+}
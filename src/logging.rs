@@ -0,0 +1,129 @@
+// --- 로그 설정 ---
+//
+// config.rs 의 logging 섹션을 따른다.
+// - level(LOG_LEVEL): 기본 수준과 모듈별 수준. EnvFilter 문법을 따른다 (예: `info,webchat::ws=debug,sqlx=warn`).
+// - format(LOG_FORMAT): `text`(기본, 한 줄), `pretty`(여러 줄로 읽기 좋게), `json`(한 줄에 JSON 객체 하나, 로그 수집기용)
+// - file(LOG_FILE): 설정하면 표준 출력과 함께 이 파일에도 쓴다. rotation(`daily` 기본, `hourly`, `minutely`,
+//   `never`)마다 새 파일로 바꾸고(`logs/webchat.log` 면 `logs/webchat.2026-10-16.log`), max_files 를 넘는
+//   오래된 파일은 지운다.
+//
+// OpenTelemetry 로 span 을 내보내는 층도 여기서 함께 붙인다 (telemetry.rs). 수준은 로그와 내보내는 span 에만
+// 걸린다. tokio-console 기능으로 빌드하면 tokio 의 태스크 정보를 수준과 관계없이 tokio-console 에 보낸다
+// (기본 주소 127.0.0.1:6669, TOKIO_CONSOLE_BIND 로 바꾼다). 이때는 수준을 층마다 따로 거는데, 이것은 `log` 크레이트의
+// 기록을 tracing 으로 옮기는 다리와 함께 쓰면 뒤따르는 로그 일부를 빠뜨리므로 다리를 놓지 않는다. 그래서 `log` 로
+// 남기는 의존성의 로그는 보이지 않는다.
+
+#[cfg(all(feature = "tokio-console", not(tokio_unstable)))]
+compile_error!("the tokio-console feature needs RUSTFLAGS=\"--cfg tokio_unstable\"");
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+use tracing::Subscriber;
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+#[cfg(not(feature = "tokio-console"))]
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    EnvFilter, Layer,
+};
+
+use crate::{config, telemetry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,
+    Pretty,
+    Json,
+}
+
+// 로그 파일을 새로 여는 주기
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl From<LogRotation> for Rotation {
+    fn from(rotation: LogRotation) -> Self {
+        match rotation {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+// 로그를 초기화한다. 파일에 쓸 때 돌려받는 guard 는 main 이 끝날 때까지 들고 있어야 남은 로그가 파일에 쓰인다.
+pub fn init() -> Option<WorkerGuard> {
+    let logging = &config::get().logging;
+    // 수준은 설정을 읽을 때 검증했다
+    let filter = EnvFilter::new(&logging.level);
+    let format = logging.format;
+
+    let (file, guard) = match logging.file.as_deref().filter(|path| !path.is_empty()) {
+        Some(path) => {
+            let (writer, guard) = tracing_appender::non_blocking(file_appender(path, logging));
+            (Some(writer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let layers: Vec<_> = [
+        Some(output(format, io::stdout, true)),
+        file.map(|writer| output(format, writer, false)),
+        telemetry::layer().map(Layer::boxed),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    #[cfg(feature = "tokio-console")]
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::registry().with(layers.with_filter(filter)).with(console_subscriber::spawn()),
+    )
+    .expect("Failed to set the tracing subscriber");
+    #[cfg(not(feature = "tokio-console"))]
+    tracing_subscriber::registry().with(filter).with(layers).init();
+    guard
+}
+
+fn output<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Json => layer.json().flatten_event(true).boxed(),
+    }
+}
+
+fn file_appender(path: &str, logging: &config::LoggingConfig) -> RollingFileAppender {
+    let file = Path::new(path);
+    let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let prefix = file.file_stem().and_then(|stem| stem.to_str()).unwrap_or("webchat");
+
+    let mut builder = RollingFileAppender::builder()
+        .rotation(logging.rotation.into())
+        .filename_prefix(prefix);
+    if let Some(extension) = file.extension().and_then(|extension| extension.to_str()) {
+        builder = builder.filename_suffix(extension);
+    }
+    if logging.max_files > 0 {
+        builder = builder.max_log_files(logging.max_files);
+    }
+    fs::create_dir_all(dir)
+        .and_then(|_| builder.build(dir).map_err(io::Error::other))
+        .unwrap_or_else(|e| panic!("logging.file has an invalid value: '{}' ({})", path, e))
+}
@@ -0,0 +1,92 @@
+// --- 매직 링크 로그인 (비밀번호 없이 메일로 로그인) ---
+//
+// `POST /login/magic` 으로 주소를 받으면 한 번만 쓸 수 있는 링크를 메일로 보낸다. 링크의 페이지가
+// `POST /login/magic/verify` 로 토큰을 제출하면 세션이 시작된다. 메일 보안 스캐너가 링크를 미리
+// 열어도 토큰이 소모되지 않도록 GET 이 아니라 페이지에서 POST 한다.
+// 링크는 요청할 때 그 주소를 가진 계정에 묶이고, 그 계정의 주소가 그대로일 때만 쓸 수 있다. 계정을 만들거나
+// 다른 계정에 붙이지 않는다 (가입은 `/register` 로만 한다).
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{
+    auth::{generate_token, hash_token},
+    config,
+    error::{ApiError, ApiJson},
+    session::{start_session, ClientInfo},
+    two_factor, AppState, PUBLIC_URL,
+};
+
+static MAGIC_LINK_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::minutes(config::get().auth.magic_link_ttl_minutes));
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequest {
+    email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkVerify {
+    token: String,
+}
+
+// POST /login/magic: 계정 존재 여부를 드러내지 않도록 항상 202
+pub async fn request_handler(State(state): State<AppState>, ApiJson(payload): ApiJson<MagicLinkRequest>) -> Response {
+    let email = payload.email.trim().to_string();
+    if !email.contains('@') {
+        return ApiError::bad_request("invalid_email", "Invalid email address").into_response();
+    }
+
+    let user_id = match state.repos.accounts.find_by_email(&email).await {
+        Ok(Some((user_id, _))) => user_id,
+        Ok(None) => return StatusCode::ACCEPTED.into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let token = generate_token();
+    let expires_at = chrono::Utc::now() + *MAGIC_LINK_TTL;
+    if state.repos.tokens.add_magic_link(user_id, &email, &hash_token(&token), expires_at).await.is_err() {
+        return ApiError::internal().into_response();
+    }
+
+    let link = format!("{}/static/magic.html#token={}", *PUBLIC_URL, token);
+    let body = format!(
+        "Open this link within {} minutes to sign in to WebChat:\n{}\n\n\
+         If you didn't ask for it, you can ignore this email.",
+        MAGIC_LINK_TTL.num_minutes(),
+        link
+    );
+    state.mailer.send_in_background(email, "Your WebChat sign-in link", body);
+    StatusCode::ACCEPTED.into_response()
+}
+
+// POST /login/magic/verify
+// 링크를 연 것으로 주소의 소유가 확인되므로, 링크를 요청한 계정의 주소가 아직 같으면 확인 처리한다.
+// 그 사이 주소가 바뀌었으면 링크는 쓸 수 없다.
+pub async fn verify_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    ApiJson(payload): ApiJson<MagicLinkVerify>,
+) -> Response {
+    let (user_id, username) = match state.repos.tokens.use_magic_link(&hash_token(&payload.token)).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return ApiError::unauthorized("invalid_link", "Invalid or expired sign-in link").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    tracing::info!("User {} signed in with a magic link", user_id);
+
+    match two_factor::login_challenge(&state, user_id).await {
+        Ok(Some(challenge)) => return two_factor::challenge_response(&challenge),
+        Ok(None) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    start_session(&state, jar, user_id, &username, client).await
+}
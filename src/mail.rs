@@ -0,0 +1,99 @@
+// --- 메일 발송 (SMTP) ---
+//
+// mail.smtp_host(SMTP_HOST)가 설정되지 않은 개발 환경에서는 메일을 보내지 않고 로그로만 남긴다.
+
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::{authentication::Credentials, AsyncSmtpTransportBuilder},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, MailConfig};
+
+// SMTP 서버와의 연결 보호 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    Starttls,
+    Tls,
+    None,
+}
+
+pub struct Mailer {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: String,
+}
+
+impl Mailer {
+    // mail 설정 섹션으로부터 구성. 값은 설정을 읽을 때 check 로 검증했다.
+    pub fn configured() -> Self {
+        let mail = &config::get().mail;
+        let transport = mail.smtp_host.as_deref().map(|host| {
+            let mut builder = builder(host, mail.smtp_tls).expect("mail.smtp_host is validated");
+            if let Some(port) = mail.smtp_port {
+                builder = builder.port(port);
+            }
+            if let (Some(user), Some(pass)) = (&mail.smtp_username, &mail.smtp_password) {
+                builder = builder.credentials(Credentials::new(user.clone(), pass.clone()));
+            }
+            builder.build()
+        });
+
+        if transport.is_none() {
+            tracing::warn!("mail.smtp_host not set; outgoing mail will only be logged");
+        }
+
+        Self { transport, from: mail.from.clone() }
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        let Some(transport) = &self.transport else {
+            tracing::info!("[mail] to={} subject={:?}\n{}", to, subject, body);
+            return Ok(());
+        };
+
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|e| format!("invalid mail.from: {}", e))?)
+            .to(to.parse().map_err(|e| format!("invalid recipient: {}", e))?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)
+            .map_err(|e| e.to_string())?;
+
+        transport.send(message).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    // 응답 시간을 통해 계정 존재 여부가 드러나지 않도록 백그라운드에서 보낸다
+    pub fn send_in_background(self: &std::sync::Arc<Self>, to: String, subject: &'static str, body: String) {
+        let mailer = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(&to, subject, body).await {
+                tracing::warn!("Failed to send mail '{}': {}", subject, e);
+            }
+        });
+    }
+}
+
+fn builder(host: &str, tls: SmtpTls) -> Result<AsyncSmtpTransportBuilder, lettre::transport::smtp::Error> {
+    match tls {
+        SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+    }
+}
+
+// 설정 검증용: 보내는 주소와 SMTP 호스트를 쓸 수 있는지
+pub fn check(mail: &MailConfig) -> Vec<(&'static str, String)> {
+    let mut problems = Vec::new();
+    if let Err(e) = mail.from.parse::<Mailbox>() {
+        problems.push(("mail.from", format!("is not a mail address ({})", e)));
+    }
+    if let Some(Err(e)) = mail.smtp_host.as_deref().map(|host| builder(host, mail.smtp_tls)) {
+        problems.push(("mail.smtp_host", e.to_string()));
+    }
+    if mail.smtp_username.is_some() != mail.smtp_password.is_some() {
+        problems.push(("mail.smtp_password", "must be set together with mail.smtp_username".to_string()));
+    }
+    problems
+}
@@ -0,0 +1,100 @@
+// --- 점검 모드 ---
+//
+// 배포나 DB 마이그레이션 동안 새 접속을 막는다. 관리자가 `PUT /admin/maintenance` 로 켜면 새 로그인(가입, 게스트,
+// 2단계 인증, 매직 링크, 패스키, OAuth 포함)과 웹소켓 업그레이드, SSE 스트림을 503 `maintenance` 와 안내 문구로
+// 거절한다. 이미 열린 연결과 로그인 세션은 그대로 두어 스스로 끝나게 한다(토큰 갱신도 된다).
+//
+// 상태는 `maintenance` 테이블의 한 행에 두므로 서버를 다시 띄우거나 여러 대를 띄워도 같다.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    audit::{self, Target},
+    auth::AdminUser,
+    error::{ApiError, ApiJson},
+    validation::ValidationErrors,
+    AppState,
+};
+
+const MESSAGE_MAX_CHARS: usize = 500;
+const DEFAULT_MESSAGE: &str = "The server is under maintenance; please try again later";
+
+// 점검 중이면 거절 응답을 돌려준다. DB 오류가 나면 막지 않는다.
+pub async fn check(state: &AppState) -> Result<(), ApiError> {
+    match state.repos.maintenance.get().await {
+        Ok(maintenance) if maintenance.enabled => Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "maintenance",
+            maintenance.message.unwrap_or_else(|| DEFAULT_MESSAGE.to_string()),
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Failed to check maintenance mode: {}", e);
+            Ok(())
+        }
+    }
+}
+
+// 로그인 라우트에 거는 미들웨어
+pub async fn enforce(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    if let Err(e) = check(&state).await {
+        tracing::info!("Rejected {} {} during maintenance", req.method(), req.uri().path());
+        return e.into_response();
+    }
+    next.run(req).await
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenancePayload {
+    enabled: bool,
+    // 거절할 때 보여 줄 안내. 없으면 기본 문구.
+    #[serde(default)]
+    message: Option<String>,
+}
+
+// GET /admin/maintenance
+pub async fn get_handler(State(state): State<AppState>, _admin: AdminUser) -> Response {
+    match state.repos.maintenance.get().await {
+        Ok(maintenance) => Json(maintenance).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PUT /admin/maintenance
+pub async fn set_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<MaintenancePayload>,
+) -> Response {
+    let message = payload.message.as_deref().map(str::trim).filter(|m| !m.is_empty());
+    if message.is_some_and(|m| m.chars().count() > MESSAGE_MAX_CHARS) {
+        return ValidationErrors::single(
+            "message",
+            "too_long",
+            format!("message must be at most {} characters", MESSAGE_MAX_CHARS),
+        )
+        .into_response();
+    }
+
+    match state.repos.maintenance.set(payload.enabled, message, claims.user_id).await {
+        Ok(maintenance) => {
+            let (action, state_name) =
+                if maintenance.enabled { ("maintenance.enable", "on") } else { ("maintenance.disable", "off") };
+            tracing::warn!("User {} turned maintenance mode {}", claims.user_id, state_name);
+            let details = json!({ "message": maintenance.message });
+            audit::record(&state, &claims, action, Target::Setting("maintenance"), None, details).await;
+            Json(maintenance).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,250 @@
+// --- 마크다운 메시지 ---
+//
+// 클라이언트가 `format: "markdown"` 으로 보낸 메시지를 서버에서 HTML 로 바꿔 저장하고 보낸다. 입력의 HTML 은
+// 전부 이스케이프하고, 아래 문법으로 만든 태그만 내보내므로 결과는 다른 클라이언트가 innerHTML 로 넣어도 안전하다.
+//
+// 지원: **굵게**, *기울임* / _기울임_, ~~취소선~~, `코드`, ``` 코드 블록 ```, [글](http(s)/mailto 링크),
+// 줄 머리의 `> ` 인용과 `- ` / `* ` 목록, 줄바꿈. 링크 주소가 허용된 스킴이 아니면 글만 남긴다.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageFormat {
+    #[default]
+    Plain,
+    Markdown,
+    // `/me` 동작 메시지. 클라이언트가 직접 보낼 수는 없다.
+    #[serde(skip_deserializing)]
+    Action,
+}
+
+impl MessageFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MessageFormat::Plain => "plain",
+            MessageFormat::Markdown => "markdown",
+            MessageFormat::Action => "action",
+        }
+    }
+
+    // DB 에 저장된 값. 모르는 값이면 plain.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "markdown" => MessageFormat::Markdown,
+            "action" => MessageFormat::Action,
+            _ => MessageFormat::Plain,
+        }
+    }
+}
+
+const ALLOWED_SCHEMES: [&str; 3] = ["http://", "https://", "mailto:"];
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+enum Block {
+    Paragraph(Vec<String>),
+    Quote(Vec<String>),
+    List(Vec<String>),
+    Code(Vec<String>),
+}
+
+pub fn render(source: &str) -> String {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut in_code = false;
+    for line in source.lines() {
+        if line.trim_start().starts_with("```") {
+            if in_code {
+                in_code = false;
+            } else {
+                in_code = true;
+                blocks.push(Block::Code(Vec::new()));
+            }
+            continue;
+        }
+        if in_code {
+            if let Some(Block::Code(lines)) = blocks.last_mut() {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            // 빈 줄은 문단을 나눈다
+            blocks.push(Block::Paragraph(Vec::new()));
+        } else if let Some(rest) = trimmed.strip_prefix('>') {
+            let rest = rest.strip_prefix(' ').unwrap_or(rest).to_string();
+            match blocks.last_mut() {
+                Some(Block::Quote(lines)) => lines.push(rest),
+                _ => blocks.push(Block::Quote(vec![rest])),
+            }
+        } else if let Some(rest) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            match blocks.last_mut() {
+                Some(Block::List(items)) => items.push(rest.to_string()),
+                _ => blocks.push(Block::List(vec![rest.to_string()])),
+            }
+        } else {
+            match blocks.last_mut() {
+                Some(Block::Paragraph(lines)) => lines.push(line.to_string()),
+                _ => blocks.push(Block::Paragraph(vec![line.to_string()])),
+            }
+        }
+    }
+
+    let mut html = String::new();
+    for block in blocks {
+        match block {
+            Block::Paragraph(lines) if lines.is_empty() => {}
+            Block::Paragraph(lines) => {
+                html.push_str("<p>");
+                html.push_str(&render_lines(&lines));
+                html.push_str("</p>");
+            }
+            Block::Quote(lines) => {
+                html.push_str("<blockquote>");
+                html.push_str(&render_lines(&lines));
+                html.push_str("</blockquote>");
+            }
+            Block::List(items) => {
+                html.push_str("<ul>");
+                for item in items {
+                    html.push_str("<li>");
+                    html.push_str(&inline(&item));
+                    html.push_str("</li>");
+                }
+                html.push_str("</ul>");
+            }
+            // 닫지 않은 코드 블록도 끝까지 코드로 본다
+            Block::Code(lines) => {
+                html.push_str("<pre><code>");
+                html.push_str(&escape(&lines.join("\n")));
+                html.push_str("</code></pre>");
+            }
+        }
+    }
+    html
+}
+
+fn render_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| inline(line.trim())).collect::<Vec<_>>().join("<br>")
+}
+
+// 여는 표시 뒤에서 닫는 표시를 찾는다. 안쪽이 비어 있거나 공백으로 시작하면 강조가 아니다.
+fn find_closing(chars: &[char], start: usize, marker: &[char]) -> Option<usize> {
+    if chars.get(start).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    (start + 1..=chars.len().saturating_sub(marker.len())).find(|&i| chars[i..i + marker.len()] == *marker)
+}
+
+fn inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+
+        // 백슬래시 뒤의 문장 부호는 글자 그대로
+        if c == '\\' && chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) {
+            out.push_str(&escape(&chars[i + 1].to_string()));
+            i += 2;
+            continue;
+        }
+
+        if c == '`' {
+            if let Some(end) = (i + 1..chars.len()).find(|&j| chars[j] == '`') {
+                let code: String = chars[i + 1..end].iter().collect();
+                out.push_str(&format!("<code>{}</code>", escape(&code)));
+                i = end + 1;
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some((label, url, end)) = parse_link(&chars, i) {
+                let label = inline(&label);
+                if ALLOWED_SCHEMES.iter().any(|s| url.to_ascii_lowercase().starts_with(s)) {
+                    out.push_str(&format!(
+                        "<a href=\"{}\" rel=\"noopener noreferrer nofollow\" target=\"_blank\">{}</a>",
+                        escape(&url),
+                        label
+                    ));
+                } else {
+                    out.push_str(&label);
+                }
+                i = end;
+                continue;
+            }
+        }
+
+        let double = [("**", "strong"), ("__", "strong"), ("~~", "del")]
+            .into_iter()
+            .find(|(marker, _)| rest.starts_with(&marker.chars().collect::<Vec<_>>()));
+        if let Some((marker, tag)) = double {
+            let marker: Vec<char> = marker.chars().collect();
+            if let Some(end) = find_closing(&chars, i + 2, &marker) {
+                let inner: String = chars[i + 2..end].iter().collect();
+                out.push_str(&format!("<{}>{}</{}>", tag, inline(&inner), tag));
+                i = end + 2;
+                continue;
+            }
+        }
+
+        // snake_case 같은 단어 안의 밑줄은 강조가 아니다
+        let word_start = i == 0 || !chars[i - 1].is_alphanumeric();
+        if c == '*' || (c == '_' && word_start) {
+            if let Some(end) = find_closing(&chars, i + 1, &[c]) {
+                if c == '*' || chars.get(end + 1).is_none_or(|n| !n.is_alphanumeric()) {
+                    let inner: String = chars[i + 1..end].iter().collect();
+                    out.push_str(&format!("<em>{}</em>", inline(&inner)));
+                    i = end + 1;
+                    continue;
+                }
+            }
+        }
+
+        out.push_str(&escape(&c.to_string()));
+        i += 1;
+    }
+    out
+}
+
+// [label](url) → (label, url, 다음 위치)
+fn parse_link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let close = (start + 1..chars.len()).find(|&j| chars[j] == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    // 주소 안의 괄호는 짝이 맞으면 주소의 일부로 본다
+    let mut depth = 0;
+    let end = (close + 2..chars.len()).find(|&j| {
+        match chars[j] {
+            '(' => depth += 1,
+            ')' if depth == 0 => return true,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        false
+    })?;
+    let label: String = chars[start + 1..close].iter().collect();
+    let url: String = chars[close + 2..end].iter().collect::<String>().trim().to_string();
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, end + 1))
+}
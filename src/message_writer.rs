@@ -0,0 +1,147 @@
+// --- 메시지 저장 대기열 ---
+//
+// 메시지마다 INSERT 를 기다리면 DB 가 느릴 때 채팅도 함께 느려진다. messages::send 는 시퀀스에서 id 만 받아
+// 바로 방에 보내고, 저장할 행은 이 대기열에 넣는다. 저장 태스크는 쌓인 행을 WRITE_BATCH_MAX 개까지 모아 한 번의
+// INSERT 로 저장하고, 실패하면 간격을 늘려 가며 다시 시도한다. 끝내 실패하면 한 행 때문일 수 있으므로 하나씩
+// 저장해 본다. 링크 미리보기와 알림은 행이 저장된 뒤에 띄운다.
+//
+// 서버가 종료할 때는 flush 로 대기열이 빌 때까지 기다린다.
+//
+// id 는 MESSAGE_ID_BLOCK 개씩 미리 받아 둘 수 있다. 1 보다 크면 DB 를 덜 부르지만, 웹훅이나 첨부 파일 메시지,
+// 다른 서버가 받은 id 와 섞여 id 순서(기록의 순서)가 보낸 순서와 어긋날 수 있다.
+
+use once_cell::sync::Lazy;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, Mutex, Notify};
+
+use crate::{
+    config, link_preview, monitoring, notifications,
+    repo::{MessageRepo, NewMessage, RepoResult},
+    AppState,
+};
+
+static ID_BLOCK: Lazy<i64> = Lazy::new(|| config::get().database.message_id_block.max(1));
+
+// 저장을 기다리는 메시지 수. 가득 차면 보내는 쪽이 기다린다.
+const QUEUE_CAPACITY: usize = 10_000;
+// 한 번의 INSERT 로 저장하는 최대 수
+const WRITE_BATCH_MAX: usize = 200;
+const WRITE_ATTEMPTS: u32 = 5;
+// 첫 재시도까지의 간격. 시도할 때마다 두 배로 늘린다.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+pub struct MessageWriter {
+    queue: mpsc::Sender<NewMessage>,
+    // 미리 받아 둔 id
+    ids: Mutex<VecDeque<i64>>,
+    // 대기열에 넣었지만 아직 저장(또는 포기)하지 않은 수
+    pending: AtomicUsize,
+    idle: Notify,
+}
+
+impl MessageWriter {
+    // 저장 태스크에 넘길 수신 쪽을 함께 돌려준다
+    pub fn new() -> (Self, mpsc::Receiver<NewMessage>) {
+        let (queue, rx) = mpsc::channel(QUEUE_CAPACITY);
+        let writer = MessageWriter {
+            queue,
+            ids: Mutex::new(VecDeque::new()),
+            pending: AtomicUsize::new(0),
+            idle: Notify::new(),
+        };
+        (writer, rx)
+    }
+
+    pub async fn next_id(&self, messages: &dyn MessageRepo) -> RepoResult<i64> {
+        let mut ids = self.ids.lock().await;
+        if ids.is_empty() {
+            ids.extend(messages.next_ids(*ID_BLOCK).await?);
+        }
+        Ok(ids.pop_front().expect("the block has at least one id"))
+    }
+
+    pub async fn enqueue(&self, message: NewMessage) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if self.queue.send(message).await.is_err() {
+            self.done(1);
+            tracing::error!("The message writer has stopped; a message was not stored");
+        }
+    }
+
+    fn done(&self, count: usize) {
+        if self.pending.fetch_sub(count, Ordering::SeqCst) == count {
+            self.idle.notify_waiters();
+        }
+    }
+
+    // 대기열에 넣은 메시지가 모두 저장될 때까지 기다린다
+    pub async fn flush(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.pending.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            tracing::info!("Waiting for {} queued messages to be stored", self.pending.load(Ordering::SeqCst));
+            idle.await;
+        }
+    }
+}
+
+pub fn spawn_task(state: AppState, mut rx: mpsc::Receiver<NewMessage>) {
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(WRITE_BATCH_MAX);
+        while rx.recv_many(&mut batch, WRITE_BATCH_MAX).await > 0 {
+            let count = batch.len();
+            write(&state, std::mem::take(&mut batch)).await;
+            state.message_writer.done(count);
+        }
+    });
+}
+
+async fn write(state: &AppState, batch: Vec<NewMessage>) {
+    let mut delay = RETRY_BASE_DELAY;
+    for attempt in 1..=WRITE_ATTEMPTS {
+        match insert(state, &batch).await {
+            Ok(()) => {
+                stored(state, &batch);
+                return;
+            }
+            Err(e) => tracing::warn!("Failed to store {} messages (attempt {}): {}", batch.len(), attempt, e),
+        }
+        if attempt < WRITE_ATTEMPTS {
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    for message in batch {
+        let one = std::slice::from_ref(&message);
+        match insert(state, one).await {
+            Ok(()) => stored(state, one),
+            Err(e) => tracing::error!("Dropped message {} from '{}': {}", message.id, message.username, e),
+        }
+    }
+}
+
+async fn insert(state: &AppState, batch: &[NewMessage]) -> RepoResult<()> {
+    let started = Instant::now();
+    let inserted = state.repos.messages.insert_batch(batch).await;
+    monitoring::message_insert(started.elapsed());
+    inserted
+}
+
+// 저장된 메시지의 후속 작업. 미리보기와 알림은 방 전체로 가므로 그림자 차단된 메시지에는 띄우지 않는다.
+fn stored(state: &AppState, batch: &[NewMessage]) {
+    if batch.iter().any(|m| m.expires_at.is_some()) {
+        state.ephemeral.notify();
+    }
+    for message in batch.iter().filter(|m| !m.hidden) {
+        link_preview::spawn(state.clone(), message.room.clone(), message.id, &message.text);
+        let sender = (message.user_id, message.username.clone());
+        notifications::spawn_for_message(state.clone(), message.room.clone(), message.id, sender, &message.text);
+    }
+}
@@ -0,0 +1,261 @@
+// --- 메시지 저장과 전송 ---
+//
+// 웹소켓으로 받은 메시지와 예약 메시지가 함께 쓰는 부분. 글을 쓸 수 있는지 확인하고, 검사를 마친 본문을 저장
+// 대기열(`message_writer`)에 넣은 뒤 방에 `message` 이벤트로 보낸다. 링크 미리보기와 알림은 저장된 뒤에 뜬다.
+//
+// 사용자가 쓴 글은 웹소켓이든 `POST /rooms/:room/messages` 든 post 에서 같은 순서로 검사한다: 수명, 길이, 게스트
+// 간격, 글쓰기 조건(게스트, 메일 확인, 음소거), 금칙어, 스팸. 방에 글을 쓰거나 이벤트를 보내는 슬래시 명령은
+// 실행하기 전에 명령 그대로의 본문으로 금칙어를 뺀 검사를 거치고, 명령이 만든 본문을 금칙어 검사에 넘긴다.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use axum::http::StatusCode;
+
+use crate::{
+    auth::Claims,
+    commands::{CommandContext, CommandRegistry, Outcome, Parsed},
+    email_verification, ephemeral,
+    error::ApiError,
+    guest,
+    markdown::{self, MessageFormat},
+    moderation,
+    protocol::ServerEvent,
+    repo::NewMessage,
+    spam, word_filter,
+    ws::MESSAGE_MAX_CHARS,
+    AppState,
+};
+
+// 방에 글을 쓸 수 있는지(게스트, 메일 확인, 음소거) 확인한다. 글을 쓸 수 있으면 그림자 차단 여부를 돌려준다.
+pub async fn check_can_post(state: &AppState, claims: &Claims, room: &str) -> Result<bool, ApiError> {
+    if claims.guest {
+        if !guest::can_join(room) {
+            return Err(ApiError::forbidden("guest_room_forbidden", "Guests cannot join this room"));
+        }
+        if !*guest::GUEST_CAN_POST {
+            return Err(ApiError::forbidden("guest_read_only", "Guests cannot post messages"));
+        }
+    } else if !email_verification::can_post(state, claims.user_id).await {
+        return Err(ApiError::forbidden("email_not_verified", "Verify your email address to post messages"));
+    }
+    let restrictions = moderation::restrictions(state, claims.user_id).await?;
+    if let Some(until) = restrictions.muted_until {
+        return Err(ApiError::forbidden(
+            "muted",
+            format!("You are muted until {}", until.format("%Y-%m-%d %H:%M UTC")),
+        ));
+    }
+    Ok(restrictions.shadow_banned)
+}
+
+// 사용자가 쓴 글을 처리한 결과
+pub enum Posted {
+    // 방에 보냈다. 저장할 id 를 받지 못했으면 None.
+    Message(Option<i64>),
+    // 보낸 사람에게만 돌려줄 명령의 응답
+    Reply { command: String, text: String },
+    // 명령이 방에 이벤트를 보냈다
+    Command,
+}
+
+// 사용자가 쓴 글을 검사하고 보낸다. 거부되면 웹소켓은 `error` 이벤트로, REST 는 오류 응답으로 돌려준다.
+pub async fn post(
+    state: &AppState,
+    claims: &Claims,
+    room: &str,
+    author: &Author,
+    text: String,
+    format: MessageFormat,
+    ttl_seconds: Option<u64>,
+) -> Result<Posted, ApiError> {
+    let expires_at = match ttl_seconds.map(ephemeral::expires_at) {
+        None => None,
+        Some(Some(expires_at)) => Some(expires_at),
+        Some(None) => {
+            return Err(ApiError::bad_request(
+                "invalid_ttl",
+                format!("ttl_seconds must be between 1 and {}", *ephemeral::EPHEMERAL_MAX_SECONDS),
+            ))
+        }
+    };
+
+    // 슬래시 명령. 방에 글을 쓰거나 이벤트를 보낼 수 있는 명령은 실행하기 전에 보통 메시지와 같은 검사(길이, 게스트
+    // 간격, 글쓰기 조건, 스팸)를 거치고, 돌려준 본문은 금칙어 검사를 거쳐 보낸다.
+    let (text, format, shadow_banned) = match CommandRegistry::parse(&text) {
+        Parsed::Text(text) => {
+            check_length(text)?;
+            let shadow_banned = admit(state, claims, room).await?;
+            let text = filter(state, room, text)?;
+            check_spam(state, room, author, &text).await?;
+            (text, format, shadow_banned)
+        }
+        Parsed::Command(name, args) => {
+            let command = state.commands.get(name).map_err(|e| ApiError::bad_request(e.code, e.message))?;
+            let mut admitted = None;
+            if command.posts(args) {
+                check_length(&text)?;
+                admitted = Some(admit(state, claims, room).await?);
+                check_spam(state, room, author, &text).await?;
+            }
+            let ctx = CommandContext { state, claims, room };
+            let outcome = command.run(&ctx, args).await.map_err(|e| ApiError::bad_request(e.code, e.message))?;
+            // posts 가 false 라고 한 명령이 글을 보내려 해도 글쓰기 조건은 확인한다
+            let shadow_banned = match (admitted, &outcome) {
+                (Some(shadow_banned), _) => shadow_banned,
+                (None, Outcome::Reply(_)) => false,
+                (None, _) => admit(state, claims, room).await?,
+            };
+            match outcome {
+                Outcome::Post { text, format } => {
+                    check_length(&text)?;
+                    (filter(state, room, &text)?, format, shadow_banned)
+                }
+                Outcome::Reply(text) => return Ok(Posted::Reply { command: name.to_ascii_lowercase(), text }),
+                Outcome::Broadcast(event) => {
+                    moderation::publish(state, room, claims.user_id, shadow_banned, *event);
+                    return Ok(Posted::Command);
+                }
+            }
+        }
+    };
+
+    let draft = Draft { expires_at, ..Draft::new(text, format) };
+    Ok(Posted::Message(send(state, room, author, draft, shadow_banned).await))
+}
+
+fn check_length(text: &str) -> Result<(), ApiError> {
+    let length = text.chars().count();
+    if length > *MESSAGE_MAX_CHARS {
+        return Err(ApiError::bad_request(
+            "message_too_long",
+            format!("Messages must be at most {} characters ({} sent)", *MESSAGE_MAX_CHARS, length),
+        ));
+    }
+    Ok(())
+}
+
+// 게스트 간격과 글쓰기 조건. 글을 쓸 수 있으면 그림자 차단 여부를 돌려준다.
+async fn admit(state: &AppState, claims: &Claims, room: &str) -> Result<bool, ApiError> {
+    if claims.guest && *guest::GUEST_CAN_POST && !state.guest_posts.allow(claims.user_id) {
+        return Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limited",
+            "Guests can only post once in a while",
+        ));
+    }
+    check_can_post(state, claims, room).await
+}
+
+// 금칙어를 가리거나 거부한다. 웹훅으로 받은 글도 거친다.
+pub fn filter(state: &AppState, room: &str, text: &str) -> Result<String, ApiError> {
+    match state.word_filter.check(room, text) {
+        word_filter::Verdict::Allow(text) => Ok(text),
+        word_filter::Verdict::Reject => {
+            Err(ApiError::bad_request("message_rejected", "Message contains a blocked word"))
+        }
+    }
+}
+
+async fn check_spam(state: &AppState, room: &str, author: &Author, text: &str) -> Result<(), ApiError> {
+    match state.spam.check(author.user_id, text) {
+        spam::Verdict::Allow => Ok(()),
+        spam::Verdict::Cooldown(remaining) => Err(ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "spam_cooldown",
+            format!("You can post again in {} seconds", remaining.as_secs().max(1)),
+        )),
+        spam::Verdict::Detected { reason, cooldown } => {
+            tracing::warn!("User '{}' flagged for spam ({}) in '{}'", author.username, reason, room);
+            spam::record(state, author.user_id, room, reason, text).await;
+            Err(ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "spam_detected",
+                format!("Repeated messages; you can post again in {} seconds", cooldown.as_secs()),
+            ))
+        }
+    }
+}
+
+// 메시지 이벤트에 붙는 보낸 사람 정보
+pub struct Author {
+    pub user_id: i32,
+    pub username: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub bot: bool,
+}
+
+// 다른 방에서 전달한 메시지의 원래 작성자와 위치. sent_at 은 유닉스 밀리초.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Forwarded {
+    pub message_id: i64,
+    pub room: String,
+    pub user_id: Option<i32>,
+    pub username: String,
+    pub sent_at: i64,
+}
+
+// 보낼 메시지. expires_at 이 있으면 그 시각에 `ephemeral` 정리 태스크가 지운다.
+pub struct Draft {
+    pub text: String,
+    pub format: MessageFormat,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub forwarded: Option<Forwarded>,
+}
+
+impl Draft {
+    pub fn new(text: String, format: MessageFormat) -> Self {
+        Self { text, format, expires_at: None, forwarded: None }
+    }
+}
+
+// 검사를 마친 메시지를 저장 대기열에 넣고 방에 보낸다. id 를 받지 못하면 저장하지 않고 message_id 없이 보낸다.
+pub async fn send(state: &AppState, room: &str, author: &Author, draft: Draft, shadow_banned: bool) -> Option<i64> {
+    let Draft { text, format, expires_at, forwarded } = draft;
+    // 원문과 함께 정리한 HTML 도 저장해 둔다
+    let html = (format == MessageFormat::Markdown).then(|| markdown::render(&text));
+    let sent_at = Utc::now();
+
+    let message_id = match state.message_writer.next_id(state.repos.messages.as_ref()).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!("Failed to allocate an id for a message from '{}': {}", author.username, e);
+            None
+        }
+    };
+    if let Some(id) = message_id {
+        let pending = NewMessage {
+            id,
+            user_id: author.user_id,
+            username: author.username.clone(),
+            room: room.to_string(),
+            text: text.clone(),
+            format,
+            html: html.clone(),
+            hidden: shadow_banned,
+            expires_at,
+            forwarded: forwarded.clone(),
+            created_at: sent_at,
+        };
+        state.message_writer.enqueue(pending).await;
+    }
+
+    let event = ServerEvent::Message {
+        room: room.to_string(),
+        message_id,
+        user_id: author.user_id,
+        username: author.username.clone(),
+        display_name: author.display_name.clone(),
+        avatar_url: author.avatar_url.clone(),
+        bot: author.bot,
+        text,
+        format,
+        html,
+        sent_at: sent_at.timestamp_millis(),
+        expires_at: expires_at.map(|t| t.timestamp_millis()),
+        forwarded,
+    };
+    moderation::publish(state, room, author.user_id, shadow_banned, event);
+    message_id
+}
@@ -0,0 +1,120 @@
+// --- 중재 동작 ---
+//
+// 신고 처리 등에서 함께 쓰는 메시지 삭제와 사용자 음소거. 동작마다 방에 알리는 이벤트(`message_deleted`,
+// `user_muted`)를 보낸다. 음소거는 방과 상관없이 사용자 단위이며 `users.muted_until` 까지 글을 쓸 수 없다.
+//
+// 그림자 차단된 사용자는 평소처럼 글을 쓰고 자기 글도 보지만, 메시지는 hidden 으로 저장되고 본인의 연결에만
+// 전달된다. 다른 사람에게는 아무 일도 없었던 것처럼 보인다.
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    audit::{self, Target},
+    auth::AdminUser,
+    config,
+    error::{ApiError, ApiJson},
+    protocol::ServerEvent,
+    repo::{RepoResult, User},
+    AppState,
+};
+
+// 기간을 정하지 않고 음소거할 때의 길이
+pub static MUTE_DEFAULT_MINUTES: Lazy<i64> = Lazy::new(|| config::get().limits.moderation_mute_minutes);
+
+// 글을 쓸 때 확인하는 사용자 제한
+#[derive(Debug, Default)]
+pub struct Restrictions {
+    // 음소거 중이면 풀리는 시각
+    pub muted_until: Option<DateTime<Utc>>,
+    pub shadow_banned: bool,
+}
+
+pub async fn restrictions(state: &AppState, user_id: i32) -> RepoResult<Restrictions> {
+    let user = state.repos.users.find(user_id).await?;
+    Ok(user
+        .map(|user| Restrictions { muted_until: user.muted_until, shadow_banned: user.shadow_banned })
+        .unwrap_or_default())
+}
+
+// 새 메시지 이벤트를 보낸다. 그림자 차단된 사용자의 것이면 본인이 그 방에 연 연결에만 보낸다.
+pub fn publish(state: &AppState, room: &str, user_id: i32, shadow_banned: bool, event: ServerEvent) {
+    if shadow_banned {
+        state.connections.send_to_user(user_id, room, &event);
+    } else {
+        state.bus.publish(room, event);
+    }
+}
+
+// 메시지와 첨부 파일을 지우고 방에 알린다. 메시지가 없으면 false.
+pub async fn delete_message(state: &AppState, message_id: i64) -> RepoResult<bool> {
+    let Some((room, keys)) = state.repos.messages.delete(message_id).await? else {
+        return Ok(false);
+    };
+    for key in keys {
+        if let Err(e) = state.attachments.delete(&key).await {
+            tracing::warn!("Failed to remove attachment file {} of deleted message {}: {}", key, message_id, e);
+        }
+    }
+    state.bus.publish(&room, ServerEvent::MessageDeleted { room: room.clone(), message_id });
+    Ok(true)
+}
+
+// until 까지 음소거하고 room 에 알린다. 이미 더 길게 음소거돼 있으면 그대로 둔다. 사용자가 없으면 None.
+pub async fn mute_user(
+    state: &AppState,
+    user_id: i32,
+    until: DateTime<Utc>,
+    room: &str,
+) -> RepoResult<Option<DateTime<Utc>>> {
+    let Some(User { username, muted_until: Some(until), .. }) = state.repos.users.mute(user_id, until).await? else {
+        return Ok(None);
+    };
+    state.bus.publish(
+        room,
+        ServerEvent::UserMuted { room: room.to_string(), user_id, username, muted_until: until.timestamp() },
+    );
+    Ok(Some(until))
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct ShadowBanPayload {
+    shadow_banned: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+// PUT /admin/users/:id/shadow-ban
+pub async fn shadow_ban_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(user_id): Path<i32>,
+    ApiJson(payload): ApiJson<ShadowBanPayload>,
+) -> Response {
+    match state.repos.users.set_shadow_banned(user_id, payload.shadow_banned).await {
+        Ok(Some(User { username, .. })) => {
+            tracing::info!(
+                "User {} {} user {}",
+                claims.user_id,
+                if payload.shadow_banned { "shadow-banned" } else { "lifted the shadow ban of" },
+                user_id
+            );
+            let action = if payload.shadow_banned { "user.shadow_ban" } else { "user.shadow_unban" };
+            let details = json!({ "username": username });
+            audit::record(&state, &claims, action, Target::User(user_id), payload.reason.as_deref(), details).await;
+            Json(json!({ "user_id": user_id, "username": username, "shadow_banned": payload.shadow_banned }))
+                .into_response()
+        }
+        Ok(None) => ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
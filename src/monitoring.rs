@@ -0,0 +1,171 @@
+// --- Prometheus 지표 ---
+//
+// `GET /metrics` 는 이 서버의 지표를 Prometheus 텍스트 형식으로 내보낸다. 방 이름이 드러나므로 server.metrics_token
+// (METRICS_TOKEN)을 설정하면 `Authorization: Bearer <토큰>` 이 있어야 한다.
+//
+// - webchat_connections_active: 이 서버에 열린 웹소켓과 SSE 연결 수
+// - webchat_room_connections{room}: 방마다의 연결 수
+// - webchat_rooms_open: 이 서버에 열린 방 수
+// - webchat_messages_broadcast_total{kind}: 이 서버가 방에 보낸 메시지 (초당 수는 rate() 로 구한다)
+// - webchat_message_insert_duration_seconds: 저장 대기열의 INSERT 한 번에 걸린 시간 (히스토그램)
+// - webchat_auth_failures_total{code}: 401 로 끝난 인증 실패
+// - webchat_broadcast_lagged_total, webchat_broadcast_lagged_events_total: 연결이 방 이벤트를 따라오지 못한 횟수와
+//   그때 건너뛴 이벤트 수
+//
+// 연결 수와 방 수는 요청을 받을 때마다 목록에서 센다.
+//
+// runtime-metrics 기능으로 빌드하면 tokio 런타임과 연결마다 띄우는 태스크(웹소켓의 쓰기, 전달, 수신과 SSE
+// 스트림)의 지표도 내보낸다. 누적 시간은 초 단위 게이지이므로 rate() 로 본다.
+//
+// - webchat_tokio_workers, webchat_tokio_alive_tasks, webchat_tokio_global_queue_depth: 작업 스레드 수, 살아 있는
+//   태스크 수, 작업 스레드가 가져가기를 기다리는 태스크 수
+// - webchat_tokio_busy_seconds: 작업 스레드가 일한 시간의 합 (작업 스레드 수로 나누면 포화도)
+// - webchat_tasks_alive{task}: 종류별 살아 있는 태스크 수
+// - webchat_task_polls_total{task}, webchat_task_slow_polls_total{task}: 폴링 횟수와 그중 50µs 를 넘긴 횟수
+// - webchat_task_poll_seconds{task}: 폴링에 쓴 시간
+// - webchat_task_scheduled_seconds{task}: 깨어난 뒤 폴링되기까지 기다린 시간 (늘어나면 실행기가 밀린 것)
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::Duration,
+};
+#[cfg(feature = "runtime-metrics")]
+use tokio_metrics::{Instrumented, TaskMonitor};
+
+use crate::{auth::AuthError, config, protocol::ServerEvent, AppState};
+
+static METRICS_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| config::get().server.metrics_token.clone().filter(|t| !t.is_empty()));
+
+// 시간 히스토그램의 구간 (초)
+const DURATION_BUCKETS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static HANDLE: OnceCell<PrometheusHandle> = OnceCell::new();
+
+// 지난번에 연결 수를 내보낸 방. 연결이 모두 떠난 방은 0 으로 한 번 내보낸다.
+static REPORTED_ROOMS: Lazy<Mutex<HashSet<String>>> = Lazy::new(Mutex::default);
+
+// 태스크 종류별 감시
+#[cfg(feature = "runtime-metrics")]
+static TASK_MONITORS: Lazy<Mutex<HashMap<&'static str, TaskMonitor>>> = Lazy::new(Mutex::default);
+
+pub fn init() {
+    Lazy::force(&METRICS_TOKEN);
+    let recorder = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), DURATION_BUCKETS)
+        .expect("duration buckets are not empty")
+        .install_recorder();
+    // 서버를 붙인 앱이 먼저 다른 recorder 를 설치했으면 /metrics 는 404 다
+    match recorder {
+        Ok(handle) => {
+            let _ = HANDLE.set(handle);
+        }
+        Err(e) => tracing::warn!("Metrics are disabled: {}", e),
+    }
+}
+
+pub fn message_broadcast(event: &ServerEvent) {
+    let kind = match event {
+        ServerEvent::Message { .. } => "message",
+        ServerEvent::Attachment { .. } => "attachment",
+        ServerEvent::VoiceMessage { .. } => "voice_message",
+        ServerEvent::WebhookMessage { .. } => "webhook_message",
+        _ => return,
+    };
+    counter!("webchat_messages_broadcast_total", "kind" => kind).increment(1);
+}
+
+pub fn message_insert(elapsed: Duration) {
+    histogram!("webchat_message_insert_duration_seconds").record(elapsed.as_secs_f64());
+}
+
+pub fn auth_failure(code: &'static str) {
+    counter!("webchat_auth_failures_total", "code" => code).increment(1);
+}
+
+pub fn broadcast_lagged(skipped: u64) {
+    counter!("webchat_broadcast_lagged_total").increment(1);
+    counter!("webchat_broadcast_lagged_events_total").increment(skipped);
+}
+
+// 연결마다 띄우는 태스크를 종류별로 감시한다. runtime-metrics 기능이 꺼져 있으면 그대로 돌려준다.
+#[cfg(feature = "runtime-metrics")]
+pub fn task<F>(kind: &'static str, task: F) -> Instrumented<F> {
+    TASK_MONITORS.lock().unwrap().entry(kind).or_default().instrument(task)
+}
+
+#[cfg(not(feature = "runtime-metrics"))]
+pub fn task<F>(_kind: &'static str, task: F) -> F {
+    task
+}
+
+#[cfg(feature = "runtime-metrics")]
+fn record_runtime() {
+    let runtime = tokio::runtime::Handle::current().metrics();
+    gauge!("webchat_tokio_workers").set(runtime.num_workers() as f64);
+    gauge!("webchat_tokio_alive_tasks").set(runtime.num_alive_tasks() as f64);
+    gauge!("webchat_tokio_global_queue_depth").set(runtime.global_queue_depth() as f64);
+    let busy: Duration = (0..runtime.num_workers()).map(|worker| runtime.worker_total_busy_duration(worker)).sum();
+    gauge!("webchat_tokio_busy_seconds").set(busy.as_secs_f64());
+
+    for (&kind, monitor) in TASK_MONITORS.lock().unwrap().iter() {
+        let metrics = monitor.cumulative();
+        let alive = metrics.instrumented_count.saturating_sub(metrics.dropped_count);
+        gauge!("webchat_tasks_alive", "task" => kind).set(alive as f64);
+        counter!("webchat_task_polls_total", "task" => kind).absolute(metrics.total_poll_count);
+        counter!("webchat_task_slow_polls_total", "task" => kind).absolute(metrics.total_slow_poll_count);
+        gauge!("webchat_task_poll_seconds", "task" => kind).set(metrics.total_poll_duration.as_secs_f64());
+        gauge!("webchat_task_scheduled_seconds", "task" => kind).set(metrics.total_scheduled_duration.as_secs_f64());
+    }
+}
+
+fn record_connections(state: &AppState) {
+    let mut rooms: HashMap<String, usize> = HashMap::new();
+    for user_rooms in state.connections.local_connections().into_values() {
+        for (room, count) in user_rooms {
+            *rooms.entry(room).or_insert(0) += count;
+        }
+    }
+    gauge!("webchat_connections_active").set(rooms.values().sum::<usize>() as f64);
+    gauge!("webchat_rooms_open").set(state.rooms.stats().rooms as f64);
+
+    let mut reported = REPORTED_ROOMS.lock().unwrap();
+    for room in reported.iter().filter(|room| !rooms.contains_key(*room)) {
+        gauge!("webchat_room_connections", "room" => room.clone()).set(0.0);
+    }
+    reported.clear();
+    for (room, count) in rooms {
+        gauge!("webchat_room_connections", "room" => room.clone()).set(count as f64);
+        reported.insert(room);
+    }
+}
+
+// GET /metrics
+pub async fn metrics_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(expected) = METRICS_TOKEN.as_deref() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if token != Some(expected) {
+            return AuthError::Invalid.into_response();
+        }
+    }
+    let Some(handle) = HANDLE.get() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    record_connections(&state);
+    #[cfg(feature = "runtime-metrics")]
+    record_runtime();
+    handle.run_upkeep();
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], handle.render()).into_response()
+}
@@ -0,0 +1,262 @@
+// --- 알림 ---
+//
+// 메시지에서 언급(@사용자이름)되거나, 친구 신청을 받거나, 중재자가 내 메시지를 지우거나 나를 음소거하면
+// `notifications` 에 알림이 남는다. 접속해 있으면 모든 연결에 `notification` 이벤트로 바로 보내고, 접속하지
+// 않았어도 나중에 `GET /me/notifications` 로 볼 수 있다. NOTIFICATION_RETENTION_DAYS 가 지난 알림은 지운다.
+//
+// 방의 메시지로 생기는 알림은 사용자가 `PUT /me/rooms/:room/notifications` 로 정한 방별 설정을 따른다:
+// all 은 모든 메시지 (그 방에 접속해 있을 때는 빼고), mentions 는 언급만 (기본), mute 는 아무것도 받지 않는다.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{
+    auth::AuthUser,
+    config,
+    error::{ApiError, ApiJson},
+    protocol::ServerEvent,
+    repo::{NewNotification, Notification, Repos},
+    validation::{self, ValidationErrors},
+    AppState,
+};
+
+static RETENTION_DAYS: Lazy<i64> = Lazy::new(|| config::get().retention.notification_days);
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+const PAGE_MAX: i64 = 100;
+// 메시지 하나에서 알림을 보낼 최대 언급 수
+const MENTIONS_MAX: usize = 20;
+const EXCERPT_MAX_CHARS: usize = 200;
+
+// 새로 만들 알림
+pub struct Notice<'a> {
+    pub kind: &'static str,
+    pub room: Option<&'a str>,
+    pub message_id: Option<i64>,
+    pub actor: Option<(i32, &'a str)>,
+    pub text: String,
+}
+
+// 알림을 저장하고 접속해 있으면 바로 보낸다. 실패해도 원래 동작은 이미 끝났으므로 로그만 남긴다.
+pub async fn create(state: &AppState, user_id: i32, notice: Notice<'_>) {
+    let new = NewNotification {
+        user_id,
+        kind: notice.kind,
+        room: notice.room,
+        message_id: notice.message_id,
+        actor_id: notice.actor.map(|(id, _)| id),
+        text: &notice.text,
+    };
+    let created = state.repos.notifications.create(&new).await;
+    let (id, created_at) = match created {
+        Ok(created) => created,
+        Err(e) => {
+            tracing::error!("Failed to create {} notification for user {}: {}", notice.kind, user_id, e);
+            return;
+        }
+    };
+    let notification = Notification {
+        id,
+        kind: notice.kind.to_string(),
+        room: notice.room.map(str::to_string),
+        message_id: notice.message_id,
+        actor_id: notice.actor.map(|(id, _)| id),
+        actor: notice.actor.map(|(_, name)| name.to_string()),
+        text: notice.text,
+        read_at: None,
+        created_at,
+    };
+    // 연결이 하나도 없으면 언급은 Web Push 로도 보낸다
+    if notification.kind == "mention" && !state.connections.is_online(user_id) {
+        state.push.enqueue(user_id, &notification);
+    }
+    state.connections.send_to_users(&[user_id], &ServerEvent::Notification(notification));
+}
+
+pub fn is_username_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+// 글에서 언급한 사용자 이름 (소문자, 중복 없음). 메일 주소처럼 @ 앞에 글자가 붙어 있으면 언급이 아니다.
+fn mentioned_usernames(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        if c == '@' && !prev.is_some_and(is_username_char) {
+            let rest = &text[i + 1..];
+            let end = rest.find(|c| !is_username_char(c)).unwrap_or(rest.len());
+            // 문장 끝의 마침표 등은 이름에 넣지 않는다
+            let name = rest[..end].trim_end_matches(['.', '-', '_']);
+            let name = validation::normalize_username(name).to_lowercase();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+                if names.len() == MENTIONS_MAX {
+                    break;
+                }
+            }
+        }
+        prev = Some(c);
+    }
+    names
+}
+
+fn excerpt(text: &str) -> String {
+    let mut excerpt: String = text.chars().take(EXCERPT_MAX_CHARS).collect();
+    if excerpt.len() < text.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+// 새 메시지로 생기는 알림을 방별 설정에 따라 만든다: 언급된 사용자와 키워드가 맞은 사용자(mute 가 아니면),
+// all 로 둔 사용자. 저장되었고 숨기지 않은 메시지에만 부른다.
+pub fn spawn_for_message(state: AppState, room: String, message_id: i64, author: (i32, String), text: &str) {
+    let names = mentioned_usernames(text);
+    let watchers: Vec<i32> = state.keywords.matches(&room, text).into_iter().collect();
+    let text = excerpt(text);
+    tokio::spawn(async move {
+        let (author_id, author_name) = author;
+        let recipients = state.repos.notifications.message_recipients(&room, author_id, &names, &watchers).await;
+        let recipients = match recipients {
+            Ok(recipients) => recipients,
+            Err(e) => {
+                tracing::warn!("Failed to find notification recipients of message {}: {}", message_id, e);
+                return;
+            }
+        };
+        for (user_id, mentioned, keyword) in recipients {
+            // 지금 보고 있는 방의 메시지는 언급이나 키워드가 아니면 알리지 않는다
+            let kind = match (mentioned, keyword) {
+                (true, _) => "mention",
+                (false, true) => "keyword",
+                (false, false) if state.connections.is_in_room(user_id, &room) => continue,
+                (false, false) => "message",
+            };
+            let notice = Notice {
+                kind,
+                room: Some(&room),
+                message_id: Some(message_id),
+                actor: Some((author_id, &author_name)),
+                text: text.clone(),
+            };
+            create(&state, user_id, notice).await;
+        }
+    });
+}
+
+// 보관 기간이 지난 알림을 지우는 백그라운드 태스크
+pub fn spawn_purge_task(repos: Repos) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let purged = repos.notifications.purge(Utc::now() - chrono::Duration::days(*RETENTION_DAYS)).await;
+            if let Err(e) = purged {
+                tracing::warn!("Failed to purge notifications: {}", e);
+            }
+        }
+    });
+}
+
+// --- API ---
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    // true 면 읽지 않은 것만
+    #[serde(default)]
+    unread: bool,
+    // 이 id 보다 오래된 것 (다음 페이지)
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// GET /me/notifications?unread=&before=&limit=: 최근 것부터
+pub async fn list_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let limit = query.limit.clamp(1, PAGE_MAX);
+    let notifications = state.repos.notifications.list(claims.user_id, query.unread, query.before, limit).await;
+    let notifications = match notifications {
+        Ok(notifications) => notifications,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    match state.repos.notifications.unread_count(claims.user_id).await {
+        Ok(unread) => Json(json!({ "notifications": notifications, "unread_count": unread })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/notifications/:id/read
+pub async fn read_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.notifications.mark_read(id, claims.user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("notification_not_found", "Notification not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/notifications/read-all
+pub async fn read_all_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.notifications.mark_all_read(claims.user_id).await {
+        Ok(marked) => Json(json!({ "marked_read": marked })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// --- 방별 알림 설정 ---
+
+#[derive(Debug, Deserialize)]
+pub struct RoomPrefPayload {
+    // "all", "mentions", "mute"
+    level: String,
+}
+
+// GET /me/rooms/:room/notifications
+pub async fn get_room_pref_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+) -> Response {
+    match state.repos.notifications.room_level(claims.user_id, &room).await {
+        Ok(level) => Json(json!({ "room": room, "level": level.as_deref().unwrap_or("mentions") })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PUT /me/rooms/:room/notifications
+pub async fn put_room_pref_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<RoomPrefPayload>,
+) -> Response {
+    if !matches!(payload.level.as_str(), "all" | "mentions" | "mute") {
+        return ValidationErrors::single("level", "invalid_value", "level must be 'all', 'mentions' or 'mute'")
+            .into_response();
+    }
+    match state.repos.notifications.set_room_level(claims.user_id, &room, &payload.level).await {
+        Ok(_) => Json(json!({ "room": room, "level": payload.level })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,331 @@
+// --- 소셜 로그인 (OAuth2 authorization code + PKCE) ---
+//
+// `GET /auth/:provider` 가 제공자의 동의 화면으로 보내고, 제공자는 `/auth/:provider/callback` 으로
+// 돌아온다. 외부 계정은 oauth_identities 로 로컬 사용자와 연결되며, 처음 보는 계정이면
+// 확인된 메일 주소가 같은 기존 사용자에 연결하거나 새 사용자를 만든다.
+// 로그인 세션은 비밀번호 로그인과 똑같이 발급한다.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::{
+    auth::generate_token,
+    config,
+    error::ApiError,
+    invites::REQUIRE_INVITE,
+    repo::{ExternalAccount, OAuthLink, RepoError, RepoResult},
+    session::{start_session, ClientInfo},
+    two_factor,
+    validation::{self, ValidationErrors},
+    AppState, PUBLIC_URL,
+};
+
+// state 와 PKCE verifier 를 담아 두는 쿠키 (콜백까지 10분)
+const STATE_COOKIE: &str = "oauth_state";
+const STATE_TTL_MINUTES: i64 = 10;
+
+struct Provider {
+    client_id: String,
+    client_secret: String,
+    authorize_url: &'static str,
+    token_url: &'static str,
+    scope: &'static str,
+}
+
+// 클라이언트 id/secret 이 설정된 제공자만 활성화된다
+static PROVIDERS: Lazy<BTreeMap<&'static str, Provider>> = Lazy::new(|| {
+    let oauth = &config::get().oauth;
+    let mut providers = BTreeMap::new();
+    if let (Some(client_id), Some(client_secret)) = (&oauth.google_client_id, &oauth.google_client_secret) {
+        providers.insert(
+            "google",
+            Provider {
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+                authorize_url: "https://accounts.google.com/o/oauth2/v2/auth",
+                token_url: "https://oauth2.googleapis.com/token",
+                scope: "openid email profile",
+            },
+        );
+    }
+    if let (Some(client_id), Some(client_secret)) = (&oauth.github_client_id, &oauth.github_client_secret) {
+        providers.insert(
+            "github",
+            Provider {
+                client_id: client_id.clone(),
+                client_secret: client_secret.clone(),
+                authorize_url: "https://github.com/login/oauth/authorize",
+                token_url: "https://github.com/login/oauth/access_token",
+                scope: "read:user user:email",
+            },
+        );
+    }
+    providers
+});
+
+// 외부 API 호출용 (CAPTCHA 검증에서도 쓴다)
+pub static HTTP: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .user_agent("WebChat")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+// 제공자에서 가져온 사용자 정보
+struct ExternalIdentity {
+    subject: String,
+    // 제공자가 확인한 주소만 채운다
+    email: Option<String>,
+    username_hint: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+fn redirect_uri(provider: &str) -> String {
+    format!("{}/auth/{}/callback", *PUBLIC_URL, provider)
+}
+
+// 실패는 로그인 페이지로 돌려보내 사유를 보여준다
+fn login_error(code: &str) -> Response {
+    Redirect::to(&format!("/static/login.html#oauth_error={}", code)).into_response()
+}
+
+// GET /auth/providers: 로그인 페이지에 표시할 제공자 목록
+pub async fn providers_handler() -> impl IntoResponse {
+    Json(PROVIDERS.keys().copied().collect::<Vec<_>>())
+}
+
+// GET /auth/:provider
+pub async fn authorize_handler(Path(name): Path<String>, jar: CookieJar) -> Response {
+    let Some(provider) = PROVIDERS.get(name.as_str()) else {
+        return ApiError::not_found("unknown_provider", "Unknown login provider").into_response();
+    };
+
+    let state = generate_token();
+    let verifier = generate_token();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+    let url = reqwest::Url::parse_with_params(
+        provider.authorize_url,
+        &[
+            ("response_type", "code"),
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", redirect_uri(&name).as_str()),
+            ("scope", provider.scope),
+            ("state", state.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .expect("provider authorize URL is valid");
+
+    // 제공자에서 돌아오는 탑레벨 GET 에도 실리도록 Lax 로 둔다
+    let cookie = Cookie::build((STATE_COOKIE, format!("{}.{}", state, verifier)))
+        .path(format!("/auth/{}", name))
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .max_age(time::Duration::minutes(STATE_TTL_MINUTES))
+        .build();
+    (jar.add(cookie), Redirect::to(url.as_str())).into_response()
+}
+
+// GET /auth/:provider/callback
+pub async fn callback_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(params): Query<CallbackParams>,
+    jar: CookieJar,
+    client: ClientInfo,
+) -> Response {
+    let Some(provider) = PROVIDERS.get(name.as_str()) else {
+        return ApiError::not_found("unknown_provider", "Unknown login provider").into_response();
+    };
+
+    let stored = jar.get(STATE_COOKIE).map(|c| c.value().to_string());
+    let jar = jar.remove(Cookie::build(STATE_COOKIE).path(format!("/auth/{}", name)));
+
+    if params.error.is_some() {
+        return (jar, login_error("denied")).into_response();
+    }
+    let (Some(code), Some(returned_state)) = (params.code, params.state) else {
+        return (jar, login_error("invalid_request")).into_response();
+    };
+    // CSRF 방지: 로그인을 시작한 브라우저의 state 와 같아야 한다
+    let verifier = match stored.as_deref().and_then(|v| v.split_once('.')) {
+        Some((expected, verifier)) if expected == returned_state => verifier.to_string(),
+        _ => return (jar, login_error("invalid_state")).into_response(),
+    };
+
+    let identity = match fetch_identity(&name, provider, &code, &verifier).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            tracing::warn!("OAuth login with {} failed: {}", name, e);
+            return (jar, login_error("provider_error")).into_response();
+        }
+    };
+
+    let (user_id, username) = match find_or_create_user(&state, &name, &identity).await {
+        Ok(Ok(user)) => user,
+        Ok(Err(code)) => return (jar, login_error(code)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to link {} identity: {}", name, e);
+            return (jar, login_error("server_error")).into_response();
+        }
+    };
+    tracing::info!("User {} logged in with {}", user_id, name);
+
+    // 2단계 인증은 소셜 로그인에도 적용된다
+    match two_factor::login_challenge(&state, user_id).await {
+        Ok(Some(challenge)) => {
+            let target = format!("/static/login.html#challenge={}", challenge);
+            return (jar, Redirect::to(&target)).into_response();
+        }
+        Ok(None) => {}
+        Err(_) => return (jar, login_error("server_error")).into_response(),
+    }
+
+    // 세션 쿠키는 그대로 두고 본문 대신 로그인 페이지로 보내면, 페이지가 /refresh 로 액세스 토큰을 받는다
+    let session = start_session(&state, jar, user_id, &username, client).await;
+    if !session.status().is_success() {
+        return login_error("server_error");
+    }
+    let (mut parts, _) = session.into_parts();
+    parts.status = StatusCode::SEE_OTHER;
+    parts.headers.insert(header::LOCATION, "/static/login.html#oauth=1".parse().unwrap());
+    parts.headers.remove(header::CONTENT_TYPE);
+    Response::from_parts(parts, axum::body::Body::empty())
+}
+
+// 인가 코드를 액세스 토큰으로 바꾸고 사용자 정보를 조회한다
+async fn fetch_identity(
+    name: &str,
+    provider: &Provider,
+    code: &str,
+    verifier: &str,
+) -> Result<ExternalIdentity, reqwest::Error> {
+    let redirect_uri = redirect_uri(name);
+    let token: Value = HTTP
+        .post(provider.token_url)
+        .header(header::ACCEPT, "application/json")
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+            ("code_verifier", verifier),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let access_token = token["access_token"].as_str().unwrap_or_default();
+
+    if name == "github" {
+        let user: Value = get_json("https://api.github.com/user", access_token).await?;
+        let emails: Value = get_json("https://api.github.com/user/emails", access_token).await?;
+        let email = emails.as_array().and_then(|list| {
+            list.iter()
+                .find(|e| e["primary"].as_bool() == Some(true) && e["verified"].as_bool() == Some(true))
+                .and_then(|e| e["email"].as_str())
+                .map(str::to_string)
+        });
+        Ok(ExternalIdentity {
+            subject: user["id"].as_i64().map(|id| id.to_string()).unwrap_or_default(),
+            email,
+            username_hint: user["login"].as_str().unwrap_or("github").to_string(),
+        })
+    } else {
+        let user: Value = get_json("https://openidconnect.googleapis.com/v1/userinfo", access_token).await?;
+        let email = user["email"]
+            .as_str()
+            .filter(|_| user["email_verified"].as_bool() == Some(true))
+            .map(str::to_string);
+        let hint = user["given_name"]
+            .as_str()
+            .or_else(|| email.as_deref().and_then(|e| e.split('@').next()))
+            .unwrap_or("google")
+            .to_string();
+        Ok(ExternalIdentity {
+            subject: user["sub"].as_str().unwrap_or_default().to_string(),
+            email,
+            username_hint: hint,
+        })
+    }
+}
+
+async fn get_json(url: &str, access_token: &str) -> Result<Value, reqwest::Error> {
+    HTTP.get(url)
+        .bearer_auth(access_token)
+        .header(header::ACCEPT, "application/json")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+// 연결된 사용자를 찾거나, 확인된 같은 주소의 사용자에 연결하거나, 새로 만든다.
+// 주소가 확인되지 않은 다른 계정이 이미 쓰고 있으면 email_in_use (가로채기 방지),
+// 초대 전용 가입이라 새 계정을 만들 수 없으면 registration_closed 오류 코드를 돌려준다.
+async fn find_or_create_user(
+    state: &AppState,
+    provider: &str,
+    identity: &ExternalIdentity,
+) -> RepoResult<Result<(i32, String), &'static str>> {
+    if identity.subject.is_empty() {
+        return Err(RepoError::Backend("provider returned no subject".into()));
+    }
+
+    if let Some(user) = state.repos.oauth.linked_user(provider, &identity.subject).await? {
+        return Ok(Ok(user));
+    }
+
+    let username_base = username_base(&identity.username_hint);
+    let account = ExternalAccount {
+        provider,
+        subject: &identity.subject,
+        email: identity.email.as_deref(),
+        username_base: &username_base,
+        allow_signup: !*REQUIRE_INVITE,
+    };
+    match state.repos.oauth.link(&account).await? {
+        OAuthLink::Linked(user_id, username) => Ok(Ok((user_id, username))),
+        OAuthLink::EmailInUse => Ok(Err("email_in_use")),
+        OAuthLink::RegistrationClosed => Ok(Err("registration_closed")),
+    }
+}
+
+// 새 사용자의 이름: 외부에서 온 이름이 규칙에 맞지 않으면 (너무 짧거나 예약어 등) 기본 이름을 쓴다
+fn username_base(username_hint: &str) -> String {
+    let base: String = validation::normalize_username(username_hint)
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
+        .take(24)
+        .collect();
+    let mut errors = ValidationErrors::default();
+    validation::check_username(&base, &mut errors);
+    if errors.is_empty() {
+        base
+    } else {
+        "user".to_string()
+    }
+}
@@ -0,0 +1,274 @@
+// --- 패스키 (WebAuthn) ---
+//
+// 비밀번호 대신 쓸 수 있는 로그인 수단이다. 등록과 인증은 각각 start/finish 두 단계로
+// 이루어지며, start 에서 만든 챌린지 상태는 webauthn_ceremonies 에 잠시 보관했다가
+// finish 에서 한 번만 꺼내 쓴다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use webauthn_rs::prelude::{
+    Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential, Url,
+    Uuid, Webauthn, WebauthnBuilder,
+};
+
+use crate::{
+    auth::{generate_token, hash_token, AuthUser},
+    config,
+    error::{ApiError, ApiJson},
+    repo::{RepoError, RepoResult, Repos},
+    session::{start_session, ClientInfo},
+    validation, AppState,
+};
+
+// start 와 finish 사이에 허용하는 시간
+const CEREMONY_TTL_MINUTES: i64 = 5;
+
+const REGISTRATION: &str = "registration";
+const AUTHENTICATION: &str = "authentication";
+
+// 신뢰 당사자(RP) 설정. 출처(origin)는 server.public_url, RP id 는 기본적으로 그 호스트 이름이다.
+static WEBAUTHN: Lazy<Webauthn> = Lazy::new(|| {
+    let config = config::get();
+    webauthn(&config.server.public_url, config.auth.webauthn_rp_id.as_deref())
+        .expect("WebAuthn settings are validated")
+});
+
+fn webauthn(public_url: &str, rp_id: Option<&str>) -> Result<Webauthn, String> {
+    let origin = Url::parse(public_url).map_err(|e| e.to_string())?;
+    let rp_id = match rp_id {
+        Some(rp_id) => rp_id,
+        None => origin.host_str().ok_or("server.public_url has no host")?,
+    };
+    WebauthnBuilder::new(rp_id, &origin)
+        .and_then(|builder| builder.rp_name("WebChat").build())
+        .map_err(|e| e.to_string())
+}
+
+// 설정 검증용: RP id 가 server.public_url 의 호스트(나 그 상위 도메인)인지
+pub fn check(public_url: &str, rp_id: Option<&str>) -> Result<(), String> {
+    webauthn(public_url, rp_id).map(|_| ())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterFinishPayload {
+    ceremony: String,
+    credential: RegisterPublicKeyCredential,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginStartPayload {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginFinishPayload {
+    ceremony: String,
+    credential: PublicKeyCredential,
+}
+
+// 사용자 핸들은 사용자마다 고정이면 된다 (개인 정보를 담지 않는다)
+fn user_handle(user_id: i32) -> Uuid {
+    Uuid::from_u128(user_id as u128)
+}
+
+fn credential_key(passkey: &Passkey) -> String {
+    URL_SAFE_NO_PAD.encode(&passkey.cred_id()[..])
+}
+
+async fn save_ceremony<T: Serialize>(repos: &Repos, user_id: i32, kind: &str, state: &T) -> RepoResult<String> {
+    let state = serde_json::to_string(state).map_err(|e| RepoError::Backend(e.to_string()))?;
+    let token = generate_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(CEREMONY_TTL_MINUTES);
+    repos.passkeys.save_ceremony(&hash_token(&token), user_id, kind, &state, expires_at).await?;
+    Ok(token)
+}
+
+// 절차 상태를 꺼내면서 지운다 (같은 챌린지로 두 번 완료할 수 없다)
+async fn take_ceremony<T: DeserializeOwned>(repos: &Repos, token: &str, kind: &str) -> RepoResult<Option<(i32, T)>> {
+    let row = repos.passkeys.take_ceremony(&hash_token(token), kind).await?;
+    Ok(row.and_then(|(user_id, state)| serde_json::from_str(&state).ok().map(|s| (user_id, s))))
+}
+
+async fn load_passkeys(repos: &Repos, user_id: i32) -> RepoResult<Vec<(i64, Passkey)>> {
+    let rows = repos.passkeys.credentials(user_id).await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, passkey)| serde_json::from_str(&passkey).ok().map(|p| (id, p)))
+        .collect())
+}
+
+// POST /me/passkeys/register/start
+pub async fn register_start_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let existing = match load_passkeys(&state.repos, claims.user_id).await {
+        Ok(keys) => keys,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    // 이미 등록한 인증기는 다시 등록하지 않도록 제외한다
+    let exclude = existing.iter().map(|(_, p)| p.cred_id().clone()).collect::<Vec<_>>();
+
+    let (options, registration) = match WEBAUTHN.start_passkey_registration(
+        user_handle(claims.user_id),
+        &claims.sub,
+        &claims.sub,
+        Some(exclude),
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!("Failed to start passkey registration: {}", e);
+            return ApiError::internal().into_response();
+        }
+    };
+
+    match save_ceremony(&state.repos, claims.user_id, REGISTRATION, &registration).await {
+        Ok(ceremony) => Json(json!({ "ceremony": ceremony, "options": options })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/passkeys/register/finish
+pub async fn register_finish_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<RegisterFinishPayload>,
+) -> Response {
+    let registration = match take_ceremony::<PasskeyRegistration>(&state.repos, &payload.ceremony, REGISTRATION).await {
+        Ok(Some((user_id, registration))) if user_id == claims.user_id => registration,
+        Ok(_) => return ApiError::bad_request("invalid_ceremony", "Invalid or expired ceremony").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let passkey = match WEBAUTHN.finish_passkey_registration(&payload.credential, &registration) {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::info!("Passkey registration for user {} rejected: {}", claims.user_id, e);
+            return ApiError::bad_request("passkey_verification_failed", "Passkey verification failed").into_response();
+        }
+    };
+
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Passkey");
+    let stored = state
+        .repos
+        .passkeys
+        .add(
+            claims.user_id,
+            &credential_key(&passkey),
+            &serde_json::to_string(&passkey).unwrap_or_default(),
+            name,
+        )
+        .await;
+
+    match stored {
+        Ok(id) => {
+            tracing::info!("User {} registered passkey {}", claims.user_id, id);
+            (StatusCode::CREATED, Json(json!({ "id": id }))).into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            ApiError::conflict("passkey_exists", "Passkey is already registered").into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /me/passkeys
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.passkeys.list(claims.user_id).await {
+        Ok(keys) => Json(keys).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/passkeys/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.passkeys.delete(id, claims.user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("passkey_not_found", "Passkey not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /login/passkey/start
+pub async fn login_start_handler(State(state): State<AppState>, ApiJson(payload): ApiJson<LoginStartPayload>) -> Response {
+    let user_id = match state.repos.users.find_by_name(&validation::normalize_username(&payload.username)).await {
+        Ok(Some(user)) => user.id,
+        Ok(None) => return ApiError::bad_request("no_passkey", "No passkey registered for this account").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let passkeys: Vec<Passkey> = match load_passkeys(&state.repos, user_id).await {
+        Ok(keys) if !keys.is_empty() => keys.into_iter().map(|(_, p)| p).collect(),
+        Ok(_) => return ApiError::bad_request("no_passkey", "No passkey registered for this account").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let (options, authentication) = match WEBAUTHN.start_passkey_authentication(&passkeys) {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!("Failed to start passkey authentication: {}", e);
+            return ApiError::internal().into_response();
+        }
+    };
+
+    match save_ceremony(&state.repos, user_id, AUTHENTICATION, &authentication).await {
+        Ok(ceremony) => Json(json!({ "ceremony": ceremony, "options": options })).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /login/passkey/finish: 검증에 성공하면 비밀번호 로그인과 같은 세션을 시작한다
+pub async fn login_finish_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    ApiJson(payload): ApiJson<LoginFinishPayload>,
+) -> Response {
+    let (user_id, authentication) =
+        match take_ceremony::<PasskeyAuthentication>(&state.repos, &payload.ceremony, AUTHENTICATION).await {
+            Ok(Some(ceremony)) => ceremony,
+            Ok(None) => return ApiError::unauthorized("invalid_ceremony", "Invalid or expired ceremony").into_response(),
+            Err(e) => return ApiError::from(e).into_response(),
+        };
+
+    let result = match WEBAUTHN.finish_passkey_authentication(&payload.credential, &authentication) {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::info!("Passkey login for user {} rejected: {}", user_id, e);
+            return ApiError::unauthorized("passkey_verification_failed", "Passkey verification failed").into_response();
+        }
+    };
+
+    // 서명 카운터 등 인증기 상태가 바뀌었으면 저장해 둔다
+    let passkeys = load_passkeys(&state.repos, user_id).await.unwrap_or_default();
+    if let Some((id, mut passkey)) = passkeys.into_iter().find(|(_, p)| p.cred_id() == result.cred_id()) {
+        let updated = (passkey.update_credential(&result) == Some(true))
+            .then(|| serde_json::to_string(&passkey).unwrap_or_default());
+        let _ = state.repos.passkeys.mark_used(id, updated.as_deref()).await;
+    }
+
+    let username = match state.repos.users.find(user_id).await {
+        Ok(Some(user)) => user.username,
+        Ok(None) => return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    tracing::info!("User {} logged in with a passkey", user_id);
+    start_session(&state, jar, user_id, &username, client).await
+}
@@ -0,0 +1,267 @@
+// --- 비밀번호 해싱 백엔드 ---
+//
+// Argon2id(기본)와 bcrypt 를 같은 트레이트 뒤에 두고, 설정(config.rs 의 passwords)으로
+// 알고리즘과 파라미터를 고른다. 저장된 해시가 다른 알고리즘(예: 예전 bcrypt)이거나
+// 현재 설정보다 약한 파라미터로 만들어졌다면 로그인 성공 시점에 다시 해싱해서 교체한다.
+// 그래서 기존 사용자는 비밀번호 재설정 없이 다음 로그인 때 점진적으로 옮겨진다.
+//
+// 해싱과 검증은 수십 ms 동안 CPU 를 쓰므로 핸들러는 `hash_async`/`verify_async` 로 블로킹 스레드에서 돌려
+// 런타임 워커(와 그 위의 웹소켓)를 막지 않는다.
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString},
+    Argon2, Params, Version,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+use crate::config;
+
+// 해시 문자열 접두사로 구분되는 알고리즘 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[serde(alias = "argon2")]
+    Argon2id,
+    Bcrypt,
+}
+
+impl HashAlgorithm {
+    // 저장된 해시 문자열(PHC / modular crypt 형식)로부터 알고리즘 판별
+    pub fn detect(hash: &str) -> Option<Self> {
+        if hash.starts_with("$argon2id$") {
+            Some(Self::Argon2id)
+        } else if hash.starts_with("$2a$") || hash.starts_with("$2b$") || hash.starts_with("$2y$") {
+            Some(Self::Bcrypt)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PasswordError {
+    Hash(String),
+    UnsupportedHash,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::Hash(e) => write!(f, "password hashing failed: {}", e),
+            PasswordError::UnsupportedHash => write!(f, "unsupported password hash format"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+// 알고리즘별 구현이 따라야 하는 공통 인터페이스
+pub trait PasswordHasher: Send + Sync {
+    fn algorithm(&self) -> HashAlgorithm;
+    fn hash(&self, password: &str) -> Result<String, PasswordError>;
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, PasswordError>;
+    // 같은 알고리즘의 해시가 현재 설정보다 약한 파라미터로 만들어졌는지 여부
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+// --- Argon2id ---
+
+#[derive(Debug, Clone)]
+pub struct Argon2Hasher {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Argon2Hasher {
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        Self { memory_kib, iterations, parallelism }
+    }
+
+    // 파라미터가 argon2 가 받아들이는 범위인지 (설정 검증용)
+    pub fn check(&self) -> Result<(), PasswordError> {
+        self.argon2().map(|_| ())
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, PasswordError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| PasswordError::Hash(e.to_string()))?;
+        Ok(Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+impl Default for Argon2Hasher {
+    // OWASP 권장 최소값 (m=19MiB, t=2, p=1)
+    fn default() -> Self {
+        Self::new(19 * 1024, 2, 1)
+    }
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Argon2id
+    }
+
+    fn hash(&self, password: &str) -> Result<String, PasswordError> {
+        let salt = SaltString::generate(&mut OsRng);
+        self.argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|h| h.to_string())
+            .map_err(|e| PasswordError::Hash(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, PasswordError> {
+        let parsed = PasswordHash::new(hash).map_err(|_| PasswordError::UnsupportedHash)?;
+        // 검증은 해시에 기록된 파라미터를 그대로 사용한다
+        Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return true;
+        };
+        match Params::try_from(&parsed) {
+            Ok(params) => {
+                params.m_cost() < self.memory_kib
+                    || params.t_cost() < self.iterations
+                    || params.p_cost() < self.parallelism
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+// --- bcrypt ---
+
+#[derive(Debug, Clone)]
+pub struct BcryptHasher {
+    cost: u32,
+}
+
+impl BcryptHasher {
+    pub fn new(cost: u32) -> Self {
+        Self { cost }
+    }
+}
+
+impl Default for BcryptHasher {
+    fn default() -> Self {
+        Self::new(12)
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::Bcrypt
+    }
+
+    fn hash(&self, password: &str) -> Result<String, PasswordError> {
+        bcrypt::hash(password, self.cost).map_err(|e| PasswordError::Hash(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, PasswordError> {
+        bcrypt::verify(password, hash).map_err(|_| PasswordError::UnsupportedHash)
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        // "$2b$12$..." 형식에서 cost 부분을 읽는다
+        match hash.split('$').nth(2).and_then(|c| c.parse::<u32>().ok()) {
+            Some(cost) => cost < self.cost,
+            None => true,
+        }
+    }
+}
+
+// --- 설정에 따라 조합된 해싱 정책 ---
+
+pub struct Passwords {
+    primary: Box<dyn PasswordHasher>,
+    argon2: Argon2Hasher,
+    bcrypt: BcryptHasher,
+    // 없는 사용자로 로그인할 때 대신 검증하는 해시. 처음 쓸 때 기본 알고리즘으로 만든다.
+    dummy_hash: OnceLock<String>,
+}
+
+impl Passwords {
+    pub fn new(algorithm: HashAlgorithm, argon2: Argon2Hasher, bcrypt: BcryptHasher) -> Self {
+        let primary: Box<dyn PasswordHasher> = match algorithm {
+            HashAlgorithm::Argon2id => Box::new(argon2.clone()),
+            HashAlgorithm::Bcrypt => Box::new(bcrypt.clone()),
+        };
+        Self { primary, argon2, bcrypt, dummy_hash: OnceLock::new() }
+    }
+
+    // passwords 설정 섹션으로부터 구성
+    pub fn configured() -> Self {
+        let passwords = &config::get().passwords;
+        let argon2 = Argon2Hasher::new(
+            passwords.argon2_memory_kib,
+            passwords.argon2_iterations,
+            passwords.argon2_parallelism,
+        );
+        Self::new(passwords.hash_algorithm, argon2, BcryptHasher::new(passwords.bcrypt_cost))
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.primary.algorithm()
+    }
+
+    // 새 비밀번호는 항상 설정된 기본 알고리즘으로 해싱
+    pub fn hash(&self, password: &str) -> Result<String, PasswordError> {
+        self.primary.hash(password)
+    }
+
+    // 저장된 해시의 알고리즘에 맞는 구현으로 검증
+    pub fn verify(&self, password: &str, hash: &str) -> Result<bool, PasswordError> {
+        self.hasher_for(hash)?.verify(password, hash)
+    }
+
+    // 로그인 성공 후 호출: 기본 알고리즘이 아니거나 현재 설정보다 약한 파라미터의 해시면 true
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        match HashAlgorithm::detect(hash) {
+            Some(algorithm) if algorithm == self.primary.algorithm() => self.primary.needs_rehash(hash),
+            Some(_) => true,
+            // 알 수 없는 형식은 검증도 통과하지 못하므로 여기까지 오지 않는다
+            None => false,
+        }
+    }
+
+    // hash 를 블로킹 스레드에서 돌린다
+    pub async fn hash_async(self: &Arc<Self>, password: &str) -> Result<String, PasswordError> {
+        let (passwords, password) = (self.clone(), password.to_string());
+        tokio::task::spawn_blocking(move || passwords.hash(&password))
+            .await
+            .map_err(|e| PasswordError::Hash(e.to_string()))?
+    }
+
+    // verify 를 블로킹 스레드에서 돌린다
+    pub async fn verify_async(self: &Arc<Self>, password: &str, hash: &str) -> Result<bool, PasswordError> {
+        let (passwords, password, hash) = (self.clone(), password.to_string(), hash.to_string());
+        tokio::task::spawn_blocking(move || passwords.verify(&password, &hash))
+            .await
+            .map_err(|e| PasswordError::Hash(e.to_string()))?
+    }
+
+    // 검증할 해시가 없을 때(없는 사용자, 비밀번호 없는 계정) 부른다. 고정된 해시를 검증해 있는 사용자와 같은
+    // 시간을 쓰게 해서 응답 시간으로 사용자 이름이 있는지 알 수 없게 한다.
+    pub async fn verify_dummy(self: &Arc<Self>, password: &str) {
+        let (passwords, password) = (self.clone(), password.to_string());
+        let _ = tokio::task::spawn_blocking(move || {
+            let hash = passwords.dummy_hash.get_or_init(|| passwords.hash("dummy password").unwrap_or_default());
+            passwords.verify(&password, hash)
+        })
+        .await;
+    }
+
+    fn hasher_for(&self, hash: &str) -> Result<&dyn PasswordHasher, PasswordError> {
+        match HashAlgorithm::detect(hash) {
+            Some(HashAlgorithm::Argon2id) => Ok(&self.argon2),
+            Some(HashAlgorithm::Bcrypt) => Ok(&self.bcrypt),
+            None => Err(PasswordError::UnsupportedHash),
+        }
+    }
+}
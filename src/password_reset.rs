@@ -0,0 +1,100 @@
+// --- 비밀번호 재설정 (메일로 보내는 일회용 토큰) ---
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{
+    auth::{generate_token, hash_token},
+    config,
+    error::{ApiError, ApiJson},
+    session::revoke_user_sessions,
+    validation, AppState, PUBLIC_URL,
+};
+
+// 재설정 링크의 유효 시간
+static PASSWORD_RESET_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::minutes(config::get().auth.password_reset_ttl_minutes));
+
+#[derive(Debug, Deserialize)]
+pub struct ResetRequestPayload {
+    email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetConfirmPayload {
+    token: String,
+    new_password: String,
+}
+
+// POST /password-reset/request
+// 계정 존재 여부를 알 수 없도록 주소와 관계없이 항상 202 를 돌려준다.
+pub async fn request_handler(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<ResetRequestPayload>,
+) -> Response {
+    let (user_id, email) = match state.repos.accounts.find_by_email(payload.email.trim()).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return StatusCode::ACCEPTED.into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let token = generate_token();
+    let expires_at = chrono::Utc::now() + *PASSWORD_RESET_TTL;
+    if state.repos.tokens.add_password_reset(user_id, &hash_token(&token), expires_at).await.is_err() {
+        return ApiError::internal().into_response();
+    }
+
+    let link = format!("{}/static/reset.html#token={}", *PUBLIC_URL, token);
+    let body = format!(
+        "Someone asked to reset the password of your WebChat account.\n\n\
+         Open this link within {} minutes to choose a new password:\n{}\n\n\
+         If this wasn't you, you can ignore this email.",
+        PASSWORD_RESET_TTL.num_minutes(),
+        link
+    );
+    state.mailer.send_in_background(email, "Reset your WebChat password", body);
+
+    tracing::info!("Password reset requested for user {}", user_id);
+    StatusCode::ACCEPTED.into_response()
+}
+
+// POST /password-reset/confirm
+// 토큰을 원자적으로 사용 처리한 뒤 비밀번호를 바꾸고 모든 세션을 폐기한다.
+pub async fn confirm_handler(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<ResetConfirmPayload>,
+) -> Response {
+    // 토큰을 쓰기 전에 검사해서, 약한 비밀번호로 거절돼도 같은 링크로 다시 시도할 수 있게 한다
+    if let Err(errors) = validation::validate_password("new_password", &payload.new_password, None) {
+        return errors.into_response();
+    }
+
+    let new_hash = match state.passwords.hash_async(&payload.new_password).await {
+        Ok(h) => h,
+        Err(_) => return ApiError::internal().into_response(),
+    };
+
+    let user_id = match state.repos.tokens.use_password_reset(&hash_token(&payload.token)).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return ApiError::bad_request("invalid_reset_token", "Invalid or expired reset token").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    if state.repos.users.set_password_hash(user_id, &new_hash, None).await.is_err() {
+        return ApiError::internal().into_response();
+    }
+
+    // 같은 사용자에게 발급된 다른 재설정 링크도 더 이상 쓸 수 없게 한다
+    if let Err(e) = state.repos.tokens.expire_password_resets(user_id).await {
+        tracing::warn!("Failed to invalidate reset tokens for user {}: {}", user_id, e);
+    }
+
+    let revoked = revoke_user_sessions(&state, user_id, None).await;
+    tracing::info!("User {} reset password, {} sessions revoked", user_id, revoked);
+    StatusCode::NO_CONTENT.into_response()
+}
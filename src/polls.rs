@@ -0,0 +1,359 @@
+// --- 투표 ---
+//
+// `POST /rooms/:room/polls` 로 투표를 올리면 방에 `poll` 이벤트가 가고, 웹소켓 `vote` 이벤트로 표를 던지거나
+// 바꾸거나 거둘 때마다 방 전체에 `poll_results` 로 현재 집계를 보낸다. 익명 투표는 누가 어디에 투표했는지
+// 싣지 않는다. 투표와 표는 DB 에 남으므로 나중에 들어온 사람도 방에 들어올 때 열린 투표를 `poll` 로 받는다.
+//
+// closes_at 이 있는 투표는 스케줄러가 그 시각에 닫고 마지막 집계를 보낸다. 리마인더처럼 가장 이른 마감
+// 시각까지 잠들고, 새 투표가 생기면 깨어난다. 그림자 차단된 사용자가 올린 투표는 본인에게만 보이고, 그런
+// 사용자의 표는 집계에 넣지 않는다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::{
+    auth::{AuthUser, Claims},
+    error::{ApiError, ApiJson},
+    guest, messages, moderation,
+    protocol::ServerEvent,
+    repo::{NewPoll, PollRow, RepoResult, Repos},
+    validation::ValidationErrors,
+    word_filter, AppState,
+};
+
+const QUESTION_MAX_CHARS: usize = 300;
+const OPTION_MAX_CHARS: usize = 100;
+const OPTIONS_MIN: usize = 2;
+const OPTIONS_MAX: usize = 10;
+// 가장 먼 마감
+const POLL_MAX_DAYS: i64 = 30;
+// 방에 들어올 때 보내는 열린 투표 수
+const JOIN_POLLS_MAX: i64 = 10;
+// GET /rooms/:room/polls 가 돌려주는 최근 투표 수
+const LIST_POLLS_MAX: i64 = 50;
+
+// 마감할 투표가 없어도 이만큼마다 한 번은 확인한다
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// 스케줄러를 깨우는 신호
+#[derive(Default)]
+pub struct Scheduler {
+    wake: Notify,
+}
+
+// 선택지 하나의 집계. voters 는 익명 투표면 null.
+#[derive(Debug, Clone, Serialize)]
+pub struct PollOption {
+    text: String,
+    votes: i64,
+    voters: Option<Vec<String>>,
+}
+
+// 투표와 현재 집계. 시각은 유닉스 밀리초. my_vote 는 보는 사람이 고른 선택지로, 방 전체에 보낼 때는 싣지 않는다.
+#[derive(Debug, Clone, Serialize)]
+pub struct Poll {
+    id: i64,
+    room: String,
+    user_id: Option<i32>,
+    username: String,
+    question: String,
+    anonymous: bool,
+    options: Vec<PollOption>,
+    total_votes: i64,
+    closes_at: Option<i64>,
+    closed: bool,
+    created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    my_vote: Option<usize>,
+}
+
+impl Poll {
+    fn results_event(&self) -> ServerEvent {
+        ServerEvent::PollResults {
+            room: self.room.clone(),
+            poll_id: self.id,
+            options: self.options.clone(),
+            total_votes: self.total_votes,
+            closed: self.closed,
+        }
+    }
+}
+
+// 표를 모아 집계한다. viewer 가 있으면 그 사람이 고른 선택지도 채운다.
+async fn tally(repos: &Repos, row: PollRow, viewer: Option<i32>) -> RepoResult<Poll> {
+    let votes = repos.polls.votes(row.id, viewer).await?;
+
+    let mut options: Vec<PollOption> = row
+        .options
+        .into_iter()
+        .map(|text| PollOption { text, votes: 0, voters: (!row.anonymous).then(Vec::new) })
+        .collect();
+    let mut my_vote = None;
+    for (option, user_id, username) in votes {
+        let Some(slot) = options.get_mut(option as usize) else {
+            continue;
+        };
+        slot.votes += 1;
+        if let Some(voters) = &mut slot.voters {
+            voters.push(username);
+        }
+        if Some(user_id) == viewer {
+            my_vote = Some(option as usize);
+        }
+    }
+    Ok(Poll {
+        id: row.id,
+        room: row.room,
+        user_id: row.user_id,
+        username: row.username,
+        question: row.question,
+        anonymous: row.anonymous,
+        total_votes: options.iter().map(|o| o.votes).sum(),
+        options,
+        closes_at: row.closes_at.map(|t| t.timestamp_millis()),
+        closed: row.closed_at.is_some(),
+        created_at: row.created_at.timestamp_millis(),
+        my_vote,
+    })
+}
+
+// 방에 들어온 사용자에게 보낼 열린 투표
+pub async fn open_in_room(repos: &Repos, room: &str, viewer: i32) -> RepoResult<Vec<ServerEvent>> {
+    let rows = repos.polls.recent(room, viewer, true, JOIN_POLLS_MAX).await?;
+    let mut events = Vec::with_capacity(rows.len());
+    // 오래된 것부터 보낸다
+    for row in rows.into_iter().rev() {
+        let poll = tally(repos, row, Some(viewer)).await?;
+        events.push(ServerEvent::Poll { room: room.to_string(), poll: Box::new(poll) });
+    }
+    Ok(events)
+}
+
+// 집계를 다시 내서 방에 보낸다. 숨겨진 투표는 올린 사람에게만 간다.
+async fn publish_results(state: &AppState, row: PollRow) -> RepoResult<()> {
+    let (room, owner, hidden) = (row.room.clone(), row.user_id.unwrap_or_default(), row.hidden);
+    let poll = tally(&state.repos, row, None).await?;
+    moderation::publish(state, &room, owner, hidden, poll.results_event());
+    Ok(())
+}
+
+// 웹소켓 `vote` 이벤트. option 이 None 이면 표를 거둔다.
+pub async fn vote(
+    state: &AppState,
+    claims: &Claims,
+    room: &str,
+    poll_id: i64,
+    option: Option<usize>,
+) -> Result<(), ApiError> {
+    messages::check_can_post(state, claims, room).await?;
+    let row = match state.repos.polls.find(poll_id).await? {
+        Some(row) if row.room == room && (!row.hidden || row.user_id == Some(claims.user_id)) => row,
+        _ => return Err(ApiError::not_found("poll_not_found", "Poll not found")),
+    };
+    if row.closed_at.is_some() {
+        return Err(ApiError::conflict("poll_closed", "This poll is closed"));
+    }
+    match option {
+        Some(option) if option >= row.options.len() => {
+            return Err(ApiError::bad_request("invalid_option", "No such option"));
+        }
+        Some(option) => state.repos.polls.vote(poll_id, claims.user_id, option as i16).await?,
+        None => state.repos.polls.unvote(poll_id, claims.user_id).await?,
+    }
+    publish_results(state, row).await?;
+    Ok(())
+}
+
+pub fn spawn_task(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            let next = match close_due(&state).await {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::warn!("Failed to close polls: {}", e);
+                    None
+                }
+            };
+            let wait = next
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(IDLE_CHECK_INTERVAL, |wait| wait.min(IDLE_CHECK_INTERVAL));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.polls.wake.notified() => {}
+            }
+        }
+    });
+}
+
+// 마감 시각이 지난 투표를 닫고 마지막 집계를 보낸 뒤, 다음 마감 시각을 돌려준다
+async fn close_due(state: &AppState) -> RepoResult<Option<DateTime<Utc>>> {
+    let closed = state.repos.polls.close_due().await?;
+    for row in closed {
+        tracing::info!("Closed poll {} in '{}'", row.id, row.room);
+        publish_results(state, row).await?;
+    }
+    state.repos.polls.next_close().await
+}
+
+// --- API ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePayload {
+    question: String,
+    options: Vec<String>,
+    #[serde(default)]
+    anonymous: bool,
+    #[serde(default)]
+    closes_at: Option<DateTime<Utc>>,
+}
+
+// POST /rooms/:room/polls: 투표를 올린다
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<CreatePayload>,
+) -> Response {
+    let mut errors = ValidationErrors::default();
+    let question = payload.question.trim();
+    if question.is_empty() {
+        errors.add("question", "required", "question is required");
+    } else if question.chars().count() > QUESTION_MAX_CHARS {
+        errors.add("question", "too_long", format!("question must be at most {} characters", QUESTION_MAX_CHARS));
+    }
+    let options: Vec<String> = payload.options.iter().map(|o| o.trim().to_string()).collect();
+    if options.len() < OPTIONS_MIN || options.len() > OPTIONS_MAX {
+        errors.add(
+            "options",
+            "invalid_count",
+            format!("a poll needs between {} and {} options", OPTIONS_MIN, OPTIONS_MAX),
+        );
+    } else if options.iter().any(|o| o.is_empty()) {
+        errors.add("options", "empty_option", "options must not be empty");
+    } else if options.iter().any(|o| o.chars().count() > OPTION_MAX_CHARS) {
+        errors.add("options", "too_long", format!("options must be at most {} characters", OPTION_MAX_CHARS));
+    } else if options.iter().enumerate().any(|(i, o)| options[..i].contains(o)) {
+        errors.add("options", "duplicate_option", "options must be different");
+    }
+    let now = Utc::now();
+    match payload.closes_at {
+        Some(closes_at) if closes_at <= now => errors.add("closes_at", "in_past", "closes_at must be in the future"),
+        Some(closes_at) if closes_at > now + chrono::Duration::days(POLL_MAX_DAYS) => errors.add(
+            "closes_at",
+            "too_far",
+            format!("closes_at must be at most {} days ahead", POLL_MAX_DAYS),
+        ),
+        _ => {}
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let shadow_banned = match messages::check_can_post(&state, &claims, &room).await {
+        Ok(shadow_banned) => shadow_banned,
+        Err(e) => return e.into_response(),
+    };
+    let text = std::iter::once(question).chain(options.iter().map(String::as_str)).collect::<Vec<_>>().join("\n");
+    if let word_filter::Verdict::Reject = state.word_filter.check(&room, &text) {
+        return ApiError::bad_request("message_rejected", "Poll contains a blocked word").into_response();
+    }
+
+    let new_poll = NewPoll {
+        room: &room,
+        user_id: claims.user_id,
+        username: &claims.sub,
+        question,
+        options: &options,
+        anonymous: payload.anonymous,
+        hidden: shadow_banned,
+        closes_at: payload.closes_at,
+    };
+    let poll = match state.repos.polls.create(new_poll).await {
+        Ok(row) => tally(&state.repos, row, None).await,
+        Err(e) => Err(e),
+    };
+    match poll {
+        Ok(poll) => {
+            tracing::info!("User {} created poll {} in '{}'", claims.user_id, poll.id, room);
+            if poll.closes_at.is_some() {
+                state.polls.wake.notify_one();
+            }
+            let event = ServerEvent::Poll { room: room.clone(), poll: Box::new(poll.clone()) };
+            moderation::publish(&state, &room, claims.user_id, shadow_banned, event);
+            (StatusCode::CREATED, Json(poll)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /rooms/:room/polls: 방의 최근 투표와 집계
+pub async fn list_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+) -> Response {
+    if claims.guest && !guest::can_join(&room) {
+        return ApiError::forbidden("guest_room_forbidden", "Guests cannot join this room").into_response();
+    }
+    let rows = match state.repos.polls.recent(&room, claims.user_id, false, LIST_POLLS_MAX).await {
+        Ok(rows) => rows,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let mut polls = Vec::with_capacity(rows.len());
+    for row in rows {
+        match tally(&state.repos, row, Some(claims.user_id)).await {
+            Ok(poll) => polls.push(poll),
+            Err(e) => return ApiError::from(e).into_response(),
+        }
+    }
+    Json(polls).into_response()
+}
+
+// POST /polls/:id/close: 올린 사람이나 관리자가 마감 전에 닫는다
+pub async fn close_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    let row = match state.repos.polls.find(id).await {
+        Ok(Some(row)) if !row.hidden || row.user_id == Some(claims.user_id) => row,
+        Ok(_) => return ApiError::not_found("poll_not_found", "Poll not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if row.user_id != Some(claims.user_id) {
+        match state.repos.users.find(claims.user_id).await {
+            Ok(Some(user)) if user.is_admin => {}
+            Ok(_) => {
+                return ApiError::forbidden("poll_forbidden", "Only the author or an administrator can close this poll")
+                    .into_response()
+            }
+            Err(e) => return ApiError::from(e).into_response(),
+        }
+    }
+    if row.closed_at.is_some() {
+        return ApiError::conflict("poll_closed", "This poll is already closed").into_response();
+    }
+
+    let row = match state.repos.polls.close(id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return ApiError::conflict("poll_closed", "This poll is already closed").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    tracing::info!("User {} closed poll {}", claims.user_id, id);
+    let (room, owner, hidden) = (row.room.clone(), row.user_id.unwrap_or_default(), row.hidden);
+    match tally(&state.repos, row, None).await {
+        Ok(poll) => {
+            moderation::publish(&state, &room, owner, hidden, poll.results_event());
+            Json(poll).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,162 @@
+// --- 접속 상태 ---
+//
+// 사용자는 `PATCH /me/status` 로 상태(online, away, busy, invisible)와 짧은 상태 메시지를 정한다. 친구에게는
+// 접속해 있을 때만 이 상태가 보이고, 접속하지 않았거나 invisible 이면 offline 으로 보인다. 상태가 바뀌거나
+// 첫 웹소켓이 열리거나 마지막 연결이 닫히면 친구들의 모든 연결에 `presence` 이벤트를 보낸다. invisible 인
+// 사용자의 접속과 종료는 알리지 않는다.
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    auth::AuthUser,
+    error::{ApiError, ApiJson},
+    protocol::ServerEvent,
+    validation::ValidationErrors,
+    AppState,
+};
+
+const STATUS_MESSAGE_MAX_CHARS: usize = 100;
+
+// 친구에게 보이는 상태
+pub fn visible_status(online: bool, status: &str) -> &'static str {
+    if !online {
+        return "offline";
+    }
+    match status {
+        "away" => "away",
+        "busy" => "busy",
+        "invisible" => "offline",
+        _ => "online",
+    }
+}
+
+// 사용자가 정한 상태와 메시지. 조회에 실패하면 online.
+async fn load_status(state: &AppState, user_id: i32) -> (String, Option<String>) {
+    state
+        .repos
+        .profiles
+        .status(user_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load status of user {}: {}", user_id, e);
+            None
+        })
+        .unwrap_or_else(|| ("online".to_string(), None))
+}
+
+fn presence_event(user_id: i32, username: &str, status: &'static str, message: Option<String>) -> ServerEvent {
+    ServerEvent::Presence {
+        user_id,
+        username: username.to_string(),
+        status,
+        // offline 으로 보일 때는 메시지도 숨긴다
+        status_message: message.filter(|_| status != "offline"),
+    }
+}
+
+async fn send_to_friends(state: &AppState, user_id: i32, event: ServerEvent) {
+    match state.repos.friends.friend_ids(user_id).await {
+        Ok(friends) if !friends.is_empty() => {
+            state.connections.send_to_users(&friends, &event);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to load friends of user {} for presence: {}", user_id, e),
+    }
+}
+
+// 첫 연결이 열리거나 마지막 연결이 닫혔을 때 부른다. 실패해도 연결에는 영향이 없다.
+pub async fn notify(state: &AppState, user_id: i32, username: &str, online: bool) {
+    let (status, message) = load_status(state, user_id).await;
+    if status == "invisible" {
+        return;
+    }
+    let event = presence_event(user_id, username, visible_status(online, &status), message);
+    send_to_friends(state, user_id, event).await;
+}
+
+// 방금 친구가 된 두 사람에게 서로의 현재 상태를 알린다
+pub async fn exchange(state: &AppState, a: (i32, &str), b: (i32, &str)) {
+    for ((user_id, username), (friend_id, _)) in [(a, b), (b, a)] {
+        let (status, message) = load_status(state, user_id).await;
+        let status = visible_status(state.connections.is_online(user_id), &status);
+        state.connections.send_to_users(&[friend_id], &presence_event(user_id, username, status, message));
+    }
+}
+
+// --- 상태 API ---
+
+// 빠진 항목은 그대로 두고, 빈 message 는 메시지를 지운다
+#[derive(Debug, Deserialize)]
+pub struct UpdateStatusPayload {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+// GET /me/status
+pub async fn get_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let (status, message) = load_status(&state, claims.user_id).await;
+    Json(json!({ "status": status, "message": message })).into_response()
+}
+
+// PATCH /me/status
+pub async fn update_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<UpdateStatusPayload>,
+) -> Response {
+    let message = payload.message.map(|m| {
+        let m = m.nfkc().collect::<String>().trim().to_string();
+        (!m.is_empty()).then_some(m)
+    });
+
+    let mut errors = ValidationErrors::default();
+    if let Some(status) = &payload.status {
+        if !matches!(status.as_str(), "online" | "away" | "busy" | "invisible") {
+            errors.add("status", "invalid_value", "status must be 'online', 'away', 'busy' or 'invisible'");
+        }
+    }
+    if let Some(Some(message)) = &message {
+        if message.chars().count() > STATUS_MESSAGE_MAX_CHARS {
+            errors.add(
+                "message",
+                "too_long",
+                format!("message must be at most {} characters", STATUS_MESSAGE_MAX_CHARS),
+            );
+        }
+        if message.chars().any(char::is_control) {
+            errors.add("message", "invalid_characters", "message must not contain control characters");
+        }
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let (previous, _) = load_status(&state, claims.user_id).await;
+    let updated = state
+        .repos
+        .profiles
+        .set_status(claims.user_id, payload.status.as_deref(), message.as_ref().map(Option::as_deref))
+        .await;
+    let (status, message) = match updated {
+        Ok(Some(updated)) => updated,
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    // 숨은 채로 남아 있으면 친구에게 알릴 것이 없다
+    let online = state.connections.is_online(claims.user_id);
+    if online && !(previous == "invisible" && status == "invisible") {
+        let event = presence_event(claims.user_id, &claims.sub, visible_status(online, &status), message.clone());
+        send_to_friends(&state, claims.user_id, event).await;
+    }
+    Json(json!({ "status": status, "message": message })).into_response()
+}
@@ -0,0 +1,139 @@
+// --- 사용자 프로필 ---
+//
+// 표시 이름, 소개, 대명사를 `profiles` 테이블에 둔다. 사용자 이름은 바뀌지 않는 식별자로 토큰과 URL 에
+// 쓰고, 채팅 화면에는 표시 이름을 보여 준다 (없으면 사용자 이름). 본인만 `PATCH /users/:username` 로
+// 수정할 수 있다.
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    auth::AuthUser,
+    avatar,
+    error::{ApiError, ApiJson},
+    repo::{Profile, ProfileUpdate, RepoResult},
+    validation::{self, ValidationErrors},
+    AppState,
+};
+
+const DISPLAY_NAME_MAX_CHARS: usize = 64;
+const BIO_MAX_CHARS: usize = 500;
+const PRONOUNS_MAX_CHARS: usize = 32;
+
+// 빠진 항목은 그대로 두고, 빈 문자열은 그 항목을 지운다
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfilePayload {
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    bio: Option<String>,
+    #[serde(default)]
+    pronouns: Option<String>,
+}
+
+// 채팅 이벤트에 싣는 표시 정보
+pub struct ChatIdentity {
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+// 표시 이름이 없거나 조회에 실패하면 사용자 이름을 쓴다
+pub async fn chat_identity(state: &AppState, user_id: i32, username: &str) -> ChatIdentity {
+    let row = state.repos.profiles.display(user_id).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load profile for user {}: {}", user_id, e);
+        None
+    });
+    let (display_name, avatar_version) = row.unwrap_or_default();
+    ChatIdentity {
+        display_name: display_name.unwrap_or_else(|| username.to_string()),
+        avatar_url: avatar::avatar_url(username, avatar_version.as_deref()),
+    }
+}
+
+// 앞뒤 공백을 없애고, 비어 있으면 None (항목 삭제)
+fn normalize_field(value: &str) -> Option<String> {
+    let value = value.nfkc().collect::<String>().trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+// 지우는 요청(None)은 검사하지 않는다
+fn check_field(
+    field: &'static str,
+    value: &Option<Option<String>>,
+    max_chars: usize,
+    allow_newlines: bool,
+    errors: &mut ValidationErrors,
+) {
+    let Some(Some(value)) = value else {
+        return;
+    };
+    if value.chars().count() > max_chars {
+        errors.add(field, "too_long", format!("{} must be at most {} characters", field, max_chars));
+    }
+    if value.chars().any(|c| c.is_control() && !(allow_newlines && c == '\n')) {
+        errors.add(field, "invalid_characters", format!("{} must not contain control characters", field));
+    }
+}
+
+async fn load_profile(state: &AppState, username: &str) -> RepoResult<Option<Profile>> {
+    let profile = state.repos.profiles.find_by_name(&validation::normalize_username(username)).await?;
+    Ok(profile.map(|mut p| {
+        p.avatar_url = avatar::avatar_url(&p.username, p.avatar_version.as_deref());
+        p
+    }))
+}
+
+// GET /users/:username
+pub async fn get_handler(State(state): State<AppState>, _user: AuthUser, Path(username): Path<String>) -> Response {
+    match load_profile(&state, &username).await {
+        Ok(Some(profile)) => Json(profile).into_response(),
+        Ok(None) => ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PATCH /users/:username: 본인 프로필만 수정할 수 있다
+pub async fn update_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(username): Path<String>,
+    ApiJson(payload): ApiJson<UpdateProfilePayload>,
+) -> Response {
+    match load_profile(&state, &username).await {
+        Ok(Some(profile)) if profile.user_id == claims.user_id => {}
+        Ok(Some(_)) => {
+            return ApiError::forbidden("profile_forbidden", "You can only edit your own profile").into_response()
+        }
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let display_name = payload.display_name.as_deref().map(normalize_field);
+    let bio = payload.bio.as_deref().map(|b| normalize_field(&b.replace("\r\n", "\n")));
+    let pronouns = payload.pronouns.as_deref().map(normalize_field);
+
+    let mut errors = ValidationErrors::default();
+    check_field("display_name", &display_name, DISPLAY_NAME_MAX_CHARS, false, &mut errors);
+    check_field("bio", &bio, BIO_MAX_CHARS, true, &mut errors);
+    check_field("pronouns", &pronouns, PRONOUNS_MAX_CHARS, false, &mut errors);
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let update = ProfileUpdate { display_name, bio, pronouns };
+    if let Err(e) = state.repos.profiles.update(claims.user_id, &update).await {
+        return ApiError::from(e).into_response();
+    }
+    tracing::info!("User {} updated profile", claims.user_id);
+
+    match load_profile(&state, &claims.sub).await {
+        Ok(Some(profile)) => Json(profile).into_response(),
+        Ok(None) => ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
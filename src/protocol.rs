@@ -0,0 +1,305 @@
+// --- 클라이언트/서버 이벤트 프로토콜 ---
+//
+// 웹소켓 프레임은 `type` 필드로 구분되는 객체다. 기본은 JSON 텍스트 프레임이고, 서브프로토콜로
+// `webchat-msgpack` 을 고른 연결은 같은 객체를 MessagePack 바이너리 프레임으로 주고받는다(Encoding).
+// JSON 이 아닌 텍스트 프레임은 예전 클라이언트 호환을 위해 채팅 메시지로 취급한다.
+//
+// 이벤트 형식을 호환되지 않게 바꿀 때는 SUPPORTED_VERSIONS 에 새 버전을 더한다. 클라이언트는 첫 이벤트로
+// `hello` 를 보내 자기가 쓰는 버전을 알리고, 서버는 지원하는 버전과 기능 목록으로 답한다. 이전 버전은 지원하는
+// 동안 목록에 남겨 두고, 그 버전의 연결에는 예전 형식으로 보낸다. 지원하지 않는 버전은 4010 으로 끊는다.
+
+use axum::extract::ws::Message;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{
+    markdown::MessageFormat,
+    messages::Forwarded,
+    polls::{Poll, PollOption},
+    repo::Notification,
+};
+
+// 받아들이는 프로토콜 버전. 마지막이 현재 버전이다.
+pub const SUPPORTED_VERSIONS: &[u32] = &[1];
+
+// hello 에 답할 때 알려 주는 기능. 새 이벤트나 필드를 더하면 이름을 붙여 둔다.
+pub const CAPABILITIES: &[&str] =
+    &["markdown", "ephemeral", "polls", "forwarding", "refresh_token", "lagged", "msgpack"];
+
+// 클라이언트 -> 서버
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientEvent {
+    // format 을 생략하면 plain. ttl_seconds 를 주면 그만큼 지난 뒤 메시지가 사라진다.
+    Message {
+        text: String,
+        #[serde(default)]
+        format: MessageFormat,
+        #[serde(default)]
+        ttl_seconds: Option<u64>,
+    },
+    // 투표에 표를 던진다. option 은 0 부터 세며, null 이면 표를 거둔다.
+    Vote { poll_id: i64, option: Option<usize> },
+    // 연결을 끊지 않고 액세스 토큰을 교체한다
+    RefreshToken { token: String },
+    // 연결의 첫 이벤트로 클라이언트가 쓰는 프로토콜 버전을 알린다
+    Hello { version: u32 },
+}
+
+// 연결의 프레임 인코딩
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+impl ClientEvent {
+    pub fn parse(text: &str) -> Result<Self, serde_json::Error> {
+        if !text.trim_start().starts_with('{') {
+            return Ok(ClientEvent::Message { text: text.to_string(), format: MessageFormat::Plain, ttl_seconds: None });
+        }
+        serde_json::from_str(text)
+    }
+
+    // MessagePack 연결의 바이너리 프레임. 필드 이름이 있는 맵이어야 한다.
+    pub fn decode(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+}
+
+// 서버 -> 클라이언트
+// username 은 바뀌지 않는 식별자, display_name 은 화면에 보여 줄 이름 (프로필에 없으면 username),
+// avatar_url 은 아바타가 없으면 null, bot 은 봇 계정이 보낸 메시지인지
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    // message_id 는 저장에 실패하면 null. markdown 이면 html 에 서버가 정리한 HTML 이 있다.
+    // expires_at(유닉스 밀리초)이 있으면 그때 message_expired 이벤트와 함께 사라진다.
+    // forwarded 는 다른 방에서 전달한 메시지일 때 원래 메시지의 작성자와 위치.
+    Message {
+        room: String,
+        message_id: Option<i64>,
+        user_id: i32,
+        username: String,
+        display_name: String,
+        avatar_url: Option<String>,
+        bot: bool,
+        text: String,
+        format: MessageFormat,
+        html: Option<String>,
+        sent_at: i64,
+        expires_at: Option<i64>,
+        forwarded: Option<Forwarded>,
+    },
+    // 첨부 파일이 붙은 메시지. url 은 url_expires_at(유닉스 초)까지 유효하다.
+    Attachment {
+        room: String,
+        message_id: i64,
+        user_id: i32,
+        username: String,
+        display_name: String,
+        avatar_url: Option<String>,
+        bot: bool,
+        text: Option<String>,
+        attachment: AttachmentInfo,
+        sent_at: i64,
+    },
+    // 음성 메시지. voice.duration_ms 에 길이가 있다.
+    VoiceMessage {
+        room: String,
+        message_id: i64,
+        user_id: i32,
+        username: String,
+        display_name: String,
+        avatar_url: Option<String>,
+        bot: bool,
+        voice: AttachmentInfo,
+        sent_at: i64,
+    },
+    // message_id 메시지에 있던 url 의 미리보기. 메시지보다 나중에 온다.
+    LinkPreview {
+        room: String,
+        message_id: i64,
+        url: String,
+        title: Option<String>,
+        description: Option<String>,
+        image_url: Option<String>,
+        site_name: Option<String>,
+    },
+    // 중재자가 메시지를 지웠다
+    MessageDeleted {
+        room: String,
+        message_id: i64,
+    },
+    // 수명이 다한 메시지가 지워졌다
+    MessageExpired {
+        room: String,
+        message_id: i64,
+    },
+    // 새 투표. 방에 들어올 때도 열린 투표를 이 이벤트로 받는다.
+    Poll {
+        room: String,
+        poll: Box<Poll>,
+    },
+    // 투표의 현재 집계. 표가 바뀌거나 투표가 닫힐 때 보낸다.
+    PollResults {
+        room: String,
+        poll_id: i64,
+        options: Vec<PollOption>,
+        total_votes: i64,
+        closed: bool,
+    },
+    // 중재자가 사용자를 muted_until(유닉스 초)까지 음소거했다
+    UserMuted {
+        room: String,
+        user_id: i32,
+        username: String,
+        muted_until: i64,
+    },
+    // 관리자가 사용자의 이 방 연결을 끊었다 (/kick)
+    UserKicked {
+        room: String,
+        user_id: i32,
+        username: String,
+        kicked_by: String,
+        reason: Option<String>,
+    },
+    // 방 주제. 바뀔 때와 방에 들어올 때 온다. set_by 는 정한 사람이 계정을 지웠으면 null.
+    Topic {
+        room: String,
+        topic: String,
+        set_by: Option<String>,
+        set_at: i64,
+    },
+    // `/remind here` 로 예약한 리마인더. created_at 은 예약한 시각.
+    Reminder {
+        room: String,
+        user_id: i32,
+        username: String,
+        text: String,
+        created_at: i64,
+    },
+    // 슬래시 명령의 응답. 명령을 보낸 연결에만 온다.
+    CommandResponse {
+        room: String,
+        command: String,
+        text: String,
+    },
+    // 수신 웹훅이 올린 메시지. 사용자가 아니므로 user_id 대신 webhook_id 와 웹훅 이름이 온다.
+    WebhookMessage {
+        room: String,
+        message_id: i64,
+        webhook_id: i64,
+        name: String,
+        text: String,
+        sent_at: i64,
+    },
+    // 관리자 공지
+    Announcement {
+        room: String,
+        text: String,
+        sent_at: i64,
+    },
+    Join {
+        room: String,
+        username: String,
+        display_name: String,
+        avatar_url: Option<String>,
+    },
+    Leave {
+        room: String,
+        username: String,
+        display_name: String,
+        avatar_url: Option<String>,
+    },
+    // 친구의 접속 상태가 바뀌었다. 방과 관계없이 모든 연결로 온다.
+    // status 는 "online", "away", "busy", "offline". offline 이면 status_message 는 null.
+    Presence {
+        user_id: i32,
+        username: String,
+        status: &'static str,
+        status_message: Option<String>,
+    },
+    // 새 알림. 방과 관계없이 모든 연결로 온다.
+    Notification(Notification),
+    TokenRefreshed {
+        expires_at: i64,
+    },
+    // hello 에 대한 답. version 은 이 연결에 쓰는 버전.
+    Hello {
+        version: u32,
+        versions: &'static [u32],
+        capabilities: &'static [&'static str],
+    },
+    Error {
+        code: String,
+        message: String,
+    },
+    // 방 이벤트를 따라오지 못해 skipped 개를 건너뛰었다 (ROOM_LAG_POLICY=drop_oldest).
+    // 클라이언트는 기록을 다시 불러와 빠진 메시지를 채운다.
+    Lagged {
+        room: String,
+        skipped: u64,
+    },
+    // 서버가 종료하려 한다. 곧 `1001 server_shutdown` 으로 연결을 닫으므로 잠시 뒤 다시 접속한다.
+    ServerShuttingDown,
+    // 다른 서버에서 중계받은 이벤트. 이미 JSON 으로 직렬화되어 있으므로 그대로 보낸다.
+    #[serde(skip)]
+    Relayed(Arc<str>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentInfo {
+    pub id: String,
+    // "file" 또는 "voice"
+    pub kind: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    // 이미지일 때 원본 크기
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub url: String,
+    pub url_expires_at: i64,
+    pub thumbnail: Option<ThumbnailInfo>,
+    // 음성 메시지 길이
+    pub duration_ms: Option<i32>,
+}
+
+// 미리보기 URL 은 원본 url 과 같은 시각에 만료된다
+#[derive(Debug, Clone, Serialize)]
+pub struct ThumbnailInfo {
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ServerEvent {
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        ServerEvent::Error {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            ServerEvent::Relayed(json) => json.to_string(),
+            event => serde_json::to_string(event).expect("ServerEvent is always serializable"),
+        }
+    }
+
+    pub fn encode(&self, encoding: Encoding) -> Message {
+        match (encoding, self) {
+            (Encoding::Json, event) => Message::Text(event.to_json()),
+            // 중계받은 JSON 은 한 번 풀어서 옮겨 담는다
+            (Encoding::MessagePack, ServerEvent::Relayed(json)) => {
+                let value: serde_json::Value = serde_json::from_str(json).unwrap_or_default();
+                Message::Binary(rmp_serde::to_vec_named(&value).expect("JSON values are always serializable"))
+            }
+            (Encoding::MessagePack, event) => {
+                Message::Binary(rmp_serde::to_vec_named(event).expect("ServerEvent is always serializable"))
+            }
+        }
+    }
+}
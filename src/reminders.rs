@@ -0,0 +1,185 @@
+// --- 리마인더 ---
+//
+// `/remind me in 10m 물 마시기` 처럼 예약한 알림을 `reminders` 에 저장하고, 스케줄러 태스크가 시각이 되면
+// 보낸다. `me` 는 본인에게 `reminder` 알림(접속해 있으면 `notification` 이벤트)으로, `here` 는 예약한 방 전체에
+// `reminder` 이벤트로 간다. DB 에 남아 있으므로 서버가 꺼져 있던 사이에 시각이 지난 리마인더는 다시 켜질 때 보낸다.
+//
+// 스케줄러는 가장 이른 예약 시각까지 잠들고, 새 리마인더가 생기면 깨어나 다시 계산한다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::{
+    auth::AuthUser,
+    error::ApiError,
+    moderation,
+    notifications::{self, Notice},
+    protocol::ServerEvent,
+    repo::{DueReminder, RepoResult},
+    AppState,
+};
+
+// 사용자 한 명이 걸어 둘 수 있는 리마인더 수
+pub const REMINDERS_PER_USER_MAX: i64 = 25;
+// 가장 먼 예약
+pub const REMIND_MAX_DAYS: i64 = 365;
+
+// 예약이 없어도 이만큼마다 한 번은 확인한다
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// 한 번에 보내는 최대 수
+const DELIVER_BATCH: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Me,
+    Room,
+}
+
+impl Target {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Target::Me => "me",
+            Target::Room => "room",
+        }
+    }
+}
+
+// "10m", "1h30m", "2d" 같은 기간. 단위는 s, m, h, d, w.
+pub fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().ok()?;
+        number.clear();
+        let part = match c.to_ascii_lowercase() {
+            's' => chrono::Duration::try_seconds(n)?,
+            'm' => chrono::Duration::try_minutes(n)?,
+            'h' => chrono::Duration::try_hours(n)?,
+            'd' => chrono::Duration::try_days(n)?,
+            'w' => chrono::Duration::try_weeks(n)?,
+            _ => return None,
+        };
+        total = total.checked_add(&part)?;
+    }
+    // 마지막 숫자에 단위가 없으면 잘못된 기간
+    if !number.is_empty() || total <= chrono::Duration::zero() {
+        return None;
+    }
+    Some(total)
+}
+
+// 스케줄러를 깨우는 신호
+#[derive(Default)]
+pub struct Scheduler {
+    wake: Notify,
+}
+
+// 리마인더를 저장하고 스케줄러를 깨운다
+pub async fn schedule(
+    state: &AppState,
+    user_id: i32,
+    room: &str,
+    target: Target,
+    text: &str,
+    remind_at: DateTime<Utc>,
+) -> RepoResult<i64> {
+    let id = state.repos.reminders.create(user_id, room, target.as_str(), text, remind_at).await?;
+    state.reminders.wake.notify_one();
+    Ok(id)
+}
+
+pub fn spawn_task(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            let next = match deliver_due(&state).await {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::warn!("Failed to deliver reminders: {}", e);
+                    None
+                }
+            };
+            let wait = next
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(IDLE_CHECK_INTERVAL, |wait| wait.min(IDLE_CHECK_INTERVAL));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.reminders.wake.notified() => {}
+            }
+        }
+    });
+}
+
+// 시각이 된 리마인더를 보내고 지운 뒤, 다음 예약 시각을 돌려준다
+async fn deliver_due(state: &AppState) -> RepoResult<Option<DateTime<Utc>>> {
+    loop {
+        let due = state.repos.reminders.take_due(DELIVER_BATCH).await?;
+        for reminder in &due {
+            deliver(state, reminder).await;
+        }
+        if (due.len() as i64) < DELIVER_BATCH {
+            break;
+        }
+    }
+    state.repos.reminders.next_due().await
+}
+
+async fn deliver(state: &AppState, reminder: &DueReminder) {
+    tracing::info!("Delivering reminder {} of user {}", reminder.id, reminder.user_id);
+    if reminder.target == Target::Me.as_str() {
+        let notice = Notice {
+            kind: "reminder",
+            room: Some(&reminder.room),
+            message_id: None,
+            actor: None,
+            text: reminder.text.clone(),
+        };
+        notifications::create(state, reminder.user_id, notice).await;
+        return;
+    }
+    // 그림자 차단된 사용자의 방 리마인더는 본인에게만 보인다
+    let shadow_banned = moderation::restrictions(state, reminder.user_id)
+        .await
+        .is_ok_and(|r| r.shadow_banned);
+    let event = ServerEvent::Reminder {
+        room: reminder.room.clone(),
+        user_id: reminder.user_id,
+        username: reminder.username.clone(),
+        text: reminder.text.clone(),
+        created_at: reminder.created_at.timestamp_millis(),
+    };
+    moderation::publish(state, &reminder.room, reminder.user_id, shadow_banned, event);
+}
+
+// --- API ---
+
+// GET /me/reminders: 아직 보내지 않은 리마인더
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.reminders.list(claims.user_id).await {
+        Ok(reminders) => Json(reminders).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/reminders/:id: 보내기 전에 취소한다
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.reminders.delete(id, claims.user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("reminder_not_found", "Reminder not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,53 @@
+// --- 계정: 메일 주소, 게스트, 탈퇴 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::RepoResult;
+
+// 게스트를 정식 계정으로 바꿀 때 정하는 값
+pub struct GuestUpgrade<'a> {
+    pub user_id: i32,
+    pub username: &'a str,
+    pub password_hash: &'a str,
+    pub email: Option<&'a str>,
+    // 있으면 같은 트랜잭션에서 사용 처리한다
+    pub invite_code: Option<&'a str>,
+}
+
+#[async_trait]
+pub trait AccountRepo: Send + Sync {
+    // 대소문자를 구분하지 않고 찾은 (id, 저장된 주소)
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<(i32, String)>>;
+
+    // 주소를 바꾸고 확인 상태를 지운다. 다른 계정이 쓰는 주소면 Conflict("email").
+    async fn set_email(&self, user_id: i32, email: &str) -> RepoResult<()>;
+
+    // (주소, 확인했는지)
+    async fn email_status(&self, user_id: i32) -> RepoResult<Option<(Option<String>, bool)>>;
+
+    // 주소를 확인했거나 봇이면 true
+    async fn is_verified(&self, user_id: i32) -> RepoResult<bool>;
+
+    // 이름이 이미 있으면 None
+    async fn create_guest(&self, username: &str) -> RepoResult<Option<i32>>;
+
+    // 게스트가 아니면 false. 이름이나 주소가 겹치면 Conflict, 초대 코드가 틀리면 InvalidInvite.
+    async fn upgrade_guest(&self, upgrade: &GuestUpgrade<'_>) -> RepoResult<bool>;
+
+    // created_before 전에 만든 게스트를 지우고 지운 수를 돌려준다
+    async fn purge_guests(&self, created_before: DateTime<Utc>) -> RepoResult<u64>;
+
+    // 관리자 계정을 만든다. 주소가 있으면 확인한 것으로 둔다. 이름이나 주소가 겹치면 Conflict.
+    async fn create_admin(&self, username: &str, password_hash: &str, email: Option<&str>) -> RepoResult<i32>;
+
+    // 있는 사용자를 관리자로 올린다
+    async fn grant_admin(&self, user_id: i32) -> RepoResult<()>;
+
+    // 사용자가 소유한 봇
+    async fn bot_ids(&self, owner_id: i32) -> RepoResult<Vec<i32>>;
+
+    // 계정을 지운다. anonymize_as 가 있으면 메시지의 작성자만 그 이름으로 바꾸고, 없으면 메시지도 지운다.
+    // (처리한 메시지 수, 저장소에서 지워야 할 첨부 파일 키)
+    async fn delete_account(&self, user_id: i32, anonymize_as: Option<&str>) -> RepoResult<(u64, Vec<String>)>;
+}
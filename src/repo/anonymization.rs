@@ -0,0 +1,99 @@
+// --- 지워진 계정 익명화 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::RepoResult;
+
+// 종류별로 고친 행 수
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Touched {
+    pub accounts: u64,
+    pub messages: u64,
+    pub attachments: u64,
+    pub mentions: u64,
+    pub notifications: u64,
+    pub forwards: u64,
+    pub polls: u64,
+    pub reports: u64,
+    pub archived: u64,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct AnonymizationJob {
+    pub id: i64,
+    pub status: String,
+    pub touched: SqlJson<Touched>,
+    pub error: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait AnonymizationRepo: Send + Sync {
+    // 돌고 있는 작업이 있으면 None
+    async fn create(&self, created_by: i32) -> RepoResult<Option<AnonymizationJob>>;
+
+    // 최근 작업부터
+    async fn list(&self, limit: i64) -> RepoResult<Vec<AnonymizationJob>>;
+
+    async fn find(&self, id: i64) -> RepoResult<Option<AnonymizationJob>>;
+
+    // 끝나지 않은 작업과 그동안 고친 행 수
+    async fn running(&self) -> RepoResult<Option<(i64, Touched)>>;
+
+    async fn save_progress(&self, id: i64, touched: &Touched) -> RepoResult<()>;
+
+    async fn finish(&self, id: i64, status: &str, touched: &Touched, error: Option<&str>) -> RepoResult<()>;
+
+    // 아직 처리하지 않은 지워진 계정 수
+    async fn pending_accounts(&self) -> RepoResult<i64>;
+
+    // 아직 처리하지 않은 지워진 계정 (id, 이름)
+    async fn deleted_accounts(&self, limit: i64) -> RepoResult<Vec<(i32, String)>>;
+
+    // 계정들이 쓴 메시지와 전달한 메시지, 투표, 보관함을 한 트랜잭션에서 고친다. 저장소에서 지울 첨부 파일 키를
+    // 돌려준다.
+    async fn scrub_accounts(
+        &self,
+        ids: &[i32],
+        names: &[String],
+        deleted_name: &str,
+        touched: &mut Touched,
+    ) -> RepoResult<Vec<String>>;
+
+    // 처리한 계정을 deleted_accounts 에서 지운다
+    async fn forget_accounts(&self, ids: &[i32]) -> RepoResult<()>;
+
+    // 작성자가 deleted_name 인데 내용이 남은 메시지를 limit 개까지 비운다. (비운 수, 지울 첨부 파일 키)
+    async fn scrub_anonymized(
+        &self,
+        deleted_name: &str,
+        limit: i64,
+        touched: &mut Touched,
+    ) -> RepoResult<(i64, Vec<String>)>;
+
+    // 내용이 patterns(LIKE, 대소문자 무시) 중 하나에 맞는 메시지 (id, 내용, 형식)
+    async fn mentioning_messages(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String, String)>>;
+
+    async fn rewrite_message(&self, id: i64, content: &str, html: Option<&str>) -> RepoResult<()>;
+
+    // 글이 patterns 중 하나에 맞는 알림 (id, 글)
+    async fn mentioning_notifications(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String)>>;
+
+    async fn rewrite_notification(&self, id: i64, text: &str) -> RepoResult<()>;
+}
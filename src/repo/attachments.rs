@@ -0,0 +1,41 @@
+// --- 메시지 첨부 파일 ---
+
+use axum::async_trait;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(FromRow)]
+pub struct Attachment {
+    pub id: String,
+    // 'file' 또는 'voice'
+    pub kind: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub storage_key: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub thumbnail_key: Option<String>,
+    pub thumbnail_width: Option<i32>,
+    pub thumbnail_height: Option<i32>,
+    pub duration_ms: Option<i32>,
+}
+
+// 첨부를 싣는 메시지
+pub struct AttachmentPost<'a> {
+    pub user_id: i32,
+    pub username: &'a str,
+    pub room: &'a str,
+    pub text: Option<&'a str>,
+    // 그림자 차단된 사용자의 글
+    pub hidden: bool,
+}
+
+#[async_trait]
+pub trait AttachmentRepo: Send + Sync {
+    async fn find(&self, id: &str) -> RepoResult<Option<Attachment>>;
+
+    // 메시지와 첨부 행을 한 트랜잭션으로 기록하고 메시지 id 를 돌려준다
+    async fn create(&self, post: AttachmentPost<'_>, attachment: &Attachment) -> RepoResult<i64>;
+}
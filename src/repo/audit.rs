@@ -0,0 +1,42 @@
+// --- 감사 기록 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::RepoResult;
+
+// 남길 기록. actor 는 당시의 id 와 이름이다.
+pub struct NewAuditEntry<'a> {
+    pub actor_id: i32,
+    pub actor_name: &'a str,
+    pub action: &'a str,
+    pub target_type: &'static str,
+    pub target_id: String,
+    pub reason: Option<&'a str>,
+    pub details: serde_json::Value,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub actor_id: Option<i32>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub reason: Option<String>,
+    pub details: SqlJson<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 기록은 추가만 한다
+#[async_trait]
+pub trait AuditRepo: Send + Sync {
+    async fn record(&self, entry: &NewAuditEntry<'_>) -> RepoResult<()>;
+
+    // since 이후의, action 인 기록 limit 개. 최근 것부터.
+    async fn list(&self, since: Option<DateTime<Utc>>, action: Option<&str>, limit: i64)
+        -> RepoResult<Vec<AuditEntry>>;
+}
@@ -0,0 +1,73 @@
+// --- 봇 계정과 API 토큰 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Bot {
+    pub id: i32,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// SQLite 는 scopes 를 JSON 문자열로 저장하므로 FromRow 는 Postgres 에서만 쓴다
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BotToken {
+    pub id: i64,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// 유효한 토큰과 그 봇
+#[derive(Debug, Clone)]
+pub struct BotTokenOwner {
+    pub token_id: i64,
+    pub bot_id: i32,
+    pub username: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait BotRepo: Send + Sync {
+    // 폐기·만료되지 않았고 봇이 활성 상태인 토큰이면 사용 시각을 남기고 돌려준다
+    async fn authenticate(&self, token_hash: &str) -> RepoResult<Option<BotTokenOwner>>;
+
+    // owner_id 가 소유한 봇인지
+    async fn is_owner(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool>;
+
+    async fn list(&self, owner_id: i32) -> RepoResult<Vec<Bot>>;
+
+    async fn count(&self, owner_id: i32) -> RepoResult<i64>;
+
+    // 이름이 이미 있으면 Conflict("username")
+    async fn create(&self, owner_id: i32, username: &str) -> RepoResult<Bot>;
+
+    // 토큰도 외래 키로 함께 지워진다. owner_id 의 봇이 아니면 false.
+    async fn delete(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool>;
+
+    async fn tokens(&self, bot_id: i32) -> RepoResult<Vec<BotToken>>;
+
+    // 폐기되지 않은 토큰 수
+    async fn active_token_count(&self, bot_id: i32) -> RepoResult<i64>;
+
+    async fn create_token(
+        &self,
+        bot_id: i32,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<BotToken>;
+
+    // 아직 폐기되지 않은 봇의 토큰이면 폐기하고 true
+    async fn revoke_token(&self, token_id: i64, bot_id: i32) -> RepoResult<bool>;
+}
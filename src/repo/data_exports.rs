@@ -0,0 +1,96 @@
+// --- 개인 데이터 내보내기 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct ExportStatus {
+    pub id: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ExportedPasskey {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct LinkedAccount {
+    pub provider: String,
+    pub email: Option<String>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ExportedSession {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+// 글을 쓴 방 하나
+#[derive(Serialize, FromRow)]
+pub struct RoomActivity {
+    pub room: Option<String>,
+    pub messages: i64,
+    pub first_message_at: Option<DateTime<Utc>>,
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct UserMessage {
+    pub id: i64,
+    pub room: Option<String>,
+    pub content: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// 사용자에 대해 저장된 정보. 비밀번호 해시, 토큰, 2단계 인증 비밀키 같은 자격 증명은 읽지 않는다.
+pub struct PersonalData {
+    pub username: String,
+    pub email: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub pronouns: Option<String>,
+    pub two_factor: bool,
+    pub passkeys: Vec<ExportedPasskey>,
+    pub linked_accounts: Vec<LinkedAccount>,
+    pub sessions: Vec<ExportedSession>,
+    pub rooms: Vec<RoomActivity>,
+    pub messages: Vec<UserMessage>,
+}
+
+#[async_trait]
+pub trait DataExportRepo: Send + Sync {
+    // 보관 기간이 지난 작업을 지운다
+    async fn purge_expired(&self) -> RepoResult<u64>;
+
+    // 아직 만들고 있는 작업
+    async fn pending(&self, user_id: i32) -> RepoResult<Option<ExportStatus>>;
+
+    async fn create(&self, id: &str, user_id: i32, expires_at: DateTime<Utc>) -> RepoResult<ExportStatus>;
+
+    // 만료되지 않은 작업, 최근 것부터
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ExportStatus>>;
+
+    async fn find(&self, id: &str, user_id: i32) -> RepoResult<Option<ExportStatus>>;
+
+    // (상태, 아카이브)
+    async fn archive(&self, id: &str, user_id: i32) -> RepoResult<Option<(String, Option<String>)>>;
+
+    // 작업을 끝낸다. 실패했으면 archive 는 None.
+    async fn finish(&self, id: &str, status: &str, archive: Option<&str>) -> RepoResult<()>;
+
+    async fn personal_data(&self, user_id: i32) -> RepoResult<PersonalData>;
+}
@@ -0,0 +1,43 @@
+// --- 친구 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+// 나와 관계가 있는 사용자 하나
+#[derive(FromRow)]
+pub struct ContactRow {
+    pub user_id: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub status: String,
+    pub status_message: Option<String>,
+    pub accepted: bool,
+    // 내가 보낸 신청인지
+    pub outgoing: bool,
+    pub since: DateTime<Utc>,
+}
+
+// 두 사람 사이에는 방향과 관계없이 관계가 하나뿐이다
+#[async_trait]
+pub trait FriendRepo: Send + Sync {
+    // 친구로 수락된 사용자 id
+    async fn friend_ids(&self, user_id: i32) -> RepoResult<Vec<i32>>;
+
+    // 친구와 주고받은 신청. 사용자 이름 순.
+    async fn contacts(&self, user_id: i32) -> RepoResult<Vec<ContactRow>>;
+
+    // 이미 관계가 있으면 Conflict("friendship")
+    async fn request(&self, requester_id: i32, addressee_id: i32) -> RepoResult<()>;
+
+    // 대기 중인 신청을 수락하고 신청한 사람의 이름을 돌려준다. 그런 신청이 없으면 None.
+    async fn accept(&self, requester_id: i32, addressee_id: i32) -> RepoResult<Option<String>>;
+
+    // 두 사람 사이의 관계가 수락됐는지. 관계가 없으면 None.
+    async fn is_accepted(&self, a: i32, b: i32) -> RepoResult<Option<bool>>;
+
+    // 어느 쪽이 신청했든 관계를 지운다. 지웠으면 true.
+    async fn delete(&self, a: i32, b: i32) -> RepoResult<bool>;
+}
@@ -0,0 +1,14 @@
+// --- 상태 확인 ---
+
+use axum::async_trait;
+
+use super::RepoResult;
+
+#[async_trait]
+pub trait HealthRepo: Send + Sync {
+    // DB 가 쿼리에 답하는지
+    async fn ping(&self) -> RepoResult<()>;
+
+    // 적용된 마이그레이션 버전, 오래된 것부터. 한 번도 마이그레이션하지 않은 DB 면 비어 있다.
+    async fn applied_migrations(&self) -> RepoResult<Vec<i64>>;
+}
@@ -0,0 +1,75 @@
+// --- Slack/Discord 기록 가져오기 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct ImportJob {
+    pub id: i64,
+    pub source: String,
+    pub room_prefix: String,
+    pub status: String,
+    pub rooms: i32,
+    pub users_created: i32,
+    pub messages_imported: i32,
+    pub messages_skipped: i32,
+    pub error: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportProgress {
+    pub rooms: i32,
+    pub users_created: i32,
+    pub imported: i32,
+    pub skipped: i32,
+}
+
+// 가져온 메시지 하나. imported_from 이 이미 있으면 건너뛴다.
+pub struct ImportedRow<'a> {
+    pub user_id: Option<i32>,
+    pub username: &'a str,
+    pub text: &'a str,
+    pub sent_at: DateTime<Utc>,
+    pub imported_from: String,
+}
+
+#[async_trait]
+pub trait ImportRepo: Send + Sync {
+    async fn create(&self, source: &str, room_prefix: &str, created_by: i32) -> RepoResult<ImportJob>;
+
+    // 최근 작업부터
+    async fn list(&self, limit: i64) -> RepoResult<Vec<ImportJob>>;
+
+    async fn find(&self, id: i64) -> RepoResult<Option<ImportJob>>;
+
+    async fn set_status(&self, id: i64, status: &str) -> RepoResult<()>;
+
+    async fn set_progress(&self, id: i64, progress: ImportProgress) -> RepoResult<()>;
+
+    async fn finish(&self, id: i64, status: &str, error: Option<&str>) -> RepoResult<()>;
+
+    // 전에 가져온 사용자 (id, 이름)
+    async fn imported_user(&self, imported_from: &str) -> RepoResult<Option<(i32, String)>>;
+
+    // 로그인할 수 없는 자리표시 사용자를 만든다. 이름이 이미 있으면 None.
+    async fn create_placeholder(
+        &self,
+        username: &str,
+        bot: bool,
+        imported_from: &str,
+        display_name: Option<&str>,
+    ) -> RepoResult<Option<i32>>;
+
+    // 주제가 없는 방에만 넣는다
+    async fn set_topic(&self, room: &str, topic: &str) -> RepoResult<()>;
+
+    // 새로 넣은 메시지 수
+    async fn insert_messages(&self, room: &str, messages: &[ImportedRow<'_>]) -> RepoResult<u64>;
+}
@@ -0,0 +1,38 @@
+// --- 초대 코드 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct Invite {
+    pub id: i64,
+    pub created_by: Option<i32>,
+    pub max_uses: i32,
+    pub uses: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 코드는 해시로만 주고받는다. 사용 처리는 가입과 같은 트랜잭션에서 해야 하므로 가입하는 쪽 (UserRepo::create,
+// AccountRepo::upgrade_guest) 이 한다.
+#[async_trait]
+pub trait InviteRepo: Send + Sync {
+    async fn create(
+        &self,
+        code_hash: &str,
+        created_by: i32,
+        max_uses: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<i64>;
+
+    // 최근 것부터
+    async fn list(&self) -> RepoResult<Vec<Invite>>;
+
+    // 폐기했으면 true
+    async fn revoke(&self, id: i64) -> RepoResult<bool>;
+}
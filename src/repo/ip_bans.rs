@@ -0,0 +1,87 @@
+// --- IP 차단 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use std::net::IpAddr;
+
+use super::RepoResult;
+
+// network 는 "주소/접두사 길이" 형식으로, 호스트 부분은 지워져 있다
+#[derive(Serialize, FromRow)]
+pub struct IpBan {
+    pub id: i64,
+    pub network: String,
+    pub reason: Option<String>,
+    pub created_by: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait IpBanRepo: Send + Sync {
+    // 만료되지 않은 차단 중 ip 를 포함하는 것이 있는지
+    async fn is_banned(&self, ip: IpAddr) -> RepoResult<bool>;
+
+    // 만료되지 않은 것만, 최근 것부터
+    async fn list(&self) -> RepoResult<Vec<IpBan>>;
+
+    // network 는 "주소" 또는 "주소/접두사 길이"로 검사를 마친 값
+    async fn create(
+        &self,
+        network: &str,
+        reason: Option<&str>,
+        created_by: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<IpBan>;
+
+    // 지운 차단의 network. 없으면 None.
+    async fn delete(&self, id: i64) -> RepoResult<Option<String>>;
+}
+
+// --- 대역 계산 ---
+//
+// SQLite 와 MySQL 에는 INET 타입이 없으므로 두 저장소는 대역을 "주소/접두사 길이" 문자열로 두고 포함 관계를 여기서
+// 계산한다.
+
+// 주소를 접두사 길이만 남긴 128비트 값으로. IPv4 는 앞 32비트에 둔다.
+fn masked(addr: IpAddr, prefix: u8) -> u128 {
+    let (bits, width) = match addr {
+        IpAddr::V4(v4) => ((u32::from(v4) as u128) << 96, 32),
+        IpAddr::V6(v6) => (u128::from(v6), 128),
+    };
+    let prefix = prefix.min(width) as u32;
+    if prefix == 0 {
+        0
+    } else {
+        bits & (u128::MAX << (128 - prefix))
+    }
+}
+
+// "주소" 또는 "주소/접두사 길이". 접두사가 없으면 주소 하나.
+pub(super) fn parse_network(network: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match network.split_once('/') {
+        Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+        None => (network.parse::<IpAddr>().ok()?, None),
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max);
+    (prefix <= max).then_some((addr, prefix))
+}
+
+// Postgres 의 network() 처럼 호스트 부분을 지운다
+pub(super) fn normalize(addr: IpAddr, prefix: u8) -> String {
+    let bits = masked(addr, prefix);
+    let network = match addr {
+        IpAddr::V4(_) => IpAddr::from(((bits >> 96) as u32).to_be_bytes()),
+        IpAddr::V6(_) => IpAddr::from(bits.to_be_bytes()),
+    };
+    format!("{}/{}", network, prefix)
+}
+
+pub(super) fn contains(network: &str, ip: IpAddr) -> bool {
+    parse_network(network).is_some_and(|(addr, prefix)| {
+        addr.is_ipv4() == ip.is_ipv4() && masked(addr, prefix) == masked(ip, prefix)
+    })
+}
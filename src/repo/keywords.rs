@@ -0,0 +1,36 @@
+// --- 키워드 알림 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Keyword {
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub user_id: i32,
+    // 정규화한 단어를 공백 하나로 이은 구절
+    pub keyword: String,
+    // None 이면 모든 방
+    pub room: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait KeywordRepo: Send + Sync {
+    // 모든 사용자의 키워드 (색인을 만들 때)
+    async fn all(&self) -> RepoResult<Vec<Keyword>>;
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Keyword>>;
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64>;
+
+    // 같은 방에 같은 구절이 이미 있으면 Conflict("keyword")
+    async fn create(&self, user_id: i32, keyword: &str, room: Option<&str>) -> RepoResult<Keyword>;
+
+    // 사용자 본인의 키워드를 지웠으면 true
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+}
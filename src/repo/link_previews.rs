@@ -0,0 +1,28 @@
+// --- 링크 미리보기 캐시 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+// 가져오지 못한 URL 도 모두 None 인 미리보기로 캐시한다
+#[derive(Default, FromRow)]
+pub struct LinkPreview {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub site_name: Option<String>,
+}
+
+#[async_trait]
+pub trait LinkPreviewRepo: Send + Sync {
+    // fetched_after 뒤에 가져온 캐시
+    async fn cached(&self, url: &str, fetched_after: DateTime<Utc>) -> RepoResult<Option<LinkPreview>>;
+
+    // 지금 가져온 것으로 캐시를 바꾼다
+    async fn save(&self, url: &str, preview: &LinkPreview) -> RepoResult<()>;
+
+    // fetched_before 전에 가져온 캐시를 지운다
+    async fn purge(&self, fetched_before: DateTime<Utc>) -> RepoResult<u64>;
+}
@@ -0,0 +1,24 @@
+// --- 점검 모드 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct Maintenance {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub updated_by: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 상태는 행 하나뿐이다
+#[async_trait]
+pub trait MaintenanceRepo: Send + Sync {
+    async fn get(&self) -> RepoResult<Maintenance>;
+
+    async fn set(&self, enabled: bool, message: Option<&str>, updated_by: i32) -> RepoResult<Maintenance>;
+}
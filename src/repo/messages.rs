@@ -0,0 +1,158 @@
+// --- 메시지 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::RepoResult;
+use crate::{markdown::MessageFormat, messages::Forwarded};
+
+// REST 로 돌려주는 저장된 메시지
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredMessage {
+    pub id: i64,
+    pub room: Option<String>,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub text: Option<String>,
+    pub format: String,
+    pub html: Option<String>,
+    pub webhook_id: Option<i64>,
+    pub forwarded: Option<Forwarded>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// SQL 구현이 읽는 메시지 행. forwarded 는 JSON 으로 저장한다.
+#[derive(FromRow)]
+pub(super) struct MessageRow {
+    id: i64,
+    room: Option<String>,
+    user_id: Option<i32>,
+    username: Option<String>,
+    text: Option<String>,
+    format: String,
+    html: Option<String>,
+    webhook_id: Option<i64>,
+    forwarded: Option<SqlJson<Forwarded>>,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<MessageRow> for StoredMessage {
+    fn from(row: MessageRow) -> Self {
+        StoredMessage {
+            id: row.id,
+            room: row.room,
+            user_id: row.user_id,
+            username: row.username,
+            text: row.text,
+            format: row.format,
+            html: row.html,
+            webhook_id: row.webhook_id,
+            forwarded: row.forwarded.map(|f| f.0),
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+// 방 기록 내보내기의 한 줄. 숨겨진 메시지도 hidden 표시와 함께 넣는다.
+#[derive(Debug, Serialize)]
+pub struct ExportedMessage {
+    pub id: i64,
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub text: Option<String>,
+    pub format: String,
+    pub hidden: bool,
+    pub webhook_id: Option<i64>,
+    pub forwarded: Option<Forwarded>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+pub(super) struct ExportRow {
+    id: i64,
+    user_id: Option<i32>,
+    username: Option<String>,
+    text: Option<String>,
+    format: String,
+    hidden: bool,
+    webhook_id: Option<i64>,
+    forwarded: Option<SqlJson<Forwarded>>,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<ExportRow> for ExportedMessage {
+    fn from(row: ExportRow) -> Self {
+        ExportedMessage {
+            id: row.id,
+            user_id: row.user_id,
+            username: row.username,
+            text: row.text,
+            format: row.format,
+            hidden: row.hidden,
+            webhook_id: row.webhook_id,
+            forwarded: row.forwarded.map(|f| f.0),
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+// 저장할 메시지. id 는 next_ids 로 미리 받는다.
+pub struct NewMessage {
+    pub id: i64,
+    pub user_id: i32,
+    pub username: String,
+    pub room: String,
+    pub text: String,
+    pub format: MessageFormat,
+    pub html: Option<String>,
+    pub hidden: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub forwarded: Option<Forwarded>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 조회는 viewer 가 볼 수 있는 메시지만 돌려준다. 그림자 차단으로 숨겨진 메시지는 쓴 사람에게만 보이고, 수명이
+// 지난 메시지는 정리 태스크가 지우기 전이라도 보이지 않는다.
+#[async_trait]
+pub trait MessageRepo: Send + Sync {
+    // 새 메시지 id count 개
+    async fn next_ids(&self, count: i64) -> RepoResult<Vec<i64>>;
+
+    // 다시 시도할 때 앞선 시도가 실제로는 저장됐을 수 있으므로 이미 있는 id 는 건너뛴다
+    async fn insert_batch(&self, messages: &[NewMessage]) -> RepoResult<()>;
+
+    async fn find_visible(&self, viewer: i32, id: i64) -> RepoResult<Option<StoredMessage>>;
+
+    // 볼 수 없거나 없는 id 는 빠진다. 순서는 정하지 않는다.
+    async fn find_visible_many(&self, viewer: i32, ids: &[i64]) -> RepoResult<Vec<StoredMessage>>;
+
+    // room 에서 before 보다 앞선 메시지 limit 개. before 가 없으면 가장 최근 것. 오래된 것부터.
+    async fn page_before(&self, viewer: i32, room: &str, before: Option<i64>, limit: i64)
+        -> RepoResult<Vec<StoredMessage>>;
+
+    // room 에서 after 다음의 메시지 limit 개. 오래된 것부터.
+    async fn page_after(&self, viewer: i32, room: &str, after: i64, limit: i64) -> RepoResult<Vec<StoredMessage>>;
+
+    // room 에서 at 이후의 첫 메시지
+    async fn first_since(&self, viewer: i32, room: &str, at: DateTime<Utc>) -> RepoResult<Option<StoredMessage>>;
+
+    // room 에서 after 다음의 메시지 limit 개, id 순. 수명이 지난 메시지는 뺀다.
+    async fn export_page(&self, room: &str, after: i64, limit: i64) -> RepoResult<Vec<ExportedMessage>>;
+
+    // 메시지를 지우고 (방, 저장소에서 지울 첨부 파일 키) 를 돌려준다. 첨부 파일 기록은 함께 지워진다.
+    async fn delete(&self, id: i64) -> RepoResult<Option<(String, Vec<String>)>>;
+
+    // 수명이 지난 메시지를 limit 개까지 그 알림과 함께 지우고 (id, 방) 을 돌려준다
+    async fn expire_due(&self, limit: i64) -> RepoResult<Vec<(i64, String)>>;
+
+    // 가장 이른 만료 시각
+    async fn next_expiry(&self) -> RepoResult<Option<DateTime<Utc>>>;
+}
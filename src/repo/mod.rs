@@ -0,0 +1,327 @@
+// --- 저장소 (리포지토리) ---
+//
+// DB 를 읽고 쓰는 곳을 기능마다 트레이트 하나(`UserRepo`, `MessageRepo`, `AuditRepo` 등)로 모았다. 트레이트와
+// 주고받는 행 타입은 이 디렉터리의 기능별 파일에, 구현은 postgres/, sqlite/, mysql/ 의 같은 이름 파일에 둔다.
+// 핸들러는 SQL 대신 `state.repos` 의 메서드를 부른다.
+// 구현은 DATABASE_URL 의 스킴으로 고른다: postgres:// 는 Postgres, sqlite: 는 SQLite, memory: 는 프로세스
+// 메모리에만 있는 SQLite DB, mysql:// (mariadb://) 는 MySQL/MariaDB 다 (mysql 기능으로 빌드했을 때).
+//
+// 핸들러 테스트(tests.rs)는 메모리 저장소의 필드 하나를 가짜 구현으로 바꿔 쓴다.
+
+mod accounts;
+mod anonymization;
+mod attachments;
+mod audit;
+mod bots;
+mod data_exports;
+mod friends;
+mod health;
+mod imports;
+mod invites;
+mod ip_bans;
+mod keywords;
+mod link_previews;
+mod maintenance;
+mod messages;
+#[cfg(feature = "mysql")]
+mod mysql;
+mod notifications;
+mod oauth;
+mod passkeys;
+mod polls;
+mod postgres;
+mod profiles;
+mod push;
+mod reminders;
+mod reports;
+mod retention;
+mod revocation;
+mod rooms;
+mod scheduled_messages;
+mod sessions;
+mod spam;
+mod sqlite;
+mod stars;
+mod throttle;
+mod tokens;
+mod two_factor;
+mod users;
+mod webhooks;
+mod word_filters;
+
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::sync::Arc;
+
+use crate::{commands::CommandError, config, error::ApiError, validation::ValidationErrors};
+
+pub use self::{
+    accounts::*,
+    anonymization::*,
+    attachments::*,
+    audit::*,
+    bots::*,
+    data_exports::*,
+    friends::*,
+    health::*,
+    imports::*,
+    invites::*,
+    ip_bans::*,
+    keywords::*,
+    link_previews::*,
+    maintenance::*,
+    messages::*,
+    notifications::*,
+    oauth::*,
+    passkeys::*,
+    polls::*,
+    postgres::PgRepo,
+    profiles::*,
+    push::*,
+    reminders::*,
+    reports::*,
+    retention::*,
+    revocation::*,
+    rooms::*,
+    scheduled_messages::*,
+    sessions::*,
+    spam::*,
+    sqlite::SqliteRepo,
+    stars::*,
+    throttle::*,
+    tokens::*,
+    two_factor::*,
+    users::*,
+    webhooks::*,
+    word_filters::*,
+};
+#[cfg(feature = "mysql")]
+pub use self::mysql::MySqlRepo;
+
+#[derive(Debug)]
+pub enum RepoError {
+    // 유니크 제약에 걸렸다. 겹친 필드 이름 ("username", "email").
+    Conflict(&'static str),
+    // 가입할 때 받은 초대 코드가 틀렸거나 만료됐다
+    InvalidInvite,
+    // 저장소 자체의 오류. 내용은 로그에만 남긴다.
+    Backend(String),
+}
+
+pub type RepoResult<T> = Result<T, RepoError>;
+
+impl std::fmt::Display for RepoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoError::Conflict(field) => write!(f, "{} is already in use", field),
+            RepoError::InvalidInvite => write!(f, "invite code is invalid or expired"),
+            RepoError::Backend(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RepoError {}
+
+impl From<sqlx::Error> for RepoError {
+    fn from(e: sqlx::Error) -> Self {
+        RepoError::Backend(e.to_string())
+    }
+}
+
+// 저장소 오류는 스키마 정보가 새지 않도록 로그에만 남긴다
+impl From<RepoError> for ApiError {
+    fn from(e: RepoError) -> Self {
+        match e {
+            RepoError::Conflict(field) => {
+                ValidationErrors::single(field, "taken", format!("{} is already in use", field)).into()
+            }
+            RepoError::InvalidInvite => {
+                ValidationErrors::single("invite_code", "invalid", "Invite code is invalid or expired").into()
+            }
+            RepoError::Backend(e) => {
+                tracing::error!("Database error: {}", e);
+                ApiError::internal()
+            }
+        }
+    }
+}
+
+impl From<RepoError> for CommandError {
+    fn from(e: RepoError) -> Self {
+        tracing::error!("Database error: {}", e);
+        CommandError::new("internal_error", "Internal server error")
+    }
+}
+
+// AppState 에 들고 다니는 저장소 묶음
+#[derive(Clone)]
+pub struct Repos {
+    pub users: Arc<dyn UserRepo>,
+    pub messages: Arc<dyn MessageRepo>,
+    pub rooms: Arc<dyn RoomRepo>,
+    pub audit: Arc<dyn AuditRepo>,
+    pub spam: Arc<dyn SpamRepo>,
+    pub maintenance: Arc<dyn MaintenanceRepo>,
+    pub ip_bans: Arc<dyn IpBanRepo>,
+    pub profiles: Arc<dyn ProfileRepo>,
+    pub friends: Arc<dyn FriendRepo>,
+    pub sessions: Arc<dyn SessionRepo>,
+    pub revocation: Arc<dyn RevocationRepo>,
+    pub throttle: Arc<dyn ThrottleRepo>,
+    pub tokens: Arc<dyn TokenRepo>,
+    pub invites: Arc<dyn InviteRepo>,
+    pub accounts: Arc<dyn AccountRepo>,
+    pub two_factor: Arc<dyn TwoFactorRepo>,
+    pub passkeys: Arc<dyn PasskeyRepo>,
+    pub oauth: Arc<dyn OAuthRepo>,
+    pub notifications: Arc<dyn NotificationRepo>,
+    pub push: Arc<dyn PushRepo>,
+    pub keywords: Arc<dyn KeywordRepo>,
+    pub word_filters: Arc<dyn WordFilterRepo>,
+    pub bots: Arc<dyn BotRepo>,
+    pub webhooks: Arc<dyn WebhookRepo>,
+    pub polls: Arc<dyn PollRepo>,
+    pub reminders: Arc<dyn ReminderRepo>,
+    pub scheduled_messages: Arc<dyn ScheduledMessageRepo>,
+    pub stars: Arc<dyn StarRepo>,
+    pub reports: Arc<dyn ReportRepo>,
+    pub retention: Arc<dyn RetentionRepo>,
+    pub attachments: Arc<dyn AttachmentRepo>,
+    pub link_previews: Arc<dyn LinkPreviewRepo>,
+    pub data_exports: Arc<dyn DataExportRepo>,
+    pub imports: Arc<dyn ImportRepo>,
+    pub anonymization: Arc<dyn AnonymizationRepo>,
+    pub health: Arc<dyn HealthRepo>,
+}
+
+impl Repos {
+    pub fn postgres(db: PgPool) -> Self {
+        Self::share(Arc::new(PgRepo::new(db)))
+    }
+
+    // 파일이 없으면 만들고 마이그레이션을 적용한다
+    pub async fn sqlite(url: &str, max_connections: u32) -> Result<Self, String> {
+        Ok(Self::share(Arc::new(SqliteRepo::connect(url, max_connections).await?)))
+    }
+
+    // 마이그레이션을 적용하고 연다
+    #[cfg(feature = "mysql")]
+    pub async fn mysql(url: &str, max_connections: u32) -> Result<Self, String> {
+        Ok(Self::share(Arc::new(MySqlRepo::connect(url, max_connections).await?)))
+    }
+
+    // 비어 있는 메모리 저장소
+    pub async fn memory() -> Result<Self, String> {
+        Ok(Self::share(Arc::new(SqliteRepo::memory().await?)))
+    }
+
+    // url 의 스킴에 맞는 저장소를 연다. Postgres 의 마이그레이션은 schema 모듈이 따로 적용한다.
+    pub async fn open(url: &str, max_connections: u32) -> Result<Self, String> {
+        match Backend::from_url(url) {
+            Some(Backend::Postgres) => {
+                let db = PgPoolOptions::new()
+                    .max_connections(max_connections)
+                    .connect(url)
+                    .await
+                    .map_err(|e| format!("Failed to connect to the database: {}", e))?;
+                Ok(Self::postgres(db))
+            }
+            Some(Backend::Sqlite) => Self::sqlite(url, max_connections).await,
+            Some(Backend::Memory) => Self::memory().await,
+            #[cfg(feature = "mysql")]
+            Some(Backend::MySql) => Self::mysql(url, max_connections).await,
+            #[cfg(not(feature = "mysql"))]
+            Some(Backend::MySql) => Err("This build has no MySQL support; rebuild with `--features mysql`".to_string()),
+            None => Err("database.url must be a postgres://, mysql://, sqlite: or memory: URL".to_string()),
+        }
+    }
+
+    // 모든 트레이트를 구현한 저장소 하나를 나눠 쓴다
+    fn share<R: Repository>(repo: Arc<R>) -> Self {
+        Repos {
+            users: repo.clone(),
+            messages: repo.clone(),
+            rooms: repo.clone(),
+            audit: repo.clone(),
+            spam: repo.clone(),
+            maintenance: repo.clone(),
+            ip_bans: repo.clone(),
+            profiles: repo.clone(),
+            friends: repo.clone(),
+            sessions: repo.clone(),
+            revocation: repo.clone(),
+            throttle: repo.clone(),
+            tokens: repo.clone(),
+            invites: repo.clone(),
+            accounts: repo.clone(),
+            two_factor: repo.clone(),
+            passkeys: repo.clone(),
+            oauth: repo.clone(),
+            notifications: repo.clone(),
+            push: repo.clone(),
+            keywords: repo.clone(),
+            word_filters: repo.clone(),
+            bots: repo.clone(),
+            webhooks: repo.clone(),
+            polls: repo.clone(),
+            reminders: repo.clone(),
+            scheduled_messages: repo.clone(),
+            stars: repo.clone(),
+            reports: repo.clone(),
+            retention: repo.clone(),
+            attachments: repo.clone(),
+            link_previews: repo.clone(),
+            data_exports: repo.clone(),
+            imports: repo.clone(),
+            anonymization: repo.clone(),
+            health: repo.clone(),
+        }
+    }
+}
+
+// 저장소 트레이트 전부
+trait Repository:
+    UserRepo + MessageRepo + RoomRepo + AuditRepo + SpamRepo + MaintenanceRepo + IpBanRepo + ProfileRepo +
+    FriendRepo + SessionRepo + RevocationRepo + ThrottleRepo + TokenRepo + InviteRepo + AccountRepo + TwoFactorRepo +
+    PasskeyRepo + OAuthRepo + NotificationRepo + PushRepo + KeywordRepo + WordFilterRepo + BotRepo + WebhookRepo +
+    PollRepo + ReminderRepo + ScheduledMessageRepo + StarRepo + ReportRepo + RetentionRepo + AttachmentRepo +
+    LinkPreviewRepo + DataExportRepo + ImportRepo + AnonymizationRepo + HealthRepo + 'static
+{}
+
+impl<R> Repository for R
+where
+    R: UserRepo + MessageRepo + RoomRepo + AuditRepo + SpamRepo + MaintenanceRepo + IpBanRepo + ProfileRepo +
+       FriendRepo + SessionRepo + RevocationRepo + ThrottleRepo + TokenRepo + InviteRepo + AccountRepo +
+       TwoFactorRepo + PasskeyRepo + OAuthRepo + NotificationRepo + PushRepo + KeywordRepo + WordFilterRepo +
+       BotRepo + WebhookRepo + PollRepo + ReminderRepo + ScheduledMessageRepo + StarRepo + ReportRepo +
+       RetentionRepo + AttachmentRepo + LinkPreviewRepo + DataExportRepo + ImportRepo + AnonymizationRepo +
+       HealthRepo + 'static,
+{}
+
+// DATABASE_URL 의 스킴으로 고르는 저장소 구현
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    Sqlite,
+    Memory,
+    // mysql 기능 없이 빌드했어도 알아보고, 열 때 알려 준다
+    MySql,
+}
+
+impl Backend {
+    // 설정한 database.url 의 구현
+    pub fn configured() -> Self {
+        let url = config::get().database.url.as_deref().unwrap_or_default();
+        Self::from_url(url).expect("database.url is validated")
+    }
+
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" => Some(Backend::Postgres),
+            "sqlite" => Some(Backend::Sqlite),
+            "memory" => Some(Backend::Memory),
+            "mysql" | "mariadb" => Some(Backend::MySql),
+            _ => None,
+        }
+    }
+}
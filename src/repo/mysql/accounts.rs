@@ -0,0 +1,170 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{duplicate_key, invites, MySqlRepo};
+use crate::{
+    auth::hash_token,
+    repo::{AccountRepo, GuestUpgrade, RepoError, RepoResult},
+};
+
+// 이름과 메일 주소 중 어느 쪽이 겹쳤는지
+fn conflict(e: &dyn sqlx::error::DatabaseError) -> RepoError {
+    if duplicate_key(e) == "users_email_key" {
+        RepoError::Conflict("email")
+    } else {
+        RepoError::Conflict("username")
+    }
+}
+
+#[async_trait]
+impl AccountRepo for MySqlRepo {
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>("SELECT id, email FROM users WHERE email_key = LOWER(?)")
+            .bind(email)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(user)
+    }
+
+    async fn set_email(&self, user_id: i32, email: &str) -> RepoResult<()> {
+        let updated = sqlx::query("UPDATE users SET email = ?, email_verified_at = NULL WHERE id = ?")
+            .bind(email)
+            .bind(user_id)
+            .execute(&self.db)
+            .await;
+        match updated {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("email")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn email_status(&self, user_id: i32) -> RepoResult<Option<(Option<String>, bool)>> {
+        let row = sqlx::query_as::<_, (Option<String>, bool)>(
+            "SELECT email, email_verified_at IS NOT NULL FROM users WHERE id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn is_verified(&self, user_id: i32) -> RepoResult<bool> {
+        let verified =
+            sqlx::query_scalar::<_, bool>("SELECT email_verified_at IS NOT NULL OR is_bot FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(verified.unwrap_or(false))
+    }
+
+    async fn create_guest(&self, username: &str) -> RepoResult<Option<i32>> {
+        let inserted = sqlx::query("INSERT IGNORE INTO users (username, is_guest) VALUES (?, TRUE)")
+            .bind(username)
+            .execute(&self.db)
+            .await?;
+        Ok((inserted.rows_affected() == 1).then(|| inserted.last_insert_id() as i32))
+    }
+
+    async fn upgrade_guest(&self, upgrade: &GuestUpgrade<'_>) -> RepoResult<bool> {
+        let mut tx = self.db.begin().await?;
+        let invite_id = match upgrade.invite_code {
+            Some(code) => {
+                Some(invites::redeem(&mut tx, &hash_token(code.trim())).await?.ok_or(RepoError::InvalidInvite)?)
+            }
+            None => None,
+        };
+        let upgraded = sqlx::query(
+            "UPDATE users SET username = ?, password_hash = ?, email = ?, invite_id = ?, is_guest = FALSE
+             WHERE id = ? AND is_guest",
+        )
+        .bind(upgrade.username)
+        .bind(upgrade.password_hash)
+        .bind(upgrade.email)
+        .bind(invite_id)
+        .bind(upgrade.user_id)
+        .execute(&mut *tx)
+        .await;
+        match upgraded {
+            Ok(r) => {
+                tx.commit().await?;
+                Ok(r.rows_affected() == 1)
+            }
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(conflict(&*e)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn purge_guests(&self, created_before: DateTime<Utc>) -> RepoResult<u64> {
+        let purged = sqlx::query("DELETE FROM users WHERE is_guest AND created_at < ?")
+            .bind(created_before)
+            .execute(&self.db)
+            .await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn create_admin(&self, username: &str, password_hash: &str, email: Option<&str>) -> RepoResult<i32> {
+        let created = sqlx::query(
+            "INSERT INTO users (username, password_hash, email, email_verified_at, is_admin)
+             VALUES (?, ?, ?, CASE WHEN ? IS NULL THEN NULL ELSE UTC_TIMESTAMP(6) END, TRUE)",
+        )
+        .bind(username)
+        .bind(password_hash)
+        .bind(email)
+        .bind(email)
+        .execute(&self.db)
+        .await;
+        match created {
+            Ok(result) => Ok(result.last_insert_id() as i32),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(conflict(&*e)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn grant_admin(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?").bind(user_id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn bot_ids(&self, owner_id: i32) -> RepoResult<Vec<i32>> {
+        let ids = sqlx::query_scalar::<_, i32>("SELECT id FROM users WHERE bot_owner_id = ?")
+            .bind(owner_id)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(ids)
+    }
+
+    async fn delete_account(&self, user_id: i32, anonymize_as: Option<&str>) -> RepoResult<(u64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        let (messages, attachment_keys) = match anonymize_as {
+            Some(name) => {
+                let updated = sqlx::query("UPDATE messages SET user_id = NULL, username = ? WHERE user_id = ?")
+                    .bind(name)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                (updated.rows_affected(), Vec::new())
+            }
+            None => {
+                // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 저장소에서 지울 키를 먼저 모은다
+                let keys = sqlx::query_scalar::<_, String>(
+                    "SELECT storage_key FROM attachments WHERE user_id = ?
+                     UNION ALL SELECT thumbnail_key FROM attachments WHERE user_id = ? AND thumbnail_key IS NOT NULL",
+                )
+                .bind(user_id)
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+                let deleted =
+                    sqlx::query("DELETE FROM messages WHERE user_id = ?").bind(user_id).execute(&mut *tx).await?;
+                (deleted.rows_affected(), keys)
+            }
+        };
+        // 외래 키의 CASCADE 로 지워진 행에는 users_record_deleted 트리거가 돌지 않으므로 봇은 직접 먼저 지운다.
+        // 세션, 토큰, 패스키 등은 외래 키로 함께 지워진다.
+        sqlx::query("DELETE FROM users WHERE bot_owner_id = ?").bind(user_id).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM users WHERE id = ?").bind(user_id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok((messages, attachment_keys))
+    }
+}
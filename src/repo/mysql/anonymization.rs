@@ -0,0 +1,310 @@
+use axum::async_trait;
+use sqlx::{types::Json as SqlJson, MySqlConnection};
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{AnonymizationJob, AnonymizationRepo, RepoResult, Touched};
+
+const JOB_COLUMNS: &str = "id, status, touched, error, created_by, created_at, completed_at";
+
+// 메시지를 지운 것처럼 비운다. 첨부 파일 기록을 지우고 저장소에서 지울 키를 돌려준다.
+async fn scrub_messages(conn: &mut MySqlConnection, ids: &[i64], touched: &mut Touched) -> sqlx::Result<Vec<String>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let list = placeholders(ids.len());
+    let sql = format!("UPDATE messages SET content = NULL, html = NULL WHERE id IN ({})", list);
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    query.execute(&mut *conn).await?;
+
+    let sql = format!(
+        "SELECT storage_key FROM attachments WHERE message_id IN ({0})
+         UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id IN ({0}) AND thumbnail_key IS NOT NULL",
+        list
+    );
+    let mut query = sqlx::query_scalar::<_, String>(&sql);
+    for id in ids.iter().chain(ids) {
+        query = query.bind(id);
+    }
+    let keys = query.fetch_all(&mut *conn).await?;
+
+    let sql = format!("DELETE FROM attachments WHERE message_id IN ({})", list);
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    touched.attachments += query.execute(&mut *conn).await?.rows_affected();
+
+    let sql = format!("UPDATE message_reports SET content = NULL WHERE message_id IN ({}) AND content IS NOT NULL", list);
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    touched.reports += query.execute(&mut *conn).await?.rows_affected();
+    Ok(keys)
+}
+
+// 내용이 patterns 중 하나에 맞는지. utf8mb4_bin 은 대소문자를 구분하므로 양쪽을 소문자로 바꿔 비교한다.
+fn like_any(column: &str, n: usize) -> String {
+    vec![format!("LOWER({}) LIKE LOWER(?)", column); n].join(" OR ")
+}
+
+#[async_trait]
+impl AnonymizationRepo for MySqlRepo {
+    // 돌고 있는 작업이 있으면 running_key 의 유니크 키에 걸린다
+    async fn create(&self, created_by: i32) -> RepoResult<Option<AnonymizationJob>> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO anonymization_jobs (status, touched, created_by) VALUES ('running', '{}', ?)",
+        )
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await;
+        let id = match inserted {
+            Ok(result) => result.last_insert_id() as i64,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let job = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "SELECT {} FROM anonymization_jobs WHERE id = ?",
+            JOB_COLUMNS
+        ))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(job))
+    }
+
+    async fn list(&self, limit: i64) -> RepoResult<Vec<AnonymizationJob>> {
+        let jobs = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "SELECT {} FROM anonymization_jobs ORDER BY id DESC LIMIT ?",
+            JOB_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(jobs)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<AnonymizationJob>> {
+        let job =
+            sqlx::query_as::<_, AnonymizationJob>(&format!("SELECT {} FROM anonymization_jobs WHERE id = ?", JOB_COLUMNS))
+                .bind(id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(job)
+    }
+
+    async fn running(&self) -> RepoResult<Option<(i64, Touched)>> {
+        let running = sqlx::query_as::<_, (i64, SqlJson<Touched>)>(
+            "SELECT id, touched FROM anonymization_jobs WHERE status = 'running'",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(running.map(|(id, touched)| (id, touched.0)))
+    }
+
+    async fn save_progress(&self, id: i64, touched: &Touched) -> RepoResult<()> {
+        sqlx::query("UPDATE anonymization_jobs SET touched = ? WHERE id = ?")
+            .bind(SqlJson(touched))
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn finish(&self, id: i64, status: &str, touched: &Touched, error: Option<&str>) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE anonymization_jobs SET status = ?, touched = ?, error = ?, completed_at = UTC_TIMESTAMP(6)
+             WHERE id = ?",
+        )
+        .bind(status)
+        .bind(SqlJson(touched))
+        .bind(error)
+        .bind(id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_accounts(&self) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM deleted_accounts").fetch_one(&self.db).await?;
+        Ok(count)
+    }
+
+    async fn deleted_accounts(&self, limit: i64) -> RepoResult<Vec<(i32, String)>> {
+        let accounts = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, username FROM deleted_accounts ORDER BY user_id LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(accounts)
+    }
+
+    // RETURNING 이 없으므로 고칠 메시지를 먼저 잠가 읽고 그 id 로 고친다
+    async fn scrub_accounts(
+        &self,
+        ids: &[i32],
+        names: &[String],
+        deleted_name: &str,
+        touched: &mut Touched,
+    ) -> RepoResult<Vec<String>> {
+        let id_list = placeholders(ids.len());
+        let mut tx = self.db.begin().await?;
+
+        let sql = format!("SELECT id FROM messages WHERE user_id IN ({}) FOR UPDATE", id_list);
+        let mut query = sqlx::query_scalar::<_, i64>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let messages = query.fetch_all(&mut *tx).await?;
+        if !messages.is_empty() {
+            let sql = format!(
+                "UPDATE messages SET user_id = NULL, username = ? WHERE id IN ({})",
+                placeholders(messages.len())
+            );
+            let mut query = sqlx::query(&sql).bind(deleted_name);
+            for id in &messages {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        touched.messages += messages.len() as u64;
+        let mut keys = scrub_messages(&mut tx, &messages, touched).await?;
+
+        // 전달한 메시지는 원래 작성자의 글을 그대로 옮긴 것이므로 내용도 지운다
+        let sql = format!(
+            "SELECT id FROM messages WHERE JSON_EXTRACT(forwarded, '$.user_id') IN ({}) FOR UPDATE",
+            id_list
+        );
+        let mut query = sqlx::query_scalar::<_, i64>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let forwards = query.fetch_all(&mut *tx).await?;
+        if !forwards.is_empty() {
+            let sql = format!(
+                "UPDATE messages SET forwarded = JSON_SET(forwarded, '$.user_id', NULL, '$.username', ?)
+                 WHERE id IN ({})",
+                placeholders(forwards.len())
+            );
+            let mut query = sqlx::query(&sql).bind(deleted_name);
+            for id in &forwards {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        touched.forwards += forwards.len() as u64;
+        keys.extend(scrub_messages(&mut tx, &forwards, touched).await?);
+
+        // 투표의 user_id 는 계정을 지울 때 이미 NULL 이 되었다
+        let sql = format!(
+            "UPDATE polls SET username = ? WHERE user_id IS NULL AND username IN ({})",
+            placeholders(names.len())
+        );
+        let mut query = sqlx::query(&sql).bind(deleted_name);
+        for name in names {
+            query = query.bind(name);
+        }
+        touched.polls += query.execute(&mut *tx).await?.rows_affected();
+
+        let sql = format!(
+            "UPDATE archived_messages
+             SET message = JSON_SET(message, '$.user_id', NULL, '$.username', ?, '$.content', NULL, '$.html', NULL)
+             WHERE JSON_EXTRACT(message, '$.user_id') IN ({})",
+            id_list
+        );
+        let mut query = sqlx::query(&sql).bind(deleted_name);
+        for id in ids {
+            query = query.bind(id);
+        }
+        touched.archived += query.execute(&mut *tx).await?.rows_affected();
+        tx.commit().await?;
+        Ok(keys)
+    }
+
+    async fn forget_accounts(&self, ids: &[i32]) -> RepoResult<()> {
+        let sql = format!("DELETE FROM deleted_accounts WHERE user_id IN ({})", placeholders(ids.len()));
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        query.execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn scrub_anonymized(
+        &self,
+        deleted_name: &str,
+        limit: i64,
+        touched: &mut Touched,
+    ) -> RepoResult<(i64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        let messages = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM messages
+             WHERE user_id IS NULL AND username = ? AND webhook_id IS NULL AND content IS NOT NULL
+             ORDER BY id LIMIT ? FOR UPDATE",
+        )
+        .bind(deleted_name)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        touched.messages += messages.len() as u64;
+        let keys = scrub_messages(&mut tx, &messages, touched).await?;
+        tx.commit().await?;
+        Ok((messages.len() as i64, keys))
+    }
+
+    async fn mentioning_messages(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String, String)>> {
+        let sql = format!(
+            "SELECT id, content, format FROM messages WHERE ({}) AND id > ? ORDER BY id LIMIT ?",
+            like_any("content", patterns.len())
+        );
+        let mut query = sqlx::query_as::<_, (i64, String, String)>(&sql);
+        for pattern in patterns {
+            query = query.bind(pattern);
+        }
+        Ok(query.bind(after).bind(limit).fetch_all(&self.db).await?)
+    }
+
+    async fn rewrite_message(&self, id: i64, content: &str, html: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE messages SET content = ?, html = ? WHERE id = ?")
+            .bind(content)
+            .bind(html)
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn mentioning_notifications(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String)>> {
+        let sql = format!(
+            "SELECT id, text FROM notifications WHERE ({}) AND id > ? ORDER BY id LIMIT ?",
+            like_any("text", patterns.len())
+        );
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql);
+        for pattern in patterns {
+            query = query.bind(pattern);
+        }
+        Ok(query.bind(after).bind(limit).fetch_all(&self.db).await?)
+    }
+
+    async fn rewrite_notification(&self, id: i64, text: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE notifications SET text = ? WHERE id = ?").bind(text).bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+}
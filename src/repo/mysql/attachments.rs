@@ -0,0 +1,57 @@
+use axum::async_trait;
+
+use super::{messages, MySqlRepo};
+use crate::repo::{Attachment, AttachmentPost, AttachmentRepo, RepoResult};
+
+#[async_trait]
+impl AttachmentRepo for MySqlRepo {
+    async fn find(&self, id: &str) -> RepoResult<Option<Attachment>> {
+        let attachment = sqlx::query_as::<_, Attachment>(
+            "SELECT id, kind, filename, content_type, size, storage_key, width, height, thumbnail_key, thumbnail_width,
+                    thumbnail_height, duration_ms
+             FROM attachments WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(attachment)
+    }
+
+    async fn create(&self, post: AttachmentPost<'_>, attachment: &Attachment) -> RepoResult<i64> {
+        let mut tx = self.db.begin().await?;
+        let message_id = messages::next_id(&mut tx).await?;
+        sqlx::query("INSERT INTO messages (id, user_id, username, room, content, hidden) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(message_id)
+            .bind(post.user_id)
+            .bind(post.username)
+            .bind(post.room)
+            .bind(post.text)
+            .bind(post.hidden)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO attachments (id, message_id, user_id, room, kind, filename, content_type, size, storage_key,
+                                      width, height, thumbnail_key, thumbnail_width, thumbnail_height, duration_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&attachment.id)
+        .bind(message_id)
+        .bind(post.user_id)
+        .bind(post.room)
+        .bind(&attachment.kind)
+        .bind(&attachment.filename)
+        .bind(&attachment.content_type)
+        .bind(attachment.size)
+        .bind(&attachment.storage_key)
+        .bind(attachment.width)
+        .bind(attachment.height)
+        .bind(&attachment.thumbnail_key)
+        .bind(attachment.thumbnail_width)
+        .bind(attachment.thumbnail_height)
+        .bind(attachment.duration_ms)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(message_id)
+    }
+}
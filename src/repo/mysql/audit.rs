@@ -0,0 +1,48 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json as SqlJson;
+
+use super::MySqlRepo;
+use crate::repo::{AuditEntry, AuditRepo, NewAuditEntry, RepoResult};
+
+#[async_trait]
+impl AuditRepo for MySqlRepo {
+    async fn record(&self, entry: &NewAuditEntry<'_>) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO audit_log (actor_id, actor_name, action, target_type, target_id, reason, details)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(entry.actor_id)
+        .bind(entry.actor_name)
+        .bind(entry.action)
+        .bind(entry.target_type)
+        .bind(&entry.target_id)
+        .bind(entry.reason)
+        .bind(SqlJson(&entry.details))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        since: Option<DateTime<Utc>>,
+        action: Option<&str>,
+        limit: i64,
+    ) -> RepoResult<Vec<AuditEntry>> {
+        let entries = sqlx::query_as::<_, AuditEntry>(
+            "SELECT id, actor_id, actor_name, action, target_type, target_id, reason, details, created_at
+             FROM audit_log
+             WHERE (? IS NULL OR created_at > ?) AND (? IS NULL OR action = ?)
+             ORDER BY id DESC LIMIT ?",
+        )
+        .bind(since)
+        .bind(since)
+        .bind(action)
+        .bind(action)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(entries)
+    }
+}
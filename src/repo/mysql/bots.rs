@@ -0,0 +1,167 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::MySqlRepo;
+use crate::repo::{Bot, BotRepo, BotToken, BotTokenOwner, RepoError, RepoResult};
+
+const TOKEN_COLUMNS: &str = "id, name, scopes, created_at, last_used_at, expires_at, revoked_at";
+
+// scopes 는 JSON 배열로 저장한다
+#[derive(FromRow)]
+struct TokenRow {
+    id: i64,
+    name: String,
+    scopes: SqlJson<Vec<String>>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<TokenRow> for BotToken {
+    fn from(row: TokenRow) -> Self {
+        BotToken {
+            id: row.id,
+            name: row.name,
+            scopes: row.scopes.0,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+#[async_trait]
+impl BotRepo for MySqlRepo {
+    async fn authenticate(&self, token_hash: &str) -> RepoResult<Option<BotTokenOwner>> {
+        let token = sqlx::query_as::<_, (i64, i32, String, SqlJson<Vec<String>>, Option<DateTime<Utc>>)>(
+            "SELECT t.id, t.bot_id, u.username, t.scopes, t.expires_at
+             FROM bot_tokens t JOIN users u ON u.id = t.bot_id AND u.is_bot AND u.disabled_at IS NULL
+             WHERE t.token_hash = ? AND t.revoked_at IS NULL
+               AND (t.expires_at IS NULL OR t.expires_at > UTC_TIMESTAMP(6))",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        let Some((token_id, bot_id, username, scopes, expires_at)) = token else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE bot_tokens SET last_used_at = UTC_TIMESTAMP(6) WHERE id = ?")
+            .bind(token_id)
+            .execute(&self.db)
+            .await?;
+        Ok(Some(BotTokenOwner { token_id, bot_id, username, scopes: scopes.0, expires_at }))
+    }
+
+    async fn is_owner(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let owned = sqlx::query_scalar::<_, bool>("SELECT bot_owner_id = ? FROM users WHERE id = ? AND is_bot")
+            .bind(owner_id)
+            .bind(bot_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(owned == Some(true))
+    }
+
+    async fn list(&self, owner_id: i32) -> RepoResult<Vec<Bot>> {
+        let bots = sqlx::query_as::<_, Bot>(
+            "SELECT id, username, created_at FROM users WHERE bot_owner_id = ? AND is_bot ORDER BY id",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bots)
+    }
+
+    async fn count(&self, owner_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE bot_owner_id = ? AND is_bot")
+            .bind(owner_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, owner_id: i32, username: &str) -> RepoResult<Bot> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO users (username, is_bot, bot_owner_id) VALUES (?, TRUE, ?)")
+            .bind(username)
+            .bind(owner_id)
+            .execute(&mut *tx)
+            .await;
+        let id = match inserted {
+            Ok(result) => result.last_insert_id() as i32,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Err(RepoError::Conflict("username")),
+            Err(e) => return Err(e.into()),
+        };
+        let bot = sqlx::query_as::<_, Bot>("SELECT id, username, created_at FROM users WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(bot)
+    }
+
+    async fn delete(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM users WHERE id = ? AND bot_owner_id = ? AND is_bot")
+            .bind(bot_id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn tokens(&self, bot_id: i32) -> RepoResult<Vec<BotToken>> {
+        let sql = format!("SELECT {} FROM bot_tokens WHERE bot_id = ? ORDER BY id", TOKEN_COLUMNS);
+        let rows = sqlx::query_as::<_, TokenRow>(&sql).bind(bot_id).fetch_all(&self.db).await?;
+        Ok(rows.into_iter().map(BotToken::from).collect())
+    }
+
+    async fn active_token_count(&self, bot_id: i32) -> RepoResult<i64> {
+        let count =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM bot_tokens WHERE bot_id = ? AND revoked_at IS NULL")
+                .bind(bot_id)
+                .fetch_one(&self.db)
+                .await?;
+        Ok(count)
+    }
+
+    async fn create_token(
+        &self,
+        bot_id: i32,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<BotToken> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO bot_tokens (bot_id, name, token_hash, scopes, expires_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(bot_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(SqlJson(scopes))
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+        let sql = format!("SELECT {} FROM bot_tokens WHERE id = ?", TOKEN_COLUMNS);
+        let row = sqlx::query_as::<_, TokenRow>(&sql)
+            .bind(inserted.last_insert_id() as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(row.into())
+    }
+
+    async fn revoke_token(&self, token_id: i64, bot_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE bot_tokens SET revoked_at = UTC_TIMESTAMP(6) WHERE id = ? AND bot_id = ? AND revoked_at IS NULL",
+        )
+        .bind(token_id)
+        .bind(bot_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
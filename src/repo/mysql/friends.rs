@@ -0,0 +1,119 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{ContactRow, FriendRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl FriendRepo for MySqlRepo {
+    async fn friend_ids(&self, user_id: i32) -> RepoResult<Vec<i32>> {
+        let ids = sqlx::query_scalar::<_, i32>(
+            "SELECT CASE WHEN requester_id = ? THEN addressee_id ELSE requester_id END
+             FROM friendships WHERE ? IN (requester_id, addressee_id) AND status = 'accepted'",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn contacts(&self, user_id: i32) -> RepoResult<Vec<ContactRow>> {
+        let rows = sqlx::query_as::<_, ContactRow>(
+            "SELECT u.id AS user_id, u.username, p.display_name, u.status, u.status_message,
+                    f.status = 'accepted' AS accepted,
+                    f.requester_id = ? AS outgoing, COALESCE(f.accepted_at, f.created_at) AS since
+             FROM friendships f
+             JOIN users u ON u.id = CASE WHEN f.requester_id = ? THEN f.addressee_id ELSE f.requester_id END
+             LEFT JOIN profiles p ON p.user_id = u.id
+             WHERE ? IN (f.requester_id, f.addressee_id)
+             ORDER BY u.username_key",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    // 한 쌍에는 요청이 하나뿐이어야 한다. 거꾸로 된 쌍을 막는 인덱스가 없으므로 양쪽 방향을 잠그고 확인한다.
+    async fn request(&self, requester_id: i32, addressee_id: i32) -> RepoResult<()> {
+        let mut tx = self.db.begin().await?;
+        let existing = sqlx::query_scalar::<_, i32>(
+            "SELECT requester_id FROM friendships
+             WHERE (requester_id = ? AND addressee_id = ?) OR (requester_id = ? AND addressee_id = ?)
+             FOR UPDATE",
+        )
+        .bind(requester_id)
+        .bind(addressee_id)
+        .bind(addressee_id)
+        .bind(requester_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if existing.is_some() {
+            return Err(RepoError::Conflict("friendship"));
+        }
+        let created = sqlx::query("INSERT INTO friendships (requester_id, addressee_id) VALUES (?, ?)")
+            .bind(requester_id)
+            .bind(addressee_id)
+            .execute(&mut *tx)
+            .await;
+        match created {
+            Ok(_) => {
+                tx.commit().await?;
+                Ok(())
+            }
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("friendship")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn accept(&self, requester_id: i32, addressee_id: i32) -> RepoResult<Option<String>> {
+        let mut tx = self.db.begin().await?;
+        let accepted = sqlx::query(
+            "UPDATE friendships SET status = 'accepted', accepted_at = UTC_TIMESTAMP(6)
+             WHERE requester_id = ? AND addressee_id = ? AND status = 'pending'",
+        )
+        .bind(requester_id)
+        .bind(addressee_id)
+        .execute(&mut *tx)
+        .await?;
+        if accepted.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let username = sqlx::query_scalar::<_, String>("SELECT username FROM users WHERE id = ?")
+            .bind(requester_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(username)
+    }
+
+    async fn is_accepted(&self, a: i32, b: i32) -> RepoResult<Option<bool>> {
+        let accepted = sqlx::query_scalar::<_, bool>(
+            "SELECT status = 'accepted' FROM friendships
+             WHERE (requester_id = ? AND addressee_id = ?) OR (requester_id = ? AND addressee_id = ?)",
+        )
+        .bind(a)
+        .bind(b)
+        .bind(b)
+        .bind(a)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(accepted)
+    }
+
+    async fn delete(&self, a: i32, b: i32) -> RepoResult<bool> {
+        let deleted = sqlx::query(
+            "DELETE FROM friendships
+             WHERE (requester_id = ? AND addressee_id = ?) OR (requester_id = ? AND addressee_id = ?)",
+        )
+        .bind(a)
+        .bind(b)
+        .bind(b)
+        .bind(a)
+        .execute(&self.db)
+        .await?;
+        Ok(deleted.rows_affected() == 1)
+    }
+}
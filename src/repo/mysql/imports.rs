@@ -0,0 +1,146 @@
+use axum::async_trait;
+
+use super::{messages, MySqlRepo};
+use crate::repo::{ImportJob, ImportProgress, ImportRepo, ImportedRow, RepoResult};
+
+const JOB_COLUMNS: &str = "id, source, room_prefix, status, rooms, users_created, messages_imported, messages_skipped,
+                           error, created_by, created_at, completed_at";
+
+#[async_trait]
+impl ImportRepo for MySqlRepo {
+    async fn create(&self, source: &str, room_prefix: &str, created_by: i32) -> RepoResult<ImportJob> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO imports (source, room_prefix, created_by) VALUES (?, ?, ?)")
+            .bind(source)
+            .bind(room_prefix)
+            .bind(created_by)
+            .execute(&mut *tx)
+            .await?;
+        let job = sqlx::query_as::<_, ImportJob>(&format!("SELECT {} FROM imports WHERE id = ?", JOB_COLUMNS))
+            .bind(inserted.last_insert_id() as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(job)
+    }
+
+    async fn list(&self, limit: i64) -> RepoResult<Vec<ImportJob>> {
+        let jobs = sqlx::query_as::<_, ImportJob>(&format!("SELECT {} FROM imports ORDER BY id DESC LIMIT ?", JOB_COLUMNS))
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(jobs)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<ImportJob>> {
+        let job = sqlx::query_as::<_, ImportJob>(&format!("SELECT {} FROM imports WHERE id = ?", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(job)
+    }
+
+    async fn set_status(&self, id: i64, status: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE imports SET status = ? WHERE id = ?").bind(status).bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn set_progress(&self, id: i64, progress: ImportProgress) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE imports SET rooms = ?, users_created = ?, messages_imported = ?, messages_skipped = ?
+             WHERE id = ?",
+        )
+        .bind(progress.rooms)
+        .bind(progress.users_created)
+        .bind(progress.imported)
+        .bind(progress.skipped)
+        .bind(id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn finish(&self, id: i64, status: &str, error: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE imports SET status = ?, error = ?, completed_at = UTC_TIMESTAMP(6) WHERE id = ?")
+            .bind(status)
+            .bind(error)
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn imported_user(&self, imported_from: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>("SELECT id, username FROM users WHERE imported_from = ?")
+            .bind(imported_from)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(user)
+    }
+
+    async fn create_placeholder(
+        &self,
+        username: &str,
+        bot: bool,
+        imported_from: &str,
+        display_name: Option<&str>,
+    ) -> RepoResult<Option<i32>> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT IGNORE INTO users (username, is_bot, imported_from) VALUES (?, ?, ?)")
+            .bind(username)
+            .bind(bot)
+            .bind(imported_from)
+            .execute(&mut *tx)
+            .await?;
+        let created = (inserted.rows_affected() == 1).then(|| inserted.last_insert_id() as i32);
+        if let (Some(id), Some(display_name)) = (created, display_name) {
+            sqlx::query("INSERT IGNORE INTO profiles (user_id, display_name) VALUES (?, ?)")
+                .bind(id)
+                .bind(display_name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn set_topic(&self, room: &str, topic: &str) -> RepoResult<()> {
+        sqlx::query("INSERT IGNORE INTO room_topics (room, topic) VALUES (?, ?)")
+            .bind(room)
+            .bind(topic)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_messages(&self, room: &str, messages: &[ImportedRow<'_>]) -> RepoResult<u64> {
+        let mut tx = self.db.begin().await?;
+        let mut inserted = 0;
+        for m in messages {
+            let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM messages WHERE imported_from = ?")
+                .bind(&m.imported_from)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if exists.is_some() {
+                continue;
+            }
+            let id = messages::next_id(&mut tx).await?;
+            sqlx::query(
+                "INSERT INTO messages (id, user_id, username, room, content, created_at, imported_from)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(m.user_id)
+            .bind(m.username)
+            .bind(room)
+            .bind(m.text)
+            .bind(m.sent_at)
+            .bind(&m.imported_from)
+            .execute(&mut *tx)
+            .await?;
+            inserted += 1;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,60 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlConnection;
+
+use super::MySqlRepo;
+use crate::repo::{Invite, InviteRepo, RepoResult};
+
+// 초대 코드를 한 번 사용 처리한다. 가입과 같은 트랜잭션에서 부른다. 쓸 수 없는 코드면 None.
+pub(super) async fn redeem(conn: &mut MySqlConnection, code_hash: &str) -> sqlx::Result<Option<i64>> {
+    // LAST_INSERT_ID(id) 로 고친 초대 코드의 id 를 돌려받는다
+    let redeemed = sqlx::query(
+        "UPDATE invite_codes SET uses = uses + 1, id = LAST_INSERT_ID(id)
+         WHERE code_hash = ? AND uses < max_uses AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))",
+    )
+    .bind(code_hash)
+    .execute(conn)
+    .await?;
+    Ok((redeemed.rows_affected() == 1).then(|| redeemed.last_insert_id() as i64))
+}
+
+#[async_trait]
+impl InviteRepo for MySqlRepo {
+    async fn create(
+        &self,
+        code_hash: &str,
+        created_by: i32,
+        max_uses: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<i64> {
+        let inserted =
+            sqlx::query("INSERT INTO invite_codes (code_hash, created_by, max_uses, expires_at) VALUES (?, ?, ?, ?)")
+                .bind(code_hash)
+                .bind(created_by)
+                .bind(max_uses)
+                .bind(expires_at)
+                .execute(&self.db)
+                .await?;
+        Ok(inserted.last_insert_id() as i64)
+    }
+
+    async fn list(&self) -> RepoResult<Vec<Invite>> {
+        let invites = sqlx::query_as::<_, Invite>(
+            "SELECT id, created_by, max_uses, uses, expires_at, revoked_at, created_at
+             FROM invite_codes ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(invites)
+    }
+
+    async fn revoke(&self, id: i64) -> RepoResult<bool> {
+        let revoked =
+            sqlx::query("UPDATE invite_codes SET revoked_at = UTC_TIMESTAMP(6) WHERE id = ? AND revoked_at IS NULL")
+                .bind(id)
+                .execute(&self.db)
+                .await?;
+        Ok(revoked.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,72 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use std::net::IpAddr;
+
+use super::MySqlRepo;
+use crate::repo::{
+    ip_bans::{contains, normalize, parse_network},
+    IpBan, IpBanRepo, RepoError, RepoResult,
+};
+
+const BAN_COLUMNS: &str = "id, network, reason, created_by, expires_at, created_at";
+
+#[async_trait]
+impl IpBanRepo for MySqlRepo {
+    async fn is_banned(&self, ip: IpAddr) -> RepoResult<bool> {
+        // IPv4 에 대응된 IPv6 주소는 Postgres 처럼 IPv4 대역에 걸리지 않는다
+        let networks = sqlx::query_scalar::<_, String>(
+            "SELECT network FROM ip_bans WHERE expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(networks.iter().any(|network| contains(network, ip)))
+    }
+
+    async fn list(&self) -> RepoResult<Vec<IpBan>> {
+        let bans = sqlx::query_as::<_, IpBan>(&format!(
+            "SELECT {} FROM ip_bans WHERE expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6) ORDER BY created_at DESC",
+            BAN_COLUMNS
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bans)
+    }
+
+    async fn create(
+        &self,
+        network: &str,
+        reason: Option<&str>,
+        created_by: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<IpBan> {
+        let (addr, prefix) =
+            parse_network(network).ok_or_else(|| RepoError::Backend(format!("invalid network: {}", network)))?;
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO ip_bans (network, reason, created_by, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(normalize(addr, prefix))
+            .bind(reason)
+            .bind(created_by)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await?;
+        let ban = sqlx::query_as::<_, IpBan>(&format!("SELECT {} FROM ip_bans WHERE id = ?", BAN_COLUMNS))
+            .bind(inserted.last_insert_id() as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(ban)
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<String>> {
+        let mut tx = self.db.begin().await?;
+        let network = sqlx::query_scalar::<_, String>("SELECT network FROM ip_bans WHERE id = ? FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if network.is_some() {
+            sqlx::query("DELETE FROM ip_bans WHERE id = ?").bind(id).execute(&mut *tx).await?;
+            tx.commit().await?;
+        }
+        Ok(network)
+    }
+}
@@ -0,0 +1,66 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{Keyword, KeywordRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl KeywordRepo for MySqlRepo {
+    async fn all(&self) -> RepoResult<Vec<Keyword>> {
+        let keywords = sqlx::query_as::<_, Keyword>(
+            "SELECT id, user_id, keyword, room, created_at FROM keyword_alerts ORDER BY id",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keywords)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Keyword>> {
+        let keywords = sqlx::query_as::<_, Keyword>(
+            "SELECT id, user_id, keyword, room, created_at FROM keyword_alerts WHERE user_id = ? ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keywords)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM keyword_alerts WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, user_id: i32, keyword: &str, room: Option<&str>) -> RepoResult<Keyword> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO keyword_alerts (user_id, keyword, room) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(keyword)
+            .bind(room)
+            .execute(&mut *tx)
+            .await;
+        let id = match inserted {
+            Ok(result) => result.last_insert_id() as i64,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Err(RepoError::Conflict("keyword")),
+            Err(e) => return Err(e.into()),
+        };
+        let created = sqlx::query_as::<_, Keyword>(
+            "SELECT id, user_id, keyword, room, created_at FROM keyword_alerts WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM keyword_alerts WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
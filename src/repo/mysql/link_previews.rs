@@ -0,0 +1,47 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{LinkPreview, LinkPreviewRepo, RepoResult};
+
+// 행은 주소의 SHA-256 으로 찾는다 (migrations_mysql 의 link_previews)
+#[async_trait]
+impl LinkPreviewRepo for MySqlRepo {
+    async fn cached(&self, url: &str, fetched_after: DateTime<Utc>) -> RepoResult<Option<LinkPreview>> {
+        let preview = sqlx::query_as::<_, LinkPreview>(
+            "SELECT title, description, image_url, site_name FROM link_previews
+             WHERE url_hash = SHA2(?, 256) AND fetched_at > ?",
+        )
+        .bind(url)
+        .bind(fetched_after)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(preview)
+    }
+
+    async fn save(&self, url: &str, preview: &LinkPreview) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO link_previews (url_hash, url, title, description, image_url, site_name, fetched_at)
+             VALUES (SHA2(?, 256), ?, ?, ?, ?, ?, UTC_TIMESTAMP(6))
+             ON DUPLICATE KEY UPDATE title = VALUES(title), description = VALUES(description),
+                 image_url = VALUES(image_url), site_name = VALUES(site_name), fetched_at = VALUES(fetched_at)",
+        )
+        .bind(url)
+        .bind(url)
+        .bind(&preview.title)
+        .bind(&preview.description)
+        .bind(&preview.image_url)
+        .bind(&preview.site_name)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn purge(&self, fetched_before: DateTime<Utc>) -> RepoResult<u64> {
+        let result = sqlx::query("DELETE FROM link_previews WHERE fetched_at < ?")
+            .bind(fetched_before)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,31 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{Maintenance, MaintenanceRepo, RepoResult};
+
+#[async_trait]
+impl MaintenanceRepo for MySqlRepo {
+    async fn get(&self) -> RepoResult<Maintenance> {
+        let maintenance =
+            sqlx::query_as::<_, Maintenance>("SELECT enabled, message, updated_by, updated_at FROM maintenance")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(maintenance)
+    }
+
+    async fn set(&self, enabled: bool, message: Option<&str>, updated_by: i32) -> RepoResult<Maintenance> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("UPDATE maintenance SET enabled = ?, message = ?, updated_by = ?, updated_at = UTC_TIMESTAMP(6)")
+            .bind(enabled)
+            .bind(message)
+            .bind(updated_by)
+            .execute(&mut *tx)
+            .await?;
+        let maintenance =
+            sqlx::query_as::<_, Maintenance>("SELECT enabled, message, updated_by, updated_at FROM maintenance")
+                .fetch_one(&mut *tx)
+                .await?;
+        tx.commit().await?;
+        Ok(maintenance)
+    }
+}
@@ -0,0 +1,223 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlConnection;
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{ExportRow, ExportedMessage, MessageRepo, MessageRow, NewMessage, RepoResult, StoredMessage};
+
+const MESSAGE_COLUMNS: &str =
+    "id, room, user_id, username, content AS text, format, html, webhook_id, forwarded, created_at, expires_at";
+
+// viewer 가 볼 수 있는 메시지만 남기는 조건. 첫 자리표시자가 viewer 다.
+const VISIBLE: &str = "(NOT hidden OR user_id = ?) AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))";
+
+// 메시지를 넣는 다른 저장소가 같은 트랜잭션에서 새 id 를 받을 때 쓴다
+pub(super) async fn next_id(conn: &mut MySqlConnection) -> sqlx::Result<i64> {
+    reserve(conn, 1).await
+}
+
+// count 개의 id 를 잡고 마지막 id 를 돌려준다. LAST_INSERT_ID(expr) 로 고친 값을 같은 연결에서 돌려받는다.
+async fn reserve(conn: &mut MySqlConnection, count: i64) -> sqlx::Result<i64> {
+    let reserved = sqlx::query("UPDATE message_id_sequence SET last_id = LAST_INSERT_ID(last_id + ?)")
+        .bind(count)
+        .execute(conn)
+        .await?;
+    Ok(reserved.last_insert_id() as i64)
+}
+
+impl MySqlRepo {
+    // viewer, room, 기준 id, limit 을 차례로 받는 목록 조회
+    async fn page(
+        &self,
+        sql: &str,
+        viewer: i32,
+        room: &str,
+        bound: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let rows = sqlx::query_as::<_, MessageRow>(sql)
+            .bind(viewer)
+            .bind(room)
+            .bind(bound)
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+}
+
+#[async_trait]
+impl MessageRepo for MySqlRepo {
+    async fn next_ids(&self, count: i64) -> RepoResult<Vec<i64>> {
+        let mut conn = self.db.acquire().await?;
+        let last = reserve(&mut conn, count).await?;
+        Ok((last - count + 1..=last).collect())
+    }
+
+    async fn insert_batch(&self, batch: &[NewMessage]) -> RepoResult<()> {
+        let mut tx = self.db.begin().await?;
+        for m in batch {
+            let forwarded = m.forwarded.as_ref().and_then(|f| serde_json::to_string(f).ok());
+            sqlx::query(
+                "INSERT INTO messages
+                     (id, user_id, username, room, content, format, html, hidden, expires_at, forwarded, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON DUPLICATE KEY UPDATE id = id",
+            )
+            .bind(m.id)
+            .bind(m.user_id)
+            .bind(&m.username)
+            .bind(&m.room)
+            .bind(&m.text)
+            .bind(m.format.as_str())
+            .bind(&m.html)
+            .bind(m.hidden)
+            .bind(m.expires_at)
+            .bind(forwarded)
+            .bind(m.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_visible(&self, viewer: i32, id: i64) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND id = ?",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn find_visible_many(&self, viewer: i32, ids: &[i64]) -> RepoResult<Vec<StoredMessage>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND id IN ({})",
+            MESSAGE_COLUMNS,
+            VISIBLE,
+            placeholders(ids.len())
+        );
+        let mut query = sqlx::query_as::<_, MessageRow>(&sql).bind(viewer);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&self.db).await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+
+    async fn page_before(
+        &self,
+        viewer: i32,
+        room: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = ? AND id < ? ORDER BY id DESC LIMIT ?",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        let mut messages = self.page(&sql, viewer, room, before.unwrap_or(i64::MAX), limit).await?;
+        messages.reverse();
+        Ok(messages)
+    }
+
+    async fn page_after(&self, viewer: i32, room: &str, after: i64, limit: i64) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = ? AND id > ? ORDER BY id LIMIT ?",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        self.page(&sql, viewer, room, after, limit).await
+    }
+
+    async fn first_since(&self, viewer: i32, room: &str, at: DateTime<Utc>) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND room = ? AND created_at >= ? ORDER BY created_at, id LIMIT 1",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(room)
+        .bind(at)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn export_page(&self, room: &str, after: i64, limit: i64) -> RepoResult<Vec<ExportedMessage>> {
+        let rows = sqlx::query_as::<_, ExportRow>(
+            "SELECT id, user_id, username, content AS text, format, hidden, webhook_id, forwarded, created_at, expires_at
+             FROM messages WHERE room = ? AND id > ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))
+             ORDER BY id LIMIT ?",
+        )
+        .bind(room)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(ExportedMessage::from).collect())
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<(String, Vec<String>)>> {
+        let mut tx = self.db.begin().await?;
+        let room = sqlx::query_scalar::<_, String>("SELECT room FROM messages WHERE id = ? FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(room) = room else {
+            return Ok(None);
+        };
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM attachments WHERE message_id = ?
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id = ? AND thumbnail_key IS NOT NULL",
+        )
+        .bind(id)
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM messages WHERE id = ?").bind(id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(Some((room, keys)))
+    }
+
+    // 알림은 메시지가 지워지면 message_id 만 비므로 같은 트랜잭션에서 먼저 지운다
+    async fn expire_due(&self, limit: i64) -> RepoResult<Vec<(i64, String)>> {
+        let mut tx = self.db.begin().await?;
+        let expired = sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, room FROM messages WHERE expires_at <= UTC_TIMESTAMP(6) ORDER BY expires_at, id LIMIT ?
+             FOR UPDATE",
+        )
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if expired.is_empty() {
+            return Ok(expired);
+        }
+        let ids = placeholders(expired.len());
+        for table in ["notifications WHERE message_id", "messages WHERE id"] {
+            let sql = format!("DELETE FROM {} IN ({})", table, ids);
+            let mut query = sqlx::query(&sql);
+            for (id, _) in &expired {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(expired)
+    }
+
+    async fn next_expiry(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT MIN(expires_at) FROM messages WHERE expires_at IS NOT NULL",
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(next)
+    }
+}
@@ -0,0 +1,197 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{DigestMention, NewNotification, Notification, NotificationRepo, RepoResult};
+
+#[async_trait]
+impl NotificationRepo for MySqlRepo {
+    async fn create(&self, notification: &NewNotification<'_>) -> RepoResult<(i64, DateTime<Utc>)> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO notifications (user_id, kind, room, message_id, actor_id, text) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(notification.user_id)
+        .bind(notification.kind)
+        .bind(notification.room)
+        .bind(notification.message_id)
+        .bind(notification.actor_id)
+        .bind(notification.text)
+        .execute(&mut *tx)
+        .await?;
+        let id = inserted.last_insert_id() as i64;
+        let created_at = sqlx::query_scalar::<_, DateTime<Utc>>("SELECT created_at FROM notifications WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok((id, created_at))
+    }
+
+    // 이름과 id 목록은 두 번씩 나오므로 나오는 순서대로 두 번 바인딩한다
+    async fn message_recipients(
+        &self,
+        room: &str,
+        author_id: i32,
+        mentioned: &[String],
+        watchers: &[i32],
+    ) -> RepoResult<Vec<(i32, bool, bool)>> {
+        let names = placeholders(mentioned.len());
+        let ids = placeholders(watchers.len());
+        let sql = format!(
+            "SELECT u.id, u.username_key IN ({names}) AS mentioned, u.id IN ({ids}) AS keyword
+             FROM users u LEFT JOIN room_notification_prefs p ON p.user_id = u.id AND p.room = ?
+             WHERE u.id <> ? AND u.disabled_at IS NULL
+               AND (((u.username_key IN ({names}) OR u.id IN ({ids})) AND COALESCE(p.level, 'mentions') <> 'mute')
+                    OR p.level = 'all')"
+        );
+        let mut query = sqlx::query_as::<_, (i32, bool, bool)>(&sql);
+        for name in mentioned {
+            query = query.bind(name);
+        }
+        for id in watchers {
+            query = query.bind(id);
+        }
+        query = query.bind(room).bind(author_id);
+        for name in mentioned {
+            query = query.bind(name);
+        }
+        for id in watchers {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.db).await?)
+    }
+
+    async fn purge(&self, created_before: DateTime<Utc>) -> RepoResult<u64> {
+        let purged = sqlx::query("DELETE FROM notifications WHERE created_at < ?")
+            .bind(created_before)
+            .execute(&self.db)
+            .await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn list(
+        &self,
+        user_id: i32,
+        unread_only: bool,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT n.id, n.kind, n.room, n.message_id, n.actor_id, u.username AS actor, n.text, n.read_at, n.created_at
+             FROM notifications n LEFT JOIN users u ON u.id = n.actor_id
+             WHERE n.user_id = ? AND (NOT ? OR n.read_at IS NULL) AND (? IS NULL OR n.id < ?)
+             ORDER BY n.id DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(before)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(notifications)
+    }
+
+    async fn unread_count(&self, user_id: i32) -> RepoResult<i64> {
+        let unread =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM notifications WHERE user_id = ? AND read_at IS NULL")
+                .bind(user_id)
+                .fetch_one(&self.db)
+                .await?;
+        Ok(unread)
+    }
+
+    async fn mark_read(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = COALESCE(read_at, UTC_TIMESTAMP(6)) WHERE id = ? AND user_id = ?",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn mark_all_read(&self, user_id: i32) -> RepoResult<u64> {
+        let result =
+            sqlx::query("UPDATE notifications SET read_at = UTC_TIMESTAMP(6) WHERE user_id = ? AND read_at IS NULL")
+                .bind(user_id)
+                .execute(&self.db)
+                .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn room_level(&self, user_id: i32, room: &str) -> RepoResult<Option<String>> {
+        let level =
+            sqlx::query_scalar::<_, String>("SELECT level FROM room_notification_prefs WHERE user_id = ? AND room = ?")
+                .bind(user_id)
+                .bind(room)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(level)
+    }
+
+    async fn set_room_level(&self, user_id: i32, room: &str, level: &str) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_notification_prefs (user_id, room, level) VALUES (?, ?, ?)
+             ON DUPLICATE KEY UPDATE level = VALUES(level), updated_at = UTC_TIMESTAMP(6)",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(level)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_digest(&self, created_before: DateTime<Utc>) -> RepoResult<Vec<DigestMention>> {
+        let mentions = sqlx::query_as::<_, DigestMention>(
+            "SELECT u.id AS user_id, u.email, n.room, a.username AS actor, n.text, n.created_at
+             FROM notifications n
+             JOIN users u ON u.id = n.user_id
+             LEFT JOIN users a ON a.id = n.actor_id
+             WHERE n.kind = 'mention' AND n.read_at IS NULL
+               AND n.created_at < ?
+               AND (u.digest_sent_at IS NULL OR n.created_at > u.digest_sent_at)
+               AND (u.last_online_at IS NULL OR u.last_online_at < n.created_at)
+               AND u.email_digest AND u.email IS NOT NULL AND u.email_verified_at IS NOT NULL
+               AND u.disabled_at IS NULL
+             ORDER BY u.id, n.id",
+        )
+        .bind(created_before)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(mentions)
+    }
+
+    async fn mark_digest_sent(&self, user_id: i32, sent_until: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET digest_sent_at = ? WHERE id = ?")
+            .bind(sent_until)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn digest_enabled(&self, user_id: i32) -> RepoResult<Option<bool>> {
+        let enabled = sqlx::query_scalar::<_, bool>("SELECT email_digest FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(enabled)
+    }
+
+    async fn set_digest(&self, user_id: i32, enabled: bool) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET email_digest = ?, digest_sent_at = CASE WHEN ? THEN digest_sent_at ELSE UTC_TIMESTAMP(6) END
+             WHERE id = ?",
+        )
+        .bind(enabled)
+        .bind(enabled)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,81 @@
+use axum::async_trait;
+
+use super::{duplicate_key, MySqlRepo};
+use crate::repo::{ExternalAccount, OAuthLink, OAuthRepo, RepoResult};
+
+#[async_trait]
+impl OAuthRepo for MySqlRepo {
+    async fn linked_user(&self, provider: &str, subject: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>(
+            "SELECT u.id, u.username FROM oauth_identities o JOIN users u ON u.id = o.user_id
+             WHERE o.provider = ? AND o.subject = ?",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user)
+    }
+
+    async fn link(&self, account: &ExternalAccount<'_>) -> RepoResult<OAuthLink> {
+        let mut tx = self.db.begin().await?;
+
+        let existing = match account.email {
+            Some(email) => {
+                sqlx::query_as::<_, (i32, String, bool)>(
+                    "SELECT id, username, email_verified_at IS NOT NULL FROM users WHERE email_key = LOWER(?)",
+                )
+                .bind(email)
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+            None => None,
+        };
+
+        let (user_id, username) = match existing {
+            Some((id, username, true)) => (id, username),
+            Some((_, _, false)) => return Ok(OAuthLink::EmailInUse),
+            None if !account.allow_signup => return Ok(OAuthLink::RegistrationClosed),
+            None => {
+                let mut n = 1;
+                loop {
+                    let candidate = match n {
+                        1 => account.username_base.to_string(),
+                        _ => format!("{}{}", account.username_base, n),
+                    };
+                    // 메일 주소는 이미 소유가 확인된 경우에만 넘어오므로 확인된 주소로 저장한다
+                    let inserted = sqlx::query(
+                        "INSERT INTO users (username, email, email_verified_at)
+                         VALUES (?, ?, CASE WHEN ? IS NULL THEN NULL ELSE UTC_TIMESTAMP(6) END)",
+                    )
+                    .bind(&candidate)
+                    .bind(account.email)
+                    .bind(account.email)
+                    .execute(&mut *tx)
+                    .await;
+                    match inserted {
+                        Ok(result) => break (result.last_insert_id() as i32, candidate),
+                        // 이름이 겹치면 숫자를 붙여 다시 넣는다
+                        Err(sqlx::Error::Database(e))
+                            if e.is_unique_violation() && duplicate_key(&*e) == "users_username_key" =>
+                        {
+                            n += 1
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            }
+        };
+
+        sqlx::query("INSERT INTO oauth_identities (provider, subject, user_id, email) VALUES (?, ?, ?, ?)")
+            .bind(account.provider)
+            .bind(account.subject)
+            .bind(user_id)
+            .bind(account.email)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(OAuthLink::Linked(user_id, username))
+    }
+}
@@ -0,0 +1,103 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{PasskeyInfo, PasskeyRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl PasskeyRepo for MySqlRepo {
+    async fn save_ceremony(
+        &self,
+        token_hash: &str,
+        user_id: i32,
+        kind: &str,
+        state: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("DELETE FROM webauthn_ceremonies WHERE expires_at < UTC_TIMESTAMP(6)").execute(&self.db).await?;
+        sqlx::query(
+            "INSERT INTO webauthn_ceremonies (token_hash, user_id, kind, state, expires_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(kind)
+        .bind(state)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // 잠그고 읽은 뒤 지워서 같은 절차를 두 번 끝내지 못하게 한다
+    async fn take_ceremony(&self, token_hash: &str, kind: &str) -> RepoResult<Option<(i32, String)>> {
+        let mut tx = self.db.begin().await?;
+        let row = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, state FROM webauthn_ceremonies
+             WHERE token_hash = ? AND kind = ? AND expires_at > UTC_TIMESTAMP(6)
+             FOR UPDATE",
+        )
+        .bind(token_hash)
+        .bind(kind)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if row.is_some() {
+            sqlx::query("DELETE FROM webauthn_ceremonies WHERE token_hash = ?")
+                .bind(token_hash)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+        Ok(row)
+    }
+
+    async fn credentials(&self, user_id: i32) -> RepoResult<Vec<(i64, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String)>("SELECT id, passkey FROM passkeys WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows)
+    }
+
+    async fn add(&self, user_id: i32, credential_id: &str, passkey: &str, name: &str) -> RepoResult<i64> {
+        let stored = sqlx::query("INSERT INTO passkeys (user_id, credential_id, passkey, name) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(credential_id)
+            .bind(passkey)
+            .bind(name)
+            .execute(&self.db)
+            .await;
+        match stored {
+            Ok(result) => Ok(result.last_insert_id() as i64),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("passkey")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PasskeyInfo>> {
+        let keys = sqlx::query_as::<_, PasskeyInfo>(
+            "SELECT id, name, created_at, last_used_at FROM passkeys WHERE user_id = ? ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keys)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM passkeys WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn mark_used(&self, id: i64, passkey: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE passkeys SET last_used_at = UTC_TIMESTAMP(6), passkey = COALESCE(?, passkey) WHERE id = ?")
+            .bind(passkey)
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{NewPoll, PollRepo, PollRow, RepoResult};
+
+const POLL_COLUMNS: &str =
+    "id, room, user_id, username, question, options, anonymous, hidden, closes_at, closed_at, created_at";
+
+// options 는 JSON 배열로 저장한다
+#[derive(FromRow)]
+struct Row {
+    id: i64,
+    room: String,
+    user_id: Option<i32>,
+    username: String,
+    question: String,
+    options: SqlJson<Vec<String>>,
+    anonymous: bool,
+    hidden: bool,
+    closes_at: Option<DateTime<Utc>>,
+    closed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<Row> for PollRow {
+    fn from(row: Row) -> Self {
+        PollRow {
+            id: row.id,
+            room: row.room,
+            user_id: row.user_id,
+            username: row.username,
+            question: row.question,
+            options: row.options.0,
+            anonymous: row.anonymous,
+            hidden: row.hidden,
+            closes_at: row.closes_at,
+            closed_at: row.closed_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl PollRepo for MySqlRepo {
+    async fn find(&self, poll_id: i64) -> RepoResult<Option<PollRow>> {
+        let sql = format!("SELECT {} FROM polls WHERE id = ?", POLL_COLUMNS);
+        let row = sqlx::query_as::<_, Row>(&sql).bind(poll_id).fetch_optional(&self.db).await?;
+        Ok(row.map(PollRow::from))
+    }
+
+    async fn recent(&self, room: &str, viewer: i32, open_only: bool, limit: i64) -> RepoResult<Vec<PollRow>> {
+        let sql = format!(
+            "SELECT {} FROM polls WHERE room = ? AND (NOT hidden OR user_id = ?) AND (NOT ? OR closed_at IS NULL)
+             ORDER BY id DESC LIMIT ?",
+            POLL_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, Row>(&sql)
+            .bind(room)
+            .bind(viewer)
+            .bind(open_only)
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(PollRow::from).collect())
+    }
+
+    async fn votes(&self, poll_id: i64, viewer: Option<i32>) -> RepoResult<Vec<(i16, i32, String)>> {
+        let votes = sqlx::query_as::<_, (i16, i32, String)>(
+            "SELECT v.`option`, v.user_id, u.username FROM poll_votes v JOIN users u ON u.id = v.user_id
+             WHERE v.poll_id = ? AND (NOT u.shadow_banned OR v.user_id = ?) ORDER BY v.voted_at",
+        )
+        .bind(poll_id)
+        .bind(viewer)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(votes)
+    }
+
+    async fn vote(&self, poll_id: i64, user_id: i32, option: i16) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO poll_votes (poll_id, user_id, `option`, voted_at) VALUES (?, ?, ?, UTC_TIMESTAMP(6))
+             ON DUPLICATE KEY UPDATE `option` = VALUES(`option`), voted_at = VALUES(voted_at)",
+        )
+        .bind(poll_id)
+        .bind(user_id)
+        .bind(option)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn unvote(&self, poll_id: i64, user_id: i32) -> RepoResult<()> {
+        sqlx::query("DELETE FROM poll_votes WHERE poll_id = ? AND user_id = ?")
+            .bind(poll_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn create(&self, poll: NewPoll<'_>) -> RepoResult<PollRow> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO polls (room, user_id, username, question, options, anonymous, hidden, closes_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(poll.room)
+        .bind(poll.user_id)
+        .bind(poll.username)
+        .bind(poll.question)
+        .bind(SqlJson(poll.options))
+        .bind(poll.anonymous)
+        .bind(poll.hidden)
+        .bind(poll.closes_at)
+        .execute(&mut *tx)
+        .await?;
+        let sql = format!("SELECT {} FROM polls WHERE id = ?", POLL_COLUMNS);
+        let row = sqlx::query_as::<_, Row>(&sql).bind(inserted.last_insert_id() as i64).fetch_one(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(row.into())
+    }
+
+    async fn close(&self, poll_id: i64) -> RepoResult<Option<PollRow>> {
+        let mut tx = self.db.begin().await?;
+        let updated = sqlx::query("UPDATE polls SET closed_at = UTC_TIMESTAMP(6) WHERE id = ? AND closed_at IS NULL")
+            .bind(poll_id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let sql = format!("SELECT {} FROM polls WHERE id = ?", POLL_COLUMNS);
+        let row = sqlx::query_as::<_, Row>(&sql).bind(poll_id).fetch_one(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(Some(row.into()))
+    }
+
+    // 마감할 투표를 잠가 두고 닫은 뒤 닫힌 모습으로 다시 읽는다
+    async fn close_due(&self) -> RepoResult<Vec<PollRow>> {
+        let mut tx = self.db.begin().await?;
+        let due = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM polls WHERE closed_at IS NULL AND closes_at <= UTC_TIMESTAMP(6) FOR UPDATE",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = placeholders(due.len());
+        let sql = format!("UPDATE polls SET closed_at = UTC_TIMESTAMP(6) WHERE id IN ({})", ids);
+        let mut query = sqlx::query(&sql);
+        for id in &due {
+            query = query.bind(id);
+        }
+        query.execute(&mut *tx).await?;
+        let sql = format!("SELECT {} FROM polls WHERE id IN ({}) ORDER BY id", POLL_COLUMNS, ids);
+        let mut query = sqlx::query_as::<_, Row>(&sql);
+        for id in &due {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(rows.into_iter().map(PollRow::from).collect())
+    }
+
+    async fn next_close(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next =
+            sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT MIN(closes_at) FROM polls WHERE closed_at IS NULL")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(next)
+    }
+}
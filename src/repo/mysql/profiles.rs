@@ -0,0 +1,120 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{Profile, ProfileRepo, ProfileUpdate, RepoResult};
+
+#[async_trait]
+impl ProfileRepo for MySqlRepo {
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<Profile>> {
+        let profile = sqlx::query_as::<_, Profile>(
+            "SELECT u.id AS user_id, u.username, p.display_name, p.bio, p.pronouns, p.avatar_version, u.is_guest,
+                    u.created_at
+             FROM users u LEFT JOIN profiles p ON p.user_id = u.id WHERE u.username_key = LOWER(?)",
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(profile)
+    }
+
+    async fn display(&self, user_id: i32) -> RepoResult<Option<(Option<String>, Option<String>)>> {
+        let row = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            "SELECT display_name, avatar_version FROM profiles WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn update(&self, user_id: i32, update: &ProfileUpdate) -> RepoResult<()> {
+        // 뒤의 세 자리표시자는 그 항목을 바꾸는지 여부
+        sqlx::query(
+            "INSERT INTO profiles (user_id, display_name, bio, pronouns) VALUES (?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE
+                 display_name = CASE WHEN ? THEN VALUES(display_name) ELSE display_name END,
+                 bio = CASE WHEN ? THEN VALUES(bio) ELSE bio END,
+                 pronouns = CASE WHEN ? THEN VALUES(pronouns) ELSE pronouns END,
+                 updated_at = UTC_TIMESTAMP(6)",
+        )
+        .bind(user_id)
+        .bind(update.display_name.clone().flatten())
+        .bind(update.bio.clone().flatten())
+        .bind(update.pronouns.clone().flatten())
+        .bind(update.display_name.is_some())
+        .bind(update.bio.is_some())
+        .bind(update.pronouns.is_some())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_avatar(&self, user_id: i32, version: Option<&str>) -> RepoResult<()> {
+        match version {
+            Some(version) => {
+                sqlx::query(
+                    "INSERT INTO profiles (user_id, avatar_version) VALUES (?, ?)
+                     ON DUPLICATE KEY UPDATE avatar_version = VALUES(avatar_version), updated_at = UTC_TIMESTAMP(6)",
+                )
+                .bind(user_id)
+                .bind(version)
+                .execute(&self.db)
+                .await?;
+            }
+            None => {
+                sqlx::query("UPDATE profiles SET avatar_version = NULL, updated_at = UTC_TIMESTAMP(6) WHERE user_id = ?")
+                    .bind(user_id)
+                    .execute(&self.db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn avatar_by_name(&self, username: &str) -> RepoResult<Option<(i32, String)>> {
+        let found = sqlx::query_as::<_, (i32, String)>(
+            "SELECT u.id, p.avatar_version FROM users u JOIN profiles p ON p.user_id = u.id
+             WHERE u.username_key = LOWER(?) AND p.avatar_version IS NOT NULL",
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(found)
+    }
+
+    async fn status(&self, user_id: i32) -> RepoResult<Option<(String, Option<String>)>> {
+        let status =
+            sqlx::query_as::<_, (String, Option<String>)>("SELECT status, status_message FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(status)
+    }
+
+    async fn set_status(
+        &self,
+        user_id: i32,
+        status: Option<&str>,
+        message: Option<Option<&str>>,
+    ) -> RepoResult<Option<(String, Option<String>)>> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "UPDATE users SET status = COALESCE(?, status),
+                              status_message = CASE WHEN ? THEN ? ELSE status_message END
+             WHERE id = ?",
+        )
+        .bind(status)
+        .bind(message.is_some())
+        .bind(message.flatten())
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        let updated =
+            sqlx::query_as::<_, (String, Option<String>)>("SELECT status, status_message FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+}
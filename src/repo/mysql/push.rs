@@ -0,0 +1,85 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{PushRepo, PushSubscription, PushTarget, RepoResult};
+
+#[async_trait]
+impl PushRepo for MySqlRepo {
+    async fn targets(&self, user_id: i32) -> RepoResult<Vec<PushTarget>> {
+        let targets = sqlx::query_as::<_, PushTarget>(
+            "SELECT id, endpoint, p256dh, auth FROM push_subscriptions WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(targets)
+    }
+
+    async fn mark_delivered(&self, id: i64) -> RepoResult<()> {
+        sqlx::query("UPDATE push_subscriptions SET last_used_at = UTC_TIMESTAMP(6) WHERE id = ?")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, id: i64) -> RepoResult<()> {
+        sqlx::query("DELETE FROM push_subscriptions WHERE id = ?").bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PushSubscription>> {
+        let subscriptions = sqlx::query_as::<_, PushSubscription>(
+            "SELECT id, endpoint, created_at, last_used_at FROM push_subscriptions WHERE user_id = ? ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    async fn count_other(&self, user_id: i32, endpoint: &str) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM push_subscriptions WHERE user_id = ? AND endpoint <> ?",
+        )
+        .bind(user_id)
+        .bind(endpoint)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    // 이미 있는 엔드포인트면 고친 행의 id 를 last_insert_id() 로 받을 수 없으므로 주소로 다시 읽는다
+    async fn subscribe(&self, user_id: i32, endpoint: &str, p256dh: &str, auth: &str) -> RepoResult<PushSubscription> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO push_subscriptions (user_id, endpoint, p256dh, auth) VALUES (?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE
+                 user_id = VALUES(user_id), p256dh = VALUES(p256dh), auth = VALUES(auth),
+                 created_at = UTC_TIMESTAMP(6), last_used_at = NULL",
+        )
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(p256dh)
+        .bind(auth)
+        .execute(&mut *tx)
+        .await?;
+        let subscription = sqlx::query_as::<_, PushSubscription>(
+            "SELECT id, endpoint, created_at, last_used_at FROM push_subscriptions WHERE endpoint = ?",
+        )
+        .bind(endpoint)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(subscription)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM push_subscriptions WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
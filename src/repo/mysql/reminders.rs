@@ -0,0 +1,86 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{DueReminder, Reminder, ReminderRepo, RepoResult};
+
+#[async_trait]
+impl ReminderRepo for MySqlRepo {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        target: &str,
+        text: &str,
+        remind_at: DateTime<Utc>,
+    ) -> RepoResult<i64> {
+        let inserted =
+            sqlx::query("INSERT INTO reminders (user_id, room, target, text, remind_at) VALUES (?, ?, ?, ?, ?)")
+                .bind(user_id)
+                .bind(room)
+                .bind(target)
+                .bind(text)
+                .bind(remind_at)
+                .execute(&self.db)
+                .await?;
+        Ok(inserted.last_insert_id() as i64)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM reminders WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Reminder>> {
+        let reminders = sqlx::query_as::<_, Reminder>(
+            "SELECT id, room, target, text, remind_at, created_at FROM reminders WHERE user_id = ? ORDER BY remind_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(reminders)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM reminders WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    // 때가 된 알림을 잠가 읽은 뒤 같은 트랜잭션에서 지운다. 서버가 여럿이어도 한 알림은 한 번만 나간다.
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueReminder>> {
+        let mut tx = self.db.begin().await?;
+        let due = sqlx::query_as::<_, DueReminder>(
+            "SELECT r.id, r.user_id, u.username, r.room, r.target, r.text, r.created_at
+             FROM reminders r JOIN users u ON u.id = r.user_id
+             WHERE r.remind_at <= UTC_TIMESTAMP(6) ORDER BY r.remind_at LIMIT ? FOR UPDATE",
+        )
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if due.is_empty() {
+            return Ok(due);
+        }
+        let sql = format!("DELETE FROM reminders WHERE id IN ({})", placeholders(due.len()));
+        let mut query = sqlx::query(&sql);
+        for reminder in &due {
+            query = query.bind(reminder.id);
+        }
+        query.execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(due)
+    }
+
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT MIN(remind_at) FROM reminders")
+            .fetch_one(&self.db)
+            .await?;
+        Ok(next)
+    }
+}
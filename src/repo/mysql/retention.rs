@@ -0,0 +1,122 @@
+use axum::async_trait;
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{RepoResult, RetentionBatch, RetentionRepo, RoomPolicy};
+
+const POLICY_COLUMNS: &str = "room, days, action, exempt, updated_by, updated_at";
+
+// Postgres 의 to_jsonb(m) 과 같은 모양으로 메시지 행을 JSON 으로 만든다. MariaDB 의 JSON 열은 문자열이라
+// JSON_EXTRACT(..., '$') 로 감싸야 문자열로 따옴표 쳐지지 않는다.
+const MESSAGE_JSON: &str = "JSON_OBJECT('id', id, 'user_id', user_id, 'username', username, 'room', room,
+     'content', content, 'created_at', DATE_FORMAT(created_at, '%Y-%m-%dT%H:%i:%s.%f+00:00'), 'format', format,
+     'html', html, 'hidden', JSON_EXTRACT(IF(hidden, 'true', 'false'), '$'), 'webhook_id', webhook_id,
+     'expires_at', DATE_FORMAT(expires_at, '%Y-%m-%dT%H:%i:%s.%f+00:00'),
+     'forwarded', JSON_EXTRACT(forwarded, '$'), 'imported_from', imported_from)";
+
+#[async_trait]
+impl RetentionRepo for MySqlRepo {
+    async fn policy(&self, room: &str) -> RepoResult<Option<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention WHERE room = ?", POLICY_COLUMNS);
+        let policy = sqlx::query_as::<_, RoomPolicy>(&sql).bind(room).fetch_optional(&self.db).await?;
+        Ok(policy)
+    }
+
+    async fn policies(&self) -> RepoResult<Vec<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention ORDER BY room", POLICY_COLUMNS);
+        let policies = sqlx::query_as::<_, RoomPolicy>(&sql).fetch_all(&self.db).await?;
+        Ok(policies)
+    }
+
+    async fn set_policy(&self, room: &str, days: Option<i32>, action: Option<&str>, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, days, action, updated_by, updated_at)
+             VALUES (?, ?, ?, ?, UTC_TIMESTAMP(6))
+             ON DUPLICATE KEY UPDATE days = VALUES(days), action = VALUES(action),
+                 updated_by = VALUES(updated_by), updated_at = VALUES(updated_at)",
+        )
+        .bind(room)
+        .bind(days)
+        .bind(action)
+        .bind(updated_by)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_exempt(&self, room: &str, exempt: bool, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, exempt, updated_by, updated_at) VALUES (?, ?, ?, UTC_TIMESTAMP(6))
+             ON DUPLICATE KEY UPDATE exempt = VALUES(exempt), updated_by = VALUES(updated_by),
+                 updated_at = VALUES(updated_at)",
+        )
+        .bind(room)
+        .bind(exempt)
+        .bind(updated_by)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // 방마다 기간이 달라 기준 시각은 SQL 안에서 계산한다
+    async fn sweep(&self, default_days: i32, default_action: &str, limit: i64) -> RepoResult<RetentionBatch> {
+        let mut tx = self.db.begin().await?;
+        let expired = sqlx::query_as::<_, (i64, bool)>(
+            "SELECT m.id, COALESCE(r.action, ?) = 'archive'
+             FROM messages m LEFT JOIN room_retention r ON r.room = m.room
+             WHERE m.room IS NOT NULL AND NOT COALESCE(r.exempt, FALSE) AND COALESCE(r.days, ?) > 0
+               AND m.created_at < UTC_TIMESTAMP(6) - INTERVAL COALESCE(r.days, ?) DAY
+             ORDER BY m.id LIMIT ? FOR UPDATE",
+        )
+        .bind(default_action)
+        .bind(default_days)
+        .bind(default_days)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if expired.is_empty() {
+            return Ok(RetentionBatch { deleted: 0, archived: 0, attachment_keys: Vec::new() });
+        }
+        let ids: Vec<i64> = expired.iter().map(|(id, _)| *id).collect();
+        let to_archive: Vec<i64> = expired.iter().filter(|(_, archive)| *archive).map(|(id, _)| *id).collect();
+        let in_ids = placeholders(ids.len());
+
+        if !to_archive.is_empty() {
+            let sql = format!(
+                "INSERT IGNORE INTO archived_messages (id, room, created_at, message)
+                 SELECT id, room, created_at, {} FROM messages WHERE id IN ({})",
+                MESSAGE_JSON,
+                placeholders(to_archive.len())
+            );
+            let mut query = sqlx::query(&sql);
+            for id in &to_archive {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let sql = format!(
+            "SELECT storage_key FROM attachments WHERE message_id IN ({0})
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id IN ({0}) AND thumbnail_key IS NOT NULL",
+            in_ids
+        );
+        let mut query = sqlx::query_scalar::<_, String>(&sql);
+        for id in ids.iter().chain(&ids) {
+            query = query.bind(id);
+        }
+        let keys = query.fetch_all(&mut *tx).await?;
+        for table in ["notifications WHERE message_id", "messages WHERE id"] {
+            let sql = format!("DELETE FROM {} IN ({})", table, in_ids);
+            let mut query = sqlx::query(&sql);
+            for id in &ids {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(RetentionBatch {
+            deleted: ids.len() - to_archive.len(),
+            archived: to_archive.len(),
+            attachment_keys: keys,
+        })
+    }
+}
@@ -0,0 +1,96 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{RepoResult, RoomOwner, RoomRepo, RoomTopic};
+
+#[async_trait]
+impl RoomRepo for MySqlRepo {
+    async fn topic(&self, room: &str) -> RepoResult<Option<RoomTopic>> {
+        let topic = sqlx::query_as::<_, (String, Option<String>, DateTime<Utc>)>(
+            "SELECT t.topic, u.username, t.set_at FROM room_topics t LEFT JOIN users u ON u.id = t.set_by
+             WHERE t.room = ?",
+        )
+        .bind(room)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(topic.map(|(topic, set_by, set_at)| RoomTopic { topic, set_by, set_at }))
+    }
+
+    async fn set_topic(&self, room: &str, topic: &str, set_by: i32) -> RepoResult<DateTime<Utc>> {
+        let set_at = Utc::now();
+        sqlx::query(
+            "INSERT INTO room_topics (room, topic, set_by, set_at) VALUES (?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE topic = VALUES(topic), set_by = VALUES(set_by), set_at = VALUES(set_at)",
+        )
+        .bind(room)
+        .bind(topic)
+        .bind(set_by)
+        .bind(set_at)
+        .execute(&self.db)
+        .await?;
+        Ok(set_at)
+    }
+
+    async fn owners(&self, room: &str) -> RepoResult<Vec<RoomOwner>> {
+        let owners = sqlx::query_as::<_, (i32, String, Option<i32>, DateTime<Utc>)>(
+            "SELECT o.user_id, u.username, o.added_by, o.created_at FROM room_owners o JOIN users u ON u.id = o.user_id
+             WHERE o.room = ? ORDER BY o.created_at",
+        )
+        .bind(room)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(owners
+            .into_iter()
+            .map(|(user_id, username, added_by, created_at)| RoomOwner { user_id, username, added_by, created_at })
+            .collect())
+    }
+
+    async fn is_owner(&self, room: &str, user_id: i32) -> RepoResult<bool> {
+        let owner = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM room_owners WHERE room = ? AND user_id = ?)",
+        )
+        .bind(room)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(owner)
+    }
+
+    async fn add_owner(&self, room: &str, user_id: i32, added_by: i32) -> RepoResult<bool> {
+        let added = sqlx::query("INSERT IGNORE INTO room_owners (room, user_id, added_by) VALUES (?, ?, ?)")
+            .bind(room)
+            .bind(user_id)
+            .bind(added_by)
+            .execute(&self.db)
+            .await?;
+        Ok(added.rows_affected() == 1)
+    }
+
+    async fn remove_owner(&self, room: &str, user_id: i32) -> RepoResult<bool> {
+        let removed = sqlx::query("DELETE FROM room_owners WHERE room = ? AND user_id = ?")
+            .bind(room)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(removed.rows_affected() == 1)
+    }
+
+    async fn purge(&self, room: &str) -> RepoResult<(u64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM attachments WHERE room = ?
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE room = ? AND thumbnail_key IS NOT NULL",
+        )
+        .bind(room)
+        .bind(room)
+        .fetch_all(&mut *tx)
+        .await?;
+        let messages =
+            sqlx::query("DELETE FROM messages WHERE room = ?").bind(room).execute(&mut *tx).await?.rows_affected();
+        sqlx::query("DELETE FROM polls WHERE room = ?").bind(room).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok((messages, keys))
+    }
+}
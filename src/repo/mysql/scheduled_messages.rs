@@ -0,0 +1,97 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, MySqlRepo};
+use crate::repo::{DueMessage, RepoResult, ScheduledMessage, ScheduledMessageRepo};
+
+#[async_trait]
+impl ScheduledMessageRepo for MySqlRepo {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        content: &str,
+        format: &str,
+        send_at: DateTime<Utc>,
+    ) -> RepoResult<ScheduledMessage> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO scheduled_messages (user_id, room, content, format, send_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(content)
+        .bind(format)
+        .bind(send_at)
+        .execute(&mut *tx)
+        .await?;
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "SELECT id, room, content AS text, format, send_at, created_at FROM scheduled_messages WHERE id = ?",
+        )
+        .bind(inserted.last_insert_id() as i64)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(scheduled)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM scheduled_messages WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ScheduledMessage>> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "SELECT id, room, content AS text, format, send_at, created_at FROM scheduled_messages
+             WHERE user_id = ? ORDER BY send_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(scheduled)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM scheduled_messages WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    // 보낼 메시지를 보낸 사람의 정보와 함께 잠가 읽은 뒤 같은 트랜잭션에서 지운다
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueMessage>> {
+        let mut tx = self.db.begin().await?;
+        let due = sqlx::query_as::<_, DueMessage>(
+            "SELECT m.id, m.user_id, u.username, u.is_bot, u.disabled_at IS NOT NULL AS disabled,
+                    m.room, m.content, m.format
+             FROM scheduled_messages m JOIN users u ON u.id = m.user_id
+             WHERE m.send_at <= UTC_TIMESTAMP(6) ORDER BY m.send_at LIMIT ? FOR UPDATE",
+        )
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if due.is_empty() {
+            return Ok(due);
+        }
+        let sql = format!("DELETE FROM scheduled_messages WHERE id IN ({})", placeholders(due.len()));
+        let mut query = sqlx::query(&sql);
+        for message in &due {
+            query = query.bind(message.id);
+        }
+        query.execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(due)
+    }
+
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT MIN(send_at) FROM scheduled_messages")
+            .fetch_one(&self.db)
+            .await?;
+        Ok(next)
+    }
+}
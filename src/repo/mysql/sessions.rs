@@ -0,0 +1,129 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{RefreshedSession, RepoResult, SessionInfo, SessionRepo};
+
+#[async_trait]
+impl SessionRepo for MySqlRepo {
+    async fn create(&self, id: &str, user_id: i32, user_agent: Option<&str>, ip: Option<&str>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO sessions (id, user_id, user_agent, ip) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .bind(user_agent)
+            .bind(ip)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_refresh_token(
+        &self,
+        user_id: i32,
+        session_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(session_id)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    // 토큰 행을 잠그고 세션과 계정을 함께 확인한 뒤 쓴 것으로 표시한다
+    async fn use_refresh_token(&self, token_hash: &str) -> RepoResult<Option<RefreshedSession>> {
+        let mut tx = self.db.begin().await?;
+        let rotated = sqlx::query_as::<_, (i32, String, String, bool)>(
+            "SELECT r.user_id, r.family_id, u.username, u.is_guest
+             FROM refresh_tokens r
+             JOIN sessions s ON s.id = r.family_id AND s.revoked_at IS NULL
+             JOIN users u ON u.id = r.user_id AND u.disabled_at IS NULL
+             WHERE r.token_hash = ? AND r.used_at IS NULL AND r.revoked_at IS NULL AND r.expires_at > UTC_TIMESTAMP(6)
+             FOR UPDATE",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, session_id, username, is_guest)) = rotated else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE refresh_tokens SET used_at = UTC_TIMESTAMP(6) WHERE token_hash = ?")
+            .bind(token_hash)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE sessions SET last_seen = UTC_TIMESTAMP(6) WHERE id = ?")
+            .bind(&session_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(Some(RefreshedSession { user_id, username, session_id, is_guest }))
+    }
+
+    async fn refresh_token_session(&self, token_hash: &str, used_only: bool) -> RepoResult<Option<String>> {
+        let session_id = sqlx::query_scalar::<_, String>(
+            "SELECT family_id FROM refresh_tokens WHERE token_hash = ? AND (NOT ? OR used_at IS NOT NULL)",
+        )
+        .bind(token_hash)
+        .bind(used_only)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(session_id)
+    }
+
+    async fn revoke(&self, id: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE sessions SET revoked_at = UTC_TIMESTAMP(6) WHERE id = ? AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        sqlx::query("UPDATE refresh_tokens SET revoked_at = UTC_TIMESTAMP(6) WHERE family_id = ? AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn active_ids(&self, user_id: i32, except: Option<&str>) -> RepoResult<Vec<String>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM sessions WHERE user_id = ? AND revoked_at IS NULL AND (? IS NULL OR id <> ?)",
+        )
+        .bind(user_id)
+        .bind(except)
+        .bind(except)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn list(&self, user_id: i32, seen_after: DateTime<Utc>) -> RepoResult<Vec<SessionInfo>> {
+        let sessions = sqlx::query_as::<_, SessionInfo>(
+            "SELECT id, user_agent, ip, created_at, last_seen FROM sessions
+             WHERE user_id = ? AND revoked_at IS NULL AND last_seen > ?
+             ORDER BY last_seen DESC",
+        )
+        .bind(user_id)
+        .bind(seen_after)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(sessions)
+    }
+
+    async fn is_active(&self, id: &str, user_id: i32) -> RepoResult<bool> {
+        let active = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = ? AND user_id = ? AND revoked_at IS NULL)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(active)
+    }
+
+    async fn touch(&self, id: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE sessions SET last_seen = UTC_TIMESTAMP(6) WHERE id = ?").bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+}
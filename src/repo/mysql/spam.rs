@@ -0,0 +1,30 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{RepoResult, SpamFlag, SpamRepo};
+
+#[async_trait]
+impl SpamRepo for MySqlRepo {
+    async fn record_flag(&self, user_id: i32, room: &str, reason: &str, sample: &str) -> RepoResult<()> {
+        sqlx::query("INSERT INTO spam_flags (user_id, room, reason, sample) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(room)
+            .bind(reason)
+            .bind(sample)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_flags(&self, limit: i64) -> RepoResult<Vec<SpamFlag>> {
+        let flags = sqlx::query_as::<_, SpamFlag>(
+            "SELECT f.id, f.user_id, u.username, f.room, f.reason, f.sample, f.created_at
+             FROM spam_flags f JOIN users u ON u.id = f.user_id
+             ORDER BY f.created_at DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(flags)
+    }
+}
@@ -0,0 +1,41 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{RepoResult, StarRepo};
+
+#[async_trait]
+impl StarRepo for MySqlRepo {
+    async fn star(&self, user_id: i32, message_id: i64) -> RepoResult<()> {
+        // INSERT IGNORE 는 외래 키 오류까지 삼키므로 겹친 행만 그대로 둔다
+        sqlx::query("INSERT INTO message_stars (user_id, message_id) VALUES (?, ?) ON DUPLICATE KEY UPDATE id = id")
+            .bind(user_id)
+            .bind(message_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn unstar(&self, user_id: i32, message_id: i64) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM message_stars WHERE user_id = ? AND message_id = ?")
+            .bind(user_id)
+            .bind(message_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn list(&self, user_id: i32, before: Option<i64>, limit: i64) -> RepoResult<Vec<(i64, i64, DateTime<Utc>)>> {
+        let stars = sqlx::query_as::<_, (i64, i64, DateTime<Utc>)>(
+            "SELECT id, message_id, created_at FROM message_stars
+             WHERE user_id = ? AND (? IS NULL OR id < ?) ORDER BY id DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(before)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(stars)
+    }
+}
@@ -0,0 +1,74 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, seconds_from_now, MySqlRepo};
+use crate::repo::{RepoResult, ThrottleRepo};
+
+// 잠금 시각은 이 프로세스의 시계로 정하므로 비교도 같은 시계로 한다
+#[async_trait]
+impl ThrottleRepo for MySqlRepo {
+    async fn retry_after(&self, keys: &[String]) -> RepoResult<Option<f64>> {
+        if keys.is_empty() {
+            return Ok(None);
+        }
+        let sql = format!(
+            "SELECT MAX(locked_until) FROM auth_throttle WHERE locked_until > ? AND `key` IN ({})",
+            placeholders(keys.len())
+        );
+        let now = Utc::now();
+        let mut query = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(&sql).bind(now);
+        for key in keys {
+            query = query.bind(key);
+        }
+        let locked_until = query.fetch_one(&self.db).await?;
+        Ok(locked_until.map(|until| (until - now).num_milliseconds() as f64 / 1000.0))
+    }
+
+    // failures 를 last_failure_at 보다 먼저 고쳐야 창이 지났는지를 이전 실패 시각으로 판단한다
+    async fn record_failure(&self, key: &str, window: f64) -> RepoResult<i32> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO auth_throttle (`key`, failures, last_failure_at) VALUES (?, 1, ?)
+             ON DUPLICATE KEY UPDATE
+                 failures = CASE WHEN last_failure_at < ? THEN 1 ELSE failures + 1 END,
+                 last_failure_at = VALUES(last_failure_at)",
+        )
+        .bind(key)
+        .bind(Utc::now())
+        .bind(seconds_from_now(-window))
+        .execute(&mut *tx)
+        .await?;
+        let failures = sqlx::query_scalar::<_, i32>("SELECT failures FROM auth_throttle WHERE `key` = ?")
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(failures)
+    }
+
+    async fn lock(&self, key: &str, seconds: f64) -> RepoResult<()> {
+        sqlx::query("UPDATE auth_throttle SET locked_until = ? WHERE `key` = ?")
+            .bind(seconds_from_now(seconds))
+            .bind(key)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> RepoResult<()> {
+        sqlx::query("DELETE FROM auth_throttle WHERE `key` = ?").bind(key).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn purge(&self, window: f64) -> RepoResult<()> {
+        sqlx::query(
+            "DELETE FROM auth_throttle
+             WHERE last_failure_at < ? AND (locked_until IS NULL OR locked_until < ?)",
+        )
+        .bind(seconds_from_now(-window))
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
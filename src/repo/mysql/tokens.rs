@@ -0,0 +1,146 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::MySqlRepo;
+use crate::repo::{RepoResult, TokenRepo};
+
+#[async_trait]
+impl TokenRepo for MySqlRepo {
+    async fn add_password_reset(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    // 토큰 행을 잠그고 읽은 뒤 쓴 것으로 표시한다. 같은 토큰을 동시에 쓰면 하나만 통과한다.
+    async fn use_password_reset(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let mut tx = self.db.begin().await?;
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "SELECT user_id FROM password_reset_tokens
+             WHERE token_hash = ? AND used_at IS NULL AND expires_at > UTC_TIMESTAMP(6)
+             FOR UPDATE",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if user_id.is_some() {
+            sqlx::query("UPDATE password_reset_tokens SET used_at = UTC_TIMESTAMP(6) WHERE token_hash = ?")
+                .bind(token_hash)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+        Ok(user_id)
+    }
+
+    async fn expire_password_resets(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = UTC_TIMESTAMP(6) WHERE user_id = ? AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn add_email_verification(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO email_verification_tokens (user_id, email, token_hash, expires_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // 주소가 바뀌었어도 토큰은 쓴 것으로 남긴다
+    async fn use_email_verification(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let mut tx = self.db.begin().await?;
+        let used = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, email FROM email_verification_tokens
+             WHERE token_hash = ? AND used_at IS NULL AND expires_at > UTC_TIMESTAMP(6)
+             FOR UPDATE",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, email)) = used else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE email_verification_tokens SET used_at = UTC_TIMESTAMP(6) WHERE token_hash = ?")
+            .bind(token_hash)
+            .execute(&mut *tx)
+            .await?;
+        let verified = sqlx::query("UPDATE users SET email_verified_at = UTC_TIMESTAMP(6) WHERE id = ? AND email = ?")
+            .bind(user_id)
+            .bind(email)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok((verified.rows_affected() == 1).then_some(user_id))
+    }
+
+    async fn add_magic_link(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("INSERT INTO magic_links (user_id, email, token_hash, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(email)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> RepoResult<Option<(i32, String)>> {
+        let mut tx = self.db.begin().await?;
+        let used = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, email FROM magic_links
+             WHERE token_hash = ? AND used_at IS NULL AND expires_at > UTC_TIMESTAMP(6) AND user_id IS NOT NULL
+             FOR UPDATE",
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, email)) = used else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE magic_links SET used_at = UTC_TIMESTAMP(6) WHERE token_hash = ?")
+            .bind(token_hash)
+            .execute(&mut *tx)
+            .await?;
+        let user = sqlx::query_as::<_, (i32, String)>(
+            "SELECT id, username FROM users WHERE id = ? AND email_key = LOWER(?) FOR UPDATE",
+        )
+        .bind(user_id)
+        .bind(email)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if user.is_some() {
+            sqlx::query("UPDATE users SET email_verified_at = COALESCE(email_verified_at, UTC_TIMESTAMP(6)) WHERE id = ?")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(user)
+    }
+}
@@ -0,0 +1,155 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{duplicate_key, invites, MySqlRepo, USER_COLUMNS};
+use crate::{
+    auth::hash_token,
+    repo::{NewUser, RepoError, RepoResult, User, UserRepo, UserSummary},
+};
+
+#[async_trait]
+impl UserRepo for MySqlRepo {
+    async fn create(&self, user: &NewUser<'_>) -> RepoResult<User> {
+        let mut tx = self.db.begin().await?;
+        let invite_id = match user.invite_code {
+            Some(code) => {
+                Some(invites::redeem(&mut tx, &hash_token(code.trim())).await?.ok_or(RepoError::InvalidInvite)?)
+            }
+            None => None,
+        };
+        let inserted = sqlx::query("INSERT INTO users (username, password_hash, email, invite_id) VALUES (?, ?, ?, ?)")
+            .bind(user.username)
+            .bind(user.password_hash)
+            .bind(user.email)
+            .bind(invite_id)
+            .execute(&mut *tx)
+            .await;
+        let id = match inserted {
+            Ok(result) => result.last_insert_id() as i32,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                return if duplicate_key(&*e) == "users_email_key" {
+                    Err(RepoError::Conflict("email"))
+                } else {
+                    Err(RepoError::Conflict("username"))
+                };
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let created = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn find(&self, id: i32) -> RepoResult<Option<User>> {
+        let row = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(row)
+    }
+
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<User>> {
+        let row =
+            sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE username_key = LOWER(?)", USER_COLUMNS))
+                .bind(username)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(row)
+    }
+
+    async fn set_password_hash(&self, id: i32, hash: &str, expected: Option<&str>) -> RepoResult<bool> {
+        let updated =
+            sqlx::query("UPDATE users SET password_hash = ? WHERE id = ? AND (? IS NULL OR password_hash = ?)")
+                .bind(hash)
+                .bind(id)
+                .bind(expected)
+                .bind(expected)
+                .execute(&self.db)
+                .await?;
+        Ok(updated.rows_affected() == 1)
+    }
+
+    async fn touch_last_online(&self, id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET last_online_at = UTC_TIMESTAMP(6) WHERE id = ?").bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn mute(&self, id: i32, until: DateTime<Utc>) -> RepoResult<Option<User>> {
+        let mut tx = self.db.begin().await?;
+        let updated = sqlx::query("UPDATE users SET muted_until = GREATEST(COALESCE(muted_until, ?), ?) WHERE id = ?")
+            .bind(until)
+            .bind(until)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let row = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    async fn set_shadow_banned(&self, id: i32, shadow_banned: bool) -> RepoResult<Option<User>> {
+        let mut tx = self.db.begin().await?;
+        let updated = sqlx::query("UPDATE users SET shadow_banned = ? WHERE id = ?")
+            .bind(shadow_banned)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let row = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    // utf8mb4_bin 은 대소문자를 구분하므로 양쪽을 소문자로 바꿔 비교한다. LIKE 의 기본 이스케이프 문자는 \ 다.
+    async fn search(&self, pattern: Option<&str>, limit: i64, offset: i64) -> RepoResult<Vec<UserSummary>> {
+        let users = sqlx::query_as::<_, UserSummary>(
+            "SELECT u.id, u.username, u.email, u.is_admin, u.is_guest, u.disabled_at, u.shadow_banned,
+                    CASE WHEN u.muted_until > UTC_TIMESTAMP(6) THEN u.muted_until END AS muted_until, u.created_at,
+                    (SELECT MAX(s.last_seen) FROM sessions s WHERE s.user_id = u.id) AS last_seen
+             FROM users u
+             WHERE ? IS NULL OR LOWER(u.username) LIKE LOWER(?) OR LOWER(u.email) LIKE LOWER(?)
+             ORDER BY u.id LIMIT ? OFFSET ?",
+        )
+        .bind(pattern)
+        .bind(pattern)
+        .bind(pattern)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(users)
+    }
+
+    async fn set_disabled(&self, id: i32, disabled: bool) -> RepoResult<Option<(String, Option<DateTime<Utc>>)>> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "UPDATE users SET disabled_at = CASE WHEN ? THEN COALESCE(disabled_at, UTC_TIMESTAMP(6)) END WHERE id = ?",
+        )
+        .bind(disabled)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        let updated = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+            "SELECT username, disabled_at FROM users WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+}
@@ -0,0 +1,84 @@
+use axum::async_trait;
+
+use super::{messages, MySqlRepo};
+use crate::repo::{RepoResult, WebhookInfo, WebhookRepo};
+
+const INFO_COLUMNS: &str = "id, room, name, created_by, created_at, last_used_at, revoked_at";
+
+#[async_trait]
+impl WebhookRepo for MySqlRepo {
+    async fn create(&self, room: &str, name: &str, token_hash: &str, created_by: i32) -> RepoResult<WebhookInfo> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO webhooks (room, name, token_hash, created_by) VALUES (?, ?, ?, ?)")
+            .bind(room)
+            .bind(name)
+            .bind(token_hash)
+            .bind(created_by)
+            .execute(&mut *tx)
+            .await?;
+        let webhook = sqlx::query_as::<_, WebhookInfo>(&format!("SELECT {} FROM webhooks WHERE id = ?", INFO_COLUMNS))
+            .bind(inserted.last_insert_id() as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(webhook)
+    }
+
+    async fn list(&self, room: Option<&str>) -> RepoResult<Vec<WebhookInfo>> {
+        let webhooks = sqlx::query_as::<_, WebhookInfo>(&format!(
+            "SELECT {} FROM webhooks WHERE ? IS NULL OR room = ? ORDER BY id",
+            INFO_COLUMNS
+        ))
+        .bind(room)
+        .bind(room)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(webhooks)
+    }
+
+    async fn revoke(&self, id: i64, room: Option<&str>) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE webhooks SET revoked_at = UTC_TIMESTAMP(6)
+             WHERE id = ? AND (? IS NULL OR room = ?) AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(room)
+        .bind(room)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn use_token(&self, token_hash: &str) -> RepoResult<Option<(i64, String, String)>> {
+        let webhook = sqlx::query_as::<_, (i64, String, String)>(
+            "SELECT id, room, name FROM webhooks WHERE token_hash = ? AND revoked_at IS NULL",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some((id, _, _)) = &webhook {
+            sqlx::query("UPDATE webhooks SET last_used_at = UTC_TIMESTAMP(6) WHERE id = ?")
+                .bind(id)
+                .execute(&self.db)
+                .await?;
+        }
+        Ok(webhook)
+    }
+
+    async fn post_message(&self, webhook_id: i64, name: &str, room: &str, text: &str) -> RepoResult<i64> {
+        let mut tx = self.db.begin().await?;
+        let id = messages::next_id(&mut tx).await?;
+        sqlx::query(
+            "INSERT INTO messages (id, user_id, username, room, content, webhook_id) VALUES (?, NULL, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(name)
+        .bind(room)
+        .bind(text)
+        .bind(webhook_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+}
@@ -0,0 +1,62 @@
+use axum::async_trait;
+
+use super::MySqlRepo;
+use crate::repo::{RepoError, RepoResult, WordFilterRepo, WordFilterRule};
+
+const RULE_COLUMNS: &str = "id, room, pattern, action, created_by, created_at";
+
+#[async_trait]
+impl WordFilterRepo for MySqlRepo {
+    async fn all(&self) -> RepoResult<Vec<WordFilterRule>> {
+        let rules = sqlx::query_as::<_, WordFilterRule>(&format!("SELECT {} FROM word_filters ORDER BY id", RULE_COLUMNS))
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rules)
+    }
+
+    async fn create(
+        &self,
+        room: Option<&str>,
+        pattern: &str,
+        action: &str,
+        created_by: i32,
+    ) -> RepoResult<WordFilterRule> {
+        let mut tx = self.db.begin().await?;
+        let inserted = sqlx::query("INSERT INTO word_filters (room, pattern, action, created_by) VALUES (?, ?, ?, ?)")
+            .bind(room)
+            .bind(pattern)
+            .bind(action)
+            .bind(created_by)
+            .execute(&mut *tx)
+            .await;
+        let id = match inserted {
+            Ok(result) => result.last_insert_id() as i64,
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                return Err(RepoError::Conflict("word_filter"))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let rule = sqlx::query_as::<_, WordFilterRule>(&format!("SELECT {} FROM word_filters WHERE id = ?", RULE_COLUMNS))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(rule)
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<WordFilterRule>> {
+        let mut tx = self.db.begin().await?;
+        let rule = sqlx::query_as::<_, WordFilterRule>(&format!(
+            "SELECT {} FROM word_filters WHERE id = ? FOR UPDATE",
+            RULE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if rule.is_some() {
+            sqlx::query("DELETE FROM word_filters WHERE id = ?").bind(id).execute(&mut *tx).await?;
+            tx.commit().await?;
+        }
+        Ok(rule)
+    }
+}
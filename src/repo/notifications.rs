@@ -0,0 +1,97 @@
+// --- 알림, 방별 알림 설정, 메일 요약 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Notification {
+    pub id: i64,
+    // "mention", "keyword", "message", "friend_request", "friend_accepted", "moderation", "reminder"
+    pub kind: String,
+    pub room: Option<String>,
+    pub message_id: Option<i64>,
+    pub actor_id: Option<i32>,
+    // 알림을 만든 사용자의 이름. 중재 안내처럼 없으면 null.
+    pub actor: Option<String>,
+    pub text: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 새로 저장할 알림
+pub struct NewNotification<'a> {
+    pub user_id: i32,
+    pub kind: &'a str,
+    pub room: Option<&'a str>,
+    pub message_id: Option<i64>,
+    pub actor_id: Option<i32>,
+    pub text: &'a str,
+}
+
+// 메일 요약에 넣을 언급
+#[derive(Debug, Clone, FromRow)]
+pub struct DigestMention {
+    pub user_id: i32,
+    pub email: String,
+    pub room: Option<String>,
+    pub actor: Option<String>,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait NotificationRepo: Send + Sync {
+    // 새 알림의 (id, 만든 시각)
+    async fn create(&self, notification: &NewNotification<'_>) -> RepoResult<(i64, DateTime<Utc>)>;
+
+    // 방 메시지로 알림을 받을 (사용자, 언급됐는지, 키워드가 맞았는지). 작성자와 비활성 사용자는 빼고,
+    // 방별 설정이 mute 면 언급도 받지 않으며 all 이면 언급이 없어도 받는다.
+    async fn message_recipients(
+        &self,
+        room: &str,
+        author_id: i32,
+        mentioned: &[String],
+        watchers: &[i32],
+    ) -> RepoResult<Vec<(i32, bool, bool)>>;
+
+    // created_before 전에 만든 알림을 지운다
+    async fn purge(&self, created_before: DateTime<Utc>) -> RepoResult<u64>;
+
+    // 최근 것부터. before 가 있으면 그 id 보다 오래된 것만.
+    async fn list(
+        &self,
+        user_id: i32,
+        unread_only: bool,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<Notification>>;
+
+    async fn unread_count(&self, user_id: i32) -> RepoResult<i64>;
+
+    // 사용자 본인의 알림이면 true (이미 읽었어도)
+    async fn mark_read(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+
+    // 새로 읽음 처리한 수
+    async fn mark_all_read(&self, user_id: i32) -> RepoResult<u64>;
+
+    // 정하지 않았으면 None (mentions 로 본다)
+    async fn room_level(&self, user_id: i32, room: &str) -> RepoResult<Option<String>>;
+
+    async fn set_room_level(&self, user_id: i32, room: &str, level: &str) -> RepoResult<()>;
+
+    // created_before 전에 생긴 읽지 않은 언급 중 아직 요약으로 보내지 않았고 그 뒤로 접속하지 않은 것.
+    // 요약을 켜고 주소를 확인한 활성 사용자만, 사용자 순서로.
+    async fn pending_digest(&self, created_before: DateTime<Utc>) -> RepoResult<Vec<DigestMention>>;
+
+    // sent_until 까지의 언급은 요약으로 보낸 것으로 기록한다
+    async fn mark_digest_sent(&self, user_id: i32, sent_until: DateTime<Utc>) -> RepoResult<()>;
+
+    async fn digest_enabled(&self, user_id: i32) -> RepoResult<Option<bool>>;
+
+    // 끄면 그동안 온 언급은 다시 켜도 보내지 않는다. 사용자가 없으면 false.
+    async fn set_digest(&self, user_id: i32, enabled: bool) -> RepoResult<bool>;
+}
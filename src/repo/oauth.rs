@@ -0,0 +1,35 @@
+// --- 소셜 로그인 계정 연결 ---
+
+use axum::async_trait;
+
+use super::RepoResult;
+
+// 처음 보는 외부 계정
+pub struct ExternalAccount<'a> {
+    pub provider: &'a str,
+    pub subject: &'a str,
+    // 제공자가 소유를 확인해 준 주소만 넘긴다
+    pub email: Option<&'a str>,
+    // 새 사용자를 만들 때 쓸 이름 (겹치면 숫자를 붙인다)
+    pub username_base: &'a str,
+    // false 면 새 사용자를 만들지 않는다 (초대 전용 가입)
+    pub allow_signup: bool,
+}
+
+#[derive(Debug)]
+pub enum OAuthLink {
+    // 연결된 사용자의 (id, 이름)
+    Linked(i32, String),
+    // 주소가 확인되지 않은 다른 계정이 이미 쓰고 있다 (가로채기 방지)
+    EmailInUse,
+    RegistrationClosed,
+}
+
+#[async_trait]
+pub trait OAuthRepo: Send + Sync {
+    // 이미 연결된 사용자의 (id, 이름)
+    async fn linked_user(&self, provider: &str, subject: &str) -> RepoResult<Option<(i32, String)>>;
+
+    // 확인된 같은 주소의 사용자에 연결하거나, 비밀번호 없는 사용자를 새로 만들어 연결한다
+    async fn link(&self, account: &ExternalAccount<'_>) -> RepoResult<OAuthLink>;
+}
@@ -0,0 +1,47 @@
+// --- 패스키 (WebAuthn 자격 증명과 진행 중인 절차) ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PasskeyInfo {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// 패스키와 절차 상태는 직렬화한 JSON 문자열로 주고받는다 (해석은 passkeys 모듈이 한다)
+#[async_trait]
+pub trait PasskeyRepo: Send + Sync {
+    // 만료된 절차를 정리하고 새로 저장한다
+    async fn save_ceremony(
+        &self,
+        token_hash: &str,
+        user_id: i32,
+        kind: &str,
+        state: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()>;
+
+    // 만료되지 않은 절차를 꺼내면서 지운다: (사용자 id, 상태)
+    async fn take_ceremony(&self, token_hash: &str, kind: &str) -> RepoResult<Option<(i32, String)>>;
+
+    // 사용자의 (id, 패스키)
+    async fn credentials(&self, user_id: i32) -> RepoResult<Vec<(i64, String)>>;
+
+    // 새 패스키의 id. 이미 등록된 자격 증명이면 Conflict("passkey")
+    async fn add(&self, user_id: i32, credential_id: &str, passkey: &str, name: &str) -> RepoResult<i64>;
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PasskeyInfo>>;
+
+    // 사용자 본인의 패스키를 지웠으면 true
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+
+    // 로그인에 쓰인 패스키의 사용 시각을 남기고, 인증기 상태가 바뀌었으면 함께 저장한다
+    async fn mark_used(&self, id: i64, passkey: Option<&str>) -> RepoResult<()>;
+}
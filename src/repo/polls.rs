@@ -0,0 +1,61 @@
+// --- 투표 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+// 저장된 투표. 집계는 polls 모듈이 표를 모아 따로 낸다.
+#[derive(Debug, Clone, FromRow)]
+pub struct PollRow {
+    pub id: i64,
+    pub room: String,
+    pub user_id: Option<i32>,
+    pub username: String,
+    pub question: String,
+    pub options: Vec<String>,
+    pub anonymous: bool,
+    pub hidden: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct NewPoll<'a> {
+    pub room: &'a str,
+    pub user_id: i32,
+    pub username: &'a str,
+    pub question: &'a str,
+    pub options: &'a [String],
+    pub anonymous: bool,
+    pub hidden: bool,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait PollRepo: Send + Sync {
+    async fn find(&self, poll_id: i64) -> RepoResult<Option<PollRow>>;
+
+    // 방의 최근 투표, 새것부터. 숨겨진 투표는 viewer 가 올린 것만. open_only 면 닫힌 투표는 뺀다.
+    async fn recent(&self, room: &str, viewer: i32, open_only: bool, limit: i64) -> RepoResult<Vec<PollRow>>;
+
+    // 표를 던진 순서대로 (선택지, 사용자 id, 이름). 그림자 차단된 사용자의 표는 viewer 본인 것만 넣는다.
+    async fn votes(&self, poll_id: i64, viewer: Option<i32>) -> RepoResult<Vec<(i16, i32, String)>>;
+
+    // 표를 던지거나 바꾼다
+    async fn vote(&self, poll_id: i64, user_id: i32, option: i16) -> RepoResult<()>;
+
+    async fn unvote(&self, poll_id: i64, user_id: i32) -> RepoResult<()>;
+
+    async fn create(&self, poll: NewPoll<'_>) -> RepoResult<PollRow>;
+
+    // 아직 열려 있던 투표를 닫았으면 Some
+    async fn close(&self, poll_id: i64) -> RepoResult<Option<PollRow>>;
+
+    // 마감 시각이 지난 투표를 닫는다
+    async fn close_due(&self) -> RepoResult<Vec<PollRow>>;
+
+    // 열린 투표 중 가장 이른 마감 시각
+    async fn next_close(&self) -> RepoResult<Option<DateTime<Utc>>>;
+}
@@ -0,0 +1,179 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{invites, PgRepo};
+use crate::{
+    auth::hash_token,
+    repo::{AccountRepo, GuestUpgrade, RepoError, RepoResult},
+};
+
+#[async_trait]
+impl AccountRepo for PgRepo {
+    async fn find_by_email(&self, email: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>("SELECT id, email FROM users WHERE lower(email) = lower($1)")
+            .bind(email)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(user)
+    }
+
+    async fn set_email(&self, user_id: i32, email: &str) -> RepoResult<()> {
+        let updated = sqlx::query("UPDATE users SET email = $1, email_verified_at = NULL WHERE id = $2")
+            .bind(email)
+            .bind(user_id)
+            .execute(&self.db)
+            .await;
+        match updated {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("email")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn email_status(&self, user_id: i32) -> RepoResult<Option<(Option<String>, bool)>> {
+        let row = sqlx::query_as::<_, (Option<String>, bool)>(
+            "SELECT email, email_verified_at IS NOT NULL FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn is_verified(&self, user_id: i32) -> RepoResult<bool> {
+        let verified = sqlx::query_scalar::<_, bool>(
+            "SELECT email_verified_at IS NOT NULL OR is_bot FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(verified.unwrap_or(false))
+    }
+
+    async fn create_guest(&self, username: &str) -> RepoResult<Option<i32>> {
+        let id = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO users (username, is_guest) VALUES ($1, true)
+             ON CONFLICT ((lower(username))) DO NOTHING RETURNING id",
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    async fn upgrade_guest(&self, upgrade: &GuestUpgrade<'_>) -> RepoResult<bool> {
+        let mut tx = self.db.begin().await?;
+        let invite_id = match upgrade.invite_code {
+            Some(code) => {
+                Some(invites::redeem(&mut tx, &hash_token(code.trim())).await?.ok_or(RepoError::InvalidInvite)?)
+            }
+            None => None,
+        };
+        let upgraded = sqlx::query(
+            "UPDATE users SET username = $2, password_hash = $3, email = $4, invite_id = $5, is_guest = false
+             WHERE id = $1 AND is_guest",
+        )
+        .bind(upgrade.user_id)
+        .bind(upgrade.username)
+        .bind(upgrade.password_hash)
+        .bind(upgrade.email)
+        .bind(invite_id)
+        .execute(&mut *tx)
+        .await;
+        match upgraded {
+            Ok(r) => {
+                tx.commit().await?;
+                Ok(r.rows_affected() == 1)
+            }
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                if e.constraint().is_some_and(|c| c.contains("email")) {
+                    Err(RepoError::Conflict("email"))
+                } else {
+                    Err(RepoError::Conflict("username"))
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn purge_guests(&self, created_before: DateTime<Utc>) -> RepoResult<u64> {
+        let purged = sqlx::query("DELETE FROM users WHERE is_guest AND created_at < $1")
+            .bind(created_before)
+            .execute(&self.db)
+            .await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn create_admin(&self, username: &str, password_hash: &str, email: Option<&str>) -> RepoResult<i32> {
+        let created = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO users (username, password_hash, email, email_verified_at, is_admin)
+             VALUES ($1, $2, $3, CASE WHEN $3::TEXT IS NULL THEN NULL ELSE now() END, true)
+             RETURNING id",
+        )
+        .bind(username)
+        .bind(password_hash)
+        .bind(email)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(id) => Ok(id),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                if e.constraint().is_some_and(|c| c.contains("email")) {
+                    Err(RepoError::Conflict("email"))
+                } else {
+                    Err(RepoError::Conflict("username"))
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn grant_admin(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET is_admin = true WHERE id = $1").bind(user_id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn bot_ids(&self, owner_id: i32) -> RepoResult<Vec<i32>> {
+        let ids = sqlx::query_scalar::<_, i32>("SELECT id FROM users WHERE bot_owner_id = $1")
+            .bind(owner_id)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(ids)
+    }
+
+    async fn delete_account(&self, user_id: i32, anonymize_as: Option<&str>) -> RepoResult<(u64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        let (messages, attachment_keys) = match anonymize_as {
+            Some(name) => {
+                let updated = sqlx::query("UPDATE messages SET user_id = NULL, username = $2 WHERE user_id = $1")
+                    .bind(user_id)
+                    .bind(name)
+                    .execute(&mut *tx)
+                    .await?;
+                (updated.rows_affected(), Vec::new())
+            }
+            None => {
+                // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 저장소에서 지울 키를 먼저 모은다
+                let keys = sqlx::query_scalar::<_, String>(
+                    "SELECT storage_key FROM attachments WHERE user_id = $1
+                     UNION ALL SELECT thumbnail_key FROM attachments WHERE user_id = $1 AND thumbnail_key IS NOT NULL",
+                )
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+                let deleted = sqlx::query("DELETE FROM messages WHERE user_id = $1")
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                (deleted.rows_affected(), keys)
+            }
+        };
+        // 세션, 토큰, 패스키 등은 외래 키로 함께 지워진다
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok((messages, attachment_keys))
+    }
+}
@@ -0,0 +1,244 @@
+use axum::async_trait;
+use sqlx::{types::Json as SqlJson, PgConnection};
+
+use super::PgRepo;
+use crate::repo::{AnonymizationJob, AnonymizationRepo, RepoResult, Touched};
+
+const JOB_COLUMNS: &str = "id, status, touched, error, created_by, created_at, completed_at";
+
+// 메시지를 지운 것처럼 비운다. 첨부 파일 기록을 지우고 저장소에서 지울 키를 돌려준다.
+async fn scrub_messages(conn: &mut PgConnection, ids: &[i64], touched: &mut Touched) -> sqlx::Result<Vec<String>> {
+    let keys = sqlx::query_scalar::<_, String>(
+        "SELECT storage_key FROM attachments WHERE message_id = ANY($1)
+         UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id = ANY($1) AND thumbnail_key IS NOT NULL",
+    )
+    .bind(ids)
+    .fetch_all(&mut *conn)
+    .await?;
+    touched.attachments += sqlx::query("DELETE FROM attachments WHERE message_id = ANY($1)")
+        .bind(ids)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    touched.reports += sqlx::query(
+        "UPDATE message_reports SET content = NULL WHERE message_id = ANY($1) AND content IS NOT NULL",
+    )
+    .bind(ids)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+    Ok(keys)
+}
+
+#[async_trait]
+impl AnonymizationRepo for PgRepo {
+    async fn create(&self, created_by: i32) -> RepoResult<Option<AnonymizationJob>> {
+        let job = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "INSERT INTO anonymization_jobs (status, created_by) VALUES ('running', $1)
+             ON CONFLICT ((true)) WHERE status = 'running' DO NOTHING RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(created_by)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(job)
+    }
+
+    async fn list(&self, limit: i64) -> RepoResult<Vec<AnonymizationJob>> {
+        let jobs = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "SELECT {} FROM anonymization_jobs ORDER BY id DESC LIMIT $1",
+            JOB_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(jobs)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<AnonymizationJob>> {
+        let job =
+            sqlx::query_as::<_, AnonymizationJob>(&format!("SELECT {} FROM anonymization_jobs WHERE id = $1", JOB_COLUMNS))
+                .bind(id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(job)
+    }
+
+    async fn running(&self) -> RepoResult<Option<(i64, Touched)>> {
+        let running = sqlx::query_as::<_, (i64, SqlJson<Touched>)>(
+            "SELECT id, touched FROM anonymization_jobs WHERE status = 'running'",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(running.map(|(id, touched)| (id, touched.0)))
+    }
+
+    async fn save_progress(&self, id: i64, touched: &Touched) -> RepoResult<()> {
+        sqlx::query("UPDATE anonymization_jobs SET touched = $2 WHERE id = $1")
+            .bind(id)
+            .bind(SqlJson(touched))
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn finish(&self, id: i64, status: &str, touched: &Touched, error: Option<&str>) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE anonymization_jobs SET status = $2, touched = $3, error = $4, completed_at = now() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(SqlJson(touched))
+        .bind(error)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_accounts(&self) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM deleted_accounts").fetch_one(&self.db).await?;
+        Ok(count)
+    }
+
+    async fn deleted_accounts(&self, limit: i64) -> RepoResult<Vec<(i32, String)>> {
+        let accounts = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, username FROM deleted_accounts ORDER BY user_id LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(accounts)
+    }
+
+    async fn scrub_accounts(
+        &self,
+        ids: &[i32],
+        names: &[String],
+        deleted_name: &str,
+        touched: &mut Touched,
+    ) -> RepoResult<Vec<String>> {
+        let mut tx = self.db.begin().await?;
+        let messages = sqlx::query_scalar::<_, i64>(
+            "UPDATE messages SET user_id = NULL, username = $2, content = NULL, html = NULL
+             WHERE user_id = ANY($1) RETURNING id",
+        )
+        .bind(ids)
+        .bind(deleted_name)
+        .fetch_all(&mut *tx)
+        .await?;
+        touched.messages += messages.len() as u64;
+        let mut keys = scrub_messages(&mut tx, &messages, touched).await?;
+
+        // 전달한 메시지는 원래 작성자의 글을 그대로 옮긴 것이므로 내용도 지운다
+        let forwards = sqlx::query_scalar::<_, i64>(
+            "UPDATE messages SET content = NULL, html = NULL,
+                 forwarded = forwarded || jsonb_build_object('user_id', NULL, 'username', $2::TEXT)
+             WHERE (forwarded->>'user_id')::INTEGER = ANY($1) RETURNING id",
+        )
+        .bind(ids)
+        .bind(deleted_name)
+        .fetch_all(&mut *tx)
+        .await?;
+        touched.forwards += forwards.len() as u64;
+        keys.extend(scrub_messages(&mut tx, &forwards, touched).await?);
+
+        // 투표의 user_id 는 계정을 지울 때 이미 NULL 이 되었다
+        touched.polls += sqlx::query("UPDATE polls SET username = $2 WHERE user_id IS NULL AND username = ANY($1)")
+            .bind(names)
+            .bind(deleted_name)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        touched.archived += sqlx::query(
+            "UPDATE archived_messages
+             SET message = message
+                 || jsonb_build_object('user_id', NULL, 'username', $2::TEXT, 'content', NULL, 'html', NULL)
+             WHERE (message->>'user_id')::INTEGER = ANY($1)",
+        )
+        .bind(ids)
+        .bind(deleted_name)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(keys)
+    }
+
+    async fn forget_accounts(&self, ids: &[i32]) -> RepoResult<()> {
+        sqlx::query("DELETE FROM deleted_accounts WHERE user_id = ANY($1)").bind(ids).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn scrub_anonymized(
+        &self,
+        deleted_name: &str,
+        limit: i64,
+        touched: &mut Touched,
+    ) -> RepoResult<(i64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        let messages = sqlx::query_scalar::<_, i64>(
+            "UPDATE messages SET content = NULL, html = NULL WHERE id IN (
+                 SELECT id FROM messages
+                 WHERE user_id IS NULL AND username = $1 AND webhook_id IS NULL AND content IS NOT NULL
+                 ORDER BY id LIMIT $2 FOR UPDATE SKIP LOCKED
+             ) RETURNING id",
+        )
+        .bind(deleted_name)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        touched.messages += messages.len() as u64;
+        let keys = scrub_messages(&mut tx, &messages, touched).await?;
+        tx.commit().await?;
+        Ok((messages.len() as i64, keys))
+    }
+
+    async fn mentioning_messages(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String, String)>(
+            "SELECT id, content, format FROM messages WHERE content ILIKE ANY($1) AND id > $2 ORDER BY id LIMIT $3",
+        )
+        .bind(patterns)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn rewrite_message(&self, id: i64, content: &str, html: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE messages SET content = $2, html = $3 WHERE id = $1")
+            .bind(id)
+            .bind(content)
+            .bind(html)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn mentioning_notifications(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, text FROM notifications WHERE text ILIKE ANY($1) AND id > $2 ORDER BY id LIMIT $3",
+        )
+        .bind(patterns)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn rewrite_notification(&self, id: i64, text: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE notifications SET text = $2 WHERE id = $1").bind(id).bind(text).execute(&self.db).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{Bot, BotRepo, BotToken, BotTokenOwner, RepoError, RepoResult};
+
+#[async_trait]
+impl BotRepo for PgRepo {
+    async fn authenticate(&self, token_hash: &str) -> RepoResult<Option<BotTokenOwner>> {
+        let owner = sqlx::query_as::<_, (i64, i32, String, Vec<String>, Option<DateTime<Utc>>)>(
+            "UPDATE bot_tokens t SET last_used_at = now()
+             FROM users u
+             WHERE t.token_hash = $1 AND u.id = t.bot_id AND u.is_bot AND u.disabled_at IS NULL
+               AND t.revoked_at IS NULL AND (t.expires_at IS NULL OR t.expires_at > now())
+             RETURNING t.id, u.id, u.username, t.scopes, t.expires_at",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(owner.map(|(token_id, bot_id, username, scopes, expires_at)| BotTokenOwner {
+            token_id,
+            bot_id,
+            username,
+            scopes,
+            expires_at,
+        }))
+    }
+
+    async fn is_owner(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let owned = sqlx::query_scalar::<_, bool>("SELECT bot_owner_id = $2 FROM users WHERE id = $1 AND is_bot")
+            .bind(bot_id)
+            .bind(owner_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(owned == Some(true))
+    }
+
+    async fn list(&self, owner_id: i32) -> RepoResult<Vec<Bot>> {
+        let bots = sqlx::query_as::<_, Bot>(
+            "SELECT id, username, created_at FROM users WHERE bot_owner_id = $1 AND is_bot ORDER BY id",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bots)
+    }
+
+    async fn count(&self, owner_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM users WHERE bot_owner_id = $1 AND is_bot")
+            .bind(owner_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, owner_id: i32, username: &str) -> RepoResult<Bot> {
+        let created = sqlx::query_as::<_, Bot>(
+            "INSERT INTO users (username, is_bot, bot_owner_id) VALUES ($1, true, $2)
+             RETURNING id, username, created_at",
+        )
+        .bind(username)
+        .bind(owner_id)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(bot) => Ok(bot),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("username")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM users WHERE id = $1 AND bot_owner_id = $2 AND is_bot")
+            .bind(bot_id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn tokens(&self, bot_id: i32) -> RepoResult<Vec<BotToken>> {
+        let tokens = sqlx::query_as::<_, BotToken>(
+            "SELECT id, name, scopes, created_at, last_used_at, expires_at, revoked_at FROM bot_tokens
+             WHERE bot_id = $1 ORDER BY id",
+        )
+        .bind(bot_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn active_token_count(&self, bot_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM bot_tokens WHERE bot_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(bot_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    async fn create_token(
+        &self,
+        bot_id: i32,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<BotToken> {
+        let token = sqlx::query_as::<_, BotToken>(
+            "INSERT INTO bot_tokens (bot_id, name, token_hash, scopes, expires_at) VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, name, scopes, created_at, last_used_at, expires_at, revoked_at",
+        )
+        .bind(bot_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(token)
+    }
+
+    async fn revoke_token(&self, token_id: i64, bot_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE bot_tokens SET revoked_at = now() WHERE id = $1 AND bot_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(token_id)
+        .bind(bot_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,24 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{HealthRepo, RepoResult};
+
+#[async_trait]
+impl HealthRepo for PgRepo {
+    async fn ping(&self) -> RepoResult<()> {
+        sqlx::query("SELECT 1").execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> RepoResult<Vec<i64>> {
+        let versions = sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+            .fetch_all(&self.db)
+            .await;
+        match versions {
+            Ok(versions) => Ok(versions),
+            // undefined_table
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("42P01") => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use std::net::IpAddr;
+
+use super::PgRepo;
+use crate::repo::{IpBan, IpBanRepo, RepoResult};
+
+#[async_trait]
+impl IpBanRepo for PgRepo {
+    async fn is_banned(&self, ip: IpAddr) -> RepoResult<bool> {
+        let banned = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM ip_bans
+                            WHERE $1::INET <<= network AND (expires_at IS NULL OR expires_at > now()))",
+        )
+        .bind(ip.to_string())
+        .fetch_one(&self.db)
+        .await?;
+        Ok(banned)
+    }
+
+    async fn list(&self) -> RepoResult<Vec<IpBan>> {
+        let bans = sqlx::query_as::<_, IpBan>(
+            "SELECT id, network::TEXT AS network, reason, created_by, expires_at, created_at FROM ip_bans
+             WHERE expires_at IS NULL OR expires_at > now() ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bans)
+    }
+
+    async fn create(
+        &self,
+        network: &str,
+        reason: Option<&str>,
+        created_by: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<IpBan> {
+        // network() 로 호스트 부분을 지워 "10.1.2.3/8" 도 "10.0.0.0/8" 로 저장한다
+        let ban = sqlx::query_as::<_, IpBan>(
+            "INSERT INTO ip_bans (network, reason, created_by, expires_at) VALUES (network($1::INET), $2, $3, $4)
+             RETURNING id, network::TEXT AS network, reason, created_by, expires_at, created_at",
+        )
+        .bind(network)
+        .bind(reason)
+        .bind(created_by)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(ban)
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<String>> {
+        let network = sqlx::query_scalar::<_, String>("DELETE FROM ip_bans WHERE id = $1 RETURNING network::TEXT")
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(network)
+    }
+}
@@ -0,0 +1,60 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{Keyword, KeywordRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl KeywordRepo for PgRepo {
+    async fn all(&self) -> RepoResult<Vec<Keyword>> {
+        let keywords = sqlx::query_as::<_, Keyword>(
+            "SELECT id, user_id, keyword, room, created_at FROM keyword_alerts ORDER BY id",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keywords)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Keyword>> {
+        let keywords = sqlx::query_as::<_, Keyword>(
+            "SELECT id, user_id, keyword, room, created_at FROM keyword_alerts WHERE user_id = $1 ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keywords)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM keyword_alerts WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, user_id: i32, keyword: &str, room: Option<&str>) -> RepoResult<Keyword> {
+        let created = sqlx::query_as::<_, Keyword>(
+            "INSERT INTO keyword_alerts (user_id, keyword, room) VALUES ($1, $2, $3)
+             RETURNING id, user_id, keyword, room, created_at",
+        )
+        .bind(user_id)
+        .bind(keyword)
+        .bind(room)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(keyword) => Ok(keyword),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("keyword")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM keyword_alerts WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,28 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{Maintenance, MaintenanceRepo, RepoResult};
+
+#[async_trait]
+impl MaintenanceRepo for PgRepo {
+    async fn get(&self) -> RepoResult<Maintenance> {
+        let maintenance =
+            sqlx::query_as::<_, Maintenance>("SELECT enabled, message, updated_by, updated_at FROM maintenance")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(maintenance)
+    }
+
+    async fn set(&self, enabled: bool, message: Option<&str>, updated_by: i32) -> RepoResult<Maintenance> {
+        let maintenance = sqlx::query_as::<_, Maintenance>(
+            "UPDATE maintenance SET enabled = $1, message = $2, updated_by = $3, updated_at = now()
+             RETURNING enabled, message, updated_by, updated_at",
+        )
+        .bind(enabled)
+        .bind(message)
+        .bind(updated_by)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(maintenance)
+    }
+}
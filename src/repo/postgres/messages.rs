@@ -0,0 +1,200 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::Instrument;
+
+use super::PgRepo;
+use crate::{
+    repo::{ExportRow, ExportedMessage, MessageRepo, MessageRow, NewMessage, RepoResult, StoredMessage},
+    telemetry,
+};
+
+const MESSAGE_COLUMNS: &str =
+    "id, room, user_id, username, content AS text, format, html, webhook_id, forwarded, created_at, expires_at";
+
+// viewer 가 볼 수 있는 메시지만 남기는 조건. viewer 는 $1 이어야 한다.
+const VISIBLE: &str = "(NOT hidden OR user_id = $1) AND (expires_at IS NULL OR expires_at > now())";
+
+impl PgRepo {
+    // viewer, room, 기준 id, limit 을 차례로 받는 목록 조회
+    async fn page(
+        &self,
+        sql: &str,
+        viewer: i32,
+        room: &str,
+        bound: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let rows = sqlx::query_as::<_, MessageRow>(sql)
+            .bind(viewer)
+            .bind(room)
+            .bind(bound)
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+}
+
+#[async_trait]
+impl MessageRepo for PgRepo {
+    async fn next_ids(&self, count: i64) -> RepoResult<Vec<i64>> {
+        let ids = sqlx::query_scalar::<_, i64>(
+            "SELECT nextval(pg_get_serial_sequence('messages', 'id')) FROM generate_series(1, $1)",
+        )
+        .bind(count)
+        .fetch_all(&self.db)
+        .instrument(telemetry::db_span("SELECT", "messages"))
+        .await?;
+        Ok(ids)
+    }
+
+    async fn insert_batch(&self, batch: &[NewMessage]) -> RepoResult<()> {
+        let forwarded: Vec<Option<String>> =
+            batch.iter().map(|m| m.forwarded.as_ref().and_then(|f| serde_json::to_string(f).ok())).collect();
+        sqlx::query(
+            "INSERT INTO messages (id, user_id, username, room, content, format, html, hidden, expires_at, forwarded,
+                                   created_at)
+             SELECT i, u, n, r, c, f, h, hd, e, fw::JSONB, ca
+             FROM UNNEST($1::BIGINT[], $2::INTEGER[], $3::TEXT[], $4::TEXT[], $5::TEXT[], $6::TEXT[], $7::TEXT[],
+                         $8::BOOLEAN[], $9::TIMESTAMPTZ[], $10::TEXT[], $11::TIMESTAMPTZ[])
+                  AS m(i, u, n, r, c, f, h, hd, e, fw, ca)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(batch.iter().map(|m| m.id).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.user_id).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.username.as_str()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.room.as_str()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.text.as_str()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.format.as_str()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.html.as_deref()).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.hidden).collect::<Vec<_>>())
+        .bind(batch.iter().map(|m| m.expires_at).collect::<Vec<_>>())
+        .bind(forwarded)
+        .bind(batch.iter().map(|m| m.created_at).collect::<Vec<_>>())
+        .execute(&self.db)
+        .instrument(telemetry::db_span("INSERT", "messages"))
+        .await?;
+        Ok(())
+    }
+
+    async fn find_visible(&self, viewer: i32, id: i64) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND id = $2",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn find_visible_many(&self, viewer: i32, ids: &[i64]) -> RepoResult<Vec<StoredMessage>> {
+        let rows = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND id = ANY($2)",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+
+    async fn page_before(
+        &self,
+        viewer: i32,
+        room: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = $2 AND id < $3 ORDER BY id DESC LIMIT $4",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        let mut messages = self.page(&sql, viewer, room, before.unwrap_or(i64::MAX), limit).await?;
+        messages.reverse();
+        Ok(messages)
+    }
+
+    async fn page_after(&self, viewer: i32, room: &str, after: i64, limit: i64) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = $2 AND id > $3 ORDER BY id LIMIT $4",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        self.page(&sql, viewer, room, after, limit).await
+    }
+
+    async fn first_since(&self, viewer: i32, room: &str, at: DateTime<Utc>) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND room = $2 AND created_at >= $3 ORDER BY created_at, id LIMIT 1",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(room)
+        .bind(at)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn export_page(&self, room: &str, after: i64, limit: i64) -> RepoResult<Vec<ExportedMessage>> {
+        let rows = sqlx::query_as::<_, ExportRow>(
+            "SELECT id, user_id, username, content AS text, format, hidden, webhook_id, forwarded, created_at, expires_at
+             FROM messages WHERE room = $1 AND id > $2 AND (expires_at IS NULL OR expires_at > now())
+             ORDER BY id LIMIT $3",
+        )
+        .bind(room)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(ExportedMessage::from).collect())
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<(String, Vec<String>)>> {
+        let mut tx = self.db.begin().await?;
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM attachments WHERE message_id = $1
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id = $1 AND thumbnail_key IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let room = sqlx::query_scalar::<_, String>("DELETE FROM messages WHERE id = $1 RETURNING room")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(room.map(|room| (room, keys)))
+    }
+
+    async fn expire_due(&self, limit: i64) -> RepoResult<Vec<(i64, String)>> {
+        let expired = sqlx::query_as::<_, (i64, String)>(
+            "WITH expired AS (
+                 DELETE FROM messages WHERE id IN (
+                     SELECT id FROM messages WHERE expires_at <= now()
+                     ORDER BY expires_at LIMIT $1 FOR UPDATE SKIP LOCKED
+                 )
+                 RETURNING id, room
+             ), notices AS (
+                 DELETE FROM notifications WHERE message_id IN (SELECT id FROM expired)
+             )
+             SELECT id, room FROM expired",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(expired)
+    }
+
+    async fn next_expiry(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT min(expires_at) FROM messages WHERE expires_at IS NOT NULL",
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(next)
+    }
+}
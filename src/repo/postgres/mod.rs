@@ -0,0 +1,56 @@
+// --- Postgres 저장소 ---
+//
+// 트레이트마다 구현을 기능별 파일에 나눠 두었다. SQL 은 migrations/ 의 스키마를 따른다.
+
+mod accounts;
+mod anonymization;
+mod attachments;
+mod audit;
+mod bots;
+mod data_exports;
+mod friends;
+mod health;
+mod imports;
+mod invites;
+mod ip_bans;
+mod keywords;
+mod link_previews;
+mod maintenance;
+mod messages;
+mod notifications;
+mod oauth;
+mod passkeys;
+mod polls;
+mod profiles;
+mod push;
+mod reminders;
+mod reports;
+mod retention;
+mod revocation;
+mod rooms;
+mod scheduled_messages;
+mod sessions;
+mod spam;
+mod stars;
+mod throttle;
+mod tokens;
+mod two_factor;
+mod users;
+mod webhooks;
+mod word_filters;
+
+use sqlx::PgPool;
+
+const USER_COLUMNS: &str = "id, username, password_hash, is_admin, is_guest, is_bot, \
+     disabled_at IS NOT NULL AS disabled, CASE WHEN muted_until > now() THEN muted_until END AS muted_until, \
+     shadow_banned";
+
+pub struct PgRepo {
+    db: PgPool,
+}
+
+impl PgRepo {
+    pub fn new(db: PgPool) -> Self {
+        PgRepo { db }
+    }
+}
@@ -0,0 +1,179 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{DigestMention, NewNotification, Notification, NotificationRepo, RepoResult};
+
+#[async_trait]
+impl NotificationRepo for PgRepo {
+    async fn create(&self, notification: &NewNotification<'_>) -> RepoResult<(i64, DateTime<Utc>)> {
+        let created = sqlx::query_as::<_, (i64, DateTime<Utc>)>(
+            "INSERT INTO notifications (user_id, kind, room, message_id, actor_id, text)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, created_at",
+        )
+        .bind(notification.user_id)
+        .bind(notification.kind)
+        .bind(notification.room)
+        .bind(notification.message_id)
+        .bind(notification.actor_id)
+        .bind(notification.text)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(created)
+    }
+
+    async fn message_recipients(
+        &self,
+        room: &str,
+        author_id: i32,
+        mentioned: &[String],
+        watchers: &[i32],
+    ) -> RepoResult<Vec<(i32, bool, bool)>> {
+        let recipients = sqlx::query_as::<_, (i32, bool, bool)>(
+            "SELECT u.id, lower(u.username) = ANY($1) AS mentioned, u.id = ANY($4) AS keyword
+             FROM users u LEFT JOIN room_notification_prefs p ON p.user_id = u.id AND p.room = $3
+             WHERE u.id <> $2 AND u.disabled_at IS NULL
+               AND (((lower(u.username) = ANY($1) OR u.id = ANY($4)) AND COALESCE(p.level, 'mentions') <> 'mute')
+                    OR p.level = 'all')",
+        )
+        .bind(mentioned)
+        .bind(author_id)
+        .bind(room)
+        .bind(watchers)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(recipients)
+    }
+
+    async fn purge(&self, created_before: DateTime<Utc>) -> RepoResult<u64> {
+        let purged = sqlx::query("DELETE FROM notifications WHERE created_at < $1")
+            .bind(created_before)
+            .execute(&self.db)
+            .await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn list(
+        &self,
+        user_id: i32,
+        unread_only: bool,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT n.id, n.kind, n.room, n.message_id, n.actor_id, u.username AS actor, n.text, n.read_at, n.created_at
+             FROM notifications n LEFT JOIN users u ON u.id = n.actor_id
+             WHERE n.user_id = $1 AND (NOT $2 OR n.read_at IS NULL) AND ($3::BIGINT IS NULL OR n.id < $3)
+             ORDER BY n.id DESC LIMIT $4",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(notifications)
+    }
+
+    async fn unread_count(&self, user_id: i32) -> RepoResult<i64> {
+        let unread = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(unread)
+    }
+
+    async fn mark_read(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = COALESCE(read_at, now()) WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn mark_all_read(&self, user_id: i32) -> RepoResult<u64> {
+        let result = sqlx::query("UPDATE notifications SET read_at = now() WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn room_level(&self, user_id: i32, room: &str) -> RepoResult<Option<String>> {
+        let level = sqlx::query_scalar::<_, String>(
+            "SELECT level FROM room_notification_prefs WHERE user_id = $1 AND room = $2",
+        )
+        .bind(user_id)
+        .bind(room)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(level)
+    }
+
+    async fn set_room_level(&self, user_id: i32, room: &str, level: &str) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_notification_prefs (user_id, room, level) VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, room) DO UPDATE SET level = EXCLUDED.level, updated_at = now()",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(level)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_digest(&self, created_before: DateTime<Utc>) -> RepoResult<Vec<DigestMention>> {
+        let mentions = sqlx::query_as::<_, DigestMention>(
+            "SELECT u.id AS user_id, u.email, n.room, a.username AS actor, n.text, n.created_at
+             FROM notifications n
+             JOIN users u ON u.id = n.user_id
+             LEFT JOIN users a ON a.id = n.actor_id
+             WHERE n.kind = 'mention' AND n.read_at IS NULL
+               AND n.created_at < $1
+               AND n.created_at > COALESCE(u.digest_sent_at, '-infinity')
+               AND (u.last_online_at IS NULL OR u.last_online_at < n.created_at)
+               AND u.email_digest AND u.email IS NOT NULL AND u.email_verified_at IS NOT NULL
+               AND u.disabled_at IS NULL
+             ORDER BY u.id, n.id",
+        )
+        .bind(created_before)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(mentions)
+    }
+
+    async fn mark_digest_sent(&self, user_id: i32, sent_until: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET digest_sent_at = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(sent_until)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn digest_enabled(&self, user_id: i32) -> RepoResult<Option<bool>> {
+        let enabled = sqlx::query_scalar::<_, bool>("SELECT email_digest FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(enabled)
+    }
+
+    async fn set_digest(&self, user_id: i32, enabled: bool) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET email_digest = $2, digest_sent_at = CASE WHEN $2 THEN digest_sent_at ELSE now() END
+             WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(enabled)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,113 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{NewPoll, PollRepo, PollRow, RepoResult};
+
+const POLL_COLUMNS: &str =
+    "id, room, user_id, username, question, options, anonymous, hidden, closes_at, closed_at, created_at";
+
+#[async_trait]
+impl PollRepo for PgRepo {
+    async fn find(&self, poll_id: i64) -> RepoResult<Option<PollRow>> {
+        let sql = format!("SELECT {} FROM polls WHERE id = $1", POLL_COLUMNS);
+        let row = sqlx::query_as::<_, PollRow>(&sql).bind(poll_id).fetch_optional(&self.db).await?;
+        Ok(row)
+    }
+
+    async fn recent(&self, room: &str, viewer: i32, open_only: bool, limit: i64) -> RepoResult<Vec<PollRow>> {
+        let sql = format!(
+            "SELECT {} FROM polls WHERE room = $1 AND (NOT hidden OR user_id = $2) AND (NOT $3 OR closed_at IS NULL)
+             ORDER BY id DESC LIMIT $4",
+            POLL_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, PollRow>(&sql)
+            .bind(room)
+            .bind(viewer)
+            .bind(open_only)
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows)
+    }
+
+    async fn votes(&self, poll_id: i64, viewer: Option<i32>) -> RepoResult<Vec<(i16, i32, String)>> {
+        let votes = sqlx::query_as::<_, (i16, i32, String)>(
+            "SELECT v.option, v.user_id, u.username FROM poll_votes v JOIN users u ON u.id = v.user_id
+             WHERE v.poll_id = $1 AND (NOT u.shadow_banned OR v.user_id = $2) ORDER BY v.voted_at",
+        )
+        .bind(poll_id)
+        .bind(viewer)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(votes)
+    }
+
+    async fn vote(&self, poll_id: i64, user_id: i32, option: i16) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO poll_votes (poll_id, user_id, option) VALUES ($1, $2, $3)
+             ON CONFLICT (poll_id, user_id) DO UPDATE SET option = $3, voted_at = now()",
+        )
+        .bind(poll_id)
+        .bind(user_id)
+        .bind(option)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn unvote(&self, poll_id: i64, user_id: i32) -> RepoResult<()> {
+        sqlx::query("DELETE FROM poll_votes WHERE poll_id = $1 AND user_id = $2")
+            .bind(poll_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn create(&self, poll: NewPoll<'_>) -> RepoResult<PollRow> {
+        let sql = format!(
+            "INSERT INTO polls (room, user_id, username, question, options, anonymous, hidden, closes_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING {}",
+            POLL_COLUMNS
+        );
+        let row = sqlx::query_as::<_, PollRow>(&sql)
+            .bind(poll.room)
+            .bind(poll.user_id)
+            .bind(poll.username)
+            .bind(poll.question)
+            .bind(poll.options)
+            .bind(poll.anonymous)
+            .bind(poll.hidden)
+            .bind(poll.closes_at)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(row)
+    }
+
+    async fn close(&self, poll_id: i64) -> RepoResult<Option<PollRow>> {
+        let sql = format!(
+            "UPDATE polls SET closed_at = now() WHERE id = $1 AND closed_at IS NULL RETURNING {}",
+            POLL_COLUMNS
+        );
+        let row = sqlx::query_as::<_, PollRow>(&sql).bind(poll_id).fetch_optional(&self.db).await?;
+        Ok(row)
+    }
+
+    async fn close_due(&self) -> RepoResult<Vec<PollRow>> {
+        let sql = format!(
+            "UPDATE polls SET closed_at = now() WHERE closed_at IS NULL AND closes_at <= now() RETURNING {}",
+            POLL_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, PollRow>(&sql).fetch_all(&self.db).await?;
+        Ok(rows)
+    }
+
+    async fn next_close(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next =
+            sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT min(closes_at) FROM polls WHERE closed_at IS NULL")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(next)
+    }
+}
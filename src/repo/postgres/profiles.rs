@@ -0,0 +1,113 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{Profile, ProfileRepo, ProfileUpdate, RepoResult};
+
+#[async_trait]
+impl ProfileRepo for PgRepo {
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<Profile>> {
+        let profile = sqlx::query_as::<_, Profile>(
+            "SELECT u.id AS user_id, u.username, p.display_name, p.bio, p.pronouns, p.avatar_version, u.is_guest,
+                    u.created_at
+             FROM users u LEFT JOIN profiles p ON p.user_id = u.id WHERE lower(u.username) = lower($1)",
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(profile)
+    }
+
+    async fn display(&self, user_id: i32) -> RepoResult<Option<(Option<String>, Option<String>)>> {
+        let row = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+            "SELECT display_name, avatar_version FROM profiles WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn update(&self, user_id: i32, update: &ProfileUpdate) -> RepoResult<()> {
+        // $2/$4/$6 는 그 항목을 바꾸는지 여부
+        sqlx::query(
+            "INSERT INTO profiles (user_id, display_name, bio, pronouns) VALUES ($1, $3, $5, $7)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 display_name = CASE WHEN $2 THEN EXCLUDED.display_name ELSE profiles.display_name END,
+                 bio = CASE WHEN $4 THEN EXCLUDED.bio ELSE profiles.bio END,
+                 pronouns = CASE WHEN $6 THEN EXCLUDED.pronouns ELSE profiles.pronouns END,
+                 updated_at = now()",
+        )
+        .bind(user_id)
+        .bind(update.display_name.is_some())
+        .bind(update.display_name.clone().flatten())
+        .bind(update.bio.is_some())
+        .bind(update.bio.clone().flatten())
+        .bind(update.pronouns.is_some())
+        .bind(update.pronouns.clone().flatten())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_avatar(&self, user_id: i32, version: Option<&str>) -> RepoResult<()> {
+        match version {
+            Some(version) => {
+                sqlx::query(
+                    "INSERT INTO profiles (user_id, avatar_version) VALUES ($1, $2)
+                     ON CONFLICT (user_id) DO UPDATE SET avatar_version = EXCLUDED.avatar_version, updated_at = now()",
+                )
+                .bind(user_id)
+                .bind(version)
+                .execute(&self.db)
+                .await?;
+            }
+            None => {
+                sqlx::query("UPDATE profiles SET avatar_version = NULL, updated_at = now() WHERE user_id = $1")
+                    .bind(user_id)
+                    .execute(&self.db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn avatar_by_name(&self, username: &str) -> RepoResult<Option<(i32, String)>> {
+        let found = sqlx::query_as::<_, (i32, String)>(
+            "SELECT u.id, p.avatar_version FROM users u JOIN profiles p ON p.user_id = u.id
+             WHERE lower(u.username) = lower($1) AND p.avatar_version IS NOT NULL",
+        )
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(found)
+    }
+
+    async fn status(&self, user_id: i32) -> RepoResult<Option<(String, Option<String>)>> {
+        let status =
+            sqlx::query_as::<_, (String, Option<String>)>("SELECT status, status_message FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(status)
+    }
+
+    async fn set_status(
+        &self,
+        user_id: i32,
+        status: Option<&str>,
+        message: Option<Option<&str>>,
+    ) -> RepoResult<Option<(String, Option<String>)>> {
+        let updated = sqlx::query_as::<_, (String, Option<String>)>(
+            "UPDATE users SET status = COALESCE($2, status),
+                              status_message = CASE WHEN $3 THEN $4 ELSE status_message END
+             WHERE id = $1 RETURNING status, status_message",
+        )
+        .bind(user_id)
+        .bind(status)
+        .bind(message.is_some())
+        .bind(message.flatten())
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(updated)
+    }
+}
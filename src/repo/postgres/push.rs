@@ -0,0 +1,80 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{PushRepo, PushSubscription, PushTarget, RepoResult};
+
+#[async_trait]
+impl PushRepo for PgRepo {
+    async fn targets(&self, user_id: i32) -> RepoResult<Vec<PushTarget>> {
+        let targets = sqlx::query_as::<_, PushTarget>(
+            "SELECT id, endpoint, p256dh, auth FROM push_subscriptions WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(targets)
+    }
+
+    async fn mark_delivered(&self, id: i64) -> RepoResult<()> {
+        sqlx::query("UPDATE push_subscriptions SET last_used_at = now() WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, id: i64) -> RepoResult<()> {
+        sqlx::query("DELETE FROM push_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PushSubscription>> {
+        let subscriptions = sqlx::query_as::<_, PushSubscription>(
+            "SELECT id, endpoint, created_at, last_used_at FROM push_subscriptions WHERE user_id = $1 ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(subscriptions)
+    }
+
+    async fn count_other(&self, user_id: i32, endpoint: &str) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM push_subscriptions WHERE user_id = $1 AND endpoint <> $2",
+        )
+        .bind(user_id)
+        .bind(endpoint)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    async fn subscribe(&self, user_id: i32, endpoint: &str, p256dh: &str, auth: &str) -> RepoResult<PushSubscription> {
+        let subscription = sqlx::query_as::<_, PushSubscription>(
+            "INSERT INTO push_subscriptions (user_id, endpoint, p256dh, auth) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (endpoint) DO UPDATE
+                 SET user_id = EXCLUDED.user_id, p256dh = EXCLUDED.p256dh, auth = EXCLUDED.auth, created_at = now(),
+                     last_used_at = NULL
+             RETURNING id, endpoint, created_at, last_used_at",
+        )
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(p256dh)
+        .bind(auth)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(subscription)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM push_subscriptions WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,96 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{NewReport, Report, ReportRepo, ReportState, RepoError, RepoResult};
+
+#[async_trait]
+impl ReportRepo for PgRepo {
+    async fn message(&self, message_id: i64) -> RepoResult<Option<(String, Option<i32>, Option<String>)>> {
+        let message = sqlx::query_as::<_, (String, Option<i32>, Option<String>)>(
+            "SELECT room, user_id, content FROM messages WHERE id = $1",
+        )
+        .bind(message_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(message)
+    }
+
+    async fn create(&self, report: NewReport<'_>) -> RepoResult<i64> {
+        let created = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO message_reports (message_id, room, author_id, content, reporter_id, reason)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+        )
+        .bind(report.message_id)
+        .bind(report.room)
+        .bind(report.author_id)
+        .bind(report.content)
+        .bind(report.reporter_id)
+        .bind(report.reason)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(id) => Ok(id),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("report")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self, status: &str, limit: i64) -> RepoResult<Vec<Report>> {
+        let order = if status == "open" { "r.created_at" } else { "r.resolved_at DESC" };
+        let reports = sqlx::query_as::<_, Report>(&format!(
+            "SELECT r.id, r.message_id, r.room, r.author_id, a.username AS author, r.content, u.username AS reporter,
+                    r.reason, r.status, r.resolution, r.resolved_at, r.created_at
+             FROM message_reports r
+             JOIN users u ON u.id = r.reporter_id
+             LEFT JOIN users a ON a.id = r.author_id
+             WHERE r.status = $1 ORDER BY {} LIMIT $2",
+            order
+        ))
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(reports)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<ReportState>> {
+        let report = sqlx::query_as::<_, (Option<i64>, String, Option<i32>, String, String)>(
+            "SELECT message_id, room, author_id, reason, status FROM message_reports WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(report.map(|(message_id, room, author_id, reason, status)| ReportState {
+            message_id,
+            room,
+            author_id,
+            reason,
+            status,
+        }))
+    }
+
+    async fn open_ids(&self, id: i64, message_id: Option<i64>) -> RepoResult<Vec<i64>> {
+        let ids = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM message_reports WHERE status = 'open' AND (id = $1 OR message_id = $2)",
+        )
+        .bind(id)
+        .bind(message_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn close(&self, ids: &[i64], status: &str, resolution: &str, resolved_by: i32) -> RepoResult<u64> {
+        let closed = sqlx::query(
+            "UPDATE message_reports SET status = $2, resolution = $3, resolved_by = $4, resolved_at = now()
+             WHERE id = ANY($1) AND status = 'open'",
+        )
+        .bind(ids)
+        .bind(status)
+        .bind(resolution)
+        .bind(resolved_by)
+        .execute(&self.db)
+        .await?;
+        Ok(closed.rows_affected())
+    }
+}
@@ -0,0 +1,94 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{RepoResult, RetentionBatch, RetentionRepo, RoomPolicy};
+
+const POLICY_COLUMNS: &str = "room, days, action, exempt, updated_by, updated_at";
+
+#[async_trait]
+impl RetentionRepo for PgRepo {
+    async fn policy(&self, room: &str) -> RepoResult<Option<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention WHERE room = $1", POLICY_COLUMNS);
+        let policy = sqlx::query_as::<_, RoomPolicy>(&sql).bind(room).fetch_optional(&self.db).await?;
+        Ok(policy)
+    }
+
+    async fn policies(&self) -> RepoResult<Vec<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention ORDER BY room", POLICY_COLUMNS);
+        let policies = sqlx::query_as::<_, RoomPolicy>(&sql).fetch_all(&self.db).await?;
+        Ok(policies)
+    }
+
+    async fn set_policy(&self, room: &str, days: Option<i32>, action: Option<&str>, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, days, action, updated_by) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (room) DO UPDATE SET days = $2, action = $3, updated_by = $4, updated_at = now()",
+        )
+        .bind(room)
+        .bind(days)
+        .bind(action)
+        .bind(updated_by)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_exempt(&self, room: &str, exempt: bool, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, exempt, updated_by) VALUES ($1, $2, $3)
+             ON CONFLICT (room) DO UPDATE SET exempt = $2, updated_by = $3, updated_at = now()",
+        )
+        .bind(room)
+        .bind(exempt)
+        .bind(updated_by)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn sweep(&self, default_days: i32, default_action: &str, limit: i64) -> RepoResult<RetentionBatch> {
+        let mut tx = self.db.begin().await?;
+        let expired = sqlx::query_as::<_, (i64, bool)>(
+            "SELECT m.id, COALESCE(r.action, $2) = 'archive'
+             FROM messages m LEFT JOIN room_retention r ON r.room = m.room
+             WHERE m.room IS NOT NULL AND NOT COALESCE(r.exempt, false) AND COALESCE(r.days, $1) > 0
+               AND m.created_at < now() - make_interval(days => COALESCE(r.days, $1))
+             ORDER BY m.id LIMIT $3 FOR UPDATE OF m SKIP LOCKED",
+        )
+        .bind(default_days)
+        .bind(default_action)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if expired.is_empty() {
+            return Ok(RetentionBatch { deleted: 0, archived: 0, attachment_keys: Vec::new() });
+        }
+        let ids: Vec<i64> = expired.iter().map(|(id, _)| *id).collect();
+        let to_archive: Vec<i64> = expired.iter().filter(|(_, archive)| *archive).map(|(id, _)| *id).collect();
+
+        sqlx::query(
+            "INSERT INTO archived_messages (id, room, created_at, message)
+             SELECT id, room, created_at, to_jsonb(m) FROM messages m WHERE id = ANY($1)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&to_archive)
+        .execute(&mut *tx)
+        .await?;
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM attachments WHERE message_id = ANY($1)
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id = ANY($1) AND thumbnail_key IS NOT NULL",
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM notifications WHERE message_id = ANY($1)").bind(&ids).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM messages WHERE id = ANY($1)").bind(&ids).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(RetentionBatch {
+            deleted: ids.len() - to_archive.len(),
+            archived: to_archive.len(),
+            attachment_keys: keys,
+        })
+    }
+}
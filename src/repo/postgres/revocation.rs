@@ -0,0 +1,41 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{RepoResult, RevocationRepo};
+
+#[async_trait]
+impl RevocationRepo for PgRepo {
+    async fn revoke_token(&self, jti: &str, expires_at: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO revoked_tokens (jti, expires_at) VALUES ($1, $2) ON CONFLICT (jti) DO NOTHING")
+            .bind(jti)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoked_tokens(&self) -> RepoResult<Vec<(String, DateTime<Utc>)>> {
+        let rows = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            "SELECT jti, expires_at FROM revoked_tokens WHERE expires_at > now()",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn revoked_sessions(&self, since: DateTime<Utc>) -> RepoResult<Vec<(String, DateTime<Utc>)>> {
+        let rows = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+            "SELECT id, revoked_at FROM sessions WHERE revoked_at > $1",
+        )
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn purge_tokens(&self) -> RepoResult<()> {
+        sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= now()").execute(&self.db).await?;
+        Ok(())
+    }
+}
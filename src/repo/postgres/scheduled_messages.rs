@@ -0,0 +1,81 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{DueMessage, RepoResult, ScheduledMessage, ScheduledMessageRepo};
+
+#[async_trait]
+impl ScheduledMessageRepo for PgRepo {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        content: &str,
+        format: &str,
+        send_at: DateTime<Utc>,
+    ) -> RepoResult<ScheduledMessage> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "INSERT INTO scheduled_messages (user_id, room, content, format, send_at) VALUES ($1, $2, $3, $4, $5)
+             RETURNING id, room, content AS text, format, send_at, created_at",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(content)
+        .bind(format)
+        .bind(send_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(scheduled)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM scheduled_messages WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ScheduledMessage>> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "SELECT id, room, content AS text, format, send_at, created_at FROM scheduled_messages
+             WHERE user_id = $1 ORDER BY send_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(scheduled)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM scheduled_messages WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueMessage>> {
+        let due = sqlx::query_as::<_, DueMessage>(
+            "DELETE FROM scheduled_messages m USING users u
+             WHERE u.id = m.user_id AND m.id IN (
+                 SELECT id FROM scheduled_messages WHERE send_at <= now()
+                 ORDER BY send_at LIMIT $1 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING m.id, m.user_id, u.username, u.is_bot, u.disabled_at IS NOT NULL AS disabled, m.room,
+                       m.content, m.format",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(due)
+    }
+
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT min(send_at) FROM scheduled_messages")
+            .fetch_one(&self.db)
+            .await?;
+        Ok(next)
+    }
+}
@@ -0,0 +1,122 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{RefreshedSession, RepoResult, SessionInfo, SessionRepo};
+
+#[async_trait]
+impl SessionRepo for PgRepo {
+    async fn create(&self, id: &str, user_id: i32, user_agent: Option<&str>, ip: Option<&str>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO sessions (id, user_id, user_agent, ip) VALUES ($1, $2, $3, $4)")
+            .bind(id)
+            .bind(user_id)
+            .bind(user_agent)
+            .bind(ip)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_refresh_token(
+        &self,
+        user_id: i32,
+        session_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(session_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn use_refresh_token(&self, token_hash: &str) -> RepoResult<Option<RefreshedSession>> {
+        let rotated = sqlx::query_as::<_, RefreshedSession>(
+            "UPDATE refresh_tokens r SET used_at = now()
+             FROM users u, sessions s
+             WHERE r.token_hash = $1 AND r.user_id = u.id AND s.id = r.family_id
+               AND r.used_at IS NULL AND r.revoked_at IS NULL AND r.expires_at > now()
+               AND s.revoked_at IS NULL AND u.disabled_at IS NULL
+             RETURNING r.user_id, u.username, r.family_id AS session_id, u.is_guest",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        if let Some(row) = &rotated {
+            self.touch(&row.session_id).await?;
+        }
+        Ok(rotated)
+    }
+
+    async fn refresh_token_session(&self, token_hash: &str, used_only: bool) -> RepoResult<Option<String>> {
+        let session_id = sqlx::query_scalar::<_, String>(
+            "SELECT family_id FROM refresh_tokens WHERE token_hash = $1 AND (NOT $2 OR used_at IS NOT NULL)",
+        )
+        .bind(token_hash)
+        .bind(used_only)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(session_id)
+    }
+
+    async fn revoke(&self, id: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE sessions SET revoked_at = now() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        sqlx::query("UPDATE refresh_tokens SET revoked_at = now() WHERE family_id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn active_ids(&self, user_id: i32, except: Option<&str>) -> RepoResult<Vec<String>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM sessions WHERE user_id = $1 AND revoked_at IS NULL AND ($2::TEXT IS NULL OR id <> $2)",
+        )
+        .bind(user_id)
+        .bind(except)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn list(&self, user_id: i32, seen_after: DateTime<Utc>) -> RepoResult<Vec<SessionInfo>> {
+        let sessions = sqlx::query_as::<_, SessionInfo>(
+            "SELECT id, user_agent, ip, created_at, last_seen FROM sessions
+             WHERE user_id = $1 AND revoked_at IS NULL AND last_seen > $2
+             ORDER BY last_seen DESC",
+        )
+        .bind(user_id)
+        .bind(seen_after)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(sessions)
+    }
+
+    async fn is_active(&self, id: &str, user_id: i32) -> RepoResult<bool> {
+        let active = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(active)
+    }
+
+    async fn touch(&self, id: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE sessions SET last_seen = now() WHERE id = $1")
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{RepoResult, StarRepo};
+
+#[async_trait]
+impl StarRepo for PgRepo {
+    async fn star(&self, user_id: i32, message_id: i64) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO message_stars (user_id, message_id) VALUES ($1, $2) ON CONFLICT (user_id, message_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(message_id)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn unstar(&self, user_id: i32, message_id: i64) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM message_stars WHERE user_id = $1 AND message_id = $2")
+            .bind(user_id)
+            .bind(message_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn list(&self, user_id: i32, before: Option<i64>, limit: i64) -> RepoResult<Vec<(i64, i64, DateTime<Utc>)>> {
+        let stars = sqlx::query_as::<_, (i64, i64, DateTime<Utc>)>(
+            "SELECT id, message_id, created_at FROM message_stars
+             WHERE user_id = $1 AND ($2::BIGINT IS NULL OR id < $2) ORDER BY id DESC LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(stars)
+    }
+}
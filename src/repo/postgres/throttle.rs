@@ -0,0 +1,62 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{RepoResult, ThrottleRepo};
+
+#[async_trait]
+impl ThrottleRepo for PgRepo {
+    async fn retry_after(&self, keys: &[String]) -> RepoResult<Option<f64>> {
+        let secs = sqlx::query_scalar::<_, Option<f64>>(
+            "SELECT EXTRACT(EPOCH FROM max(locked_until) - now())::FLOAT8
+             FROM auth_throttle WHERE key = ANY($1) AND locked_until > now()",
+        )
+        .bind(keys)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(secs)
+    }
+
+    async fn record_failure(&self, key: &str, window: f64) -> RepoResult<i32> {
+        let failures = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO auth_throttle (key, failures, last_failure_at) VALUES ($1, 1, now())
+             ON CONFLICT (key) DO UPDATE SET
+                 failures = CASE
+                     WHEN auth_throttle.last_failure_at < now() - make_interval(secs => $2) THEN 1
+                     ELSE auth_throttle.failures + 1
+                 END,
+                 last_failure_at = now()
+             RETURNING failures",
+        )
+        .bind(key)
+        .bind(window)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(failures)
+    }
+
+    async fn lock(&self, key: &str, seconds: f64) -> RepoResult<()> {
+        sqlx::query("UPDATE auth_throttle SET locked_until = now() + make_interval(secs => $2) WHERE key = $1")
+            .bind(key)
+            .bind(seconds)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> RepoResult<()> {
+        sqlx::query("DELETE FROM auth_throttle WHERE key = $1").bind(key).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn purge(&self, window: f64) -> RepoResult<()> {
+        sqlx::query(
+            "DELETE FROM auth_throttle
+             WHERE last_failure_at < now() - make_interval(secs => $1)
+               AND (locked_until IS NULL OR locked_until < now())",
+        )
+        .bind(window)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{RepoResult, TokenRepo};
+
+#[async_trait]
+impl TokenRepo for PgRepo {
+    async fn add_password_reset(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)")
+            .bind(user_id)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_password_reset(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "UPDATE password_reset_tokens SET used_at = now()
+             WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+             RETURNING user_id",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn expire_password_resets(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE password_reset_tokens SET used_at = now() WHERE user_id = $1 AND used_at IS NULL")
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_email_verification(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO email_verification_tokens (user_id, email, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn use_email_verification(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "WITH used AS (
+                 UPDATE email_verification_tokens SET used_at = now()
+                 WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+                 RETURNING user_id, email
+             )
+             UPDATE users u SET email_verified_at = now()
+             FROM used WHERE u.id = used.user_id AND u.email = used.email
+             RETURNING u.id",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn add_magic_link(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("INSERT INTO magic_links (user_id, email, token_hash, expires_at) VALUES ($1, $2, $3, $4)")
+            .bind(user_id)
+            .bind(email)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>(
+            "WITH used AS (
+                 UPDATE magic_links SET used_at = now()
+                 WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+                 RETURNING user_id, email
+             )
+             UPDATE users u SET email_verified_at = COALESCE(u.email_verified_at, now())
+             FROM used WHERE u.id = used.user_id AND lower(u.email) = lower(used.email)
+             RETURNING u.id, u.username",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user)
+    }
+}
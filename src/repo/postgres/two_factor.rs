@@ -0,0 +1,125 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::PgRepo;
+use crate::repo::{RepoResult, TwoFactorRepo};
+
+#[async_trait]
+impl TwoFactorRepo for PgRepo {
+    async fn is_enabled(&self, user_id: i32) -> RepoResult<bool> {
+        let enabled = sqlx::query_scalar::<_, i32>(
+            "SELECT user_id FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(enabled.is_some())
+    }
+
+    async fn add_challenge(&self, token_hash: &str, user_id: i32, expires_at: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("DELETE FROM login_challenges WHERE expires_at < now()")
+            .execute(&self.db)
+            .await?;
+        sqlx::query("INSERT INTO login_challenges (token_hash, user_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(token_hash)
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn attempt_challenge(&self, token_hash: &str, max_attempts: i32) -> RepoResult<Option<i32>> {
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "UPDATE login_challenges SET attempts = attempts + 1
+             WHERE token_hash = $1 AND expires_at > now() AND attempts < $2
+             RETURNING user_id",
+        )
+        .bind(token_hash)
+        .bind(max_attempts)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn delete_challenge(&self, token_hash: &str) -> RepoResult<()> {
+        sqlx::query("DELETE FROM login_challenges WHERE token_hash = $1")
+            .bind(token_hash)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn challenge_username(&self, token_hash: &str) -> RepoResult<Option<String>> {
+        let username = sqlx::query_scalar::<_, String>(
+            "SELECT u.username FROM login_challenges c JOIN users u ON u.id = c.user_id
+             WHERE c.token_hash = $1 AND c.expires_at > now()",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(username)
+    }
+
+    async fn confirmed_secret(&self, user_id: i32) -> RepoResult<Option<(String, Option<i64>)>> {
+        let row = sqlx::query_as::<_, (String, Option<i64>)>(
+            "SELECT secret, last_used_step FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn pending_secret(&self, user_id: i32) -> RepoResult<Option<String>> {
+        let secret = sqlx::query_scalar::<_, String>(
+            "SELECT secret FROM user_totp WHERE user_id = $1 AND confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(secret)
+    }
+
+    async fn mark_step_used(&self, user_id: i32, step: i64) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE user_totp SET last_used_step = $2
+             WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn enroll(&self, user_id: i32, secret: &str) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "INSERT INTO user_totp (user_id, secret) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE SET secret = EXCLUDED.secret, created_at = now(), last_used_step = NULL
+             WHERE user_totp.confirmed_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn confirm(&self, user_id: i32, step: i64) -> RepoResult<()> {
+        sqlx::query("UPDATE user_totp SET confirmed_at = now(), last_used_step = $2 WHERE user_id = $1")
+            .bind(user_id)
+            .bind(step)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn disable(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{invites, PgRepo, USER_COLUMNS};
+use crate::{
+    auth::hash_token,
+    repo::{NewUser, RepoError, RepoResult, User, UserRepo, UserSummary},
+};
+
+#[async_trait]
+impl UserRepo for PgRepo {
+    async fn create(&self, user: &NewUser<'_>) -> RepoResult<User> {
+        let mut tx = self.db.begin().await?;
+        let invite_id = match user.invite_code {
+            Some(code) => {
+                Some(invites::redeem(&mut tx, &hash_token(code.trim())).await?.ok_or(RepoError::InvalidInvite)?)
+            }
+            None => None,
+        };
+        let created = sqlx::query_as::<_, User>(&format!(
+            "INSERT INTO users (username, password_hash, email, invite_id) VALUES ($1, $2, $3, $4) RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user.username)
+        .bind(user.password_hash)
+        .bind(user.email)
+        .bind(invite_id)
+        .fetch_one(&mut *tx)
+        .await;
+        match created {
+            Ok(row) => {
+                tx.commit().await?;
+                Ok(row)
+            }
+            // 대소문자만 다른 이름도 유니크 인덱스에 걸린다
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                if e.constraint().is_some_and(|c| c.contains("email")) {
+                    Err(RepoError::Conflict("email"))
+                } else {
+                    Err(RepoError::Conflict("username"))
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn find(&self, id: i32) -> RepoResult<Option<User>> {
+        let row = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(row)
+    }
+
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<User>> {
+        let row = sqlx::query_as::<_, User>(&format!(
+            "SELECT {} FROM users WHERE lower(username) = lower($1)",
+            USER_COLUMNS
+        ))
+        .bind(username)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn set_password_hash(&self, id: i32, hash: &str, expected: Option<&str>) -> RepoResult<bool> {
+        let updated = sqlx::query(
+            "UPDATE users SET password_hash = $1 WHERE id = $2 AND ($3::TEXT IS NULL OR password_hash = $3)",
+        )
+        .bind(hash)
+        .bind(id)
+        .bind(expected)
+        .execute(&self.db)
+        .await?;
+        Ok(updated.rows_affected() == 1)
+    }
+
+    async fn touch_last_online(&self, id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET last_online_at = now() WHERE id = $1").bind(id).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn mute(&self, id: i32, until: DateTime<Utc>) -> RepoResult<Option<User>> {
+        let row = sqlx::query_as::<_, User>(&format!(
+            "UPDATE users SET muted_until = GREATEST(COALESCE(muted_until, $2), $2) WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(id)
+        .bind(until)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn set_shadow_banned(&self, id: i32, shadow_banned: bool) -> RepoResult<Option<User>> {
+        let row = sqlx::query_as::<_, User>(&format!(
+            "UPDATE users SET shadow_banned = $2 WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(id)
+        .bind(shadow_banned)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn search(&self, pattern: Option<&str>, limit: i64, offset: i64) -> RepoResult<Vec<UserSummary>> {
+        let users = sqlx::query_as::<_, UserSummary>(
+            "SELECT u.id, u.username, u.email, u.is_admin, u.is_guest, u.disabled_at, u.shadow_banned,
+                    CASE WHEN u.muted_until > now() THEN u.muted_until END AS muted_until, u.created_at,
+                    (SELECT max(s.last_seen) FROM sessions s WHERE s.user_id = u.id) AS last_seen
+             FROM users u
+             WHERE $1::TEXT IS NULL OR u.username ILIKE $1 OR u.email ILIKE $1
+             ORDER BY u.id LIMIT $2 OFFSET $3",
+        )
+        .bind(pattern)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(users)
+    }
+
+    async fn set_disabled(&self, id: i32, disabled: bool) -> RepoResult<Option<(String, Option<DateTime<Utc>>)>> {
+        let updated = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+            "UPDATE users SET disabled_at = CASE WHEN $2 THEN COALESCE(disabled_at, now()) END WHERE id = $1
+             RETURNING username, disabled_at",
+        )
+        .bind(id)
+        .bind(disabled)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(updated)
+    }
+}
@@ -0,0 +1,71 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{RepoResult, WebhookInfo, WebhookRepo};
+
+const INFO_COLUMNS: &str = "id, room, name, created_by, created_at, last_used_at, revoked_at";
+
+#[async_trait]
+impl WebhookRepo for PgRepo {
+    async fn create(&self, room: &str, name: &str, token_hash: &str, created_by: i32) -> RepoResult<WebhookInfo> {
+        let webhook = sqlx::query_as::<_, WebhookInfo>(&format!(
+            "INSERT INTO webhooks (room, name, token_hash, created_by) VALUES ($1, $2, $3, $4) RETURNING {}",
+            INFO_COLUMNS
+        ))
+        .bind(room)
+        .bind(name)
+        .bind(token_hash)
+        .bind(created_by)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn list(&self, room: Option<&str>) -> RepoResult<Vec<WebhookInfo>> {
+        let webhooks = sqlx::query_as::<_, WebhookInfo>(&format!(
+            "SELECT {} FROM webhooks WHERE $1::TEXT IS NULL OR room = $1 ORDER BY id",
+            INFO_COLUMNS
+        ))
+        .bind(room)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(webhooks)
+    }
+
+    async fn revoke(&self, id: i64, room: Option<&str>) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE webhooks SET revoked_at = now()
+             WHERE id = $1 AND ($2::TEXT IS NULL OR room = $2) AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(room)
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn use_token(&self, token_hash: &str) -> RepoResult<Option<(i64, String, String)>> {
+        let webhook = sqlx::query_as::<_, (i64, String, String)>(
+            "UPDATE webhooks SET last_used_at = now() WHERE token_hash = $1 AND revoked_at IS NULL
+             RETURNING id, room, name",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn post_message(&self, webhook_id: i64, name: &str, room: &str, text: &str) -> RepoResult<i64> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO messages (user_id, username, room, content, webhook_id) VALUES (NULL, $1, $2, $3, $4)
+             RETURNING id",
+        )
+        .bind(name)
+        .bind(room)
+        .bind(text)
+        .bind(webhook_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(id)
+    }
+}
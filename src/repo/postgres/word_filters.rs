@@ -0,0 +1,50 @@
+use axum::async_trait;
+
+use super::PgRepo;
+use crate::repo::{RepoError, RepoResult, WordFilterRepo, WordFilterRule};
+
+#[async_trait]
+impl WordFilterRepo for PgRepo {
+    async fn all(&self) -> RepoResult<Vec<WordFilterRule>> {
+        let rules = sqlx::query_as::<_, WordFilterRule>(
+            "SELECT id, room, pattern, action, created_by, created_at FROM word_filters ORDER BY id",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rules)
+    }
+
+    async fn create(
+        &self,
+        room: Option<&str>,
+        pattern: &str,
+        action: &str,
+        created_by: i32,
+    ) -> RepoResult<WordFilterRule> {
+        let created = sqlx::query_as::<_, WordFilterRule>(
+            "INSERT INTO word_filters (room, pattern, action, created_by) VALUES ($1, $2, $3, $4)
+             RETURNING id, room, pattern, action, created_by, created_at",
+        )
+        .bind(room)
+        .bind(pattern)
+        .bind(action)
+        .bind(created_by)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(rule) => Ok(rule),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("word_filter")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<WordFilterRule>> {
+        let rule = sqlx::query_as::<_, WordFilterRule>(
+            "DELETE FROM word_filters WHERE id = $1 RETURNING id, room, pattern, action, created_by, created_at",
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(rule)
+    }
+}
@@ -0,0 +1,59 @@
+// --- 프로필과 접속 상태 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct Profile {
+    #[serde(skip_serializing)]
+    pub user_id: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub pronouns: Option<String>,
+    #[serde(skip_serializing)]
+    pub avatar_version: Option<String>,
+    #[sqlx(skip)]
+    pub avatar_url: Option<String>,
+    pub is_guest: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+// 바꿀 항목. 바깥 None 은 그대로 두고, Some(None) 은 지운다.
+pub struct ProfileUpdate {
+    pub display_name: Option<Option<String>>,
+    pub bio: Option<Option<String>>,
+    pub pronouns: Option<Option<String>>,
+}
+
+#[async_trait]
+pub trait ProfileRepo: Send + Sync {
+    // 대소문자를 구분하지 않는다. 프로필 행이 없어도 사용자가 있으면 빈 프로필.
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<Profile>>;
+
+    // 표시 이름과 아바타 버전. 프로필 행이 없으면 None.
+    async fn display(&self, user_id: i32) -> RepoResult<Option<(Option<String>, Option<String>)>>;
+
+    async fn update(&self, user_id: i32, update: &ProfileUpdate) -> RepoResult<()>;
+
+    // None 이면 아바타를 지운다
+    async fn set_avatar(&self, user_id: i32, version: Option<&str>) -> RepoResult<()>;
+
+    // 아바타가 있는 사용자의 id 와 아바타 버전. 대소문자를 구분하지 않는다.
+    async fn avatar_by_name(&self, username: &str) -> RepoResult<Option<(i32, String)>>;
+
+    // 사용자가 정한 접속 상태와 상태 메시지. 사용자가 없으면 None.
+    async fn status(&self, user_id: i32) -> RepoResult<Option<(String, Option<String>)>>;
+
+    // status 가 None 이면 그대로 두고, message 의 바깥 None 은 그대로, Some(None) 은 지운다. 바뀐 뒤의 값.
+    async fn set_status(
+        &self,
+        user_id: i32,
+        status: Option<&str>,
+        message: Option<Option<&str>>,
+    ) -> RepoResult<Option<(String, Option<String>)>>;
+}
@@ -0,0 +1,46 @@
+// --- Web Push 구독 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PushSubscription {
+    pub id: i64,
+    pub endpoint: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// 알림을 보낼 곳과 암호화 키
+#[derive(Debug, Clone, FromRow)]
+pub struct PushTarget {
+    pub id: i64,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+}
+
+#[async_trait]
+pub trait PushRepo: Send + Sync {
+    async fn targets(&self, user_id: i32) -> RepoResult<Vec<PushTarget>>;
+
+    async fn mark_delivered(&self, id: i64) -> RepoResult<()>;
+
+    // 만료되거나 해지된 구독을 지운다
+    async fn remove(&self, id: i64) -> RepoResult<()>;
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PushSubscription>>;
+
+    // endpoint 를 뺀 사용자의 구독 수
+    async fn count_other(&self, user_id: i32, endpoint: &str) -> RepoResult<i64>;
+
+    // 같은 endpoint 가 있으면 키와 사용자를 바꾼다
+    async fn subscribe(&self, user_id: i32, endpoint: &str, p256dh: &str, auth: &str) -> RepoResult<PushSubscription>;
+
+    // 사용자 본인의 구독을 지웠으면 true
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+}
@@ -0,0 +1,56 @@
+// --- 리마인더 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct Reminder {
+    pub id: i64,
+    pub room: String,
+    pub target: String,
+    pub text: String,
+    pub remind_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 시각이 되어 꺼낸 리마인더
+#[derive(FromRow)]
+pub struct DueReminder {
+    pub id: i64,
+    pub user_id: i32,
+    pub username: String,
+    pub room: String,
+    pub target: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait ReminderRepo: Send + Sync {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        target: &str,
+        text: &str,
+        remind_at: DateTime<Utc>,
+    ) -> RepoResult<i64>;
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64>;
+
+    // 아직 보내지 않은 리마인더, 이른 것부터
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Reminder>>;
+
+    // 사용자 본인의 리마인더를 지웠으면 true
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+
+    // 시각이 된 리마인더를 limit 개까지 지우고 돌려준다
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueReminder>>;
+
+    // 가장 이른 예약 시각
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>>;
+}
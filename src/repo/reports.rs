@@ -0,0 +1,64 @@
+// --- 메시지 신고 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct Report {
+    pub id: i64,
+    // 메시지가 지워졌으면 null
+    pub message_id: Option<i64>,
+    pub room: String,
+    pub author_id: Option<i32>,
+    pub author: Option<String>,
+    pub content: Option<String>,
+    pub reporter: String,
+    pub reason: String,
+    pub status: String,
+    pub resolution: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 새 신고. 메시지가 나중에 지워져도 볼 수 있도록 방, 작성자, 본문을 복사해 둔다.
+pub struct NewReport<'a> {
+    pub message_id: i64,
+    pub room: &'a str,
+    pub author_id: Option<i32>,
+    pub content: Option<&'a str>,
+    pub reporter_id: i32,
+    pub reason: &'a str,
+}
+
+// 처리할 때 읽는 신고
+pub struct ReportState {
+    pub message_id: Option<i64>,
+    pub room: String,
+    pub author_id: Option<i32>,
+    pub reason: String,
+    pub status: String,
+}
+
+#[async_trait]
+pub trait ReportRepo: Send + Sync {
+    // 신고할 메시지의 (방, 작성자, 본문)
+    async fn message(&self, message_id: i64) -> RepoResult<Option<(String, Option<i32>, Option<String>)>>;
+
+    // 같은 사람이 같은 메시지를 이미 신고했으면 Conflict("report")
+    async fn create(&self, report: NewReport<'_>) -> RepoResult<i64>;
+
+    // status 의 신고 limit 개. 열린 신고는 오래된 것부터, 처리한 신고는 최근 것부터.
+    async fn list(&self, status: &str, limit: i64) -> RepoResult<Vec<Report>>;
+
+    async fn find(&self, id: i64) -> RepoResult<Option<ReportState>>;
+
+    // id 이거나 같은 메시지에 대한 열린 신고
+    async fn open_ids(&self, id: i64, message_id: Option<i64>) -> RepoResult<Vec<i64>>;
+
+    // 아직 열린 신고를 닫고 닫은 수를 돌려준다
+    async fn close(&self, ids: &[i64], status: &str, resolution: &str, resolved_by: i32) -> RepoResult<u64>;
+}
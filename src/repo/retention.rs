@@ -0,0 +1,44 @@
+// --- 메시지 보관 정책 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+// 방에 따로 정한 정책
+#[derive(Serialize, FromRow)]
+pub struct RoomPolicy {
+    pub room: String,
+    pub days: Option<i32>,
+    pub action: Option<String>,
+    pub exempt: bool,
+    pub updated_by: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 보관 기간이 지난 메시지를 한 번 처리한 결과
+pub struct RetentionBatch {
+    pub deleted: usize,
+    pub archived: usize,
+    // 저장소에서 지울 첨부 파일 키
+    pub attachment_keys: Vec<String>,
+}
+
+#[async_trait]
+pub trait RetentionRepo: Send + Sync {
+    async fn policy(&self, room: &str) -> RepoResult<Option<RoomPolicy>>;
+
+    // 방 이름 순
+    async fn policies(&self) -> RepoResult<Vec<RoomPolicy>>;
+
+    // 보관 예외 여부는 그대로 둔다
+    async fn set_policy(&self, room: &str, days: Option<i32>, action: Option<&str>, updated_by: i32) -> RepoResult<()>;
+
+    async fn set_exempt(&self, room: &str, exempt: bool, updated_by: i32) -> RepoResult<()>;
+
+    // 방 정책이 없으면 default_days, default_action 을 따른다. 기간이 지난 메시지를 limit 개까지 보관함으로 옮기거나
+    // 지우고, 그 알림과 첨부 파일 기록도 함께 지운다.
+    async fn sweep(&self, default_days: i32, default_action: &str, limit: i64) -> RepoResult<RetentionBatch>;
+}
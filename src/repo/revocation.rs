@@ -0,0 +1,21 @@
+// --- 폐기한 액세스 토큰 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::RepoResult;
+
+#[async_trait]
+pub trait RevocationRepo: Send + Sync {
+    // 이미 있으면 그대로 둔다
+    async fn revoke_token(&self, jti: &str, expires_at: DateTime<Utc>) -> RepoResult<()>;
+
+    // 아직 만료되지 않은 jti 와 원래 만료 시각
+    async fn revoked_tokens(&self) -> RepoResult<Vec<(String, DateTime<Utc>)>>;
+
+    // since 이후에 폐기된 세션과 폐기한 시각
+    async fn revoked_sessions(&self, since: DateTime<Utc>) -> RepoResult<Vec<(String, DateTime<Utc>)>>;
+
+    // 원래 만료 시각이 지난 항목을 지운다
+    async fn purge_tokens(&self) -> RepoResult<()>;
+}
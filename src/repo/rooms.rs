@@ -0,0 +1,45 @@
+// --- 방 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::RepoResult;
+
+#[derive(Debug)]
+pub struct RoomTopic {
+    pub topic: String,
+    // 정한 사람이 탈퇴했으면 None
+    pub set_by: Option<String>,
+    pub set_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoomOwner {
+    pub user_id: i32,
+    pub username: String,
+    pub added_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait RoomRepo: Send + Sync {
+    async fn topic(&self, room: &str) -> RepoResult<Option<RoomTopic>>;
+
+    // 정한 시각을 돌려준다
+    async fn set_topic(&self, room: &str, topic: &str, set_by: i32) -> RepoResult<DateTime<Utc>>;
+
+    // 지정된 순서대로
+    async fn owners(&self, room: &str) -> RepoResult<Vec<RoomOwner>>;
+
+    async fn is_owner(&self, room: &str, user_id: i32) -> RepoResult<bool>;
+
+    // 새로 지정했으면 true, 이미 주인이었으면 false
+    async fn add_owner(&self, room: &str, user_id: i32, added_by: i32) -> RepoResult<bool>;
+
+    // 주인이 아니었으면 false
+    async fn remove_owner(&self, room: &str, user_id: i32) -> RepoResult<bool>;
+
+    // 방의 메시지와 투표를 모두 지우고 (지운 메시지 수, 저장소에서 지울 첨부 파일 키) 를 돌려준다
+    async fn purge(&self, room: &str) -> RepoResult<(u64, Vec<String>)>;
+}
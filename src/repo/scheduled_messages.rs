@@ -0,0 +1,57 @@
+// --- 예약 메시지 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct ScheduledMessage {
+    pub id: i64,
+    pub room: String,
+    pub text: String,
+    pub format: String,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 시각이 되어 꺼낸 예약 메시지와 보낸 사람
+#[derive(FromRow)]
+pub struct DueMessage {
+    pub id: i64,
+    pub user_id: i32,
+    pub username: String,
+    pub is_bot: bool,
+    pub disabled: bool,
+    pub room: String,
+    pub content: String,
+    pub format: String,
+}
+
+#[async_trait]
+pub trait ScheduledMessageRepo: Send + Sync {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        content: &str,
+        format: &str,
+        send_at: DateTime<Utc>,
+    ) -> RepoResult<ScheduledMessage>;
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64>;
+
+    // 아직 보내지 않은 예약 메시지, 이른 것부터
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ScheduledMessage>>;
+
+    // 사용자 본인의 예약을 지웠으면 true
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool>;
+
+    // 시각이 된 예약을 limit 개까지 지우고 돌려준다
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueMessage>>;
+
+    // 가장 이른 예약 시각
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>>;
+}
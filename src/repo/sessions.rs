@@ -0,0 +1,62 @@
+// --- 로그인 세션과 리프레시 토큰 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+// 리프레시 토큰을 쓴 세션
+#[derive(FromRow)]
+pub struct RefreshedSession {
+    pub user_id: i32,
+    pub username: String,
+    pub session_id: String,
+    pub is_guest: bool,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct SessionInfo {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    #[sqlx(skip)]
+    pub current: bool,
+}
+
+// 리프레시 토큰의 계열(family_id)은 세션 id 와 같다. 토큰은 해시로만 주고받는다.
+#[async_trait]
+pub trait SessionRepo: Send + Sync {
+    async fn create(&self, id: &str, user_id: i32, user_agent: Option<&str>, ip: Option<&str>) -> RepoResult<()>;
+
+    async fn add_refresh_token(
+        &self,
+        user_id: i32,
+        session_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()>;
+
+    // 아직 쓰이지 않은 유효한 토큰이면 원자적으로 사용 처리하고 세션의 last_seen 을 갱신한다.
+    // 세션이 폐기됐거나 계정이 비활성화됐으면 None.
+    async fn use_refresh_token(&self, token_hash: &str) -> RepoResult<Option<RefreshedSession>>;
+
+    // 토큰이 속한 세션 id. used_only 면 이미 쓰인 토큰만 찾는다.
+    async fn refresh_token_session(&self, token_hash: &str, used_only: bool) -> RepoResult<Option<String>>;
+
+    // 세션과 그 리프레시 토큰을 폐기한다
+    async fn revoke(&self, id: &str) -> RepoResult<()>;
+
+    // 폐기되지 않은 세션 id (except 는 뺀다)
+    async fn active_ids(&self, user_id: i32, except: Option<&str>) -> RepoResult<Vec<String>>;
+
+    // seen_after 이후에 쓰인 활성 세션. 최근 것부터.
+    async fn list(&self, user_id: i32, seen_after: DateTime<Utc>) -> RepoResult<Vec<SessionInfo>>;
+
+    async fn is_active(&self, id: &str, user_id: i32) -> RepoResult<bool>;
+
+    async fn touch(&self, id: &str) -> RepoResult<()>;
+}
@@ -0,0 +1,27 @@
+// --- 스팸 기록 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Serialize, FromRow)]
+pub struct SpamFlag {
+    pub id: i64,
+    pub user_id: i32,
+    pub username: String,
+    pub room: String,
+    pub reason: String,
+    pub sample: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait SpamRepo: Send + Sync {
+    async fn record_flag(&self, user_id: i32, room: &str, reason: &str, sample: &str) -> RepoResult<()>;
+
+    // 최근 것부터 limit 개
+    async fn list_flags(&self, limit: i64) -> RepoResult<Vec<SpamFlag>>;
+}
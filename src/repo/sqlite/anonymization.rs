@@ -0,0 +1,275 @@
+use axum::async_trait;
+use chrono::Utc;
+use sqlx::{types::Json as SqlJson, SqliteConnection};
+
+use super::{placeholders, SqliteRepo};
+use crate::repo::{AnonymizationJob, AnonymizationRepo, RepoResult, Touched};
+
+const JOB_COLUMNS: &str = "id, status, touched, error, created_by, created_at, completed_at";
+
+// 메시지를 지운 것처럼 비운다. 첨부 파일 기록을 지우고 저장소에서 지울 키를 돌려준다.
+async fn scrub_messages(conn: &mut SqliteConnection, ids: &[i64], touched: &mut Touched) -> sqlx::Result<Vec<String>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let list = placeholders(1, ids.len());
+    let sql = format!(
+        "SELECT storage_key FROM attachments WHERE message_id IN ({0})
+         UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id IN ({0}) AND thumbnail_key IS NOT NULL",
+        list
+    );
+    let mut query = sqlx::query_scalar::<_, String>(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    let keys = query.fetch_all(&mut *conn).await?;
+
+    let sql = format!("DELETE FROM attachments WHERE message_id IN ({})", list);
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    touched.attachments += query.execute(&mut *conn).await?.rows_affected();
+
+    let sql = format!("UPDATE message_reports SET content = NULL WHERE message_id IN ({}) AND content IS NOT NULL", list);
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    touched.reports += query.execute(&mut *conn).await?.rows_affected();
+    Ok(keys)
+}
+
+// 내용이 patterns 중 하나에 맞는지. patterns 는 ?3 부터 받는다.
+fn like_any(column: &str, n: usize) -> String {
+    (3..3 + n).map(|i| format!("{} LIKE ?{} ESCAPE '\\'", column, i)).collect::<Vec<_>>().join(" OR ")
+}
+
+#[async_trait]
+impl AnonymizationRepo for SqliteRepo {
+    async fn create(&self, created_by: i32) -> RepoResult<Option<AnonymizationJob>> {
+        let job = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "INSERT INTO anonymization_jobs (status, created_by) VALUES ('running', ?1)
+             ON CONFLICT (status) WHERE status = 'running' DO NOTHING RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(created_by)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(job)
+    }
+
+    async fn list(&self, limit: i64) -> RepoResult<Vec<AnonymizationJob>> {
+        let jobs = sqlx::query_as::<_, AnonymizationJob>(&format!(
+            "SELECT {} FROM anonymization_jobs ORDER BY id DESC LIMIT ?1",
+            JOB_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(jobs)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<AnonymizationJob>> {
+        let job =
+            sqlx::query_as::<_, AnonymizationJob>(&format!("SELECT {} FROM anonymization_jobs WHERE id = ?1", JOB_COLUMNS))
+                .bind(id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(job)
+    }
+
+    async fn running(&self) -> RepoResult<Option<(i64, Touched)>> {
+        let running = sqlx::query_as::<_, (i64, SqlJson<Touched>)>(
+            "SELECT id, touched FROM anonymization_jobs WHERE status = 'running'",
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(running.map(|(id, touched)| (id, touched.0)))
+    }
+
+    async fn save_progress(&self, id: i64, touched: &Touched) -> RepoResult<()> {
+        sqlx::query("UPDATE anonymization_jobs SET touched = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(SqlJson(touched))
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn finish(&self, id: i64, status: &str, touched: &Touched, error: Option<&str>) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE anonymization_jobs SET status = ?2, touched = ?3, error = ?4, completed_at = ?5 WHERE id = ?1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(SqlJson(touched))
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_accounts(&self) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM deleted_accounts").fetch_one(&self.db).await?;
+        Ok(count)
+    }
+
+    async fn deleted_accounts(&self, limit: i64) -> RepoResult<Vec<(i32, String)>> {
+        let accounts = sqlx::query_as::<_, (i32, String)>(
+            "SELECT user_id, username FROM deleted_accounts ORDER BY user_id LIMIT ?1",
+        )
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(accounts)
+    }
+
+    async fn scrub_accounts(
+        &self,
+        ids: &[i32],
+        names: &[String],
+        deleted_name: &str,
+        touched: &mut Touched,
+    ) -> RepoResult<Vec<String>> {
+        let id_list = placeholders(2, ids.len());
+        let mut tx = self.db.begin().await?;
+
+        let sql = format!(
+            "UPDATE messages SET user_id = NULL, username = ?1, content = NULL, html = NULL
+             WHERE user_id IN ({}) RETURNING id",
+            id_list
+        );
+        let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(deleted_name);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let messages = query.fetch_all(&mut *tx).await?;
+        touched.messages += messages.len() as u64;
+        let mut keys = scrub_messages(&mut tx, &messages, touched).await?;
+
+        // 전달한 메시지는 원래 작성자의 글을 그대로 옮긴 것이므로 내용도 지운다
+        let sql = format!(
+            "UPDATE messages SET content = NULL, html = NULL,
+                 forwarded = json_set(forwarded, '$.user_id', NULL, '$.username', ?1)
+             WHERE json_extract(forwarded, '$.user_id') IN ({}) RETURNING id",
+            id_list
+        );
+        let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(deleted_name);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let forwards = query.fetch_all(&mut *tx).await?;
+        touched.forwards += forwards.len() as u64;
+        keys.extend(scrub_messages(&mut tx, &forwards, touched).await?);
+
+        // 투표의 user_id 는 계정을 지울 때 이미 NULL 이 되었다
+        let sql = format!(
+            "UPDATE polls SET username = ?1 WHERE user_id IS NULL AND username IN ({})",
+            placeholders(2, names.len())
+        );
+        let mut query = sqlx::query(&sql).bind(deleted_name);
+        for name in names {
+            query = query.bind(name);
+        }
+        touched.polls += query.execute(&mut *tx).await?.rows_affected();
+
+        let sql = format!(
+            "UPDATE archived_messages
+             SET message = json_set(message, '$.user_id', NULL, '$.username', ?1, '$.content', NULL, '$.html', NULL)
+             WHERE json_extract(message, '$.user_id') IN ({})",
+            id_list
+        );
+        let mut query = sqlx::query(&sql).bind(deleted_name);
+        for id in ids {
+            query = query.bind(id);
+        }
+        touched.archived += query.execute(&mut *tx).await?.rows_affected();
+        tx.commit().await?;
+        Ok(keys)
+    }
+
+    async fn forget_accounts(&self, ids: &[i32]) -> RepoResult<()> {
+        let sql = format!("DELETE FROM deleted_accounts WHERE user_id IN ({})", placeholders(1, ids.len()));
+        let mut query = sqlx::query(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        query.execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn scrub_anonymized(
+        &self,
+        deleted_name: &str,
+        limit: i64,
+        touched: &mut Touched,
+    ) -> RepoResult<(i64, Vec<String>)> {
+        let mut tx = self.db.begin().await?;
+        let messages = sqlx::query_scalar::<_, i64>(
+            "UPDATE messages SET content = NULL, html = NULL WHERE id IN (
+                 SELECT id FROM messages
+                 WHERE user_id IS NULL AND username = ?1 AND webhook_id IS NULL AND content IS NOT NULL
+                 ORDER BY id LIMIT ?2
+             ) RETURNING id",
+        )
+        .bind(deleted_name)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        touched.messages += messages.len() as u64;
+        let keys = scrub_messages(&mut tx, &messages, touched).await?;
+        tx.commit().await?;
+        Ok((messages.len() as i64, keys))
+    }
+
+    async fn mentioning_messages(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String, String)>> {
+        let sql = format!(
+            "SELECT id, content, format FROM messages WHERE ({}) AND id > ?1 ORDER BY id LIMIT ?2",
+            like_any("content", patterns.len())
+        );
+        let mut query = sqlx::query_as::<_, (i64, String, String)>(&sql).bind(after).bind(limit);
+        for pattern in patterns {
+            query = query.bind(pattern);
+        }
+        Ok(query.fetch_all(&self.db).await?)
+    }
+
+    async fn rewrite_message(&self, id: i64, content: &str, html: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE messages SET content = ?2, html = ?3 WHERE id = ?1")
+            .bind(id)
+            .bind(content)
+            .bind(html)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn mentioning_notifications(
+        &self,
+        patterns: &[String],
+        after: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<(i64, String)>> {
+        let sql = format!(
+            "SELECT id, text FROM notifications WHERE ({}) AND id > ?1 ORDER BY id LIMIT ?2",
+            like_any("text", patterns.len())
+        );
+        let mut query = sqlx::query_as::<_, (i64, String)>(&sql).bind(after).bind(limit);
+        for pattern in patterns {
+            query = query.bind(pattern);
+        }
+        Ok(query.fetch_all(&self.db).await?)
+    }
+
+    async fn rewrite_notification(&self, id: i64, text: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE notifications SET text = ?2 WHERE id = ?1").bind(id).bind(text).execute(&self.db).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json as SqlJson;
+
+use super::SqliteRepo;
+use crate::repo::{AuditEntry, AuditRepo, NewAuditEntry, RepoResult};
+
+#[async_trait]
+impl AuditRepo for SqliteRepo {
+    async fn record(&self, entry: &NewAuditEntry<'_>) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO audit_log (actor_id, actor_name, action, target_type, target_id, reason, details)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(entry.actor_id)
+        .bind(entry.actor_name)
+        .bind(entry.action)
+        .bind(entry.target_type)
+        .bind(&entry.target_id)
+        .bind(entry.reason)
+        .bind(SqlJson(&entry.details))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        since: Option<DateTime<Utc>>,
+        action: Option<&str>,
+        limit: i64,
+    ) -> RepoResult<Vec<AuditEntry>> {
+        let entries = sqlx::query_as::<_, AuditEntry>(
+            "SELECT id, actor_id, actor_name, action, target_type, target_id, reason, details, created_at
+             FROM audit_log
+             WHERE (?1 IS NULL OR created_at > ?1) AND (?2 IS NULL OR action = ?2)
+             ORDER BY id DESC LIMIT ?3",
+        )
+        .bind(since)
+        .bind(action)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(entries)
+    }
+}
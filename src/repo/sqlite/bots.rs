@@ -0,0 +1,163 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json as SqlJson, FromRow};
+
+use super::SqliteRepo;
+use crate::repo::{Bot, BotRepo, BotToken, BotTokenOwner, RepoError, RepoResult};
+
+const TOKEN_COLUMNS: &str = "id, name, scopes, created_at, last_used_at, expires_at, revoked_at";
+
+// scopes 는 JSON 배열 문자열로 저장한다
+#[derive(FromRow)]
+struct TokenRow {
+    id: i64,
+    name: String,
+    scopes: SqlJson<Vec<String>>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<TokenRow> for BotToken {
+    fn from(row: TokenRow) -> Self {
+        BotToken {
+            id: row.id,
+            name: row.name,
+            scopes: row.scopes.0,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+#[async_trait]
+impl BotRepo for SqliteRepo {
+    // RETURNING 에는 고치는 테이블의 열만 쓸 수 있으므로 봇 이름은 따로 읽는다
+    async fn authenticate(&self, token_hash: &str) -> RepoResult<Option<BotTokenOwner>> {
+        let token = sqlx::query_as::<_, (i64, i32, SqlJson<Vec<String>>, Option<DateTime<Utc>>)>(
+            "UPDATE bot_tokens SET last_used_at = ?2
+             WHERE token_hash = ?1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > ?2)
+               AND EXISTS (SELECT 1 FROM users u WHERE u.id = bot_id AND u.is_bot AND u.disabled_at IS NULL)
+             RETURNING id, bot_id, scopes, expires_at",
+        )
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+        let Some((token_id, bot_id, scopes, expires_at)) = token else {
+            return Ok(None);
+        };
+        let username = sqlx::query_scalar::<_, String>("SELECT username FROM users WHERE id = ?1")
+            .bind(bot_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(username.map(|username| BotTokenOwner { token_id, bot_id, username, scopes: scopes.0, expires_at }))
+    }
+
+    async fn is_owner(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let owned = sqlx::query_scalar::<_, bool>("SELECT bot_owner_id = ?2 FROM users WHERE id = ?1 AND is_bot")
+            .bind(bot_id)
+            .bind(owner_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(owned == Some(true))
+    }
+
+    async fn list(&self, owner_id: i32) -> RepoResult<Vec<Bot>> {
+        let bots = sqlx::query_as::<_, Bot>(
+            "SELECT id, username, created_at FROM users WHERE bot_owner_id = ?1 AND is_bot ORDER BY id",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bots)
+    }
+
+    async fn count(&self, owner_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM users WHERE bot_owner_id = ?1 AND is_bot")
+            .bind(owner_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn create(&self, owner_id: i32, username: &str) -> RepoResult<Bot> {
+        let created = sqlx::query_as::<_, Bot>(
+            "INSERT INTO users (username, is_bot, bot_owner_id) VALUES (?1, 1, ?2)
+             RETURNING id, username, created_at",
+        )
+        .bind(username)
+        .bind(owner_id)
+        .fetch_one(&self.db)
+        .await;
+        match created {
+            Ok(bot) => Ok(bot),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("username")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, bot_id: i32, owner_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM users WHERE id = ?1 AND bot_owner_id = ?2 AND is_bot")
+            .bind(bot_id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn tokens(&self, bot_id: i32) -> RepoResult<Vec<BotToken>> {
+        let sql = format!("SELECT {} FROM bot_tokens WHERE bot_id = ?1 ORDER BY id", TOKEN_COLUMNS);
+        let rows = sqlx::query_as::<_, TokenRow>(&sql).bind(bot_id).fetch_all(&self.db).await?;
+        Ok(rows.into_iter().map(BotToken::from).collect())
+    }
+
+    async fn active_token_count(&self, bot_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM bot_tokens WHERE bot_id = ?1 AND revoked_at IS NULL",
+        )
+        .bind(bot_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    async fn create_token(
+        &self,
+        bot_id: i32,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<BotToken> {
+        let sql = format!(
+            "INSERT INTO bot_tokens (bot_id, name, token_hash, scopes, expires_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             RETURNING {}",
+            TOKEN_COLUMNS
+        );
+        let row = sqlx::query_as::<_, TokenRow>(&sql)
+            .bind(bot_id)
+            .bind(name)
+            .bind(token_hash)
+            .bind(SqlJson(scopes))
+            .bind(expires_at)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(row.into())
+    }
+
+    async fn revoke_token(&self, token_id: i64, bot_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE bot_tokens SET revoked_at = ?3 WHERE id = ?1 AND bot_id = ?2 AND revoked_at IS NULL",
+        )
+        .bind(token_id)
+        .bind(bot_id)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
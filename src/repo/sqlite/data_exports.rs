@@ -0,0 +1,170 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{
+    DataExportRepo, ExportStatus, ExportedPasskey, ExportedSession, LinkedAccount, PersonalData, RepoResult, RoomActivity,
+    UserMessage,
+};
+
+const STATUS_COLUMNS: &str = "id, status, created_at, completed_at, expires_at";
+
+#[async_trait]
+impl DataExportRepo for SqliteRepo {
+    async fn purge_expired(&self) -> RepoResult<u64> {
+        let result = sqlx::query("DELETE FROM data_exports WHERE expires_at < ?1")
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn pending(&self, user_id: i32) -> RepoResult<Option<ExportStatus>> {
+        let sql = format!("SELECT {} FROM data_exports WHERE user_id = ?1 AND status = 'pending'", STATUS_COLUMNS);
+        let export = sqlx::query_as::<_, ExportStatus>(&sql).bind(user_id).fetch_optional(&self.db).await?;
+        Ok(export)
+    }
+
+    async fn create(&self, id: &str, user_id: i32, expires_at: DateTime<Utc>) -> RepoResult<ExportStatus> {
+        let sql = format!(
+            "INSERT INTO data_exports (id, user_id, expires_at) VALUES (?1, ?2, ?3) RETURNING {}",
+            STATUS_COLUMNS
+        );
+        let export = sqlx::query_as::<_, ExportStatus>(&sql)
+            .bind(id)
+            .bind(user_id)
+            .bind(expires_at)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(export)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ExportStatus>> {
+        let sql = format!(
+            "SELECT {} FROM data_exports WHERE user_id = ?1 AND expires_at > ?2 ORDER BY created_at DESC",
+            STATUS_COLUMNS
+        );
+        let exports = sqlx::query_as::<_, ExportStatus>(&sql)
+            .bind(user_id)
+            .bind(Utc::now())
+            .fetch_all(&self.db)
+            .await?;
+        Ok(exports)
+    }
+
+    async fn find(&self, id: &str, user_id: i32) -> RepoResult<Option<ExportStatus>> {
+        let sql = format!(
+            "SELECT {} FROM data_exports WHERE id = ?1 AND user_id = ?2 AND expires_at > ?3",
+            STATUS_COLUMNS
+        );
+        let export = sqlx::query_as::<_, ExportStatus>(&sql)
+            .bind(id)
+            .bind(user_id)
+            .bind(Utc::now())
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(export)
+    }
+
+    async fn archive(&self, id: &str, user_id: i32) -> RepoResult<Option<(String, Option<String>)>> {
+        let export = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT status, archive FROM data_exports WHERE id = ?1 AND user_id = ?2 AND expires_at > ?3",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(export)
+    }
+
+    async fn finish(&self, id: &str, status: &str, archive: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE data_exports SET status = ?2, archive = ?3, completed_at = ?4 WHERE id = ?1")
+            .bind(id)
+            .bind(status)
+            .bind(archive)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn personal_data(&self, user_id: i32) -> RepoResult<PersonalData> {
+        let (username, email, email_verified_at, created_at) = sqlx::query_as::<
+            _,
+            (String, Option<String>, Option<DateTime<Utc>>, DateTime<Utc>),
+        >("SELECT username, email, email_verified_at, created_at FROM users WHERE id = ?1")
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let (display_name, bio, pronouns) = sqlx::query_as::<_, (Option<String>, Option<String>, Option<String>)>(
+            "SELECT display_name, bio, pronouns FROM profiles WHERE user_id = ?1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .unwrap_or_default();
+
+        let two_factor = sqlx::query_scalar::<_, i32>(
+            "SELECT user_id FROM user_totp WHERE user_id = ?1 AND confirmed_at IS NOT NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .is_some();
+
+        let passkeys = sqlx::query_as::<_, ExportedPasskey>(
+            "SELECT name, created_at, last_used_at FROM passkeys WHERE user_id = ?1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let linked_accounts = sqlx::query_as::<_, LinkedAccount>(
+            "SELECT provider, email FROM oauth_identities WHERE user_id = ?1",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let sessions = sqlx::query_as::<_, ExportedSession>(
+            "SELECT user_agent, ip, created_at, last_seen FROM sessions WHERE user_id = ?1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        // 방 멤버십은 따로 저장하지 않으므로 글을 쓴 방을 기준으로 한다
+        let rooms = sqlx::query_as::<_, RoomActivity>(
+            "SELECT room, count(*) AS messages, min(created_at) AS first_message_at, max(created_at) AS last_message_at
+             FROM messages WHERE user_id = ?1 GROUP BY room ORDER BY room",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let messages = sqlx::query_as::<_, UserMessage>(
+            "SELECT id, room, content, created_at FROM messages WHERE user_id = ?1 ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(PersonalData {
+            username,
+            email,
+            email_verified_at,
+            created_at,
+            display_name,
+            bio,
+            pronouns,
+            two_factor,
+            passkeys,
+            linked_accounts,
+            sessions,
+            rooms,
+            messages,
+        })
+    }
+}
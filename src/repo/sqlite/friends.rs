@@ -0,0 +1,96 @@
+use axum::async_trait;
+use chrono::Utc;
+
+use super::SqliteRepo;
+use crate::repo::{ContactRow, FriendRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl FriendRepo for SqliteRepo {
+    async fn friend_ids(&self, user_id: i32) -> RepoResult<Vec<i32>> {
+        let ids = sqlx::query_scalar::<_, i32>(
+            "SELECT CASE WHEN requester_id = ?1 THEN addressee_id ELSE requester_id END
+             FROM friendships WHERE ?1 IN (requester_id, addressee_id) AND status = 'accepted'",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn contacts(&self, user_id: i32) -> RepoResult<Vec<ContactRow>> {
+        let rows = sqlx::query_as::<_, ContactRow>(
+            "SELECT u.id AS user_id, u.username, p.display_name, u.status, u.status_message,
+                    f.status = 'accepted' AS accepted,
+                    f.requester_id = ?1 AS outgoing, COALESCE(f.accepted_at, f.created_at) AS since
+             FROM friendships f
+             JOIN users u ON u.id = CASE WHEN f.requester_id = ?1 THEN f.addressee_id ELSE f.requester_id END
+             LEFT JOIN profiles p ON p.user_id = u.id
+             WHERE ?1 IN (f.requester_id, f.addressee_id)
+             ORDER BY lower(u.username)",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows)
+    }
+
+    async fn request(&self, requester_id: i32, addressee_id: i32) -> RepoResult<()> {
+        let created = sqlx::query("INSERT INTO friendships (requester_id, addressee_id) VALUES (?1, ?2)")
+            .bind(requester_id)
+            .bind(addressee_id)
+            .execute(&self.db)
+            .await;
+        match created {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("friendship")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // RETURNING 에는 고친 테이블의 열만 쓸 수 있으므로 이름은 따로 읽는다
+    async fn accept(&self, requester_id: i32, addressee_id: i32) -> RepoResult<Option<String>> {
+        let mut tx = self.db.begin().await?;
+        let accepted = sqlx::query(
+            "UPDATE friendships SET status = 'accepted', accepted_at = ?3
+             WHERE requester_id = ?1 AND addressee_id = ?2 AND status = 'pending'",
+        )
+        .bind(requester_id)
+        .bind(addressee_id)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+        if accepted.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let username = sqlx::query_scalar::<_, String>("SELECT username FROM users WHERE id = ?1")
+            .bind(requester_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(username)
+    }
+
+    async fn is_accepted(&self, a: i32, b: i32) -> RepoResult<Option<bool>> {
+        let accepted = sqlx::query_scalar::<_, bool>(
+            "SELECT status = 'accepted' FROM friendships
+             WHERE min(requester_id, addressee_id) = min(?1, ?2) AND max(requester_id, addressee_id) = max(?1, ?2)",
+        )
+        .bind(a)
+        .bind(b)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(accepted)
+    }
+
+    async fn delete(&self, a: i32, b: i32) -> RepoResult<bool> {
+        let deleted = sqlx::query(
+            "DELETE FROM friendships
+             WHERE (requester_id = ?1 AND addressee_id = ?2) OR (requester_id = ?2 AND addressee_id = ?1)",
+        )
+        .bind(a)
+        .bind(b)
+        .execute(&self.db)
+        .await?;
+        Ok(deleted.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,27 @@
+use axum::async_trait;
+
+use super::SqliteRepo;
+use crate::repo::{HealthRepo, RepoResult};
+
+#[async_trait]
+impl HealthRepo for SqliteRepo {
+    async fn ping(&self) -> RepoResult<()> {
+        sqlx::query("SELECT 1").execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> RepoResult<Vec<i64>> {
+        let exists = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+        )
+        .fetch_one(&self.db)
+        .await?;
+        if exists == 0 {
+            return Ok(Vec::new());
+        }
+        let versions = sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+            .fetch_all(&self.db)
+            .await?;
+        Ok(versions)
+    }
+}
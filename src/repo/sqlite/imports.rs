@@ -0,0 +1,151 @@
+use axum::async_trait;
+use chrono::Utc;
+
+use super::{messages, SqliteRepo};
+use crate::repo::{ImportJob, ImportProgress, ImportRepo, ImportedRow, RepoResult};
+
+const JOB_COLUMNS: &str = "id, source, room_prefix, status, rooms, users_created, messages_imported, messages_skipped,
+                           error, created_by, created_at, completed_at";
+
+#[async_trait]
+impl ImportRepo for SqliteRepo {
+    async fn create(&self, source: &str, room_prefix: &str, created_by: i32) -> RepoResult<ImportJob> {
+        let job = sqlx::query_as::<_, ImportJob>(&format!(
+            "INSERT INTO imports (source, room_prefix, created_by) VALUES (?1, ?2, ?3) RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(source)
+        .bind(room_prefix)
+        .bind(created_by)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(job)
+    }
+
+    async fn list(&self, limit: i64) -> RepoResult<Vec<ImportJob>> {
+        let jobs = sqlx::query_as::<_, ImportJob>(&format!("SELECT {} FROM imports ORDER BY id DESC LIMIT ?1", JOB_COLUMNS))
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(jobs)
+    }
+
+    async fn find(&self, id: i64) -> RepoResult<Option<ImportJob>> {
+        let job = sqlx::query_as::<_, ImportJob>(&format!("SELECT {} FROM imports WHERE id = ?1", JOB_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(job)
+    }
+
+    async fn set_status(&self, id: i64, status: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE imports SET status = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(status)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_progress(&self, id: i64, progress: ImportProgress) -> RepoResult<()> {
+        sqlx::query(
+            "UPDATE imports SET rooms = ?2, users_created = ?3, messages_imported = ?4, messages_skipped = ?5
+             WHERE id = ?1",
+        )
+        .bind(id)
+        .bind(progress.rooms)
+        .bind(progress.users_created)
+        .bind(progress.imported)
+        .bind(progress.skipped)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn finish(&self, id: i64, status: &str, error: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE imports SET status = ?2, error = ?3, completed_at = ?4 WHERE id = ?1")
+            .bind(id)
+            .bind(status)
+            .bind(error)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn imported_user(&self, imported_from: &str) -> RepoResult<Option<(i32, String)>> {
+        let user = sqlx::query_as::<_, (i32, String)>("SELECT id, username FROM users WHERE imported_from = ?1")
+            .bind(imported_from)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(user)
+    }
+
+    async fn create_placeholder(
+        &self,
+        username: &str,
+        bot: bool,
+        imported_from: &str,
+        display_name: Option<&str>,
+    ) -> RepoResult<Option<i32>> {
+        let mut tx = self.db.begin().await?;
+        let created = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO users (username, is_bot, imported_from) VALUES (?1, ?2, ?3)
+             ON CONFLICT DO NOTHING RETURNING id",
+        )
+        .bind(username)
+        .bind(bot)
+        .bind(imported_from)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let (Some(id), Some(display_name)) = (created, display_name) {
+            sqlx::query("INSERT INTO profiles (user_id, display_name) VALUES (?1, ?2) ON CONFLICT DO NOTHING")
+                .bind(id)
+                .bind(display_name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn set_topic(&self, room: &str, topic: &str) -> RepoResult<()> {
+        sqlx::query("INSERT INTO room_topics (room, topic) VALUES (?1, ?2) ON CONFLICT (room) DO NOTHING")
+            .bind(room)
+            .bind(topic)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_messages(&self, room: &str, messages: &[ImportedRow<'_>]) -> RepoResult<u64> {
+        let mut tx = self.db.begin().await?;
+        let mut inserted = 0;
+        for m in messages {
+            let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM messages WHERE imported_from = ?1")
+                .bind(&m.imported_from)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if exists.is_some() {
+                continue;
+            }
+            let id = messages::next_id(&mut tx).await?;
+            sqlx::query(
+                "INSERT INTO messages (id, user_id, username, room, content, created_at, imported_from)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .bind(id)
+            .bind(m.user_id)
+            .bind(m.username)
+            .bind(room)
+            .bind(m.text)
+            .bind(m.sent_at)
+            .bind(&m.imported_from)
+            .execute(&mut *tx)
+            .await?;
+            inserted += 1;
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+}
@@ -0,0 +1,62 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::SqliteConnection;
+
+use super::SqliteRepo;
+use crate::repo::{Invite, InviteRepo, RepoResult};
+
+// 초대 코드를 한 번 사용 처리한다. 가입과 같은 트랜잭션에서 부른다. 쓸 수 없는 코드면 None.
+pub(super) async fn redeem(conn: &mut SqliteConnection, code_hash: &str) -> sqlx::Result<Option<i64>> {
+    sqlx::query_scalar::<_, i64>(
+        "UPDATE invite_codes SET uses = uses + 1
+         WHERE code_hash = ?1 AND uses < max_uses AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > ?2)
+         RETURNING id",
+    )
+    .bind(code_hash)
+    .bind(Utc::now())
+    .fetch_optional(conn)
+    .await
+}
+
+#[async_trait]
+impl InviteRepo for SqliteRepo {
+    async fn create(
+        &self,
+        code_hash: &str,
+        created_by: i32,
+        max_uses: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<i64> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO invite_codes (code_hash, created_by, max_uses, expires_at) VALUES (?1, ?2, ?3, ?4)
+             RETURNING id",
+        )
+        .bind(code_hash)
+        .bind(created_by)
+        .bind(max_uses)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    async fn list(&self) -> RepoResult<Vec<Invite>> {
+        let invites = sqlx::query_as::<_, Invite>(
+            "SELECT id, created_by, max_uses, uses, expires_at, revoked_at, created_at
+             FROM invite_codes ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(invites)
+    }
+
+    async fn revoke(&self, id: i64) -> RepoResult<bool> {
+        let revoked = sqlx::query("UPDATE invite_codes SET revoked_at = ?2 WHERE id = ?1 AND revoked_at IS NULL")
+            .bind(id)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(revoked.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,65 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use std::net::IpAddr;
+
+use super::SqliteRepo;
+use crate::repo::{
+    ip_bans::{contains, normalize, parse_network},
+    IpBan, IpBanRepo, RepoError, RepoResult,
+};
+
+const BAN_COLUMNS: &str = "id, network, reason, created_by, expires_at, created_at";
+
+#[async_trait]
+impl IpBanRepo for SqliteRepo {
+    async fn is_banned(&self, ip: IpAddr) -> RepoResult<bool> {
+        // IPv4 에 대응된 IPv6 주소는 Postgres 처럼 IPv4 대역에 걸리지 않는다
+        let networks =
+            sqlx::query_scalar::<_, String>("SELECT network FROM ip_bans WHERE expires_at IS NULL OR expires_at > ?1")
+                .bind(Utc::now())
+                .fetch_all(&self.db)
+                .await?;
+        Ok(networks.iter().any(|network| contains(network, ip)))
+    }
+
+    async fn list(&self) -> RepoResult<Vec<IpBan>> {
+        let bans = sqlx::query_as::<_, IpBan>(&format!(
+            "SELECT {} FROM ip_bans WHERE expires_at IS NULL OR expires_at > ?1 ORDER BY created_at DESC",
+            BAN_COLUMNS
+        ))
+        .bind(Utc::now())
+        .fetch_all(&self.db)
+        .await?;
+        Ok(bans)
+    }
+
+    async fn create(
+        &self,
+        network: &str,
+        reason: Option<&str>,
+        created_by: i32,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepoResult<IpBan> {
+        let (addr, prefix) =
+            parse_network(network).ok_or_else(|| RepoError::Backend(format!("invalid network: {}", network)))?;
+        let ban = sqlx::query_as::<_, IpBan>(&format!(
+            "INSERT INTO ip_bans (network, reason, created_by, expires_at) VALUES (?1, ?2, ?3, ?4) RETURNING {}",
+            BAN_COLUMNS
+        ))
+        .bind(normalize(addr, prefix))
+        .bind(reason)
+        .bind(created_by)
+        .bind(expires_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(ban)
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<String>> {
+        let network = sqlx::query_scalar::<_, String>("DELETE FROM ip_bans WHERE id = ?1 RETURNING network")
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(network)
+    }
+}
@@ -0,0 +1,45 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{LinkPreview, LinkPreviewRepo, RepoResult};
+
+#[async_trait]
+impl LinkPreviewRepo for SqliteRepo {
+    async fn cached(&self, url: &str, fetched_after: DateTime<Utc>) -> RepoResult<Option<LinkPreview>> {
+        let preview = sqlx::query_as::<_, LinkPreview>(
+            "SELECT title, description, image_url, site_name FROM link_previews WHERE url = ?1 AND fetched_at > ?2",
+        )
+        .bind(url)
+        .bind(fetched_after)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(preview)
+    }
+
+    async fn save(&self, url: &str, preview: &LinkPreview) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO link_previews (url, title, description, image_url, site_name, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (url) DO UPDATE SET title = excluded.title, description = excluded.description,
+                 image_url = excluded.image_url, site_name = excluded.site_name, fetched_at = excluded.fetched_at",
+        )
+        .bind(url)
+        .bind(&preview.title)
+        .bind(&preview.description)
+        .bind(&preview.image_url)
+        .bind(&preview.site_name)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn purge(&self, fetched_before: DateTime<Utc>) -> RepoResult<u64> {
+        let result = sqlx::query("DELETE FROM link_previews WHERE fetched_at < ?1")
+            .bind(fetched_before)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,30 @@
+use axum::async_trait;
+use chrono::Utc;
+
+use super::SqliteRepo;
+use crate::repo::{Maintenance, MaintenanceRepo, RepoResult};
+
+#[async_trait]
+impl MaintenanceRepo for SqliteRepo {
+    async fn get(&self) -> RepoResult<Maintenance> {
+        let maintenance =
+            sqlx::query_as::<_, Maintenance>("SELECT enabled, message, updated_by, updated_at FROM maintenance")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(maintenance)
+    }
+
+    async fn set(&self, enabled: bool, message: Option<&str>, updated_by: i32) -> RepoResult<Maintenance> {
+        let maintenance = sqlx::query_as::<_, Maintenance>(
+            "UPDATE maintenance SET enabled = ?1, message = ?2, updated_by = ?3, updated_at = ?4
+             RETURNING enabled, message, updated_by, updated_at",
+        )
+        .bind(enabled)
+        .bind(message)
+        .bind(updated_by)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+        Ok(maintenance)
+    }
+}
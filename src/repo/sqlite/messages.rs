@@ -0,0 +1,213 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::SqliteConnection;
+
+use super::{placeholders, SqliteRepo};
+use crate::repo::{ExportRow, ExportedMessage, MessageRepo, MessageRow, NewMessage, RepoResult, StoredMessage};
+
+const MESSAGE_COLUMNS: &str =
+    "id, room, user_id, username, content AS text, format, html, webhook_id, forwarded, created_at, expires_at";
+
+// viewer 가 볼 수 있는 메시지만 남기는 조건. viewer 는 ?1, 지금 시각은 ?2 여야 한다.
+const VISIBLE: &str = "(NOT hidden OR user_id = ?1) AND (expires_at IS NULL OR expires_at > ?2)";
+
+// 메시지를 넣는 다른 저장소가 같은 트랜잭션에서 새 id 를 받을 때 쓴다
+pub(super) async fn next_id(conn: &mut SqliteConnection) -> sqlx::Result<i64> {
+    sqlx::query_scalar::<_, i64>("UPDATE message_id_sequence SET last_id = last_id + 1 RETURNING last_id")
+        .fetch_one(conn)
+        .await
+}
+
+impl SqliteRepo {
+    // viewer, 지금 시각, room, 기준 id, limit 을 차례로 받는 목록 조회
+    async fn page(
+        &self,
+        sql: &str,
+        viewer: i32,
+        room: &str,
+        bound: i64,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let rows = sqlx::query_as::<_, MessageRow>(sql)
+            .bind(viewer)
+            .bind(Utc::now())
+            .bind(room)
+            .bind(bound)
+            .bind(limit)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+}
+
+#[async_trait]
+impl MessageRepo for SqliteRepo {
+    async fn next_ids(&self, count: i64) -> RepoResult<Vec<i64>> {
+        let last = sqlx::query_scalar::<_, i64>(
+            "UPDATE message_id_sequence SET last_id = last_id + ?1 RETURNING last_id",
+        )
+        .bind(count)
+        .fetch_one(&self.db)
+        .await?;
+        Ok((last - count + 1..=last).collect())
+    }
+
+    async fn insert_batch(&self, batch: &[NewMessage]) -> RepoResult<()> {
+        let mut tx = self.db.begin().await?;
+        for m in batch {
+            let forwarded = m.forwarded.as_ref().and_then(|f| serde_json::to_string(f).ok());
+            sqlx::query(
+                "INSERT INTO messages
+                     (id, user_id, username, room, content, format, html, hidden, expires_at, forwarded, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT (id) DO NOTHING",
+            )
+            .bind(m.id)
+            .bind(m.user_id)
+            .bind(&m.username)
+            .bind(&m.room)
+            .bind(&m.text)
+            .bind(m.format.as_str())
+            .bind(&m.html)
+            .bind(m.hidden)
+            .bind(m.expires_at)
+            .bind(forwarded)
+            .bind(m.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_visible(&self, viewer: i32, id: i64) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND id = ?3",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn find_visible_many(&self, viewer: i32, ids: &[i64]) -> RepoResult<Vec<StoredMessage>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND id IN ({})",
+            MESSAGE_COLUMNS,
+            VISIBLE,
+            placeholders(3, ids.len())
+        );
+        let mut query = sqlx::query_as::<_, MessageRow>(&sql).bind(viewer).bind(Utc::now());
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&self.db).await?;
+        Ok(rows.into_iter().map(StoredMessage::from).collect())
+    }
+
+    async fn page_before(
+        &self,
+        viewer: i32,
+        room: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = ?3 AND id < ?4 ORDER BY id DESC LIMIT ?5",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        let mut messages = self.page(&sql, viewer, room, before.unwrap_or(i64::MAX), limit).await?;
+        messages.reverse();
+        Ok(messages)
+    }
+
+    async fn page_after(&self, viewer: i32, room: &str, after: i64, limit: i64) -> RepoResult<Vec<StoredMessage>> {
+        let sql = format!(
+            "SELECT {} FROM messages WHERE {} AND room = ?3 AND id > ?4 ORDER BY id LIMIT ?5",
+            MESSAGE_COLUMNS, VISIBLE
+        );
+        self.page(&sql, viewer, room, after, limit).await
+    }
+
+    async fn first_since(&self, viewer: i32, room: &str, at: DateTime<Utc>) -> RepoResult<Option<StoredMessage>> {
+        let row = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {} FROM messages WHERE {} AND room = ?3 AND created_at >= ?4 ORDER BY created_at, id LIMIT 1",
+            MESSAGE_COLUMNS, VISIBLE
+        ))
+        .bind(viewer)
+        .bind(Utc::now())
+        .bind(room)
+        .bind(at)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row.map(StoredMessage::from))
+    }
+
+    async fn export_page(&self, room: &str, after: i64, limit: i64) -> RepoResult<Vec<ExportedMessage>> {
+        let rows = sqlx::query_as::<_, ExportRow>(
+            "SELECT id, user_id, username, content AS text, format, hidden, webhook_id, forwarded, created_at, expires_at
+             FROM messages WHERE room = ?1 AND id > ?2 AND (expires_at IS NULL OR expires_at > ?4)
+             ORDER BY id LIMIT ?3",
+        )
+        .bind(room)
+        .bind(after)
+        .bind(limit)
+            .bind(Utc::now())
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.into_iter().map(ExportedMessage::from).collect())
+    }
+
+    async fn delete(&self, id: i64) -> RepoResult<Option<(String, Vec<String>)>> {
+        let mut tx = self.db.begin().await?;
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT storage_key FROM attachments WHERE message_id = ?1
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id = ?1 AND thumbnail_key IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let room = sqlx::query_scalar::<_, String>("DELETE FROM messages WHERE id = ?1 RETURNING room")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(room.map(|room| (room, keys)))
+    }
+
+    // 알림은 메시지가 지워지면 message_id 만 비므로 같은 트랜잭션에서 먼저 지운다
+    async fn expire_due(&self, limit: i64) -> RepoResult<Vec<(i64, String)>> {
+        const DUE: &str = "SELECT id FROM messages WHERE expires_at <= ?1 ORDER BY expires_at, id LIMIT ?2";
+        let now = Utc::now();
+        let mut tx = self.db.begin().await?;
+        sqlx::query(&format!("DELETE FROM notifications WHERE message_id IN ({})", DUE))
+            .bind(now)
+            .bind(limit)
+            .execute(&mut *tx)
+            .await?;
+        let expired =
+            sqlx::query_as::<_, (i64, String)>(&format!("DELETE FROM messages WHERE id IN ({}) RETURNING id, room", DUE))
+                .bind(now)
+                .bind(limit)
+                .fetch_all(&mut *tx)
+                .await?;
+        tx.commit().await?;
+        Ok(expired)
+    }
+
+    async fn next_expiry(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT min(expires_at) FROM messages WHERE expires_at IS NOT NULL",
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(next)
+    }
+}
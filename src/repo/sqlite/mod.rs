@@ -0,0 +1,107 @@
+// --- SQLite 저장소 ---
+//
+// `DATABASE_URL=sqlite:data/webchat.db` 처럼 sqlite: 로 시작하는 주소면 이 구현을 쓴다. 파일이 없으면 만들고
+// migrations_sqlite/ 의 마이그레이션을 연결할 때 적용한다. `DATABASE_URL=memory:` 이면 같은 구현을 메모리 속 DB
+// 로 연다.
+//
+// 서버와 명령줄 도구는 database.url 로 고른 저장소만 쓰므로 Postgres 없이도 뜬다. `webchat migrate` 는 파일 DB 를
+// 만들고 마이그레이션만 적용한다.
+//
+// SQLite 에는 now() 가 없고 시각을 RFC 3339 문자열로 저장하므로, 시각 비교는 모두 지금 시각을 인자로 넘겨 같은
+// 형식끼리 비교한다.
+
+mod accounts;
+mod anonymization;
+mod attachments;
+mod audit;
+mod bots;
+mod data_exports;
+mod friends;
+mod health;
+mod imports;
+mod invites;
+mod ip_bans;
+mod keywords;
+mod link_previews;
+mod maintenance;
+mod messages;
+mod notifications;
+mod oauth;
+mod passkeys;
+mod polls;
+mod profiles;
+mod push;
+mod reminders;
+mod reports;
+mod retention;
+mod revocation;
+mod rooms;
+mod scheduled_messages;
+mod sessions;
+mod spam;
+mod stars;
+mod throttle;
+mod tokens;
+mod two_factor;
+mod users;
+mod webhooks;
+mod word_filters;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+use std::str::FromStr;
+
+use crate::schema;
+
+const USER_COLUMNS: &str = "id, username, password_hash, is_admin, is_guest, is_bot, \
+     disabled_at IS NOT NULL AS disabled, CASE WHEN muted_until > ?1 THEN muted_until END AS muted_until, \
+     shadow_banned";
+
+// ?from 부터 n 개의 자리 표시자. `= ANY($1)` 대신 IN (...) 에 쓴다.
+fn placeholders(from: usize, n: usize) -> String {
+    (from..from + n).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ")
+}
+
+// 지금부터 secs 초 뒤 (음수면 앞)의 시각. Postgres 의 `now() + make_interval(secs => ...)` 자리에 쓴다.
+fn seconds_from_now(secs: f64) -> DateTime<Utc> {
+    Utc::now() + Duration::milliseconds((secs * 1000.0) as i64)
+}
+
+pub struct SqliteRepo {
+    db: SqlitePool,
+}
+
+impl SqliteRepo {
+    pub async fn connect(url: &str, max_connections: u32) -> Result<Self, String> {
+        let options = SqliteConnectOptions::from_str(url)
+            .map_err(|e| format!("Invalid SQLite URL: {}", e))?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let db = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await
+            .map_err(|e| format!("Failed to open the SQLite database: {}", e))?;
+        schema::SQLITE_MIGRATOR.run(&db).await.map_err(|e| format!("Failed to migrate the database: {}", e))?;
+        Ok(SqliteRepo { db })
+    }
+
+    // 프로세스 메모리에만 있는 빈 DB. 연결이 모두 닫히면 DB 도 사라지므로 연결 하나를 끝까지 붙들고 쓴다.
+    pub async fn memory() -> Result<Self, String> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .map_err(|e| format!("Invalid SQLite URL: {}", e))?
+            .foreign_keys(true);
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await
+            .map_err(|e| format!("Failed to open the in-memory database: {}", e))?;
+        schema::SQLITE_MIGRATOR.run(&db).await.map_err(|e| format!("Failed to migrate the database: {}", e))?;
+        Ok(SqliteRepo { db })
+    }
+}
@@ -0,0 +1,186 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, SqliteRepo};
+use crate::repo::{DigestMention, NewNotification, Notification, NotificationRepo, RepoResult};
+
+#[async_trait]
+impl NotificationRepo for SqliteRepo {
+    async fn create(&self, notification: &NewNotification<'_>) -> RepoResult<(i64, DateTime<Utc>)> {
+        let created = sqlx::query_as::<_, (i64, DateTime<Utc>)>(
+            "INSERT INTO notifications (user_id, kind, room, message_id, actor_id, text)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id, created_at",
+        )
+        .bind(notification.user_id)
+        .bind(notification.kind)
+        .bind(notification.room)
+        .bind(notification.message_id)
+        .bind(notification.actor_id)
+        .bind(notification.text)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(created)
+    }
+
+    async fn message_recipients(
+        &self,
+        room: &str,
+        author_id: i32,
+        mentioned: &[String],
+        watchers: &[i32],
+    ) -> RepoResult<Vec<(i32, bool, bool)>> {
+        let names = placeholders(3, mentioned.len());
+        let ids = placeholders(3 + mentioned.len(), watchers.len());
+        let sql = format!(
+            "SELECT u.id, lower(u.username) IN ({names}) AS mentioned, u.id IN ({ids}) AS keyword
+             FROM users u LEFT JOIN room_notification_prefs p ON p.user_id = u.id AND p.room = ?2
+             WHERE u.id <> ?1 AND u.disabled_at IS NULL
+               AND (((lower(u.username) IN ({names}) OR u.id IN ({ids})) AND COALESCE(p.level, 'mentions') <> 'mute')
+                    OR p.level = 'all')"
+        );
+        let mut query = sqlx::query_as::<_, (i32, bool, bool)>(&sql).bind(author_id).bind(room);
+        for name in mentioned {
+            query = query.bind(name);
+        }
+        for id in watchers {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.db).await?)
+    }
+
+    async fn purge(&self, created_before: DateTime<Utc>) -> RepoResult<u64> {
+        let purged = sqlx::query("DELETE FROM notifications WHERE created_at < ?1")
+            .bind(created_before)
+            .execute(&self.db)
+            .await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn list(
+        &self,
+        user_id: i32,
+        unread_only: bool,
+        before: Option<i64>,
+        limit: i64,
+    ) -> RepoResult<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT n.id, n.kind, n.room, n.message_id, n.actor_id, u.username AS actor, n.text, n.read_at, n.created_at
+             FROM notifications n LEFT JOIN users u ON u.id = n.actor_id
+             WHERE n.user_id = ?1 AND (NOT ?2 OR n.read_at IS NULL) AND (?3 IS NULL OR n.id < ?3)
+             ORDER BY n.id DESC LIMIT ?4",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(notifications)
+    }
+
+    async fn unread_count(&self, user_id: i32) -> RepoResult<i64> {
+        let unread = sqlx::query_scalar::<_, i64>(
+            "SELECT count(*) FROM notifications WHERE user_id = ?1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(unread)
+    }
+
+    async fn mark_read(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = COALESCE(read_at, ?3) WHERE id = ?1 AND user_id = ?2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn mark_all_read(&self, user_id: i32) -> RepoResult<u64> {
+        let result = sqlx::query("UPDATE notifications SET read_at = ?2 WHERE user_id = ?1 AND read_at IS NULL")
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn room_level(&self, user_id: i32, room: &str) -> RepoResult<Option<String>> {
+        let level = sqlx::query_scalar::<_, String>(
+            "SELECT level FROM room_notification_prefs WHERE user_id = ?1 AND room = ?2",
+        )
+        .bind(user_id)
+        .bind(room)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(level)
+    }
+
+    async fn set_room_level(&self, user_id: i32, room: &str, level: &str) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_notification_prefs (user_id, room, level) VALUES (?1, ?2, ?3)
+             ON CONFLICT (user_id, room) DO UPDATE SET level = excluded.level, updated_at = ?4",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(level)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending_digest(&self, created_before: DateTime<Utc>) -> RepoResult<Vec<DigestMention>> {
+        let mentions = sqlx::query_as::<_, DigestMention>(
+            "SELECT u.id AS user_id, u.email, n.room, a.username AS actor, n.text, n.created_at
+             FROM notifications n
+             JOIN users u ON u.id = n.user_id
+             LEFT JOIN users a ON a.id = n.actor_id
+             WHERE n.kind = 'mention' AND n.read_at IS NULL
+               AND n.created_at < ?1
+               AND (u.digest_sent_at IS NULL OR n.created_at > u.digest_sent_at)
+               AND (u.last_online_at IS NULL OR u.last_online_at < n.created_at)
+               AND u.email_digest AND u.email IS NOT NULL AND u.email_verified_at IS NOT NULL
+               AND u.disabled_at IS NULL
+             ORDER BY u.id, n.id",
+        )
+        .bind(created_before)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(mentions)
+    }
+
+    async fn mark_digest_sent(&self, user_id: i32, sent_until: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("UPDATE users SET digest_sent_at = ?2 WHERE id = ?1")
+            .bind(user_id)
+            .bind(sent_until)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn digest_enabled(&self, user_id: i32) -> RepoResult<Option<bool>> {
+        let enabled = sqlx::query_scalar::<_, bool>("SELECT email_digest FROM users WHERE id = ?1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(enabled)
+    }
+
+    async fn set_digest(&self, user_id: i32, enabled: bool) -> RepoResult<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET email_digest = ?2, digest_sent_at = CASE WHEN ?2 THEN digest_sent_at ELSE ?3 END
+             WHERE id = ?1",
+        )
+        .bind(user_id)
+        .bind(enabled)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
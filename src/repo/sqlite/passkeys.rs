@@ -0,0 +1,101 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{PasskeyInfo, PasskeyRepo, RepoError, RepoResult};
+
+#[async_trait]
+impl PasskeyRepo for SqliteRepo {
+    async fn save_ceremony(
+        &self,
+        token_hash: &str,
+        user_id: i32,
+        kind: &str,
+        state: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("DELETE FROM webauthn_ceremonies WHERE expires_at < ?1")
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        sqlx::query(
+            "INSERT INTO webauthn_ceremonies (token_hash, user_id, kind, state, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(kind)
+        .bind(state)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn take_ceremony(&self, token_hash: &str, kind: &str) -> RepoResult<Option<(i32, String)>> {
+        let row = sqlx::query_as::<_, (i32, String)>(
+            "DELETE FROM webauthn_ceremonies WHERE token_hash = ?1 AND kind = ?2 AND expires_at > ?3
+             RETURNING user_id, state",
+        )
+        .bind(token_hash)
+        .bind(kind)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(row)
+    }
+
+    async fn credentials(&self, user_id: i32) -> RepoResult<Vec<(i64, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String)>("SELECT id, passkey FROM passkeys WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_all(&self.db)
+            .await?;
+        Ok(rows)
+    }
+
+    async fn add(&self, user_id: i32, credential_id: &str, passkey: &str, name: &str) -> RepoResult<i64> {
+        let stored = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO passkeys (user_id, credential_id, passkey, name) VALUES (?1, ?2, ?3, ?4) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(credential_id)
+        .bind(passkey)
+        .bind(name)
+        .fetch_one(&self.db)
+        .await;
+        match stored {
+            Ok(id) => Ok(id),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(RepoError::Conflict("passkey")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<PasskeyInfo>> {
+        let keys = sqlx::query_as::<_, PasskeyInfo>(
+            "SELECT id, name, created_at, last_used_at FROM passkeys WHERE user_id = ?1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(keys)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM passkeys WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn mark_used(&self, id: i64, passkey: Option<&str>) -> RepoResult<()> {
+        sqlx::query("UPDATE passkeys SET last_used_at = ?3, passkey = COALESCE(?2, passkey) WHERE id = ?1")
+            .bind(id)
+            .bind(passkey)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
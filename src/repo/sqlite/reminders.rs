@@ -0,0 +1,78 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{DueReminder, Reminder, ReminderRepo, RepoResult};
+
+#[async_trait]
+impl ReminderRepo for SqliteRepo {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        target: &str,
+        text: &str,
+        remind_at: DateTime<Utc>,
+    ) -> RepoResult<i64> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO reminders (user_id, room, target, text, remind_at) VALUES (?1, ?2, ?3, ?4, ?5) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(target)
+        .bind(text)
+        .bind(remind_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM reminders WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<Reminder>> {
+        let reminders = sqlx::query_as::<_, Reminder>(
+            "SELECT id, room, target, text, remind_at, created_at FROM reminders WHERE user_id = ?1 ORDER BY remind_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(reminders)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM reminders WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    // 쓰기는 한 번에 하나뿐이라 잠글 필요가 없다. 이름은 RETURNING 안의 부분 질의로 읽는다.
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueReminder>> {
+        let due = sqlx::query_as::<_, DueReminder>(
+            "DELETE FROM reminders
+             WHERE id IN (SELECT id FROM reminders WHERE remind_at <= ?1 ORDER BY remind_at LIMIT ?2)
+             RETURNING id, user_id, (SELECT username FROM users u WHERE u.id = reminders.user_id) AS username,
+                       room, target, text, created_at",
+        )
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(due)
+    }
+
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT min(remind_at) FROM reminders")
+            .fetch_one(&self.db)
+            .await?;
+        Ok(next)
+    }
+}
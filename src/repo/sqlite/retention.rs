@@ -0,0 +1,122 @@
+use axum::async_trait;
+use chrono::Utc;
+
+use super::{placeholders, SqliteRepo};
+use crate::repo::{RepoResult, RetentionBatch, RetentionRepo, RoomPolicy};
+
+const POLICY_COLUMNS: &str = "room, days, action, exempt, updated_by, updated_at";
+
+// Postgres 의 to_jsonb(m) 과 같은 모양으로 메시지 행을 JSON 으로 만든다
+const MESSAGE_JSON: &str = "json_object('id', id, 'user_id', user_id, 'username', username, 'room', room,
+     'content', content, 'created_at', created_at, 'format', format, 'html', html,
+     'hidden', json(CASE WHEN hidden THEN 'true' ELSE 'false' END), 'webhook_id', webhook_id,
+     'expires_at', expires_at, 'forwarded', json(forwarded), 'imported_from', imported_from)";
+
+#[async_trait]
+impl RetentionRepo for SqliteRepo {
+    async fn policy(&self, room: &str) -> RepoResult<Option<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention WHERE room = ?1", POLICY_COLUMNS);
+        let policy = sqlx::query_as::<_, RoomPolicy>(&sql).bind(room).fetch_optional(&self.db).await?;
+        Ok(policy)
+    }
+
+    async fn policies(&self) -> RepoResult<Vec<RoomPolicy>> {
+        let sql = format!("SELECT {} FROM room_retention ORDER BY room", POLICY_COLUMNS);
+        let policies = sqlx::query_as::<_, RoomPolicy>(&sql).fetch_all(&self.db).await?;
+        Ok(policies)
+    }
+
+    async fn set_policy(&self, room: &str, days: Option<i32>, action: Option<&str>, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, days, action, updated_by, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (room) DO UPDATE SET days = excluded.days, action = excluded.action,
+                 updated_by = excluded.updated_by, updated_at = excluded.updated_at",
+        )
+        .bind(room)
+        .bind(days)
+        .bind(action)
+        .bind(updated_by)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_exempt(&self, room: &str, exempt: bool, updated_by: i32) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO room_retention (room, exempt, updated_by, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (room) DO UPDATE SET exempt = excluded.exempt, updated_by = excluded.updated_by,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(room)
+        .bind(exempt)
+        .bind(updated_by)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // 방마다 기간이 달라 기준 시각은 SQL 안에서 계산한다
+    async fn sweep(&self, default_days: i32, default_action: &str, limit: i64) -> RepoResult<RetentionBatch> {
+        let mut tx = self.db.begin().await?;
+        let expired = sqlx::query_as::<_, (i64, bool)>(
+            "SELECT m.id, COALESCE(r.action, ?2) = 'archive'
+             FROM messages m LEFT JOIN room_retention r ON r.room = m.room
+             WHERE m.room IS NOT NULL AND NOT COALESCE(r.exempt, 0) AND COALESCE(r.days, ?1) > 0
+               AND m.created_at < strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now', '-' || COALESCE(r.days, ?1) || ' days')
+             ORDER BY m.id LIMIT ?3",
+        )
+        .bind(default_days)
+        .bind(default_action)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        if expired.is_empty() {
+            return Ok(RetentionBatch { deleted: 0, archived: 0, attachment_keys: Vec::new() });
+        }
+        let ids: Vec<i64> = expired.iter().map(|(id, _)| *id).collect();
+        let to_archive: Vec<i64> = expired.iter().filter(|(_, archive)| *archive).map(|(id, _)| *id).collect();
+        let in_ids = placeholders(1, ids.len());
+
+        if !to_archive.is_empty() {
+            let sql = format!(
+                "INSERT INTO archived_messages (id, room, created_at, message)
+                 SELECT id, room, created_at, {} FROM messages WHERE id IN ({})
+                 ON CONFLICT (id) DO NOTHING",
+                MESSAGE_JSON,
+                placeholders(1, to_archive.len())
+            );
+            let mut query = sqlx::query(&sql);
+            for id in &to_archive {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        // 메시지를 지우면 첨부 파일 기록도 함께 지워지므로 키를 먼저 모은다
+        let sql = format!(
+            "SELECT storage_key FROM attachments WHERE message_id IN ({0})
+             UNION ALL SELECT thumbnail_key FROM attachments WHERE message_id IN ({0}) AND thumbnail_key IS NOT NULL",
+            in_ids
+        );
+        let mut query = sqlx::query_scalar::<_, String>(&sql);
+        for id in &ids {
+            query = query.bind(id);
+        }
+        let keys = query.fetch_all(&mut *tx).await?;
+        for table in ["notifications WHERE message_id", "messages WHERE id"] {
+            let sql = format!("DELETE FROM {} IN ({})", table, in_ids);
+            let mut query = sqlx::query(&sql);
+            for id in &ids {
+                query = query.bind(id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(RetentionBatch {
+            deleted: ids.len() - to_archive.len(),
+            archived: to_archive.len(),
+            attachment_keys: keys,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{DueMessage, RepoResult, ScheduledMessage, ScheduledMessageRepo};
+
+#[async_trait]
+impl ScheduledMessageRepo for SqliteRepo {
+    async fn create(
+        &self,
+        user_id: i32,
+        room: &str,
+        content: &str,
+        format: &str,
+        send_at: DateTime<Utc>,
+    ) -> RepoResult<ScheduledMessage> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "INSERT INTO scheduled_messages (user_id, room, content, format, send_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             RETURNING id, room, content AS text, format, send_at, created_at",
+        )
+        .bind(user_id)
+        .bind(room)
+        .bind(content)
+        .bind(format)
+        .bind(send_at)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(scheduled)
+    }
+
+    async fn count(&self, user_id: i32) -> RepoResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count(*) FROM scheduled_messages WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(count)
+    }
+
+    async fn list(&self, user_id: i32) -> RepoResult<Vec<ScheduledMessage>> {
+        let scheduled = sqlx::query_as::<_, ScheduledMessage>(
+            "SELECT id, room, content AS text, format, send_at, created_at FROM scheduled_messages
+             WHERE user_id = ?1 ORDER BY send_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(scheduled)
+    }
+
+    async fn delete(&self, id: i64, user_id: i32) -> RepoResult<bool> {
+        let result = sqlx::query("DELETE FROM scheduled_messages WHERE id = ?1 AND user_id = ?2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    // 보낸 사람의 정보는 RETURNING 안의 부분 질의로 읽는다
+    async fn take_due(&self, limit: i64) -> RepoResult<Vec<DueMessage>> {
+        let due = sqlx::query_as::<_, DueMessage>(
+            "DELETE FROM scheduled_messages
+             WHERE id IN (SELECT id FROM scheduled_messages WHERE send_at <= ?1 ORDER BY send_at LIMIT ?2)
+             RETURNING id, user_id,
+                       (SELECT username FROM users u WHERE u.id = scheduled_messages.user_id) AS username,
+                       (SELECT is_bot FROM users u WHERE u.id = scheduled_messages.user_id) AS is_bot,
+                       (SELECT disabled_at IS NOT NULL FROM users u WHERE u.id = scheduled_messages.user_id)
+                           AS disabled,
+                       room, content, format",
+        )
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(due)
+    }
+
+    async fn next_due(&self) -> RepoResult<Option<DateTime<Utc>>> {
+        let next = sqlx::query_scalar::<_, Option<DateTime<Utc>>>("SELECT min(send_at) FROM scheduled_messages")
+            .fetch_one(&self.db)
+            .await?;
+        Ok(next)
+    }
+}
@@ -0,0 +1,132 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{RefreshedSession, RepoResult, SessionInfo, SessionRepo};
+
+#[async_trait]
+impl SessionRepo for SqliteRepo {
+    async fn create(&self, id: &str, user_id: i32, user_agent: Option<&str>, ip: Option<&str>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO sessions (id, user_id, user_agent, ip) VALUES (?1, ?2, ?3, ?4)")
+            .bind(id)
+            .bind(user_id)
+            .bind(user_agent)
+            .bind(ip)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_refresh_token(
+        &self,
+        user_id: i32,
+        session_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(user_id)
+        .bind(session_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // UPDATE 에는 다른 테이블을 붙일 수 없으므로 세션과 계정은 조건 속 하위 질의로 확인하고 이름은 따로 읽는다
+    async fn use_refresh_token(&self, token_hash: &str) -> RepoResult<Option<RefreshedSession>> {
+        let rotated = sqlx::query_as::<_, (i32, String)>(
+            "UPDATE refresh_tokens SET used_at = ?2
+             WHERE token_hash = ?1 AND used_at IS NULL AND revoked_at IS NULL AND expires_at > ?2
+               AND EXISTS (SELECT 1 FROM sessions s WHERE s.id = family_id AND s.revoked_at IS NULL)
+               AND EXISTS (SELECT 1 FROM users u WHERE u.id = user_id AND u.disabled_at IS NULL)
+             RETURNING user_id, family_id",
+        )
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+        let Some((user_id, session_id)) = rotated else {
+            return Ok(None);
+        };
+        self.touch(&session_id).await?;
+        let user = sqlx::query_as::<_, (String, bool)>("SELECT username, is_guest FROM users WHERE id = ?1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        Ok(user.map(|(username, is_guest)| RefreshedSession { user_id, username, session_id, is_guest }))
+    }
+
+    async fn refresh_token_session(&self, token_hash: &str, used_only: bool) -> RepoResult<Option<String>> {
+        let session_id = sqlx::query_scalar::<_, String>(
+            "SELECT family_id FROM refresh_tokens WHERE token_hash = ?1 AND (NOT ?2 OR used_at IS NOT NULL)",
+        )
+        .bind(token_hash)
+        .bind(used_only)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(session_id)
+    }
+
+    async fn revoke(&self, id: &str) -> RepoResult<()> {
+        let now = Utc::now();
+        sqlx::query("UPDATE sessions SET revoked_at = ?2 WHERE id = ?1 AND revoked_at IS NULL")
+            .bind(id)
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+        sqlx::query("UPDATE refresh_tokens SET revoked_at = ?2 WHERE family_id = ?1 AND revoked_at IS NULL")
+            .bind(id)
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn active_ids(&self, user_id: i32, except: Option<&str>) -> RepoResult<Vec<String>> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM sessions WHERE user_id = ?1 AND revoked_at IS NULL AND (?2 IS NULL OR id <> ?2)",
+        )
+        .bind(user_id)
+        .bind(except)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn list(&self, user_id: i32, seen_after: DateTime<Utc>) -> RepoResult<Vec<SessionInfo>> {
+        let sessions = sqlx::query_as::<_, SessionInfo>(
+            "SELECT id, user_agent, ip, created_at, last_seen FROM sessions
+             WHERE user_id = ?1 AND revoked_at IS NULL AND last_seen > ?2
+             ORDER BY last_seen DESC",
+        )
+        .bind(user_id)
+        .bind(seen_after)
+        .fetch_all(&self.db)
+        .await?;
+        Ok(sessions)
+    }
+
+    async fn is_active(&self, id: &str, user_id: i32) -> RepoResult<bool> {
+        let active = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(active)
+    }
+
+    async fn touch(&self, id: &str) -> RepoResult<()> {
+        sqlx::query("UPDATE sessions SET last_seen = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{placeholders, seconds_from_now, SqliteRepo};
+use crate::repo::{RepoResult, ThrottleRepo};
+
+#[async_trait]
+impl ThrottleRepo for SqliteRepo {
+    async fn retry_after(&self, keys: &[String]) -> RepoResult<Option<f64>> {
+        if keys.is_empty() {
+            return Ok(None);
+        }
+        let sql = format!(
+            "SELECT max(locked_until) FROM auth_throttle WHERE locked_until > ?1 AND key IN ({})",
+            placeholders(2, keys.len())
+        );
+        let now = Utc::now();
+        let mut query = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(&sql).bind(now);
+        for key in keys {
+            query = query.bind(key);
+        }
+        let locked_until = query.fetch_one(&self.db).await?;
+        Ok(locked_until.map(|until| (until - now).num_milliseconds() as f64 / 1000.0))
+    }
+
+    async fn record_failure(&self, key: &str, window: f64) -> RepoResult<i32> {
+        let failures = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO auth_throttle (key, failures, last_failure_at) VALUES (?1, 1, ?2)
+             ON CONFLICT (key) DO UPDATE SET
+                 failures = CASE WHEN auth_throttle.last_failure_at < ?3 THEN 1 ELSE auth_throttle.failures + 1 END,
+                 last_failure_at = ?2
+             RETURNING failures",
+        )
+        .bind(key)
+        .bind(Utc::now())
+        .bind(seconds_from_now(-window))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(failures)
+    }
+
+    async fn lock(&self, key: &str, seconds: f64) -> RepoResult<()> {
+        sqlx::query("UPDATE auth_throttle SET locked_until = ?2 WHERE key = ?1")
+            .bind(key)
+            .bind(seconds_from_now(seconds))
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> RepoResult<()> {
+        sqlx::query("DELETE FROM auth_throttle WHERE key = ?1").bind(key).execute(&self.db).await?;
+        Ok(())
+    }
+
+    async fn purge(&self, window: f64) -> RepoResult<()> {
+        sqlx::query(
+            "DELETE FROM auth_throttle
+             WHERE last_failure_at < ?1 AND (locked_until IS NULL OR locked_until < ?2)",
+        )
+        .bind(seconds_from_now(-window))
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
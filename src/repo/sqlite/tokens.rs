@@ -0,0 +1,134 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::SqliteRepo;
+use crate::repo::{RepoResult, TokenRepo};
+
+#[async_trait]
+impl TokenRepo for SqliteRepo {
+    async fn add_password_reset(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> RepoResult<()> {
+        sqlx::query("INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES (?1, ?2, ?3)")
+            .bind(user_id)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_password_reset(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let user_id = sqlx::query_scalar::<_, i32>(
+            "UPDATE password_reset_tokens SET used_at = ?2
+             WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2
+             RETURNING user_id",
+        )
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn expire_password_resets(&self, user_id: i32) -> RepoResult<()> {
+        sqlx::query("UPDATE password_reset_tokens SET used_at = ?2 WHERE user_id = ?1 AND used_at IS NULL")
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn add_email_verification(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query(
+            "INSERT INTO email_verification_tokens (user_id, email, token_hash, expires_at) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    // WITH 안에서 UPDATE 를 쓸 수 없으므로 토큰을 쓴 뒤 같은 트랜잭션에서 주소를 확인 처리한다.
+    // 주소가 바뀌었어도 토큰은 쓴 것으로 남긴다.
+    async fn use_email_verification(&self, token_hash: &str) -> RepoResult<Option<i32>> {
+        let now = Utc::now();
+        let mut tx = self.db.begin().await?;
+        let used = sqlx::query_as::<_, (i32, String)>(
+            "UPDATE email_verification_tokens SET used_at = ?2
+             WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2
+             RETURNING user_id, email",
+        )
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, email)) = used else {
+            return Ok(None);
+        };
+        let verified = sqlx::query_scalar::<_, i32>(
+            "UPDATE users SET email_verified_at = ?3 WHERE id = ?1 AND email = ?2 RETURNING id",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(verified)
+    }
+
+    async fn add_magic_link(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()> {
+        sqlx::query("INSERT INTO magic_links (user_id, email, token_hash, expires_at) VALUES (?1, ?2, ?3, ?4)")
+            .bind(user_id)
+            .bind(email)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> RepoResult<Option<(i32, String)>> {
+        let now = Utc::now();
+        let mut tx = self.db.begin().await?;
+        let used = sqlx::query_as::<_, (i32, String)>(
+            "UPDATE magic_links SET used_at = ?2
+             WHERE token_hash = ?1 AND used_at IS NULL AND expires_at > ?2 AND user_id IS NOT NULL
+             RETURNING user_id, email",
+        )
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, email)) = used else {
+            return Ok(None);
+        };
+        let user = sqlx::query_as::<_, (i32, String)>(
+            "UPDATE users SET email_verified_at = COALESCE(email_verified_at, ?3)
+             WHERE id = ?1 AND lower(email) = lower(?2)
+             RETURNING id, username",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(user)
+    }
+}
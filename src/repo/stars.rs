@@ -0,0 +1,18 @@
+// --- 별표 메시지 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::RepoResult;
+
+#[async_trait]
+pub trait StarRepo: Send + Sync {
+    // 이미 별표가 있으면 그대로 둔다
+    async fn star(&self, user_id: i32, message_id: i64) -> RepoResult<()>;
+
+    // 별표를 뗐으면 true
+    async fn unstar(&self, user_id: i32, message_id: i64) -> RepoResult<bool>;
+
+    // before 보다 먼저 붙인 별표 (id, message_id, 붙인 시각), 최근 것부터
+    async fn list(&self, user_id: i32, before: Option<i64>, limit: i64) -> RepoResult<Vec<(i64, i64, DateTime<Utc>)>>;
+}
@@ -0,0 +1,22 @@
+// --- 로그인/가입 시도 카운터 ---
+
+use axum::async_trait;
+
+use super::RepoResult;
+
+// 시간은 모두 지금부터의 초로 주고받는다
+#[async_trait]
+pub trait ThrottleRepo: Send + Sync {
+    // keys 중 잠긴 것이 있으면 가장 늦게 풀리는 것까지 남은 초
+    async fn retry_after(&self, keys: &[String]) -> RepoResult<Option<f64>>;
+
+    // 실패를 하나 세고 센 값을 돌려준다. 마지막 실패가 window 초보다 오래됐으면 1 부터 다시 센다.
+    async fn record_failure(&self, key: &str, window: f64) -> RepoResult<i32>;
+
+    async fn lock(&self, key: &str, seconds: f64) -> RepoResult<()>;
+
+    async fn reset(&self, key: &str) -> RepoResult<()>;
+
+    // 마지막 실패가 window 초보다 오래됐고 잠겨 있지 않은 카운터를 지운다
+    async fn purge(&self, window: f64) -> RepoResult<()>;
+}
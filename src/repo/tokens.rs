@@ -0,0 +1,40 @@
+// --- 메일로 보내는 일회용 토큰 (비밀번호 재설정, 주소 확인, 매직 링크) ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::RepoResult;
+
+// 토큰은 해시로만 주고받는다. use_* 는 아직 쓰이지 않은 유효한 토큰을 원자적으로 사용 처리한다.
+#[async_trait]
+pub trait TokenRepo: Send + Sync {
+    async fn add_password_reset(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> RepoResult<()>;
+
+    // 토큰을 발급받은 사용자
+    async fn use_password_reset(&self, token_hash: &str) -> RepoResult<Option<i32>>;
+
+    // 사용자에게 발급된 다른 재설정 토큰도 쓸 수 없게 한다
+    async fn expire_password_resets(&self, user_id: i32) -> RepoResult<()>;
+
+    async fn add_email_verification(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()>;
+
+    // 발급할 때의 주소가 그대로면 확인 처리하고 사용자 id 를 돌려준다
+    async fn use_email_verification(&self, token_hash: &str) -> RepoResult<Option<i32>>;
+
+    async fn add_magic_link(
+        &self,
+        user_id: i32,
+        email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepoResult<()>;
+
+    // 링크를 요청한 계정의 주소가 그대로면 주소를 확인 처리하고 (id, 이름) 을 돌려준다
+    async fn use_magic_link(&self, token_hash: &str) -> RepoResult<Option<(i32, String)>>;
+}
@@ -0,0 +1,40 @@
+// --- 2단계 인증 (TOTP 비밀키와 로그인 challenge) ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::RepoResult;
+
+// 비밀키는 확인(confirm)되기 전까지 로그인에 쓰이지 않는다. challenge 는 해시로만 주고받는다.
+#[async_trait]
+pub trait TwoFactorRepo: Send + Sync {
+    // 확인을 마친 비밀키가 있는지
+    async fn is_enabled(&self, user_id: i32) -> RepoResult<bool>;
+
+    // 만료된 challenge 를 정리하고 새로 저장한다
+    async fn add_challenge(&self, token_hash: &str, user_id: i32, expires_at: DateTime<Utc>) -> RepoResult<()>;
+
+    // 시도 횟수를 올리고 challenge 의 사용자를 돌려준다 (만료됐거나 횟수를 다 썼으면 None)
+    async fn attempt_challenge(&self, token_hash: &str, max_attempts: i32) -> RepoResult<Option<i32>>;
+
+    async fn delete_challenge(&self, token_hash: &str) -> RepoResult<()>;
+
+    // 만료되지 않은 challenge 의 사용자 이름. 시도 횟수는 올리지 않는다.
+    async fn challenge_username(&self, token_hash: &str) -> RepoResult<Option<String>>;
+
+    // 확인을 마친 (비밀키, 마지막으로 쓴 step)
+    async fn confirmed_secret(&self, user_id: i32) -> RepoResult<Option<(String, Option<i64>)>>;
+
+    // 확인을 기다리는 비밀키
+    async fn pending_secret(&self, user_id: i32) -> RepoResult<Option<String>>;
+
+    // 아직 쓰지 않은 더 나중 step 일 때만 기록하고 true
+    async fn mark_step_used(&self, user_id: i32, step: i64) -> RepoResult<bool>;
+
+    // 새 비밀키를 저장한다. 이미 활성화돼 있으면 덮어쓰지 않고 false
+    async fn enroll(&self, user_id: i32, secret: &str) -> RepoResult<bool>;
+
+    async fn confirm(&self, user_id: i32, step: i64) -> RepoResult<()>;
+
+    async fn disable(&self, user_id: i32) -> RepoResult<()>;
+}
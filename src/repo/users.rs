@@ -0,0 +1,77 @@
+// --- 사용자 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct User {
+    pub id: i32,
+    pub username: String,
+    // 소셜 로그인으로만 가입한 계정과 게스트, 봇은 비밀번호가 없다
+    pub password_hash: Option<String>,
+    pub is_admin: bool,
+    pub is_guest: bool,
+    pub is_bot: bool,
+    pub disabled: bool,
+    // 음소거 중이면 풀리는 시각. 지난 시각은 None 으로 읽는다.
+    pub muted_until: Option<DateTime<Utc>>,
+    pub shadow_banned: bool,
+}
+
+// 가입할 사용자
+pub struct NewUser<'a> {
+    pub username: &'a str,
+    pub password_hash: &'a str,
+    pub email: Option<&'a str>,
+    // 있으면 같은 트랜잭션에서 사용 횟수를 올리고, 가입이 실패하면 함께 되돌린다
+    pub invite_code: Option<&'a str>,
+}
+
+// 관리자의 사용자 목록 한 줄
+#[derive(Serialize, FromRow)]
+pub struct UserSummary {
+    pub id: i32,
+    pub username: String,
+    pub email: Option<String>,
+    pub is_admin: bool,
+    pub is_guest: bool,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub shadow_banned: bool,
+    pub muted_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen: Option<DateTime<Utc>>,
+    // 지금 열려 있는 웹소켓 수. 저장소가 아니라 호출한 쪽이 채운다.
+    #[sqlx(skip)]
+    pub connections: usize,
+}
+
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    async fn create(&self, user: &NewUser<'_>) -> RepoResult<User>;
+
+    async fn find(&self, id: i32) -> RepoResult<Option<User>>;
+
+    // 대소문자를 구분하지 않는다
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<User>>;
+
+    // expected 가 있으면 저장된 해시가 그 값일 때만 바꾼다. 바꿨으면 true.
+    async fn set_password_hash(&self, id: i32, hash: &str, expected: Option<&str>) -> RepoResult<bool>;
+
+    // 마지막 연결이 끊긴 시각을 지금으로 남긴다
+    async fn touch_last_online(&self, id: i32) -> RepoResult<()>;
+
+    // until 까지 음소거한다. 이미 더 길게 음소거돼 있으면 그대로 둔다. 사용자가 없으면 None.
+    async fn mute(&self, id: i32, until: DateTime<Utc>) -> RepoResult<Option<User>>;
+
+    async fn set_shadow_banned(&self, id: i32, shadow_banned: bool) -> RepoResult<Option<User>>;
+
+    // pattern 은 이름이나 이메일에 대한 LIKE 패턴 (\ 로 이스케이프). None 이면 모두. id 순.
+    async fn search(&self, pattern: Option<&str>, limit: i64, offset: i64) -> RepoResult<Vec<UserSummary>>;
+
+    // 비활성화하거나 되살리고 (이름, 비활성화한 시각) 을 돌려준다. 이미 비활성화돼 있으면 시각을 그대로 둔다.
+    async fn set_disabled(&self, id: i32, disabled: bool) -> RepoResult<Option<(String, Option<DateTime<Utc>>)>>;
+}
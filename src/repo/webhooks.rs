@@ -0,0 +1,36 @@
+// --- 수신 웹훅 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WebhookInfo {
+    pub id: i64,
+    pub room: String,
+    pub name: String,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// 토큰은 해시로만 주고받는다. room 이 None 인 조회와 폐기는 모든 방이 대상이다.
+#[async_trait]
+pub trait WebhookRepo: Send + Sync {
+    async fn create(&self, room: &str, name: &str, token_hash: &str, created_by: i32) -> RepoResult<WebhookInfo>;
+
+    async fn list(&self, room: Option<&str>) -> RepoResult<Vec<WebhookInfo>>;
+
+    // 아직 폐기되지 않은 웹훅을 폐기했으면 true
+    async fn revoke(&self, id: i64, room: Option<&str>) -> RepoResult<bool>;
+
+    // 폐기되지 않은 토큰이면 사용 시각을 남기고 (id, 방, 이름) 을 돌려준다
+    async fn use_token(&self, token_hash: &str) -> RepoResult<Option<(i64, String, String)>>;
+
+    // 웹훅 이름으로 메시지를 저장하고 id 를 돌려준다
+    async fn post_message(&self, webhook_id: i64, name: &str, room: &str, text: &str) -> RepoResult<i64>;
+}
@@ -0,0 +1,37 @@
+// --- 금칙어 규칙 ---
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use super::RepoResult;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WordFilterRule {
+    pub id: i64,
+    // None 이면 모든 방
+    pub room: Option<String>,
+    pub pattern: String,
+    // "mask" 또는 "reject"
+    pub action: String,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait WordFilterRepo: Send + Sync {
+    async fn all(&self) -> RepoResult<Vec<WordFilterRule>>;
+
+    // 같은 방에 같은 패턴이 이미 있으면 Conflict("word_filter")
+    async fn create(
+        &self,
+        room: Option<&str>,
+        pattern: &str,
+        action: &str,
+        created_by: i32,
+    ) -> RepoResult<WordFilterRule>;
+
+    // 지운 규칙 (없었으면 None)
+    async fn delete(&self, id: i64) -> RepoResult<Option<WordFilterRule>>;
+}
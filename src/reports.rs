@@ -0,0 +1,243 @@
+// --- 메시지 신고 ---
+//
+// 사용자는 `POST /messages/:id/report` 로 메시지를 신고하고, 관리자는 `GET /admin/reports` 에서 처리할 신고를 보고
+// `POST /admin/reports/:id/resolve` 로 무시(dismiss), 메시지 삭제(delete_message), 작성자 음소거(mute_author) 중
+// 하나를 고른다. 처리하면 같은 메시지에 대한 열린 신고가 모두 함께 닫힌다.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    auth::{AdminUser, AuthUser},
+    audit::{self, Target},
+    error::{ApiError, ApiJson},
+    moderation,
+    notifications::{self, Notice},
+    repo::{NewReport, RepoError, ReportState},
+    validation::ValidationErrors,
+    AppState,
+};
+
+const REASON_MAX_CHARS: usize = 500;
+const LIST_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ReportPayload {
+    reason: String,
+}
+
+// POST /messages/:id/report
+pub async fn report_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(message_id): Path<i64>,
+    ApiJson(payload): ApiJson<ReportPayload>,
+) -> Response {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return ValidationErrors::single("reason", "required", "reason is required").into_response();
+    }
+    if reason.chars().count() > REASON_MAX_CHARS {
+        return ValidationErrors::single(
+            "reason",
+            "too_long",
+            format!("reason must be at most {} characters", REASON_MAX_CHARS),
+        )
+        .into_response();
+    }
+
+    let message = state.repos.reports.message(message_id).await;
+    let (room, author_id, content) = match message {
+        Ok(Some(message)) => message,
+        Ok(None) => return ApiError::not_found("message_not_found", "Message not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if author_id == Some(claims.user_id) {
+        return ApiError::bad_request("cannot_report_own_message", "You cannot report your own message")
+            .into_response();
+    }
+
+    let report = NewReport {
+        message_id,
+        room: &room,
+        author_id,
+        content: content.as_deref(),
+        reporter_id: claims.user_id,
+        reason,
+    };
+    let created = state.repos.reports.create(report).await;
+    match created {
+        Ok(id) => {
+            tracing::info!("User {} reported message {} in '{}'", claims.user_id, message_id, room);
+            (StatusCode::CREATED, Json(json!({ "id": id, "status": "open" }))).into_response()
+        }
+        Err(RepoError::Conflict(_)) => {
+            ApiError::conflict("already_reported", "You have already reported this message").into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    // 기본은 처리하지 않은 신고
+    #[serde(default = "default_status")]
+    status: String,
+}
+
+fn default_status() -> String {
+    "open".to_string()
+}
+
+// GET /admin/reports?status=open|dismissed|resolved
+pub async fn list_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    if !matches!(query.status.as_str(), "open" | "dismissed" | "resolved") {
+        return ValidationErrors::single("status", "invalid_value", "status must be 'open', 'dismissed' or 'resolved'")
+            .into_response();
+    }
+    let reports = state.repos.reports.list(&query.status, LIST_LIMIT).await;
+    match reports {
+        Ok(reports) => Json(reports).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolvePayload {
+    // "dismiss", "delete_message", "mute_author"
+    action: String,
+    // mute_author 일 때 음소거 시간 (생략하면 MODERATION_MUTE_MINUTES)
+    #[serde(default)]
+    mute_minutes: Option<i64>,
+    // 감사 기록에 남길 사유 (생략하면 신고 사유)
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+// POST /admin/reports/:id/resolve
+pub async fn resolve_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<i64>,
+    ApiJson(payload): ApiJson<ResolvePayload>,
+) -> Response {
+    let mut errors = ValidationErrors::default();
+    if !matches!(payload.action.as_str(), "dismiss" | "delete_message" | "mute_author") {
+        errors.add("action", "invalid_value", "action must be 'dismiss', 'delete_message' or 'mute_author'");
+    }
+    let mute_minutes = payload.mute_minutes.unwrap_or(*moderation::MUTE_DEFAULT_MINUTES);
+    if mute_minutes <= 0 {
+        errors.add("mute_minutes", "out_of_range", "mute_minutes must be positive");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let report = state.repos.reports.find(id).await;
+    let ReportState { message_id, room, author_id, reason: report_reason, status } = match report {
+        Ok(Some(report)) => report,
+        Ok(None) => return ApiError::not_found("report_not_found", "Report not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if status != "open" {
+        return ApiError::conflict("report_already_resolved", "This report has already been handled").into_response();
+    }
+    let reason = payload.reason.as_deref().map(str::trim).filter(|r| !r.is_empty()).unwrap_or(&report_reason);
+
+    // 메시지를 지우면 신고의 message_id 가 비므로 함께 닫을 신고를 먼저 모은다
+    let report_ids = match state.repos.reports.open_ids(id, message_id).await {
+        Ok(ids) => ids,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let mut muted_until = None;
+    match payload.action.as_str() {
+        "delete_message" => {
+            // 이미 지워진 메시지라면 신고만 닫는다
+            if let Some(message_id) = message_id {
+                match moderation::delete_message(&state, message_id).await {
+                    Ok(true) => {
+                        let target = Target::Message(message_id);
+                        let details = json!({ "room": room, "author_id": author_id, "report_id": id });
+                        audit::record(&state, &claims, "message.delete", target, Some(reason), details).await;
+                        if let Some(author_id) = author_id {
+                            let notice = Notice {
+                                kind: "moderation",
+                                room: Some(&room),
+                                message_id: None,
+                                actor: None,
+                                text: format!("A moderator removed your message in '{}'", room),
+                            };
+                            notifications::create(&state, author_id, notice).await;
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => return ApiError::from(e).into_response(),
+                }
+            }
+        }
+        "mute_author" => {
+            let Some(author_id) = author_id else {
+                return ApiError::bad_request("author_deleted", "The author of this message no longer exists")
+                    .into_response();
+            };
+            let until = Utc::now() + Duration::minutes(mute_minutes);
+            match moderation::mute_user(&state, author_id, until, &room).await {
+                Ok(until) => muted_until = until,
+                Err(e) => return ApiError::from(e).into_response(),
+            }
+            if let Some(until) = muted_until {
+                let details = json!({ "muted_until": until, "room": room, "report_id": id });
+                audit::record(&state, &claims, "user.mute", Target::User(author_id), Some(reason), details).await;
+                let notice = Notice {
+                    kind: "moderation",
+                    room: Some(&room),
+                    message_id,
+                    actor: None,
+                    text: format!("A moderator muted you until {}", until.format("%Y-%m-%d %H:%M UTC")),
+                };
+                notifications::create(&state, author_id, notice).await;
+            }
+        }
+        _ => {}
+    }
+
+    let new_status = if payload.action == "dismiss" { "dismissed" } else { "resolved" };
+    let closed = state.repos.reports.close(&report_ids, new_status, &payload.action, claims.user_id).await;
+    match closed {
+        Ok(closed) => {
+            tracing::info!(
+                "User {} resolved report {} with {} ({} reports closed)",
+                claims.user_id,
+                id,
+                payload.action,
+                closed
+            );
+            let details = json!({ "resolution": payload.action, "reports": report_ids });
+            let action = format!("report.{}", new_status);
+            audit::record(&state, &claims, &action, Target::Report(id), Some(reason), details).await;
+            Json(json!({
+                "id": id,
+                "status": new_status,
+                "resolution": payload.action,
+                "reports_closed": closed,
+                "muted_until": muted_until,
+            }))
+            .into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
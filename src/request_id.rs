@@ -0,0 +1,55 @@
+// --- 요청 id ---
+//
+// 모든 HTTP 요청에 `x-request-id` 를 붙여 로그와 클라이언트의 오류 보고를 서로 찾을 수 있게 한다. 클라이언트나
+// 앞단 프록시가 보낸 값이 쓸 만하면(128자 이하의 영숫자와 `-`, `_`, `.`) 그대로 쓰고, 아니면 새로 만든다.
+// 이 id 는 요청의 로그 span(`http.request`), 응답 헤더, 오류 응답 본문의 `request_id` 에 실린다.
+// 웹소켓과 SSE 연결은 업그레이드 요청의 id 와 연결 id 를 연결의 span 에 남긴다.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use rand::{distributions::Alphanumeric, Rng};
+
+pub static HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+const MAX_LEN: usize = 128;
+const GENERATED_LEN: usize = 20;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+fn generate() -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(GENERATED_LEN).map(char::from).collect()
+}
+
+// 지금 처리 중인 요청의 id. 요청 밖(백그라운드 태스크 등)에서는 None.
+pub fn current() -> Option<String> {
+    CURRENT.try_with(String::clone).ok()
+}
+
+// 라우터 가장 바깥에 거는 미들웨어
+pub async fn layer(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(generate);
+    let value = HeaderValue::from_str(&id).expect("request ids are visible ASCII");
+    req.headers_mut().insert(HEADER.clone(), value.clone());
+
+    let mut res = CURRENT.scope(id, next.run(req)).await;
+    res.headers_mut().insert(HEADER.clone(), value);
+    res
+}
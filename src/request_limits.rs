@@ -0,0 +1,68 @@
+// --- 요청 크기와 처리 시간 제한 ---
+//
+// 로그인·가입·토큰 갱신 라우트는 JSON 본문을 limits.auth_body_max_bytes (AUTH_BODY_MAX_BYTES) 까지만 읽는다.
+// 넘으면 끝까지 받지 않고 413 `body_too_large` 로 답한다(error.rs 의 ApiJson). 업로드처럼 더 큰 본문을 받는
+// 라우트는 각자 DefaultBodyLimit 을 건다.
+//
+// 모든 요청은 limits.request_timeout_seconds (REQUEST_TIMEOUT_SECONDS) 안에 응답 헤더까지 만들어야 하고, 넘으면
+// 핸들러를 취소하고 408 `request_timeout` 으로 답한다. 본문을 느리게 보내거나 DB 를 오래 붙잡는 요청이 작업자를
+// 잡아 두지 못하게 하기 위한 것이다. 응답 본문(SSE, 내보내기 다운로드)과 업그레이드한 웹소켓은 세지 않는다.
+// 본문을 받는 데 오래 걸릴 수 있는 업로드 라우트는 `for_uploads` 를 걸어 limits.upload_timeout_seconds 로 늘린다.
+
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+use crate::{config, error::ApiError};
+
+pub static AUTH_BODY_MAX_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.auth_body_max_bytes);
+static REQUEST_TIMEOUT: Lazy<Duration> =
+    Lazy::new(|| Duration::from_secs(config::get().limits.request_timeout_seconds));
+static UPLOAD_TIMEOUT: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().limits.upload_timeout_seconds));
+
+// 요청의 마감 시각. 라우트의 미들웨어가 늦출 수 있도록 요청 확장에 넣어 둔다.
+#[derive(Clone)]
+struct Deadline(Arc<Mutex<Instant>>);
+
+impl Deadline {
+    fn get(&self) -> Instant {
+        *self.0.lock().unwrap()
+    }
+}
+
+// 라우터 전체에 거는 미들웨어
+pub async fn timeout(mut request: Request, next: Next) -> Response {
+    let deadline = Deadline(Arc::new(Mutex::new(Instant::now() + *REQUEST_TIMEOUT)));
+    request.extensions_mut().insert(deadline.clone());
+    let response = next.run(request);
+    tokio::pin!(response);
+    loop {
+        tokio::select! {
+            response = &mut response => return response,
+            _ = tokio::time::sleep_until(deadline.get()) => {
+                // 그 사이에 업로드 라우트가 마감을 늦췄으면 다시 기다린다
+                if deadline.get() <= Instant::now() {
+                    return ApiError::new(StatusCode::REQUEST_TIMEOUT, "request_timeout", "The request took too long")
+                        .into_response();
+                }
+            }
+        }
+    }
+}
+
+// 업로드 라우트에 거는 미들웨어. 마감을 지금부터 upload_timeout_seconds 뒤로 늦춘다.
+pub async fn for_uploads(request: Request, next: Next) -> Response {
+    if let Some(deadline) = request.extensions().get::<Deadline>() {
+        *deadline.0.lock().unwrap() = Instant::now() + *UPLOAD_TIMEOUT;
+    }
+    next.run(request).await
+}
@@ -0,0 +1,215 @@
+// --- 메시지 보관 정책 ---
+//
+// 보관 기간이 지난 메시지를 백그라운드 태스크가 지우거나(delete) `archived_messages` 로 옮긴다(archive).
+// 전역 기본값은 RETENTION_DAYS(0 이면 영구 보관)와 RETENTION_ACTION 이고, 방 주인이나 관리자는
+// `PUT /rooms/:room/retention` 으로 방마다 다르게 정할 수 있다. 관리자가 보관 예외로 지정한 방
+// (`PUT /admin/rooms/:room/retention/exempt`)은 어떤 정책이든 지우지 않는다.
+//
+// 옮기든 지우든 메시지를 가리키는 알림과 첨부 파일은 함께 지운다. 보관함에는 메시지 행만 남는다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{
+    audit::{self, Target},
+    auth::{AdminUser, AuthUser},
+    config,
+    error::{ApiError, ApiJson},
+    repo::{RepoResult, RoomPolicy},
+    room_owners, AppState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    Delete,
+    Archive,
+}
+
+impl RetentionAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            RetentionAction::Delete => "delete",
+            RetentionAction::Archive => "archive",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "delete" => Some(RetentionAction::Delete),
+            "archive" => Some(RetentionAction::Archive),
+            _ => None,
+        }
+    }
+}
+
+// 정책이 없는 방의 보관 기간(일). 0 이면 지우지 않는다.
+static DEFAULT_DAYS: Lazy<i32> = Lazy::new(|| config::get().retention.days);
+static DEFAULT_ACTION: Lazy<RetentionAction> = Lazy::new(|| config::get().retention.action);
+static CHECK_INTERVAL: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().retention.check_seconds));
+
+// 방 정책으로 정할 수 있는 가장 긴 기간 (10년)
+const DAYS_MAX: i32 = 3650;
+// 한 트랜잭션에서 처리하는 메시지 수
+const SWEEP_BATCH: i64 = 500;
+
+// 방에 실제로 적용되는 정책. days 가 None 이면 영구 보관.
+#[derive(Serialize)]
+struct EffectivePolicy {
+    room: String,
+    // 방에 따로 정한 값 (null 이면 전역 기본값)
+    days: Option<i32>,
+    action: Option<RetentionAction>,
+    exempt: bool,
+    effective_days: Option<i32>,
+    effective_action: RetentionAction,
+}
+
+impl EffectivePolicy {
+    fn new(room: String, policy: Option<RoomPolicy>) -> Self {
+        let (days, action, exempt) = match policy {
+            Some(p) => (p.days, p.action.as_deref().and_then(RetentionAction::parse), p.exempt),
+            None => (None, None, false),
+        };
+        let effective_days = days.or(Some(*DEFAULT_DAYS)).filter(|d| *d > 0 && !exempt);
+        let effective_action = action.unwrap_or(*DEFAULT_ACTION);
+        EffectivePolicy { room, days, action, exempt, effective_days, effective_action }
+    }
+}
+
+pub fn spawn_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(*CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sweep(&state).await {
+                tracing::warn!("Failed to apply message retention: {}", e);
+            }
+        }
+    });
+}
+
+// 보관 기간이 지난 메시지를 SWEEP_BATCH 개씩 처리한다
+async fn sweep(state: &AppState) -> RepoResult<()> {
+    let (mut deleted, mut archived) = (0, 0);
+    loop {
+        let batch = state.repos.retention.sweep(*DEFAULT_DAYS, DEFAULT_ACTION.as_str(), SWEEP_BATCH).await?;
+        for key in batch.attachment_keys {
+            if let Err(e) = state.attachments.delete(&key).await {
+                tracing::warn!("Failed to remove attachment file {} past retention: {}", key, e);
+            }
+        }
+        archived += batch.archived;
+        deleted += batch.deleted;
+        if ((batch.archived + batch.deleted) as i64) < SWEEP_BATCH {
+            break;
+        }
+    }
+    if deleted > 0 || archived > 0 {
+        tracing::info!("Retention removed {} messages and archived {}", deleted, archived);
+    }
+    Ok(())
+}
+
+async fn load_policy(state: &AppState, room: String) -> Result<EffectivePolicy, ApiError> {
+    let policy = state.repos.retention.policy(&room).await?;
+    Ok(EffectivePolicy::new(room, policy))
+}
+
+// GET /rooms/:room/retention
+pub async fn get_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    match load_policy(&state, room).await {
+        Ok(policy) => Json(policy).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PolicyPayload {
+    // 생략하거나 null 이면 전역 기본값
+    #[serde(default)]
+    days: Option<i32>,
+    #[serde(default)]
+    action: Option<RetentionAction>,
+}
+
+// PUT /rooms/:room/retention: 방 주인이나 관리자. 보관 예외 여부는 바꾸지 않는다.
+pub async fn put_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<PolicyPayload>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    if payload.days.is_some_and(|d| !(1..=DAYS_MAX).contains(&d)) {
+        let message = format!("days must be between 1 and {}", DAYS_MAX);
+        return ApiError::bad_request("invalid_retention_days", message).into_response();
+    }
+
+    let action = payload.action.map(RetentionAction::as_str);
+    let saved = state.repos.retention.set_policy(&room, payload.days, action, claims.user_id).await;
+    if let Err(e) = saved {
+        return ApiError::from(e).into_response();
+    }
+    tracing::info!("User {} set the retention of '{}' to {:?} days", claims.user_id, room, payload.days);
+    let details = json!({ "days": payload.days, "action": payload.action });
+    audit::record(&state, &claims, "room.retention", Target::Room(&room), None, details).await;
+    match load_policy(&state, room).await {
+        Ok(policy) => Json(policy).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// --- 관리 API ---
+
+// GET /admin/retention: 전역 기본값과 정책을 따로 정한 방
+pub async fn list_handler(State(state): State<AppState>) -> Response {
+    match state.repos.retention.policies().await {
+        Ok(rooms) => {
+            let days = Some(*DEFAULT_DAYS).filter(|d| *d > 0);
+            Json(json!({ "days": days, "action": *DEFAULT_ACTION, "rooms": rooms })).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExemptPayload {
+    exempt: bool,
+}
+
+// PUT /admin/rooms/:room/retention/exempt
+pub async fn exempt_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<ExemptPayload>,
+) -> Response {
+    let saved = state.repos.retention.set_exempt(&room, payload.exempt, claims.user_id).await;
+    match saved {
+        Ok(_) => {
+            tracing::info!("User {} set retention exemption of '{}' to {}", claims.user_id, room, payload.exempt);
+            let details = json!({ "exempt": payload.exempt });
+            audit::record(&state, &claims, "room.retention_exempt", Target::Room(&room), None, details).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
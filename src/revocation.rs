@@ -0,0 +1,120 @@
+// --- 액세스 토큰 폐기 목록 ---
+//
+// 폐기된 jti 는 DB 에 기록하고, 매 요청마다 DB 를 조회하지 않도록 메모리에도 들고 있는다.
+// 시작 시 아직 만료되지 않은 항목을 읽어 오고, 주기적으로 만료된 항목을 정리한다.
+// 세션이 폐기되면 그 세션으로 발급된 액세스 토큰이 모두 만료될 때까지 세션 id 도 함께 막는다.
+//
+// 서버를 여러 대 띄우면 폐기를 버스로 다른 서버에도 알려 바로 막는다. Redis 가 끊겨 알림을 놓친 서버도 정리
+// 주기마다 DB 에서 다시 읽어 오므로 늦어도 그 주기 안에는 막는다.
+
+use chrono::{DateTime, TimeZone, Utc};
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+use tokio::sync::mpsc;
+
+use crate::{
+    auth::{Claims, ACCESS_TOKEN_TTL},
+    cluster::{ClusterEvent, Relay},
+    repo::{RepoResult, Repos},
+};
+
+pub struct RevocationStore {
+    // jti -> 토큰 만료 시각 (유닉스 초)
+    revoked: RwLock<HashMap<String, i64>>,
+    // 세션 id -> 이 세션의 액세스 토큰이 모두 만료되는 시각 (유닉스 초)
+    revoked_sessions: RwLock<HashMap<String, i64>>,
+    relay: Relay,
+}
+
+impl RevocationStore {
+    pub async fn load(repos: &Repos) -> RepoResult<Self> {
+        let (tokens, sessions) = Self::read(repos).await?;
+        Ok(Self {
+            revoked: RwLock::new(tokens),
+            revoked_sessions: RwLock::new(sessions),
+            relay: Relay::default(),
+        })
+    }
+
+    // 아직 막아야 하는 jti 와 세션
+    async fn read(repos: &Repos) -> RepoResult<(HashMap<String, i64>, HashMap<String, i64>)> {
+        let tokens = repos.revocation.revoked_tokens().await?;
+        let sessions = repos.revocation.revoked_sessions(Utc::now() - *ACCESS_TOKEN_TTL).await?;
+        let ttl = ACCESS_TOKEN_TTL.num_seconds();
+        Ok((
+            tokens.into_iter().map(|(jti, exp)| (jti, exp.timestamp())).collect(),
+            sessions.into_iter().map(|(id, revoked_at)| (id, revoked_at.timestamp() + ttl)).collect(),
+        ))
+    }
+
+    // 다른 서버에 폐기를 알릴 대기열 (Redis 버스가 연결한다)
+    pub fn relay_to(&self, tx: mpsc::Sender<ClusterEvent>) {
+        self.relay.connect(tx);
+    }
+
+    pub fn is_revoked(&self, claims: &Claims) -> bool {
+        self.revoked.read().unwrap().contains_key(&claims.jti)
+            || self.revoked_sessions.read().unwrap().contains_key(&claims.sid)
+    }
+
+    pub async fn revoke(&self, repos: &Repos, jti: &str, exp: usize) -> RepoResult<()> {
+        let expires_at: DateTime<Utc> = Utc.timestamp_opt(exp as i64, 0).single().unwrap_or_else(Utc::now);
+        repos.revocation.revoke_token(jti, expires_at).await?;
+
+        let event = ClusterEvent::RevokeToken { jti: jti.to_string(), exp };
+        self.apply(&event);
+        self.relay.send(event);
+        Ok(())
+    }
+
+    // 세션 폐기 자체는 sessions 테이블에 기록되므로 여기서는 메모리만 갱신한다
+    pub fn revoke_session(&self, session_id: &str) {
+        let event = ClusterEvent::RevokeSession { session_id: session_id.to_string() };
+        self.apply(&event);
+        self.relay.send(event);
+    }
+
+    // 폐기를 이 서버의 목록에만 적용한다. 다른 서버가 알린 이벤트도 여기로 온다.
+    pub fn apply(&self, event: &ClusterEvent) {
+        match event {
+            ClusterEvent::RevokeToken { jti, exp } => {
+                self.revoked.write().unwrap().insert(jti.clone(), *exp as i64);
+            }
+            ClusterEvent::RevokeSession { session_id } => {
+                let until = (chrono::Utc::now() + *ACCESS_TOKEN_TTL).timestamp();
+                self.revoked_sessions.write().unwrap().insert(session_id.clone(), until);
+            }
+            _ => {}
+        }
+    }
+
+    // 원래 만료 시각이 지난 토큰은 어차피 검증에서 걸러지므로 목록에서 뺀다.
+    // 다른 서버가 DB 에 기록한 폐기 중 알림을 놓친 것도 이때 채운다.
+    pub async fn refresh(&self, repos: &Repos) -> RepoResult<()> {
+        repos.revocation.purge_tokens().await?;
+        let (tokens, sessions) = Self::read(repos).await?;
+        let now = chrono::Utc::now().timestamp();
+        let mut revoked = self.revoked.write().unwrap();
+        revoked.retain(|_, exp| *exp > now);
+        revoked.extend(tokens);
+        drop(revoked);
+        let mut revoked_sessions = self.revoked_sessions.write().unwrap();
+        revoked_sessions.retain(|_, until| *until > now);
+        for (session_id, until) in sessions {
+            revoked_sessions.entry(session_id).or_insert(until);
+        }
+        Ok(())
+    }
+}
+
+// 만료된 폐기 항목을 정리하고 DB 와 맞추는 백그라운드 태스크
+pub fn spawn_purge_task(store: std::sync::Arc<RevocationStore>, repos: Repos) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = store.refresh(&repos).await {
+                tracing::warn!("Failed to refresh revoked tokens: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,191 @@
+// --- 방 기록 내보내기 ---
+//
+// `GET /rooms/:room/export?format=json|csv` 는 방 주인이나 관리자가 방에 저장된 메시지 전체를 내려받게 한다.
+// 메시지를 한꺼번에 읽지 않고 id 순서로 EXPORT_BATCH 개씩 읽어 바로 응답 본문으로 흘려 보내므로(chunked),
+// 메시지가 많은 방도 메모리를 크게 쓰지 않는다. 그림자 차단으로 숨겨진 메시지도 hidden 표시와 함께 넣고,
+// 수명이 지난 메시지는 뺀다.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures::stream;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    audit::{self, Target},
+    auth::AuthUser,
+    repo::{ExportedMessage, RepoResult, Repos},
+    room_owners, AppState,
+};
+
+// 한 번에 읽는 메시지 수
+const EXPORT_BATCH: i64 = 500;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+const CSV_HEADER: &str =
+    "id,created_at,user_id,username,format,hidden,webhook_id,forwarded_message_id,forwarded_username,text\r\n";
+
+enum Phase {
+    Header,
+    Rows { after: i64, first: bool },
+    Done,
+}
+
+struct Export {
+    repos: Repos,
+    room: String,
+    format: ExportFormat,
+    phase: Phase,
+}
+
+impl Export {
+    // 응답 본문의 다음 조각. 끝나면 None.
+    async fn next_chunk(&mut self) -> RepoResult<Option<Bytes>> {
+        match self.phase {
+            Phase::Header => {
+                self.phase = Phase::Rows { after: 0, first: true };
+                let header = match self.format {
+                    ExportFormat::Json => {
+                        let meta = json!({ "room": self.room, "exported_at": Utc::now() }).to_string();
+                        // 마지막 } 를 떼고 messages 배열을 연다
+                        format!("{},\"messages\":[", &meta[..meta.len() - 1])
+                    }
+                    ExportFormat::Csv => CSV_HEADER.to_string(),
+                };
+                Ok(Some(Bytes::from(header)))
+            }
+            Phase::Rows { after, first } => {
+                let rows = self.repos.messages.export_page(&self.room, after, EXPORT_BATCH).await?;
+                let Some(last) = rows.last().map(|row| row.id) else {
+                    self.phase = Phase::Done;
+                    return Ok(match self.format {
+                        ExportFormat::Json => Some(Bytes::from_static(b"]}")),
+                        ExportFormat::Csv => None,
+                    });
+                };
+                self.phase = Phase::Rows { after: last, first: false };
+                let mut chunk = String::new();
+                for (i, row) in rows.iter().enumerate() {
+                    match self.format {
+                        ExportFormat::Json => {
+                            if !(first && i == 0) {
+                                chunk.push(',');
+                            }
+                            chunk.push_str(&serde_json::to_string(row).unwrap_or_default());
+                        }
+                        ExportFormat::Csv => push_csv_row(&mut chunk, row),
+                    }
+                }
+                Ok(Some(Bytes::from(chunk)))
+            }
+            Phase::Done => Ok(None),
+        }
+    }
+}
+
+fn push_csv_row(out: &mut String, row: &ExportedMessage) {
+    let forwarded = row.forwarded.as_ref();
+    let fields = [
+        row.id.to_string(),
+        row.created_at.to_rfc3339(),
+        row.user_id.map(|id| id.to_string()).unwrap_or_default(),
+        row.username.clone().unwrap_or_default(),
+        row.format.clone(),
+        row.hidden.to_string(),
+        row.webhook_id.map(|id| id.to_string()).unwrap_or_default(),
+        forwarded.map(|f| f.message_id.to_string()).unwrap_or_default(),
+        forwarded.map(|f| f.username.clone()).unwrap_or_default(),
+        row.text.clone().unwrap_or_default(),
+    ];
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_csv_field(out, field);
+    }
+    out.push_str("\r\n");
+}
+
+// RFC 4180: 쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감싸고 안의 따옴표는 두 번 쓴다. 스프레드시트가 수식으로 읽는
+// 글자(=, +, -, @, 탭, CR)로 시작하는 칸은 앞에 ' 를 붙여 글자 그대로 보이게 한다.
+pub fn push_csv_field(out: &mut String, field: &str) {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\r', '\n']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(&field);
+    }
+}
+
+// 내려받을 파일 이름에는 방 이름의 영문자, 숫자, -, _ 만 쓴다
+fn file_name(room: &str, format: ExportFormat) -> String {
+    let safe: String = room.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')).collect();
+    let extension = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Csv => "csv",
+    };
+    format!("{}-export.{}", if safe.is_empty() { "room" } else { &safe }, extension)
+}
+
+// GET /rooms/:room/export?format=json|csv
+pub async fn export_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    tracing::info!("User {} exported room '{}'", claims.user_id, room);
+    let details = json!({ "format": query.format });
+    audit::record(&state, &claims, "room.export", Target::Room(&room), None, details).await;
+
+    let content_type = match query.format {
+        ExportFormat::Json => "application/json",
+        ExportFormat::Csv => "text/csv; charset=utf-8",
+    };
+    let disposition = format!("attachment; filename=\"{}\"", file_name(&room, query.format));
+    let export = Export { repos: state.repos.clone(), room, format: query.format, phase: Phase::Header };
+    let chunks = stream::unfold(Some(export), |export| async move {
+        let mut export = export?;
+        match export.next_chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk), Some(export))),
+            Ok(None) => None,
+            // 이미 보낸 응답은 되돌릴 수 없으므로 본문을 끊어 실패를 알린다
+            Err(e) => {
+                tracing::error!("Failed to export room '{}': {}", export.room, e);
+                Some((Err(e), None))
+            }
+        }
+    });
+    (
+        [(header::CONTENT_TYPE, content_type.to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        Body::from_stream(chunks),
+    )
+        .into_response()
+}
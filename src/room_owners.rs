@@ -0,0 +1,98 @@
+// --- 방 주인 ---
+//
+// 방은 따로 만들지 않고 처음 들어올 때 생기므로 주인도 자동으로 정해지지 않는다. 관리자가
+// `PUT /admin/rooms/:room/owners/:user_id` 로 사용자를 방 주인으로 지정하면, 그 사용자는 방 내보내기처럼
+// 방 하나에 한정된 관리 기능을 관리자와 같이 쓸 수 있다. 한 방에 주인이 여럿일 수 있다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use crate::{
+    audit::{self, Target},
+    auth::{AdminUser, Claims},
+    error::ApiError,
+    repo::RepoResult,
+    AppState,
+};
+
+// 방 주인이거나 관리자면 true
+pub async fn can_manage(state: &AppState, claims: &Claims, room: &str) -> RepoResult<bool> {
+    if claims.guest || claims.is_bot() {
+        return Ok(false);
+    }
+    match state.repos.users.find(claims.user_id).await? {
+        Some(user) if user.is_admin => Ok(true),
+        Some(_) => state.repos.rooms.is_owner(room, claims.user_id).await,
+        None => Ok(false),
+    }
+}
+
+// 방 주인이나 관리자가 아니면 403
+pub async fn require_manager(state: &AppState, claims: &Claims, room: &str) -> Result<(), ApiError> {
+    if can_manage(state, claims, room).await? {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("room_owner_required", "Only the room's owners or an administrator can do this"))
+    }
+}
+
+// --- 관리 API ---
+
+// GET /admin/rooms/:room/owners
+pub async fn list_handler(State(state): State<AppState>, Path(room): Path<String>) -> Response {
+    match state.repos.rooms.owners(&room).await {
+        Ok(owners) => Json(owners).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// PUT /admin/rooms/:room/owners/:user_id: 이미 주인이어도 성공한다
+pub async fn add_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path((room, user_id)): Path<(String, i32)>,
+) -> Response {
+    match state.repos.users.find(user_id).await {
+        Ok(Some(user)) if !user.is_guest && !user.is_bot => {}
+        Ok(Some(_)) => {
+            return ApiError::bad_request("invalid_owner", "Guests and bots cannot own rooms").into_response()
+        }
+        Ok(None) => return ApiError::not_found("user_not_found", "User not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    match state.repos.rooms.add_owner(&room, user_id, claims.user_id).await {
+        Ok(added) => {
+            if added {
+                tracing::info!("User {} made user {} an owner of '{}'", claims.user_id, user_id, room);
+                let details = json!({ "user_id": user_id });
+                audit::record(&state, &claims, "room.owner_add", Target::Room(&room), None, details).await;
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /admin/rooms/:room/owners/:user_id
+pub async fn remove_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path((room, user_id)): Path<(String, i32)>,
+) -> Response {
+    match state.repos.rooms.remove_owner(&room, user_id).await {
+        Ok(true) => {
+            tracing::info!("User {} removed user {} from the owners of '{}'", claims.user_id, user_id, room);
+            let details = json!({ "user_id": user_id });
+            audit::record(&state, &claims, "room.owner_remove", Target::Room(&room), None, details).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("owner_not_found", "User does not own this room").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,122 @@
+// --- 열려 있는 방 목록 ---
+//
+// 방 이름마다 브로드캐스트 채널을 하나 둔다. 접속, 접속 종료, 이벤트 전달이 모두 이 목록을 거치므로 전역 잠금
+// 하나로 묶지 않고, 방 이름의 해시로 고른 조각(shard)마다 따로 잠근다. 방이 수만 개여도 서로 다른 조각의 방은
+// 잠금을 다투지 않는다. 조각 수는 rooms.registry_shards 로 정한다.
+//
+// 잠금을 쥔 채 기다리는 일이 없으므로, 다른 곳의 panic 으로 잠금이 오염되어도 안의 값은 그대로 쓴다.
+// 방은 처음 구독할 때 열리고 닫을 때까지 남는다.
+//
+// 채널에는 rooms.channel_capacity 개의 이벤트가 쌓인다. 사람이 많은 방은 rooms.channel_capacity_overrides
+// 로 따로 정할 수 있다. 크기는 방이 열릴 때 정해진다. 구독자가 이만큼 뒤처졌을 때의 처리는
+// 웹소켓 쪽(rooms.lag_policy)이 정한다.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use tokio::sync::broadcast;
+
+use crate::{config, protocol::ServerEvent};
+
+static SHARDS: Lazy<usize> = Lazy::new(|| config::get().rooms.registry_shards);
+
+fn capacity(room: &str) -> usize {
+    let rooms = &config::get().rooms;
+    rooms.channel_capacity_overrides.get(room).copied().unwrap_or(rooms.channel_capacity)
+}
+
+type Shard = RwLock<HashMap<String, broadcast::Sender<ServerEvent>>>;
+
+pub struct RoomRegistry {
+    shards: Box<[Shard]>,
+}
+
+#[derive(Serialize)]
+pub struct ShardStats {
+    pub rooms: usize,
+    pub subscribers: usize,
+}
+
+#[derive(Serialize)]
+pub struct RegistryStats {
+    pub rooms: usize,
+    pub subscribers: usize,
+    pub shards: Vec<ShardStats>,
+}
+
+impl Default for RoomRegistry {
+    fn default() -> Self {
+        RoomRegistry { shards: (0..*SHARDS).map(|_| Shard::default()).collect() }
+    }
+}
+
+impl RoomRegistry {
+    fn shard(&self, room: &str) -> &Shard {
+        let mut hasher = DefaultHasher::new();
+        room.hash(&mut hasher);
+        &self.shards[(hasher.finish() % self.shards.len() as u64) as usize]
+    }
+
+    fn read(shard: &Shard) -> RwLockReadGuard<'_, HashMap<String, broadcast::Sender<ServerEvent>>> {
+        shard.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(shard: &Shard) -> RwLockWriteGuard<'_, HashMap<String, broadcast::Sender<ServerEvent>>> {
+        shard.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // 방의 이벤트를 받는다. 방이 열려 있지 않으면 연다.
+    pub fn subscribe(&self, room: &str) -> broadcast::Receiver<ServerEvent> {
+        let shard = self.shard(room);
+        if let Some(tx) = Self::read(shard).get(room) {
+            return tx.subscribe();
+        }
+        Self::write(shard)
+            .entry(room.to_string())
+            .or_insert_with(|| broadcast::channel(capacity(room)).0)
+            .subscribe()
+    }
+
+    // 열려 있지 않은 방이면 버린다. 받은 구독자가 있으면 true.
+    pub fn send(&self, room: &str, event: ServerEvent) -> bool {
+        Self::read(self.shard(room)).get(room).is_some_and(|tx| tx.send(event).is_ok())
+    }
+
+    // 열려 있는 모든 방에 방마다 만든 이벤트를 보낸다. 받은 구독자가 있는 방 수를 돌려준다.
+    pub fn send_all(&self, event: impl Fn(&str) -> ServerEvent) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| Self::read(shard).iter().filter(|(room, tx)| tx.send(event(room)).is_ok()).count())
+            .sum()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.shards.iter().flat_map(|shard| Self::read(shard).keys().cloned().collect::<Vec<_>>()).collect()
+    }
+
+    // 방을 닫는다. 구독자는 채널이 닫혔음을 받는다. 열려 있었으면 true.
+    pub fn remove(&self, room: &str) -> bool {
+        Self::write(self.shard(room)).remove(room).is_some()
+    }
+
+    // 조각마다의 방 수와 구독자 수. 조각 하나씩 잠그므로 모든 조각을 같은 순간에 센 값은 아니다.
+    pub fn stats(&self) -> RegistryStats {
+        let shards: Vec<ShardStats> = self
+            .shards
+            .iter()
+            .map(|shard| {
+                let rooms = Self::read(shard);
+                ShardStats { rooms: rooms.len(), subscribers: rooms.values().map(|tx| tx.receiver_count()).sum() }
+            })
+            .collect();
+        RegistryStats {
+            rooms: shards.iter().map(|s| s.rooms).sum(),
+            subscribers: shards.iter().map(|s| s.subscribers).sum(),
+            shards,
+        }
+    }
+}
@@ -0,0 +1,249 @@
+// --- 예약 메시지 ---
+//
+// `POST /rooms/:room/messages` 는 웹소켓 없이 메시지를 보낸다(스크립트, 연동, SSE 클라이언트). `send_at` 이
+// 없으면 웹소켓으로 보낸 메시지와 똑같이(messages::post) 검사하고 바로 보낸다. `send_at` 을 주면 메시지를
+// `scheduled_messages` 에 저장해 두고, 스케줄러 태스크가 그 시각에 보통 메시지처럼 저장하고 방에 보낸다.
+// 보내기 전에는 `GET /me/scheduled-messages` 로 보고 `DELETE /me/scheduled-messages/:id` 로 취소할 수 있다.
+//
+// 예약할 때와 보낼 때 모두 글쓰기 조건을 확인한다. 보낼 때 음소거 중이거나 계정이 비활성화됐거나 금칙어 규칙에
+// 걸리면 보내지 않고 버린다. 스케줄러는 리마인더처럼 가장 이른 예약 시각까지 잠들고, 새 예약이 생기면 깨어난다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::{
+    auth::{AuthUser, Claims},
+    error::{ApiError, ApiJson},
+    markdown::MessageFormat,
+    messages::{self, Author, Draft, Posted},
+    moderation, profile,
+    repo::{DueMessage, RepoResult},
+    validation::ValidationErrors,
+    word_filter,
+    ws::MESSAGE_MAX_CHARS,
+    AppState,
+};
+
+// 사용자 한 명이 걸어 둘 수 있는 예약 메시지 수
+const SCHEDULED_PER_USER_MAX: i64 = 50;
+// 가장 먼 예약
+const SCHEDULE_MAX_DAYS: i64 = 365;
+
+// 예약이 없어도 이만큼마다 한 번은 확인한다
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// 한 번에 보내는 최대 수
+const DELIVER_BATCH: i64 = 100;
+
+// 스케줄러를 깨우는 신호
+#[derive(Default)]
+pub struct Scheduler {
+    wake: Notify,
+}
+
+pub fn spawn_task(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            let next = match deliver_due(&state).await {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::warn!("Failed to send scheduled messages: {}", e);
+                    None
+                }
+            };
+            let wait = next
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(IDLE_CHECK_INTERVAL, |wait| wait.min(IDLE_CHECK_INTERVAL));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = state.scheduled_messages.wake.notified() => {}
+            }
+        }
+    });
+}
+
+// 시각이 된 메시지를 보내고 지운 뒤, 다음 예약 시각을 돌려준다
+async fn deliver_due(state: &AppState) -> RepoResult<Option<DateTime<Utc>>> {
+    loop {
+        let due = state.repos.scheduled_messages.take_due(DELIVER_BATCH).await?;
+        for message in &due {
+            deliver(state, message).await;
+        }
+        if (due.len() as i64) < DELIVER_BATCH {
+            break;
+        }
+    }
+    state.repos.scheduled_messages.next_due().await
+}
+
+async fn deliver(state: &AppState, message: &DueMessage) {
+    if message.disabled {
+        tracing::info!("Dropped scheduled message {}: user {} is disabled", message.id, message.user_id);
+        return;
+    }
+    let restrictions = match moderation::restrictions(state, message.user_id).await {
+        Ok(restrictions) => restrictions,
+        Err(e) => {
+            tracing::warn!("Failed to check restrictions of user {}: {}", message.user_id, e);
+            moderation::Restrictions::default()
+        }
+    };
+    if restrictions.muted_until.is_some() {
+        tracing::info!("Dropped scheduled message {}: user {} is muted", message.id, message.user_id);
+        return;
+    }
+    // 예약한 뒤에 규칙이 바뀌었을 수 있다
+    let text = match state.word_filter.check(&message.room, &message.content) {
+        word_filter::Verdict::Allow(text) => text,
+        word_filter::Verdict::Reject => {
+            tracing::info!("Dropped scheduled message {}: contains a blocked word", message.id);
+            return;
+        }
+    };
+
+    let identity = profile::chat_identity(state, message.user_id, &message.username).await;
+    let author = Author {
+        user_id: message.user_id,
+        username: message.username.clone(),
+        display_name: identity.display_name,
+        avatar_url: identity.avatar_url,
+        bot: message.is_bot,
+    };
+    let format = MessageFormat::parse(&message.format);
+    let shadow_banned = restrictions.shadow_banned;
+    let message_id = messages::send(state, &message.room, &author, Draft::new(text, format), shadow_banned).await;
+    tracing::info!("Sent scheduled message {} as message {:?}", message.id, message_id);
+}
+
+// --- API ---
+
+#[derive(Debug, Deserialize)]
+pub struct SchedulePayload {
+    text: String,
+    #[serde(default)]
+    format: MessageFormat,
+    send_at: Option<DateTime<Utc>>,
+    // 바로 보내는 메시지만 수명을 정할 수 있다
+    #[serde(default)]
+    ttl_seconds: Option<u64>,
+}
+
+// POST /rooms/:room/messages: 메시지를 바로 보내거나 send_at 에 보내도록 예약한다
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<SchedulePayload>,
+) -> Response {
+    let mut errors = ValidationErrors::default();
+    let length = payload.text.chars().count();
+    if payload.text.trim().is_empty() {
+        errors.add("text", "required", "text is required");
+    } else if length > *MESSAGE_MAX_CHARS {
+        errors.add("text", "too_long", format!("text must be at most {} characters", *MESSAGE_MAX_CHARS));
+    }
+    let now = Utc::now();
+    match payload.send_at {
+        None => {}
+        Some(send_at) if send_at <= now => errors.add("send_at", "in_past", "send_at must be in the future"),
+        Some(send_at) if send_at > now + chrono::Duration::days(SCHEDULE_MAX_DAYS) => errors.add(
+            "send_at",
+            "too_far",
+            format!("send_at must be at most {} days ahead", SCHEDULE_MAX_DAYS),
+        ),
+        Some(_) => {}
+    }
+    if payload.send_at.is_some() && payload.ttl_seconds.is_some() {
+        errors.add("ttl_seconds", "unsupported", "Scheduled messages cannot disappear");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let Some(send_at) = payload.send_at else {
+        return send_now(&state, &claims, &room, payload).await;
+    };
+    if let Err(e) = messages::check_can_post(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    // 금칙어는 보낼 때 다시 확인하지만, 걸리는 글은 미리 알려 준다
+    if let word_filter::Verdict::Reject = state.word_filter.check(&room, &payload.text) {
+        return ApiError::bad_request("message_rejected", "Message contains a blocked word").into_response();
+    }
+
+    match state.repos.scheduled_messages.count(claims.user_id).await {
+        Ok(count) if count >= SCHEDULED_PER_USER_MAX => {
+            return ApiError::conflict(
+                "scheduled_limit_reached",
+                format!("You can have at most {} scheduled messages", SCHEDULED_PER_USER_MAX),
+            )
+            .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let created = state
+        .repos
+        .scheduled_messages
+        .create(claims.user_id, &room, &payload.text, payload.format.as_str(), send_at)
+        .await;
+    match created {
+        Ok(scheduled) => {
+            tracing::info!("User {} scheduled message {} for {}", claims.user_id, scheduled.id, send_at);
+            state.scheduled_messages.wake.notify_one();
+            (StatusCode::CREATED, Json(scheduled)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// 웹소켓으로 보낸 메시지와 똑같이 검사하고 바로 보낸다. 명령의 응답은 본문으로 돌려준다.
+async fn send_now(state: &AppState, claims: &Claims, room: &str, payload: SchedulePayload) -> Response {
+    let identity = profile::chat_identity(state, claims.user_id, &claims.sub).await;
+    let author = Author {
+        user_id: claims.user_id,
+        username: claims.sub.clone(),
+        display_name: identity.display_name,
+        avatar_url: identity.avatar_url,
+        bot: claims.is_bot(),
+    };
+    let SchedulePayload { text, format, ttl_seconds, .. } = payload;
+    match messages::post(state, claims, room, &author, text, format, ttl_seconds).await {
+        Ok(Posted::Message(message_id)) => {
+            (StatusCode::CREATED, Json(json!({ "message_id": message_id, "room": room }))).into_response()
+        }
+        Ok(Posted::Reply { command, text }) => Json(json!({ "command": command, "text": text })).into_response(),
+        Ok(Posted::Command) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// GET /me/scheduled-messages: 아직 보내지 않은 예약 메시지
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.scheduled_messages.list(claims.user_id).await {
+        Ok(scheduled) => Json(scheduled).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/scheduled-messages/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.scheduled_messages.delete(id, claims.user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("scheduled_message_not_found", "Scheduled message not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,78 @@
+// --- DB 스키마 ---
+//
+// migrations/ 의 SQL 파일은 빌드할 때 바이너리에 들어가므로(sqlx::migrate!) 배포할 때 함께 옮길 필요가 없다.
+// 적용한 버전은 DB 의 `_sqlx_migrations` 테이블에 남는다.
+//
+// 서버는 시작할 때 아직 적용하지 않은 마이그레이션을 적용한다. 여러 인스턴스가 함께 떠도 Postgres 의 advisory
+// lock 으로 한 곳에서만 적용한다. database.migrate_on_startup (DATABASE_MIGRATE_ON_STARTUP) 를 끄면 적용하지 않고
+// 확인만 해서, 밀려 있으면 무엇이 빠졌는지 남기고 바로 끝낸다. 그때는 `webchat migrate` 로 먼저 적용한다.
+// DB 에 이 빌드가 모르는 마이그레이션이 적용돼 있으면(더 새 버전이 적용한 DB 에 옛 바이너리를 띄운 경우) 어느
+// 쪽이든 끝낸다.
+//
+// SQLite 와 메모리 저장소(repo/sqlite/), MySQL 저장소(repo/mysql/, mysql 기능)는 migrations_sqlite/ 와
+// migrations_mysql/ 의 따로 쓴 마이그레이션을 열 때 바로 적용하므로 database.migrate_on_startup 을 보지 않는다.
+
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    PgPool,
+};
+
+use crate::config;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+pub static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations_sqlite");
+#[cfg(feature = "mysql")]
+pub static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations_mysql");
+
+// DB 에 적용된 버전. 한 번도 마이그레이션하지 않은 DB 면 비어 있다.
+async fn applied(db: &PgPool) -> Result<Vec<i64>, sqlx::Error> {
+    let versions = sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+        .fetch_all(db)
+        .await;
+    match versions {
+        // undefined_table
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("42P01") => Ok(Vec::new()),
+        versions => versions,
+    }
+}
+
+// 아직 적용하지 않은 마이그레이션을 적용하고 그 수를 돌려준다
+pub async fn migrate(db: &PgPool) -> Result<usize, MigrateError> {
+    let applied = applied(db).await?;
+    let pending = MIGRATOR.iter().filter(|m| !applied.contains(&m.version)).count();
+    MIGRATOR.run(db).await?;
+    Ok(pending)
+}
+
+// 이 빌드가 기대하는 스키마와 DB 가 맞는지 확인한다
+async fn check(db: &PgPool) -> Result<(), String> {
+    let applied = applied(db).await.map_err(|e| format!("Failed to read the applied migrations: {}", e))?;
+    if let Some(unknown) = applied.iter().find(|&&version| !MIGRATOR.iter().any(|m| m.version == version)) {
+        return Err(format!(
+            "The database has migration {} applied, which this build does not know; it was migrated by a newer version",
+            unknown
+        ));
+    }
+    let pending: Vec<_> = MIGRATOR.iter().filter(|m| !applied.contains(&m.version)).collect();
+    if let Some(first) = pending.first() {
+        return Err(format!(
+            "The database schema is behind: {} migration(s) not applied, starting with {} ({}). \
+             Run `webchat migrate` or enable database.migrate_on_startup",
+            pending.len(),
+            first.version,
+            first.description
+        ));
+    }
+    Ok(())
+}
+
+// Postgres 저장소로 서버를 띄우기 전에 부른다
+pub async fn prepare(db: &PgPool) -> Result<(), String> {
+    if config::get().database.migrate_on_startup {
+        let pending = migrate(db).await.map_err(|e| format!("Failed to migrate the database: {}", e))?;
+        if pending > 0 {
+            tracing::info!("Applied {} database migration(s)", pending);
+        }
+    }
+    check(db).await
+}
@@ -0,0 +1,73 @@
+// --- 보안 헤더 ---
+//
+// 정적 파일, 오류 응답, CORS 사전 요청까지 모든 응답에 Content-Security-Policy, Strict-Transport-Security,
+// X-Content-Type-Options 를 붙인다. 값은 [security_headers] 설정에서 읽고, 빈 문자열이나 0 이면 그 헤더를 뺀다.
+//
+// frame-ancestors 는 따로 설정해 CSP 끝에 붙인다. `'none'` 이나 `'self'` 면 오래된 브라우저를 위해
+// X-Frame-Options 도 보낸다. 기본 CSP 는 static/ 의 페이지(인라인 스크립트, 캡차 위젯, 저장소의 이미지와 음성)가
+// 동작하는 범위로 잡았으니, 프론트엔드를 바꾸면 함께 고친다.
+//
+// 핸들러가 이미 같은 헤더를 붙였으면 그 값을 그대로 둔다.
+// HSTS 는 브라우저가 HTTPS 응답에서만 따르므로 평문 HTTP 로 개발할 때는 영향이 없다.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use once_cell::sync::Lazy;
+
+use crate::config;
+
+static HEADERS: Lazy<Vec<(HeaderName, HeaderValue)>> = Lazy::new(|| {
+    let settings = &config::get().security_headers;
+    let mut headers = Vec::new();
+    let mut add = |name: HeaderName, value: String| {
+        // 설정을 읽을 때 검증했다
+        headers.push((name, HeaderValue::from_str(&value).expect("security headers are validated")));
+    };
+
+    let ancestors = settings.frame_ancestors.trim();
+    let policy = settings.content_security_policy.trim().trim_end_matches(';');
+    let policy = match (policy.is_empty(), ancestors.is_empty()) {
+        (_, true) => policy.to_string(),
+        (true, false) => format!("frame-ancestors {}", ancestors),
+        (false, false) => format!("{}; frame-ancestors {}", policy, ancestors),
+    };
+    if !policy.is_empty() {
+        add(header::CONTENT_SECURITY_POLICY, policy);
+    }
+    match ancestors {
+        "'none'" => add(header::X_FRAME_OPTIONS, "DENY".to_string()),
+        "'self'" => add(header::X_FRAME_OPTIONS, "SAMEORIGIN".to_string()),
+        _ => {}
+    }
+
+    if settings.hsts_max_age_seconds > 0 {
+        let mut hsts = format!("max-age={}", settings.hsts_max_age_seconds);
+        if settings.hsts_include_subdomains {
+            hsts.push_str("; includeSubDomains");
+        }
+        if settings.hsts_preload {
+            hsts.push_str("; preload");
+        }
+        add(header::STRICT_TRANSPORT_SECURITY, hsts);
+    }
+    if settings.content_type_nosniff {
+        add(header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string());
+    }
+    headers
+});
+
+// 라우터 가장 바깥에 거는 미들웨어
+pub async fn layer(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    for (name, value) in HEADERS.iter() {
+        if !headers.contains_key(name) {
+            headers.insert(name.clone(), value.clone());
+        }
+    }
+    response
+}
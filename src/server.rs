@@ -0,0 +1,396 @@
+// --- 서버 ---
+//
+// `Server::builder().build()` 가 설정을 정하고 database.url 의 저장소를 연 뒤 공유 상태를 만들고 백그라운드 태스크를 띄운다.
+// 만든 서버는 `serve()` 로 설정한 주소에서 직접 서비스하거나(바이너리), `router()` 를 다른 axum 앱에 붙인 뒤 그
+// 앱이 멈출 때 `shutdown()` 을 부른다.
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    response::Redirect,
+    routing::{delete, get, post, put},
+    Router,
+};
+use sqlx::PgPool;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tower_http::trace::TraceLayer;
+
+use crate::{
+    account, admin, anonymization, attachments, avatar, bots, bus, capabilities, captcha, cluster,
+    commands::CommandRegistry,
+    config, connect_db,
+    connections::{ConnectionLimits, ConnectionRegistry},
+    cors, digest, email_verification, ephemeral, error, export, forwarding, friends, get_rooms_handler, grpc, guest,
+    health, history, ip_bans, keys,
+    keyword_alerts::{self, KeywordIndex},
+    link_preview, login_handler, magic_link,
+    mail::Mailer,
+    maintenance, message_writer, monitoring, notifications, oauth, passkeys,
+    password::Passwords,
+    password_reset, polls, presence, profile, register_handler, reminders,
+    repo::{self, NewMessage, Repos},
+    reports, request_id, request_limits, retention,
+    revocation::{self, RevocationStore},
+    room_export,
+    room_registry::RoomRegistry,
+    scheduled_messages, schema, security_headers, session, shutdown,
+    spam::{self, SpamDetector},
+    sse, stars, storage, telemetry, throttle, tls, two_factor, voice,
+    web_push::{self, WebPush},
+    webhooks,
+    word_filter::WordFilter,
+    ws, AppState,
+};
+
+#[derive(Default)]
+pub struct ServerBuilder {
+    config: Option<config::Config>,
+    db: Option<PgPool>,
+}
+
+impl ServerBuilder {
+    // 설정을 직접 넘긴다. 넘기지 않으면 config::init 으로 이미 정한 설정을 쓰고, 그것도 없으면 설정 파일과 환경
+    // 변수에서 읽는다.
+    pub fn config(mut self, config: config::Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    // 앱이 이미 연 Postgres 풀을 나눠 쓴다. 넘기지 않으면 database.url 로 연결한다.
+    pub fn database(mut self, db: PgPool) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    pub async fn build(self) -> Result<Server, String> {
+        if self.config.is_some() || !config::is_installed() {
+            let config = match self.config {
+                Some(config) => config,
+                None => config::load().map_err(|problems| problems.join("; "))?,
+            };
+            config::install(config).map_err(|problems| problems.join("; "))?;
+        }
+        // 설정을 확인할 때 읽어 본 키라도 그 사이 파일이 바뀌었을 수 있으므로, 여기서 읽지 못하면 패닉 대신 오류로
+        // 돌려준다
+        let jwt_keys = keys::load().map_err(|e| format!("Failed to load the JWT keys: {}", e))?;
+
+        // Postgres 는 여기서 스키마를 맞추고(schema.rs), 나머지 저장소는 열 때 마이그레이션을 적용한다
+        let db = match self.db {
+            Some(db) => Some(db),
+            None if repo::Backend::configured() == repo::Backend::Postgres => Some(connect_db().await?),
+            None => None,
+        };
+        let repos = match &db {
+            Some(db) => {
+                schema::prepare(db).await?;
+                Repos::postgres(db.clone())
+            }
+            None => {
+                let database = &config::get().database;
+                Repos::open(database.url.as_deref().expect("database.url is validated"), database.max_connections)
+                    .await?
+            }
+        };
+        tracing::info!("Database connected successfully");
+
+        let (state, pending_messages) = app_state(repos).await?;
+        revocation::spawn_purge_task(state.revoked_tokens.clone(), state.repos.clone());
+        throttle::spawn_purge_task(state.repos.clone());
+        guest::spawn_purge_task(state.repos.clone());
+        link_preview::spawn_purge_task(state.repos.clone());
+        notifications::spawn_purge_task(state.repos.clone());
+        spam::spawn_purge_task(state.spam.clone());
+        captcha::init();
+        attachments::init();
+        shutdown::init();
+        monitoring::init();
+
+        tracing::info!(
+            "JWT signing algorithm: {:?}, verification keys: {:?}",
+            jwt_keys.algorithm(),
+            jwt_keys.kids()
+        );
+        tracing::info!("Password hashing algorithm: {:?}", state.passwords.algorithm());
+
+        digest::spawn_task(state.clone());
+        reminders::spawn_task(state.clone());
+        scheduled_messages::spawn_task(state.clone());
+        ephemeral::spawn_task(state.clone());
+        polls::spawn_task(state.clone());
+        message_writer::spawn_task(state.clone(), pending_messages);
+        retention::spawn_task(state.clone());
+        cluster::spawn_presence_task(state.clone());
+        let grpc = grpc::spawn_server(state.clone());
+        anonymization::resume(state.clone()).await;
+        Ok(Server { state, db, grpc })
+    }
+}
+
+// 저장소로 공유 상태를 만든다. 백그라운드 태스크는 띄우지 않는다. 저장 대기열의 받는 쪽을 함께 돌려준다.
+pub(crate) async fn app_state(repos: Repos) -> Result<(AppState, mpsc::Receiver<NewMessage>), String> {
+    let revoked_tokens =
+        Arc::new(RevocationStore::load(&repos).await.map_err(|e| format!("Failed to load revoked tokens: {}", e))?);
+    let word_filter =
+        Arc::new(WordFilter::load(&repos).await.map_err(|e| format!("Failed to load word filters: {}", e))?);
+    let keywords =
+        Arc::new(KeywordIndex::load(&repos).await.map_err(|e| format!("Failed to load keyword alerts: {}", e))?);
+    let push = Arc::new(WebPush::start(repos.clone()));
+
+    let (message_writer, pending_messages) = message_writer::MessageWriter::new();
+    let rooms = Arc::new(RoomRegistry::default());
+    let connections = Arc::new(ConnectionRegistry::default());
+
+    // 애플리케이션 상태 초기화
+    let state = AppState {
+        repos,
+        rooms: rooms.clone(),
+        bus: bus::configured(rooms, connections.clone(), revoked_tokens.clone()),
+        passwords: Arc::new(Passwords::configured()),
+        revoked_tokens,
+        connections,
+        connection_limits: Arc::new(ConnectionLimits::default()),
+        mailer: Arc::new(Mailer::configured()),
+        avatars: storage::open("Avatar", &config::get().storage.avatar),
+        attachments: storage::open("Attachment", &config::get().storage.attachment),
+        word_filter,
+        keywords,
+        push,
+        spam: Arc::new(SpamDetector::default()),
+        guest_posts: Arc::new(guest::PostInterval::default()),
+        webhook_posts: Arc::new(webhooks::PostRate::default()),
+        commands: Arc::new(CommandRegistry::with_builtins()),
+        reminders: Arc::new(reminders::Scheduler::default()),
+        scheduled_messages: Arc::new(scheduled_messages::Scheduler::default()),
+        ephemeral: Arc::new(ephemeral::Sweeper::default()),
+        polls: Arc::new(polls::Scheduler::default()),
+        message_writer: Arc::new(message_writer),
+        shutdown: Arc::new(shutdown::Shutdown::default()),
+    };
+    Ok((state, pending_messages))
+}
+
+pub struct Server {
+    state: AppState,
+    db: Option<PgPool>,
+    grpc: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::default()
+    }
+
+    // 채팅 API, 웹소켓, 정적 프론트엔드를 모두 담은 라우터. 상태는 이미 들어 있다.
+    pub fn router(&self) -> Router {
+        router(self.state.clone())
+    }
+
+    // Postgres 저장소로 떴을 때의 연결 풀
+    pub fn db(&self) -> Option<&PgPool> {
+        self.db.as_ref()
+    }
+
+    // server.bind 에서 직접 서비스한다. 종료 신호를 받으면 연결을 정리하고 돌아온다.
+    pub async fn serve(self) -> Result<(), String> {
+        let app = self.router();
+        let addr = config::get().server.bind;
+        let on_signal = shutdown::on_signal(self.state.clone());
+        match tls::load().await {
+            Some(rustls) => {
+                tracing::info!("Server listening on https://{}", addr);
+                tls::serve(addr, rustls, app, on_signal).await.map_err(|e| format!("HTTPS server failed: {}", e))?;
+            }
+            None => {
+                tracing::info!("Server listening on {}", addr);
+                let listener =
+                    tokio::net::TcpListener::bind(addr).await.map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+                axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                    .with_graceful_shutdown(on_signal)
+                    .await
+                    .map_err(|e| format!("HTTP server failed: {}", e))?;
+            }
+        }
+        self.finish().await;
+        Ok(())
+    }
+
+    // 라우터를 붙인 앱이 요청 처리를 멈춘 뒤 부른다. 열린 연결을 닫고 저장 대기열을 비운다.
+    // SHUTDOWN_DRAIN_SECONDS 안에 끝나지 않으면 남은 일을 버리고 돌아온다.
+    pub async fn shutdown(self) {
+        shutdown::begin(&self.state);
+        let timeout = shutdown::drain_timeout();
+        if tokio::time::timeout(timeout, self.finish()).await.is_err() {
+            tracing::warn!("Drain timeout reached; shutting down with work left");
+        }
+    }
+
+    async fn finish(self) {
+        if let Some(grpc) = self.grpc {
+            let _ = grpc.await;
+        }
+        shutdown::drain(&self.state).await;
+    }
+}
+
+fn router(state: AppState) -> Router {
+    // 새로 로그인하거나 가입하는 라우트. 점검 중에는 받지 않는다.
+    let login_routes = Router::new()
+        .route(
+            "/register",
+            post(register_handler).layer(middleware::from_fn_with_state(state.clone(), throttle::register)),
+        )
+        .route(
+            "/login",
+            post(login_handler).layer(middleware::from_fn_with_state(state.clone(), throttle::login)),
+        )
+        .route(
+            "/guest",
+            post(guest::guest_handler).layer(middleware::from_fn_with_state(state.clone(), throttle::register)),
+        )
+        .route("/guest/upgrade", post(guest::upgrade_handler))
+        .route(
+            "/login/2fa",
+            post(two_factor::login_handler).layer(middleware::from_fn_with_state(state.clone(), throttle::login_code)),
+        )
+        .route(
+            "/login/magic",
+            post(magic_link::request_handler)
+                .layer(middleware::from_fn_with_state(state.clone(), throttle::magic_link)),
+        )
+        .route("/login/magic/verify", post(magic_link::verify_handler))
+        .route("/login/passkey/start", post(passkeys::login_start_handler))
+        .route("/login/passkey/finish", post(passkeys::login_finish_handler))
+        .route("/auth/:provider", get(oauth::authorize_handler))
+        .route("/auth/:provider/callback", get(oauth::callback_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), maintenance::enforce));
+
+    // 로그인·가입·토큰 갱신 라우트. 차단된 IP 에서 온 요청은 받지 않는다.
+    let auth_routes = Router::new()
+        .merge(login_routes)
+        .route("/refresh", post(session::refresh_handler))
+        .route(
+            "/password-reset/request",
+            post(password_reset::request_handler)
+                .layer(middleware::from_fn_with_state(state.clone(), throttle::password_reset)),
+        )
+        .route("/password-reset/confirm", post(password_reset::confirm_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), ip_bans::enforce))
+        .layer(DefaultBodyLimit::max(*request_limits::AUTH_BODY_MAX_BYTES));
+
+    // 라우터 설정
+    let app = Router::new()
+        .route("/", get(|| async { Redirect::to("/static/login.html") }))
+        .route("/rooms", get(get_rooms_handler))
+        .route("/healthz", get(health::liveness_handler))
+        .route("/readyz", get(health::readiness_handler))
+        .route("/metrics", get(monitoring::metrics_handler))
+        .merge(auth_routes)
+        .route("/logout", post(session::logout_handler))
+        .route("/auth/providers", get(oauth::providers_handler))
+        .route("/auth/captcha", get(captcha::config_handler))
+        .route("/verify/:token", post(email_verification::verify_handler))
+        .route("/me", delete(account::delete_account_handler))
+        .route("/me/export", get(export::list_handler).post(export::request_handler))
+        .route("/me/export/:id", get(export::status_handler))
+        .route("/me/export/:id/download", get(export::download_handler))
+        .route(
+            "/me/avatar",
+            post(avatar::upload_handler)
+                .delete(avatar::delete_handler)
+                // multipart 경계와 다른 필드가 들어갈 여유를 둔다
+                .layer(DefaultBodyLimit::max(*avatar::AVATAR_MAX_BYTES + 64 * 1024))
+                .layer(middleware::from_fn(request_limits::for_uploads)),
+        )
+        .route("/me/password", post(account::change_password_handler))
+        .route("/me/email", put(email_verification::change_email_handler))
+        .route(
+            "/me/2fa/totp",
+            post(two_factor::enroll_handler).delete(two_factor::disable_handler),
+        )
+        .route("/me/2fa/totp/confirm", post(two_factor::confirm_handler))
+        .route("/me/passkeys", get(passkeys::list_handler))
+        .route("/me/passkeys/:id", delete(passkeys::delete_handler))
+        .route("/me/passkeys/register/start", post(passkeys::register_start_handler))
+        .route("/me/passkeys/register/finish", post(passkeys::register_finish_handler))
+        .route("/me/email/verify", post(email_verification::resend_handler))
+        .route("/me/notifications", get(notifications::list_handler))
+        .route("/me/notifications/read-all", post(notifications::read_all_handler))
+        .route("/me/notifications/:id/read", post(notifications::read_handler))
+        .route(
+            "/me/rooms/:room/notifications",
+            get(notifications::get_room_pref_handler).put(notifications::put_room_pref_handler),
+        )
+        .route("/me/keywords", get(keyword_alerts::list_handler).post(keyword_alerts::create_handler))
+        .route("/me/keywords/:id", delete(keyword_alerts::delete_handler))
+        .route("/me/email-digest", get(digest::get_handler).put(digest::put_handler))
+        .route("/me/reminders", get(reminders::list_handler))
+        .route("/me/reminders/:id", delete(reminders::delete_handler))
+        .route("/me/scheduled-messages", get(scheduled_messages::list_handler))
+        .route("/me/scheduled-messages/:id", delete(scheduled_messages::delete_handler))
+        .route("/me/bots", get(bots::list_handler).post(bots::create_handler))
+        .route("/me/bots/:id", delete(bots::delete_handler))
+        .route("/me/bots/:id/tokens", get(bots::list_tokens_handler).post(bots::create_token_handler))
+        .route("/me/bots/:id/tokens/:token_id", delete(bots::revoke_token_handler))
+        .route("/me/push-subscriptions", get(web_push::list_handler).post(web_push::subscribe_handler))
+        .route("/me/push-subscriptions/:id", delete(web_push::delete_handler))
+        .route("/me/status", get(presence::get_handler).patch(presence::update_handler))
+        .route("/me/friends", get(friends::list_handler).post(friends::request_handler))
+        .route("/me/friends/:id", delete(friends::delete_handler))
+        .route("/me/friends/:id/accept", post(friends::accept_handler))
+        .route("/me/sessions", get(session::list_sessions_handler))
+        .route("/me/sessions/:id", delete(session::delete_session_handler))
+        .route("/rooms/:room/messages", get(history::list_handler).post(scheduled_messages::create_handler))
+        .route("/rooms/:room/messages/:id", get(history::permalink_handler))
+        .route("/rooms/:room/export", get(room_export::export_handler))
+        .route("/rooms/:room/retention", get(retention::get_handler).put(retention::put_handler))
+        .route("/rooms/:room/webhooks", get(webhooks::room_list_handler).post(webhooks::room_create_handler))
+        .route("/rooms/:room/webhooks/:id", delete(webhooks::room_revoke_handler))
+        .route("/rooms/:room/polls", get(polls::list_handler).post(polls::create_handler))
+        .route("/polls/:id/close", post(polls::close_handler))
+        .route(
+            "/rooms/:room/attachments",
+            post(attachments::upload_handler)
+                .layer(DefaultBodyLimit::max(*attachments::ATTACHMENT_MAX_BYTES + 64 * 1024))
+                .layer(middleware::from_fn(request_limits::for_uploads)),
+        )
+        .route(
+            "/rooms/:room/voice",
+            post(attachments::voice_upload_handler)
+                .layer(DefaultBodyLimit::max(*voice::VOICE_MAX_BYTES + 64 * 1024))
+                .layer(middleware::from_fn(request_limits::for_uploads)),
+        )
+        .route("/attachments/:id", get(attachments::download_handler))
+        .route("/attachments/:id/url", get(attachments::url_handler))
+        .route("/attachments/:id/thumbnail", get(attachments::thumbnail_handler))
+        .route("/avatars/:user", get(avatar::serve_handler))
+        .route("/messages/:id/report", post(reports::report_handler))
+        .route("/messages/:id/forward", post(forwarding::forward_handler))
+        .route("/messages/:id/star", post(stars::star_handler).delete(stars::unstar_handler))
+        .route("/me/starred", get(stars::list_handler))
+        .route("/users/:username", get(profile::get_handler).patch(profile::update_handler))
+        .nest("/admin", admin::routes(state.clone()))
+        .route("/ws/:room", get(ws::websocket_handler))
+        .route("/sse/:room", get(sse::stream_handler))
+        .route("/.well-known/jwks.json", get(keys::jwks_handler))
+        .route("/.well-known/webchat", get(capabilities::discovery_handler))
+        .route("/push/vapid-public-key", get(web_push::public_key_handler))
+        .route("/hooks/:token", post(webhooks::post_handler))
+        .fallback(error::not_found_handler)
+        .layer(middleware::from_fn(request_limits::timeout))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(telemetry::http_span)
+                .on_response(telemetry::record_status)
+                .on_failure(()),
+        )
+        .with_state(state)
+        // 정적 파일 서빙 (프론트엔드)
+        .nest_service("/static", tower_http::services::ServeDir::new("static"));
+    // 다른 출처의 프론트엔드를 허용했으면 사전 요청까지 여기서 처리한다
+    let app = match cors::layer() {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
+    app.layer(middleware::from_fn(security_headers::layer)).layer(middleware::from_fn(request_id::layer))
+}
@@ -0,0 +1,253 @@
+// --- 로그인 세션: 리프레시 토큰, 로그아웃, 세션 관리 ---
+//
+// 로그인하면 세션을 하나 만들고, 짧은 액세스 JWT 와 함께 DB 에 저장되는 리프레시 토큰을
+// httpOnly 쿠키로 내려준다. `POST /refresh` 는 리프레시 토큰을 한 번만 쓸 수 있게
+// 회전시키고, 이미 사용된 토큰이 다시 오면 탈취로 보고 세션 전체를 폐기한다.
+// 리프레시 토큰의 계열(family_id)은 세션 id 와 같다.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use once_cell::sync::Lazy;
+use std::convert::Infallible;
+
+use crate::{
+    auth::{decode_token, generate_token, hash_token, issue_access_token, token_from_request, AuthUser},
+    client_ip::ClientIp,
+    config,
+    error::ApiError,
+    repo::RepoResult,
+    ws::CloseReason,
+    AppState,
+};
+
+pub const ACCESS_COOKIE: &str = "token";
+pub const REFRESH_COOKIE: &str = "refresh_token";
+
+static REFRESH_TOKEN_TTL: Lazy<chrono::Duration> =
+    Lazy::new(|| chrono::Duration::days(config::get().jwt.refresh_token_ttl_days));
+
+// 로그인 요청을 보낸 클라이언트 정보 (세션 목록에 표시)
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let ip = ClientIp::of(&parts.extensions, &parts.headers).map(|ClientIp(ip)| ip.to_string());
+        Ok(ClientInfo { user_agent, ip })
+    }
+}
+
+// 리프레시 토큰을 새로 만들어 저장하고 원문을 돌려준다
+async fn issue_refresh_token(state: &AppState, user_id: i32, session_id: &str) -> RepoResult<String> {
+    let token = generate_token();
+    let expires_at = chrono::Utc::now() + *REFRESH_TOKEN_TTL;
+    state.repos.sessions.add_refresh_token(user_id, session_id, &hash_token(&token), expires_at).await?;
+    Ok(token)
+}
+
+fn access_cookie(token: String) -> Cookie<'static> {
+    Cookie::build((ACCESS_COOKIE, token))
+        .path("/")
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .build()
+}
+
+fn refresh_cookie(token: String) -> Cookie<'static> {
+    Cookie::build((REFRESH_COOKIE, token))
+        .path("/")
+        .same_site(SameSite::Strict)
+        .http_only(true)
+        .max_age(time_duration(*REFRESH_TOKEN_TTL))
+        .build()
+}
+
+fn time_duration(d: chrono::Duration) -> time::Duration {
+    time::Duration::seconds(d.num_seconds())
+}
+
+pub fn clear_auth_cookies(jar: CookieJar) -> CookieJar {
+    jar.remove(Cookie::build(ACCESS_COOKIE).path("/"))
+        .remove(Cookie::build(REFRESH_COOKIE).path("/"))
+}
+
+// 새 로그인 세션을 시작하고 토큰을 발급한다.
+// 로그인 방식과 관계없이 인증에 성공하면 이 함수로 응답한다.
+pub async fn start_session(
+    state: &AppState,
+    jar: CookieJar,
+    user_id: i32,
+    username: &str,
+    client: ClientInfo,
+) -> Response {
+    // 어떤 방법으로 로그인하든 비활성화된 계정은 여기서 막는다
+    let guest = match state.repos.users.find(user_id).await {
+        Ok(Some(user)) if user.disabled => {
+            return ApiError::forbidden("account_disabled", "This account has been disabled").into_response()
+        }
+        Ok(Some(user)) => user.is_guest,
+        Ok(None) => return ApiError::unauthorized("invalid_credentials", "Invalid credentials").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let session_id = generate_token();
+    let created =
+        state.repos.sessions.create(&session_id, user_id, client.user_agent.as_deref(), client.ip.as_deref()).await;
+    if let Err(e) = created {
+        return ApiError::from(e).into_response();
+    }
+
+    issue_tokens(state, jar, user_id, username, &session_id, guest).await
+}
+
+// 액세스 토큰과 리프레시 토큰을 함께 발급하는 공통 응답
+pub async fn issue_tokens(
+    state: &AppState,
+    jar: CookieJar,
+    user_id: i32,
+    username: &str,
+    session_id: &str,
+    guest: bool,
+) -> Response {
+    let (token, claims) = match issue_access_token(user_id, username, session_id, guest) {
+        Ok(t) => t,
+        Err(_) => return ApiError::internal().into_response(),
+    };
+
+    let refresh_token = match issue_refresh_token(state, user_id, session_id).await {
+        Ok(t) => t,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let jar = jar.add(access_cookie(token.clone())).add(refresh_cookie(refresh_token));
+    let body = Json(serde_json::json!({ "token": token, "expires_at": claims.exp }));
+    (jar, body).into_response()
+}
+
+// 세션을 폐기한다: 리프레시 토큰 무효화, 이 세션의 액세스 토큰 차단, 열린 소켓 종료
+pub async fn revoke_session(state: &AppState, session_id: &str) -> RepoResult<()> {
+    state.repos.sessions.revoke(session_id).await?;
+
+    state.revoked_tokens.revoke_session(session_id);
+    let closed = state.connections.close_session(session_id, CloseReason::SessionRevoked);
+    tracing::info!("Revoked session {} ({} open connections closed)", session_id, closed);
+    Ok(())
+}
+
+// 사용자의 활성 세션을 모두 폐기한다 (`except` 세션은 남긴다). 폐기한 세션 수를 돌려준다.
+pub async fn revoke_user_sessions(state: &AppState, user_id: i32, except: Option<&str>) -> usize {
+    let sessions = state.repos.sessions.active_ids(user_id, except).await.unwrap_or_default();
+
+    for session_id in &sessions {
+        if let Err(e) = revoke_session(state, session_id).await {
+            tracing::warn!("Failed to revoke session {}: {}", session_id, e);
+        }
+    }
+    sessions.len()
+}
+
+// 리프레시 핸들러
+pub async fn refresh_handler(State(state): State<AppState>, jar: CookieJar) -> Response {
+    let presented = match jar.get(REFRESH_COOKIE) {
+        Some(c) => c.value().to_string(),
+        None => return ApiError::unauthorized("missing_refresh_token", "Refresh token not provided").into_response(),
+    };
+    let token_hash = hash_token(&presented);
+
+    // 아직 쓰이지 않은 유효한 토큰이면 원자적으로 사용 처리한다
+    match state.repos.sessions.use_refresh_token(&token_hash).await {
+        Ok(Some(row)) => issue_tokens(&state, jar, row.user_id, &row.username, &row.session_id, row.is_guest).await,
+        Ok(None) => {
+            // 이미 사용된 토큰의 재사용이면 세션 전체를 폐기
+            let reused = state.repos.sessions.refresh_token_session(&token_hash, true).await;
+
+            if let Ok(Some(session_id)) = reused {
+                tracing::warn!("Refresh token reuse detected, revoking session {}", session_id);
+                if let Err(e) = revoke_session(&state, &session_id).await {
+                    tracing::warn!("Failed to revoke session {}: {}", session_id, e);
+                }
+            }
+
+            let error = ApiError::unauthorized("invalid_refresh_token", "Invalid refresh token");
+            (clear_auth_cookies(jar), error).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// 로그아웃 핸들러: 현재 액세스 토큰을 폐기 목록에 올리고, 세션을 폐기한 뒤 쿠키를 지운다
+pub async fn logout_handler(State(state): State<AppState>, headers: HeaderMap, jar: CookieJar) -> Response {
+    let session_id = match token_from_request(&headers, &jar).and_then(|t| decode_token(&t).ok()) {
+        Some(claims) => {
+            if let Err(e) = state.revoked_tokens.revoke(&state.repos, &claims.jti, claims.exp).await {
+                tracing::warn!("Failed to revoke token for user {}: {}", claims.user_id, e);
+                return ApiError::internal().into_response();
+            }
+            Some(claims.sid)
+        }
+        // 액세스 토큰이 이미 만료됐다면 리프레시 쿠키로 세션을 찾는다
+        None => match jar.get(REFRESH_COOKIE) {
+            Some(refresh) => {
+                state.repos.sessions.refresh_token_session(&hash_token(refresh.value()), false).await.ok().flatten()
+            }
+            None => None,
+        },
+    };
+
+    if let Some(session_id) = session_id {
+        if let Err(e) = revoke_session(&state, &session_id).await {
+            tracing::warn!("Failed to revoke session on logout: {}", e);
+        }
+    }
+
+    (StatusCode::NO_CONTENT, clear_auth_cookies(jar)).into_response()
+}
+
+// --- 세션 관리 API ---
+
+// GET /me/sessions: 내 활성 세션 목록
+pub async fn list_sessions_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let sessions = state.repos.sessions.list(claims.user_id, chrono::Utc::now() - *REFRESH_TOKEN_TTL).await;
+
+    match sessions {
+        Ok(mut sessions) => {
+            for s in &mut sessions {
+                s.current = s.id == claims.sid;
+            }
+            Json(sessions).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/sessions/:id: 세션 폐기 (해당 세션의 웹소켓도 강제 종료)
+pub async fn delete_session_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(session_id): Path<String>,
+) -> Response {
+    match state.repos.sessions.is_active(&session_id, claims.user_id).await {
+        Ok(true) => match revoke_session(&state, &session_id).await {
+            Ok(()) => StatusCode::NO_CONTENT.into_response(),
+            Err(e) => ApiError::from(e).into_response(),
+        },
+        Ok(false) => ApiError::not_found("session_not_found", "Session not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
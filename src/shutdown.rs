@@ -0,0 +1,115 @@
+// --- 서버 종료 ---
+//
+// SIGTERM 이나 SIGINT(Ctrl+C)를 받으면 새 연결을 받지 않고 열린 연결을 정리한 뒤 끝낸다.
+//
+// 1. 새 웹소켓 업그레이드와 SSE 스트림은 `503 shutting_down` 으로 거절한다. HTTP 서버와 gRPC 서버는 새 접속을
+//    받지 않고, 처리 중인 요청이 끝나기를 기다린다.
+// 2. 열린 웹소켓과 SSE 스트림, gRPC 방 스트림에 `server_shutting_down` 이벤트를 보내고
+//    `1001 server_shutdown` 으로 닫는다. 클라이언트는 잠시 뒤 다른 서버나 재시작한 서버로 다시 접속한다.
+// 3. 연결이 모두 닫히면 저장 대기열에 남은 메시지를 마저 저장한다.
+//
+// 신호를 받은 뒤 SHUTDOWN_DRAIN_SECONDS 안에 끝나지 않으면 남은 일을 버리고 바로 끝낸다.
+
+use axum::http::StatusCode;
+use once_cell::sync::Lazy;
+use std::time::Duration;
+use tokio::{signal, sync::watch};
+
+use crate::{config, error::ApiError, protocol::ServerEvent, telemetry, ws::CloseReason, AppState};
+
+static DRAIN_TIMEOUT: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().server.shutdown_drain_seconds));
+
+// 남은 연결이 닫혔는지 다시 확인하는 간격
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn init() {
+    Lazy::force(&DRAIN_TIMEOUT);
+}
+
+pub struct Shutdown {
+    started: watch::Sender<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown { started: watch::channel(false).0 }
+    }
+}
+
+impl Shutdown {
+    pub fn is_started(&self) -> bool {
+        *self.started.borrow()
+    }
+
+    // 종료가 시작될 때까지 기다린다. 이미 시작했으면 바로 끝난다.
+    pub async fn wait(&self) {
+        let mut started = self.started.subscribe();
+        let _ = started.wait_for(|started| *started).await;
+    }
+}
+
+// 종료 중에 들어온 새 연결에 돌려주는 오류
+pub fn unavailable() -> ApiError {
+    ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "shutting_down", "The server is shutting down")
+}
+
+// 연결을 닫기 직전에 보내는 이벤트
+pub fn event() -> ServerEvent {
+    ServerEvent::ServerShuttingDown
+}
+
+async fn terminate_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+// 종료 신호를 기다렸다가 종료를 시작한다. axum::serve 의 with_graceful_shutdown(HTTPS 면 tls::serve)에 넘긴다.
+pub async fn on_signal(state: AppState) {
+    terminate_signal().await;
+    begin(&state);
+    tokio::spawn(async {
+        tokio::time::sleep(*DRAIN_TIMEOUT).await;
+        tracing::warn!("Drain timeout reached; exiting with work left");
+        std::process::exit(1);
+    });
+}
+
+// 새 연결을 거절하고 열린 연결을 닫기 시작한다
+pub fn begin(state: &AppState) {
+    tracing::info!("Shutting down; draining connections for up to {:?}", *DRAIN_TIMEOUT);
+    state.shutdown.started.send_replace(true);
+    let closed = state.connections.close_all(CloseReason::ServerShutdown);
+    tracing::info!("Closing {} connections", closed);
+}
+
+pub fn drain_timeout() -> Duration {
+    *DRAIN_TIMEOUT
+}
+
+// HTTP 서버가 멈춘 뒤 남은 연결이 정리를 마치기를 기다리고 저장 대기열을 비운다.
+// 업그레이드된 웹소켓은 HTTP 서버가 기다려 주지 않으므로 여기서 기다린다.
+pub async fn drain(state: &AppState) {
+    while !state.connections.is_empty() || !state.connection_limits.is_empty() {
+        // 종료를 시작할 무렵에 막 등록된 연결도 닫는다
+        state.connections.close_all(CloseReason::ServerShutdown);
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
+    state.message_writer.flush().await;
+    tracing::info!("Shutdown complete");
+    telemetry::shutdown();
+}
@@ -0,0 +1,170 @@
+// --- 반복 메시지 스팸 감지 ---
+//
+// 사용자별로 최근 SPAM_WINDOW_SECONDS 동안 보낸 메시지와 링크를 메모리에 기억한다. 같은(또는 거의 같은)
+// 메시지를 SPAM_DUPLICATE_LIMIT 번, 같은 링크를 SPAM_LINK_LIMIT 번 보내면 그 메시지를 거부하고
+// SPAM_COOLDOWN_SECONDS 동안 글을 쓸 수 없게 하며, `spam_flags` 에 남겨 관리자가 `GET /admin/spam-flags` 로
+// 볼 수 있게 한다. 연결이 아니라 사용자 단위라서 여러 탭이나 방에 나눠 보내도 함께 센다.
+//
+// "거의 같은" 은 숫자·기호를 빼고 소문자로 바꾼 글자의 3-gram 자카드 유사도가 SPAM_SIMILARITY 이상인 경우다.
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use once_cell::sync::Lazy;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{auth::AdminUser, config, error::ApiError, link_preview, AppState};
+
+static WINDOW: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().limits.spam_window_seconds));
+static DUPLICATE_LIMIT: Lazy<usize> = Lazy::new(|| config::get().limits.spam_duplicate_limit);
+static LINK_LIMIT: Lazy<usize> = Lazy::new(|| config::get().limits.spam_link_limit);
+static SIMILARITY: Lazy<f64> = Lazy::new(|| config::get().limits.spam_similarity);
+static COOLDOWN: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().limits.spam_cooldown_seconds));
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(300);
+const SAMPLE_MAX_CHARS: usize = 200;
+
+pub enum Verdict {
+    Allow,
+    // 이미 쿨다운 중 (남은 시간)
+    Cooldown(Duration),
+    // 이 메시지로 감지됨. reason 은 spam_flags.reason 에 남는다.
+    Detected { reason: &'static str, cooldown: Duration },
+}
+
+#[derive(Default)]
+struct History {
+    // (보낸 시각, 정규화한 글자)
+    messages: VecDeque<(Instant, String)>,
+    links: VecDeque<(Instant, String)>,
+    cooldown_until: Option<Instant>,
+}
+
+impl History {
+    fn prune(&mut self, now: Instant) {
+        while self.messages.front().is_some_and(|(t, _)| now.duration_since(*t) > *WINDOW) {
+            self.messages.pop_front();
+        }
+        while self.links.front().is_some_and(|(t, _)| now.duration_since(*t) > *WINDOW) {
+            self.links.pop_front();
+        }
+        if self.cooldown_until.is_some_and(|until| until <= now) {
+            self.cooldown_until = None;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.messages.is_empty() && self.links.is_empty() && self.cooldown_until.is_none()
+    }
+}
+
+#[derive(Default)]
+pub struct SpamDetector {
+    users: Mutex<HashMap<i32, History>>,
+}
+
+// 글자만 남긴다 (숫자와 기호를 바꿔 가며 보내는 것도 같은 메시지로 본다)
+fn normalize(text: &str) -> String {
+    text.nfkc().flat_map(char::to_lowercase).filter(|c| c.is_alphabetic()).collect()
+}
+
+fn trigrams(text: &str) -> HashSet<[char; 3]> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+fn is_similar(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let (ta, tb) = (trigrams(a), trigrams(b));
+    if ta.is_empty() || tb.is_empty() {
+        return false;
+    }
+    let common = ta.intersection(&tb).count();
+    common as f64 / (ta.len() + tb.len() - common) as f64 >= *SIMILARITY
+}
+
+impl SpamDetector {
+    // 메시지를 저장하기 전에 부른다. 허용된 메시지만 기록에 남는다.
+    pub fn check(&self, user_id: i32, text: &str) -> Verdict {
+        let now = Instant::now();
+        let mut users = self.users.lock().unwrap();
+        let history = users.entry(user_id).or_default();
+        history.prune(now);
+        if let Some(until) = history.cooldown_until {
+            return Verdict::Cooldown(until - now);
+        }
+
+        let normalized = normalize(text);
+        let links: Vec<String> = link_preview::extract_urls(text).into_iter().map(String::from).collect();
+
+        let duplicates = if normalized.is_empty() {
+            0
+        } else {
+            history.messages.iter().filter(|(_, m)| is_similar(m, &normalized)).count()
+        };
+        let reason = if duplicates + 1 >= *DUPLICATE_LIMIT {
+            Some("duplicate_messages")
+        } else if links
+            .iter()
+            .any(|link| history.links.iter().filter(|(_, l)| l == link).count() + 1 >= *LINK_LIMIT)
+        {
+            Some("repeated_link")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            history.cooldown_until = Some(now + *COOLDOWN);
+            history.messages.clear();
+            history.links.clear();
+            return Verdict::Detected { reason, cooldown: *COOLDOWN };
+        }
+
+        history.messages.push_back((now, normalized));
+        history.links.extend(links.into_iter().map(|link| (now, link)));
+        Verdict::Allow
+    }
+
+    fn purge(&self) {
+        let now = Instant::now();
+        self.users.lock().unwrap().retain(|_, history| {
+            history.prune(now);
+            !history.is_empty()
+        });
+    }
+}
+
+// 오래 조용한 사용자의 기록을 주기적으로 지우는 백그라운드 태스크
+pub fn spawn_purge_task(detector: std::sync::Arc<SpamDetector>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            detector.purge();
+        }
+    });
+}
+
+// 관리자가 볼 수 있도록 남긴다. 실패해도 쿨다운은 이미 걸렸으므로 기록만 한다.
+pub async fn record(state: &AppState, user_id: i32, room: &str, reason: &str, text: &str) {
+    let sample: String = text.chars().take(SAMPLE_MAX_CHARS).collect();
+    if let Err(e) = state.repos.spam.record_flag(user_id, room, reason, &sample).await {
+        tracing::warn!("Failed to record spam flag for user {}: {}", user_id, e);
+    }
+}
+
+// GET /admin/spam-flags: 최근 것부터 100개
+pub async fn list_handler(State(state): State<AppState>, _admin: AdminUser) -> Response {
+    match state.repos.spam.list_flags(100).await {
+        Ok(flags) => Json(flags).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,205 @@
+// --- SSE 연결 ---
+//
+// 웹소켓을 막거나 끊어 버리는 프록시 뒤의 클라이언트를 위한 대체 경로. `GET /sse/:room` 은 웹소켓과 같은 이벤트를
+// 같은 JSON 으로 Server-Sent Events 스트림에 내보내고, 메시지는 `POST /rooms/:room/messages` 로 보낸다.
+//
+// 스트림 하나는 웹소켓 연결 하나와 같게 다룬다. 입장/퇴장 이벤트를 보내고, 연결 목록에 등록되어 접속 상태와
+// 사용자에게 가는 이벤트를 받으며, 연결 수 제한과 세션 폐기, 토큰 만료도 똑같이 적용된다. 서버가 스트림을
+// 끝낼 때는 웹소켓의 종료 코드와 사유를 담은 `close` 이벤트를 마지막으로 보낸다.
+//
+// 토큰은 Authorization 헤더나 인증 쿠키로 받는다. 브라우저의 EventSource 는 헤더를 붙일 수 없으므로 쿠키를 쓴다.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures::stream;
+use serde_json::json;
+use std::{convert::Infallible, net::IpAddr};
+use tokio::sync::{
+    broadcast::error::RecvError,
+    mpsc::{self, error::TrySendError},
+};
+use tracing::Instrument;
+
+use crate::{
+    auth::{AuthError, AuthUser, Claims},
+    client_ip::ClientIp,
+    connections::{ConnectionSlot, LimitExceeded},
+    error::ApiError,
+    guest, ip_bans, maintenance, monitoring, presence, profile,
+    protocol::ServerEvent,
+    request_id, shutdown,
+    ws::{self, CloseReason},
+    AppState,
+};
+
+// GET /sse/:room
+pub async fn stream_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ClientIp(ip): ClientIp,
+) -> Response {
+    if state.shutdown.is_started() {
+        return shutdown::unavailable().into_response();
+    }
+    if let Err(e) = maintenance::check(&state).await {
+        return e.into_response();
+    }
+    if ip_bans::is_banned(&state, ip).await {
+        return ip_bans::banned_response();
+    }
+    // 봇 토큰은 웹소켓처럼 chat 권한이 있어야 한다
+    if !claims.allows("chat") {
+        return AuthError::Scope.into_response();
+    }
+    if claims.guest && !guest::can_join(&room) {
+        return ApiError::forbidden("guest_room_forbidden", "Guests cannot join this room").into_response();
+    }
+    let slot = match state.connection_limits.acquire(claims.user_id, ip) {
+        Ok(slot) => slot,
+        Err(exceeded) => {
+            let message = match exceeded {
+                LimitExceeded::User(max) => format!("You already have {} open connections", max),
+                LimitExceeded::Ip(max) => format!("Too many connections from your address (max {})", max),
+            };
+            tracing::warn!("Rejected SSE stream of user {} from {}: {}", claims.user_id, ip, message);
+            return ApiError::new(StatusCode::TOO_MANY_REQUESTS, "too_many_connections", message).into_response();
+        }
+    };
+
+    // 응답 본문은 이 대기열을 읽기만 한다. 클라이언트가 떠나 본문이 버려지면 연결 태스크가 알아채고 정리한다.
+    let (events_tx, events_rx) = mpsc::channel::<Event>(*ws::SEND_QUEUE_CAPACITY);
+    let connection_id = state.connections.next_id();
+    let span = tracing::info_span!(
+        parent: None,
+        "sse.connection",
+        connection_id,
+        request_id = request_id::current().unwrap_or_default(),
+        room,
+        user_id = claims.user_id,
+    );
+    let stream = run(state, connection_id, room, claims, ip, slot, events_tx);
+    tokio::spawn(monitoring::task("sse", stream).instrument(span));
+
+    let events = stream::unfold(events_rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok::<_, Infallible>(event), rx))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+fn event(event: &ServerEvent) -> Event {
+    Event::default().data(event.to_json())
+}
+
+fn close_event(reason: CloseReason) -> Event {
+    let data = json!({ "code": reason.code(), "reason": reason.reason(), "retryable": reason.is_retryable() });
+    Event::default().event("close").data(data.to_string())
+}
+
+// 스트림 하나의 수명. 웹소켓의 handle_socket 에서 수신 처리를 뺀 것과 같다.
+async fn run(
+    state: AppState,
+    connection_id: u64,
+    room: String,
+    claims: Claims,
+    who: IpAddr,
+    slot: ConnectionSlot,
+    events: mpsc::Sender<Event>,
+) {
+    let username = claims.sub.clone();
+    let user_id = claims.user_id;
+
+    let mut rx = state.bus.subscribe(&room);
+    let identity = profile::chat_identity(&state, user_id, &username).await;
+    tracing::info!("User '{}' ({}) joined room '{}' over SSE from {}", &username, user_id, &room, who);
+
+    let join = ServerEvent::Join {
+        room: room.clone(),
+        username: username.clone(),
+        display_name: identity.display_name.clone(),
+        avatar_url: identity.avatar_url.clone(),
+    };
+    state.bus.publish(&room, join);
+
+    // 사용자에게 가는 이벤트와 종료 요청은 웹소켓처럼 연결 목록을 거쳐 온다
+    let (out_tx, mut out_rx) = mpsc::channel::<ServerEvent>(*ws::SEND_QUEUE_CAPACITY);
+    let (close_tx, mut close_rx) = mpsc::channel::<CloseReason>(1);
+    let first_connection = state.connections.register(connection_id, user_id, &claims.sid, &room, close_tx, out_tx);
+    if first_connection {
+        presence::notify(&state, user_id, &username, true).await;
+    }
+    let _ = state.repos.sessions.touch(&claims.sid).await;
+    for initial in ws::initial_events(&state, &room, user_id).await {
+        let _ = events.try_send(event(&initial));
+    }
+
+    // SSE 로는 토큰을 바꿀 수 없으므로 접속할 때의 토큰이 만료되면 끝낸다
+    let expiry = tokio::time::sleep(ws::until_expiry(claims.exp));
+    tokio::pin!(expiry);
+
+    let close = loop {
+        let next = tokio::select! {
+            biased;
+            _ = events.closed() => break None,
+            Some(reason) = close_rx.recv() => break Some(reason),
+            _ = &mut expiry => break Some(CloseReason::TokenExpired),
+            event = out_rx.recv() => match event {
+                Some(event) => event,
+                None => break None,
+            },
+            event = rx.recv() => match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("'{}' lagged behind by {} messages", username, skipped);
+                    match ws::lagged(&room, skipped) {
+                        Some(event) => event,
+                        None => break Some(CloseReason::SlowConsumer),
+                    }
+                }
+                Err(RecvError::Closed) => break Some(CloseReason::RoomDeleted),
+            },
+        };
+        match events.try_send(event(&next)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                tracing::warn!("Disconnecting slow SSE consumer '{}'", username);
+                break Some(CloseReason::SlowConsumer);
+            }
+            Err(TrySendError::Closed(_)) => break None,
+        }
+    };
+    if let Some(reason) = close {
+        // 대기열이 가득 차 있으면 종료 이벤트 없이 끝낸다
+        if reason == CloseReason::ServerShutdown {
+            let _ = events.try_send(event(&shutdown::event()));
+        }
+        let _ = events.try_send(close_event(reason));
+    }
+    drop(events);
+
+    let last_connection = state.connections.unregister(connection_id);
+
+    let leave = ServerEvent::Leave {
+        room: room.clone(),
+        username: username.clone(),
+        display_name: identity.display_name,
+        avatar_url: identity.avatar_url,
+    };
+    state.bus.publish(&room, leave);
+
+    if last_connection {
+        if let Err(e) = state.repos.users.touch_last_online(user_id).await {
+            tracing::warn!("Failed to record last online time of user {}: {}", user_id, e);
+        }
+        presence::notify(&state, user_id, &username, false).await;
+    }
+
+    tracing::info!("SSE stream for '{}' from {} closed", username, who);
+    drop(slot);
+}
@@ -0,0 +1,107 @@
+// --- 별표 메시지 ---
+//
+// 나중에 다시 볼 메시지에 별표를 붙인다. 별표는 본인만 보며 다른 사람에게 알리지 않는다. 메시지가 지워지면
+// 별표도 함께 지워지고, 그림자 차단으로 숨겨지거나 수명이 지난 메시지는 목록에서 빠진다.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{
+    auth::AuthUser,
+    error::ApiError,
+    history,
+    repo::StoredMessage,
+    AppState,
+};
+
+const PAGE_MAX: i64 = 100;
+
+#[derive(Serialize)]
+struct Star {
+    // 다음 페이지를 가져올 때 before 로 쓴다
+    id: i64,
+    starred_at: DateTime<Utc>,
+    message: StoredMessage,
+}
+
+// POST /messages/:id/star: 이미 별표가 있어도 성공한다
+pub async fn star_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    let message = match state.repos.messages.find_visible(claims.user_id, id).await {
+        Ok(Some(message)) => message,
+        Ok(None) => return ApiError::not_found("message_not_found", "Message not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if let Err(e) = history::check_can_read(&claims, message.room.as_deref().unwrap_or_default()) {
+        return e.into_response();
+    }
+    match state.repos.stars.star(claims.user_id, id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /messages/:id/star
+pub async fn unstar_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.stars.unstar(claims.user_id, id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("star_not_found", "Message is not starred").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    // 이 id 보다 먼저 붙인 것 (다음 페이지)
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// GET /me/starred?before=&limit=: 최근에 붙인 것부터
+pub async fn list_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let stars = state.repos.stars.list(claims.user_id, query.before, query.limit.clamp(1, PAGE_MAX)).await;
+    let stars = match stars {
+        Ok(stars) => stars,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let ids: Vec<i64> = stars.iter().map(|(_, message_id, _)| *message_id).collect();
+    let messages = state.repos.messages.find_visible_many(claims.user_id, &ids).await;
+    let mut messages: HashMap<i64, StoredMessage> = match messages {
+        Ok(messages) => messages.into_iter().map(|m| (m.id, m)).collect(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    // 지금 볼 수 없는 메시지는 건너뛰므로 한 페이지가 limit 보다 짧을 수 있다
+    let starred: Vec<Star> = stars
+        .into_iter()
+        .filter_map(|(id, message_id, starred_at)| {
+            let message = messages.remove(&message_id)?;
+            Some(Star { id, starred_at, message })
+        })
+        .collect();
+    Json(starred).into_response()
+}
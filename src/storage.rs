@@ -0,0 +1,253 @@
+// --- 업로드 파일 저장소 ---
+//
+// 아바타와 첨부 파일은 `Storage` 트레이트 뒤에 저장한다. 구현은 설정(storage.avatar, storage.attachment)으로
+// 고른다: backend 가 `local` 이면 dir 아래의 파일로, `s3` 면 storage.s3 의 버킷에 s3_prefix 를 붙인 키로
+// 저장한다. S3 는 SDK 없이 SigV4 서명을 직접 붙여 PUT/GET/DELETE 만
+// 쓴다 (MinIO, R2 등 호환 서비스 포함).
+
+use axum::async_trait;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    config::{self, StoreConfig},
+    oauth::HTTP,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 키는 서버가 만든 값만 쓴다 ('/' 로 구분된 경로 형태)
+#[async_trait]
+pub trait Storage: Send + Sync {
+    // 시작할 때 로그에 남길 설명
+    fn describe(&self) -> String;
+
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> io::Result<()>;
+
+    // 없으면 None
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    // 없는 키를 지워도 오류가 아니다
+    async fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Local,
+    S3,
+}
+
+// 예: open("Avatar", &config.storage.avatar). name 은 로그에만 쓴다.
+pub fn open(name: &str, store: &StoreConfig) -> Arc<dyn Storage> {
+    let storage: Arc<dyn Storage> = match store.backend {
+        Backend::Local => Arc::new(LocalStorage { dir: PathBuf::from(&store.dir) }),
+        Backend::S3 => Arc::new(S3Storage::configured(store.s3_prefix.clone())),
+    };
+    tracing::info!("{} storage: {}", name, storage.describe());
+    storage
+}
+
+pub struct LocalStorage {
+    dir: PathBuf,
+}
+
+impl LocalStorage {
+    fn path(&self, key: &str) -> PathBuf {
+        key.split('/')
+            .filter(|s| !s.is_empty() && *s != "..")
+            .fold(self.dir.clone(), |p, s| p.join(s))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn describe(&self) -> String {
+        format!("local ({})", self.dir.display())
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> io::Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // 읽는 쪽이 쓰다 만 파일을 보지 않도록 임시 파일에 쓰고 이름을 바꾼다
+        let tmp = tmp_path(&path);
+        tokio::fs::write(&tmp, data).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+// storage.s3 (S3_BUCKET, S3_REGION, S3_ENDPOINT, S3_ACCESS_KEY_ID, S3_SECRET_ACCESS_KEY, S3_PATH_STYLE)
+pub struct S3Storage {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    // true 면 {endpoint}/{bucket}/{key}, false 면 {bucket}.{host}/{key}
+    path_style: bool,
+    // 같은 버킷을 여러 용도로 나눠 쓸 때 키 앞에 붙인다
+    key_prefix: String,
+}
+
+impl S3Storage {
+    // 버킷과 자격 증명은 설정을 읽을 때 있는지 확인했다
+    fn configured(key_prefix: String) -> Self {
+        let s3 = &config::get().storage.s3;
+        let endpoint = match &s3.endpoint {
+            Some(endpoint) => endpoint.trim_end_matches('/').to_string(),
+            None => format!("https://s3.{}.amazonaws.com", s3.region),
+        };
+        Self {
+            endpoint,
+            bucket: s3.bucket.clone().unwrap_or_default(),
+            region: s3.region.clone(),
+            access_key: s3.access_key_id.clone().unwrap_or_default(),
+            secret_key: s3.secret_access_key.clone().unwrap_or_default(),
+            path_style: s3.path_style,
+            key_prefix,
+        }
+    }
+
+    // (요청 URL, Host 헤더, 서명에 쓰는 경로)
+    fn locate(&self, key: &str) -> (String, String, String) {
+        let key = uri_encode_path(&format!("{}{}", self.key_prefix, key));
+        let (scheme, host) = self.endpoint.split_once("://").unwrap_or(("https", &self.endpoint));
+        if self.path_style {
+            let path = format!("/{}/{}", self.bucket, key);
+            (format!("{}{}", self.endpoint, path), host.to_string(), path)
+        } else {
+            let host = format!("{}.{}", self.bucket, host);
+            let path = format!("/{}", key);
+            (format!("{}://{}{}", scheme, host, path), host, path)
+        }
+    }
+
+    fn signed_request(&self, method: reqwest::Method, key: &str, payload: &[u8]) -> reqwest::RequestBuilder {
+        let (url, host, path) = self.locate(key);
+        let now = chrono::Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = format!("{:x}", Sha256::digest(payload));
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, path, host, payload_hash, amz_date, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(hmac(format!("AWS4{}", self.secret_key).as_bytes(), date.as_bytes()), |k, part| {
+                hmac(&k, part.as_bytes())
+            });
+        let signature: String = hmac(&signing_key, string_to_sign.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.access_key, scope, signature
+        );
+
+        HTTP.request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    fn describe(&self) -> String {
+        format!("s3 ({}/{}/{})", self.endpoint, self.bucket, self.key_prefix)
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> io::Result<()> {
+        self.signed_request(reqwest::Method::PUT, key, &data)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(data)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map(|_| ())
+            .map_err(io::Error::other)
+    }
+
+    async fn get(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let response = self
+            .signed_request(reqwest::Method::GET, key, &[])
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = response
+            .error_for_status()
+            .map_err(io::Error::other)?
+            .bytes()
+            .await
+            .map_err(io::Error::other)?;
+        Ok(Some(body.to_vec()))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        self.signed_request(reqwest::Method::DELETE, key, &[])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map(|_| ())
+            .map_err(io::Error::other)
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// SigV4 규칙: 비예약 문자와 '/' 를 빼고 모두 퍼센트 인코딩
+fn uri_encode_path(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+// --- 분산 추적 (OpenTelemetry) ---
+//
+// telemetry.otlp_endpoint(OTEL_EXPORTER_OTLP_ENDPOINT)를 설정하면 tracing 의 span 을 OTLP(gRPC)로 그 주소
+// (예: http://localhost:4317)의 수집기에 보낸다. 설정하지 않으면 span 은 로그의 맥락으로만 쓰인다.
+//
+// - `http.request`: HTTP 요청마다 (메서드, 라우트, 요청 id, 클라이언트 주소, 상태 코드)
+// - `ws.connection`, `sse.connection`: 연결이 끝날 때까지 (연결 id, 업그레이드 요청의 id, 방, 사용자, 웹소켓은
+//   인코딩도). 업그레이드 요청과 따로 새 추적을 시작한다.
+// - `ws.message`: 웹소켓으로 받은 메시지 하나의 처리. 많으므로 telemetry.message_sample_ratio 의 비율만 만든다.
+// - `db.query`: 메시지 저장 대기열의 쿼리
+//
+// 부모가 없는 span(새 추적)은 telemetry.sample_ratio 의 비율로 고르고, 자식은 부모의 결정을 따른다.
+
+use axum::{extract::MatchedPath, http::Request, response::Response};
+use once_cell::sync::{Lazy, OnceCell};
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    runtime,
+    trace::{Sampler, Tracer, TracerProvider},
+    Resource,
+};
+use std::time::Duration;
+use tracing::{field, Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{client_ip::ClientIp, config, request_id};
+
+static SERVICE_NAME: Lazy<String> = Lazy::new(|| config::get().telemetry.service_name.clone());
+static SAMPLE_RATIO: Lazy<f64> = Lazy::new(|| config::get().telemetry.sample_ratio);
+static MESSAGE_SAMPLE_RATIO: Lazy<f64> = Lazy::new(|| config::get().telemetry.message_sample_ratio);
+
+static PROVIDER: OnceCell<TracerProvider> = OnceCell::new();
+
+// 로그 설정에 더할 OTLP 내보내기 층. 끝점을 설정하지 않았으면 None.
+pub fn layer<S>() -> Option<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = config::get().telemetry.otlp_endpoint.as_deref()?;
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .unwrap_or_else(|e| panic!("telemetry.otlp_endpoint has an invalid value: '{}' ({})", endpoint, e));
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(*SAMPLE_RATIO))))
+        .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME.clone())]))
+        .build();
+    let tracer = provider.tracer("webchat");
+    let _ = PROVIDER.set(provider);
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+// 서버가 끝날 때 아직 보내지 않은 span 을 내보낸다
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get() {
+        if let Err(e) = provider.shutdown() {
+            tracing::warn!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+// HTTP 요청의 span. 경로 대신 라우트(`/rooms/:room/messages`)를 남겨 같은 라우트끼리 묶이게 한다.
+pub fn http_span<B>(req: &Request<B>) -> Span {
+    let route = req.extensions().get::<MatchedPath>().map_or("", MatchedPath::as_str);
+    let request_id = req.headers().get(&request_id::HEADER).and_then(|v| v.to_str().ok()).unwrap_or("");
+    let client_ip = ClientIp::of(req.extensions(), req.headers()).map(|ClientIp(ip)| ip.to_string());
+    tracing::info_span!(
+        "http.request",
+        method = %req.method(),
+        route,
+        request_id,
+        client_ip = client_ip.unwrap_or_default(),
+        status = field::Empty,
+        otel.status_code = field::Empty,
+    )
+}
+
+// 응답의 상태 코드를 남긴다. 5xx 는 추적 도구에서 실패로 보이게 한다.
+pub fn record_status<B>(res: &Response<B>, _latency: Duration, span: &Span) {
+    span.record("status", res.status().as_u16());
+    if res.status().is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+}
+
+// DB 쿼리의 span
+pub fn db_span(operation: &str, table: &str) -> Span {
+    tracing::info_span!("db.query", db.system = "postgresql", db.operation = operation, db.sql.table = table)
+}
+
+// 웹소켓 메시지 하나의 span. 내보내지 않거나 고르지 않은 메시지는 span 을 만들지 않는다.
+pub fn message_span(room: &str) -> Span {
+    if PROVIDER.get().is_some() && rand::random::<f64>() < *MESSAGE_SAMPLE_RATIO {
+        tracing::info_span!("ws.message", room)
+    } else {
+        Span::none()
+    }
+}
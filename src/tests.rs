@@ -0,0 +1,362 @@
+// --- 핸들러 테스트 ---
+//
+// 메모리 저장소로 공유 상태를 만들고 사용자 저장소만 가짜로 바꿔 가입·로그인 핸들러를 직접 부른다. 마지막
+// 테스트는 `DATABASE_URL=memory:` 로 서버 전체를 띄워 HTTP 로 부른다.
+
+use axum::{
+    async_trait,
+    extract::State,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, Once},
+};
+
+use crate::{
+    client_ip::{self, ForwardedHeader, Network},
+    config, login_handler, register_handler,
+    repo::{NewUser, RepoError, RepoResult, Repos, User, UserRepo, UserSummary},
+    room_export, server, webhooks, AppState, ApiJson, AuthPayload, ClientInfo, Server,
+};
+
+static CONFIG: Once = Once::new();
+
+fn install_config() {
+    CONFIG.call_once(|| {
+        let mut config = config::Config::default();
+        config.database.url = Some("memory:".to_string());
+        config.jwt.secret = Some("test-secret".to_string());
+        config.features.require_verified_email = false;
+        config::install(config).expect("test configuration is valid");
+    });
+}
+
+// 메모리 속 사용자 목록. 이름은 대소문자를 구분하지 않는다.
+#[derive(Default)]
+struct MockUsers {
+    users: Mutex<Vec<User>>,
+}
+
+impl MockUsers {
+    fn insert(&self, username: &str, password_hash: &str, disabled: bool) {
+        let mut users = self.users.lock().unwrap();
+        let id = users.len() as i32 + 1;
+        users.push(User {
+            id,
+            username: username.to_string(),
+            password_hash: Some(password_hash.to_string()),
+            is_admin: false,
+            is_guest: false,
+            is_bot: false,
+            disabled,
+            muted_until: None,
+            shadow_banned: false,
+        });
+    }
+
+    fn get(&self, pred: impl Fn(&User) -> bool) -> Option<User> {
+        self.users.lock().unwrap().iter().find(|u| pred(u)).cloned()
+    }
+}
+
+#[async_trait]
+impl UserRepo for MockUsers {
+    async fn create(&self, user: &NewUser<'_>) -> RepoResult<User> {
+        if self.get(|u| u.username.eq_ignore_ascii_case(user.username)).is_some() {
+            return Err(RepoError::Conflict("username"));
+        }
+        self.insert(user.username, user.password_hash, false);
+        Ok(self.get(|u| u.username == user.username).expect("just inserted"))
+    }
+
+    async fn find(&self, id: i32) -> RepoResult<Option<User>> {
+        Ok(self.get(|u| u.id == id))
+    }
+
+    async fn find_by_name(&self, username: &str) -> RepoResult<Option<User>> {
+        Ok(self.get(|u| u.username.eq_ignore_ascii_case(username)))
+    }
+
+    async fn set_password_hash(&self, id: i32, hash: &str, expected: Option<&str>) -> RepoResult<bool> {
+        let mut users = self.users.lock().unwrap();
+        match users.iter_mut().find(|u| u.id == id) {
+            Some(user) if expected.is_none() || user.password_hash.as_deref() == expected => {
+                user.password_hash = Some(hash.to_string());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn touch_last_online(&self, _id: i32) -> RepoResult<()> {
+        Ok(())
+    }
+
+    async fn mute(&self, id: i32, until: DateTime<Utc>) -> RepoResult<Option<User>> {
+        let mut users = self.users.lock().unwrap();
+        Ok(users.iter_mut().find(|u| u.id == id).map(|user| {
+            user.muted_until = user.muted_until.max(Some(until));
+            user.clone()
+        }))
+    }
+
+    async fn set_shadow_banned(&self, id: i32, shadow_banned: bool) -> RepoResult<Option<User>> {
+        let mut users = self.users.lock().unwrap();
+        Ok(users.iter_mut().find(|u| u.id == id).map(|user| {
+            user.shadow_banned = shadow_banned;
+            user.clone()
+        }))
+    }
+
+    async fn search(&self, _pattern: Option<&str>, _limit: i64, _offset: i64) -> RepoResult<Vec<UserSummary>> {
+        Ok(Vec::new())
+    }
+
+    async fn set_disabled(&self, id: i32, disabled: bool) -> RepoResult<Option<(String, Option<DateTime<Utc>>)>> {
+        let mut users = self.users.lock().unwrap();
+        Ok(users.iter_mut().find(|u| u.id == id).map(|user| {
+            user.disabled = disabled;
+            (user.username.clone(), disabled.then(Utc::now))
+        }))
+    }
+}
+
+async fn state(users: Arc<MockUsers>) -> AppState {
+    install_config();
+    let mut repos = Repos::memory().await.expect("memory repository opens");
+    repos.users = users;
+    let (state, _pending) = server::app_state(repos).await.expect("state builds");
+    state
+}
+
+fn payload(username: &str, password: &str) -> ApiJson<AuthPayload> {
+    ApiJson(AuthPayload {
+        username: username.to_string(),
+        password: password.to_string(),
+        email: None,
+        invite_code: None,
+        captcha_token: None,
+    })
+}
+
+fn client() -> ClientInfo {
+    ClientInfo { user_agent: Some("tests".to_string()), ip: Some("127.0.0.1".to_string()) }
+}
+
+async fn body(response: Response) -> (StatusCode, Value) {
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body is readable");
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+const PASSWORD: &str = "correct horse battery staple";
+
+#[tokio::test]
+async fn register_stores_normalized_user_with_hashed_password() {
+    let users = Arc::new(MockUsers::default());
+    let state = state(users.clone()).await;
+
+    let response = register_handler(State(state.clone()), client(), payload("  Alice ", PASSWORD)).await;
+    let (status, body) = body(response.into_response()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["username"], "Alice");
+
+    let stored = users.get(|u| u.username == "Alice").expect("user is stored");
+    assert_eq!(body["id"], stored.id);
+    let hash = stored.password_hash.expect("password is hashed");
+    assert_ne!(hash, PASSWORD);
+    assert!(state.passwords.verify(PASSWORD, &hash).unwrap());
+}
+
+#[tokio::test]
+async fn register_reports_taken_username() {
+    let users = Arc::new(MockUsers::default());
+    users.insert("alice", "unused", false);
+    let state = state(users.clone()).await;
+
+    let response = register_handler(State(state), client(), payload("ALICE", PASSWORD)).await;
+    let (status, body) = body(response.into_response()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(body.to_string().contains("taken"), "unexpected body: {}", body);
+    assert_eq!(users.users.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn login_rejects_wrong_password_and_unknown_user() {
+    let users = Arc::new(MockUsers::default());
+    let state = state(users.clone()).await;
+    users.insert("bob", &state.passwords.hash(PASSWORD).unwrap(), false);
+
+    for (username, password) in [("bob", "wrong password"), ("nobody", PASSWORD)] {
+        let response =
+            login_handler(State(state.clone()), CookieJar::new(), client(), payload(username, password)).await;
+        let (status, body) = body(response.into_response()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", username);
+        assert!(body.to_string().contains("invalid_credentials"), "unexpected body: {}", body);
+    }
+}
+
+#[tokio::test]
+async fn login_refuses_disabled_account() {
+    let users = Arc::new(MockUsers::default());
+    let state = state(users.clone()).await;
+    users.insert("carol", &state.passwords.hash(PASSWORD).unwrap(), true);
+
+    let response = login_handler(State(state), CookieJar::new(), client(), payload("Carol", PASSWORD)).await;
+    let (status, body) = body(response.into_response()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body.to_string().contains("account_disabled"), "unexpected body: {}", body);
+}
+
+// `memory:` 로 서버 전체를 띄우고 주소를 돌려준다. 돌려준 태스크를 멈추고 서버를 닫는다.
+async fn serve() -> (Server, String, tokio::task::JoinHandle<std::io::Result<()>>) {
+    install_config();
+    let server = Server::builder().build().await.expect("server starts on memory:");
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let app = server.router().into_make_service_with_connect_info::<SocketAddr>();
+    let serving = tokio::spawn(async move { axum::serve(listener, app).await });
+    (server, base, serving)
+}
+
+#[tokio::test]
+async fn server_starts_on_memory_backend_without_pool() {
+    let (server, base, serving) = serve().await;
+    assert!(server.db().is_none());
+    let client = reqwest::Client::new();
+
+    let ready = client.get(format!("{}/readyz", base)).send().await.unwrap();
+    assert_eq!(ready.status(), reqwest::StatusCode::OK);
+    let ready: Value = ready.json().await.unwrap();
+    assert_eq!(ready["status"], "ready");
+
+    let credentials = serde_json::json!({ "username": "erin", "password": PASSWORD });
+    let registered = client.post(format!("{}/register", base)).json(&credentials).send().await.unwrap();
+    assert_eq!(registered.status(), reqwest::StatusCode::CREATED);
+    let login = client.post(format!("{}/login", base)).json(&credentials).send().await.unwrap();
+    assert_eq!(login.status(), reqwest::StatusCode::OK);
+    let token: Value = login.json().await.unwrap();
+    assert!(token["token"].is_string(), "unexpected body: {}", token);
+
+    serving.abort();
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn wrong_two_factor_codes_lock_the_login_even_after_new_challenges() {
+    let (server, base, serving) = serve().await;
+    let client = reqwest::Client::new();
+    let post = |path: &str, body: Value| client.post(format!("{}{}", base, path)).json(&body).send();
+
+    let credentials = serde_json::json!({ "username": "grace", "password": PASSWORD });
+    assert_eq!(post("/register", credentials.clone()).await.unwrap().status(), reqwest::StatusCode::CREATED);
+    let login: Value = post("/login", credentials.clone()).await.unwrap().json().await.unwrap();
+    let bearer = format!("Bearer {}", login["token"].as_str().expect("token issued"));
+    let enrolled: Value = client
+        .post(format!("{}/me/2fa/totp", base))
+        .header("authorization", &bearer)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let totp = totp_rs::TOTP::from_url(enrolled["otpauth_uri"].as_str().unwrap()).unwrap();
+    let confirmed = client
+        .post(format!("{}/me/2fa/totp/confirm", base))
+        .header("authorization", &bearer)
+        .json(&serde_json::json!({ "code": totp.generate_current().unwrap() }))
+        .send()
+        .await
+        .unwrap();
+    assert!(confirmed.status().is_success(), "{}", confirmed.status());
+
+    // 비밀번호가 맞아 받은 challenge 마다 틀린 코드를 한 번씩 보낸다
+    let max_failures = config::get().limits.login_max_failures;
+    for _ in 0..max_failures {
+        let challenged: Value = post("/login", credentials.clone()).await.unwrap().json().await.unwrap();
+        assert_eq!(challenged["two_factor_required"], true, "unexpected body: {}", challenged);
+        let code = serde_json::json!({ "challenge": challenged["challenge"], "code": "not-a-code" });
+        assert_eq!(post("/login/2fa", code).await.unwrap().status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+    let locked = post("/login", credentials.clone()).await.unwrap();
+    assert_eq!(locked.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    serving.abort();
+    server.shutdown().await;
+}
+
+#[test]
+fn webhook_rate_limit_allows_a_burst_per_webhook() {
+    install_config();
+    let rate = webhooks::PostRate::default();
+    let burst = config::get().limits.webhook_burst as usize;
+    for _ in 0..burst {
+        assert!(rate.check(1).is_ok());
+    }
+    let wait = rate.check(1).expect_err("bucket is empty");
+    assert!(wait.as_secs_f64() > 0.0);
+    assert!(rate.check(2).is_ok(), "other webhooks keep their own bucket");
+}
+
+#[test]
+fn csv_export_neutralizes_formula_cells() {
+    let csv = |field: &str| {
+        let mut out = String::new();
+        room_export::push_csv_field(&mut out, field);
+        out
+    };
+    assert_eq!(csv("=HYPERLINK(\"http://evil\")"), "\"'=HYPERLINK(\"\"http://evil\"\")\"");
+    assert_eq!(csv("+1+2"), "'+1+2");
+    assert_eq!(csv("-2+3"), "'-2+3");
+    assert_eq!(csv("@SUM(A1)"), "'@SUM(A1)");
+    assert_eq!(csv("\tcmd"), "'\tcmd");
+    assert_eq!(csv("\r=1"), "\"'\r=1\"");
+    assert_eq!(csv("a, b"), "\"a, b\"");
+    assert_eq!(csv("1 + 1 = 2"), "1 + 1 = 2");
+}
+
+#[test]
+fn only_the_configured_forwarding_header_is_trusted() {
+    let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+    let trusted = ["10.0.0.0/8".parse::<Network>().unwrap()];
+    // 프록시는 X-Forwarded-For 를 쓰고, 클라이언트가 Forwarded 를 직접 보냈다
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", HeaderValue::from_static("198.51.100.7"));
+    headers.insert("forwarded", HeaderValue::from_static("for=203.0.113.9"));
+
+    let resolve = |headers: &HeaderMap, header| client_ip::resolve(proxy, headers, &trusted, header);
+    assert_eq!(resolve(&headers, ForwardedHeader::XForwardedFor), "198.51.100.7".parse::<IpAddr>().unwrap());
+    assert_eq!(resolve(&headers, ForwardedHeader::Forwarded), "203.0.113.9".parse::<IpAddr>().unwrap());
+
+    // 설정한 헤더가 없으면 다른 헤더로 넘어가지 않고 프록시 주소를 쓴다
+    headers.remove("x-forwarded-for");
+    assert_eq!(resolve(&headers, ForwardedHeader::XForwardedFor), proxy);
+    // 믿지 않는 곳에서 온 요청은 어느 헤더도 보지 않는다
+    let client: IpAddr = "192.0.2.1".parse().unwrap();
+    assert_eq!(client_ip::resolve(client, &headers, &trusted, ForwardedHeader::Forwarded), client);
+}
+
+#[test]
+fn unreadable_or_malformed_jwt_keys_are_configuration_problems() {
+    let dir = std::env::temp_dir().join(format!("webchat-keys-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let malformed = dir.join("public.pem");
+    std::fs::write(&malformed, "-----BEGIN PUBLIC KEY-----\nnot a key\n-----END PUBLIC KEY-----\n").unwrap();
+
+    let mut config = config::Config::default();
+    config.database.url = Some("memory:".to_string());
+    config.jwt.algorithm = "RS256".to_string();
+    config.jwt.private_key_path = Some(dir.join("missing.pem").display().to_string());
+    config.jwt.public_key_path = Some(malformed.display().to_string());
+    let problems = config::install(config).expect_err("keys cannot be loaded");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].starts_with("jwt.private_key_path (JWT_PRIVATE_KEY_PATH): cannot read"), "{:?}", problems);
+}
@@ -0,0 +1,267 @@
+// --- 로그인/가입 시도 제한 ---
+//
+// `/login` 은 IP 별, 사용자 이름별로 실패를 세고, `/register` 는 IP 별로 시도를 센다. `/login/2fa` 의 틀린 코드도
+// challenge 의 사용자 이름으로 같은 카운터에 센다. 사용자 카운터는 세션을 발급했을 때만 지우므로, 비밀번호를 아는
+// 공격자가 challenge 를 계속 받아 TOTP 코드를 맞혀 볼 수 없다.
+// `/password-reset/request` 와 `/login/magic` 은 메일을 보내므로 결과와 관계없이 IP 별, 메일 주소별로 시도를 센다.
+// 허용 횟수를 넘으면 잠금 시간이 실패할 때마다 두 배로 늘어나며(최대값까지),
+// 잠긴 동안에는 핸들러를 실행하지 않고 429 와 Retry-After 를 돌려준다.
+// 카운터는 DB 에 저장되므로 서버를 재시작하거나 여러 대로 늘려도 유지된다.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+use crate::{
+    auth::hash_token,
+    client_ip::ClientIp,
+    config,
+    error::ApiError,
+    repo::{RepoResult, Repos},
+    two_factor, validation, AppState,
+};
+
+// 사용자 이름과 메일 주소를 꺼내려고 읽는 요청 본문의 최대 크기
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+#[derive(Clone, Copy)]
+struct Policy {
+    // 이 횟수만큼 실패하면 잠그기 시작한다
+    max_failures: i32,
+}
+
+static LOGIN_USER_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.login_max_failures,
+});
+static LOGIN_IP_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.login_ip_max_failures,
+});
+static REGISTER_IP_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.register_ip_max_attempts,
+});
+static PASSWORD_RESET_IP_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.password_reset_ip_max_attempts,
+});
+static PASSWORD_RESET_EMAIL_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.password_reset_email_max_attempts,
+});
+static MAGIC_LINK_IP_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.magic_link_ip_max_attempts,
+});
+static MAGIC_LINK_EMAIL_POLICY: Lazy<Policy> = Lazy::new(|| Policy {
+    max_failures: config::get().limits.magic_link_email_max_attempts,
+});
+
+// 첫 잠금 시간과 최대 잠금 시간
+static LOCKOUT_BASE: Lazy<i64> = Lazy::new(|| config::get().limits.lockout_base_seconds);
+static LOCKOUT_MAX: Lazy<i64> = Lazy::new(|| config::get().limits.lockout_max_seconds);
+// 마지막 실패 후 이 시간이 지나면 카운터를 처음부터 센다
+static FAILURE_WINDOW: Lazy<i64> = Lazy::new(|| config::get().limits.login_failure_window_minutes * 60);
+
+// 잠금 초과분에 대해 기본 시간 × 2^n (최대값 제한)
+fn lockout_seconds(policy: Policy, failures: i32) -> Option<i64> {
+    let over = failures - policy.max_failures;
+    if over < 0 {
+        return None;
+    }
+    Some(LOCKOUT_BASE.saturating_mul(1i64 << over.min(20)).min(*LOCKOUT_MAX))
+}
+
+// 잠긴 키가 있으면 남은 초를 돌려준다
+async fn retry_after(state: &AppState, keys: &[String]) -> RepoResult<Option<i64>> {
+    let secs = state.repos.throttle.retry_after(keys).await?;
+    Ok(secs.map(|s| s.ceil().max(1.0) as i64))
+}
+
+async fn record_failure(state: &AppState, key: &str, policy: Policy) -> RepoResult<()> {
+    let failures = state.repos.throttle.record_failure(key, *FAILURE_WINDOW as f64).await?;
+    if let Some(secs) = lockout_seconds(policy, failures) {
+        state.repos.throttle.lock(key, secs as f64).await?;
+        tracing::warn!("Throttling '{}' for {}s after {} attempts", key, secs, failures);
+    }
+    Ok(())
+}
+
+fn too_many_requests(secs: i64) -> Response {
+    let error = ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        "too_many_attempts",
+        "Too many attempts, try again later",
+    );
+    ([(header::RETRY_AFTER, secs.to_string())], error).into_response()
+}
+
+// 본문의 문자열 필드를 읽고, 핸들러가 다시 읽을 수 있도록 요청을 재구성한다
+async fn field_from_body(req: Request, field: &str) -> Result<(Option<String>, Request), Response> {
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| {
+            ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large", "Request body too large").into_response()
+        })?;
+    let value = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|v| v[field].as_str().map(str::to_string));
+    Ok((value, Request::from_parts(parts, Body::from(bytes))))
+}
+
+// /login 미들웨어: 실패(401)를 세고, 세션을 발급하면 해당 사용자 이름의 카운터를 지운다
+pub async fn login(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let (username, req) = match field_from_body(req, "username").await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    count_login_failures(&state, format!("login-ip:{}", ip), username, req, next).await
+}
+
+// /login/2fa 미들웨어: challenge 를 받은 사용자 이름으로 /login 과 같은 카운터를 쓴다
+pub async fn login_code(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let (challenge, req) = match field_from_body(req, "challenge").await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let username = match challenge {
+        Some(challenge) => match state.repos.two_factor.challenge_username(&hash_token(&challenge)).await {
+            Ok(username) => username,
+            Err(e) => {
+                tracing::warn!("Failed to look up login challenge: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    count_login_failures(&state, format!("login-ip:{}", ip), username, req, next).await
+}
+
+async fn count_login_failures(
+    state: &AppState,
+    ip_key: String,
+    username: Option<String>,
+    req: Request,
+    next: Next,
+) -> Response {
+    // 로그인과 같이 정규화해서, 모양만 다른 같은 이름이 각자 카운터를 갖지 않게 한다
+    let user_key = username.map(|u| format!("login-user:{}", validation::normalize_username(&u).to_lowercase()));
+
+    let mut keys = vec![ip_key.clone()];
+    keys.extend(user_key.clone());
+    match retry_after(state, &keys).await {
+        Ok(Some(secs)) => return too_many_requests(secs),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to check login throttle: {}", e),
+    }
+
+    let response = next.run(req).await;
+
+    // 2단계 인증 challenge 는 비밀번호만 맞은 것이므로 카운터를 그대로 둔다
+    let challenged = response.extensions().get::<two_factor::ChallengeIssued>().is_some();
+    let result = if response.status() == StatusCode::UNAUTHORIZED {
+        let mut result = record_failure(state, &ip_key, *LOGIN_IP_POLICY).await;
+        if let Some(user_key) = &user_key {
+            result = result.and(record_failure(state, user_key, *LOGIN_USER_POLICY).await);
+        }
+        result
+    } else if response.status().is_success() && !challenged {
+        // IP 카운터는 남긴다: 자기 계정으로 로그인해 다른 계정 공격 기록을 지울 수 없게
+        match &user_key {
+            Some(user_key) => state.repos.throttle.reset(user_key).await,
+            None => Ok(()),
+        }
+    } else {
+        Ok(())
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to update login throttle: {}", e);
+    }
+    response
+}
+
+// 성공 여부와 관계없이 모든 키에 시도를 센다
+async fn count_attempts(state: &AppState, what: &str, keys: &[(String, Policy)], req: Request, next: Next) -> Response {
+    let names: Vec<String> = keys.iter().map(|(key, _)| key.clone()).collect();
+    match retry_after(state, &names).await {
+        Ok(Some(secs)) => return too_many_requests(secs),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to check {} throttle: {}", what, e),
+    }
+
+    let response = next.run(req).await;
+    for (key, policy) in keys {
+        if let Err(e) = record_failure(state, key, *policy).await {
+            tracing::warn!("Failed to update {} throttle: {}", what, e);
+        }
+    }
+    response
+}
+
+// /register 미들웨어: IP 별 시도를 센다
+pub async fn register(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let keys = [(format!("register-ip:{}", ip), *REGISTER_IP_POLICY)];
+    count_attempts(&state, "register", &keys, req, next).await
+}
+
+// /password-reset/request 미들웨어: 주소가 없는 계정이어도 202 이므로 IP 별, 메일 주소별로 모든 요청을 센다
+pub async fn password_reset(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let (email, req) = match field_from_body(req, "email").await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let mut keys = vec![(format!("reset-ip:{}", ip), *PASSWORD_RESET_IP_POLICY)];
+    keys.extend(email.map(|e| (format!("reset-email:{}", e.trim().to_lowercase()), *PASSWORD_RESET_EMAIL_POLICY)));
+    count_attempts(&state, "password reset", &keys, req, next).await
+}
+
+// /login/magic 미들웨어: 비밀번호 재설정과 같이 IP 별, 메일 주소별로 모든 요청을 센다
+pub async fn magic_link(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    req: Request,
+    next: Next,
+) -> Response {
+    let (email, req) = match field_from_body(req, "email").await {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let mut keys = vec![(format!("magic-ip:{}", ip), *MAGIC_LINK_IP_POLICY)];
+    keys.extend(email.map(|e| (format!("magic-email:{}", e.trim().to_lowercase()), *MAGIC_LINK_EMAIL_POLICY)));
+    count_attempts(&state, "magic link", &keys, req, next).await
+}
+
+// 오래된 카운터 정리
+pub fn spawn_purge_task(repos: Repos) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = repos.throttle.purge(*FAILURE_WINDOW as f64).await {
+                tracing::warn!("Failed to purge auth throttle counters: {}", e);
+            }
+        }
+    });
+}
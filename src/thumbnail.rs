@@ -0,0 +1,87 @@
+// --- 이미지 첨부 미리보기 ---
+//
+// 이미지 첨부를 storage.thumbnail_max_dimension 안에 들어가게 줄인 미리보기를 만든다. 디코딩과 리사이즈는
+// CPU 를 오래 쓰므로 spawn_blocking 스레드에서 돌린다. 투명도가 있으면 PNG, 없으면 JPEG 로 저장한다.
+// 실패해도 업로드는 그대로 진행하고 미리보기만 빠진다.
+
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, GenericImageView, ImageFormat};
+use once_cell::sync::Lazy;
+use std::io::Cursor;
+
+use crate::config;
+
+const JPEG_QUALITY: u8 = 80;
+// 이보다 큰 원본은 디코딩하지 않는다 (압축 폭탄 방지)
+const MAX_SOURCE_DIMENSION: u32 = 12_000;
+
+static THUMBNAIL_MAX_DIMENSION: Lazy<u32> = Lazy::new(|| config::get().storage.thumbnail_max_dimension);
+
+pub struct Thumbnail {
+    pub data: Vec<u8>,
+    // 저장소 키에 붙일 확장자
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+    // 원본 크기
+    pub source_width: u32,
+    pub source_height: u32,
+}
+
+pub fn content_type_for(key: &str) -> &'static str {
+    if key.ends_with(".png") {
+        "image/png"
+    } else {
+        "image/jpeg"
+    }
+}
+
+// 미리보기를 만들 수 없는 형식이거나 실패하면 None
+pub async fn generate(data: Vec<u8>, content_type: &str) -> Option<Thumbnail> {
+    let format = match content_type {
+        "image/png" => ImageFormat::Png,
+        "image/jpeg" => ImageFormat::Jpeg,
+        "image/gif" => ImageFormat::Gif,
+        "image/webp" => ImageFormat::WebP,
+        _ => return None,
+    };
+    match tokio::task::spawn_blocking(move || render(&data, format)).await {
+        Ok(Ok(thumbnail)) => Some(thumbnail),
+        Ok(Err(e)) => {
+            tracing::info!("No thumbnail for {} attachment: {}", content_type, e);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Thumbnail task failed: {}", e);
+            None
+        }
+    }
+}
+
+fn render(data: &[u8], format: ImageFormat) -> image::ImageResult<Thumbnail> {
+    let mut reader = image::ImageReader::with_format(Cursor::new(data), format);
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+    let source = reader.decode()?;
+    let (source_width, source_height) = source.dimensions();
+
+    let max = *THUMBNAIL_MAX_DIMENSION;
+    // 이미 작은 이미지는 키우지 않는다
+    let resized = if source_width <= max && source_height <= max {
+        source
+    } else {
+        source.resize(max, max, FilterType::Triangle)
+    };
+    let (width, height) = resized.dimensions();
+
+    let mut out = Vec::new();
+    let extension = if resized.color().has_alpha() {
+        resized.write_to(&mut Cursor::new(&mut out), ImageFormat::Png)?;
+        "png"
+    } else {
+        resized.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))?;
+        "jpg"
+    };
+    Ok(Thumbnail { data: out, extension, width, height, source_width, source_height })
+}
@@ -0,0 +1,82 @@
+// --- HTTPS ---
+//
+// tls.cert_path 와 tls.key_path (TLS_CERT_PATH, TLS_KEY_PATH) 를 설정하면 리버스 프록시 없이 HTTPS 와 WSS 를 직접
+// 받는다. 인증서는 중간 인증서까지 이어 붙인 PEM, 키는 PKCS#8, PKCS#1, SEC1 PEM 중 하나다.
+//
+// 두 파일의 수정 시각을 tls.reload_check_seconds 마다 확인해 바뀌었으면 다시 읽는다 (certbot 의 갱신 등).
+// 새 인증서는 새 연결부터 쓰고, 이미 열린 연결은 그대로 둔다. 새 파일을 읽지 못하면(키만 먼저 바뀐 경우 등)
+// 경고를 남기고 이전 인증서를 계속 쓰다가 다음 확인 때 다시 시도한다.
+
+use axum::Router;
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    time::{Duration, SystemTime},
+};
+
+use crate::config::{self, TlsConfig};
+
+// TLS 를 설정하지 않았으면 None
+pub async fn load() -> Option<RustlsConfig> {
+    let tls = &config::get().tls;
+    let (cert, key) = (tls.cert_path.as_deref()?, tls.key_path.as_deref()?);
+    // 다른 의존성이 이미 골랐으면 그것을 쓴다
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let rustls = RustlsConfig::from_pem_file(cert, key)
+        .await
+        .unwrap_or_else(|e| panic!("Failed to load the TLS certificate '{}' and key '{}': {}", cert, key, e));
+    spawn_reload_task(rustls.clone(), tls);
+    Some(rustls)
+}
+
+// 인증서와 키 파일의 수정 시각. 파일을 읽을 수 없으면 None.
+async fn modified(tls: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let cert = tokio::fs::metadata(tls.cert_path.as_deref()?).await.ok()?.modified().ok()?;
+    let key = tokio::fs::metadata(tls.key_path.as_deref()?).await.ok()?.modified().ok()?;
+    Some((cert, key))
+}
+
+fn spawn_reload_task(rustls: RustlsConfig, tls: &'static TlsConfig) {
+    tokio::spawn(async move {
+        let mut loaded = modified(tls).await;
+        let mut interval = tokio::time::interval(Duration::from_secs(tls.reload_check_seconds));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let current = modified(tls).await;
+            if current.is_none() || current == loaded {
+                continue;
+            }
+            let cert = tls.cert_path.as_deref().unwrap_or_default();
+            let key = tls.key_path.as_deref().unwrap_or_default();
+            match rustls.reload_from_pem_file(cert, key).await {
+                Ok(()) => {
+                    tracing::info!("Reloaded the TLS certificate from {}", cert);
+                    loaded = current;
+                }
+                Err(e) => tracing::warn!("Failed to reload the TLS certificate; keeping the previous one: {}", e),
+            }
+        }
+    });
+}
+
+// axum::serve 대신 HTTPS 로 받는다. `shutdown` 이 끝나면 새 접속을 받지 않고 처리 중인 요청을 기다린다.
+pub async fn serve(
+    addr: SocketAddr,
+    rustls: RustlsConfig,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    let handle = Handle::new();
+    let signal_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown.await;
+        signal_handle.graceful_shutdown(None);
+    });
+    axum_server::bind_rustls(addr, rustls)
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+}
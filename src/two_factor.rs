@@ -0,0 +1,212 @@
+// --- 2단계 인증 (TOTP) ---
+//
+// 등록: `POST /me/2fa/totp` 로 비밀키와 otpauth:// URI(QR 코드로 표시)를 받고, 인증 앱의 코드로
+// `POST /me/2fa/totp/confirm` 을 호출하면 활성화된다.
+// 로그인: 비밀번호가 맞아도 토큰 대신 challenge 를 돌려주고, `POST /login/2fa` 에 challenge 와
+// 코드를 보내야 세션이 시작된다.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::CookieJar;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    auth::{generate_token, hash_token, AuthUser},
+    config,
+    error::{ApiError, ApiJson},
+    repo::RepoResult,
+    session::{start_session, ClientInfo},
+    AppState,
+};
+
+// 두 번째 단계를 마쳐야 하는 시간과 허용하는 시도 횟수
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+const TOTP_STEP: u64 = 30;
+
+// 인증 앱에 표시되는 발급자 이름
+static TOTP_ISSUER: Lazy<String> = Lazy::new(|| config::get().auth.totp_issuer.clone());
+
+#[derive(Debug, Deserialize)]
+pub struct CodePayload {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginCodePayload {
+    challenge: String,
+    code: String,
+}
+
+fn totp(secret: &str, username: &str) -> Option<TOTP> {
+    let bytes = Secret::Encoded(secret.to_string()).to_bytes().ok()?;
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP,
+        bytes,
+        Some(TOTP_ISSUER.clone()),
+        username.to_string(),
+    )
+    .ok()
+}
+
+// 시계 오차를 고려해 앞뒤 한 스텝까지 허용한다. 맞으면 해당 시간 스텝을 돌려준다.
+// 이미 사용한 스텝 이하의 코드는 재사용으로 보고 거부한다.
+fn verify_code(totp: &TOTP, code: &str, last_used_step: Option<i64>) -> Option<i64> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64 / TOTP_STEP;
+    (now.saturating_sub(1)..=now + 1)
+        .filter(|step| last_used_step.is_none_or(|last| *step as i64 > last))
+        .find(|step| totp.check(code.trim(), step * TOTP_STEP))
+        .map(|step| step as i64)
+}
+
+// 2단계 인증이 켜진 사용자면 로그인 challenge 를 만들어 돌려준다
+pub async fn login_challenge(state: &AppState, user_id: i32) -> RepoResult<Option<String>> {
+    if !state.repos.two_factor.is_enabled(user_id).await? {
+        return Ok(None);
+    }
+
+    let challenge = generate_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(CHALLENGE_TTL_MINUTES);
+    state.repos.two_factor.add_challenge(&hash_token(&challenge), user_id, expires_at).await?;
+    Ok(Some(challenge))
+}
+
+// 세션 대신 challenge 를 돌려준 응답에 붙는 표시. 로그인 시도 제한(throttle.rs)이 성공으로 세지 않는다.
+#[derive(Clone, Copy)]
+pub struct ChallengeIssued;
+
+// 로그인 응답: 토큰 대신 두 번째 단계가 필요하다고 알린다
+pub fn challenge_response(challenge: &str) -> Response {
+    let mut response = Json(json!({
+        "two_factor_required": true,
+        "challenge": challenge,
+        "methods": ["totp"],
+        "expires_in": CHALLENGE_TTL_MINUTES * 60,
+    }))
+    .into_response();
+    response.extensions_mut().insert(ChallengeIssued);
+    response
+}
+
+// POST /login/2fa
+pub async fn login_handler(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: ClientInfo,
+    ApiJson(payload): ApiJson<LoginCodePayload>,
+) -> Response {
+    // 시도 횟수를 먼저 올려서 병렬 요청으로 제한을 우회하지 못하게 한다
+    let challenge_hash = hash_token(&payload.challenge);
+    let user_id = match state.repos.two_factor.attempt_challenge(&challenge_hash, MAX_CHALLENGE_ATTEMPTS).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return ApiError::unauthorized("challenge_expired", "Login challenge expired, log in again").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let username = match state.repos.users.find(user_id).await {
+        Ok(Some(user)) => user.username,
+        Ok(None) => return ApiError::unauthorized("invalid_code", "Invalid code").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    let (secret, last_used_step) = match state.repos.two_factor.confirmed_secret(user_id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return ApiError::unauthorized("invalid_code", "Invalid code").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let step = totp(&secret, &username).and_then(|t| verify_code(&t, &payload.code, last_used_step));
+    let Some(step) = step else {
+        return ApiError::unauthorized("invalid_code", "Invalid code").into_response();
+    };
+    match state.repos.two_factor.mark_step_used(user_id, step).await {
+        Ok(true) => {}
+        Ok(false) => return ApiError::unauthorized("invalid_code", "Invalid code").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let _ = state.repos.two_factor.delete_challenge(&challenge_hash).await;
+
+    start_session(&state, jar, user_id, &username, client).await
+}
+
+// POST /me/2fa/totp: 새 비밀키 발급 (확인 전까지는 로그인에 적용되지 않는다)
+pub async fn enroll_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let secret = Secret::generate_secret().to_encoded().to_string();
+    let Some(totp) = totp(&secret, &claims.sub) else {
+        return ApiError::internal().into_response();
+    };
+
+    // 이미 활성화돼 있으면 덮어쓰지 않는다 (먼저 해제해야 한다)
+    match state.repos.two_factor.enroll(claims.user_id, &secret).await {
+        Ok(true) => Json(json!({
+            "secret": secret,
+            "otpauth_uri": totp.get_url(),
+        }))
+        .into_response(),
+        Ok(false) => ApiError::conflict("two_factor_enabled", "Two-factor authentication is already enabled").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/2fa/totp/confirm: 인증 앱의 코드로 등록을 마친다
+pub async fn confirm_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<CodePayload>,
+) -> Response {
+    let secret = match state.repos.two_factor.pending_secret(claims.user_id).await {
+        Ok(Some(secret)) => secret,
+        Ok(None) => return ApiError::bad_request("no_pending_enrollment", "No pending TOTP enrollment").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    let Some(step) = totp(&secret, &claims.sub).and_then(|t| verify_code(&t, &payload.code, None)) else {
+        return ApiError::bad_request("invalid_code", "Invalid code").into_response();
+    };
+
+    match state.repos.two_factor.confirm(claims.user_id, step).await {
+        Ok(_) => {
+            tracing::info!("User {} enabled two-factor authentication", claims.user_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/2fa/totp: 현재 코드를 확인한 뒤 해제한다
+pub async fn disable_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<CodePayload>,
+) -> Response {
+    let (secret, last_used_step) = match state.repos.two_factor.confirmed_secret(claims.user_id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return ApiError::bad_request("two_factor_not_enabled", "Two-factor authentication is not enabled").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    if totp(&secret, &claims.sub)
+        .and_then(|t| verify_code(&t, &payload.code, last_used_step))
+        .is_none()
+    {
+        return ApiError::bad_request("invalid_code", "Invalid code").into_response();
+    }
+
+    match state.repos.two_factor.disable(claims.user_id).await {
+        Ok(_) => {
+            tracing::info!("User {} disabled two-factor authentication", claims.user_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,213 @@
+// --- 사용자 이름 / 비밀번호 검증 ---
+//
+// 사용자 이름은 NFKC 로 정규화한 뒤 길이, 문자 집합, 예약어를 검사한다. 대소문자만 다른 이름은
+// 같은 이름으로 취급한다 (DB 의 lower(username) 유니크 인덱스). 비밀번호는 최소 길이와
+// 문자 종류로 추정한 엔트로피를 요구한다. 실패하면 필드별 오류 코드를 모아 JSON 으로 돌려준다.
+
+use axum::response::{IntoResponse, Response};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{config, error::ApiError};
+
+const USERNAME_MIN_CHARS: usize = 3;
+const USERNAME_MAX_CHARS: usize = 32;
+const PASSWORD_MAX_CHARS: usize = 256;
+
+static PASSWORD_MIN_LENGTH: Lazy<usize> = Lazy::new(|| config::get().passwords.min_length);
+static PASSWORD_MIN_ENTROPY_BITS: Lazy<f64> = Lazy::new(|| config::get().passwords.min_entropy_bits);
+
+// 시스템 메시지나 관리자로 오인될 수 있는 이름
+const RESERVED_USERNAMES: &[&str] = &[
+    "admin", "administrator", "root", "system", "server", "moderator", "mod", "support", "help", "staff",
+    "official", "webchat", "bot", "api", "static", "null", "undefined", "anonymous", "guest", "everyone",
+    "here", "me",
+];
+
+// 게스트에게 자동으로 붙는 이름의 접두사. 일반 가입에서는 쓸 수 없다.
+pub const GUEST_USERNAME_PREFIX: &str = "guest-";
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    code: &'static str,
+    message: String,
+}
+
+// 필드 이름 → 오류 목록
+#[derive(Debug, Default)]
+pub struct ValidationErrors(BTreeMap<&'static str, Vec<FieldError>>);
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &'static str, code: &'static str, message: impl Into<String>) {
+        self.0.entry(field).or_default().push(FieldError { code, message: message.into() });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn single(field: &'static str, code: &'static str, message: impl Into<String>) -> Self {
+        let mut errors = Self::default();
+        errors.add(field, code, message);
+        errors
+    }
+
+    pub fn has_code(&self, code: &str) -> bool {
+        self.0.values().flatten().any(|e| e.code == code)
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(&self.0).unwrap_or_default()
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+// 응답 형식은 ApiError 와 같다 (code 는 validation_failed, fields 에 필드별 오류)
+// 명령줄 도구가 보여 줄 한 줄짜리 요약 (`password: ...; username: ...`)
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self
+            .0
+            .iter()
+            .flat_map(|(field, errors)| errors.iter().map(move |e| format!("{}: {}", field, e.message)))
+            .collect();
+        f.write_str(&messages.join("; "))
+    }
+}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+// 아주 느슨한 형식 검사. 실제 소유 여부는 확인 메일로 검증한다.
+pub fn check_email(email: &str, errors: &mut ValidationErrors) {
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }
+        None => false,
+    };
+    if !valid || email.len() > 254 || email.chars().any(char::is_whitespace) {
+        errors.add("email", "invalid", "Email address is not valid");
+    }
+}
+
+// 호환 문자(전각 문자, 합자 등)를 표준 형태로 바꾸고 앞뒤 공백을 없앤다
+pub fn normalize_username(username: &str) -> String {
+    username.nfkc().collect::<String>().trim().to_string()
+}
+
+// 정규화된 사용자 이름 검사
+pub fn check_username(username: &str, errors: &mut ValidationErrors) {
+    let chars = username.chars().count();
+    if chars < USERNAME_MIN_CHARS {
+        errors.add(
+            "username",
+            "too_short",
+            format!("Username must be at least {} characters", USERNAME_MIN_CHARS),
+        );
+    } else if chars > USERNAME_MAX_CHARS {
+        errors.add(
+            "username",
+            "too_long",
+            format!("Username must be at most {} characters", USERNAME_MAX_CHARS),
+        );
+    }
+
+    if !username.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        errors.add(
+            "username",
+            "invalid_characters",
+            "Username may only contain letters, digits, '_', '-' and '.'",
+        );
+    } else if username.chars().next().is_some_and(|c| !c.is_alphanumeric()) {
+        errors.add("username", "invalid_start", "Username must start with a letter or digit");
+    }
+
+    let lower = username.to_lowercase();
+    if RESERVED_USERNAMES.contains(&lower.as_str()) || lower.starts_with(GUEST_USERNAME_PREFIX) {
+        errors.add("username", "reserved", "This username is reserved");
+    }
+}
+
+// 문자 종류로 추정한 엔트로피(비트): 길이 × log2(사용된 문자 종류의 크기)
+fn estimate_entropy_bits(password: &str) -> f64 {
+    let mut pool = 0u32;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if password.chars().any(|c| c.is_ascii_punctuation() || c == ' ') {
+        pool += 33;
+    }
+    if !password.is_ascii() {
+        pool += 100;
+    }
+
+    // 같은 문자의 반복은 엔트로피에 보태지 않는다
+    let mut distinct: Vec<char> = password.chars().collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    let effective_len = password.chars().count().min(distinct.len() * 2);
+
+    effective_len as f64 * (pool.max(1) as f64).log2()
+}
+
+// `field` 는 오류를 표시할 필드 이름 (가입은 password, 변경/재설정은 new_password)
+pub fn check_password(field: &'static str, password: &str, username: Option<&str>, errors: &mut ValidationErrors) {
+    let chars = password.chars().count();
+    if chars < *PASSWORD_MIN_LENGTH {
+        errors.add(
+            field,
+            "too_short",
+            format!("Password must be at least {} characters", *PASSWORD_MIN_LENGTH),
+        );
+        return;
+    }
+    if chars > PASSWORD_MAX_CHARS {
+        errors.add(
+            field,
+            "too_long",
+            format!("Password must be at most {} characters", PASSWORD_MAX_CHARS),
+        );
+        return;
+    }
+
+    if let Some(username) = username.filter(|u| !u.is_empty()) {
+        if password.to_lowercase().contains(&username.to_lowercase()) {
+            errors.add(field, "contains_username", "Password must not contain the username");
+        }
+    }
+
+    if estimate_entropy_bits(password) < *PASSWORD_MIN_ENTROPY_BITS {
+        errors.add(
+            field,
+            "too_weak",
+            "Password is too predictable; use a longer password or mix letters, digits and symbols",
+        );
+    }
+}
+
+// 새 비밀번호만 검사할 때 (비밀번호 변경, 재설정)
+pub fn validate_password(field: &'static str, password: &str, username: Option<&str>) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    check_password(field, password, username, &mut errors);
+    errors.into_result()
+}
@@ -0,0 +1,176 @@
+// --- 음성 메시지 ---
+//
+// `POST /rooms/:room/voice` 로 올린 짧은 녹음(WebM 또는 Ogg)을 `voice` 종류의 첨부로 저장한다. 길이는
+// 클라이언트가 보낸 값을 믿지 않고 컨테이너를 직접 읽어 구한다: Ogg 는 마지막 페이지의 granule position,
+// WebM 은 Info 의 Duration 이 있으면 그 값, 없으면 (브라우저 MediaRecorder 는 쓰지 않는다) 가장 늦은
+// 블록의 시각이다. 오디오 코덱 자체는 디코딩하지 않는다.
+
+use once_cell::sync::Lazy;
+
+use crate::config;
+
+pub static VOICE_MAX_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.voice_max_bytes);
+pub static VOICE_MAX_DURATION_SECONDS: Lazy<u64> = Lazy::new(|| config::get().limits.voice_max_duration_seconds);
+
+pub struct AudioInfo {
+    // 저장할 때 쓰는 형식 (audio/ogg 또는 audio/webm)
+    pub content_type: &'static str,
+    pub duration_ms: u64,
+}
+
+// 지원하지 않는 형식이거나 길이를 알 수 없으면 None
+pub fn probe(data: &[u8]) -> Option<AudioInfo> {
+    if data.starts_with(b"OggS") {
+        ogg_duration_ms(data).map(|duration_ms| AudioInfo { content_type: "audio/ogg", duration_ms })
+    } else if data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        webm_duration_ms(data).map(|duration_ms| AudioInfo { content_type: "audio/webm", duration_ms })
+    } else {
+        None
+    }
+}
+
+// --- Ogg (Opus, Vorbis) ---
+
+const OGG_HEADER_LEN: usize = 27;
+
+// 첫 페이지의 첫 패킷 (코덱 식별 헤더)
+fn ogg_first_packet(data: &[u8]) -> Option<&[u8]> {
+    let segments = *data.get(26)? as usize;
+    let table = data.get(OGG_HEADER_LEN..OGG_HEADER_LEN + segments)?;
+    let start = OGG_HEADER_LEN + segments;
+    let len: usize = table.iter().map(|&s| s as usize).sum();
+    data.get(start..start + len)
+}
+
+// 뒤에서부터 granule position 이 정해진 마지막 페이지를 찾는다
+fn ogg_last_granule(data: &[u8]) -> Option<u64> {
+    let mut end = data.len();
+    while let Some(pos) = data[..end].windows(4).rposition(|w| w == b"OggS") {
+        if let Some(bytes) = data.get(pos + 6..pos + 14) {
+            let granule = i64::from_le_bytes(bytes.try_into().ok()?);
+            if granule >= 0 && data.get(pos + 4) == Some(&0) {
+                return Some(granule as u64);
+            }
+        }
+        end = pos;
+    }
+    None
+}
+
+fn ogg_duration_ms(data: &[u8]) -> Option<u64> {
+    let head = ogg_first_packet(data)?;
+    // (샘플 레이트, 앞에서 버리는 샘플 수)
+    let (rate, pre_skip) = if head.starts_with(b"OpusHead") {
+        (48_000, u16::from_le_bytes(head.get(10..12)?.try_into().ok()?) as u64)
+    } else if head.starts_with(b"\x01vorbis") {
+        (u32::from_le_bytes(head.get(12..16)?.try_into().ok()?) as u64, 0)
+    } else {
+        return None;
+    };
+    if rate == 0 {
+        return None;
+    }
+    let samples = ogg_last_granule(data)?.saturating_sub(pre_skip);
+    Some(samples * 1000 / rate)
+}
+
+// --- WebM (Matroska) ---
+
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x18538067;
+const INFO: u32 = 0x1549A966;
+const TIMECODE_SCALE: u32 = 0x2AD7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const CLUSTER: u32 = 0x1F43B675;
+const CLUSTER_TIMECODE: u32 = 0xE7;
+const BLOCK_GROUP: u32 = 0xA0;
+const BLOCK: u32 = 0xA1;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+const TRACK_TYPE_AUDIO: u64 = 2;
+
+// 가변 길이 정수. (값, 길이, 모든 값 비트가 1 인지 = 크기 미정)
+fn read_vint(data: &[u8], pos: usize, keep_marker: bool) -> Option<(u64, usize, bool)> {
+    let first = *data.get(pos)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 {
+        return None;
+    }
+    let mut value = if keep_marker { first as u64 } else { (first as u64) & (0xFF >> len) };
+    for &b in data.get(pos + 1..pos + len)? {
+        value = (value << 8) | b as u64;
+    }
+    let all_ones = value == (1u64 << (7 * len)) - 1;
+    Some((value, len, !keep_marker && all_ones))
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |v, &b| (v << 8) | b as u64)
+}
+
+// 요소를 처음부터 차례로 훑는다. 관심 있는 마스터 요소는 건너뛰지 않고 안으로 들어가므로 크기가 정해지지
+// 않은 Segment/Cluster 도 읽을 수 있다.
+fn webm_duration_ms(data: &[u8]) -> Option<u64> {
+    let mut pos = 0;
+    let mut doc_type_ok = false;
+    let mut has_audio = false;
+    let mut timecode_scale = 1_000_000u64;
+    let mut declared: Option<f64> = None;
+    let mut cluster_timecode = 0u64;
+    let mut last_block = 0u64;
+
+    while pos < data.len() {
+        let (id, id_len, _) = read_vint(data, pos, true)?;
+        let (size, size_len, unknown) = read_vint(data, pos + id_len, false)?;
+        let start = pos + id_len + size_len;
+        let id = id as u32;
+        if matches!(id, EBML_HEADER | SEGMENT | INFO | TRACKS | TRACK_ENTRY | CLUSTER | BLOCK_GROUP) {
+            pos = start;
+            continue;
+        }
+        if unknown {
+            return None;
+        }
+        let Some(body) = data.get(start..start + size as usize) else {
+            // 잘린 파일이면 거기까지만 본다
+            break;
+        };
+        match id {
+            DOC_TYPE => doc_type_ok = body == b"webm",
+            TRACK_TYPE => match read_uint(body) {
+                TRACK_TYPE_AUDIO => has_audio = true,
+                _ => return None,
+            },
+            TIMECODE_SCALE => timecode_scale = read_uint(body),
+            DURATION => {
+                declared = match body.len() {
+                    4 => Some(f32::from_be_bytes(body.try_into().ok()?) as f64),
+                    8 => Some(f64::from_be_bytes(body.try_into().ok()?)),
+                    _ => None,
+                }
+            }
+            CLUSTER_TIMECODE => cluster_timecode = read_uint(body),
+            BLOCK | SIMPLE_BLOCK => {
+                // 트랙 번호 뒤에 클러스터 기준 상대 시각 (부호 있는 16비트)
+                let (_, track_len, _) = read_vint(body, 0, false)?;
+                let relative = i16::from_be_bytes(body.get(track_len..track_len + 2)?.try_into().ok()?);
+                last_block = last_block.max(cluster_timecode.saturating_add_signed(relative as i64));
+            }
+            _ => {}
+        }
+        pos = start + size as usize;
+    }
+
+    if !doc_type_ok || !has_audio {
+        return None;
+    }
+    let ticks = match declared {
+        Some(d) if d.is_finite() && d > 0.0 => d as u64,
+        _ => last_block,
+    };
+    Some(ticks.saturating_mul(timecode_scale) / 1_000_000)
+}
@@ -0,0 +1,341 @@
+// --- Web Push ---
+//
+// 브라우저는 `GET /push/vapid-public-key` 의 공개키로 구독을 만들어 `POST /me/push-subscriptions` 로 등록한다.
+// 언급 알림이 생겼는데 받는 사용자의 웹소켓 연결이 하나도 없으면, 배달 작업자가 그 사용자의 모든 구독으로
+// 알림을 보낸다. 내용은 RFC 8291(aes128gcm)로 암호화하고 요청은 VAPID(RFC 8292)로 서명한다. 푸시 서비스가
+// 404 나 410 으로 답한 구독은 만료된 것이므로 지운다.
+//
+// notifications.vapid_private_key(P-256 개인키 32바이트, base64url)가 없으면 구독은 받아 두지만 푸시는 보내지 않는다.
+
+use aes_gcm::{aead::Aead, Aes128Gcm, KeyInit, Nonce};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use futures::future::join_all;
+use hkdf::Hkdf;
+use once_cell::sync::Lazy;
+use p256::{
+    ecdh::EphemeralSecret,
+    ecdsa::{signature::Signer, Signature, SigningKey},
+    elliptic_curve::sec1::ToEncodedPoint,
+    PublicKey,
+};
+use rand::rngs::OsRng;
+use reqwest::{header, redirect, Url};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::{
+    auth::AuthUser,
+    config,
+    error::{ApiError, ApiJson},
+    link_preview,
+    repo::{Notification, PushTarget, Repos},
+    validation::ValidationErrors,
+    AppState, PUBLIC_URL,
+};
+
+// 푸시 서비스가 연락할 수 있는 운영자 주소 (mailto: 또는 https:)
+static VAPID_SUBJECT: Lazy<String> =
+    Lazy::new(|| config::get().notifications.vapid_subject.clone().unwrap_or_else(|| PUBLIC_URL.clone()));
+// 받는 기기가 꺼져 있을 때 푸시 서비스가 보관하는 시간
+static TTL_SECONDS: Lazy<u32> = Lazy::new(|| config::get().notifications.push_ttl_seconds);
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// 배달을 기다리는 알림 수. 넘치면 버린다.
+const QUEUE_CAPACITY: usize = 1024;
+const SUBSCRIPTIONS_PER_USER_MAX: i64 = 10;
+const ENDPOINT_MAX_CHARS: usize = 2048;
+// aes128gcm 레코드 크기. 알림 하나는 레코드 하나에 들어간다.
+const RECORD_SIZE: u32 = 4096;
+
+#[derive(Clone)]
+struct Vapid {
+    key: SigningKey,
+    // 비압축 공개키 (base64url)
+    public_key: String,
+}
+
+impl Vapid {
+    fn from_base64(private_key: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD.decode(private_key.trim()).map_err(|e| e.to_string())?;
+        let key = SigningKey::from_slice(&bytes).map_err(|e| e.to_string())?;
+        let public_key = URL_SAFE_NO_PAD.encode(key.verifying_key().to_encoded_point(false).as_bytes());
+        Ok(Self { key, public_key })
+    }
+
+    // 푸시 서비스 주소(origin)에 대해 서명한 Authorization 헤더 값
+    fn authorization(&self, endpoint: &Url) -> String {
+        let claims = json!({
+            "aud": endpoint.origin().ascii_serialization(),
+            "exp": (Utc::now() + chrono::Duration::hours(12)).timestamp(),
+            "sub": *VAPID_SUBJECT,
+        });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"typ":"JWT","alg":"ES256"}"#),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature: Signature = self.key.sign(signing_input.as_bytes());
+        format!("vapid t={}.{}, k={}", signing_input, URL_SAFE_NO_PAD.encode(signature.to_bytes()), self.public_key)
+    }
+}
+
+// 설정 검증용: VAPID 개인키로 쓸 수 있는 값인지
+pub fn check_vapid_key(private_key: &str) -> Result<(), String> {
+    Vapid::from_base64(private_key).map(|_| ())
+}
+
+struct Job {
+    user_id: i32,
+    payload: Vec<u8>,
+}
+
+pub struct WebPush {
+    vapid: Option<Vapid>,
+    queue: mpsc::Sender<Job>,
+}
+
+impl WebPush {
+    // notifications.vapid_private_key 가 있으면 배달 작업자를 띄운다
+    pub fn start(repos: Repos) -> Self {
+        let vapid = config::get()
+            .notifications
+            .vapid_private_key
+            .as_deref()
+            .map(|key| Vapid::from_base64(key).expect("notifications.vapid_private_key is validated"));
+        let (queue, jobs) = mpsc::channel(QUEUE_CAPACITY);
+        match &vapid {
+            Some(vapid) => {
+                tokio::spawn(deliver_jobs(repos, vapid.clone(), jobs));
+            }
+            None => tracing::warn!("notifications.vapid_private_key not set; Web Push notifications are disabled"),
+        }
+        Self { vapid, queue }
+    }
+
+    // 사용자의 모든 구독으로 알림을 보내도록 맡긴다
+    pub fn enqueue(&self, user_id: i32, notification: &Notification) {
+        if self.vapid.is_none() {
+            return;
+        }
+        let payload = match serde_json::to_vec(notification) {
+            Ok(payload) => payload,
+            Err(e) => return tracing::error!("Failed to serialize push notification: {}", e),
+        };
+        if let Err(e) = self.queue.try_send(Job { user_id, payload }) {
+            tracing::warn!("Dropped push notification for user {}: {}", user_id, e);
+        }
+    }
+}
+
+async fn deliver_jobs(repos: Repos, vapid: Vapid, mut jobs: mpsc::Receiver<Job>) {
+    while let Some(job) = jobs.recv().await {
+        let targets = match repos.push.targets(job.user_id).await {
+            Ok(targets) => targets,
+            Err(e) => {
+                tracing::warn!("Failed to load push subscriptions of user {}: {}", job.user_id, e);
+                continue;
+            }
+        };
+        join_all(targets.iter().map(|target| deliver(&repos, &vapid, target, &job.payload))).await;
+    }
+}
+
+async fn deliver(repos: &Repos, vapid: &Vapid, target: &PushTarget, payload: &[u8]) {
+    let result = match send(vapid, target, payload).await {
+        Ok(()) => repos.push.mark_delivered(target.id).await,
+        // 구독이 만료되었거나 해지되었다
+        Err(Some(status)) if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE => {
+            tracing::info!("Push subscription {} expired ({}); removing it", target.id, status);
+            repos.push.remove(target.id).await
+        }
+        Err(_) => return,
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to update push subscription {}: {}", target.id, e);
+    }
+}
+
+// 실패하면 푸시 서비스의 응답 상태 (받았다면)
+async fn send(vapid: &Vapid, target: &PushTarget, payload: &[u8]) -> Result<(), Option<reqwest::StatusCode>> {
+    let fail = |e: String| {
+        tracing::warn!("Failed to send push notification to subscription {}: {}", target.id, e);
+        None
+    };
+    let url = Url::parse(&target.endpoint).map_err(|e| fail(e.to_string()))?;
+    let body = encrypt(&target.p256dh, &target.auth, payload).map_err(fail)?;
+    // 구독 주소는 사용자가 보낸 것이므로 미리보기처럼 내부망으로는 보내지 않는다
+    let addr = link_preview::resolve_public(&url).await.map_err(fail)?;
+    let mut client = reqwest::Client::builder()
+        .user_agent("WebChat push")
+        .timeout(TIMEOUT)
+        .redirect(redirect::Policy::none());
+    if let Some(domain) = url.domain() {
+        client = client.resolve(domain, addr);
+    }
+    let client = client.build().map_err(|e| fail(e.to_string()))?;
+
+    let response = client
+        .post(url.clone())
+        .header(header::AUTHORIZATION, vapid.authorization(&url))
+        .header(header::CONTENT_ENCODING, "aes128gcm")
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header("TTL", *TTL_SECONDS)
+        .header("Urgency", "high")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| fail(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        tracing::warn!("Push service rejected notification for subscription {}: {}", target.id, status);
+        return Err(Some(status));
+    }
+    Ok(())
+}
+
+// RFC 8291: 구독자 공개키와 일회용 키의 ECDH 로 얻은 비밀에서 내용 키를 만들어 레코드 하나로 암호화한다
+fn encrypt(p256dh: &str, auth: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let ua_key = decode_public_key(p256dh)?;
+    let ua_public = ua_key.to_encoded_point(false);
+    let auth_secret = URL_SAFE_NO_PAD.decode(auth).map_err(|e| e.to_string())?;
+
+    let as_secret = EphemeralSecret::random(&mut OsRng);
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let shared = as_secret.diffie_hellman(&ua_key);
+
+    let key_info = [b"WebPush: info\0".as_slice(), ua_public.as_bytes(), as_public.as_bytes()].concat();
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&auth_secret), shared.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|e| e.to_string())?;
+
+    let salt: [u8; 16] = rand::random();
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let mut cek = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut cek).map_err(|e| e.to_string())?;
+    hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce).map_err(|e| e.to_string())?;
+
+    // 마지막 레코드 구분자 0x02
+    let record = [plaintext, &[2]].concat();
+    if record.len() + 16 > RECORD_SIZE as usize {
+        return Err("payload too large".to_string());
+    }
+    let ciphertext = Aes128Gcm::new(&cek.into())
+        .encrypt(Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|e| e.to_string())?;
+
+    // 헤더: salt(16) | rs(4) | idlen(1) | keyid(일회용 공개키)
+    let mut body = Vec::with_capacity(21 + as_public.len() + ciphertext.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.len() as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+fn decode_public_key(p256dh: &str) -> Result<PublicKey, String> {
+    let bytes = URL_SAFE_NO_PAD.decode(p256dh).map_err(|e| e.to_string())?;
+    PublicKey::from_sec1_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+// --- API ---
+
+// GET /push/vapid-public-key: 브라우저의 pushManager.subscribe 에 applicationServerKey 로 넘긴다
+pub async fn public_key_handler(State(state): State<AppState>) -> Response {
+    match &state.push.vapid {
+        Some(vapid) => Json(json!({ "public_key": vapid.public_key })).into_response(),
+        None => ApiError::not_found("push_disabled", "Web Push is not configured").into_response(),
+    }
+}
+
+// 브라우저의 PushSubscription.toJSON() 형식
+#[derive(Debug, Deserialize)]
+pub struct SubscribePayload {
+    endpoint: String,
+    keys: SubscriptionKeys,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionKeys {
+    p256dh: String,
+    auth: String,
+}
+
+// GET /me/push-subscriptions
+pub async fn list_handler(State(state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    match state.repos.push.list(claims.user_id).await {
+        Ok(subscriptions) => Json(subscriptions).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// POST /me/push-subscriptions: 같은 endpoint 를 다시 등록하면 키와 사용자를 바꾼다 (브라우저에서 다른 계정으로
+// 로그인한 경우)
+pub async fn subscribe_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    ApiJson(payload): ApiJson<SubscribePayload>,
+) -> Response {
+    let mut errors = ValidationErrors::default();
+    if payload.endpoint.chars().count() > ENDPOINT_MAX_CHARS {
+        errors.add("endpoint", "too_long", format!("endpoint must be at most {} characters", ENDPOINT_MAX_CHARS));
+    } else if !Url::parse(&payload.endpoint).is_ok_and(|url| url.scheme() == "https" && url.host().is_some()) {
+        errors.add("endpoint", "invalid_url", "endpoint must be an https URL");
+    }
+    if decode_public_key(&payload.keys.p256dh).is_err() {
+        errors.add("keys.p256dh", "invalid_key", "p256dh must be a base64url-encoded P-256 public key");
+    }
+    if !URL_SAFE_NO_PAD.decode(&payload.keys.auth).is_ok_and(|auth| auth.len() == 16) {
+        errors.add("keys.auth", "invalid_key", "auth must be a base64url-encoded 16-byte secret");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    match state.repos.push.count_other(claims.user_id, &payload.endpoint).await {
+        Ok(count) if count >= SUBSCRIPTIONS_PER_USER_MAX => {
+            return ApiError::conflict(
+                "push_subscription_limit_reached",
+                format!("You can register at most {} push subscriptions", SUBSCRIPTIONS_PER_USER_MAX),
+            )
+            .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let subscription = state
+        .repos
+        .push
+        .subscribe(claims.user_id, &payload.endpoint, &payload.keys.p256dh, &payload.keys.auth)
+        .await;
+    match subscription {
+        Ok(subscription) => (StatusCode::CREATED, Json(subscription)).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /me/push-subscriptions/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.push.delete(id, claims.user_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiError::not_found("push_subscription_not_found", "Push subscription not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
@@ -0,0 +1,262 @@
+// --- 수신 웹훅 ---
+//
+// 관리자가 `POST /admin/webhooks` 로, 방 주인이 `POST /rooms/:room/webhooks` 로 방 하나에 묶인 웹훅을 만들면
+// 토큰이 든 주소를 한 번만 돌려준다. CI 나 모니터링 같은 외부 시스템이 그 주소(`POST /hooks/:token`)에
+// `{"text"}` 를 보내면 메시지로 저장하고 방에 `webhook_message` 이벤트로 보낸다. 관리자는 모든 웹훅을, 방 주인은
+// 자기 방의 웹훅만 보고 폐기할 수 있다.
+//
+// 받은 글은 사용자의 글과 같은 금칙어 검사(messages::filter)를 거친다. 토큰이 새도 방을 도배할 수 없도록 웹훅마다
+// 토큰 버킷(분당 WEBHOOK_RATE_PER_MINUTE 개, 최대 WEBHOOK_BURST 개)을 두고, 비면 429 와 Retry-After 로 거절한다.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    audit::{self, Target},
+    auth::{generate_token, hash_token, AdminUser, AuthUser, Claims},
+    config,
+    error::{ApiError, ApiJson},
+    messages,
+    protocol::ServerEvent,
+    room_owners,
+    validation::ValidationErrors,
+    ws::MESSAGE_MAX_CHARS,
+    AppState, PUBLIC_URL,
+};
+
+const NAME_MAX_CHARS: usize = 32;
+const ROOM_MAX_CHARS: usize = 64;
+
+// 초당 채워지는 수와 버킷의 크기
+static RATE: Lazy<f64> = Lazy::new(|| config::get().limits.webhook_rate_per_minute / 60.0);
+static BURST: Lazy<f64> = Lazy::new(|| config::get().limits.webhook_burst);
+
+// 웹훅별 (남은 수, 마지막으로 채운 시각)
+#[derive(Default)]
+pub struct PostRate {
+    buckets: Mutex<HashMap<i64, (f64, Instant)>>,
+}
+
+impl PostRate {
+    // 보낼 수 있으면 하나를 쓴다. 못 보내면 다음 하나가 찰 때까지의 시간을 돌려준다.
+    pub fn check(&self, webhook_id: i64) -> Result<(), Duration> {
+        let now = Instant::now();
+        let refill = |tokens: f64, last: Instant| (tokens + now.duration_since(last).as_secs_f64() * *RATE).min(*BURST);
+        let mut buckets = self.buckets.lock().unwrap();
+        // 다시 가득 찬 버킷은 지워서 한동안 쓰지 않은 웹훅이 남지 않게 한다
+        buckets.retain(|_, (tokens, last)| refill(*tokens, *last) < *BURST);
+        let (tokens, last) = buckets.entry(webhook_id).or_insert((*BURST, now));
+        *tokens = refill(*tokens, *last);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / *RATE))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookPayload {
+    room: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRoomWebhookPayload {
+    name: String,
+}
+
+// POST /admin/webhooks: 토큰 원문은 이 응답에서만 볼 수 있다
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<CreateWebhookPayload>,
+) -> Response {
+    create(&state, &claims, &payload.room, &payload.name).await
+}
+
+// POST /rooms/:room/webhooks: 방 주인이나 관리자
+pub async fn room_create_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+    ApiJson(payload): ApiJson<CreateRoomWebhookPayload>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    create(&state, &claims, &room, &payload.name).await
+}
+
+async fn create(state: &AppState, claims: &Claims, room: &str, name: &str) -> Response {
+    let room = room.trim();
+    let name = name.trim();
+    let mut errors = ValidationErrors::default();
+    if room.is_empty() {
+        errors.add("room", "required", "room is required");
+    } else if room.chars().count() > ROOM_MAX_CHARS {
+        errors.add("room", "too_long", format!("room must be at most {} characters", ROOM_MAX_CHARS));
+    }
+    if name.is_empty() {
+        errors.add("name", "required", "name is required");
+    } else if name.chars().count() > NAME_MAX_CHARS {
+        errors.add("name", "too_long", format!("name must be at most {} characters", NAME_MAX_CHARS));
+    } else if name.chars().any(char::is_control) {
+        errors.add("name", "invalid_characters", "name must not contain control characters");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let token = generate_token();
+    match state.repos.webhooks.create(room, name, &hash_token(&token), claims.user_id).await {
+        Ok(webhook) => {
+            tracing::info!("User {} created webhook {} for room '{}'", claims.user_id, webhook.id, room);
+            let details = json!({ "room": room, "name": name });
+            audit::record(state, claims, "webhook.create", Target::Webhook(webhook.id), None, details).await;
+            let url = format!("{}/hooks/{}", *PUBLIC_URL, token);
+            let body = json!({
+                "id": webhook.id,
+                "room": webhook.room,
+                "name": webhook.name,
+                "token": token,
+                "url": url,
+                "created_at": webhook.created_at,
+            });
+            (StatusCode::CREATED, Json(body)).into_response()
+        }
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// GET /admin/webhooks
+pub async fn list_handler(State(state): State<AppState>, _admin: AdminUser) -> Response {
+    list(&state, None).await
+}
+
+// GET /rooms/:room/webhooks: 방 주인이나 관리자
+pub async fn room_list_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path(room): Path<String>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    list(&state, Some(&room)).await
+}
+
+// room 이 None 이면 모든 방
+async fn list(state: &AppState, room: Option<&str>) -> Response {
+    match state.repos.webhooks.list(room).await {
+        Ok(webhooks) => Json(webhooks).into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// DELETE /admin/webhooks/:id: 토큰을 더 이상 받지 않는다. 올린 메시지는 남는다.
+pub async fn revoke_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<i64>,
+) -> Response {
+    revoke(&state, &claims, id, None).await
+}
+
+// DELETE /rooms/:room/webhooks/:id: 방 주인이나 관리자. 다른 방의 웹훅은 없는 것으로 본다.
+pub async fn room_revoke_handler(
+    State(state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Path((room, id)): Path<(String, i64)>,
+) -> Response {
+    if let Err(e) = room_owners::require_manager(&state, &claims, &room).await {
+        return e.into_response();
+    }
+    revoke(&state, &claims, id, Some(&room)).await
+}
+
+async fn revoke(state: &AppState, claims: &Claims, id: i64, room: Option<&str>) -> Response {
+    match state.repos.webhooks.revoke(id, room).await {
+        Ok(true) => {
+            tracing::info!("User {} revoked webhook {}", claims.user_id, id);
+            audit::record(state, claims, "webhook.revoke", Target::Webhook(id), None, json!({})).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => ApiError::not_found("webhook_not_found", "Webhook not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
+
+// --- 메시지 받기 ---
+
+#[derive(Debug, Deserialize)]
+pub struct HookPayload {
+    text: String,
+}
+
+// POST /hooks/:token
+pub async fn post_handler(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    ApiJson(payload): ApiJson<HookPayload>,
+) -> Response {
+    let (webhook_id, room, name) = match state.repos.webhooks.use_token(&hash_token(&token)).await {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => return ApiError::not_found("webhook_not_found", "Webhook not found").into_response(),
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    if let Err(wait) = state.webhook_posts.check(webhook_id) {
+        let error = ApiError::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", "Webhook is posting too fast");
+        let secs = wait.as_secs_f64().ceil().max(1.0) as u64;
+        return ([(header::RETRY_AFTER, secs.to_string())], error).into_response();
+    }
+
+    let text = payload.text.trim();
+    if text.is_empty() {
+        return ValidationErrors::single("text", "required", "text is required").into_response();
+    }
+    if text.chars().count() > *MESSAGE_MAX_CHARS {
+        return ValidationErrors::single(
+            "text",
+            "too_long",
+            format!("text must be at most {} characters", *MESSAGE_MAX_CHARS),
+        )
+        .into_response();
+    }
+    let text = match messages::filter(&state, &room, text) {
+        Ok(text) => text,
+        Err(e) => return e.into_response(),
+    };
+
+    let message_id = match state.repos.webhooks.post_message(webhook_id, &name, &room, &text).await {
+        Ok(id) => id,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    // 아무도 접속하지 않은 방이면 저장만 한다
+    let event = ServerEvent::WebhookMessage {
+        room: room.clone(),
+        message_id,
+        webhook_id,
+        name: name.clone(),
+        text,
+        sent_at: Utc::now().timestamp_millis(),
+    };
+    state.bus.publish(&room, event);
+    (StatusCode::CREATED, Json(json!({ "message_id": message_id, "room": room }))).into_response()
+}
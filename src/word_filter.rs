@@ -0,0 +1,206 @@
+// --- 금칙어 필터 ---
+//
+// 메시지를 저장하고 보내기 전에 금칙어 목록과 맞춰 본다. 규칙마다 `mask`(해당 단어를 *** 로 가림) 또는
+// `reject`(메시지를 거부) 동작이 있고, room 이 없으면 모든 방, 있으면 그 방에만 적용된다. 목록은
+// `word_filters` 테이블에 두고 메모리에 올려 쓰며, 관리자가 `/admin/word-filters` 로 바꾸면 바로 반영된다.
+//
+// 비교는 NFKC 정규화 후 대소문자를 무시하고 단어 단위로 한다 (단어 속 일부는 걸리지 않는다). 패턴 끝의 `*` 는
+// 그 글자로 시작하는 단어 모두를 뜻한다.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::RwLock;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    audit::{self, Target},
+    auth::AdminUser,
+    error::{ApiError, ApiJson},
+    repo::{RepoError, RepoResult, Repos, WordFilterRule},
+    validation::ValidationErrors,
+    AppState,
+};
+
+const PATTERN_MAX_CHARS: usize = 64;
+
+impl WordFilterRule {
+    fn applies_to(&self, room: &str) -> bool {
+        self.room.as_deref().is_none_or(|r| r == room)
+    }
+
+    fn matches(&self, word: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => word.starts_with(prefix),
+            None => word == self.pattern,
+        }
+    }
+}
+
+pub enum Verdict {
+    // 고칠 것이 없거나 가린 결과
+    Allow(String),
+    Reject,
+}
+
+pub struct WordFilter {
+    rules: RwLock<Vec<WordFilterRule>>,
+}
+
+fn normalize(text: &str) -> String {
+    text.nfkc().collect::<String>().to_lowercase()
+}
+
+impl WordFilter {
+    pub async fn load(repos: &Repos) -> RepoResult<Self> {
+        let rules = repos.word_filters.all().await?;
+        Ok(Self { rules: RwLock::new(rules) })
+    }
+
+    // 걸린 단어는 글자 수만큼 * 로 바꾼다. reject 규칙에 하나라도 걸리면 거부.
+    pub fn check(&self, room: &str, text: &str) -> Verdict {
+        let rules = self.rules.read().unwrap();
+        let rules: Vec<&WordFilterRule> = rules.iter().filter(|r| r.applies_to(room)).collect();
+        if rules.is_empty() {
+            return Verdict::Allow(text.to_string());
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut word = String::new();
+        let mut rejected = false;
+        let mut flush = |word: &mut String, out: &mut String| {
+            if word.is_empty() {
+                return;
+            }
+            let normalized = normalize(word);
+            match rules.iter().filter(|r| r.matches(&normalized)).map(|r| r.action.as_str()).max() {
+                // "reject" > "mask"
+                Some("reject") => rejected = true,
+                Some(_) => out.extend(std::iter::repeat_n('*', word.chars().count())),
+                None => out.push_str(word),
+            }
+            word.clear();
+        };
+        for c in text.chars() {
+            if c.is_alphanumeric() {
+                word.push(c);
+            } else {
+                flush(&mut word, &mut out);
+                out.push(c);
+            }
+        }
+        flush(&mut word, &mut out);
+
+        if rejected {
+            Verdict::Reject
+        } else {
+            Verdict::Allow(out)
+        }
+    }
+}
+
+// --- 관리 API ---
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    // 지정하면 그 방에 적용되는 규칙 (전역 포함)
+    #[serde(default)]
+    room: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRulePayload {
+    pattern: String,
+    #[serde(default = "default_action")]
+    action: String,
+    #[serde(default)]
+    room: Option<String>,
+}
+
+fn default_action() -> String {
+    "mask".to_string()
+}
+
+// GET /admin/word-filters
+pub async fn list_handler(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let rules = state.word_filter.rules.read().unwrap();
+    let rules: Vec<&WordFilterRule> = match &query.room {
+        Some(room) => rules.iter().filter(|r| r.applies_to(room)).collect(),
+        None => rules.iter().collect(),
+    };
+    Json(rules).into_response()
+}
+
+// POST /admin/word-filters
+pub async fn create_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    ApiJson(payload): ApiJson<CreateRulePayload>,
+) -> Response {
+    let pattern = normalize(payload.pattern.trim());
+    let room = payload.room.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+
+    let mut errors = ValidationErrors::default();
+    let word = pattern.strip_suffix('*').unwrap_or(&pattern);
+    if word.is_empty() {
+        errors.add("pattern", "required", "pattern is required");
+    } else if pattern.chars().count() > PATTERN_MAX_CHARS {
+        errors.add("pattern", "too_long", format!("pattern must be at most {} characters", PATTERN_MAX_CHARS));
+    } else if !word.chars().all(char::is_alphanumeric) {
+        errors.add("pattern", "invalid_characters", "pattern must be a single word, optionally ending with *");
+    }
+    if !matches!(payload.action.as_str(), "mask" | "reject") {
+        errors.add("action", "invalid_value", "action must be 'mask' or 'reject'");
+    }
+    if !errors.is_empty() {
+        return errors.into_response();
+    }
+
+    let created = state.repos.word_filters.create(room.as_deref(), &pattern, &payload.action, claims.user_id).await;
+    let rule = match created {
+        Ok(rule) => rule,
+        Err(RepoError::Conflict(_)) => {
+            return ApiError::conflict("word_filter_exists", "This pattern is already filtered here").into_response()
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+    tracing::info!(
+        "User {} added word filter {} ({}) for {}",
+        claims.user_id,
+        rule.id,
+        rule.action,
+        rule.room.as_deref().unwrap_or("all rooms")
+    );
+    let details = json!({ "pattern": rule.pattern, "action": rule.action, "room": rule.room });
+    audit::record(&state, &claims, "word_filter.create", Target::WordFilter(rule.id), None, details).await;
+    state.word_filter.rules.write().unwrap().push(rule.clone());
+    (StatusCode::CREATED, Json(rule)).into_response()
+}
+
+// DELETE /admin/word-filters/:id
+pub async fn delete_handler(
+    State(state): State<AppState>,
+    AdminUser(claims): AdminUser,
+    Path(id): Path<i64>,
+) -> Response {
+    match state.repos.word_filters.delete(id).await {
+        Ok(Some(rule)) => {
+            tracing::info!("User {} removed word filter {}", claims.user_id, id);
+            state.word_filter.rules.write().unwrap().retain(|r| r.id != id);
+            let details = json!({ "pattern": rule.pattern, "action": rule.action, "room": rule.room });
+            audit::record(&state, &claims, "word_filter.delete", Target::WordFilter(id), None, details).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(None) => ApiError::not_found("word_filter_not_found", "Word filter not found").into_response(),
+        Err(e) => ApiError::from(e).into_response(),
+    }
+}
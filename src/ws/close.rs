@@ -0,0 +1,98 @@
+// --- 웹소켓 종료 코드 ---
+//
+// 서버가 연결을 끊을 때는 항상 종료 프레임에 코드와 사유를 담아 보낸다.
+// 클라이언트는 코드로 재접속을 시도할지(retryable) 포기할지를 판단할 수 있다.
+// 4000-4999 범위는 애플리케이션 전용 코드다.
+
+use axum::extract::ws::{close_code, CloseFrame};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const TOKEN_EXPIRED: u16 = 4001;
+pub const KICKED: u16 = 4003;
+pub const SESSION_REVOKED: u16 = 4004;
+pub const ACCOUNT_DELETED: u16 = 4005;
+pub const ACCOUNT_DISABLED: u16 = 4006;
+pub const ROOM_DELETED: u16 = 4007;
+pub const SLOW_CONSUMER: u16 = 4008;
+pub const UNSUPPORTED_VERSION: u16 = 4010;
+pub const FLOODING: u16 = 4029;
+
+// 다른 서버에 알릴 때는 reason() 과 같은 이름으로 직렬화한다
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseReason {
+    // 서버 재시작/종료 중 (잠시 후 재접속)
+    ServerShutdown,
+    // 연결의 인코딩과 맞지 않는 데이터 프레임 (JSON 은 텍스트, MessagePack 은 바이너리)
+    UnsupportedData,
+    // 메시지가 너무 큼
+    MessageTooBig,
+    // 인증 토큰 만료 (토큰 갱신 후 재접속)
+    TokenExpired,
+    // 관리자/방장에 의해 강퇴됨
+    Kicked,
+    // 로그인 세션이 폐기됨 (로그아웃, 세션 삭제)
+    SessionRevoked,
+    // 계정이 삭제됨
+    AccountDeleted,
+    // 관리자가 계정을 비활성화함
+    AccountDisabled,
+    // 관리자가 방을 삭제함
+    RoomDeleted,
+    // 전송 대기열을 따라오지 못함 (재접속 가능)
+    SlowConsumer,
+    // hello 로 알린 프로토콜 버전을 지원하지 않음
+    UnsupportedVersion,
+    // 경고와 음소거 뒤에도 계속 도배함
+    Flooding,
+}
+
+impl CloseReason {
+    pub fn code(self) -> u16 {
+        match self {
+            CloseReason::ServerShutdown => close_code::AWAY,
+            CloseReason::UnsupportedData => close_code::UNSUPPORTED,
+            CloseReason::MessageTooBig => close_code::SIZE,
+            CloseReason::TokenExpired => TOKEN_EXPIRED,
+            CloseReason::Kicked => KICKED,
+            CloseReason::SessionRevoked => SESSION_REVOKED,
+            CloseReason::AccountDeleted => ACCOUNT_DELETED,
+            CloseReason::AccountDisabled => ACCOUNT_DISABLED,
+            CloseReason::RoomDeleted => ROOM_DELETED,
+            CloseReason::SlowConsumer => SLOW_CONSUMER,
+            CloseReason::UnsupportedVersion => UNSUPPORTED_VERSION,
+            CloseReason::Flooding => FLOODING,
+        }
+    }
+
+    // 종료 프레임에 담기는 기계 판독용 사유 (123바이트 제한)
+    pub fn reason(self) -> &'static str {
+        match self {
+            CloseReason::ServerShutdown => "server_shutdown",
+            CloseReason::UnsupportedData => "unsupported_data",
+            CloseReason::MessageTooBig => "message_too_big",
+            CloseReason::TokenExpired => "token_expired",
+            CloseReason::Kicked => "kicked",
+            CloseReason::SessionRevoked => "session_revoked",
+            CloseReason::AccountDeleted => "account_deleted",
+            CloseReason::AccountDisabled => "account_disabled",
+            CloseReason::RoomDeleted => "room_deleted",
+            CloseReason::SlowConsumer => "slow_consumer",
+            CloseReason::UnsupportedVersion => "unsupported_version",
+            CloseReason::Flooding => "flooding",
+        }
+    }
+
+    // 같은 자격 증명으로 다시 접속해도 되는지 여부
+    pub fn is_retryable(self) -> bool {
+        matches!(self, CloseReason::ServerShutdown | CloseReason::SlowConsumer)
+    }
+
+    pub fn frame(self) -> CloseFrame<'static> {
+        CloseFrame {
+            code: self.code(),
+            reason: Cow::Borrowed(self.reason()),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// --- 연결별 도배 방지 ---
+//
+// 연결마다 토큰 버킷(초당 WS_FLOOD_RATE 개, 최대 WS_FLOOD_BURST 개)을 두고 받은 프레임마다 하나씩 쓴다.
+// 버킷이 비면 단계적으로 대응한다: 처음에는 경고만 하고 그 프레임을 버리고, 또 넘치면 WS_FLOOD_MUTE_SECONDS
+// 동안 보내는 것을 모두 버리며, 음소거가 풀린 뒤에도 넘치면 연결을 끊는다. FLOOD_RESET 동안 넘치지 않으면 처음 단계로
+// 돌아간다. 방의 설정과 상관없이 모든 연결에 적용된다.
+
+use once_cell::sync::Lazy;
+use std::time::{Duration, Instant};
+
+use crate::config;
+
+static RATE: Lazy<f64> = Lazy::new(|| config::get().limits.ws_flood_rate);
+static BURST: Lazy<f64> = Lazy::new(|| config::get().limits.ws_flood_burst);
+static MUTE: Lazy<Duration> = Lazy::new(|| Duration::from_secs(config::get().limits.ws_flood_mute_seconds));
+
+// 이 시간 동안 넘치지 않으면 경고 단계부터 다시 시작한다
+const FLOOD_RESET: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    // 처음 넘침: 경고하고 이 프레임은 버린다
+    Warn,
+    // 두 번째 넘침: 지금부터 MUTE 동안 버린다
+    Mute(Duration),
+    // 음소거 중이라 버린다 (이미 알렸으므로 조용히)
+    Muted,
+    // 음소거가 풀린 뒤에도 또 넘침
+    Disconnect,
+}
+
+pub struct FloodGuard {
+    tokens: f64,
+    last_refill: Instant,
+    // 0: 정상, 1: 경고함, 2: 음소거함
+    level: u8,
+    last_violation: Option<Instant>,
+    muted_until: Option<Instant>,
+}
+
+impl Default for FloodGuard {
+    fn default() -> Self {
+        Self { tokens: *BURST, last_refill: Instant::now(), level: 0, last_violation: None, muted_until: None }
+    }
+}
+
+impl FloodGuard {
+    // 프레임을 받을 때마다 부른다
+    pub fn check(&mut self) -> Verdict {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.last_refill).as_secs_f64() * *RATE).min(*BURST);
+        self.last_refill = now;
+        if self.last_violation.is_some_and(|t| now.duration_since(t) > FLOOD_RESET) {
+            self.level = 0;
+            self.last_violation = None;
+        }
+
+        // 음소거 중에는 세지 않고 버리기만 한다
+        if self.muted_until.is_some_and(|until| now < until) {
+            return Verdict::Muted;
+        }
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Verdict::Allow;
+        }
+
+        self.last_violation = Some(now);
+        match self.level {
+            0 => {
+                self.level = 1;
+                Verdict::Warn
+            }
+            1 => {
+                self.level = 2;
+                self.muted_until = Some(now + *MUTE);
+                Verdict::Mute(*MUTE)
+            }
+            _ => Verdict::Disconnect,
+        }
+    }
+}
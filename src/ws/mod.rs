@@ -0,0 +1,557 @@
+// --- 웹소켓 연결 처리 ---
+
+mod close;
+mod flood;
+
+pub use close::CloseReason;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use axum_extra::extract::cookie::CookieJar;
+use futures::{
+    sink::SinkExt,
+    stream::{SplitSink, StreamExt},
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::Duration,
+};
+use tokio::sync::{
+    broadcast::error::RecvError,
+    mpsc::{self, error::TrySendError},
+    watch,
+};
+use tracing::Instrument;
+
+use crate::{
+    auth::{self, token_from_request, verify_access_token, AuthError, Claims},
+    client_ip::ClientIp,
+    commands, config,
+    connections::LimitExceeded,
+    error::ApiError,
+    guest, ip_bans, maintenance,
+    messages::{self, Posted},
+    monitoring,
+    polls, presence, profile,
+    protocol::{self, ClientEvent, Encoding, ServerEvent},
+    request_id,
+    shutdown, telemetry, AppState,
+};
+
+// 메시지 본문 최대 길이 (글자 수). 첨부 파일 설명에도 같은 제한을 쓴다.
+pub static MESSAGE_MAX_CHARS: Lazy<usize> = Lazy::new(|| config::get().limits.message_max_chars);
+
+// 웹소켓 프레임/메시지 최대 크기. 넘으면 읽지 않고 1009 message_too_big 으로 끊는다.
+pub static WS_MAX_MESSAGE_BYTES: Lazy<usize> = Lazy::new(|| config::get().limits.ws_max_message_bytes);
+
+// 클라이언트 하나당 전송 대기열 크기. 이 이상 밀리면 느린 소비자로 보고 연결을 끊는다.
+pub static SEND_QUEUE_CAPACITY: Lazy<usize> = Lazy::new(|| config::get().limits.ws_send_queue_capacity);
+
+// 방 이벤트가 채널 크기(rooms.channel_capacity)보다 많이 밀린 연결의 처리
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LagPolicy {
+    // 오래된 이벤트를 건너뛰고 `lagged` 로 알린다. 클라이언트가 기록을 다시 불러 채운다.
+    DropOldest,
+    // slow_consumer 로 연결을 끊는다
+    Disconnect,
+}
+
+static LAG_POLICY: Lazy<LagPolicy> = Lazy::new(|| config::get().rooms.lag_policy);
+
+// 방 이벤트를 skipped 개 놓친 연결에 보낼 이벤트. 연결을 끊어야 하면(disconnect) None.
+pub fn lagged(room: &str, skipped: u64) -> Option<ServerEvent> {
+    monitoring::broadcast_lagged(skipped);
+    match *LAG_POLICY {
+        LagPolicy::DropOldest => Some(ServerEvent::Lagged { room: room.to_string(), skipped }),
+        LagPolicy::Disconnect => None,
+    }
+}
+
+// 방에 들어온 연결에 먼저 보내는 방 상태 (주제, 진행 중인 투표)
+pub async fn initial_events(state: &AppState, room: &str, user_id: i32) -> Vec<ServerEvent> {
+    let mut events = Vec::new();
+    if let Ok(Some(topic)) = commands::topic::current(state, room).await {
+        events.push(topic);
+    }
+    match polls::open_in_room(&state.repos, room, user_id).await {
+        Ok(open) => events.extend(open),
+        Err(e) => tracing::warn!("Failed to load open polls of '{}': {}", room, e),
+    }
+    events
+}
+
+// 종료 프레임을 보낼 때까지 기다려 주는 시간
+const CLOSE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// 서브프로토콜 이름. 토큰은 `bearer.<JWT>` 형태의 다른 서브프로토콜로 함께 전달할 수 있다.
+// `webchat-msgpack` 을 제시한 연결은 MessagePack 바이너리 프레임을 쓰고, `webchat-json` 과 예전 이름 `webchat` 은
+// JSON 텍스트 프레임을 쓴다. 여럿을 제시하면 이 순서대로 고른다.
+pub const SUBPROTOCOLS: [(&str, Encoding); 3] = [
+    ("webchat-msgpack", Encoding::MessagePack),
+    ("webchat-json", Encoding::Json),
+    ("webchat", Encoding::Json),
+];
+const BEARER_PROTOCOL_PREFIX: &str = "bearer.";
+
+// hello 를 보내지 않는 클라이언트의 프로토콜 버전
+const IMPLICIT_VERSION: u32 = 1;
+
+// 클라이언트가 제시한 서브프로토콜 중 하나를 고른다. 제시하지 않았으면 JSON.
+fn negotiate_encoding(headers: &HeaderMap) -> (Option<&'static str>, Encoding) {
+    let offered: Vec<&str> = headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .collect();
+    SUBPROTOCOLS
+        .iter()
+        .find(|(name, _)| offered.contains(name))
+        .map_or((None, Encoding::Json), |&(name, encoding)| (Some(name), encoding))
+}
+
+// 업그레이드 요청에서 액세스 토큰을 찾는다.
+// 우선순위: Sec-WebSocket-Protocol > Authorization 헤더 > 인증 쿠키 > ?token= (더 이상 권장하지 않음)
+fn upgrade_token(headers: &HeaderMap, params: &HashMap<String, String>) -> Option<String> {
+    let from_protocol = headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .find_map(|p| p.trim().strip_prefix(BEARER_PROTOCOL_PREFIX))
+        .map(str::to_string);
+    if from_protocol.is_some() {
+        return from_protocol;
+    }
+
+    let jar = CookieJar::from_headers(headers);
+    if let Some(token) = token_from_request(headers, &jar) {
+        return Some(token);
+    }
+
+    // 쿼리 문자열의 토큰은 접근 로그와 브라우저 기록에 남으므로 하위 호환용으로만 받는다
+    let token = params.get("token").cloned();
+    if token.is_some() {
+        tracing::warn!("WebSocket token passed in query string; this is deprecated");
+    }
+    token
+}
+
+// 웹소켓 핸들러
+pub async fn websocket_handler(
+    ws: WebSocketUpgrade,
+    Path(room): Path<String>,
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    ClientIp(ip): ClientIp,
+) -> impl IntoResponse {
+    if state.shutdown.is_started() {
+        return shutdown::unavailable().into_response();
+    }
+    if let Err(e) = maintenance::check(&state).await {
+        return e.into_response();
+    }
+    if ip_bans::is_banned(&state, ip).await {
+        return ip_bans::banned_response();
+    }
+
+    let token = match upgrade_token(&headers, &params) {
+        Some(t) => t,
+        None => return AuthError::Missing.into_response(),
+    };
+
+    let claims = match auth::authenticate(&state, &token).await {
+        Ok(claims) => claims,
+        Err(e) => return e.into_response(),
+    };
+    // 봇 토큰은 chat 권한이 있어야 접속할 수 있다
+    if !claims.allows("chat") {
+        return AuthError::Scope.into_response();
+    }
+
+    if claims.guest && !guest::can_join(&room) {
+        return ApiError::forbidden("guest_room_forbidden", "Guests cannot join this room").into_response();
+    }
+
+    // 업그레이드가 끝나지 않아도 클로저와 함께 자리가 풀린다
+    let slot = match state.connection_limits.acquire(claims.user_id, ip) {
+        Ok(slot) => slot,
+        Err(exceeded) => {
+            let message = match exceeded {
+                LimitExceeded::User(max) => format!("You already have {} open connections", max),
+                LimitExceeded::Ip(max) => format!("Too many connections from your address (max {})", max),
+            };
+            tracing::warn!("Rejected WebSocket upgrade of user {} from {}: {}", claims.user_id, ip, message);
+            return ApiError::new(StatusCode::TOO_MANY_REQUESTS, "too_many_connections", message).into_response();
+        }
+    };
+
+    // 클라이언트가 서브프로토콜을 제시했다면 토큰이 아닌 고른 인코딩의 이름을 돌려준다
+    let (protocol, encoding) = negotiate_encoding(&headers);
+    // 이 연결의 로그는 모두 연결 id 와 업그레이드 요청의 id 를 단다. 연결은 오래 가므로 요청과 따로 추적한다.
+    let connection_id = state.connections.next_id();
+    let span = tracing::info_span!(
+        parent: None,
+        "ws.connection",
+        connection_id,
+        request_id = request_id::current().unwrap_or_default(),
+        room,
+        user_id = claims.user_id,
+        encoding = ?encoding,
+    );
+    ws.protocols(protocol)
+        .max_message_size(*WS_MAX_MESSAGE_BYTES)
+        .max_frame_size(*WS_MAX_MESSAGE_BYTES)
+        .on_upgrade(move |socket| {
+            async move {
+                handle_socket(socket, ip, state, connection_id, room, claims, encoding).await;
+                drop(slot);
+            }
+            .instrument(span)
+        })
+}
+
+// 개별 웹소켓 연결 처리
+async fn handle_socket(
+    socket: WebSocket,
+    who: IpAddr,
+    state: AppState,
+    connection_id: u64,
+    room: String,
+    claims: Claims,
+    encoding: Encoding,
+) {
+    let is_bot = claims.is_bot();
+    let username = claims.sub.clone();
+    let user_id = claims.user_id;
+    let session_id = claims.sid.clone();
+
+    // 채팅방을 구독 (없으면 새로 생성)
+    let mut rx = state.bus.subscribe(&room);
+
+    // 연결하는 동안에는 접속할 때의 표시 이름과 아바타를 쓴다
+    let identity = profile::chat_identity(&state, user_id, &username).await;
+
+    tracing::info!("User '{}' ({}) joined room '{}' from {}", &username, user_id, &room, who);
+
+    // 접속 메시지 브로드캐스팅
+    let join = ServerEvent::Join {
+        room: room.clone(),
+        username: username.clone(),
+        display_name: identity.display_name.clone(),
+        avatar_url: identity.avatar_url.clone(),
+    };
+    state.bus.publish(&room, join);
+
+    // socket을 읽기(receiver)와 쓰기(sender)로 분리
+    let (sender, mut receiver) = socket.split();
+
+    // 소켓 쓰기는 전용 태스크 하나만 담당하고, 나머지는 제한된 대기열을 통해 전달한다.
+    // 대기열에는 이벤트를 넣고, 연결의 인코딩으로 바꾸는 일은 쓰기 태스크가 한다.
+    let (out_tx, out_rx) = mpsc::channel::<ServerEvent>(*SEND_QUEUE_CAPACITY);
+    let (close_tx, close_rx) = mpsc::channel::<CloseReason>(1);
+    let write = write_loop(sender, encoding, out_rx, close_rx);
+    let mut write_task = tokio::spawn(monitoring::task("ws_write", write).in_current_span());
+
+    // 세션 폐기 등 외부에서 이 연결을 닫을 수 있도록 등록
+    let first_connection =
+        state.connections.register(connection_id, user_id, &session_id, &room, close_tx.clone(), out_tx.clone());
+    if first_connection {
+        presence::notify(&state, user_id, &username, true).await;
+    }
+    let _ = state.repos.sessions.touch(&session_id).await;
+    for event in initial_events(&state, &room, user_id).await {
+        let _ = out_tx.try_send(event);
+    }
+
+    // 현재 연결에 적용되는 토큰 만료 시각. 대역 내 토큰 갱신으로 늘어날 수 있다.
+    let (exp_tx, mut exp_rx) = watch::channel(claims.exp);
+
+    // 방의 브로드캐스트를 이 클라이언트의 전송 대기열로 옮기는 태스크.
+    // 대기열이 가득 차면 기다리지 않고 연결을 끊는다.
+    let forward_username = username.clone();
+    let forward_room = room.clone();
+    let forward_out = out_tx.clone();
+    let forward_close = close_tx.clone();
+    let forward = async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("'{}' lagged behind by {} messages", forward_username, skipped);
+                    let Some(event) = lagged(&forward_room, skipped) else {
+                        let _ = forward_close.try_send(CloseReason::SlowConsumer);
+                        break;
+                    };
+                    event
+                }
+                Err(RecvError::Closed) => break,
+            };
+            match forward_out.try_send(event) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    tracing::warn!("Disconnecting slow consumer '{}'", forward_username);
+                    let _ = forward_close.try_send(CloseReason::SlowConsumer);
+                    break;
+                }
+                Err(TrySendError::Closed(_)) => break,
+            }
+        }
+    };
+    let mut forward_task = tokio::spawn(monitoring::task("ws_forward", forward).in_current_span());
+
+    // 토큰 만료 시각이 지나면 token_expired 로 연결을 닫는 태스크
+    let expiry_close = close_tx.clone();
+    let expiry = async move {
+        loop {
+            let exp = *exp_rx.borrow_and_update();
+            tokio::select! {
+                _ = tokio::time::sleep(until_expiry(exp)) => {
+                    let _ = expiry_close.try_send(CloseReason::TokenExpired);
+                    break;
+                }
+                changed = exp_rx.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    };
+    let mut expiry_task = tokio::spawn(expiry.in_current_span());
+
+    let mut flood = flood::FloodGuard::default();
+    // 이 연결의 프로토콜 버전. 첫 이벤트를 받으면 정해진다.
+    let mut version: Option<u32> = None;
+
+    // 이 클라이언트의 메시지를 '수신'해서 처리하는 태스크 (읽기)
+    let recv_username = username.clone();
+    let author = messages::Author {
+        user_id,
+        username: username.clone(),
+        display_name: identity.display_name.clone(),
+        avatar_url: identity.avatar_url.clone(),
+        bot: is_bot,
+    };
+    let recv_room = room.clone();
+    let recv_state = state.clone();
+    let recv = async move {
+        let state = recv_state;
+        while let Some(msg) = receiver.next().await {
+            let msg = match msg {
+                Ok(msg) => msg,
+                Err(e) => {
+                    if is_capacity_error(&e) {
+                        tracing::info!("Closing connection of '{}': {}", recv_username, e);
+                        let _ = close_tx.try_send(CloseReason::MessageTooBig);
+                    }
+                    break;
+                }
+            };
+            // 연결의 인코딩과 다른 종류의 데이터 프레임은 받지 않는다
+            let parsed = match (encoding, msg) {
+                (Encoding::Json, Message::Text(text)) => ClientEvent::parse(&text).ok(),
+                (Encoding::MessagePack, Message::Binary(bytes)) => ClientEvent::decode(&bytes).ok(),
+                (_, Message::Text(_) | Message::Binary(_)) => {
+                    let _ = close_tx.try_send(CloseReason::UnsupportedData);
+                    break;
+                }
+                _ => continue,
+            };
+
+            match flood.check() {
+                flood::Verdict::Allow => {}
+                flood::Verdict::Warn => {
+                    let error = ServerEvent::error("flood_warning", "You are sending too fast; slow down");
+                    let _ = out_tx.try_send(error);
+                    continue;
+                }
+                flood::Verdict::Mute(duration) => {
+                    tracing::info!("Muting '{}' for flooding", recv_username);
+                    let error = ServerEvent::error(
+                        "flood_muted",
+                        format!("You are sending too fast; messages are ignored for {} seconds", duration.as_secs()),
+                    );
+                    let _ = out_tx.try_send(error);
+                    continue;
+                }
+                flood::Verdict::Muted => continue,
+                flood::Verdict::Disconnect => {
+                    tracing::warn!("Disconnecting '{}' for flooding", recv_username);
+                    let _ = close_tx.try_send(CloseReason::Flooding);
+                    break;
+                }
+            }
+
+            let Some(event) = parsed else {
+                let _ = out_tx.try_send(ServerEvent::error("invalid_event", "Malformed event"));
+                continue;
+            };
+
+            // hello 없이 시작한 연결은 hello 가 생기기 전의 형식(버전 1)을 쓰는 것으로 본다
+            if !matches!(event, ClientEvent::Hello { .. }) {
+                version.get_or_insert(IMPLICIT_VERSION);
+            }
+
+            match event {
+                ClientEvent::Hello { version: requested } => {
+                    if version.is_some() {
+                        let error =
+                            ServerEvent::error("hello_too_late", "hello must be the first event of a connection");
+                        let _ = out_tx.try_send(error);
+                        continue;
+                    }
+                    if !protocol::SUPPORTED_VERSIONS.contains(&requested) {
+                        tracing::info!(
+                            "Closing connection of '{}': unsupported protocol version {}",
+                            recv_username,
+                            requested
+                        );
+                        let _ = close_tx.try_send(CloseReason::UnsupportedVersion);
+                        break;
+                    }
+                    version = Some(requested);
+                    let reply = ServerEvent::Hello {
+                        version: requested,
+                        versions: protocol::SUPPORTED_VERSIONS,
+                        capabilities: protocol::CAPABILITIES,
+                    };
+                    let _ = out_tx.try_send(reply);
+                }
+                ClientEvent::Message { text, format, ttl_seconds } => {
+                    let posted = messages::post(&state, &claims, &recv_room, &author, text, format, ttl_seconds)
+                        .instrument(telemetry::message_span(&recv_room))
+                        .await;
+                    match posted {
+                        Ok(Posted::Reply { command, text }) => {
+                            let reply = ServerEvent::CommandResponse { room: recv_room.clone(), command, text };
+                            let _ = out_tx.try_send(reply);
+                        }
+                        Ok(Posted::Message(_) | Posted::Command) => {}
+                        Err(e) => {
+                            let _ = out_tx.try_send(ServerEvent::error(e.code(), e.message()));
+                        }
+                    }
+                }
+                ClientEvent::Vote { poll_id, option } => {
+                    if let Err(e) = polls::vote(&state, &claims, &recv_room, poll_id, option).await {
+                        let _ = out_tx.try_send(ServerEvent::error(e.code(), e.message()));
+                    }
+                }
+                ClientEvent::RefreshToken { token } => {
+                    // 같은 사용자에게 발급된 유효한 토큰만 받아들인다
+                    let reply = match verify_access_token(&state, &token) {
+                        Ok(new_claims) if new_claims.user_id == user_id && new_claims.sid == session_id => {
+                            let _ = exp_tx.send(new_claims.exp);
+                            ServerEvent::TokenRefreshed { expires_at: new_claims.exp as i64 }
+                        }
+                        Ok(_) => ServerEvent::error("token_mismatch", "Token belongs to a different session"),
+                        Err(e) => ServerEvent::error("invalid_token", e.to_string()),
+                    };
+                    let _ = out_tx.try_send(reply);
+                }
+            }
+        }
+    };
+    let mut recv_task = tokio::spawn(monitoring::task("ws_receive", recv).in_current_span());
+
+    // 한쪽 태스크가 끝나면 나머지도 종료
+    tokio::select! {
+        _ = (&mut write_task) => {}
+        _ = (&mut forward_task) => {}
+        _ = (&mut expiry_task) => {}
+        _ = (&mut recv_task) => {}
+    };
+    forward_task.abort();
+    expiry_task.abort();
+    recv_task.abort();
+    // 목록도 전송 대기열의 송신자를 갖고 있으므로 먼저 빠진다
+    let last_connection = state.connections.unregister(connection_id);
+    // 요청된 종료 프레임이 나갈 시간을 주되, 소켓이 막혀 있으면 그냥 끊는다.
+    // 전송 대기열의 송신자가 모두 사라지면 쓰기 태스크는 스스로 끝난다.
+    if !write_task.is_finished()
+        && tokio::time::timeout(CLOSE_GRACE_PERIOD, &mut write_task).await.is_err()
+    {
+        write_task.abort();
+    }
+
+    // 접속 종료 메시지 브로드캐스팅
+    let leave = ServerEvent::Leave {
+        room: room.clone(),
+        username: username.clone(),
+        display_name: identity.display_name,
+        avatar_url: identity.avatar_url,
+    };
+    state.bus.publish(&room, leave);
+
+    if last_connection {
+        // 메일 요약은 이 시각 뒤에 온 언급만 모은다
+        if let Err(e) = state.repos.users.touch_last_online(user_id).await {
+            tracing::warn!("Failed to record last online time of user {}: {}", user_id, e);
+        }
+        presence::notify(&state, user_id, &username, false).await;
+    }
+
+    tracing::info!("WebSocket connection for '{}' from {} closed", username, who);
+}
+
+// 프레임/메시지 크기 제한에 걸린 수신 오류인지
+fn is_capacity_error(e: &axum::Error) -> bool {
+    std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<tungstenite::Error>())
+        .is_some_and(|e| matches!(e, tungstenite::Error::Capacity(_)))
+}
+
+// JWT exp(유닉스 초)까지 남은 시간
+pub fn until_expiry(exp: usize) -> Duration {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    Duration::from_secs((exp as u64).saturating_sub(now))
+}
+
+// 전송 대기열의 이벤트를 연결의 인코딩으로 바꿔 소켓으로 내보낸다. 종료 요청이 오면 우선 처리한다.
+async fn write_loop(
+    mut sender: SplitSink<WebSocket, Message>,
+    encoding: Encoding,
+    mut out_rx: mpsc::Receiver<ServerEvent>,
+    mut close_rx: mpsc::Receiver<CloseReason>,
+) {
+    loop {
+        tokio::select! {
+            biased;
+            Some(reason) = close_rx.recv() => {
+                tracing::debug!(
+                    "Closing WebSocket: {} ({}), retryable={}",
+                    reason.reason(),
+                    reason.code(),
+                    reason.is_retryable()
+                );
+                // 서버 종료는 닫기 전에 이벤트로도 알린다
+                if reason == CloseReason::ServerShutdown {
+                    let _ = sender.send(shutdown::event().encode(encoding)).await;
+                }
+                let _ = sender.send(Message::Close(Some(reason.frame()))).await;
+                break;
+            }
+            event = out_rx.recv() => match event {
+                Some(event) => {
+                    if sender.send(event.encode(encoding)).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+}
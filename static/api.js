@@ -0,0 +1,14 @@
+// 오류 응답({"code", "message", "fields"})을 사람이 읽을 문장으로 바꾼다.
+// 검증 실패는 필드별 메시지를 한 줄씩 보여준다.
+async function errorText(response) {
+    const text = await response.text();
+    try {
+        const body = JSON.parse(text);
+        if (body.fields) {
+            return Object.values(body.fields).flat().map(e => e.message).join('\n');
+        }
+        return body.message || text;
+    } catch {
+        return text;
+    }
+}
@@ -0,0 +1,52 @@
+// 서버(webauthn-rs)가 주는 옵션은 바이너리 필드가 base64url 문자열이므로
+// 브라우저 API 에 맞게 변환하고, 결과도 다시 base64url JSON 으로 만든다.
+
+function b64urlToBuffer(value) {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - base64.length % 4) % 4);
+    return Uint8Array.from(atob(padded), c => c.charCodeAt(0)).buffer;
+}
+
+function bufferToB64url(buffer) {
+    const bytes = String.fromCharCode(...new Uint8Array(buffer));
+    return btoa(bytes).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+async function createPasskey(options) {
+    const publicKey = options.publicKey;
+    publicKey.challenge = b64urlToBuffer(publicKey.challenge);
+    publicKey.user.id = b64urlToBuffer(publicKey.user.id);
+    (publicKey.excludeCredentials || []).forEach(c => { c.id = b64urlToBuffer(c.id); });
+
+    const credential = await navigator.credentials.create({ publicKey });
+    return {
+        id: credential.id,
+        rawId: bufferToB64url(credential.rawId),
+        type: credential.type,
+        extensions: credential.getClientExtensionResults(),
+        response: {
+            attestationObject: bufferToB64url(credential.response.attestationObject),
+            clientDataJSON: bufferToB64url(credential.response.clientDataJSON),
+        },
+    };
+}
+
+async function getPasskey(options) {
+    const publicKey = options.publicKey;
+    publicKey.challenge = b64urlToBuffer(publicKey.challenge);
+    (publicKey.allowCredentials || []).forEach(c => { c.id = b64urlToBuffer(c.id); });
+
+    const credential = await navigator.credentials.get({ publicKey });
+    return {
+        id: credential.id,
+        rawId: bufferToB64url(credential.rawId),
+        type: credential.type,
+        extensions: credential.getClientExtensionResults(),
+        response: {
+            authenticatorData: bufferToB64url(credential.response.authenticatorData),
+            clientDataJSON: bufferToB64url(credential.response.clientDataJSON),
+            signature: bufferToB64url(credential.response.signature),
+            userHandle: credential.response.userHandle ? bufferToB64url(credential.response.userHandle) : null,
+        },
+    };
+}